@@ -1,5 +1,6 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
 use onevox::audio::buffer::AudioBuffer;
+use onevox::models::mel::MelExtractor;
 
 fn bench_audio_ring_buffer_write(c: &mut Criterion) {
     c.bench_function("audio_ring_buffer_write_100ms", |b| {
@@ -24,9 +25,18 @@ fn bench_audio_ring_buffer_read(c: &mut Criterion) {
     });
 }
 
+fn bench_mel_extraction(c: &mut Criterion) {
+    c.bench_function(&format!("mel_80_10s_{}", MelExtractor::backend()), |b| {
+        let extractor = MelExtractor::new(80);
+        let samples: Vec<f32> = (0..160_000).map(|i| (i as f32 * 0.05).sin()).collect();
+        b.iter(|| extractor.extract(black_box(&samples)).unwrap());
+    });
+}
+
 criterion_group!(
     audio_processing_benches,
     bench_audio_ring_buffer_write,
-    bench_audio_ring_buffer_read
+    bench_audio_ring_buffer_read,
+    bench_mel_extraction
 );
 criterion_main!(audio_processing_benches);
@@ -68,7 +68,7 @@ if [ -n "$FEATURES" ]; then
     echo "Additional features: $FEATURES"
 else
     FEATURES_FLAG=""
-    echo "Features: default (whisper-cpp, overlay-indicator)"
+    echo "Features: default (whisper-cpp, onnx, desktop)"
 fi
 echo ""
 
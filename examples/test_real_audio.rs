@@ -62,6 +62,9 @@ fn main() -> Result<()> {
         use_gpu: false,
         n_threads: 4,
         beam_size: 1,
+        language: None,
+        translate: false,
+        prompt: None,
     };
 
     model.load(config)?;
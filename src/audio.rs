@@ -2,19 +2,40 @@
 //!
 //! Provides real-time microphone capture, buffering, and streaming audio processing.
 
+pub mod agc;
+pub mod app_capture;
 pub mod buffer;
 pub mod capture;
+pub mod convert;
+pub mod denoise;
 pub mod devices;
+pub mod feedback;
+pub mod gain_hint;
+pub mod pre_buffer;
+pub mod sample_path;
+pub mod selftest;
+pub mod tones;
 
 // Re-export commonly used types
+pub use agc::{Agc, GainMeter};
+pub use app_capture::{AppCapture, AppStream};
 pub use buffer::{AudioBuffer, AudioChunk, AudioConsumer, AudioProducer};
-pub use capture::{AudioCapture, CaptureConfig};
-pub use devices::{AudioDeviceInfo, AudioDeviceManager};
+pub use capture::{AudioCapture, CaptureConfig, CaptureSource};
+pub use denoise::Denoiser;
+pub use devices::{AudioDeviceInfo, AudioDeviceManager, DeviceChange};
+pub use feedback::SoundFeedback;
+pub use pre_buffer::PreBuffer;
+pub use tones::{Tone, TonePlayer};
 
 /// Audio engine - main interface for audio system
 pub struct AudioEngine {
     device_manager: AudioDeviceManager,
     capture: Option<AudioCapture>,
+    /// Captures running alongside the main one (e.g. system audio next to
+    /// the microphone), stopped with it
+    extra_captures: Vec<AudioCapture>,
+    /// Holds recent audio from the capture between sessions
+    pre_buffer: Option<PreBuffer>,
 }
 
 impl AudioEngine {
@@ -23,6 +44,8 @@ impl AudioEngine {
         Self {
             device_manager: AudioDeviceManager::new(),
             capture: None,
+            extra_captures: Vec::new(),
+            pre_buffer: None,
         }
     }
 
@@ -31,11 +54,28 @@ impl AudioEngine {
         self.device_manager.list_input_devices()
     }
 
+    /// Names of the loopback sources `[audio].source = "system"` can
+    /// capture from
+    pub fn list_loopback_devices(&self) -> crate::Result<Vec<String>> {
+        Ok(self.device_manager.loopback_device_names()?.0)
+    }
+
     /// Start audio capture with config
+    ///
+    /// With a pre-buffer running, its capture carries on instead (`config`
+    /// is not applied) and the channel starts with the held audio.
     pub fn start_capture(
         &mut self,
         config: CaptureConfig,
     ) -> crate::Result<tokio::sync::mpsc::Receiver<AudioChunk>> {
+        if let Some(pre_buffer) = &self.pre_buffer {
+            if self.is_capturing() {
+                return Ok(pre_buffer.attach());
+            }
+            // The stream died; start over without it
+            self.pre_buffer = None;
+        }
+
         // Ensure any existing capture is fully stopped before starting a new one
         if let Some(mut existing_capture) = self.capture.take() {
             existing_capture.stop()?;
@@ -47,14 +87,96 @@ impl AudioEngine {
         Ok(rx)
     }
 
-    /// Stop audio capture
+    /// Start another capture alongside the current one, delivering to its
+    /// own channel (device switches and gain apply to the main capture)
+    pub fn add_capture(
+        &mut self,
+        config: CaptureConfig,
+    ) -> crate::Result<tokio::sync::mpsc::Receiver<AudioChunk>> {
+        let mut capture = AudioCapture::new(config);
+        let rx = capture.start()?;
+        self.extra_captures.push(capture);
+        Ok(rx)
+    }
+
+    /// Stop audio capture, including captures added alongside it (a
+    /// pre-buffer's capture goes back to holding audio)
     pub fn stop_capture(&mut self) -> crate::Result<()> {
+        for mut capture in self.extra_captures.drain(..) {
+            capture.stop()?;
+        }
+        if let Some(pre_buffer) = &self.pre_buffer {
+            pre_buffer.detach();
+            return Ok(());
+        }
         if let Some(mut capture) = self.capture.take() {
             capture.stop()?;
         }
         Ok(())
     }
 
+    /// Keep capturing between sessions, holding the last `window_ms` of
+    /// audio for the next `start_capture`
+    pub fn start_pre_buffer(&mut self, config: CaptureConfig, window_ms: u32) -> crate::Result<()> {
+        self.stop_pre_buffer()?;
+        self.stop_capture()?;
+
+        let live_capacity = config.channel_capacity();
+        let mut capture = AudioCapture::new(config);
+        let rx = capture.start()?;
+        self.pre_buffer = Some(PreBuffer::spawn(rx, window_ms as u64, live_capacity)?);
+        self.capture = Some(capture);
+        Ok(())
+    }
+
+    /// Stop the pre-buffer and its capture, ending a session using it
+    pub fn stop_pre_buffer(&mut self) -> crate::Result<()> {
+        if self.pre_buffer.take().is_some()
+            && let Some(mut capture) = self.capture.take()
+        {
+            capture.stop()?;
+        }
+        Ok(())
+    }
+
+    /// Whether a pre-buffer is holding audio or feeding a session
+    pub fn is_pre_buffering(&self) -> bool {
+        self.pre_buffer.is_some() && self.is_capturing()
+    }
+
+    /// Gain applied by the capture's AGC, while it is on
+    pub fn gain_meter(&self) -> Option<GainMeter> {
+        self.capture.as_ref().and_then(AudioCapture::gain_meter)
+    }
+
+    /// Chunks the current capture (or the session fed by the pre-buffer)
+    /// dropped because processing fell behind
+    pub fn dropped_chunks(&self) -> u64 {
+        if let Some(pre_buffer) = &self.pre_buffer {
+            return pre_buffer.dropped_chunks();
+        }
+        self.capture
+            .as_ref()
+            .map(AudioCapture::dropped_chunks)
+            .unwrap_or(0)
+    }
+
+    /// Capture from another input device, switching the running capture
+    /// over
+    pub fn set_device(&mut self, device_name: &str) {
+        if let Some(capture) = self.capture.as_mut() {
+            capture.set_device(device_name);
+        }
+    }
+
+    /// Input device switches of the current capture (taken once per
+    /// capture)
+    pub fn device_changes(&mut self) -> Option<tokio::sync::mpsc::UnboundedReceiver<DeviceChange>> {
+        self.capture
+            .as_mut()
+            .and_then(AudioCapture::take_device_changes)
+    }
+
     /// Check if capture is running
     pub fn is_capturing(&self) -> bool {
         self.capture
@@ -73,6 +195,7 @@ impl Default for AudioEngine {
 impl Drop for AudioEngine {
     fn drop(&mut self) {
         // Ensure audio is properly stopped when engine is dropped
+        let _ = self.stop_pre_buffer();
         let _ = self.stop_capture();
     }
 }
@@ -0,0 +1,161 @@
+//! Automatic Gain Control
+//!
+//! Brings microphone input to a steady level so quiet microphones still
+//! trigger the VAD and loud ones don't clip, enabled with `[audio.agc]`.
+//! The input level is tracked as a running RMS envelope in 10 ms blocks,
+//! rising with the attack time and falling with the release time; the gain
+//! that would bring the envelope to the target level is ramped in sample
+//! by sample. Below the noise gate the gain is held, so silence and room
+//! noise are not pumped up between words. A hard limiter catches the peaks
+//! the envelope is too slow for.
+
+use crate::config::AgcConfig;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Level-tracking block length
+const BLOCK_MS: f32 = 10.0;
+
+/// Envelope level (dBFS) below which the gain is held
+const GATE_DBFS: f32 = -60.0;
+
+/// Decibels to a linear factor
+fn from_db(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Current AGC gain, shared with level meters
+#[derive(Debug, Clone, Default)]
+pub struct GainMeter(Arc<AtomicU32>);
+
+impl GainMeter {
+    /// Applied gain in dB
+    pub fn db(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, db: f32) {
+        self.0.store(db.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Streaming gain control for mono audio
+#[derive(Debug)]
+pub struct Agc {
+    target: f32,
+    min_gain: f32,
+    max_gain: f32,
+    limit: f32,
+    gate: f32,
+    attack: f32,
+    release: f32,
+    block_len: usize,
+    sum_squares: f32,
+    count: usize,
+    envelope: f32,
+    gain: f32,
+    step: f32,
+    meter: GainMeter,
+}
+
+impl Agc {
+    /// Create for audio at `sample_rate`, reporting its gain to `meter`
+    pub fn new(config: &AgcConfig, sample_rate: u32, meter: GainMeter) -> Self {
+        // Per-block smoothing factor for a time constant
+        let coefficient = |ms: u32| (-BLOCK_MS / ms.max(1) as f32).exp();
+        meter.set(0.0);
+        Self {
+            target: from_db(config.target_dbfs),
+            min_gain: from_db(-config.max_gain_db),
+            max_gain: from_db(config.max_gain_db),
+            limit: from_db(config.limiter_dbfs.min(0.0)),
+            gate: from_db(GATE_DBFS),
+            attack: coefficient(config.attack_ms),
+            release: coefficient(config.release_ms),
+            block_len: ((sample_rate as f32 * BLOCK_MS / 1000.0) as usize).max(1),
+            sum_squares: 0.0,
+            count: 0,
+            envelope: 0.0,
+            gain: 1.0,
+            step: 0.0,
+            meter,
+        }
+    }
+
+    /// Apply gain and the limiter in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            self.sum_squares += *sample * *sample;
+            self.count += 1;
+            if self.count == self.block_len {
+                self.update();
+            }
+            self.gain += self.step;
+            *sample = (*sample * self.gain).clamp(-self.limit, self.limit);
+        }
+    }
+
+    /// Follow the level of the finished block and ramp towards its gain
+    fn update(&mut self) {
+        let level = (self.sum_squares / self.count as f32).sqrt();
+        self.sum_squares = 0.0;
+        self.count = 0;
+
+        let coefficient = if level > self.envelope {
+            self.attack
+        } else {
+            self.release
+        };
+        self.envelope = coefficient * self.envelope + (1.0 - coefficient) * level;
+
+        let desired = if self.envelope < self.gate {
+            self.gain
+        } else {
+            (self.target / self.envelope).clamp(self.min_gain, self.max_gain)
+        };
+        self.step = (desired - self.gain) / self.block_len as f32;
+        self.meter.set(20.0 * desired.log10());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::selftest::dbfs;
+
+    fn tone(amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(16000.0 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 300.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_agc_levels_and_limits() {
+        let config = AgcConfig::default();
+        let meter = GainMeter::default();
+        let mut agc = Agc::new(&config, 16000, meter.clone());
+
+        // A quiet voice (-45 dBFS RMS) is brought up to the target
+        let mut quiet = tone(0.008, 3.0);
+        agc.process(&mut quiet);
+        let level = dbfs(rms(&quiet[32000..]));
+        assert!((level - config.target_dbfs).abs() < 1.5, "{}", level);
+        assert!(meter.db() > 20.0);
+
+        // A sudden loud burst never exceeds the limiter
+        let mut loud = tone(0.9, 0.5);
+        agc.process(&mut loud);
+        let ceiling = from_db(config.limiter_dbfs);
+        assert!(loud.iter().all(|s| s.abs() <= ceiling + 1e-6));
+
+        // Near-silence is not pumped up
+        let mut agc = Agc::new(&config, 16000, GainMeter::default());
+        let mut silence = tone(0.0001, 2.0);
+        agc.process(&mut silence);
+        assert!(rms(&silence[16000..]) < 0.0002);
+    }
+}
@@ -0,0 +1,366 @@
+//! Per-Application Capture
+//!
+//! `onevox listen --app <name>` transcribes the audio a single application
+//! plays (e.g. just the conference app) instead of a microphone or the
+//! whole system mix. On Linux the app's PipeWire playback stream is found
+//! with `pw-dump` and tapped with `pw-record --target`, which leaves the
+//! app's own playback untouched. Other platforms aren't supported yet.
+//! System audio capture uses the same tap on the default output's monitor
+//! when there is no loopback device.
+
+use super::buffer::AudioChunk;
+use serde_json::Value;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// PipeWire media class of application playback streams
+const PLAYBACK_CLASS: &str = "Stream/Output/Audio";
+
+/// An application's audio playback stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppStream {
+    /// PipeWire node id
+    pub id: u64,
+    /// Node serial, preferred as a `pw-record` target (ids are reused)
+    pub serial: Option<u64>,
+    /// Application name ("Firefox", "ZOOM VoiceEngine")
+    pub app_name: String,
+    /// Executable name, when known
+    pub binary: Option<String>,
+    /// What the stream is playing
+    pub media_name: Option<String>,
+}
+
+impl AppStream {
+    /// Whether `app` names this stream's application or executable
+    /// (case-insensitive substring)
+    pub fn matches(&self, app: &str) -> bool {
+        let app = app.to_lowercase();
+        std::iter::once(&self.app_name)
+            .chain(self.binary.as_ref())
+            .any(|name| name.to_lowercase().contains(&app))
+    }
+
+    fn target(&self) -> String {
+        self.serial.unwrap_or(self.id).to_string()
+    }
+}
+
+impl std::fmt::Display for AppStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.app_name)?;
+        if let Some(binary) = &self.binary {
+            write!(f, " ({})", binary)?;
+        }
+        if let Some(media_name) = &self.media_name {
+            write!(f, " - {}", media_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Playback streams in `pw-dump` output
+fn parse_pw_dump(json: &str) -> crate::Result<Vec<AppStream>> {
+    let objects: Vec<Value> = serde_json::from_str(json)
+        .map_err(|e| crate::Error::Platform(format!("Unexpected pw-dump output: {}", e)))?;
+
+    let streams = objects
+        .iter()
+        .filter(|object| object["type"] == "PipeWire:Interface:Node")
+        .filter_map(|object| {
+            let props = &object["info"]["props"];
+            if props["media.class"] != PLAYBACK_CLASS {
+                return None;
+            }
+            let text = |key: &str| props[key].as_str().map(str::to_string);
+            let app_name = text("application.name").or_else(|| text("node.name"))?;
+            Some(AppStream {
+                id: object["id"].as_u64()?,
+                serial: props["object.serial"].as_u64(),
+                app_name,
+                binary: text("application.process.binary"),
+                media_name: text("media.name"),
+            })
+        })
+        .collect();
+    Ok(streams)
+}
+
+/// Applications currently playing audio
+pub fn list_apps() -> crate::Result<Vec<AppStream>> {
+    #[cfg(target_os = "linux")]
+    {
+        let output = Command::new("pw-dump").output().map_err(|e| {
+            crate::Error::Platform(format!(
+                "Per-app capture needs PipeWire's pw-dump ({}). Install pipewire-bin / pipewire-utils",
+                e
+            ))
+        })?;
+        if !output.status.success() {
+            return Err(crate::Error::Platform(format!(
+                "pw-dump failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        parse_pw_dump(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(crate::Error::Platform(
+            "Per-app capture is only supported on Linux with PipeWire".to_string(),
+        ))
+    }
+}
+
+/// The playback stream of `app`
+///
+/// When the app has several streams the first is used.
+pub fn find_app(app: &str) -> crate::Result<AppStream> {
+    let streams = list_apps()?;
+    let mut matching = streams.iter().filter(|stream| stream.matches(app));
+    let Some(stream) = matching.next() else {
+        let playing: Vec<String> = streams.iter().map(|s| s.app_name.clone()).collect();
+        return Err(crate::Error::Audio(if playing.is_empty() {
+            format!("'{}' isn't playing audio (no apps are)", app)
+        } else {
+            format!(
+                "'{}' isn't playing audio. Apps playing: {}",
+                app,
+                playing.join(", ")
+            )
+        }));
+    };
+    if matching.next().is_some() {
+        warn!("'{}' has several audio streams; capturing {}", app, stream);
+    }
+    Ok(stream.clone())
+}
+
+/// Running capture of one application's audio
+pub struct AppCapture {
+    stream: AppStream,
+    child: Child,
+}
+
+impl AppCapture {
+    /// Start capturing `app` as mono chunks at `sample_rate`
+    pub fn start(
+        app: &str,
+        sample_rate: u32,
+        chunk_duration_ms: u32,
+    ) -> crate::Result<(Self, mpsc::Receiver<AudioChunk>)> {
+        let stream = find_app(app)?;
+        info!("Capturing audio from {}", stream);
+
+        let chunk_samples = (sample_rate * chunk_duration_ms / 1000).max(1) as usize;
+        let (chunk_tx, chunk_rx) = mpsc::channel(32);
+        let child = pw_record(
+            &["--target".to_string(), stream.target()],
+            sample_rate,
+            chunk_samples,
+            chunk_tx,
+        )?;
+        Ok((Self { stream, child }, chunk_rx))
+    }
+
+    /// Stream being captured
+    pub fn stream(&self) -> &AppStream {
+        &self.stream
+    }
+
+    /// Stop capturing
+    pub fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for AppCapture {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Record the default output's monitor (everything the system plays) as
+/// mono chunks into `chunk_tx`; kill the returned process to stop
+#[cfg(all(feature = "audio-capture", target_os = "linux"))]
+pub(crate) fn record_default_monitor(
+    sample_rate: u32,
+    chunk_samples: usize,
+    chunk_tx: mpsc::Sender<AudioChunk>,
+) -> crate::Result<Child> {
+    info!("Capturing system audio from the default output's monitor");
+    pw_record(
+        &[
+            "-P".to_string(),
+            "{ stream.capture.sink = true }".to_string(),
+        ],
+        sample_rate,
+        chunk_samples,
+        chunk_tx,
+    )
+    .map_err(|e| {
+        crate::Error::Platform(format!(
+            "System audio capture needs a loopback device or PipeWire's pw-record ({})",
+            e
+        ))
+    })
+}
+
+/// Start `pw-record` with `args` and forward what it records as mono
+/// chunks from a reader thread
+fn pw_record(
+    args: &[String],
+    sample_rate: u32,
+    chunk_samples: usize,
+    chunk_tx: mpsc::Sender<AudioChunk>,
+) -> crate::Result<Child> {
+    let mut child = Command::new("pw-record")
+        .args(args)
+        .arg("--rate")
+        .arg(sample_rate.to_string())
+        .args(["--channels", "1", "--format", "f32", "-"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| crate::Error::Platform(format!("Failed to start pw-record: {}", e)))?;
+
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    std::thread::spawn(move || {
+        read_chunks(stdout, sample_rate, chunk_samples, chunk_tx);
+        let mut message = String::new();
+        let _ = { stderr }.read_to_string(&mut message);
+        if !message.trim().is_empty() {
+            warn!("pw-record: {}", message.trim());
+        }
+    });
+    Ok(child)
+}
+
+/// Forward little-endian f32 samples from `reader` as chunks until it ends
+/// (sending the partial last chunk) or the receiver goes away
+fn read_chunks(
+    mut reader: impl Read,
+    sample_rate: u32,
+    chunk_samples: usize,
+    chunk_tx: mpsc::Sender<AudioChunk>,
+) {
+    let mut bytes = match skip_wav_header(&mut reader) {
+        Ok(rest) => rest,
+        Err(e) => {
+            warn!("Failed to read captured audio: {}", e);
+            return;
+        }
+    };
+    let mut buf = vec![0u8; chunk_samples * 4];
+    let mut samples = Vec::with_capacity(chunk_samples);
+    loop {
+        let whole = bytes.len() - bytes.len() % 4;
+        samples.extend(
+            bytes[..whole]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+        bytes.drain(..whole);
+
+        while samples.len() >= chunk_samples {
+            let rest = samples.split_off(chunk_samples);
+            let chunk = AudioChunk::new(std::mem::replace(&mut samples, rest), sample_rate);
+            if chunk_tx.blocking_send(chunk).is_err() {
+                return;
+            }
+        }
+
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => bytes.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                warn!("Failed to read captured audio: {}", e);
+                break;
+            }
+        }
+    }
+    if !samples.is_empty() {
+        let _ = chunk_tx.blocking_send(AudioChunk::new(samples, sample_rate));
+    }
+    debug!("Application capture ended");
+}
+
+/// Consume a WAV header if the stream starts with one (pw-record writes
+/// WAV to a pipe unless raw output is supported), returning any audio
+/// bytes read past it
+fn skip_wav_header(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut magic = [0u8; 4];
+    let mut read = 0;
+    while read < magic.len() {
+        match reader.read(&mut magic[read..])? {
+            0 => return Ok(magic[..read].to_vec()),
+            n => read += n,
+        }
+    }
+    if &magic != b"RIFF" {
+        return Ok(magic.to_vec());
+    }
+
+    // Size and "WAVE"
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    loop {
+        let mut chunk = [0u8; 8];
+        reader.read_exact(&mut chunk)?;
+        if &chunk[..4] == b"data" {
+            return Ok(Vec::new());
+        }
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        std::io::copy(&mut reader.take(size + size % 2), &mut std::io::sink())?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pw_dump_and_samples() {
+        let json = r#"[
+            {"id": 30, "type": "PipeWire:Interface:Node",
+             "info": {"props": {"media.class": "Audio/Sink", "node.name": "speakers"}}},
+            {"id": 57, "type": "PipeWire:Interface:Node",
+             "info": {"props": {"media.class": "Stream/Output/Audio",
+                                "application.name": "ZOOM VoiceEngine",
+                                "application.process.binary": "zoom",
+                                "media.name": "playStream", "object.serial": 412}}},
+            {"id": 58, "type": "PipeWire:Interface:Link", "info": {}}
+        ]"#;
+        let streams = parse_pw_dump(json).unwrap();
+        assert_eq!(streams.len(), 1);
+        assert!(streams[0].matches("zoom"));
+        assert!(streams[0].matches("voiceengine"));
+        assert!(!streams[0].matches("firefox"));
+        assert_eq!(streams[0].target(), "412");
+        assert_eq!(
+            streams[0].to_string(),
+            "ZOOM VoiceEngine (zoom) - playStream"
+        );
+
+        // WAV-wrapped output yields the same samples as raw output
+        let samples = [0.5f32, -0.25, 1.0];
+        let raw: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut wav = b"RIFF\xff\xff\xff\xffWAVEfmt \x02\x00\x00\x00\x01\x00".to_vec();
+        wav.extend_from_slice(b"data\xff\xff\xff\xff");
+        wav.extend_from_slice(&raw);
+        for input in [raw.clone(), wav] {
+            let (tx, mut rx) = mpsc::channel(4);
+            read_chunks(input.as_slice(), 16000, 2, tx);
+            assert_eq!(rx.try_recv().unwrap().samples, vec![0.5, -0.25]);
+            assert_eq!(rx.try_recv().unwrap().samples, vec![1.0]);
+            assert!(rx.try_recv().is_err());
+        }
+    }
+}
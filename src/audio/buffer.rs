@@ -144,4 +144,19 @@ impl AudioChunk {
     pub fn len(&self) -> usize {
         self.samples.len()
     }
+
+    /// Root-mean-square level
+    pub fn rms(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        (self.samples.iter().map(|s| s * s).sum::<f32>() / self.samples.len() as f32).sqrt()
+    }
+
+    /// Largest absolute sample
+    pub fn peak(&self) -> f32 {
+        self.samples
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()))
+    }
 }
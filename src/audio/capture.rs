@@ -1,18 +1,53 @@
 //! Audio Capture
 //!
 //! Real-time microphone input using cpal.
-
+//!
+//! [`CaptureSource::System`] captures what the computer plays instead,
+//! from a loopback source (see [`super::devices`]); on Linux without one,
+//! PipeWire's monitor of the default output is recorded with `pw-record`.
+//! Devices are opened with their own channel count and mixed down to mono
+//! before anything else sees the audio.
+//!
+//! The stream lives on a capture thread that keeps it on a working device:
+//! when the device is unplugged (the stream reports it, or it stops
+//! delivering audio) the stream is reopened on the system default, and it
+//! moves back once the configured device reappears. The configured device
+//! can also change while capturing ([`AudioCapture::set_device`]).
+//! Consumers keep reading the same channel and can follow the switches
+//! through [`AudioCapture::take_device_changes`].
+
+use super::agc::{Agc, GainMeter};
 use super::buffer::AudioChunk;
-use super::devices::AudioDeviceManager;
+use super::devices::DeviceChange;
+#[cfg(feature = "audio-capture")]
+use super::devices::{AudioDeviceManager, wanted_device};
+#[cfg(feature = "audio-capture")]
 use cpal::traits::{DeviceTrait, StreamTrait};
+#[cfg(feature = "audio-capture")]
 use cpal::{Device, Sample as CpalSample, SampleFormat, Stream, StreamConfig};
 use rubato::{
     Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
 };
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(feature = "audio-capture")]
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::{error, info, trace, warn};
+use tracing::info;
+#[cfg(feature = "audio-capture")]
+use tracing::{debug, error, trace, warn};
+
+/// How often the capture thread checks on the stream
+#[cfg(feature = "audio-capture")]
+const SUPERVISE_TICK: Duration = Duration::from_millis(100);
+
+/// How often input devices are listed to notice ones plugged in or out
+#[cfg(feature = "audio-capture")]
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A stream that delivers no audio for this long is restarted
+#[cfg(feature = "audio-capture")]
+const STALL_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// Parameters for building an audio stream
 struct StreamParams {
@@ -20,12 +55,18 @@ struct StreamParams {
     chunk_size: usize,
     target_sample_rate: u32,
     device_sample_rate: u32,
+    /// Interleaved channels per frame from the device
+    channels: usize,
     is_running: Arc<AtomicBool>,
     channel_open: Arc<AtomicBool>,
+    agc: Option<Agc>,
+    dropped: Arc<AtomicU64>,
+    #[cfg(feature = "audio-capture")]
+    health: Arc<StreamHealth>,
 }
 
 /// Audio resampler for converting between sample rates
-struct AudioResampler {
+pub(crate) struct AudioResampler {
     resampler: SincFixedIn<f32>,
     input_buffer: Vec<Vec<f32>>,
     output_buffer: Vec<Vec<f32>>,
@@ -33,7 +74,7 @@ struct AudioResampler {
 
 impl AudioResampler {
     /// Create a new resampler
-    fn new(from_rate: u32, to_rate: u32, chunk_size: usize) -> crate::Result<Self> {
+    pub(crate) fn new(from_rate: u32, to_rate: u32, chunk_size: usize) -> crate::Result<Self> {
         let resample_ratio = to_rate as f64 / from_rate as f64;
 
         // Configure high-quality sinc resampler
@@ -70,7 +111,7 @@ impl AudioResampler {
     }
 
     /// Resample audio samples
-    fn resample(&mut self, input: &[f32]) -> crate::Result<Vec<f32>> {
+    pub(crate) fn resample(&mut self, input: &[f32]) -> crate::Result<Vec<f32>> {
         // Copy input to resampler buffer
         self.input_buffer[0][..input.len()].copy_from_slice(input);
 
@@ -83,11 +124,54 @@ impl AudioResampler {
         // Extract output samples
         Ok(self.output_buffer[0][..out_len].to_vec())
     }
+
+    /// Output samples of latency introduced by the sinc filter
+    pub(crate) fn output_delay(&self) -> usize {
+        self.resampler.output_delay()
+    }
+}
+
+/// Sample rates `audio.sample_rate` may be set to
+pub(crate) const VALID_SAMPLE_RATES: &[u32] = &[8000, 16000, 22050, 44100, 48000];
+
+/// Where audio is captured from (`[audio].source`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaptureSource {
+    /// An input device such as a microphone
+    #[default]
+    Microphone,
+    /// What the computer plays (loopback / monitor)
+    System,
+}
+
+impl CaptureSource {
+    /// Parse `audio.source` ("microphone" or "system")
+    pub fn parse(source: &str) -> crate::Result<Self> {
+        match source.to_ascii_lowercase().as_str() {
+            "microphone" | "mic" => Ok(CaptureSource::Microphone),
+            "system" | "loopback" => Ok(CaptureSource::System),
+            other => Err(crate::Error::Config(format!(
+                "Unknown audio source '{}' (expected microphone or system)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureSource::Microphone => write!(f, "microphone"),
+            CaptureSource::System => write!(f, "system"),
+        }
+    }
 }
 
 /// Audio capture configuration
 #[derive(Debug, Clone)]
 pub struct CaptureConfig {
+    /// What to capture
+    pub source: CaptureSource,
     /// Device name (or "default")
     pub device_name: String,
     /// Target sample rate
@@ -96,15 +180,19 @@ pub struct CaptureConfig {
     pub chunk_duration_ms: u32,
     /// Buffer capacity in seconds
     pub buffer_capacity_secs: u32,
+    /// Automatic gain control (`None` = off)
+    pub agc: Option<crate::config::AgcConfig>,
 }
 
 impl Default for CaptureConfig {
     fn default() -> Self {
         Self {
+            source: CaptureSource::Microphone,
             device_name: "default".to_string(),
             sample_rate: 16000,
             chunk_duration_ms: 200,
             buffer_capacity_secs: 2,
+            agc: None,
         }
     }
 }
@@ -112,8 +200,6 @@ impl Default for CaptureConfig {
 impl CaptureConfig {
     /// Validate capture settings before starting audio stream.
     fn validate(&self) -> crate::Result<()> {
-        const VALID_SAMPLE_RATES: &[u32] = &[8000, 16000, 22050, 44100, 48000];
-
         if !VALID_SAMPLE_RATES.contains(&self.sample_rate) {
             return Err(crate::Error::Config(format!(
                 "Unsupported sample rate {}. Supported values: {:?}",
@@ -135,15 +221,33 @@ impl CaptureConfig {
 
         Ok(())
     }
+
+    /// Chunks the capture channel holds: `buffer_capacity_secs` of audio,
+    /// at least 10
+    pub fn channel_capacity(&self) -> usize {
+        let chunk_size = (self.sample_rate * self.chunk_duration_ms / 1000).max(1);
+        ((self.sample_rate * self.buffer_capacity_secs / chunk_size) as usize).max(10)
+    }
 }
 
 /// Audio capture engine
 pub struct AudioCapture {
     config: CaptureConfig,
-    device_manager: AudioDeviceManager,
-    stream: Option<Stream>,
+    /// Thread that owns the stream and moves it between devices, with the
+    /// sender that passes it a new device (dropping it stops the thread)
+    #[cfg(feature = "audio-capture")]
+    supervisor: Option<(std::sync::mpsc::Sender<String>, std::thread::JoinHandle<()>)>,
+    /// `pw-record` of the default output's monitor, for system audio on
+    /// Linux without a loopback device
+    #[cfg(all(feature = "audio-capture", target_os = "linux"))]
+    monitor: Option<std::process::Child>,
     is_running: Arc<AtomicBool>,
     chunk_tx: Option<mpsc::Sender<AudioChunk>>,
+    gain_meter: Option<GainMeter>,
+    /// Chunks dropped because the consumer fell behind
+    dropped: Arc<AtomicU64>,
+    /// Device switches, until taken
+    device_changes: Option<mpsc::UnboundedReceiver<DeviceChange>>,
 }
 
 impl AudioCapture {
@@ -151,14 +255,53 @@ impl AudioCapture {
     pub fn new(config: CaptureConfig) -> Self {
         Self {
             config,
-            device_manager: AudioDeviceManager::new(),
-            stream: None,
+            #[cfg(feature = "audio-capture")]
+            supervisor: None,
+            #[cfg(all(feature = "audio-capture", target_os = "linux"))]
+            monitor: None,
             is_running: Arc::new(AtomicBool::new(false)),
             chunk_tx: None,
+            gain_meter: None,
+            dropped: Arc::new(AtomicU64::new(0)),
+            device_changes: None,
+        }
+    }
+
+    /// Gain applied by the AGC, while it is on
+    pub fn gain_meter(&self) -> Option<GainMeter> {
+        self.gain_meter.clone()
+    }
+
+    /// Chunks dropped so far because the buffer was full
+    pub fn dropped_chunks(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Capture from `device_name` ("default" for the system default),
+    /// switching a running stream over
+    pub fn set_device(&mut self, device_name: &str) {
+        if self.config.device_name == device_name {
+            return;
+        }
+        self.config.device_name = device_name.to_string();
+        #[cfg(feature = "audio-capture")]
+        if let Some((control, _)) = &self.supervisor {
+            let _ = control.send(device_name.to_string());
         }
     }
 
+    /// Switches to another input device while capturing (taken once per
+    /// start)
+    pub fn take_device_changes(&mut self) -> Option<mpsc::UnboundedReceiver<DeviceChange>> {
+        self.device_changes.take()
+    }
+
     /// Start capturing audio
+    ///
+    /// The stream runs on its own thread, which restarts it when the device
+    /// is unplugged or stops delivering audio, falling back to the default
+    /// device, and moves back to the configured device when it returns.
+    #[cfg(feature = "audio-capture")]
     pub fn start(&mut self) -> crate::Result<mpsc::Receiver<AudioChunk>> {
         if self.is_running.load(Ordering::SeqCst) {
             return Err(crate::Error::Audio("Capture already running".to_string()));
@@ -168,34 +311,12 @@ impl AudioCapture {
 
         info!("Starting audio capture");
 
-        // Get device
-        let device = if self.config.device_name == "default" {
-            self.device_manager.default_input_device()?
-        } else {
-            self.device_manager
-                .get_device_by_name(&self.config.device_name)?
-        };
-
-        let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
-        info!("Using audio device: {}", device_name);
-
-        // Get device config
-        let supported_config = self.device_manager.get_device_config(&device)?;
-        let sample_format = supported_config.sample_format();
-        let device_sample_rate = supported_config.sample_rate().0;
-
-        info!(
-            "Device config: {}Hz, format: {:?}",
-            device_sample_rate, sample_format
-        );
-
         // Create bounded channel for audio chunks
         // Buffer size = (sample_rate * buffer_capacity_secs) / chunk_size
         // This ensures we don't buffer more than buffer_capacity_secs of audio
         let chunk_size = (self.config.sample_rate * self.config.chunk_duration_ms / 1000) as usize;
-        let buffer_capacity = ((self.config.sample_rate * self.config.buffer_capacity_secs)
-            / chunk_size as u32) as usize;
-        let (chunk_tx, chunk_rx) = mpsc::channel(buffer_capacity.max(10)); // At least 10 chunks
+        let buffer_capacity = self.config.channel_capacity();
+        let (chunk_tx, chunk_rx) = mpsc::channel(buffer_capacity);
 
         info!(
             "Audio buffer capacity: {} chunks (~{}s of audio), chunk_size: {} samples",
@@ -203,39 +324,200 @@ impl AudioCapture {
         );
 
         self.chunk_tx = Some(chunk_tx.clone());
+        self.gain_meter = self.config.agc.as_ref().map(|_| GainMeter::default());
+
+        #[cfg(target_os = "linux")]
+        if self.config.source == CaptureSource::System
+            && self.config.device_name == "default"
+            && AudioDeviceManager::new()
+                .loopback_device_names()
+                .is_ok_and(|(names, _)| names.is_empty())
+        {
+            let monitor = match super::app_capture::record_default_monitor(
+                self.config.sample_rate,
+                chunk_size,
+                chunk_tx,
+            ) {
+                Ok(monitor) => monitor,
+                Err(e) => {
+                    self.chunk_tx = None;
+                    return Err(e);
+                }
+            };
+            self.monitor = Some(monitor);
+            self.is_running.store(true, Ordering::SeqCst);
+            info!("Audio capture started (monitor of the default output)");
+            return Ok(chunk_rx);
+        }
+
+        let setup = StreamSetup {
+            source: self.config.source,
+            chunk_tx,
+            chunk_size,
+            target_sample_rate: self.config.sample_rate,
+            is_running: Arc::clone(&self.is_running),
+            channel_open: Arc::new(AtomicBool::new(true)),
+            agc: self.config.agc.clone(),
+            gain_meter: self.gain_meter.clone(),
+            dropped: Arc::clone(&self.dropped),
+        };
+        let preferred = self.config.device_name.clone();
+        let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+        let (control_tx, control_rx) = std::sync::mpsc::channel();
+        let (changes_tx, changes_rx) = mpsc::unbounded_channel();
+        let thread = std::thread::Builder::new()
+            .name("onevox-audio-capture".to_string())
+            .spawn(move || supervise(setup, preferred, ready_tx, control_rx, changes_tx))
+            .map_err(|e| crate::Error::Audio(format!("Failed to start capture thread: {}", e)))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                self.chunk_tx = None;
+                return Err(e);
+            }
+            Err(_) => {
+                let _ = thread.join();
+                self.chunk_tx = None;
+                return Err(crate::Error::Audio(
+                    "Capture thread exited during startup".to_string(),
+                ));
+            }
+        }
+        self.supervisor = Some((control_tx, thread));
+        self.device_changes = Some(changes_rx);
+
+        info!("Audio capture started");
+        Ok(chunk_rx)
+    }
+
+    /// Start capturing audio (unavailable without the audio-capture feature)
+    #[cfg(not(feature = "audio-capture"))]
+    pub fn start(&mut self) -> crate::Result<mpsc::Receiver<AudioChunk>> {
+        Err(crate::Error::Audio(
+            "audio-capture feature not enabled".to_string(),
+        ))
+    }
+
+    /// Stop capturing audio
+    pub fn stop(&mut self) -> crate::Result<()> {
+        if !self.is_running.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        info!("Stopping audio capture");
+        self.is_running.store(false, Ordering::SeqCst);
+
+        // Dropping the sender wakes the capture thread, which pauses and
+        // drops the stream
+        #[cfg(feature = "audio-capture")]
+        if let Some((control, thread)) = self.supervisor.take() {
+            drop(control);
+            if thread.join().is_err() {
+                error!("Audio capture thread panicked");
+            }
+        }
+
+        #[cfg(all(feature = "audio-capture", target_os = "linux"))]
+        if let Some(mut monitor) = self.monitor.take() {
+            let _ = monitor.kill();
+            let _ = monitor.wait();
+        }
+
+        self.chunk_tx = None;
+
+        info!("Audio capture stopped");
+        Ok(())
+    }
+
+    /// Check if capture is running
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for AudioCapture {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// What the audio callback reports to the capture thread
+#[cfg(feature = "audio-capture")]
+#[derive(Default)]
+struct StreamHealth {
+    /// The stream reported its device gone
+    lost: AtomicBool,
+    /// Callbacks so far, to notice a stream that stopped delivering audio
+    callbacks: AtomicU64,
+}
 
-        // Create stream config
+/// Everything needed to open a stream on any device
+#[cfg(feature = "audio-capture")]
+struct StreamSetup {
+    source: CaptureSource,
+    chunk_tx: mpsc::Sender<AudioChunk>,
+    chunk_size: usize,
+    target_sample_rate: u32,
+    is_running: Arc<AtomicBool>,
+    channel_open: Arc<AtomicBool>,
+    agc: Option<crate::config::AgcConfig>,
+    gain_meter: Option<GainMeter>,
+    dropped: Arc<AtomicU64>,
+}
+
+#[cfg(feature = "audio-capture")]
+impl StreamSetup {
+    /// Open and start a stream on `device`
+    fn open(
+        &self,
+        manager: &AudioDeviceManager,
+        device: &Device,
+        health: &Arc<StreamHealth>,
+    ) -> crate::Result<Stream> {
+        let supported_config = manager.get_device_config(device)?;
+        let sample_format = supported_config.sample_format();
+        let device_sample_rate = supported_config.sample_rate().0;
+        let channels = supported_config.channels();
+
+        info!(
+            "Device config: {}Hz, {} ch, format: {:?}",
+            device_sample_rate, channels, sample_format
+        );
+
+        // Opened as the device delivers and mixed down to mono, since many
+        // devices (loopbacks especially) can't do mono themselves
         let stream_config = StreamConfig {
-            channels: 1, // We want mono
+            channels,
             sample_rate: cpal::SampleRate(device_sample_rate),
             buffer_size: cpal::BufferSize::Default,
         };
 
-        let target_sample_rate = self.config.sample_rate;
-        let is_running = Arc::clone(&self.is_running);
-        let channel_open = Arc::new(AtomicBool::new(true));
-
-        // Build stream config
-        let stream_params = StreamParams {
-            chunk_tx,
-            chunk_size,
-            target_sample_rate,
+        // A fresh AGC per stream; the new device has its own level
+        let agc = self
+            .agc
+            .as_ref()
+            .zip(self.gain_meter.clone())
+            .map(|(agc, meter)| Agc::new(agc, self.target_sample_rate, meter));
+
+        let params = StreamParams {
+            chunk_tx: self.chunk_tx.clone(),
+            chunk_size: self.chunk_size,
+            target_sample_rate: self.target_sample_rate,
             device_sample_rate,
-            is_running: Arc::clone(&is_running),
-            channel_open,
+            channels: usize::from(channels.max(1)),
+            is_running: Arc::clone(&self.is_running),
+            channel_open: Arc::clone(&self.channel_open),
+            agc,
+            dropped: Arc::clone(&self.dropped),
+            health: Arc::clone(health),
         };
 
-        // Build the input stream
         let stream = match sample_format {
-            SampleFormat::F32 => {
-                self.build_input_stream::<f32>(&device, &stream_config, stream_params)?
-            }
-            SampleFormat::I16 => {
-                self.build_input_stream::<i16>(&device, &stream_config, stream_params)?
-            }
-            SampleFormat::U16 => {
-                self.build_input_stream::<u16>(&device, &stream_config, stream_params)?
-            }
+            SampleFormat::F32 => build_input_stream::<f32>(device, &stream_config, params)?,
+            SampleFormat::I16 => build_input_stream::<i16>(device, &stream_config, params)?,
+            SampleFormat::U16 => build_input_stream::<u16>(device, &stream_config, params)?,
             _ => {
                 return Err(crate::Error::Audio(format!(
                     "Unsupported sample format: {:?}",
@@ -244,167 +526,324 @@ impl AudioCapture {
             }
         };
 
-        // Start the stream
         stream
             .play()
             .map_err(|e| crate::Error::Audio(format!("Failed to start stream: {}", e)))?;
-
-        self.stream = Some(stream);
-        is_running.store(true, Ordering::SeqCst);
-
-        info!("Audio capture started");
-        Ok(chunk_rx)
+        Ok(stream)
     }
 
-    /// Build input stream for a specific sample type
-    fn build_input_stream<T>(
+    /// Open a stream on the device named `name`
+    fn open_named(
         &self,
-        device: &Device,
-        config: &StreamConfig,
-        params: StreamParams,
-    ) -> crate::Result<Stream>
-    where
-        T: CpalSample + cpal::SizedSample,
-        f32: cpal::FromSample<T>,
-    {
-        let StreamParams {
-            chunk_tx,
-            chunk_size,
-            target_sample_rate,
-            device_sample_rate,
-            is_running,
-            channel_open,
-        } = params;
+        manager: &AudioDeviceManager,
+        name: &str,
+        health: &Arc<StreamHealth>,
+    ) -> crate::Result<Stream> {
+        let device = manager
+            .find_device(self.source, name)?
+            .ok_or_else(|| crate::Error::Audio(format!("Device '{}' not found", name)))?;
+        self.open(manager, &device, health)
+    }
+}
+
+/// Run the capture stream until `control` is dropped, moving it to the
+/// wanted device when the current one goes away, stalls, or is no longer
+/// wanted; `control` also delivers a newly configured device
+#[cfg(feature = "audio-capture")]
+fn supervise(
+    setup: StreamSetup,
+    mut preferred: String,
+    ready: std::sync::mpsc::SyncSender<crate::Result<()>>,
+    control: std::sync::mpsc::Receiver<String>,
+    changes: mpsc::UnboundedSender<DeviceChange>,
+) {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let manager = AudioDeviceManager::new();
+    let mut health = Arc::new(StreamHealth::default());
+
+    let device = match setup.source {
+        CaptureSource::Microphone if preferred == "default" => manager.default_input_device(),
+        CaptureSource::Microphone => manager.get_device_by_name(&preferred),
+        CaptureSource::System => manager.loopback_device(&preferred),
+    };
+    let opened = device.and_then(|device| {
+        let name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        info!("Using audio device: {}", name);
+        setup
+            .open(&manager, &device, &health)
+            .map(|stream| (name, stream))
+    });
+    let (mut current, mut stream) = match opened {
+        Ok((name, stream)) => (Some(name), Some(stream)),
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    setup.is_running.store(true, Ordering::SeqCst);
+    let _ = ready.send(Ok(()));
+
+    let mut last_poll = Instant::now();
+    let mut last_stall_check = Instant::now();
+    let mut callbacks = 0;
+    loop {
+        let mut reconfigured = false;
+        match control.recv_timeout(SUPERVISE_TICK) {
+            Ok(device) => {
+                reconfigured = device != preferred;
+                preferred = device;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
 
-        let mut local_accumulator = Vec::with_capacity(chunk_size);
-        let needs_resampling = device_sample_rate != target_sample_rate;
+        let lost = health.lost.swap(false, Ordering::Relaxed);
+        let mut stalled = false;
+        if last_stall_check.elapsed() >= STALL_TIMEOUT {
+            let now = health.callbacks.load(Ordering::Relaxed);
+            stalled = stream.is_some() && now == callbacks;
+            callbacks = now;
+            last_stall_check = Instant::now();
+        }
+        if !lost && !stalled && !reconfigured && last_poll.elapsed() < DEVICE_POLL_INTERVAL {
+            continue;
+        }
+        last_poll = Instant::now();
 
-        // Create resampler if needed
-        let mut resampler = if needs_resampling {
-            match AudioResampler::new(device_sample_rate, target_sample_rate, chunk_size) {
-                Ok(r) => Some(r),
-                Err(e) => {
-                    warn!(
-                        "Failed to create resampler, audio quality may be degraded: {}",
-                        e
-                    );
-                    None
-                }
+        let (present, default) = match manager.device_names(setup.source) {
+            Ok(names) => names,
+            Err(e) => {
+                warn!("{}", e);
+                continue;
             }
-        } else {
-            None
         };
+        let wanted = wanted_device(&preferred, &present, default.as_deref());
+        let current_present = current.as_ref().is_some_and(|name| present.contains(name));
+        if !lost && !stalled && current_present && wanted == current {
+            continue;
+        }
+        if lost || stalled {
+            warn!(
+                "Audio device {} {}; restarting capture",
+                current.as_deref().unwrap_or("(none)"),
+                if lost {
+                    "disconnected"
+                } else {
+                    "stopped delivering audio"
+                }
+            );
+        }
 
-        let mut dropped_chunks = 0u64;
-        let mut last_warning = std::time::Instant::now();
+        if let Some(old) = stream.take()
+            && let Err(e) = old.pause()
+        {
+            debug!("Failed to pause audio stream: {}", e);
+        }
+        health = Arc::new(StreamHealth::default());
+        callbacks = 0;
+        last_stall_check = Instant::now();
+
+        // The wanted device, else the default if that's another one
+        let fallback = default.filter(|name| Some(name) != wanted.as_ref());
+        let mut next = None;
+        for name in wanted.into_iter().chain(fallback) {
+            match setup.open_named(&manager, &name, &health) {
+                Ok(opened) => {
+                    next = Some((name, opened));
+                    break;
+                }
+                Err(e) => warn!("Failed to open audio device {}: {}", name, e),
+            }
+        }
+        let to = next.as_ref().map(|(name, _)| name.clone());
+        stream = next.map(|(_, stream)| stream);
+
+        if to != current {
+            let change = DeviceChange {
+                from: current.clone(),
+                to: to.clone(),
+            };
+            warn!("🎙️  {}", change);
+            let _ = changes.send(change);
+        }
+        current = to;
+    }
 
-        let stream = device
-            .build_input_stream(
-                config,
-                move |data: &[T], _: &cpal::InputCallbackInfo| {
-                    if !is_running.load(Ordering::Relaxed) || !channel_open.load(Ordering::Relaxed)
-                    {
-                        return;
-                    }
+    if let Some(stream) = stream.take()
+        && let Err(e) = stream.pause()
+    {
+        error!("Failed to pause audio stream: {}", e);
+    }
+}
+
+/// Build input stream for a specific sample type
+#[cfg(feature = "audio-capture")]
+fn build_input_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    params: StreamParams,
+) -> crate::Result<Stream>
+where
+    T: CpalSample + cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let StreamParams {
+        chunk_tx,
+        chunk_size,
+        target_sample_rate,
+        device_sample_rate,
+        channels,
+        is_running,
+        channel_open,
+        mut agc,
+        dropped,
+        health,
+    } = params;
+
+    let mut local_accumulator = Vec::with_capacity(chunk_size);
+    let needs_resampling = device_sample_rate != target_sample_rate;
+
+    // Create resampler if needed
+    let mut resampler = if needs_resampling {
+        match AudioResampler::new(device_sample_rate, target_sample_rate, chunk_size) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                warn!(
+                    "Failed to create resampler, audio quality may be degraded: {}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut dropped_chunks = 0u64;
+    let mut last_warning = std::time::Instant::now();
+
+    let error_health = Arc::clone(&health);
+    let stream = device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                health.callbacks.fetch_add(1, Ordering::Relaxed);
+                if !is_running.load(Ordering::Relaxed) || !channel_open.load(Ordering::Relaxed)
+                {
+                    return;
+                }
 
-                    // Convert samples to f32
-                    for &sample in data.iter() {
-                        let f32_sample: f32 = cpal::Sample::from_sample(sample);
-                        local_accumulator.push(f32_sample);
-
-                        // When we have enough samples for a chunk
-                        if local_accumulator.len() >= chunk_size {
-                            let samples = std::mem::replace(
-                                &mut local_accumulator,
-                                Vec::with_capacity(chunk_size),
-                            );
-
-                            // Resample if necessary
-                            let chunk = if let Some(ref mut resampler) = resampler {
-                                match resampler.resample(&samples) {
-                                    Ok(resampled) => AudioChunk::new(resampled, target_sample_rate),
-                                    Err(e) => {
-                                        // Fallback to original samples on error
-                                        warn!("Resampling error, using original samples: {}", e);
-                                        AudioChunk::new(samples, device_sample_rate)
-                                    }
+                // Convert samples to f32 and mix down to mono
+                let samples: Vec<f32> = data
+                    .iter()
+                    .map(|&sample| cpal::Sample::from_sample(sample))
+                    .collect();
+                for sample in mix_to_mono(&samples, channels) {
+                    local_accumulator.push(sample);
+
+                    // When we have enough samples for a chunk
+                    if local_accumulator.len() >= chunk_size {
+                        let samples = std::mem::replace(
+                            &mut local_accumulator,
+                            Vec::with_capacity(chunk_size),
+                        );
+
+                        // Resample if necessary
+                        let mut chunk = if let Some(ref mut resampler) = resampler {
+                            match resampler.resample(&samples) {
+                                Ok(resampled) => AudioChunk::new(resampled, target_sample_rate),
+                                Err(e) => {
+                                    // Fallback to original samples on error
+                                    warn!("Resampling error, using original samples: {}", e);
+                                    AudioChunk::new(samples, device_sample_rate)
                                 }
-                            } else {
-                                AudioChunk::new(samples, target_sample_rate)
-                            };
-
-                            // Send chunk (with backpressure handling)
-                            // Use try_send to avoid blocking the audio thread
-                            match chunk_tx.try_send(chunk) {
-                                Ok(_) => {
-                                    // Reset dropped counter on success
-                                    if dropped_chunks > 0 {
-                                        dropped_chunks = 0;
-                                    }
+                            }
+                        } else if needs_resampling {
+                            // No resampler: label the chunk with the rate it really has
+                            AudioChunk::new(samples, device_sample_rate)
+                        } else {
+                            AudioChunk::new(samples, target_sample_rate)
+                        };
+
+                        if let Some(agc) = agc.as_mut() {
+                            agc.process(&mut chunk.samples);
+                        }
+
+                        // Send chunk (with backpressure handling)
+                        // Use try_send to avoid blocking the audio thread
+                        match chunk_tx.try_send(chunk) {
+                            Ok(_) => {
+                                // Reset dropped counter on success
+                                if dropped_chunks > 0 {
+                                    dropped_chunks = 0;
                                 }
-                                Err(mpsc::error::TrySendError::Full(_)) => {
-                                    // Buffer full - drop this chunk to avoid blocking audio callback
-                                    dropped_chunks += 1;
-
-                                    // Warn periodically about dropped chunks
-                                    if last_warning.elapsed().as_secs() >= 5 {
-                                        warn!(
-                                            "Audio buffer full, dropped {} chunks (transcription too slow)",
-                                            dropped_chunks
-                                        );
-                                        last_warning = std::time::Instant::now();
-                                    }
+                            }
+                            Err(mpsc::error::TrySendError::Full(_)) => {
+                                // Buffer full - drop this chunk to avoid blocking audio callback
+                                dropped_chunks += 1;
+                                dropped.fetch_add(1, Ordering::Relaxed);
+
+                                // Warn periodically about dropped chunks
+                                if last_warning.elapsed().as_secs() >= 5 {
+                                    warn!(
+                                        "Audio buffer full, dropped {} chunks (transcription too slow)",
+                                        dropped_chunks
+                                    );
+                                    last_warning = std::time::Instant::now();
                                 }
-                                Err(mpsc::error::TrySendError::Closed(_)) => {
-                                    if channel_open.swap(false, Ordering::Relaxed) {
-                                        trace!("Audio receiver closed, stopping chunk delivery");
-                                    }
+                            }
+                            Err(mpsc::error::TrySendError::Closed(_)) => {
+                                if channel_open.swap(false, Ordering::Relaxed) {
+                                    trace!("Audio receiver closed, stopping chunk delivery");
                                 }
                             }
                         }
                     }
-                },
-                move |err| {
-                    error!("Audio stream error: {}", err);
-                },
-                None,
-            )
-            .map_err(|e| crate::Error::Audio(format!("Failed to build stream: {}", e)))?;
-
-        Ok(stream)
-    }
-
-    /// Stop capturing audio
-    pub fn stop(&mut self) -> crate::Result<()> {
-        if !self.is_running.load(Ordering::SeqCst) {
-            return Ok(());
-        }
-
-        info!("Stopping audio capture");
-        self.is_running.store(false, Ordering::SeqCst);
-
-        if let Some(stream) = self.stream.take() {
-            // Explicitly pause the stream before dropping to ensure proper cleanup
-            if let Err(e) = stream.pause() {
-                error!("Failed to pause audio stream: {}", e);
-            }
-            drop(stream);
-        }
-
-        self.chunk_tx = None;
+                }
+            },
+            move |err| {
+                error!("Audio stream error: {}", err);
+                if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                    error_health.lost.store(true, Ordering::Relaxed);
+                }
+            },
+            None,
+        )
+        .map_err(|e| crate::Error::Audio(format!("Failed to build stream: {}", e)))?;
 
-        info!("Audio capture stopped");
-        Ok(())
-    }
+    Ok(stream)
+}
 
-    /// Check if capture is running
-    pub fn is_running(&self) -> bool {
-        self.is_running.load(Ordering::SeqCst)
+/// Average interleaved frames of `channels` samples into mono samples
+fn mix_to_mono(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
     }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
 }
 
-impl Drop for AudioCapture {
-    fn drop(&mut self) {
-        let _ = self.stop();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_to_mono_and_source() {
+        assert_eq!(mix_to_mono(&[0.5, -0.5, 1.0, 0.0], 2), vec![0.0, 0.5]);
+        assert_eq!(mix_to_mono(&[0.25, 0.5, 0.75], 3), vec![0.5]);
+        assert_eq!(mix_to_mono(&[0.1, 0.2], 1), vec![0.1, 0.2]);
+
+        assert_eq!(
+            CaptureSource::parse("System").unwrap(),
+            CaptureSource::System
+        );
+        assert_eq!(
+            CaptureSource::parse(&CaptureSource::Microphone.to_string()).unwrap(),
+            CaptureSource::Microphone
+        );
+        assert!(CaptureSource::parse("speakers").is_err());
     }
 }
@@ -0,0 +1,415 @@
+//! File Audio Conversion
+//!
+//! Backends only accept mono audio at the model's sample rate. Audio files
+//! come in arbitrary formats (WAV, MP3, FLAC, OGG Vorbis, AAC in MP4/MOV),
+//! sample rates, bit depths, and channel counts, so file transcription
+//! decodes them to f32, mixes channels down to mono, and resamples with the
+//! same sinc resampler used for live capture. Containers the built-in
+//! decoders can't read (e.g. WebM screen recordings) go through `ffmpeg`
+//! when it is installed. The conversion applied is reported so users can
+//! tell what the model heard.
+
+use super::capture::AudioResampler;
+use std::path::Path;
+
+/// Input frames per resampler call
+const RESAMPLE_CHUNK: usize = 4096;
+
+/// Audio decoded from a file, before conversion
+#[derive(Debug, Clone)]
+pub struct DecodedAudio {
+    /// Interleaved samples in [-1.0, 1.0]
+    pub samples: Vec<f32>,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Number of interleaved channels
+    pub channels: u16,
+    /// Bits per sample in the file
+    pub bits_per_sample: u16,
+    /// Whether the file stores floating-point samples
+    pub float: bool,
+    /// Compressed codec, e.g. "mp3" (`None` for PCM)
+    pub codec: Option<String>,
+}
+
+/// Conversion applied to make a file model-ready
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionReport {
+    /// File sample rate in Hz
+    pub source_rate: u32,
+    /// File channel count
+    pub source_channels: u16,
+    /// File sample format, e.g. "24-bit int"
+    pub source_format: String,
+    /// Sample rate the model expects
+    pub target_rate: u32,
+    /// Duration in seconds
+    pub duration_secs: f32,
+}
+
+impl ConversionReport {
+    /// Whether the channels were mixed down to mono
+    pub fn downmixed(&self) -> bool {
+        self.source_channels > 1
+    }
+
+    /// Whether the audio was resampled
+    pub fn resampled(&self) -> bool {
+        self.source_rate != self.target_rate
+    }
+
+    /// Whether the file was already in the model's format
+    pub fn is_passthrough(&self) -> bool {
+        !self.downmixed() && !self.resampled()
+    }
+}
+
+impl std::fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let channels = match self.source_channels {
+            1 => "mono".to_string(),
+            2 => "stereo".to_string(),
+            n => format!("{} channels", n),
+        };
+        write!(
+            f,
+            "{} Hz {} {} ({:.1}s)",
+            self.source_rate, channels, self.source_format, self.duration_secs
+        )?;
+
+        if self.is_passthrough() {
+            return write!(f, ", no conversion needed");
+        }
+
+        let mut steps = Vec::new();
+        if self.downmixed() {
+            steps.push("mixed to mono".to_string());
+        }
+        if self.resampled() {
+            steps.push(format!(
+                "resampled {} → {} Hz",
+                self.source_rate, self.target_rate
+            ));
+        }
+        write!(f, ", {}", steps.join(", "))
+    }
+}
+
+/// Decode a WAV file of any bit depth and channel count to f32
+pub fn decode_wav(path: &Path) -> crate::Result<DecodedAudio> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| crate::Error::Audio(format!("Failed to read {:?}: {}", path, e)))?;
+    let spec = reader.spec();
+
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| crate::Error::Audio(format!("Failed to decode {:?}: {}", path, e)))?;
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels.max(1),
+        bits_per_sample: spec.bits_per_sample,
+        float: spec.sample_format == hound::SampleFormat::Float,
+        codec: None,
+    })
+}
+
+/// Decode a compressed file (MP3, FLAC, OGG Vorbis, AAC in MP4/MOV) to f32
+pub fn decode_compressed(path: &Path) -> crate::Result<DecodedAudio> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+    use symphonia::core::errors::Error as DecodeError;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::probe::Hint;
+
+    let decode_err =
+        |e: DecodeError| crate::Error::Audio(format!("Failed to decode {:?}: {}", path, e));
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| crate::Error::Audio(format!("Failed to read {:?}: {}", path, e)))?;
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            MediaSourceStream::new(Box::new(file), Default::default()),
+            &Default::default(),
+            &Default::default(),
+        )
+        .map_err(decode_err)?
+        .format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| crate::Error::Audio(format!("No audio track in {:?}", path)))?;
+    let track_id = track.id;
+    let params = track.codec_params.clone();
+    let codecs = symphonia::default::get_codecs();
+    let mut decoder = codecs
+        .make(&params, &DecoderOptions::default())
+        .map_err(decode_err)?;
+
+    let mut samples = Vec::new();
+    let mut sample_rate = params.sample_rate.unwrap_or(0);
+    let mut channels = params.channels.map_or(0, |c| c.count() as u16);
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(decode_err(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Skip corrupt frames rather than failing the whole file
+            Err(DecodeError::DecodeError(_)) => continue,
+            Err(e) => return Err(decode_err(e)),
+        };
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        channels = spec.channels.count() as u16;
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    if sample_rate == 0 || samples.is_empty() {
+        return Err(crate::Error::Audio(format!(
+            "No audio decoded from {:?}",
+            path
+        )));
+    }
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels: channels.max(1),
+        bits_per_sample: params.bits_per_sample.unwrap_or(16) as u16,
+        float: false,
+        codec: codecs
+            .get_codec(params.codec)
+            .map(|descriptor| descriptor.short_name.to_string()),
+    })
+}
+
+/// Decode a WAV, MP3, FLAC, OGG, or MP4/MOV file, chosen by extension
+pub fn decode_file(path: &Path) -> crate::Result<DecodedAudio> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "wav" | "wave" => decode_wav(path),
+        _ => decode_compressed(path),
+    }
+}
+
+/// Write mono samples as a 16-bit PCM WAV file
+pub fn save_wav(path: &Path, samples: &[f32], sample_rate: u32) -> crate::Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let encode_err =
+        |e: hound::Error| crate::Error::Audio(format!("Failed to write {:?}: {}", path, e));
+
+    let mut writer = hound::WavWriter::create(path, spec).map_err(encode_err)?;
+    for &sample in samples {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(encode_err)?;
+    }
+    writer.finalize().map_err(encode_err)
+}
+
+/// Average interleaved channels into mono
+pub fn mix_to_mono(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Resample mono audio between sample rates
+///
+/// The output is aligned with the input (filter delay removed) and has the
+/// length implied by the rate ratio.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> crate::Result<Vec<f32>> {
+    if from_rate == to_rate || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let mut resampler = AudioResampler::new(from_rate, to_rate, RESAMPLE_CHUNK)?;
+    let expected = (samples.len() as f64 * to_rate as f64 / from_rate as f64).round() as usize;
+    let delay = resampler.output_delay();
+
+    // Zero-padding past the end flushes the filter tail
+    let mut output = Vec::with_capacity(expected + delay + RESAMPLE_CHUNK);
+    let mut chunk = vec![0.0f32; RESAMPLE_CHUNK];
+    let mut offset = 0;
+    while output.len() < expected + delay {
+        chunk.fill(0.0);
+        let take = RESAMPLE_CHUNK.min(samples.len() - offset);
+        chunk[..take].copy_from_slice(&samples[offset..offset + take]);
+        offset += take;
+        output.extend(resampler.resample(&chunk)?);
+    }
+
+    output.drain(..delay);
+    output.truncate(expected);
+    Ok(output)
+}
+
+/// Convert decoded audio to mono at `target_rate`
+pub fn convert(
+    audio: DecodedAudio,
+    target_rate: u32,
+) -> crate::Result<(Vec<f32>, ConversionReport)> {
+    let frames = audio.samples.len() / audio.channels as usize;
+    let report = ConversionReport {
+        source_rate: audio.sample_rate,
+        source_channels: audio.channels,
+        source_format: match &audio.codec {
+            Some(codec) => codec.clone(),
+            None => format!(
+                "{}-bit {}",
+                audio.bits_per_sample,
+                if audio.float { "float" } else { "int" }
+            ),
+        },
+        target_rate,
+        duration_secs: frames as f32 / audio.sample_rate.max(1) as f32,
+    };
+
+    let mono = mix_to_mono(&audio.samples, audio.channels as usize);
+    let samples = resample(&mono, audio.sample_rate, target_rate)?;
+    Ok((samples, report))
+}
+
+/// Load a WAV file as mono samples at `target_rate` (use
+/// `ModelRuntime::sample_rate` for the model's rate)
+pub fn load_wav(path: &Path, target_rate: u32) -> crate::Result<(Vec<f32>, ConversionReport)> {
+    convert(decode_wav(path)?, target_rate)
+}
+
+/// Load a WAV, MP3, FLAC, OGG, or MP4/MOV file as mono samples at
+/// `target_rate`, falling back to `ffmpeg` for other containers
+pub fn load_audio(path: &Path, target_rate: u32) -> crate::Result<(Vec<f32>, ConversionReport)> {
+    match decode_file(path) {
+        Ok(audio) => convert(audio, target_rate),
+        Err(e) => extract_with_ffmpeg(path, target_rate).map_err(|ffmpeg_err| {
+            crate::Error::Audio(format!("{} (ffmpeg fallback: {})", e, ffmpeg_err))
+        }),
+    }
+}
+
+/// Extract the audio track of any media file with `ffmpeg`
+fn extract_with_ffmpeg(
+    path: &Path,
+    target_rate: u32,
+) -> crate::Result<(Vec<f32>, ConversionReport)> {
+    let wav = tempfile::Builder::new().suffix(".wav").tempfile()?;
+    let output = std::process::Command::new("ffmpeg")
+        .args(["-nostdin", "-loglevel", "error", "-y", "-i"])
+        .arg(path)
+        .args([
+            "-vn",
+            "-ac",
+            "1",
+            "-ar",
+            &target_rate.to_string(),
+            "-f",
+            "wav",
+        ])
+        .arg(wav.path())
+        .output()
+        .map_err(|e| crate::Error::Audio(format!("cannot run ffmpeg: {}", e)))?;
+    if !output.status.success() {
+        return Err(crate::Error::Audio(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let (samples, mut report) = load_wav(wav.path(), target_rate)?;
+    report.source_format = format!("{} via ffmpeg", report.source_format);
+    Ok((samples, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_stereo_44k_to_mono_16k() {
+        // 0.5s of a 440 Hz tone, left channel only
+        let frames = 22050;
+        let mut samples = Vec::with_capacity(frames * 2);
+        for i in 0..frames {
+            let t = i as f32 / 44100.0;
+            samples.push((2.0 * std::f32::consts::PI * 440.0 * t).sin());
+            samples.push(0.0);
+        }
+        let audio = DecodedAudio {
+            samples,
+            sample_rate: 44100,
+            channels: 2,
+            bits_per_sample: 16,
+            float: false,
+            codec: None,
+        };
+
+        let (mono, report) = convert(audio, 16000).unwrap();
+        assert_eq!(mono.len(), 8000);
+        assert!(report.downmixed() && report.resampled());
+        assert_eq!(
+            report.to_string(),
+            "44100 Hz stereo 16-bit int (0.5s), mixed to mono, resampled 44100 → 16000 Hz"
+        );
+
+        // Mixed at half amplitude, and the tone survives resampling
+        let peak = mono[1000..7000].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.05, "peak {}", peak);
+
+        assert_eq!(mix_to_mono(&[0.2, 0.4], 1), vec![0.2, 0.4]);
+        assert_eq!(resample(&[0.1, 0.2], 16000, 16000).unwrap(), vec![0.1, 0.2]);
+    }
+
+    #[test]
+    fn test_save_wav_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("segment.wav");
+        save_wav(&path, &[0.0, 0.5, -0.5, 2.0], 16000).unwrap();
+
+        let decoded = decode_wav(&path).unwrap();
+        assert_eq!((decoded.sample_rate, decoded.channels), (16000, 1));
+        assert_eq!(decoded.samples.len(), 4);
+        assert!((decoded.samples[1] - 0.5).abs() < 1e-3);
+        // Out-of-range samples are clipped, not wrapped
+        assert!(decoded.samples[3] > 0.99);
+
+        // The compressed-format decoder reads the same samples
+        let via_symphonia = decode_compressed(&path).unwrap();
+        assert_eq!(via_symphonia.samples.len(), 4);
+        assert!((via_symphonia.samples[1] - decoded.samples[1]).abs() < 1e-3);
+        assert!(via_symphonia.codec.is_some());
+    }
+}
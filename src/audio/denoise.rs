@@ -0,0 +1,256 @@
+//! Noise Suppression
+//!
+//! Spectral-subtraction denoiser for steady background noise (fans, hum,
+//! air conditioning, keyboard rumble), enabled with `[audio].denoise`.
+//! Audio is processed in 50%-overlapping ~32 ms frames; the noise spectrum
+//! is tracked per frequency bin from the smoothed power of frames near the
+//! current estimate, drops at once when the signal gets quieter, and only
+//! creeps up through louder frames, so it follows changing noise without
+//! learning speech. Each bin is
+//! attenuated by how much of its power is noise, down to a floor that keeps
+//! the residual noise natural instead of "musical".
+//!
+//! Streaming output lags the input by [`Denoiser::latency_samples`] so
+//! chunks keep their length.
+
+use super::AudioChunk;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Target frame length; rounded up to a power of two
+const FRAME_MS: usize = 32;
+
+/// Noise estimate growth per frame while the signal is above it (~3 dB/s)
+const NOISE_RISE: f32 = 1.01;
+
+/// Power below this multiple of the noise estimate counts as noise
+const NOISE_GATE: f32 = 3.0;
+
+/// How fast the noise estimate follows noise-only power
+const NOISE_ADAPTATION: f32 = 0.05;
+
+/// Frames (~130 ms) averaged for the initial noise estimate, before speech
+/// usually starts
+const INITIAL_FRAMES: usize = 8;
+
+/// Power smoothing between frames (0 = none)
+const POWER_SMOOTHING: f32 = 0.7;
+
+/// Over-subtraction factor for the noise power
+const OVER_SUBTRACTION: f32 = 2.0;
+
+/// Lowest gain applied to a bin (-20 dB)
+const GAIN_FLOOR: f32 = 0.1;
+
+/// Streaming spectral-subtraction denoiser
+pub struct Denoiser {
+    sample_rate: u32,
+    hop: usize,
+    window: Vec<f32>,
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+    /// Second half of the previous analysis frame
+    previous: Vec<f32>,
+    /// Second half of the previous synthesis frame, to overlap-add
+    overlap: Vec<f32>,
+    pending: Vec<f32>,
+    output: VecDeque<f32>,
+    power: Vec<f32>,
+    noise: Vec<f32>,
+    frames: usize,
+    spectrum: Vec<Complex<f32>>,
+}
+
+impl Denoiser {
+    /// Create a denoiser for mono audio at `sample_rate`
+    pub fn new(sample_rate: u32) -> Self {
+        let frame = (sample_rate as usize * FRAME_MS / 1000)
+            .next_power_of_two()
+            .max(64);
+        let hop = frame / 2;
+        let mut planner = FftPlanner::new();
+
+        // Square-root periodic Hann: analysis and synthesis windows
+        // multiply to a Hann, which sums to one at 50% overlap
+        let window = (0..frame)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / frame as f32;
+                (0.5 - 0.5 * phase.cos()).sqrt()
+            })
+            .collect();
+
+        let mut denoiser = Self {
+            sample_rate,
+            hop,
+            window,
+            forward: planner.plan_fft_forward(frame),
+            inverse: planner.plan_fft_inverse(frame),
+            previous: Vec::new(),
+            overlap: Vec::new(),
+            pending: Vec::new(),
+            output: VecDeque::new(),
+            power: Vec::new(),
+            noise: Vec::new(),
+            frames: 0,
+            spectrum: vec![Complex::default(); frame],
+        };
+        denoiser.reset();
+        denoiser
+    }
+
+    /// Sample rate the denoiser was built for
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Delay between input and output samples
+    ///
+    /// A frame's first half is final once the next hop arrives, and up to
+    /// a hop of input may be waiting for a frame.
+    pub fn latency_samples(&self) -> usize {
+        3 * self.hop
+    }
+
+    /// Forget the noise estimate and buffered audio
+    pub fn reset(&mut self) {
+        let bins = self.hop + 1;
+        self.previous = vec![0.0; self.hop];
+        self.overlap = vec![0.0; self.hop];
+        self.pending.clear();
+        self.output = std::iter::repeat_n(0.0, 2 * self.hop).collect();
+        self.power = vec![0.0; bins];
+        self.noise = vec![0.0; bins];
+        self.frames = 0;
+    }
+
+    /// Denoise samples, returning as many as were passed in (delayed by
+    /// the latency)
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.pending.extend_from_slice(samples);
+        let blocks = self.pending.len() / self.hop;
+        for block in 0..blocks {
+            let start = block * self.hop;
+            let input: Vec<f32> = self.pending[start..start + self.hop].to_vec();
+            self.process_frame(&input);
+        }
+        self.pending.drain(..blocks * self.hop);
+
+        self.output.drain(..samples.len()).collect()
+    }
+
+    /// Denoise a chunk in place, rebuilding for a new sample rate
+    pub fn process_chunk(&mut self, chunk: &mut AudioChunk) {
+        if chunk.sample_rate != self.sample_rate {
+            *self = Self::new(chunk.sample_rate);
+        }
+        chunk.samples = self.process(&chunk.samples);
+    }
+
+    /// Run one hop of new input through the analysis/synthesis frame
+    fn process_frame(&mut self, input: &[f32]) {
+        let frame = self.window.len();
+        for (i, value) in self.previous.iter().chain(input).enumerate() {
+            self.spectrum[i] = Complex::new(value * self.window[i], 0.0);
+        }
+        self.previous.copy_from_slice(input);
+        self.forward.process(&mut self.spectrum);
+        self.frames += 1;
+
+        for bin in 0..=self.hop {
+            let power = self.spectrum[bin].norm_sqr();
+            let smoothed = if self.frames == 1 {
+                power
+            } else {
+                POWER_SMOOTHING * self.power[bin] + (1.0 - POWER_SMOOTHING) * power
+            };
+            self.power[bin] = smoothed;
+            let noise = &mut self.noise[bin];
+            if self.frames <= INITIAL_FRAMES {
+                *noise += (power - *noise) / self.frames as f32;
+            } else if smoothed < NOISE_GATE * *noise {
+                *noise += NOISE_ADAPTATION * (smoothed - *noise);
+            } else {
+                *noise *= NOISE_RISE;
+            }
+
+            let gain = if power > 0.0 {
+                (1.0 - OVER_SUBTRACTION * self.noise[bin] / power).max(GAIN_FLOOR)
+            } else {
+                GAIN_FLOOR
+            };
+            self.spectrum[bin] *= gain;
+            // Keep the spectrum conjugate-symmetric so the output is real
+            if bin > 0 && bin < self.hop {
+                self.spectrum[frame - bin] = self.spectrum[bin].conj();
+            }
+        }
+        self.inverse.process(&mut self.spectrum);
+
+        let scale = 1.0 / frame as f32;
+        for i in 0..self.hop {
+            let value = self.spectrum[i].re * scale * self.window[i];
+            self.output.push_back(self.overlap[i] + value);
+            self.overlap[i] = self.spectrum[i + self.hop].re * scale * self.window[i + self.hop];
+        }
+    }
+}
+
+/// Denoise a whole recording, with the latency removed
+pub fn denoise(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let mut denoiser = Denoiser::new(sample_rate);
+    let latency = denoiser.latency_samples();
+    let mut padded = samples.to_vec();
+    padded.resize(samples.len() + latency, 0.0);
+    let mut output = denoiser.process(&padded);
+    output.drain(..latency);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::selftest::dbfs;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_denoise_suppresses_steady_noise() {
+        let rate = 16000;
+        // Deterministic white noise from a linear congruential generator
+        let mut seed = 12345u32;
+        let noise: Vec<f32> = (0..rate * 3)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.02
+            })
+            .collect();
+        // A tone stands in for speech in the last second
+        let mut signal = noise.clone();
+        for (i, sample) in signal.iter_mut().enumerate().skip(rate as usize * 2) {
+            *sample += 0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / rate as f32).sin();
+        }
+
+        let output = denoise(&signal, rate);
+        assert_eq!(output.len(), signal.len());
+
+        // Noise-only second: at least 10 dB quieter once the estimate settles
+        let second = rate as usize;
+        let before = dbfs(rms(&signal[second..2 * second]));
+        let after = dbfs(rms(&output[second..2 * second]));
+        assert!(before - after > 10.0, "{} -> {}", before, after);
+
+        // The tone passes nearly untouched
+        let tone = rms(&signal[2 * second + 1000..]);
+        let kept = rms(&output[2 * second + 1000..]);
+        assert!((dbfs(tone) - dbfs(kept)).abs() < 1.5);
+
+        // Streaming keeps chunk lengths
+        let mut denoiser = Denoiser::new(rate);
+        assert_eq!(denoiser.process(&signal[..100]).len(), 100);
+        assert_eq!(denoiser.process(&signal[100..1700]).len(), 1600);
+    }
+}
@@ -1,11 +1,44 @@
 //! Audio Device Enumeration
 //!
-//! Handles listing and selecting audio input devices.
+//! Handles listing and selecting audio input devices, and picking the
+//! device a running capture should be on as devices come and go: the
+//! configured device while it is plugged in, otherwise the system default
+//! (which `"default"` follows as it changes).
+//!
+//! System audio (`[audio].source = "system"`) is captured from a loopback
+//! source instead: on Windows any output device, through WASAPI loopback;
+//! elsewhere an input device that carries what is played, recognized by
+//! name (macOS BlackHole or aggregate devices, ALSA loopback and monitor
+//! devices). On Linux with PipeWire the default output's monitor is
+//! recorded when no such device exists (see [`super::capture`]).
 
+#[cfg(feature = "audio-capture")]
+use super::capture::CaptureSource;
+#[cfg(feature = "audio-capture")]
 use cpal::traits::{DeviceTrait, HostTrait};
+#[cfg(feature = "audio-capture")]
 use cpal::{Device, Host, SupportedStreamConfig};
 use std::fmt;
 
+/// Name fragments of input devices that carry system audio
+const LOOPBACK_NAMES: &[&str] = &[
+    "monitor",
+    "loopback",
+    "blackhole",
+    "soundflower",
+    "aggregate",
+    "stereo mix",
+    "what u hear",
+];
+
+/// Whether an input device named `name` looks like a loopback source
+pub fn is_loopback_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_NAMES
+        .iter()
+        .any(|fragment| name.contains(fragment))
+}
+
 /// Audio device information
 #[derive(Debug, Clone)]
 pub struct AudioDeviceInfo {
@@ -15,6 +48,50 @@ pub struct AudioDeviceInfo {
     pub channels: u16,
 }
 
+/// The capture moved to another input device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceChange {
+    /// Device capture was on (`None` if none was available)
+    pub from: Option<String>,
+    /// Device capturing now (`None` while no input device is available)
+    pub to: Option<String>,
+}
+
+impl fmt::Display for DeviceChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => write!(f, "Switched input from {} to {}", from, to),
+            (Some(from), None) => write!(f, "Lost input {}; no input device available", from),
+            (None, Some(to)) => write!(f, "Capturing from {}", to),
+            (None, None) => write!(f, "No input device available"),
+        }
+    }
+}
+
+/// Check that `name` is "default" or the name of an input device present
+pub fn check_input_device(name: &str) -> crate::Result<()> {
+    if name == "default" {
+        return Ok(());
+    }
+    let devices = AudioDeviceManager::new().list_input_devices()?;
+    if devices.iter().any(|device| device.name == name) {
+        return Ok(());
+    }
+    Err(crate::Error::Audio(format!(
+        "No input device named '{}' (see: onevox devices list)",
+        name
+    )))
+}
+
+/// Device a capture configured with `preferred` should use, given the
+/// input devices present and the system default
+pub fn wanted_device(preferred: &str, present: &[String], default: Option<&str>) -> Option<String> {
+    if preferred != "default" && present.iter().any(|name| name == preferred) {
+        return Some(preferred.to_string());
+    }
+    default.map(str::to_string)
+}
+
 impl fmt::Display for AudioDeviceInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -29,10 +106,12 @@ impl fmt::Display for AudioDeviceInfo {
 }
 
 /// Audio device manager
+#[cfg(feature = "audio-capture")]
 pub struct AudioDeviceManager {
     host: Host,
 }
 
+#[cfg(feature = "audio-capture")]
 impl AudioDeviceManager {
     /// Create a new device manager
     pub fn new() -> Self {
@@ -78,6 +157,103 @@ impl AudioDeviceManager {
         Ok(devices)
     }
 
+    /// Names of the input devices present, and of the default one
+    pub fn input_device_names(&self) -> crate::Result<(Vec<String>, Option<String>)> {
+        let names = self
+            .host
+            .input_devices()
+            .map_err(|e| crate::Error::Audio(format!("Failed to enumerate devices: {}", e)))?
+            .filter_map(|device| device.name().ok())
+            .collect();
+        let default = self
+            .host
+            .default_input_device()
+            .and_then(|device| device.name().ok());
+        Ok((names, default))
+    }
+
+    /// Names of the loopback sources present, and of the one to use by
+    /// default (the default output on Windows, else the first found)
+    pub fn loopback_device_names(&self) -> crate::Result<(Vec<String>, Option<String>)> {
+        #[cfg(target_os = "windows")]
+        {
+            let names = self
+                .host
+                .output_devices()
+                .map_err(|e| crate::Error::Audio(format!("Failed to enumerate devices: {}", e)))?
+                .filter_map(|device| device.name().ok())
+                .collect();
+            let default = self
+                .host
+                .default_output_device()
+                .and_then(|device| device.name().ok());
+            Ok((names, default))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let (names, _) = self.input_device_names()?;
+            let names: Vec<String> = names
+                .into_iter()
+                .filter(|name| is_loopback_name(name))
+                .collect();
+            let default = names.first().cloned();
+            Ok((names, default))
+        }
+    }
+
+    /// Loopback source named `name`, if it is present
+    pub fn find_loopback_device(&self, name: &str) -> crate::Result<Option<Device>> {
+        #[cfg(target_os = "windows")]
+        {
+            Ok(self
+                .host
+                .output_devices()
+                .map_err(|e| crate::Error::Audio(format!("Failed to enumerate devices: {}", e)))?
+                .find(|device| device.name().is_ok_and(|device_name| device_name == name)))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            self.find_input_device(name)
+        }
+    }
+
+    /// The loopback source named `preferred`, or the default one
+    pub fn loopback_device(&self, preferred: &str) -> crate::Result<Device> {
+        let (present, default) = self.loopback_device_names()?;
+        wanted_device(preferred, &present, default.as_deref())
+            .map(|name| self.find_loopback_device(&name))
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| {
+                crate::Error::Audio(
+                    "No system audio source found. Add a loopback device (e.g. BlackHole on macOS, \
+                     snd-aloop on Linux) or, on Linux, install PipeWire's pw-record"
+                        .to_string(),
+                )
+            })
+    }
+
+    /// Names of the devices present for `source`, and of its default
+    pub fn device_names(
+        &self,
+        source: CaptureSource,
+    ) -> crate::Result<(Vec<String>, Option<String>)> {
+        match source {
+            CaptureSource::Microphone => self.input_device_names(),
+            CaptureSource::System => self.loopback_device_names(),
+        }
+    }
+
+    /// Device for `source` named `name`, if it is present
+    pub fn find_device(&self, source: CaptureSource, name: &str) -> crate::Result<Option<Device>> {
+        match source {
+            CaptureSource::Microphone => self.find_input_device(name),
+            CaptureSource::System => self.find_loopback_device(name),
+        }
+    }
+
     /// Get the default input device
     pub fn default_input_device(&self) -> crate::Result<Device> {
         self.host
@@ -85,19 +261,20 @@ impl AudioDeviceManager {
             .ok_or_else(|| crate::Error::Audio("No default input device found".to_string()))
     }
 
-    /// Get device by name, with fallback to default
-    pub fn get_device_by_name(&self, name: &str) -> crate::Result<Device> {
-        // Try to find the exact device
-        for device in self
+    /// Input device named `name`, if it is present
+    pub fn find_input_device(&self, name: &str) -> crate::Result<Option<Device>> {
+        Ok(self
             .host
             .input_devices()
             .map_err(|e| crate::Error::Audio(format!("Failed to enumerate devices: {}", e)))?
-        {
-            if let Ok(device_name) = device.name()
-                && device_name == name
-            {
-                return Ok(device);
-            }
+            .find(|device| device.name().is_ok_and(|device_name| device_name == name)))
+    }
+
+    /// Get device by name, with fallback to default
+    pub fn get_device_by_name(&self, name: &str) -> crate::Result<Device> {
+        // Try to find the exact device
+        if let Some(device) = self.find_input_device(name)? {
+            return Ok(device);
         }
 
         // Device not found - try default as fallback
@@ -108,10 +285,11 @@ impl AudioDeviceManager {
         self.default_input_device()
     }
 
-    /// Get device config
+    /// Get device config (an output device's, for WASAPI loopback)
     pub fn get_device_config(&self, device: &Device) -> crate::Result<SupportedStreamConfig> {
         device
             .default_input_config()
+            .or_else(|e| device.default_output_config().map_err(|_| e))
             .map_err(|e| crate::Error::Audio(format!("Failed to get device config: {}", e)))
     }
 }
@@ -121,3 +299,66 @@ impl Default for AudioDeviceManager {
         Self::new()
     }
 }
+
+// Stub implementation when feature is disabled
+#[cfg(not(feature = "audio-capture"))]
+pub struct AudioDeviceManager;
+
+#[cfg(not(feature = "audio-capture"))]
+impl AudioDeviceManager {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn list_input_devices(&self) -> crate::Result<Vec<AudioDeviceInfo>> {
+        Err(crate::Error::Audio(
+            "audio-capture feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn loopback_device_names(&self) -> crate::Result<(Vec<String>, Option<String>)> {
+        Err(crate::Error::Audio(
+            "audio-capture feature not enabled".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wanted_device_falls_back_to_default() {
+        let present = vec!["Built-in Microphone".to_string(), "USB Mic".to_string()];
+        let default = Some("Built-in Microphone");
+
+        assert_eq!(
+            wanted_device("USB Mic", &present, default).as_deref(),
+            Some("USB Mic")
+        );
+        // Unplugged: the default until it comes back
+        assert_eq!(
+            wanted_device("USB Mic", &present[..1], default).as_deref(),
+            Some("Built-in Microphone")
+        );
+        // "default" follows the system default
+        assert_eq!(
+            wanted_device("default", &present, Some("USB Mic")).as_deref(),
+            Some("USB Mic")
+        );
+        assert_eq!(wanted_device("USB Mic", &[], None), None);
+
+        assert!(is_loopback_name("Monitor of Built-in Audio Analog Stereo"));
+        assert!(is_loopback_name("BlackHole 2ch"));
+        assert!(!is_loopback_name("USB Mic"));
+
+        let change = DeviceChange {
+            from: Some("USB Mic".to_string()),
+            to: Some("Built-in Microphone".to_string()),
+        };
+        assert_eq!(
+            change.to_string(),
+            "Switched input from USB Mic to Built-in Microphone"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Sound Feedback
+//!
+//! Short cues on the default output device as dictation starts and stops,
+//! as each segment is transcribed or typed, and when something fails
+//! (`[ui.sound_feedback]`). Each cue is one of:
+//!
+//! - "tone": a built-in beep, pitched per cue
+//! - "system": the platform's own sound for it (macOS alert sounds, the
+//!   freedesktop sound theme, Windows speech sounds)
+//! - a path to a WAV, MP3, FLAC, or OGG file
+//! - "none"
+//!
+//! Cues play in the background and never hold up dictation.
+
+use super::tones::{self, Tone};
+use crate::config::SoundFeedbackConfig;
+use crate::ipc::protocol::Event;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Moment a cue marks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    Start,
+    Stop,
+    Transcribed,
+    Injected,
+    Error,
+}
+
+impl Cue {
+    /// Cue for a daemon event, if any
+    pub fn for_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::DictationStarted => Some(Cue::Start),
+            Event::DictationStopped => Some(Cue::Stop),
+            Event::TranscriptionComplete { .. } => Some(Cue::Transcribed),
+            Event::InjectionComplete { .. } => Some(Cue::Injected),
+            Event::TranscriptionFailed { .. }
+            | Event::InjectionFailed { .. }
+            | Event::Error { .. } => Some(Cue::Error),
+            _ => None,
+        }
+    }
+
+    /// Built-in tone: rising for start, falling for stop, short ticks for
+    /// segments, a low buzz for errors
+    fn tone(self, volume: f32) -> Tone {
+        let (frequency_hz, duration_ms) = match self {
+            Cue::Start => (880.0, 80),
+            Cue::Stop => (660.0, 80),
+            Cue::Transcribed => (1320.0, 40),
+            Cue::Injected => (990.0, 40),
+            Cue::Error => (220.0, 250),
+        };
+        Tone {
+            frequency_hz,
+            duration_ms,
+            volume,
+        }
+    }
+
+    /// The platform's sound for this cue
+    fn system_sound(self) -> PathBuf {
+        #[cfg(target_os = "macos")]
+        let path = format!(
+            "/System/Library/Sounds/{}.aiff",
+            match self {
+                Cue::Start => "Tink",
+                Cue::Stop => "Pop",
+                Cue::Transcribed => "Morse",
+                Cue::Injected => "Purr",
+                Cue::Error => "Basso",
+            }
+        );
+        #[cfg(windows)]
+        let path = format!(
+            r"C:\Windows\Media\{}.wav",
+            match self {
+                Cue::Start => "Speech On",
+                Cue::Stop => "Speech Off",
+                Cue::Transcribed => "Speech Sleep",
+                Cue::Injected => "Speech Disambiguation",
+                Cue::Error => "Speech Misrecognition",
+            }
+        );
+        #[cfg(not(any(target_os = "macos", windows)))]
+        let path = format!(
+            "/usr/share/sounds/freedesktop/stereo/{}.oga",
+            match self {
+                Cue::Start => "message-new-instant",
+                Cue::Stop => "complete",
+                Cue::Transcribed => "message",
+                Cue::Injected => "bell",
+                Cue::Error => "dialog-error",
+            }
+        );
+        PathBuf::from(path)
+    }
+}
+
+/// What a cue plays
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sound {
+    None,
+    Tone(Tone),
+    File(PathBuf),
+}
+
+impl Sound {
+    /// Parse a cue setting ("tone", "system", "none", or a file path)
+    pub fn parse(value: &str, cue: Cue, volume: f32) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "none" | "off" => Sound::None,
+            "tone" => Sound::Tone(cue.tone(volume)),
+            "system" => Sound::File(cue.system_sound()),
+            _ => Sound::File(expand_home(value.trim())),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Plays the configured cue for daemon events
+#[derive(Debug, Clone)]
+pub struct SoundFeedback {
+    volume: f32,
+    start: Sound,
+    stop: Sound,
+    transcribed: Sound,
+    injected: Sound,
+    error: Sound,
+}
+
+impl SoundFeedback {
+    pub fn new(config: &SoundFeedbackConfig) -> Self {
+        let volume = config.volume.clamp(0.0, 1.0);
+        let sound = |value: &str, cue: Cue| Sound::parse(value, cue, volume);
+        Self {
+            volume,
+            start: sound(&config.start, Cue::Start),
+            stop: sound(&config.stop, Cue::Stop),
+            transcribed: sound(&config.transcribed, Cue::Transcribed),
+            injected: sound(&config.injected, Cue::Injected),
+            error: sound(&config.error, Cue::Error),
+        }
+    }
+
+    /// Sound configured for a cue
+    pub fn sound(&self, cue: Cue) -> &Sound {
+        match cue {
+            Cue::Start => &self.start,
+            Cue::Stop => &self.stop,
+            Cue::Transcribed => &self.transcribed,
+            Cue::Injected => &self.injected,
+            Cue::Error => &self.error,
+        }
+    }
+
+    /// Play the cue for `event`, if it has one (never blocks)
+    pub fn handle(&self, event: &Event) {
+        if let Some(cue) = Cue::for_event(event) {
+            self.play(cue);
+        }
+    }
+
+    /// Play a cue in the background
+    pub fn play(&self, cue: Cue) {
+        let sound = self.sound(cue).clone();
+        let volume = self.volume;
+        if sound == Sound::None {
+            return;
+        }
+        std::thread::spawn(move || {
+            let result = match &sound {
+                Sound::None => Ok(()),
+                Sound::Tone(tone) => tones::play_blocking(*tone),
+                Sound::File(path) => play_file(path, volume),
+            };
+            if let Err(e) = result {
+                warn!("Failed to play {:?} cue: {}", cue, e);
+            }
+        });
+    }
+}
+
+/// Play an audio file at `volume` and wait for it to finish
+fn play_file(path: &Path, volume: f32) -> crate::Result<()> {
+    // Core Audio plays the AIFF system sounds, which aren't decoded here
+    #[cfg(target_os = "macos")]
+    if path.extension().is_some_and(|ext| ext == "aiff") {
+        let status = std::process::Command::new("afplay")
+            .arg("-v")
+            .arg(volume.to_string())
+            .arg(path)
+            .status()?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(crate::Error::Audio(format!("afplay failed ({})", status)))
+        };
+    }
+
+    let audio = super::convert::decode_file(path)?;
+    let sample_rate = audio.sample_rate;
+    let (mut samples, _) = super::convert::convert(audio, sample_rate)?;
+    for sample in &mut samples {
+        *sample *= volume;
+    }
+    tones::play_samples_blocking(&samples, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cues_for_events() {
+        let config = SoundFeedbackConfig {
+            enabled: true,
+            volume: 0.5,
+            start: "tone".to_string(),
+            stop: "none".to_string(),
+            transcribed: "~/sounds/tick.wav".to_string(),
+            injected: "System".to_string(),
+            error: "tone".to_string(),
+        };
+        let feedback = SoundFeedback::new(&config);
+
+        let cue = Cue::for_event(&Event::DictationStarted).unwrap();
+        assert_eq!(cue, Cue::Start);
+        assert!(matches!(feedback.sound(cue), Sound::Tone(tone) if tone.volume == 0.5));
+        assert_eq!(feedback.sound(Cue::Stop), &Sound::None);
+        match feedback.sound(Cue::Transcribed) {
+            Sound::File(path) => assert!(path.ends_with("sounds/tick.wav")),
+            other => panic!("expected a file, got {:?}", other),
+        }
+        assert_eq!(
+            feedback.sound(Cue::Injected),
+            &Sound::File(Cue::Injected.system_sound())
+        );
+
+        let failed = Event::InjectionFailed {
+            message: "no focus".to_string(),
+        };
+        assert_eq!(Cue::for_event(&failed), Some(Cue::Error));
+        assert_eq!(Cue::for_event(&Event::SpeechStarted), None);
+    }
+}
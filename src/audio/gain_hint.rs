@@ -0,0 +1,122 @@
+//! Gain Hints
+//!
+//! After each dictation the recorded audio is checked for clipping and very
+//! low speech level. When either shows up, the user gets one actionable hint
+//! ("mic too hot, reduce gain" / "move closer to the mic") as a desktop
+//! notification and in the history entry. Each hint is repeated at most once
+//! per `audio.gain_hint_interval_mins` so a noisy setup doesn't nag.
+
+use super::selftest::{self, LevelReport, MAX_CLIPPED_RATIO, MIN_SPEECH_DBFS};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Recordings shorter than this are too short to judge
+const MIN_DURATION_SECS: f32 = 0.5;
+
+/// Peak below this means no signal at all (muted mic), not a quiet speaker
+const MIN_PEAK: f32 = 0.001;
+
+/// Problem with the input level of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GainIssue {
+    /// Samples clipped: input gain too high
+    TooHot,
+    /// Speech very quiet: speaker too far or gain too low
+    TooQuiet,
+}
+
+impl GainIssue {
+    /// Classify a level report (`None` when levels look fine)
+    pub fn from_report(report: &LevelReport) -> Option<Self> {
+        if report.duration_secs < MIN_DURATION_SECS || report.peak < MIN_PEAK {
+            None
+        } else if report.clipped_ratio > MAX_CLIPPED_RATIO {
+            Some(GainIssue::TooHot)
+        } else if report.speech_dbfs < MIN_SPEECH_DBFS {
+            Some(GainIssue::TooQuiet)
+        } else {
+            None
+        }
+    }
+
+    /// Actionable hint shown to the user
+    pub fn hint(&self) -> &'static str {
+        match self {
+            GainIssue::TooHot => "Mic too hot: reduce the input gain",
+            GainIssue::TooQuiet => {
+                "Speech is quiet: move closer to the mic or raise the input gain"
+            }
+        }
+    }
+}
+
+/// Rate-limited gain checks across dictations
+pub struct GainHints {
+    /// Minimum time between repeats of the same hint (`None` = disabled)
+    interval: Option<Duration>,
+    /// When each hint was last shown
+    last_shown: Mutex<HashMap<GainIssue, Instant>>,
+}
+
+impl GainHints {
+    /// Create from `audio.gain_hint_interval_mins` (0 disables hints)
+    pub fn new(interval_mins: u64) -> Self {
+        Self {
+            interval: (interval_mins > 0).then(|| Duration::from_secs(interval_mins * 60)),
+            last_shown: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Analyze a recording, returning an issue that is due to be shown
+    pub fn check(&self, samples: &[f32], sample_rate: u32) -> Option<GainIssue> {
+        self.interval?;
+        let issue = GainIssue::from_report(&selftest::analyze(samples, sample_rate))?;
+        self.take(issue, Instant::now()).then_some(issue)
+    }
+
+    /// Mark `issue` as shown at `now` unless it was shown within the interval
+    fn take(&self, issue: GainIssue, now: Instant) -> bool {
+        let Some(interval) = self.interval else {
+            return false;
+        };
+        let mut last_shown = self.last_shown.lock().unwrap_or_else(|e| e.into_inner());
+        match last_shown.get(&issue) {
+            Some(&shown) if now.duration_since(shown) < interval => false,
+            _ => {
+                last_shown.insert(issue, now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..16000)
+            .map(|i| amplitude * (i as f32 * 0.05).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_hints_are_classified_and_rate_limited() {
+        let hints = GainHints::new(60);
+        assert_eq!(hints.check(&tone(0.3), 16000), None);
+        assert_eq!(hints.check(&tone(0.005), 16000), Some(GainIssue::TooQuiet));
+        assert_eq!(hints.check(&tone(0.005), 16000), None);
+        let clipped: Vec<f32> = tone(1.5).iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+        assert_eq!(hints.check(&clipped, 16000), Some(GainIssue::TooHot));
+
+        // Muted input is not a placement problem
+        assert_eq!(hints.check(&vec![0.0; 16000], 16000), None);
+
+        // Repeats are allowed once the interval has passed
+        let later = Instant::now() + Duration::from_secs(3601);
+        assert!(hints.take(GainIssue::TooQuiet, later));
+
+        assert_eq!(GainHints::new(0).check(&tone(0.005), 16000), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Pre-Buffer
+//!
+//! With `[audio] pre_buffer_ms` set, the microphone stays open between
+//! sessions and the last few hundred milliseconds of audio are held in
+//! memory. A session that starts takes that audio ahead of the live chunks,
+//! so a first syllable spoken while the stream would still be starting up
+//! isn't lost. Held audio older than the window is dropped and never leaves
+//! the buffer unless a session starts.
+
+use super::AudioChunk;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::debug;
+
+/// Recent audio and, during a session, where live audio goes
+#[derive(Default)]
+struct Tap {
+    recent: VecDeque<AudioChunk>,
+    recent_ms: u64,
+    session: Option<mpsc::Sender<AudioChunk>>,
+    /// Chunks the session fell too far behind to take
+    dropped: u64,
+}
+
+impl Tap {
+    /// Pass a chunk to the session, or hold it with the last `window_ms`
+    fn push(&mut self, chunk: AudioChunk, window_ms: u64) {
+        let chunk = match &self.session {
+            Some(session) => match session.try_send(chunk) {
+                Ok(()) => return,
+                Err(TrySendError::Full(_)) => {
+                    self.dropped += 1;
+                    debug!("Session fell behind; dropping an audio chunk");
+                    return;
+                }
+                // The session ended without detaching
+                Err(TrySendError::Closed(chunk)) => {
+                    self.session = None;
+                    chunk
+                }
+            },
+            None => chunk,
+        };
+
+        self.recent_ms += chunk.duration_ms();
+        self.recent.push_back(chunk);
+        while let Some(oldest) = self.recent.front() {
+            let oldest_ms = oldest.duration_ms();
+            if self.recent_ms - oldest_ms < window_ms {
+                break;
+            }
+            self.recent_ms -= oldest_ms;
+            self.recent.pop_front();
+        }
+    }
+
+    /// Start a session: the held audio, then live chunks
+    fn attach(&mut self, live_capacity: usize) -> mpsc::Receiver<AudioChunk> {
+        let (tx, rx) = mpsc::channel(self.recent.len() + live_capacity);
+        for chunk in self.recent.drain(..) {
+            // The channel has room for everything held
+            let _ = tx.try_send(chunk);
+        }
+        self.recent_ms = 0;
+        self.dropped = 0;
+        self.session = Some(tx);
+        rx
+    }
+}
+
+/// Holds the last `window_ms` of a running capture between sessions
+pub struct PreBuffer {
+    tap: Arc<Mutex<Tap>>,
+    live_capacity: usize,
+}
+
+impl PreBuffer {
+    /// Take over a capture's chunks, holding the last `window_ms` of them;
+    /// sessions get a channel of `live_capacity` chunks beyond that
+    pub fn spawn(
+        mut chunks: mpsc::Receiver<AudioChunk>,
+        window_ms: u64,
+        live_capacity: usize,
+    ) -> crate::Result<Self> {
+        let tap = Arc::new(Mutex::new(Tap::default()));
+        let shared = Arc::clone(&tap);
+        // Ends when the capture stops and closes the channel
+        std::thread::Builder::new()
+            .name("onevox-pre-buffer".to_string())
+            .spawn(move || {
+                while let Some(chunk) = chunks.blocking_recv() {
+                    shared.lock().push(chunk, window_ms);
+                }
+            })
+            .map_err(|e| crate::Error::Audio(format!("Failed to start pre-buffer: {}", e)))?;
+        Ok(Self { tap, live_capacity })
+    }
+
+    /// Start a session with the held audio followed by live audio
+    pub fn attach(&self) -> mpsc::Receiver<AudioChunk> {
+        self.tap.lock().attach(self.live_capacity)
+    }
+
+    /// End the session (its channel closes) and go back to holding audio
+    pub fn detach(&self) {
+        self.tap.lock().session = None;
+    }
+
+    /// Chunks dropped since the session started
+    pub fn dropped_chunks(&self) -> u64 {
+        self.tap.lock().dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_starts_with_held_audio() {
+        let chunk = |value: f32| AudioChunk::new(vec![value; 1600], 16000);
+        let mut tap = Tap::default();
+        for i in 0..10 {
+            tap.push(chunk(i as f32), 300);
+        }
+        // 100ms chunks: the last 300ms are held
+        assert_eq!(tap.recent.len(), 3);
+        assert_eq!(tap.recent_ms, 300);
+
+        let mut rx = tap.attach(1);
+        tap.push(chunk(10.0), 300);
+        tap.push(chunk(11.0), 300);
+        assert_eq!(tap.dropped, 1);
+        let received: Vec<f32> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|chunk| chunk.samples[0])
+            .collect();
+        assert_eq!(received, vec![7.0, 8.0, 9.0, 10.0]);
+
+        // Once the session is gone, audio is held again
+        drop(rx);
+        tap.push(chunk(12.0), 300);
+        assert!(tap.session.is_none());
+        assert_eq!(tap.recent.len(), 1);
+    }
+}
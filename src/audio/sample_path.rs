@@ -0,0 +1,254 @@
+//! Sample Rate Path Audit
+//!
+//! Traces the rate audio has at each step from the microphone to the model
+//! (device rate → resampler → chunk rate → backend rate) for
+//! `onevox doctor audio`. Combinations that would hand a model audio at the
+//! wrong rate are reported together with the config change that fixes
+//! them, when there is one.
+
+use super::capture::{AudioResampler, VALID_SAMPLE_RATES};
+use super::devices::AudioDeviceManager;
+use crate::config::Config;
+use std::fmt;
+
+/// Rates the Silero VAD model accepts
+const SILERO_RATES: &[u32] = &[8000, 16000];
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Works, but not as configured
+    Warning,
+    /// Transcription or VAD will fail or produce garbage
+    Error,
+}
+
+/// A problem on the sample path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+    /// Config key and value that fix it
+    pub fix: Option<(&'static str, String)>,
+}
+
+/// Sample rates along the capture → transcription path
+#[derive(Debug, Clone)]
+pub struct SamplePath {
+    /// Input device name
+    pub device: String,
+    /// Rate the device captures at
+    pub device_rate: u32,
+    /// `audio.sample_rate`, the rate chunks are resampled to
+    pub capture_rate: u32,
+    /// `audio.chunk_duration_ms`
+    pub chunk_duration_ms: u32,
+    /// Resampler setup error (`None` when it works or isn't needed)
+    pub resampler_error: Option<String>,
+    /// Model backend name
+    pub backend: String,
+    /// Rate the backend expects
+    pub backend_rate: u32,
+    /// `vad.detector`
+    pub vad_detector: String,
+}
+
+impl SamplePath {
+    /// Trace the path for `config` on this machine's input devices
+    pub fn trace(config: &Config) -> crate::Result<Self> {
+        let devices = AudioDeviceManager::new().list_input_devices()?;
+        let device = devices
+            .iter()
+            .find(|d| config.audio.device != "default" && d.name == config.audio.device)
+            .or_else(|| devices.iter().find(|d| d.is_default))
+            .or(devices.first())
+            .ok_or_else(|| crate::Error::Audio("No audio input devices found".to_string()))?;
+
+        let backend_rate = crate::models::backend::create_runtime(&config.model)
+            .map(|runtime| runtime.sample_rate())
+            .unwrap_or(16000);
+
+        let mut path = Self {
+            device: device.name.clone(),
+            device_rate: device.sample_rate,
+            capture_rate: config.audio.sample_rate,
+            chunk_duration_ms: config.audio.chunk_duration_ms,
+            resampler_error: None,
+            backend: crate::models::backend::resolve_backend(&config.model).to_string(),
+            backend_rate,
+            vad_detector: config.vad.detector.clone(),
+        };
+        if path.needs_resampling() {
+            path.resampler_error =
+                AudioResampler::new(path.device_rate, path.capture_rate, path.chunk_size())
+                    .err()
+                    .map(|e| e.to_string());
+        }
+        Ok(path)
+    }
+
+    /// Whether the device rate differs from the capture rate
+    pub fn needs_resampling(&self) -> bool {
+        self.device_rate != self.capture_rate
+    }
+
+    /// Samples collected per chunk (counted at the device rate)
+    fn chunk_size(&self) -> usize {
+        (self.capture_rate * self.chunk_duration_ms / 1000) as usize
+    }
+
+    /// Rate chunks actually leave the capture stage at
+    pub fn chunk_rate(&self) -> u32 {
+        if self.needs_resampling() && self.resampler_error.is_some() {
+            self.device_rate
+        } else {
+            self.capture_rate
+        }
+    }
+
+    /// Audio duration of one chunk in milliseconds
+    pub fn chunk_ms(&self) -> u32 {
+        (self.chunk_size() as u64 * 1000 / self.device_rate.max(1) as u64) as u32
+    }
+
+    /// Problems on the path, most serious first
+    pub fn findings(&self) -> Vec<Finding> {
+        let fix_rate = Some(("audio.sample_rate", self.backend_rate.to_string()));
+        let mut findings = Vec::new();
+
+        if !VALID_SAMPLE_RATES.contains(&self.capture_rate) {
+            findings.push(Finding {
+                severity: Severity::Error,
+                message: format!(
+                    "audio.sample_rate {} Hz is not supported (expected one of {:?}); capture won't start",
+                    self.capture_rate, VALID_SAMPLE_RATES
+                ),
+                fix: fix_rate.clone(),
+            });
+        }
+
+        if let Some(error) = &self.resampler_error {
+            findings.push(Finding {
+                severity: Severity::Error,
+                message: format!(
+                    "Resampler {} → {} Hz unavailable ({}); chunks stay at {} Hz",
+                    self.device_rate, self.capture_rate, error, self.device_rate
+                ),
+                fix: None,
+            });
+        }
+
+        if self.chunk_rate() != self.backend_rate {
+            findings.push(Finding {
+                severity: Severity::Error,
+                message: format!(
+                    "Chunks arrive at {} Hz but the {} backend expects {} Hz",
+                    self.chunk_rate(),
+                    self.backend,
+                    self.backend_rate
+                ),
+                fix: (self.capture_rate != self.backend_rate)
+                    .then(|| fix_rate.clone())
+                    .flatten(),
+            });
+        }
+
+        if self.vad_detector == "silero" && !SILERO_RATES.contains(&self.chunk_rate()) {
+            findings.push(Finding {
+                severity: Severity::Error,
+                message: format!(
+                    "Silero VAD needs 8000 or 16000 Hz audio, chunks are {} Hz",
+                    self.chunk_rate()
+                ),
+                fix: (self.capture_rate != 16000)
+                    .then(|| ("audio.sample_rate", "16000".to_string())),
+            });
+        }
+
+        if self.chunk_ms() != self.chunk_duration_ms {
+            findings.push(Finding {
+                severity: Severity::Warning,
+                message: format!(
+                    "Chunks hold {} ms of audio, not the configured {} ms (the device runs at {} Hz); VAD chunk counts cover less time",
+                    self.chunk_ms(),
+                    self.chunk_duration_ms,
+                    self.device_rate
+                ),
+                fix: None,
+            });
+        }
+
+        findings.sort_by_key(|finding| finding.severity != Severity::Error);
+        findings
+    }
+}
+
+impl fmt::Display for SamplePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🎤 Device:    {} @ {} Hz", self.device, self.device_rate)?;
+        let resampler = match (&self.resampler_error, self.needs_resampling()) {
+            (Some(_), _) => "failed, passing audio through".to_string(),
+            (None, true) => format!("{} → {} Hz", self.device_rate, self.capture_rate),
+            (None, false) => "not needed".to_string(),
+        };
+        writeln!(f, "🔁 Resampler: {}", resampler)?;
+        writeln!(
+            f,
+            "📦 Chunks:    {} Hz, {} ms",
+            self.chunk_rate(),
+            self.chunk_ms()
+        )?;
+        write!(
+            f,
+            "🧠 Backend:   {} @ {} Hz",
+            self.backend, self.backend_rate
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(device_rate: u32, capture_rate: u32) -> SamplePath {
+        SamplePath {
+            device: "Mic".to_string(),
+            device_rate,
+            capture_rate,
+            chunk_duration_ms: 200,
+            resampler_error: None,
+            backend: "whisper-cpp".to_string(),
+            backend_rate: 16000,
+            vad_detector: "energy".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_findings() {
+        assert!(path(16000, 16000).findings().is_empty());
+
+        // 48 kHz device resampled to 16 kHz: chunks hold a third of the audio
+        let resampled = path(48000, 16000).findings();
+        assert_eq!(resampled.len(), 1);
+        assert_eq!(resampled[0].severity, Severity::Warning);
+
+        // Resampler fallback sends 48 kHz chunks to a 16 kHz backend
+        let mut fallback = path(48000, 16000);
+        fallback.resampler_error = Some("bad ratio".to_string());
+        assert_eq!(fallback.chunk_rate(), 48000);
+        let findings = fallback.findings();
+        assert!(findings[..2].iter().all(|f| f.severity == Severity::Error));
+        assert!(findings.iter().all(|f| f.fix.is_none()));
+
+        // Capturing at 44.1 kHz is fixable in config
+        let mut silero = path(44100, 44100);
+        silero.vad_detector = "silero".to_string();
+        let findings = silero.findings();
+        assert_eq!(findings.len(), 2);
+        assert!(
+            findings
+                .iter()
+                .all(|f| f.fix == Some(("audio.sample_rate", "16000".to_string())))
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Microphone Self-Test
+//!
+//! Guided check used by `onevox devices test` and `onevox setup`: record a
+//! short sample, play it back, measure speech level, noise floor, SNR and
+//! clipping, confirm the selected device against the OS default, and check
+//! whether speaker output leaks back into the microphone (echo).
+
+use super::{AudioDeviceInfo, AudioEngine, CaptureConfig};
+use std::time::{Duration, Instant};
+
+/// Samples at or above this magnitude count as clipped
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Analysis frame length in milliseconds
+const FRAME_MS: usize = 20;
+
+/// Clipped fraction above which gain should be lowered
+pub(super) const MAX_CLIPPED_RATIO: f32 = 0.001;
+
+/// Speech quieter than this (dBFS) is too quiet for reliable recognition
+pub(super) const MIN_SPEECH_DBFS: f32 = -35.0;
+
+/// Speech louder than this (dBFS) leaves too little headroom
+const MAX_SPEECH_DBFS: f32 = -6.0;
+
+/// SNR below this (dB) hurts accuracy
+const MIN_SNR_DB: f32 = 15.0;
+
+/// Echo-test tone frequency
+pub const ECHO_TONE_HZ: f32 = 1000.0;
+
+/// Tone rise over the baseline (dB) that counts as echo
+const ECHO_THRESHOLD_DB: f32 = 10.0;
+
+/// Convert an RMS level to dBFS
+pub fn dbfs(level: f32) -> f32 {
+    20.0 * level.max(1e-6).log10()
+}
+
+/// Levels measured from a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelReport {
+    /// Recording length in seconds
+    pub duration_secs: f32,
+    /// Peak sample magnitude
+    pub peak: f32,
+    /// Level of the loudest frames (speech), dBFS
+    pub speech_dbfs: f32,
+    /// Level of the quietest frames (background), dBFS
+    pub noise_dbfs: f32,
+    /// Speech-to-noise ratio in dB
+    pub snr_db: f32,
+    /// Fraction of clipped samples
+    pub clipped_ratio: f32,
+}
+
+impl LevelReport {
+    /// Gain and environment advice (empty when everything looks fine)
+    pub fn recommendations(&self) -> Vec<String> {
+        let mut advice = Vec::new();
+
+        if self.peak < 0.001 {
+            advice.push(
+                "No signal: check that the microphone is unmuted and onevox has microphone permission"
+                    .to_string(),
+            );
+            return advice;
+        }
+
+        if self.clipped_ratio > MAX_CLIPPED_RATIO {
+            advice.push(format!(
+                "Clipping on {:.1}% of samples: lower the input gain",
+                self.clipped_ratio * 100.0
+            ));
+        } else if self.speech_dbfs > MAX_SPEECH_DBFS {
+            advice.push(format!(
+                "Speech peaks at {:.0} dBFS with little headroom: lower the input gain slightly",
+                self.speech_dbfs
+            ));
+        }
+
+        if self.speech_dbfs < MIN_SPEECH_DBFS {
+            advice.push(format!(
+                "Speech is quiet ({:.0} dBFS): raise the input gain or move closer to the microphone",
+                self.speech_dbfs
+            ));
+        }
+
+        if self.snr_db < MIN_SNR_DB {
+            advice.push(format!(
+                "Low signal-to-noise ratio ({:.0} dB): reduce background noise or use a headset microphone",
+                self.snr_db
+            ));
+        }
+
+        advice
+    }
+}
+
+/// RMS of each analysis frame
+fn frame_levels(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let frame_len = (sample_rate as usize * FRAME_MS / 1000).max(1);
+    samples
+        .chunks(frame_len)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect()
+}
+
+/// Measure levels of a recording
+///
+/// Speech and noise levels are the 90th and 10th percentile frame levels,
+/// so the user only needs to speak for part of the recording.
+pub fn analyze(samples: &[f32], sample_rate: u32) -> LevelReport {
+    let mut levels = frame_levels(samples, sample_rate);
+    levels.sort_by(f32::total_cmp);
+    let percentile = |p: f32| {
+        levels
+            .get(((levels.len().saturating_sub(1)) as f32 * p) as usize)
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    let speech = percentile(0.9);
+    let noise = percentile(0.1);
+    let clipped = samples.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+
+    LevelReport {
+        duration_secs: samples.len() as f32 / sample_rate.max(1) as f32,
+        peak: samples.iter().fold(0.0f32, |m, s| m.max(s.abs())),
+        speech_dbfs: dbfs(speech),
+        noise_dbfs: dbfs(noise),
+        snr_db: dbfs(speech) - dbfs(noise),
+        clipped_ratio: clipped as f32 / samples.len().max(1) as f32,
+    }
+}
+
+/// Selected input device compared with the OS default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCheck {
+    /// Device name from config ("default" = follow the OS)
+    pub configured: String,
+    /// Input device the OS reports as default
+    pub system_default: Option<String>,
+    /// Whether the configured device exists
+    pub found: bool,
+}
+
+impl DeviceCheck {
+    /// Compare the configured device with the available devices
+    pub fn new(configured: &str, devices: &[AudioDeviceInfo]) -> Self {
+        Self {
+            configured: configured.to_string(),
+            system_default: devices
+                .iter()
+                .find(|d| d.is_default)
+                .map(|d| d.name.clone()),
+            found: configured == "default" || devices.iter().any(|d| d.name == configured),
+        }
+    }
+
+    /// Device onevox records from
+    pub fn effective(&self) -> Option<&str> {
+        if self.configured == "default" || !self.found {
+            self.system_default.as_deref()
+        } else {
+            Some(&self.configured)
+        }
+    }
+
+    /// Warning when the selection is likely not what the user expects
+    pub fn warning(&self) -> Option<String> {
+        if !self.found {
+            return Some(format!(
+                "Configured device '{}' was not found; onevox falls back to the system default",
+                self.configured
+            ));
+        }
+        match &self.system_default {
+            Some(default) if self.configured != "default" && *default != self.configured => {
+                Some(format!(
+                    "onevox records from '{}' but the system default input is '{}'",
+                    self.configured, default
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Amplitude of one frequency in `samples` (Goertzel algorithm)
+pub fn tone_amplitude(samples: &[f32], sample_rate: u32, frequency_hz: f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let omega = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate as f32;
+    let coeff = 2.0 * omega.cos();
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+    for &sample in samples {
+        let s0 = sample + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    let power = (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0);
+    2.0 * power.sqrt() / samples.len() as f32
+}
+
+/// Result of the echo check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EchoReport {
+    /// Rise of the test tone over the background, in dB
+    pub rise_db: f32,
+}
+
+impl EchoReport {
+    /// Compare the tone frequency before and during playback
+    pub fn new(baseline: &[f32], during: &[f32], sample_rate: u32) -> Self {
+        let before = tone_amplitude(baseline, sample_rate, ECHO_TONE_HZ);
+        let after = tone_amplitude(during, sample_rate, ECHO_TONE_HZ);
+        Self {
+            rise_db: dbfs(after) - dbfs(before),
+        }
+    }
+
+    /// Whether speaker output reaches the microphone
+    pub fn echo_detected(&self) -> bool {
+        self.rise_db >= ECHO_THRESHOLD_DB
+    }
+}
+
+/// Record `duration` of audio from the configured device
+pub async fn record(
+    engine: &mut AudioEngine,
+    config: CaptureConfig,
+    duration: Duration,
+) -> crate::Result<Vec<f32>> {
+    let mut rx = engine.start_capture(config)?;
+    let deadline = Instant::now() + duration;
+    let mut samples = Vec::new();
+
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        match tokio::time::timeout(remaining, rx.recv()).await {
+            Ok(Some(chunk)) => samples.extend_from_slice(&chunk.samples),
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    engine.stop_capture()?;
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_and_echo() {
+        // One second of quiet noise followed by one second of speech-level tone
+        let sample_rate = 16000;
+        let mut samples: Vec<f32> = (0..16000)
+            .map(|i| 0.001 * ((i * 7919) % 200) as f32 / 100.0 - 0.001)
+            .collect();
+        samples.extend((0..16000).map(|i| 0.3 * (i as f32 * 0.2).sin()));
+
+        let report = analyze(&samples, sample_rate);
+        assert!(report.snr_db > 30.0, "snr {}", report.snr_db);
+        assert!(report.speech_dbfs > -15.0 && report.speech_dbfs < -6.0);
+        assert_eq!(report.clipped_ratio, 0.0);
+        assert!(report.recommendations().is_empty());
+
+        let clipped: Vec<f32> = samples
+            .iter()
+            .map(|s| (s * 10.0).clamp(-1.0, 1.0))
+            .collect();
+        assert!(analyze(&clipped, sample_rate).recommendations()[0].contains("Clipping"));
+        assert!(analyze(&[0.0; 1600], sample_rate).recommendations()[0].contains("No signal"));
+
+        let tone: Vec<f32> = (0..8000)
+            .map(|i| 0.1 * (2.0 * std::f32::consts::PI * ECHO_TONE_HZ * i as f32 / 16000.0).sin())
+            .collect();
+        assert!((tone_amplitude(&tone, sample_rate, ECHO_TONE_HZ) - 0.1).abs() < 0.01);
+        assert!(EchoReport::new(&samples[..8000], &tone, sample_rate).echo_detected());
+        assert!(!EchoReport::new(&samples[..8000], &samples[..8000], sample_rate).echo_detected());
+    }
+}
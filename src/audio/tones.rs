@@ -0,0 +1,223 @@
+//! Tone Playback
+//!
+//! Short sine-wave tones on the default output device, used for the
+//! recording consent beeps and periodic reminder tones, plus playback of
+//! recorded clips for the microphone self-test.
+
+#[cfg(feature = "audio-capture")]
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+#[cfg(feature = "audio-capture")]
+use cpal::{FromSample, SampleFormat, SizedSample};
+#[cfg(feature = "audio-capture")]
+use std::time::Duration;
+#[cfg(feature = "audio-capture")]
+use tracing::debug;
+use tracing::warn;
+
+/// Tone parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// Frequency in Hz
+    pub frequency_hz: f32,
+    /// Duration in milliseconds
+    pub duration_ms: u32,
+    /// Volume (0.0 - 1.0)
+    pub volume: f32,
+}
+
+impl Default for Tone {
+    fn default() -> Self {
+        Self {
+            frequency_hz: 880.0,
+            duration_ms: 150,
+            volume: 0.3,
+        }
+    }
+}
+
+/// Plays tones on the default output device
+#[derive(Debug, Clone, Default)]
+pub struct TonePlayer;
+
+impl TonePlayer {
+    /// Create a new tone player
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Play a tone in the background (never blocks the caller)
+    pub fn play(&self, tone: Tone) {
+        std::thread::spawn(move || {
+            if let Err(e) = play_blocking(tone) {
+                warn!("Failed to play tone: {}", e);
+            }
+        });
+    }
+}
+
+/// Play a tone and wait for it to finish
+#[cfg(feature = "audio-capture")]
+pub fn play_blocking(tone: Tone) -> crate::Result<()> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| crate::Error::Audio("No output device available".to_string()))?;
+
+    let supported = device
+        .default_output_config()
+        .map_err(|e| crate::Error::Audio(format!("Failed to get output config: {}", e)))?;
+
+    debug!(
+        "Playing {}Hz tone for {}ms",
+        tone.frequency_hz, tone.duration_ms
+    );
+
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+
+    match sample_format {
+        SampleFormat::F32 => run_tone::<f32>(&device, &config, tone),
+        SampleFormat::I16 => run_tone::<i16>(&device, &config, tone),
+        SampleFormat::U16 => run_tone::<u16>(&device, &config, tone),
+        other => Err(crate::Error::Audio(format!(
+            "Unsupported output sample format: {:?}",
+            other
+        ))),
+    }
+}
+
+#[cfg(feature = "audio-capture")]
+fn run_tone<T>(device: &cpal::Device, config: &cpal::StreamConfig, tone: Tone) -> crate::Result<()>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let sample_rate = config.sample_rate.0 as f32;
+    let channels = config.channels as usize;
+    let volume = tone.volume.clamp(0.0, 1.0);
+    let total_frames = (sample_rate * tone.duration_ms as f32 / 1000.0) as usize;
+    // Short linear fade to avoid clicks at start/end
+    let fade_frames = ((sample_rate * 0.005) as usize).max(1);
+    let mut frame = 0usize;
+
+    let stream = device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for out in data.chunks_mut(channels) {
+                    let value = if frame < total_frames {
+                        let envelope =
+                            (frame.min(total_frames - frame) as f32 / fade_frames as f32).min(1.0);
+                        let t = frame as f32 / sample_rate;
+                        (t * tone.frequency_hz * 2.0 * std::f32::consts::PI).sin()
+                            * volume
+                            * envelope
+                    } else {
+                        0.0
+                    };
+                    frame += 1;
+                    for sample in out.iter_mut() {
+                        *sample = T::from_sample(value);
+                    }
+                }
+            },
+            |err| warn!("Tone output stream error: {}", err),
+            None,
+        )
+        .map_err(|e| crate::Error::Audio(format!("Failed to build output stream: {}", e)))?;
+
+    stream
+        .play()
+        .map_err(|e| crate::Error::Audio(format!("Failed to start output stream: {}", e)))?;
+
+    // Let the tone play out (plus a little slack for device latency)
+    std::thread::sleep(Duration::from_millis(tone.duration_ms as u64 + 50));
+    drop(stream);
+
+    Ok(())
+}
+
+/// Play mono `samples` recorded at `sample_rate` and wait for them to finish
+#[cfg(feature = "audio-capture")]
+pub fn play_samples_blocking(samples: &[f32], sample_rate: u32) -> crate::Result<()> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| crate::Error::Audio("No output device available".to_string()))?;
+
+    let supported = device
+        .default_output_config()
+        .map_err(|e| crate::Error::Audio(format!("Failed to get output config: {}", e)))?;
+
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+    let samples = super::convert::resample(samples, sample_rate, config.sample_rate.0)?;
+
+    debug!(
+        "Playing {:.1}s clip",
+        samples.len() as f32 / config.sample_rate.0 as f32
+    );
+
+    match sample_format {
+        SampleFormat::F32 => run_samples::<f32>(&device, &config, samples),
+        SampleFormat::I16 => run_samples::<i16>(&device, &config, samples),
+        SampleFormat::U16 => run_samples::<u16>(&device, &config, samples),
+        other => Err(crate::Error::Audio(format!(
+            "Unsupported output sample format: {:?}",
+            other
+        ))),
+    }
+}
+
+#[cfg(feature = "audio-capture")]
+fn run_samples<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    samples: Vec<f32>,
+) -> crate::Result<()>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels as usize;
+    let duration_ms = samples.len() as u64 * 1000 / config.sample_rate.0.max(1) as u64;
+    let mut position = 0usize;
+
+    let stream = device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for out in data.chunks_mut(channels) {
+                    let value = samples.get(position).copied().unwrap_or(0.0);
+                    position += 1;
+                    for sample in out.iter_mut() {
+                        *sample = T::from_sample(value);
+                    }
+                }
+            },
+            |err| warn!("Playback output stream error: {}", err),
+            None,
+        )
+        .map_err(|e| crate::Error::Audio(format!("Failed to build output stream: {}", e)))?;
+
+    stream
+        .play()
+        .map_err(|e| crate::Error::Audio(format!("Failed to start output stream: {}", e)))?;
+
+    std::thread::sleep(Duration::from_millis(duration_ms + 50));
+    drop(stream);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "audio-capture"))]
+pub fn play_samples_blocking(_samples: &[f32], _sample_rate: u32) -> crate::Result<()> {
+    Err(crate::Error::Audio(
+        "audio-capture feature not enabled".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "audio-capture"))]
+pub fn play_blocking(_tone: Tone) -> crate::Result<()> {
+    Err(crate::Error::Audio(
+        "audio-capture feature not enabled".to_string(),
+    ))
+}
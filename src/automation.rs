@@ -0,0 +1,151 @@
+//! Automation URLs
+//!
+//! `onevox://` URLs expose core actions to macOS Shortcuts, AppleScript
+//! (`open location "onevox://start"`) and other launchers:
+//!
+//! - `onevox://start`, `onevox://stop`: start or stop dictation
+//! - `onevox://transcribe?file=/path/to/audio.wav`: transcribe a file
+//! - `onevox://last`: the most recent transcript
+//!
+//! URLs are forwarded to the running daemon over IPC. The same URLs can be
+//! run from a shell with `onevox open-url <url>`, which prints any text
+//! result; when opened through the URL scheme, text results are copied to
+//! the clipboard instead.
+
+use crate::ipc::{IpcClient, IpcError, IpcResult};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// URL scheme registered by the macOS app bundle
+pub const URL_SCHEME: &str = "onevox";
+
+/// How long a file transcription may take before the request times out
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Action requested by an automation URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Start dictation
+    StartDictation,
+    /// Stop dictation
+    StopDictation,
+    /// Transcribe an audio file
+    TranscribeFile(PathBuf),
+    /// Fetch the most recent transcript
+    LastTranscript,
+}
+
+impl Action {
+    /// Parse an `onevox://<action>[?query]` URL
+    pub fn from_url(url: &str) -> crate::Result<Self> {
+        let invalid =
+            |reason: &str| crate::Error::Other(format!("Invalid URL '{}': {}", url, reason));
+
+        let rest = url
+            .split_once("://")
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(URL_SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or_else(|| invalid("expected onevox://<action>"))?;
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        match action.trim_end_matches('/').to_ascii_lowercase().as_str() {
+            "start" => Ok(Action::StartDictation),
+            "stop" => Ok(Action::StopDictation),
+            "last" => Ok(Action::LastTranscript),
+            "transcribe" => query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| *key == "file")
+                .map(|(_, value)| Action::TranscribeFile(PathBuf::from(percent_decode(value))))
+                .ok_or_else(|| invalid("transcribe needs ?file=<path>")),
+            other => Err(invalid(&format!(
+                "unknown action '{}' (use start, stop, transcribe or last)",
+                other
+            ))),
+        }
+    }
+
+    /// Run the action against the daemon, returning any text result
+    pub async fn run(&self) -> IpcResult<Option<String>> {
+        let mut client = IpcClient::default().with_client_name("automation");
+        match self {
+            Action::StartDictation => client.start_dictation().await.map(|_| None),
+            Action::StopDictation => client.stop_dictation().await.map(|_| None),
+            Action::TranscribeFile(path) => {
+                // The daemon resolves relative paths against its own directory
+                let path = std::path::absolute(path)?;
+                let mut client = client
+                    .with_timeouts(
+                        crate::ipc::client::DEFAULT_CONNECT_TIMEOUT,
+                        TRANSCRIBE_TIMEOUT,
+                    )
+                    .with_max_attempts(1);
+                client
+                    .transcribe_file(&path.to_string_lossy())
+                    .await
+                    .map(Some)
+            }
+            Action::LastTranscript => match client.get_last_transcript().await? {
+                Some(last) => Ok(Some(last.text)),
+                None => Err(IpcError::Daemon("No transcriptions yet".to_string())),
+            },
+        }
+    }
+}
+
+/// Decode `%XX` escapes and `+` in a URL query value
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_urls() {
+        assert_eq!(
+            Action::from_url("onevox://start").unwrap(),
+            Action::StartDictation
+        );
+        assert_eq!(
+            Action::from_url("ONEVOX://Stop/").unwrap(),
+            Action::StopDictation
+        );
+        assert_eq!(
+            Action::from_url("onevox://last").unwrap(),
+            Action::LastTranscript
+        );
+        assert_eq!(
+            Action::from_url("onevox://transcribe?lang=en&file=%2FUsers%2Fme%2FMy%20Memo.wav")
+                .unwrap(),
+            Action::TranscribeFile(PathBuf::from("/Users/me/My Memo.wav"))
+        );
+
+        assert!(Action::from_url("onevox://transcribe").is_err());
+        assert!(Action::from_url("onevox://explode").is_err());
+        assert!(Action::from_url("https://start").is_err());
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+}
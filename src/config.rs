@@ -3,8 +3,9 @@
 //! Handles loading, validation, and hot-reloading of configuration.
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,48 +19,272 @@ pub struct Config {
     pub vad: VadConfig,
     #[serde(default)]
     pub model: ModelConfig,
-    #[serde(default)]
+    #[serde(default, alias = "postprocess")]
     pub post_processing: PostProcessingConfig,
     #[serde(default)]
     pub injection: InjectionConfig,
     #[serde(default)]
     pub history: HistoryConfig,
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    #[serde(default)]
+    pub consent: ConsentConfig,
+    #[serde(default)]
+    pub dictation: DictationConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub screen_recordings: ScreenRecordingsConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub commands: CommandsConfig,
+    #[serde(default)]
+    pub clipboard_ring: ClipboardRingConfig,
+    #[serde(default)]
+    pub startup: StartupConfig,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    #[serde(default)]
+    pub vocabulary: VocabularyConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Extra hotkeys, as `action = "combo"` ("dictate",
+    /// "dictate-translate", "hands-free", "command", "undo", "picker",
+    /// "pause")
+    #[serde(default)]
+    pub hotkeys: BTreeMap<String, String>,
+    /// Per-application / per-input-source overrides, checked in order
+    #[serde(default)]
+    pub profiles: Vec<ProfileConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DaemonConfig {
     pub auto_start: bool,
+    /// Default log level: trace, debug, info, warn, or error
     pub log_level: String,
+    /// Per-module levels, e.g. `"onevox::models" = "debug"`
+    #[serde(default)]
+    pub log_modules: BTreeMap<String, String>,
+    /// Write daemon logs to daily files in the log directory (transcript
+    /// text is only logged at debug level)
+    #[serde(default = "default_log_file")]
+    pub log_file: bool,
+    /// Daily log files kept
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+    /// Most IPC clients connected at once; further clients wait until one
+    /// disconnects
+    #[serde(default = "default_max_ipc_connections")]
+    pub max_ipc_connections: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotkeyConfig {
     pub trigger: String,
+    /// "hold" (push-to-talk), "toggle", or "hands-free"
     pub mode: String,
+    /// How hotkeys are read: "auto" (evdev under Wayland, native
+    /// otherwise), "native", or "evdev" (Linux, needs the `input` group)
+    #[serde(default = "default_hotkey_backend")]
+    pub backend: String,
+    /// Hotkey that undoes the last injection (e.g. "Ctrl+Shift+Z")
+    #[serde(default)]
+    pub undo: Option<String>,
+    /// Hotkey that opens the clipboard ring picker (e.g. "Ctrl+Alt+V")
+    #[serde(default)]
+    pub picker: Option<String>,
+    /// Foot pedal / macro pad buttons mapped to actions
+    #[serde(default)]
+    pub hid: Vec<HidTriggerConfig>,
+}
+
+/// A button on a HID device (foot pedal, macro pad) that triggers an action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HidTriggerConfig {
+    /// Device name (case-insensitive substring) or USB id "vendor:product"
+    /// in hex, as shown by `onevox hotkey devices`
+    pub device: String,
+    /// Button: evdev key name ("BTN_0", "KEY_B") or numeric code
+    pub button: String,
+    /// "dictate" (follows hotkey.mode), "undo", "picker", or "pause"
+    #[serde(default = "default_hid_action")]
+    pub action: String,
+    /// Take exclusive access to the device so its buttons don't also type
+    #[serde(default = "default_hid_grab")]
+    pub grab: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
     pub device: String,
+    /// What to capture: "microphone", or "system" for what the computer
+    /// plays through a loopback source (`device` then names the source)
+    #[serde(default = "default_audio_source")]
+    pub source: String,
     pub sample_rate: u32,
     pub chunk_duration_ms: u32,
+    /// Minutes between repeats of the same mic gain/placement hint
+    /// (0 = never hint)
+    #[serde(default = "default_gain_hint_interval_mins")]
+    pub gain_hint_interval_mins: u64,
+    /// Suppress steady background noise (fans, hum) before the VAD and
+    /// model (`onevox test-denoise` compares the result)
+    #[serde(default)]
+    pub denoise: bool,
+    /// Keep the microphone open between sessions and start each hotkey
+    /// session with this much audio from before the key press (ms, 0 = off)
+    #[serde(default)]
+    pub pre_buffer_ms: u32,
+    /// Automatic gain control for quiet or loud microphones
+    #[serde(default)]
+    pub agc: AgcConfig,
+    /// Keep each dictated segment as a WAV file linked to its history
+    /// entries (`onevox history play <id>`)
+    #[serde(default)]
+    pub save_recordings: bool,
+    /// Saved recordings to keep (0 = unlimited)
+    #[serde(default = "default_recordings_max_files")]
+    pub recordings_max_files: usize,
+    /// Days to keep saved recordings (0 = forever)
+    #[serde(default = "default_recordings_max_days")]
+    pub recordings_max_days: u64,
+    /// Disk space for saved recordings in MB (0 = unlimited)
+    #[serde(default = "default_recordings_max_mb")]
+    pub recordings_max_mb: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgcConfig {
+    /// Normalize the input level while capturing
+    pub enabled: bool,
+    /// Speech level to aim for (RMS, dBFS)
+    pub target_dbfs: f32,
+    /// Most gain (and attenuation) applied, in dB
+    pub max_gain_db: f32,
+    /// How fast the gain drops when the input gets louder (ms)
+    pub attack_ms: u32,
+    /// How fast the gain recovers when the input gets quieter (ms)
+    pub release_ms: u32,
+    /// Hard limiter ceiling (dBFS)
+    pub limiter_dbfs: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiConfig {
     pub recording_overlay: bool,
+    /// Overlay placement and look
+    #[serde(default)]
+    pub overlay: OverlayConfig,
+    /// Sound cues for dictation events
+    #[serde(default)]
+    pub sound_feedback: SoundFeedbackConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundFeedbackConfig {
+    /// Play cues at all
+    #[serde(default)]
+    pub enabled: bool,
+    /// Cue volume (0.0 - 1.0)
+    #[serde(default = "default_feedback_volume")]
+    pub volume: f32,
+    /// Each cue: "tone", "system", "none", or an audio file path
+    #[serde(default = "default_feedback_cue")]
+    pub start: String,
+    #[serde(default = "default_feedback_cue")]
+    pub stop: String,
+    /// Played for every transcribed segment
+    #[serde(default = "default_feedback_cue")]
+    pub transcribed: String,
+    #[serde(default = "default_feedback_none")]
+    pub injected: String,
+    /// Played when transcription or injection fails
+    #[serde(default = "default_feedback_cue")]
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayConfig {
+    /// Where the overlay sits: "top-left", "top-center", "top-right",
+    /// "bottom-left", "bottom-center", "bottom-right"
+    #[serde(default = "default_overlay_anchor")]
+    pub anchor: String,
+    /// Horizontal distance from the anchored edge, in points (from the
+    /// middle for center anchors, positive = right)
+    #[serde(default)]
+    pub offset_x: f32,
+    /// Vertical distance from the anchored edge, in points
+    #[serde(default = "default_overlay_offset_y")]
+    pub offset_y: f32,
+    /// Size multiplier
+    #[serde(default = "default_overlay_scale")]
+    pub scale: f32,
+    /// Background color ("#RRGGBB")
+    #[serde(default = "default_overlay_background")]
+    pub background: String,
+    /// Waveform and text color ("#RRGGBB")
+    #[serde(default = "default_overlay_foreground")]
+    pub foreground: String,
+    /// Window opacity, 0.0-1.0
+    #[serde(default = "default_overlay_opacity")]
+    pub opacity: f32,
+    /// Show the time recorded so far
+    #[serde(default = "default_overlay_timer")]
+    pub timer: bool,
+    /// Clicking the overlay stops dictation (otherwise clicks pass through)
+    #[serde(default)]
+    pub click_to_stop: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VadConfig {
     pub enabled: bool,
-    pub backend: String,
+    /// Speech detector: "energy" or "silero" (neural, needs the onnx feature)
+    #[serde(alias = "backend")]
+    pub detector: String,
     pub threshold: f32,
     pub pre_roll_ms: u32,
     pub post_roll_ms: u32,
     pub min_speech_chunks: usize,
     pub min_silence_chunks: usize,
     pub adaptive: bool,
+    /// Background energy measured by `onevox calibrate` (0.0 = learn it
+    /// from the audio)
+    #[serde(default)]
+    pub noise_floor: f32,
+    /// Keep following the background noise during long sessions (energy
+    /// detector with `adaptive`)
+    #[serde(default)]
+    pub track_noise_floor: bool,
+    /// Adapt post-roll to speech cadence and trailing energy
+    #[serde(default = "default_adaptive_post_roll")]
+    pub adaptive_post_roll: bool,
+    /// Lower bound for adaptive post-roll (ms)
+    #[serde(default = "default_min_post_roll_ms")]
+    pub min_post_roll_ms: u32,
+    /// Upper bound for adaptive post-roll (ms)
+    #[serde(default = "default_max_post_roll_ms")]
+    pub max_post_roll_ms: u32,
+    /// Drop segments with less speech than this (ms, 0 = off)
+    #[serde(default = "default_min_speech_ms")]
+    pub min_speech_ms: u32,
+    /// Drop noise-like segments above this spectral flatness (1.0 = off)
+    #[serde(default = "default_max_spectral_flatness")]
+    pub max_spectral_flatness: f32,
+    /// Speech probability threshold for the Silero detector (0.0 - 1.0)
+    #[serde(default = "default_silero_threshold")]
+    pub silero_threshold: f32,
+    /// End a toggle or hands-free session after this much silence (ms,
+    /// 0 = never); unlike the post-roll this stops the whole dictation
+    #[serde(default)]
+    pub auto_stop_silence_ms: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +292,100 @@ pub struct ModelConfig {
     pub model_path: String,
     pub device: String,
     pub preload: bool,
+    /// Default decoding language (ISO 639-1 code, or "auto" to detect)
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Backend: "auto" (detect from model path), "whisper-cpp", "onnx",
+    /// or "whisper-server"
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    /// Local whisper-server settings (used when backend = "whisper-server")
+    #[serde(default)]
+    pub server: WhisperServerConfig,
+    /// Run extra backends on every segment and reconcile the results
+    #[serde(default)]
+    pub ensemble: EnsembleConfig,
+    /// Decoding: "greedy" (fastest) or "beam" (beam search; on ONNX CTC
+    /// models with `lm_path` fused in)
+    #[serde(default = "default_decoding")]
+    pub decoding: String,
+    /// N-gram language model in ARPA format for beam search decoding
+    #[serde(default)]
+    pub lm_path: Option<String>,
+    /// CTC beam search and language model rescoring (ONNX CTC models)
+    #[serde(default)]
+    pub rescoring: RescoringConfig,
+    /// Splitting recordings longer than the model's window
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+    /// ONNX Runtime execution provider: "auto" (the platform's GPU provider
+    /// unless `device` is "cpu"), "cpu", "coreml", "cuda", "tensorrt", or
+    /// "directml"; falls back to the CPU when unavailable
+    #[serde(default = "default_execution_provider")]
+    pub execution_provider: String,
+    /// HTTPS URL of a JSON model registry (`{"models": [...]}`) that adds
+    /// or overrides models; cached and refreshed daily by the daemon
+    #[serde(default)]
+    pub registry_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnsembleConfig {
+    /// Transcribe with `model_path` and every model in `models`
+    pub enabled: bool,
+    /// Additional models (backend detected from each model path)
+    pub models: Vec<String>,
+    /// Reconciliation: "confidence" (most confident hypothesis wins) or
+    /// "vote" (ROVER-style word voting)
+    pub strategy: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RescoringConfig {
+    /// CTC beam width (1 = greedy decoding, no rescoring)
+    pub beam_width: usize,
+    /// Hypotheses kept from the beam for rescoring
+    pub n_best: usize,
+    /// Language model: "word-frequency" (built-in or `lm_path` word list),
+    /// "arpa" (n-gram model at `lm_path`), or "none"
+    pub lm: String,
+    /// Word list ("word count" per line) or ARPA file for `lm`
+    pub lm_path: Option<String>,
+    /// Weight of the language model score against the acoustic score
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkingConfig {
+    /// Transcribe recordings longer than `max_chunk_secs` in pieces
+    pub enabled: bool,
+    /// Longest piece sent to the model (Whisper decodes at most 30 s)
+    pub max_chunk_secs: u32,
+    /// Audio shared by neighbouring pieces when there is no pause to cut at
+    pub overlap_ms: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperServerConfig {
+    /// whisper.cpp server binary (name on PATH or absolute path)
+    pub binary: String,
+    /// Port to listen on (0 = pick a free port)
+    pub port: u16,
+    /// Seconds to wait for the server to load the model
+    pub startup_timeout_secs: u64,
+    /// Seconds to wait for a single transcription request
+    pub request_timeout_secs: u64,
+    /// Extra arguments passed to the server (no `keyring:<name>` secrets:
+    /// arguments show up in the process list)
+    pub extra_args: Vec<String>,
+    /// Let the daemon install a pinned whisper-server when `binary` isn't
+    /// found (prebuilt download, else a build from source)
+    #[serde(default = "default_server_auto_install")]
+    pub auto_install: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,14 +393,119 @@ pub struct PostProcessingConfig {
     pub auto_punctuation: bool,
     pub auto_capitalize: bool,
     pub remove_filler_words: bool,
+    /// Mask profanity, keeping the first letter ("f***")
+    #[serde(default)]
+    pub mask_profanity: bool,
+    /// Words dropped by `remove_filler_words`
+    #[serde(default = "default_filler_words")]
+    pub filler_words: Vec<String>,
+    /// Words masked in addition to the built-in list
+    #[serde(default)]
+    pub profanity_words: Vec<String>,
+    /// Stages in the order they run: "numbers", "fillers", "profanity",
+    /// "punctuation", "capitalize". Empty = that order, with each stage
+    /// switched by its flag above
+    #[serde(default)]
+    pub stages: Vec<String>,
+    /// Locale for numbers, amounts, and dates ("auto" = OS locale)
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Overrides for transcripts in a given language, keyed by ISO 639-1
+    /// code (detected per utterance)
+    #[serde(default)]
+    pub languages: HashMap<String, LanguagePostProcessing>,
+}
+
+/// Post-processing settings replaced for one language; unset ones keep the
+/// `[post_processing]` value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguagePostProcessing {
+    pub auto_punctuation: Option<bool>,
+    pub auto_capitalize: Option<bool>,
+    pub remove_filler_words: Option<bool>,
+    pub mask_profanity: Option<bool>,
+    pub filler_words: Option<Vec<String>>,
+    pub profanity_words: Option<Vec<String>>,
+    pub stages: Option<Vec<String>>,
+    pub locale: Option<String>,
+}
+
+impl LanguagePostProcessing {
+    /// `base` with this language's overrides applied
+    pub fn apply_to(&self, base: &PostProcessingConfig) -> PostProcessingConfig {
+        PostProcessingConfig {
+            auto_punctuation: self.auto_punctuation.unwrap_or(base.auto_punctuation),
+            auto_capitalize: self.auto_capitalize.unwrap_or(base.auto_capitalize),
+            remove_filler_words: self.remove_filler_words.unwrap_or(base.remove_filler_words),
+            mask_profanity: self.mask_profanity.unwrap_or(base.mask_profanity),
+            filler_words: self
+                .filler_words
+                .clone()
+                .unwrap_or_else(|| base.filler_words.clone()),
+            profanity_words: self
+                .profanity_words
+                .clone()
+                .unwrap_or_else(|| base.profanity_words.clone()),
+            stages: self.stages.clone().unwrap_or_else(|| base.stages.clone()),
+            locale: self.locale.clone().unwrap_or_else(|| base.locale.clone()),
+            languages: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InjectionConfig {
     pub method: String,
     pub paste_delay_ms: u32,
+    /// Milliseconds to wait after pasting before putting the previous
+    /// clipboard text back (0 = leave the transcript on the clipboard)
+    #[serde(default = "default_clipboard_restore_ms")]
+    pub clipboard_restore_ms: u64,
     #[serde(default = "default_focus_settle_ms")]
     pub focus_settle_ms: u32,
+    /// Number of injections remembered for `onevox undo`
+    #[serde(default = "default_undo_depth")]
+    pub undo_depth: usize,
+    /// Seconds to hold a transcript whose app lost focus, re-injecting once
+    /// it is focused again (0 = fail immediately)
+    #[serde(default = "default_refocus_timeout_secs")]
+    pub refocus_timeout_secs: u64,
+    /// What to do when a password field has focus: "block", "confirm"
+    /// (hold until `onevox confirm`), or "defer" (wait like a focus change)
+    #[serde(default = "default_secure_fields")]
+    pub secure_fields: String,
+    /// Hold transcripts the model scored below this (0-1) for `onevox
+    /// confirm` instead of typing them (0 = type everything)
+    #[serde(default)]
+    pub min_confidence: f32,
+    /// What to do with such a transcript: "confirm" (notify and hold for
+    /// `onevox confirm`) or "review" (review window: insert, discard, or
+    /// re-record)
+    #[serde(default = "default_low_confidence")]
+    pub low_confidence: String,
+    /// Per-app injection overrides, checked before the built-in quirks
+    #[serde(default)]
+    pub quirks: Vec<InjectionQuirk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InjectionQuirk {
+    /// Focused application to match (case-insensitive substring of the
+    /// app name, bundle ID, or window class)
+    pub app: String,
+    /// Injection method: "accessibility", "ax", "paste", or "keystrokes"
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Characters typed per chunk with "keystrokes" (0 = whole text)
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// Delay between chunks in milliseconds
+    #[serde(default)]
+    pub key_delay_ms: Option<u64>,
+    /// Delay before injection starts in milliseconds
+    #[serde(default)]
+    pub initial_delay_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +515,264 @@ pub struct HistoryConfig {
     pub auto_save: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    /// Announce injection results through the screen reader
+    pub announce: bool,
+    /// Announcement detail: "minimal", "normal", or "verbose"
+    pub verbosity: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsentConfig {
+    /// Play a beep when recording starts
+    pub start_beep: bool,
+    /// Play a beep when recording stops
+    pub stop_beep: bool,
+    /// Play a reminder tone every N seconds while recording (0 = disabled)
+    pub reminder_interval_secs: u32,
+    /// Tone volume (0.0 - 1.0)
+    pub volume: f32,
+    /// Base tone frequency in Hz
+    pub frequency_hz: f32,
+    /// Tone duration in milliseconds
+    pub duration_ms: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationConfig {
+    /// Turn on Do Not Disturb while recording, restoring it afterwards
+    #[serde(default)]
+    pub auto_dnd: bool,
+    /// Skip injecting a VAD segment that repeats the previous one within
+    /// this many milliseconds (0 = disabled)
+    #[serde(default = "default_duplicate_window_ms")]
+    pub duplicate_window_ms: u64,
+}
+
+impl Default for DictationConfig {
+    fn default() -> Self {
+        Self {
+            auto_dnd: false,
+            duplicate_window_ms: default_duplicate_window_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Take a daily history backup while the daemon runs
+    pub enabled: bool,
+    /// Number of backups of each kind (daily, before-clear, before-restore)
+    /// to keep before the oldest are rotated out
+    pub keep: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Cap for retained audio recordings in MB (0 = unlimited)
+    pub max_audio_mb: u64,
+    /// Cap for log files in MB (0 = unlimited)
+    pub max_logs_mb: u64,
+    /// Cap for downloaded models in MB (0 = unlimited); only inactive
+    /// versions are evicted
+    pub max_models_mb: u64,
+    /// Warn before a model download that would leave less free space than
+    /// this, in MB
+    pub low_space_warning_mb: u64,
+    /// How often the daemon enforces the caps, in minutes (0 = only on
+    /// `onevox storage cleanup`)
+    pub cleanup_interval_minutes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// Record local usage analytics for `onevox dashboard` (opt-in; data
+    /// never leaves the machine)
+    pub enabled: bool,
+    /// Days of daily aggregates to keep
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenRecordingsConfig {
+    /// Transcribe new screen recordings into sidecar files while the
+    /// daemon runs
+    pub enabled: bool,
+    /// Recordings to pick up; `*` and `?` wildcards in the file name, `~`
+    /// for the home directory
+    #[serde(default = "default_screen_recording_globs")]
+    pub globs: Vec<String>,
+    /// Sidecar format: "srt", "vtt", "text", or "json"
+    #[serde(default = "default_sidecar_format")]
+    pub format: String,
+    /// Seconds a file must stop growing before it counts as finished
+    #[serde(default = "default_settle_secs")]
+    pub settle_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Serve the HTTP/WebSocket API on 127.0.0.1 while the daemon runs
+    /// (builds with the `http-api` feature)
+    pub enabled: bool,
+    /// Port to listen on
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Bearer token required on every request ("" = generate one into
+    /// `api-token` in the config directory; `keyring:<name>` reads it from
+    /// the OS keychain)
+    #[serde(default)]
+    pub token: String,
+    /// Browser origins allowed to call the API, e.g.
+    /// "chrome-extension://<id>"; other web pages are refused
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandsConfig {
+    /// Turn spoken commands ("new line", "period", "scratch that") into
+    /// edits before injection
+    #[serde(default = "default_commands_enabled")]
+    pub enabled: bool,
+    /// Extra or overriding phrases: `"spoken phrase" = "text"`, where
+    /// `{enter}`, `{tab}`, and `{undo}` are actions and "" disables a
+    /// built-in phrase
+    #[serde(flatten)]
+    pub phrases: std::collections::HashMap<String, String>,
+}
+
+impl Default for CommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_commands_enabled(),
+            phrases: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardRingConfig {
+    /// Keep recent transcripts for the picker (`hotkey.picker`)
+    pub enabled: bool,
+    /// Number of transcripts kept
+    #[serde(default = "default_clipboard_ring_size")]
+    pub size: usize,
+}
+
+impl Default for ClipboardRingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: default_clipboard_ring_size(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupConfig {
+    /// When the model / dictation engine can't start: "fail-fast" (the
+    /// daemon exits) or "degrade" (keep serving IPC and retry)
+    #[serde(default = "default_startup_policy")]
+    pub model: String,
+    /// When no hotkey can be registered: "fail-fast" or "degrade"
+    /// (dictation over IPC only, retrying the hotkeys)
+    #[serde(default = "default_startup_policy")]
+    pub hotkeys: String,
+    /// Seconds between retries of a degraded subsystem
+    #[serde(default = "default_startup_retry_secs")]
+    pub retry_interval_secs: u64,
+    /// Retries before a degraded subsystem is given up on (0 = keep
+    /// retrying)
+    #[serde(default)]
+    pub max_retries: u32,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            model: default_startup_policy(),
+            hotkeys: default_startup_policy(),
+            retry_interval_secs: default_startup_retry_secs(),
+            max_retries: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// Applications where dictation hotkeys are ignored and nothing is
+    /// recorded (case-insensitive substrings of the app name, bundle ID,
+    /// window class, or window title)
+    #[serde(default)]
+    pub blocked_apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VocabularyConfig {
+    /// Product names and jargon to bias transcription towards (more can
+    /// be listed in vocabulary.txt in the config directory)
+    pub terms: Vec<VocabularyTerm>,
+    /// Replace phrases in transcripts that nearly spell a term
+    pub fuzzy_replace: bool,
+    /// How close (0-1) a phrase must be to a term to be replaced
+    pub fuzzy_threshold: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Show desktop notifications for daemon events
+    pub enabled: bool,
+    /// Transcription and injection failures, and other daemon errors
+    pub errors: bool,
+    /// Permissions the daemon needs but doesn't have
+    pub permissions: bool,
+    /// Background model downloads finishing or failing
+    pub downloads: bool,
+    /// Every finished transcription
+    pub transcriptions: bool,
+    /// Seconds before the same kind of notification is shown again
+    /// (transcriptions are never held back)
+    pub min_interval_secs: u64,
+}
+
+/// A vocabulary term: `"Onevox"`, or `{ term = "PostgreSQL", boost = 2.0 }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VocabularyTerm {
+    Plain(String),
+    Boosted {
+        term: String,
+        /// Decoder bias weight (1.0 = default)
+        #[serde(default = "default_boost")]
+        boost: f32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileConfig {
+    /// Profile name (for logs)
+    pub name: String,
+    /// Focused application to match (case-insensitive substring of the
+    /// app name, bundle ID, or window class)
+    #[serde(default)]
+    pub app: Option<String>,
+    /// Keyboard input source to match (case-insensitive substring,
+    /// e.g. "Spanish" or "es")
+    #[serde(default)]
+    pub input_source: Option<String>,
+    /// Decoding language for this profile (ISO 639-1 code, or "auto")
+    pub language: String,
+}
+
 impl Default for Config {
     fn default() -> Self {
         // Platform-specific default hotkey
@@ -110,15 +792,32 @@ impl Default for Config {
             daemon: DaemonConfig {
                 auto_start: true,
                 log_level: "info".to_string(),
+                log_modules: BTreeMap::new(),
+                log_file: true,
+                log_retention_days: default_log_retention_days(),
+                max_ipc_connections: default_max_ipc_connections(),
             },
             hotkey: HotkeyConfig {
                 trigger: default_hotkey.to_string(),
-                mode: "push-to-talk".to_string(),
+                mode: "hold".to_string(),
+                backend: default_hotkey_backend(),
+                undo: None,
+                picker: None,
+                hid: Vec::new(),
             },
             audio: AudioConfig {
                 device: "default".to_string(),
+                source: default_audio_source(),
                 sample_rate: 16000,
                 chunk_duration_ms: 200,
+                gain_hint_interval_mins: default_gain_hint_interval_mins(),
+                denoise: false,
+                pre_buffer_ms: 0,
+                agc: AgcConfig::default(),
+                save_recordings: false,
+                recordings_max_files: default_recordings_max_files(),
+                recordings_max_days: default_recordings_max_days(),
+                recordings_max_mb: default_recordings_max_mb(),
             },
             ui: UiConfig::default(),
             vad: VadConfig::default(),
@@ -126,6 +825,22 @@ impl Default for Config {
             post_processing: PostProcessingConfig::default(),
             injection: InjectionConfig::default(),
             history: HistoryConfig::default(),
+            accessibility: AccessibilityConfig::default(),
+            consent: ConsentConfig::default(),
+            dictation: DictationConfig::default(),
+            backup: BackupConfig::default(),
+            storage: StorageConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            screen_recordings: ScreenRecordingsConfig::default(),
+            api: ApiConfig::default(),
+            commands: CommandsConfig::default(),
+            clipboard_ring: ClipboardRingConfig::default(),
+            startup: StartupConfig::default(),
+            privacy: PrivacyConfig::default(),
+            vocabulary: VocabularyConfig::default(),
+            notifications: NotificationsConfig::default(),
+            hotkeys: BTreeMap::new(),
+            profiles: Vec::new(),
         }
     }
 }
@@ -134,13 +849,22 @@ impl Default for VadConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            backend: "energy".to_string(),
+            detector: "energy".to_string(),
             threshold: 0.02,
             pre_roll_ms: 300,
             post_roll_ms: 500,
             min_speech_chunks: 2,
             min_silence_chunks: 3,
             adaptive: true,
+            noise_floor: 0.0,
+            track_noise_floor: false,
+            adaptive_post_roll: default_adaptive_post_roll(),
+            min_post_roll_ms: default_min_post_roll_ms(),
+            max_post_roll_ms: default_max_post_roll_ms(),
+            min_speech_ms: default_min_speech_ms(),
+            max_spectral_flatness: default_max_spectral_flatness(),
+            silero_threshold: default_silero_threshold(),
+            auto_stop_silence_ms: 0,
         }
     }
 }
@@ -149,6 +873,38 @@ impl Default for UiConfig {
     fn default() -> Self {
         Self {
             recording_overlay: true,
+            overlay: OverlayConfig::default(),
+            sound_feedback: SoundFeedbackConfig::default(),
+        }
+    }
+}
+
+impl Default for SoundFeedbackConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: default_feedback_volume(),
+            start: default_feedback_cue(),
+            stop: default_feedback_cue(),
+            transcribed: default_feedback_cue(),
+            injected: default_feedback_none(),
+            error: default_feedback_cue(),
+        }
+    }
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            anchor: default_overlay_anchor(),
+            offset_x: 0.0,
+            offset_y: default_overlay_offset_y(),
+            scale: default_overlay_scale(),
+            background: default_overlay_background(),
+            foreground: default_overlay_foreground(),
+            opacity: default_overlay_opacity(),
+            timer: default_overlay_timer(),
+            click_to_stop: false,
         }
     }
 }
@@ -159,6 +915,97 @@ impl Default for ModelConfig {
             model_path: "ggml-base.en".to_string(), // Model ID, not full filename
             device: "auto".to_string(),
             preload: true,
+            language: default_language(),
+            backend: default_backend(),
+            server: WhisperServerConfig::default(),
+            ensemble: EnsembleConfig::default(),
+            decoding: default_decoding(),
+            lm_path: None,
+            rescoring: RescoringConfig::default(),
+            chunking: ChunkingConfig::default(),
+            execution_provider: default_execution_provider(),
+            registry_url: None,
+        }
+    }
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_dbfs: -20.0,
+            max_gain_db: 30.0,
+            attack_ms: 10,
+            release_ms: 500,
+            limiter_dbfs: -1.0,
+        }
+    }
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            models: Vec::new(),
+            strategy: "confidence".to_string(),
+        }
+    }
+}
+
+impl Default for RescoringConfig {
+    fn default() -> Self {
+        Self {
+            beam_width: 1,
+            n_best: 5,
+            lm: "word-frequency".to_string(),
+            lm_path: None,
+            weight: 0.3,
+        }
+    }
+}
+
+impl Default for VocabularyConfig {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            fuzzy_replace: true,
+            fuzzy_threshold: 0.85,
+        }
+    }
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            errors: true,
+            permissions: true,
+            downloads: true,
+            transcriptions: false,
+            min_interval_secs: 30,
+        }
+    }
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_chunk_secs: 28,
+            overlap_ms: 1000,
+        }
+    }
+}
+
+impl Default for WhisperServerConfig {
+    fn default() -> Self {
+        Self {
+            binary: "whisper-server".to_string(),
+            port: 0,
+            startup_timeout_secs: 30,
+            request_timeout_secs: 120,
+            extra_args: Vec::new(),
+            auto_install: default_server_auto_install(),
         }
     }
 }
@@ -169,6 +1016,12 @@ impl Default for PostProcessingConfig {
             auto_punctuation: true,
             auto_capitalize: true,
             remove_filler_words: false,
+            mask_profanity: false,
+            filler_words: default_filler_words(),
+            profanity_words: Vec::new(),
+            stages: Vec::new(),
+            locale: default_locale(),
+            languages: HashMap::new(),
         }
     }
 }
@@ -178,7 +1031,14 @@ impl Default for InjectionConfig {
         Self {
             method: "accessibility".to_string(),
             paste_delay_ms: 50,
+            clipboard_restore_ms: default_clipboard_restore_ms(),
             focus_settle_ms: default_focus_settle_ms(),
+            undo_depth: default_undo_depth(),
+            refocus_timeout_secs: default_refocus_timeout_secs(),
+            secure_fields: default_secure_fields(),
+            min_confidence: 0.0,
+            low_confidence: default_low_confidence(),
+            quirks: Vec::new(),
         }
     }
 }
@@ -193,10 +1053,294 @@ impl Default for HistoryConfig {
     }
 }
 
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            announce: false,
+            verbosity: "normal".to_string(),
+        }
+    }
+}
+
+impl Default for ConsentConfig {
+    fn default() -> Self {
+        Self {
+            start_beep: false,
+            stop_beep: false,
+            reminder_interval_secs: 0,
+            volume: 0.3,
+            frequency_hz: 880.0,
+            duration_ms: 150,
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keep: 7,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            max_audio_mb: 2048,
+            max_logs_mb: 200,
+            max_models_mb: 0,
+            low_space_warning_mb: 1024,
+            cleanup_interval_minutes: 60,
+        }
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 365,
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+            token: String::new(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+fn default_api_port() -> u16 {
+    7863
+}
+
+impl Default for ScreenRecordingsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            globs: default_screen_recording_globs(),
+            format: default_sidecar_format(),
+            settle_secs: default_settle_secs(),
+        }
+    }
+}
+
+fn default_screen_recording_globs() -> Vec<String> {
+    let globs: &[&str] = if cfg!(target_os = "macos") {
+        &["~/Desktop/Screen Recording*.mov"]
+    } else if cfg!(target_os = "windows") {
+        &["~/Videos/Captures/*.mp4"]
+    } else {
+        &["~/Videos/Screencasts/*.webm", "~/Videos/Screencasts/*.mp4"]
+    };
+    globs.iter().map(|glob| glob.to_string()).collect()
+}
+
+fn default_sidecar_format() -> String {
+    "srt".to_string()
+}
+
+fn default_settle_secs() -> u64 {
+    3
+}
+
+fn default_filler_words() -> Vec<String> {
+    crate::postprocess::stages::DEFAULT_FILLER_WORDS
+        .iter()
+        .map(|word| word.to_string())
+        .collect()
+}
+
+fn default_max_ipc_connections() -> usize {
+    32
+}
+
+fn default_log_file() -> bool {
+    true
+}
+
+fn default_log_retention_days() -> u32 {
+    7
+}
+
+fn default_startup_policy() -> String {
+    "degrade".to_string()
+}
+
+fn default_startup_retry_secs() -> u64 {
+    5
+}
+
+fn default_clipboard_ring_size() -> usize {
+    10
+}
+
+fn default_commands_enabled() -> bool {
+    true
+}
+
+fn default_hotkey_backend() -> String {
+    "auto".to_string()
+}
+
+fn default_duplicate_window_ms() -> u64 {
+    2000
+}
+
+fn default_language() -> String {
+    "auto".to_string()
+}
+
+fn default_backend() -> String {
+    "auto".to_string()
+}
+
+fn default_boost() -> f32 {
+    1.0
+}
+
+fn default_decoding() -> String {
+    "greedy".to_string()
+}
+
+/// Beam width for `model.decoding = "beam"` (and vocabulary biasing) when
+/// `[model.rescoring]` doesn't set one
+pub const DEFAULT_BEAM_WIDTH: usize = 8;
+
+fn default_execution_provider() -> String {
+    "auto".to_string()
+}
+
 fn default_focus_settle_ms() -> u32 {
     80
 }
 
+fn default_undo_depth() -> usize {
+    10
+}
+
+fn default_refocus_timeout_secs() -> u64 {
+    30
+}
+
+fn default_clipboard_restore_ms() -> u64 {
+    300
+}
+
+fn default_secure_fields() -> String {
+    "confirm".to_string()
+}
+
+fn default_low_confidence() -> String {
+    "confirm".to_string()
+}
+
+fn default_locale() -> String {
+    "auto".to_string()
+}
+
+fn default_audio_source() -> String {
+    "microphone".to_string()
+}
+
+fn default_gain_hint_interval_mins() -> u64 {
+    60
+}
+
+fn default_recordings_max_files() -> usize {
+    1000
+}
+
+fn default_recordings_max_days() -> u64 {
+    30
+}
+
+fn default_recordings_max_mb() -> u64 {
+    500
+}
+
+fn default_hid_action() -> String {
+    "dictate".to_string()
+}
+
+fn default_server_auto_install() -> bool {
+    true
+}
+
+fn default_overlay_anchor() -> String {
+    "bottom-center".to_string()
+}
+
+fn default_overlay_offset_y() -> f32 {
+    20.0
+}
+
+fn default_overlay_scale() -> f32 {
+    1.0
+}
+
+fn default_overlay_background() -> String {
+    "#000000".to_string()
+}
+
+fn default_overlay_foreground() -> String {
+    "#FFFFFF".to_string()
+}
+
+fn default_overlay_opacity() -> f32 {
+    1.0
+}
+
+fn default_overlay_timer() -> bool {
+    true
+}
+
+fn default_feedback_volume() -> f32 {
+    0.3
+}
+
+fn default_feedback_cue() -> String {
+    "tone".to_string()
+}
+
+fn default_feedback_none() -> String {
+    "none".to_string()
+}
+
+fn default_hid_grab() -> bool {
+    true
+}
+
+fn default_adaptive_post_roll() -> bool {
+    true
+}
+
+fn default_min_post_roll_ms() -> u32 {
+    200
+}
+
+fn default_max_post_roll_ms() -> u32 {
+    1000
+}
+
+fn default_min_speech_ms() -> u32 {
+    0
+}
+
+fn default_max_spectral_flatness() -> f32 {
+    1.0
+}
+
+fn default_silero_threshold() -> f32 {
+    0.5
+}
+
 impl Config {
     /// Load configuration from file
     pub fn load(path: &PathBuf) -> crate::Result<Self> {
@@ -247,6 +1391,269 @@ impl Config {
     pub fn save_default(&self) -> crate::Result<()> {
         self.save(&Self::default_path())
     }
+
+    /// Value of a dotted key (e.g. `vad.threshold`) as written in the config
+    ///
+    /// `keyring:` references are returned as-is, not resolved.
+    pub fn get_key(&self, key: &str) -> crate::Result<toml::Value> {
+        let root = toml::Value::try_from(self)
+            .map_err(|e| crate::Error::Config(format!("Failed to serialize config: {}", e)))?;
+        lookup_key(&root, key)
+            .cloned()
+            .ok_or_else(|| crate::Error::Config(format!("Unknown or unset config key '{}'", key)))
+    }
+
+    /// Set a dotted key in the config file at `path`, keeping the file's
+    /// comments and formatting
+    ///
+    /// `value` is parsed as the key's current type (string, number,
+    /// boolean), or as a TOML literal for arrays and unset keys. The file is
+    /// only written if the result is a valid config. Returns the new config.
+    pub fn set_key(path: &Path, key: &str, value: &str) -> crate::Result<Self> {
+        let current = toml::Value::try_from(Self::load(&path.to_path_buf())?)
+            .map_err(|e| crate::Error::Config(format!("Failed to serialize config: {}", e)))?;
+        let expected =
+            |kind: &str| crate::Error::Config(format!("{} expects {}, got '{}'", key, kind, value));
+        let parsed = match lookup_key(&current, key) {
+            Some(toml::Value::String(_)) => toml::Value::String(value.to_string()),
+            Some(toml::Value::Integer(_)) => {
+                toml::Value::Integer(value.parse().map_err(|_| expected("an integer"))?)
+            }
+            Some(toml::Value::Float(_)) => {
+                toml::Value::Float(value.parse().map_err(|_| expected("a number"))?)
+            }
+            Some(toml::Value::Boolean(_)) => {
+                toml::Value::Boolean(value.parse().map_err(|_| expected("true or false"))?)
+            }
+            Some(toml::Value::Table(_)) => {
+                return Err(crate::Error::Config(format!(
+                    "'{}' is a section; set one of its keys instead",
+                    key
+                )));
+            }
+            _ => parse_literal(value),
+        };
+
+        let contents = if path.exists() {
+            fs::read_to_string(path)
+                .map_err(|e| crate::Error::Config(format!("Failed to read config: {}", e)))?
+        } else {
+            toml::to_string_pretty(&Self::default())
+                .map_err(|e| crate::Error::Config(format!("Failed to serialize config: {}", e)))?
+        };
+        let mut document: toml_edit::DocumentMut = contents
+            .parse()
+            .map_err(|e| crate::Error::Config(format!("Failed to parse config: {}", e)))?;
+
+        let (sections, leaf) = match key.rsplit_once('.') {
+            Some((sections, leaf)) => (sections.split('.').collect(), leaf),
+            None => (Vec::new(), key),
+        };
+        let mut table: &mut dyn toml_edit::TableLike = document.as_table_mut();
+        for section in sections {
+            table = table
+                .entry(section)
+                .or_insert(toml_edit::table())
+                .as_table_like_mut()
+                .ok_or_else(|| crate::Error::Config(format!("'{}' is not a section", section)))?;
+        }
+
+        let mut new_value: toml_edit::Value = parsed
+            .to_string()
+            .parse()
+            .map_err(|e| crate::Error::Config(format!("Invalid value '{}': {}", value, e)))?;
+        match table.get_mut(leaf).and_then(|item| item.as_value_mut()) {
+            Some(old) => {
+                // Keep the key's trailing comment
+                *new_value.decor_mut() = old.decor().clone();
+                *old = new_value;
+            }
+            None => {
+                table.insert(leaf, toml_edit::Item::Value(new_value));
+            }
+        }
+
+        let contents = document.to_string();
+        let updated: Config = toml::from_str(&contents)
+            .map_err(|e| crate::Error::Config(format!("Invalid value for {}: {}", key, e)))?;
+        let check = toml::Value::try_from(&updated)
+            .map_err(|e| crate::Error::Config(format!("Failed to serialize config: {}", e)))?;
+        if lookup_key(&check, key).is_none() {
+            return Err(crate::Error::Config(format!(
+                "Unknown config key '{}'",
+                key
+            )));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| crate::Error::Config(format!("Failed to create config dir: {}", e)))?;
+        }
+        fs::write(path, contents)
+            .map_err(|e| crate::Error::Config(format!("Failed to write config: {}", e)))?;
+        Ok(updated)
+    }
+
+    /// Every configured hotkey combo, named by its config key
+    /// (`hotkey.trigger`, `hotkey.undo`, `hotkeys.<action>`), for conflict
+    /// checks
+    pub fn hotkey_bindings(&self) -> Vec<(String, String)> {
+        let mut bindings = vec![("hotkey.trigger".to_string(), self.hotkey.trigger.clone())];
+        for (name, combo) in [
+            ("hotkey.undo", &self.hotkey.undo),
+            ("hotkey.picker", &self.hotkey.picker),
+        ] {
+            if let Some(combo) = combo {
+                bindings.push((name.to_string(), combo.clone()));
+            }
+        }
+        bindings.extend(
+            self.hotkeys
+                .iter()
+                .map(|(action, combo)| (format!("hotkeys.{}", action), combo.clone())),
+        );
+        bindings
+    }
+
+    /// First profile matching the focused app and input source
+    pub fn matching_profile(
+        &self,
+        app: Option<&str>,
+        input_source: Option<&str>,
+    ) -> Option<&ProfileConfig> {
+        self.profiles.iter().find(|p| p.matches(app, input_source))
+    }
+
+    /// Decoding language for the focused app and input source
+    ///
+    /// Falls back to `model.language` when no profile matches.
+    pub fn language_for(&self, app: Option<&str>, input_source: Option<&str>) -> Option<&str> {
+        match self.matching_profile(app, input_source) {
+            Some(profile) => language_code(&profile.language),
+            None => self.model.language(),
+        }
+    }
+}
+
+/// Follow a dotted key through nested tables
+fn lookup_key<'a>(root: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.').try_fold(root, |value, part| value.get(part))
+}
+
+/// A TOML literal (`[1, 2]`, `42`, `true`), or a plain string
+fn parse_literal(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+impl AudioConfig {
+    /// `source` parsed, falling back to the microphone
+    pub fn capture_source(&self) -> crate::audio::capture::CaptureSource {
+        crate::audio::capture::CaptureSource::parse(&self.source).unwrap_or_else(|e| {
+            tracing::warn!("{}; capturing from the microphone", e);
+            crate::audio::capture::CaptureSource::Microphone
+        })
+    }
+}
+
+impl ModelConfig {
+    /// Default decoding language, `None` for auto-detection
+    pub fn language(&self) -> Option<&str> {
+        language_code(&self.language)
+    }
+
+    /// Beam search and language model settings after applying `decoding`
+    /// and `lm_path` to `[model.rescoring]`
+    pub fn decoding_config(&self) -> crate::Result<RescoringConfig> {
+        let mut rescoring = self.rescoring.clone();
+        match self.decoding.to_ascii_lowercase().as_str() {
+            // A beam set in [model.rescoring] still applies
+            "greedy" => {}
+            "beam" => {
+                if rescoring.beam_width <= 1 {
+                    rescoring.beam_width = DEFAULT_BEAM_WIDTH;
+                }
+            }
+            other => {
+                return Err(crate::Error::Config(format!(
+                    "Unknown model.decoding '{}' (expected greedy or beam)",
+                    other
+                )));
+            }
+        }
+        if let Some(lm_path) = &self.lm_path {
+            rescoring.lm = "arpa".to_string();
+            rescoring.lm_path = Some(lm_path.clone());
+        }
+        Ok(rescoring)
+    }
+
+    /// Whether decoding uses a beam search
+    pub fn beam_search(&self) -> bool {
+        self.decoding_config()
+            .is_ok_and(|rescoring| rescoring.beam_width > 1)
+    }
+}
+
+impl ProfileConfig {
+    /// Whether this profile applies to the focused app and input source
+    ///
+    /// Every criterion that is set must match; a profile with no criteria
+    /// never matches.
+    pub fn matches(&self, app: Option<&str>, input_source: Option<&str>) -> bool {
+        fn contains(pattern: &Option<String>, value: Option<&str>) -> Option<bool> {
+            let pattern = pattern.as_deref()?.to_lowercase();
+            Some(value.is_some_and(|v| v.to_lowercase().contains(&pattern)))
+        }
+
+        match (
+            contains(&self.app, app),
+            contains(&self.input_source, input_source),
+        ) {
+            (None, None) => false,
+            (app, source) => app.unwrap_or(true) && source.unwrap_or(true),
+        }
+    }
+}
+
+/// Normalize a configured language ("auto" or empty means detect)
+fn language_code(language: &str) -> Option<&str> {
+    let language = language.trim();
+    if language.is_empty() || language.eq_ignore_ascii_case("auto") {
+        None
+    } else {
+        Some(language)
+    }
+}
+
+impl ConsentConfig {
+    /// Tone played when recording starts
+    pub fn start_tone(&self) -> crate::audio::Tone {
+        crate::audio::Tone {
+            frequency_hz: self.frequency_hz,
+            duration_ms: self.duration_ms,
+            volume: self.volume,
+        }
+    }
+
+    /// Tone played when recording stops (a fourth lower than the start tone)
+    pub fn stop_tone(&self) -> crate::audio::Tone {
+        crate::audio::Tone {
+            frequency_hz: self.frequency_hz * 0.75,
+            ..self.start_tone()
+        }
+    }
+
+    /// Periodic reminder tone (shorter and quieter than the start tone)
+    pub fn reminder_tone(&self) -> crate::audio::Tone {
+        crate::audio::Tone {
+            duration_ms: (self.duration_ms / 2).max(30),
+            volume: self.volume * 0.6,
+            ..self.start_tone()
+        }
+    }
 }
 
 impl VadConfig {
@@ -258,6 +1665,8 @@ impl VadConfig {
             min_silence_chunks: self.min_silence_chunks,
             adaptive: self.adaptive,
             adaptive_window_size: 30,
+            noise_floor: self.noise_floor,
+            track_noise_floor: self.track_noise_floor,
         }
     }
 
@@ -266,6 +1675,76 @@ impl VadConfig {
         crate::vad::VadProcessorConfig {
             pre_roll_ms: self.pre_roll_ms,
             post_roll_ms: self.post_roll_ms,
+            adaptive_post_roll: self.adaptive_post_roll,
+            min_post_roll_ms: self.min_post_roll_ms,
+            max_post_roll_ms: self.max_post_roll_ms,
+            min_speech_ms: self.min_speech_ms,
+            max_spectral_flatness: self.max_spectral_flatness,
+            auto_stop_silence_ms: self.auto_stop_silence_ms,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_get_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut contents = toml::to_string_pretty(&Config::default()).unwrap();
+        contents = contents.replace("[vad]\n", "[vad]\n# tuned for my office\n");
+        fs::write(&path, contents).unwrap();
+
+        let config = Config::set_key(&path, "vad.threshold", "0.05").unwrap();
+        assert_eq!(config.vad.threshold, 0.05);
+        let config = Config::set_key(&path, "hotkey.trigger", "Ctrl+Alt+D").unwrap();
+        assert_eq!(config.hotkey.trigger, "Ctrl+Alt+D");
+        let config = Config::set_key(&path, "hotkey.undo", "Ctrl+Shift+Z").unwrap();
+        assert_eq!(config.hotkey.undo.as_deref(), Some("Ctrl+Shift+Z"));
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("# tuned for my office"));
+        assert_eq!(
+            Config::load(&path)
+                .unwrap()
+                .get_key("hotkey.trigger")
+                .unwrap(),
+            toml::Value::String("Ctrl+Alt+D".to_string())
+        );
+
+        assert!(Config::set_key(&path, "vad.threshold", "loud").is_err());
+        assert!(Config::set_key(&path, "vad.enabled", "yes").is_err());
+        assert!(Config::set_key(&path, "vad.treshold", "0.1").is_err());
+        assert!(Config::set_key(&path, "vad", "1").is_err());
+        assert!(Config::default().get_key("audio.missing").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn test_partial_tables_use_defaults() {
+        let telemetry: TelemetryConfig = toml::from_str("enabled = true").unwrap();
+        assert!(telemetry.enabled);
+        assert_eq!(
+            telemetry.retention_days,
+            TelemetryConfig::default().retention_days
+        );
+
+        let accessibility: AccessibilityConfig = toml::from_str("announce = true").unwrap();
+        assert_eq!(
+            accessibility.verbosity,
+            AccessibilityConfig::default().verbosity
+        );
+        let consent: ConsentConfig = toml::from_str("start_beep = true").unwrap();
+        assert_eq!(consent.duration_ms, ConsentConfig::default().duration_ms);
+        let backup: BackupConfig = toml::from_str("keep = 3").unwrap();
+        assert_eq!(backup.enabled, BackupConfig::default().enabled);
+        let storage: StorageConfig = toml::from_str("max_logs_mb = 10").unwrap();
+        assert_eq!(storage.max_audio_mb, StorageConfig::default().max_audio_mb);
+        let server: WhisperServerConfig = toml::from_str("port = 8178").unwrap();
+        assert_eq!(server.port, 8178);
+        assert_eq!(server.binary, WhisperServerConfig::default().binary);
+        assert!(server.extra_args.is_empty());
+    }
+}
@@ -2,12 +2,25 @@
 //!
 //! Main daemon process and lifecycle management.
 
+pub mod detach;
 pub mod dictation;
+pub mod downloads;
+pub mod duplicates;
+pub mod events;
+#[cfg(feature = "http-api")]
+pub mod http_api;
 pub mod lifecycle;
+pub mod metrics;
+pub mod privacy;
+pub mod recovery;
+pub mod screen_recordings;
+pub mod startup;
 pub mod state;
+pub mod upgrade;
 
 // Re-export commonly used types
 pub use dictation::DictationEngine;
+pub use events::EventBus;
 pub use lifecycle::Lifecycle;
 pub use state::DaemonState;
 
@@ -0,0 +1,211 @@
+//! Background Daemon
+//!
+//! `onevox daemon` without `--foreground` starts the daemon as a detached
+//! copy of itself: `onevox daemon --foreground` in a new session with no
+//! controlling terminal (Unix), or a detached process with no console
+//! (Windows). Its stdin is closed; it logs to the daemon log file only,
+//! and anything else it prints (such as a panic) is appended to
+//! `onevox-stderr.log` in the log directory. The command returns once the
+//! daemon answers an IPC ping; if the daemon exits first or stays silent,
+//! the error carries the end of its output and log. The Tokio runtime is already running
+//! by the time the command is parsed, so the daemon is spawned fresh rather
+//! than forked.
+
+use crate::daemon::lifecycle;
+use crate::ipc::IpcClient;
+use crate::logging::LogRecord;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Output of a background daemon outside its log, in the log directory
+pub const OUTPUT_FILE: &str = "onevox-stderr.log";
+
+/// How long the daemon gets to answer its first ping
+pub const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Log lines shown when the daemon fails to start
+const TAIL_LINES: usize = 10;
+
+/// Path of the background daemon's output
+pub fn output_path() -> crate::Result<PathBuf> {
+    Ok(crate::platform::paths::log_dir()?.join(OUTPUT_FILE))
+}
+
+/// Length of a file, 0 if it doesn't exist
+fn file_len(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+/// Start the daemon in the background and wait until it answers
+///
+/// Returns the daemon's PID.
+pub async fn spawn(dev: bool) -> crate::Result<u32> {
+    let output_path = output_path()?;
+    let output = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&output_path)?;
+    // Only this start's lines are shown on failure
+    let output_start = file_len(&output_path);
+    let log_file = crate::logging::latest_log_file();
+    let log_start = log_file.as_deref().map_or(0, file_len);
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command.args(["daemon", "--foreground"]);
+    if dev {
+        command.arg("--dev");
+    }
+    if let Some(dir) = crate::platform::paths::portable_dir() {
+        command.env(crate::platform::paths::PORTABLE_DIR_ENV, dir);
+    }
+    if crate::models::inference_debug::enabled() {
+        command.env(crate::models::inference_debug::DEBUG_INFERENCE_ENV, "1");
+    }
+    command
+        .env(crate::logging::FILE_ONLY_ENV, "1")
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(output.try_clone()?)
+        .stderr(output);
+    detach(&mut command);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| crate::Error::Other(format!("Failed to start daemon: {}", e)))?;
+    info!("Spawned daemon (pid {})", child.id());
+
+    let failed = |reason: String| {
+        let mut text = read_from(&output_path, output_start);
+        // The log may have rolled over to a new file meanwhile
+        if let Some(latest) = crate::logging::latest_log_file() {
+            let offset = if Some(&latest) == log_file.as_ref() {
+                log_start
+            } else {
+                0
+            };
+            text.push('\n');
+            text.push_str(&read_from(&latest, offset));
+        }
+        let tail = last_lines(&text, TAIL_LINES);
+        let details = if tail.is_empty() {
+            String::new()
+        } else {
+            format!("\n{}", tail)
+        };
+        crate::Error::Other(format!(
+            "{} (output: {}){}",
+            reason,
+            output_path.display(),
+            details
+        ))
+    };
+
+    let deadline = Instant::now() + READY_TIMEOUT;
+    let mut client = IpcClient::default().with_client_name("onevox-cli");
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Err(failed(format!("Daemon exited during startup ({})", status)));
+        }
+        if client.ping().await.unwrap_or(false) {
+            return Ok(child.id());
+        }
+        if Instant::now() >= deadline {
+            return Err(failed(format!(
+                "Daemon (pid {}) did not answer within {}s",
+                child.id(),
+                READY_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Whether a daemon already answers over IPC
+///
+/// Also removes a PID file left behind by a daemon that is gone.
+pub async fn is_running() -> bool {
+    lifecycle::check_pid_file();
+    IpcClient::default()
+        .with_client_name("onevox-cli")
+        .ping()
+        .await
+        .unwrap_or(false)
+}
+
+/// Start the child in its own session so it outlives the terminal
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: setsid is async-signal-safe and runs in the child before exec
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Start the child without a console, outside the terminal's process group
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detach(_command: &mut Command) {}
+
+/// Text written to a file since `offset`, with log records as text
+fn read_from(path: &Path, offset: u64) -> String {
+    let mut bytes = Vec::new();
+    if let Ok(mut file) = std::fs::File::open(path)
+        && file.seek(SeekFrom::Start(offset)).is_ok()
+    {
+        let _ = file.read_to_end(&mut bytes);
+    }
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(|line| LogRecord::parse(line).map_or_else(|| line.to_string(), |r| r.to_text()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The last `count` non-empty lines of `text`
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_shows_end_of_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OUTPUT_FILE);
+        std::fs::write(&path, "old run\n").unwrap();
+        let offset = file_len(&path);
+        let lines: String = (1..=15).map(|i| format!("line {}\n\n", i)).collect();
+        let record = r#"{"timestamp":"2026-01-02T14:03:12.512Z","level":"ERROR","fields":{"message":"Daemon is already running"},"target":"onevox::daemon::lifecycle"}"#;
+        std::fs::write(&path, format!("old run\n{}{}\n", lines, record)).unwrap();
+
+        let tail = last_lines(&read_from(&path, offset), TAIL_LINES);
+        assert_eq!(tail.lines().count(), TAIL_LINES);
+        assert!(tail.starts_with("line 7"));
+        assert!(tail.ends_with("ERROR onevox::daemon::lifecycle: Daemon is already running"));
+        assert_eq!(read_from(&dir.path().join("missing.log"), 0), "");
+    }
+}
@@ -3,24 +3,52 @@
 //! Orchestrates the full dictation pipeline:
 //! Hotkey → Audio Capture → VAD → Model → Text Injection
 
-use crate::audio::{AudioEngine, CaptureConfig};
+use crate::audio::gain_hint::GainHints;
+use crate::audio::{AudioEngine, CaptureConfig, Denoiser, GainMeter, TonePlayer};
 use crate::config::Config;
-use crate::history::{HistoryEntry, HistoryManager};
+use crate::daemon::duplicates::DuplicateGuard;
+use crate::daemon::events::EventBus;
+use crate::daemon::metrics::{self, PipelineMetrics};
+use crate::daemon::privacy::{self, Privacy};
+use crate::daemon::recovery::{HeldSlot, InjectionDelivery};
+use crate::daemon::startup::{self, Attempt, StartupTracker};
+use crate::history::{ClipboardRing, HistoryEntry, HistoryManager};
 use crate::indicator::RecordingIndicator;
-use crate::models::{ModelConfig, ModelRuntime, Transcription, WhisperCpp};
-
-#[cfg(feature = "onnx")]
-use crate::models::OnnxRuntime;
+use crate::ipc::Event;
+use crate::ipc::protocol::LastTranscript;
+use crate::models::{ModelRuntime, Transcription, backend};
+use crate::platform::hotkey::{self, evdev_listener};
 use crate::platform::{
-    HotkeyConfig as PlatformHotkeyConfig, HotkeyEvent, HotkeyManager, InjectorConfig, TextInjector,
+    DictationMode, FocusContext, HotkeyAction, HotkeyBackend, HotkeyConfig as PlatformHotkeyConfig,
+    HotkeyEvent, HotkeyManager, InjectorConfig, Permission, PermissionStatus, TextInjector,
+    UndoStack, dnd, focus, hid, notify, undo,
 };
-use crate::vad::{EnergyVad, VadDetector, VadProcessor};
+use crate::postprocess::{Interpreted, PostProcessor, VoiceCommands};
+use crate::vad::VadProcessor;
+use crate::vocabulary::Vocabulary;
 use anyhow::{Context, Result};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+/// Segment audio of the last transcript, in the recordings dir
+const LAST_TRANSCRIPT_AUDIO: &str = "last-transcript.wav";
+
+/// What happened to a transcribed segment, noted on its history entries
+struct SegmentNotes {
+    /// Mic gain or placement hint raised for the recording
+    audio_hint: Option<String>,
+    /// Near-duplicate of the previous segment, not injected
+    duplicate: bool,
+    /// Why the text wasn't typed
+    not_injected: Option<String>,
+    /// Length of the segment's audio
+    audio_ms: u64,
+    /// Session the segment was dictated in
+    session_id: u64,
+}
+
 /// Dictation engine state
 pub struct DictationEngine {
     /// Configuration
@@ -44,14 +72,74 @@ pub struct DictationEngine {
     /// Is currently dictating
     is_dictating: Arc<AtomicBool>,
 
-    /// Toggle state (for toggle mode)
-    is_toggle_active: Arc<AtomicBool>,
+    /// Session paused: audio keeps flowing but is dropped
+    paused: Arc<AtomicBool>,
 
     /// Shutdown signal
     shutdown_signal: Arc<AtomicBool>,
 
     /// Floating UI indicator
     indicator: Arc<RecordingIndicator>,
+
+    /// Event bus (dictation, VAD, model, and injection events)
+    events: EventBus,
+
+    /// Consent beep / reminder tone player
+    tone_player: TonePlayer,
+
+    /// Recent injections that can be undone
+    undo_stack: Arc<parking_lot::Mutex<UndoStack>>,
+
+    /// Transcript kept out of a password field until `onevox confirm`
+    held_injection: HeldSlot,
+
+    /// Injects transcripts, holding them while their app lacks focus
+    delivery: InjectionDelivery,
+
+    /// Rate-limited mic gain/placement hints
+    gain_hints: Arc<GainHints>,
+
+    /// Locale formatting and other transcript stages
+    postprocessor: PostProcessor,
+
+    /// Spoken editing commands applied before injection (`[commands]`)
+    commands: VoiceCommands,
+
+    /// Recent transcripts for the picker (`[clipboard_ring]`)
+    clipboard_ring: Option<Arc<parking_lot::Mutex<ClipboardRing>>>,
+
+    /// Do Not Disturb held for the current session (`dictation.auto_dnd`)
+    dnd: Option<tokio::task::JoinHandle<Option<dnd::DndGuard>>>,
+
+    /// Configuration reloads pushed by the daemon
+    config_updates: Option<tokio::sync::watch::Receiver<Config>>,
+
+    /// Most recent finalized transcript (served over IPC)
+    last_transcript: Arc<parking_lot::Mutex<Option<LastTranscript>>>,
+
+    /// Subsystem health and `[startup]` policies
+    startup: StartupTracker,
+
+    /// Keyboard hotkeys to register again while degraded: the event sender
+    /// and when to try next
+    hotkey_retry: Option<(mpsc::UnboundedSender<HotkeyEvent>, std::time::Instant)>,
+
+    /// Binding that started the current (or last) session; only it stops
+    /// the session
+    session: HotkeyAction,
+
+    /// Latency and throughput counters (shared with the daemon status)
+    metrics: PipelineMetrics,
+
+    /// Privacy pause, shared with the daemon (`onevox privacy pause`)
+    privacy: Privacy,
+
+    /// Set by the session's VAD after `vad.auto_stop_silence_ms` of silence
+    auto_stop: Arc<AtomicBool>,
+
+    /// The `audio.pre_buffer_ms` capture failed to start; not retried
+    /// until the configuration changes
+    pre_buffer_failed: bool,
 }
 
 impl DictationEngine {
@@ -61,82 +149,107 @@ impl DictationEngine {
 
         // Create history manager
         let history_config = config.history.clone();
+        let recordings = crate::history::Recordings::from_config(&config.audio)
+            .map_err(|e| anyhow::anyhow!("Failed to set up recordings: {}", e))?;
         let history_manager = HistoryManager::new(history_config)
-            .map_err(|e| anyhow::anyhow!("Failed to create history manager: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to create history manager: {}", e))?
+            .with_recordings(recordings);
 
         Self::with_history(config, Arc::new(history_manager))
     }
 
     /// Create a new dictation engine with an existing history manager
     pub fn with_history(config: Config, history_manager: Arc<HistoryManager>) -> Result<Self> {
+        Self::with_events(config, history_manager, EventBus::default())
+    }
+
+    /// Create a new dictation engine publishing to a shared event bus
+    pub fn with_events(
+        config: Config,
+        history_manager: Arc<HistoryManager>,
+        events: EventBus,
+    ) -> Result<Self> {
         info!("Initializing dictation engine");
 
         // Create hotkey manager. If this fails (common on some Wayland setups),
         // keep the engine available for manual IPC start/stop dictation commands.
-        let hotkey_manager = match HotkeyManager::new() {
-            Ok(manager) => Some(manager),
-            Err(e) => {
-                warn!(
-                    "Global hotkeys unavailable ({}). Manual IPC commands will still work.",
-                    e
-                );
-                None
-            }
+        // The evdev backend reads keyboards itself and doesn't need one.
+        let hotkey_manager = match HotkeyBackend::from_config(&config.hotkey.backend) {
+            HotkeyBackend::Evdev => None,
+            _ => match HotkeyManager::new() {
+                Ok(manager) => Some(manager),
+                Err(e) => {
+                    warn!(
+                        "Global hotkeys unavailable ({}). Manual IPC commands will still work.",
+                        e
+                    );
+                    None
+                }
+            },
         };
 
         // Create text injector
-        let injector_config = InjectorConfig {
-            key_delay_ms: config.injection.paste_delay_ms as u64,
-            initial_delay_ms: 50,
-        };
-        let text_injector = TextInjector::new(injector_config);
+        let text_injector = TextInjector::new(InjectorConfig::from_config(&config.injection));
 
         // Create audio engine
         let audio_engine = AudioEngine::new();
 
-        // Auto-detect backend from model path
-        let model_path = &config.model.model_path;
-        let is_onnx_model = model_path.contains("parakeet")
-            || model_path.ends_with(".onnx")
-            || model_path.contains("onnx");
-
-        let mut model: Box<dyn ModelRuntime> = if is_onnx_model {
-            #[cfg(feature = "onnx")]
-            {
-                info!("Auto-detected ONNX model from path: {}", model_path);
-                info!("Using ONNX Runtime backend");
-                Box::new(OnnxRuntime::new()?)
-            }
-            #[cfg(not(feature = "onnx"))]
-            {
-                error!(
-                    "ONNX model detected ('{}') but feature not enabled. Rebuild with --features onnx",
-                    model_path
-                );
-                return Err(crate::Error::Model(format!(
-                    "ONNX model requires --features onnx build. Model: {}",
-                    model_path
-                ))
-                .into());
-            }
-        } else {
-            // Default to whisper.cpp for GGML models
-            info!("Auto-detected GGML model from path: {}", model_path);
-            info!("Using whisper.cpp backend");
-            Box::new(WhisperCpp::new()?)
-        };
-
-        let model_config = ModelConfig {
-            model_path: config.model.model_path.clone(),
-            use_gpu: config.model.device == "gpu" || config.model.device == "auto",
-            ..Default::default()
-        };
-        model.load(model_config)?;
+        // Explicit backend, or auto-detect from model path
+        let mut model = backend::create_runtime(&config.model)?;
+        let model_config = backend::runtime_config(&config.model);
+        events.publish(Event::ModelLoading {
+            name: config.model.model_path.clone(),
+        });
+        let memory_before = metrics::process_memory();
+        if let Err(e) = model.load(model_config) {
+            events.publish(Event::Error {
+                message: format!("Failed to load model: {}", e),
+            });
+            return Err(e.into());
+        }
+        events.publish(Event::ModelLoaded {
+            name: config.model.model_path.clone(),
+        });
+        let vocabulary = Vocabulary::from_config(&config.vocabulary);
+        model.set_vocabulary(&vocabulary);
+        let metrics = PipelineMetrics::default();
+        metrics.set_model_memory(metrics::process_memory().saturating_sub(memory_before));
 
         info!("✅ Dictation engine initialized");
 
+        let undo_stack = Arc::new(parking_lot::Mutex::new(UndoStack::new(
+            config.injection.undo_depth,
+        )));
+        let held_injection = HeldSlot::default();
+        let clipboard_ring = Self::load_clipboard_ring(&config);
+        let delivery = InjectionDelivery::new(
+            text_injector.clone(),
+            Arc::clone(&undo_stack),
+            Arc::clone(&held_injection),
+            events.clone(),
+            &config.injection,
+        )
+        .with_clipboard_ring(clipboard_ring.clone());
+
         Ok(Self {
-            indicator: Arc::new(RecordingIndicator::new(config.ui.recording_overlay)),
+            indicator: Arc::new(RecordingIndicator::new(&config.ui)),
+            events,
+            tone_player: TonePlayer::new(),
+            undo_stack,
+            held_injection,
+            delivery,
+            gain_hints: Arc::new(GainHints::new(config.audio.gain_hint_interval_mins)),
+            dnd: None,
+            config_updates: None,
+            last_transcript: Arc::new(parking_lot::Mutex::new(None)),
+            postprocessor: PostProcessor::from_config(&config.post_processing)
+                .with_vocabulary(vocabulary),
+            commands: VoiceCommands::from_config(
+                &config.commands,
+                config.post_processing.auto_capitalize,
+            ),
+            clipboard_ring,
+            startup: StartupTracker::new(&config.startup),
             config,
             hotkey_manager,
             text_injector,
@@ -144,8 +257,160 @@ impl DictationEngine {
             model: Arc::new(Mutex::new(model)),
             history_manager,
             is_dictating: Arc::new(AtomicBool::new(false)),
-            is_toggle_active: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
             shutdown_signal: Arc::new(AtomicBool::new(false)),
+            hotkey_retry: None,
+            session: HotkeyAction::Dictate,
+            metrics,
+            privacy: Privacy::default(),
+            auto_stop: Arc::new(AtomicBool::new(false)),
+            pre_buffer_failed: false,
+        })
+    }
+
+    /// Record pipeline metrics into a shared set (the daemon's, for
+    /// `onevox status`)
+    pub fn with_metrics(mut self, metrics: PipelineMetrics) -> Self {
+        if let Some(bytes) = self.metrics.snapshot().model_memory_bytes {
+            metrics.set_model_memory(bytes);
+        }
+        self.metrics = metrics;
+        self
+    }
+
+    /// Follow a shared privacy pause (the daemon's)
+    pub fn with_privacy(mut self, privacy: Privacy) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Share an undo stack (e.g. the daemon's, so `onevox undo` sees
+    /// hotkey dictations)
+    pub fn with_undo_stack(mut self, undo_stack: Arc<parking_lot::Mutex<UndoStack>>) -> Self {
+        self.undo_stack = undo_stack;
+        self.delivery = self.new_delivery();
+        self
+    }
+
+    /// Share the daemon's slot for transcripts awaiting `onevox confirm`
+    pub fn with_held_injection(mut self, held_injection: HeldSlot) -> Self {
+        self.held_injection = held_injection;
+        self.delivery = self.new_delivery();
+        self
+    }
+
+    /// Share the daemon's last-transcript slot
+    pub fn with_last_transcript(
+        mut self,
+        last_transcript: Arc<parking_lot::Mutex<Option<LastTranscript>>>,
+    ) -> Self {
+        self.last_transcript = last_transcript;
+        self
+    }
+
+    /// Report subsystem health to the daemon's tracker
+    pub fn with_startup(mut self, startup: StartupTracker) -> Self {
+        self.startup = startup;
+        self
+    }
+
+    /// Follow configuration reloads (`onevox reload-config`, `onevox config set`)
+    pub fn with_config_updates(mut self, updates: tokio::sync::watch::Receiver<Config>) -> Self {
+        self.config_updates = Some(updates);
+        self
+    }
+
+    /// Move a session in progress to the input device of the latest
+    /// configuration (`onevox devices use`); other settings wait for the
+    /// session to end
+    pub fn follow_device(&mut self) {
+        if !self.is_dictating() {
+            return;
+        }
+        if let Some(updates) = &self.config_updates {
+            let device = updates.borrow().audio.device.clone();
+            self.audio_engine.set_device(&device);
+        }
+    }
+
+    /// Apply a reloaded configuration, if one arrived since the last check
+    ///
+    /// Takes effect from the next dictation. Model and hotkey changes need a
+    /// daemon restart.
+    pub fn refresh_config(&mut self) {
+        // Keep the running session's settings; the update waits until it ends
+        if self.is_dictating() {
+            return;
+        }
+        let Some(updates) = self.config_updates.as_mut() else {
+            return;
+        };
+        if !updates.has_changed().unwrap_or(false) {
+            return;
+        }
+        let config = updates.borrow_and_update().clone();
+
+        if config.model.model_path != self.config.model.model_path
+            || config.model.backend != self.config.model.backend
+        {
+            warn!("Model changes apply after restarting the daemon");
+        }
+        if config.hotkey.trigger != self.config.hotkey.trigger
+            || config.hotkey.undo != self.config.hotkey.undo
+            || config.hotkey.backend != self.config.hotkey.backend
+            || config.hotkeys != self.config.hotkeys
+        {
+            warn!("Hotkey changes apply after restarting the daemon");
+        }
+        let hands_free = |config: &Config| {
+            DictationMode::parse(&config.hotkey.mode).ok() == Some(DictationMode::HandsFree)
+        };
+        if hands_free(&config) != hands_free(&self.config) {
+            warn!("Switching to or from hands-free mode applies after restarting the daemon");
+        }
+
+        self.text_injector = TextInjector::new(InjectorConfig::from_config(&config.injection));
+        self.gain_hints = Arc::new(GainHints::new(config.audio.gain_hint_interval_mins));
+        let vocabulary = Vocabulary::from_config(&config.vocabulary);
+        if let Ok(mut model) = self.model.lock() {
+            model.set_vocabulary(&vocabulary);
+        }
+        self.postprocessor =
+            PostProcessor::from_config(&config.post_processing).with_vocabulary(vocabulary);
+        self.commands =
+            VoiceCommands::from_config(&config.commands, config.post_processing.auto_capitalize);
+        if config.clipboard_ring.enabled != self.config.clipboard_ring.enabled
+            || config.clipboard_ring.size != self.config.clipboard_ring.size
+        {
+            self.clipboard_ring = Self::load_clipboard_ring(&config);
+        }
+        self.config = config;
+        self.delivery = self.new_delivery();
+        // Restarted with the new settings on the next tick
+        if let Err(e) = self.audio_engine.stop_pre_buffer() {
+            warn!("Failed to stop the pre-buffer: {}", e);
+        }
+        self.pre_buffer_failed = false;
+        info!("🔄 Configuration reloaded");
+    }
+
+    /// Injection delivery for the current injector and settings
+    fn new_delivery(&self) -> InjectionDelivery {
+        InjectionDelivery::new(
+            self.text_injector.clone(),
+            Arc::clone(&self.undo_stack),
+            Arc::clone(&self.held_injection),
+            self.events.clone(),
+            &self.config.injection,
+        )
+        .with_clipboard_ring(self.clipboard_ring.clone())
+    }
+
+    /// Dictation mode from `hotkey.mode` (hold when unrecognized)
+    fn mode(&self) -> DictationMode {
+        DictationMode::parse(&self.config.hotkey.mode).unwrap_or_else(|e| {
+            warn!("{}; using hold", e);
+            DictationMode::Hold
         })
     }
 
@@ -156,6 +421,81 @@ impl DictationEngine {
         // List available audio devices for debugging
         self.list_audio_devices();
 
+        self.check_hotkeys();
+        self.report_permissions();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let hid_devices = self.start_hid_triggers(&event_tx);
+        let hands_free = self.mode() == DictationMode::HandsFree;
+
+        match self.start_keyboard_hotkeys(event_tx.clone()) {
+            Ok(()) => self.startup.ready(startup::HOTKEYS),
+            Err(e) => {
+                let attempt = self.startup.failed(startup::HOTKEYS, &format!("{:#}", e));
+                if attempt == Attempt::Exit {
+                    return Err(
+                        e.context("Hotkeys unavailable and startup.hotkeys = \"fail-fast\"")
+                    );
+                }
+                if hands_free {
+                    warn!("{:#}; hands-free listening can't be paused", e);
+                } else if hid_devices == 0 {
+                    warn!(
+                        "{:#}; dictation works over IPC only (onevox start-dictation / stop-dictation)",
+                        e
+                    );
+                } else {
+                    warn!("{:#}; using HID triggers only", e);
+                }
+                if attempt == Attempt::Retry {
+                    info!(
+                        "🔄 Retrying hotkeys every {}s",
+                        self.startup.retry_interval().as_secs()
+                    );
+                    self.hotkey_retry = Some((
+                        event_tx,
+                        std::time::Instant::now() + self.startup.retry_interval(),
+                    ));
+                }
+            }
+        }
+
+        if hands_free {
+            info!("👂 Hands-free mode - listening for speech (hotkey pauses/resumes)");
+            if let Err(e) = self.start_dictation().await {
+                error!("Failed to start hands-free listening: {}", e);
+            }
+        }
+
+        // Start hotkey event loop
+        self.run_event_loop(event_rx).await?;
+
+        Ok(())
+    }
+
+    /// Register the keyboard hotkeys and forward their events to `event_tx`
+    fn start_keyboard_hotkeys(
+        &mut self,
+        event_tx: mpsc::UnboundedSender<HotkeyEvent>,
+    ) -> Result<()> {
+        if HotkeyBackend::from_config(&self.config.hotkey.backend) == HotkeyBackend::Evdev {
+            return self.start_evdev_hotkeys(event_tx);
+        }
+
+        // Without Input Monitoring macOS registers the hotkeys but never
+        // delivers their events
+        #[cfg(target_os = "macos")]
+        if crate::platform::check_input_monitoring_permission() == PermissionStatus::Denied {
+            return Err(anyhow::anyhow!(
+                "Input Monitoring permission denied, so hotkeys can't be heard; {}",
+                Permission::InputMonitoring.fix_hint()
+            ));
+        }
+
+        let actions = self.action_hotkeys();
+        // A manager that failed to initialize may work on a retry
+        if self.hotkey_manager.is_none() {
+            self.hotkey_manager = HotkeyManager::new().ok();
+        }
         let hotkey_manager = self.hotkey_manager.as_mut().ok_or_else(|| {
             anyhow::anyhow!(
                 "Global hotkey backend unavailable on this system. Use 'onevox start-dictation' and 'onevox stop-dictation' (recommended for some Wayland environments)."
@@ -167,12 +507,21 @@ impl DictationEngine {
         let hotkey_config = PlatformHotkeyConfig::from_string(&hotkey_str)
             .context("Failed to parse hotkey configuration")?;
 
-        let event_rx = hotkey_manager
+        let mut hotkey_rx = hotkey_manager
             .register(hotkey_config)
             .context("Failed to register hotkey")?;
 
         info!("✅ Hotkey registered: {}", hotkey_str);
 
+        for (action_str, action) in actions {
+            let action_config = PlatformHotkeyConfig::from_string(&action_str)
+                .with_context(|| format!("Failed to parse {} hotkey configuration", action))?;
+            match hotkey_manager.register_action(action_config, action.event()) {
+                Ok(()) => info!("✅ {} hotkey registered: {}", action, action_str),
+                Err(e) => warn!("Failed to register {} hotkey {}: {}", action, action_str, e),
+            }
+        }
+
         // Take ownership of hotkey_manager to start the listener
         // (it consumes self and moves into the listener thread)
         let hotkey_manager = self
@@ -186,12 +535,164 @@ impl DictationEngine {
 
         info!("✅ Hotkey listener started");
 
-        // Start hotkey event loop
-        self.run_event_loop(event_rx).await?;
+        tokio::spawn(async move {
+            while let Some(event) = hotkey_rx.recv().await {
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(())
+    }
 
+    /// Read the hotkeys from `/dev/input` keyboards (works under Wayland)
+    fn start_evdev_hotkeys(&self, event_tx: mpsc::UnboundedSender<HotkeyEvent>) -> Result<()> {
+        let hotkey_str = &self.config.hotkey.trigger;
+        let trigger = PlatformHotkeyConfig::from_string(hotkey_str)
+            .context("Failed to parse hotkey configuration")?;
+        let actions = self
+            .action_hotkeys()
+            .into_iter()
+            .map(|(action_str, action)| {
+                let config = PlatformHotkeyConfig::from_string(&action_str)
+                    .with_context(|| format!("Failed to parse {} hotkey configuration", action))?;
+                Ok((config, action.event()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        evdev_listener::spawn_listeners(&trigger, &actions, event_tx)
+            .context("Failed to start evdev hotkeys")?;
+        info!("✅ Hotkey registered (evdev): {}", hotkey_str);
+        for (action_str, action) in self.action_hotkeys() {
+            info!("✅ {} hotkey registered (evdev): {}", action, action_str);
+        }
         Ok(())
     }
 
+    /// Register the keyboard hotkeys again if they're degraded and due a retry
+    fn retry_hotkeys(&mut self) {
+        let Some((event_tx, next)) = &self.hotkey_retry else {
+            return;
+        };
+        if std::time::Instant::now() < *next {
+            return;
+        }
+
+        let event_tx = event_tx.clone();
+        match self.start_keyboard_hotkeys(event_tx.clone()) {
+            Ok(()) => {
+                info!("✅ Hotkeys available again");
+                self.startup.ready(startup::HOTKEYS);
+                self.hotkey_retry = None;
+            }
+            Err(e) => match self.startup.failed(startup::HOTKEYS, &format!("{:#}", e)) {
+                Attempt::Retry => {
+                    debug!("Hotkeys still unavailable: {:#}", e);
+                    self.hotkey_retry = Some((
+                        event_tx,
+                        std::time::Instant::now() + self.startup.retry_interval(),
+                    ));
+                }
+                Attempt::GiveUp | Attempt::Exit => {
+                    error!("❌ Giving up on hotkeys: {:#}", e);
+                    self.hotkey_retry = None;
+                }
+            },
+        }
+    }
+
+    /// Configured extra hotkeys (undo, picker, `[hotkeys]`) and their
+    /// actions; unknown actions are left out
+    fn action_hotkeys(&self) -> Vec<(String, HotkeyAction)> {
+        let bindings = self.config.hotkeys.iter().filter_map(|(action, combo)| {
+            Some((Some(combo.clone()), HotkeyAction::parse(action).ok()?))
+        });
+        [
+            (self.config.hotkey.undo.clone(), HotkeyAction::Undo),
+            (self.config.hotkey.picker.clone(), HotkeyAction::Picker),
+        ]
+        .into_iter()
+        .chain(bindings)
+        .filter_map(|(hotkey, action)| Some((hotkey?, action)))
+        .collect()
+    }
+
+    /// Warn about denied permissions the daemon needs, publishing each so
+    /// subscribers and notifications can surface it
+    fn report_permissions(&self) {
+        let permissions = [
+            Permission::Accessibility,
+            Permission::Microphone,
+            #[cfg(target_os = "macos")]
+            Permission::InputMonitoring,
+        ];
+        for permission in permissions {
+            if crate::platform::check_permission(permission) != PermissionStatus::Denied {
+                continue;
+            }
+            let impact = match permission {
+                Permission::Accessibility => "transcripts can't be typed into apps",
+                Permission::Microphone => "nothing can be recorded",
+                _ => "hotkeys can't be heard",
+            };
+            let hint = permission.fix_hint();
+            warn!("⚠️  {} permission denied; {}. {}", permission, impact, hint);
+            self.events.publish(Event::PermissionDenied {
+                permission: permission.to_string(),
+                hint,
+            });
+        }
+    }
+
+    /// Report unknown `[hotkeys]` actions and combos bound more than once
+    fn check_hotkeys(&self) {
+        for action in self.config.hotkeys.keys() {
+            if let Err(e) = HotkeyAction::parse(action) {
+                warn!("Ignoring [hotkeys] binding: {}", e);
+            }
+        }
+        for conflict in hotkey::find_conflicts(&self.config.hotkey_bindings()) {
+            warn!(
+                "⚠️  Hotkey conflict: {}; only one of them will work",
+                conflict
+            );
+        }
+        if self.config.hotkeys.contains_key("command") && self.commands.is_empty() {
+            warn!("The command hotkey needs [commands] enabled; it won't do anything");
+        }
+    }
+
+    /// Listen to the configured foot pedal / macro pad buttons, returning the
+    /// number of devices opened
+    fn start_hid_triggers(&self, event_tx: &mpsc::UnboundedSender<HotkeyEvent>) -> usize {
+        if self.config.hotkey.hid.is_empty() {
+            return 0;
+        }
+
+        let triggers: Vec<hid::HidTrigger> = self
+            .config
+            .hotkey
+            .hid
+            .iter()
+            .filter_map(|trigger| match hid::HidTrigger::from_config(trigger) {
+                Ok(trigger) => Some(trigger),
+                Err(e) => {
+                    warn!("Ignoring HID trigger: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        match hid::spawn_listeners(triggers, event_tx.clone()) {
+            Ok(count) => count,
+            Err(e) => {
+                warn!("HID triggers unavailable: {}", e);
+                0
+            }
+        }
+    }
+
     /// Run the hotkey event loop
     async fn run_event_loop(
         &mut self,
@@ -206,6 +707,13 @@ impl DictationEngine {
                 }
                 _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
                     // Check shutdown signal periodically
+                    self.retry_hotkeys();
+                    self.enforce_privacy().await;
+                    self.enforce_auto_stop().await;
+                    self.enforce_overlay_stop().await;
+                    self.start_rerecord().await;
+                    self.follow_device();
+                    self.keep_pre_buffer();
                 }
             }
         }
@@ -216,68 +724,281 @@ impl DictationEngine {
 
     /// Handle hotkey event
     async fn handle_hotkey_event(&mut self, event: HotkeyEvent) {
-        let mode = &self.config.hotkey.mode;
-
-        if mode == "toggle" {
-            // Toggle mode: press once to start, press again to stop
-            if let HotkeyEvent::Pressed = event {
-                let is_active = self.is_toggle_active.load(Ordering::SeqCst);
-                if is_active {
-                    // Currently recording, stop it
-                    info!("🎹 Hotkey pressed (toggle mode) - stopping dictation");
-                    self.is_toggle_active.store(false, Ordering::SeqCst);
-                    if let Err(e) = self.stop_dictation().await {
-                        error!("Failed to stop dictation: {}", e);
-                    }
-                } else {
-                    // Not recording, start it
-                    info!("🎹 Hotkey pressed (toggle mode) - starting dictation");
-                    self.is_toggle_active.store(true, Ordering::SeqCst);
-                    if let Err(e) = self.start_dictation().await {
-                        error!("Failed to start dictation: {}", e);
-                    }
-                }
+        self.refresh_config();
+
+        if let HotkeyEvent::Picker = event {
+            info!("🎹 Picker hotkey pressed");
+            self.open_picker();
+            return;
+        }
+
+        if let HotkeyEvent::Pause = event {
+            info!("🎹 Pause hotkey pressed");
+            if let Err(e) = self.set_paused(!self.is_paused()) {
+                warn!("{}", e);
+            }
+            return;
+        }
+
+        if let HotkeyEvent::Undo = event {
+            info!("🎹 Undo hotkey pressed");
+            let undo_stack = Arc::clone(&self.undo_stack);
+            let injector = self.text_injector.clone();
+            match tokio::task::spawn_blocking(move || {
+                undo::undo_injections(&undo_stack, &injector, 1)
+            })
+            .await
+            {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => warn!("Undo failed: {}", e),
+                Err(e) => error!("Undo task failed: {}", e),
+            }
+            return;
+        }
+
+        let (action, event) = match event {
+            HotkeyEvent::ActionPressed(action) => (action, HotkeyEvent::Pressed),
+            HotkeyEvent::ActionReleased(action) => (action, HotkeyEvent::Released),
+            event => (HotkeyAction::Dictate, event),
+        };
+        let mut mode = self.mode();
+        let action = match action {
+            // Already hands-free: the binding pauses and resumes like the trigger
+            HotkeyAction::HandsFree if mode == DictationMode::HandsFree => HotkeyAction::Dictate,
+            HotkeyAction::HandsFree => {
+                mode = DictationMode::HandsFree;
+                action
             }
-            // Ignore Released events in toggle mode
+            action => action,
+        };
+        let dictating = self.is_dictating();
+        if dictating && action != self.session {
+            debug!(
+                "Ignoring {} hotkey during a {} session",
+                action, self.session
+            );
+            return;
+        }
+        let Some(start) = mode.next_state(event, dictating) else {
+            return;
+        };
+        if start == dictating {
+            return;
+        }
+
+        let verb = match (start, mode) {
+            (true, DictationMode::HandsFree) => "resuming listening",
+            (false, DictationMode::HandsFree) => "pausing listening",
+            (true, _) => "starting dictation",
+            (false, _) => "stopping dictation",
+        };
+        info!(
+            "🎹 {} hotkey {:?} ({} mode) - {}",
+            action, event, mode, verb
+        );
+        let result = if start {
+            self.start_session(action).await
         } else {
-            // Push-to-talk mode: hold to record
-            match event {
-                HotkeyEvent::Pressed => {
-                    info!("🎹 Hotkey pressed (push-to-talk mode) - starting dictation");
-                    if let Err(e) = self.start_dictation().await {
-                        error!("Failed to start dictation: {}", e);
-                    }
-                }
-                HotkeyEvent::Released => {
-                    info!("🎹 Hotkey released (push-to-talk mode) - stopping dictation");
-                    if let Err(e) = self.stop_dictation().await {
-                        error!("Failed to stop dictation: {}", e);
-                    }
-                }
+            self.stop_dictation().await
+        };
+        if let Err(e) = result {
+            error!("Failed {}: {}", verb, e);
+        }
+    }
+
+    /// Pause or resume the session in progress
+    ///
+    /// The microphone stays open and the model loaded; while paused, audio
+    /// is dropped before it reaches the VAD or the model.
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        if !self.is_dictating() {
+            anyhow::bail!(
+                "Not dictating; nothing to {}",
+                if paused { "pause" } else { "resume" }
+            );
+        }
+        if self.paused.swap(paused, Ordering::SeqCst) == paused {
+            return Ok(());
+        }
+        if paused {
+            info!("⏸️  Dictation paused");
+            self.indicator.paused();
+            self.events.publish(Event::DictationPaused);
+        } else {
+            info!("▶️  Dictation resumed");
+            self.indicator.recording();
+            self.events.publish(Event::DictationResumed);
+        }
+        Ok(())
+    }
+
+    /// Check if the session in progress is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// End the session in progress if capture was paused for privacy
+    pub async fn enforce_privacy(&mut self) {
+        if self.is_dictating() && self.privacy.is_paused() {
+            info!("🔒 Privacy pause - stopping dictation");
+            if let Err(e) = self.stop_dictation().await {
+                error!("Failed to stop dictation: {}", e);
+            }
+        }
+    }
+
+    /// End the session in progress after `vad.auto_stop_silence_ms` of
+    /// silence
+    pub async fn enforce_auto_stop(&mut self) {
+        if self.auto_stop.swap(false, Ordering::SeqCst) && self.is_dictating() {
+            info!(
+                "🤫 {}ms of silence - stopping dictation",
+                self.config.vad.auto_stop_silence_ms
+            );
+            if let Err(e) = self.stop_dictation().await {
+                error!("Failed to stop dictation: {}", e);
             }
         }
     }
 
+    /// End the session in progress when its overlay was clicked
+    /// (`ui.overlay.click_to_stop`)
+    pub async fn enforce_overlay_stop(&mut self) {
+        if self.is_dictating() && self.indicator.take_stop_request() {
+            info!("🖱️  Overlay clicked - stopping dictation");
+            if let Err(e) = self.stop_dictation().await {
+                error!("Failed to stop dictation: {}", e);
+            }
+        }
+    }
+
+    /// Dictate again when the user chose to re-record a reviewed transcript
+    pub async fn start_rerecord(&mut self) {
+        if self.delivery.take_rerecord() && !self.is_dictating() {
+            info!("🔁 Re-recording after review");
+            if let Err(e) = self.start_session(self.session).await {
+                error!("Failed to start dictation: {}", e);
+            }
+        }
+    }
+
+    /// Keep the `audio.pre_buffer_ms` capture running between sessions, so
+    /// a session starts with the audio from just before the hotkey
+    ///
+    /// Stopped while capture is paused for privacy. Hands-free mode listens
+    /// all along and needs none.
+    pub fn keep_pre_buffer(&mut self) {
+        if self.is_dictating() {
+            return;
+        }
+        self.refresh_config();
+        let wanted = self.config.audio.pre_buffer_ms > 0
+            && self.mode() != DictationMode::HandsFree
+            && !self.privacy.is_paused();
+        if wanted == self.audio_engine.is_pre_buffering() {
+            return;
+        }
+        if !wanted {
+            info!("⏸️  Stopping the pre-buffer");
+            if let Err(e) = self.audio_engine.stop_pre_buffer() {
+                warn!("Failed to stop the pre-buffer: {}", e);
+            }
+            return;
+        }
+        if self.pre_buffer_failed {
+            return;
+        }
+        let window_ms = self.config.audio.pre_buffer_ms;
+        match self
+            .audio_engine
+            .start_pre_buffer(self.capture_config(), window_ms)
+        {
+            Ok(()) => info!(
+                "⏺️  Holding the last {}ms of audio for the hotkey",
+                window_ms
+            ),
+            Err(e) => {
+                warn!("Pre-buffer unavailable: {}", e);
+                self.pre_buffer_failed = true;
+            }
+        }
+    }
+
+    /// Capture settings from `[audio]`
+    fn capture_config(&self) -> CaptureConfig {
+        CaptureConfig {
+            source: self.config.audio.capture_source(),
+            sample_rate: self.config.audio.sample_rate,
+            device_name: self.config.audio.device.clone(),
+            chunk_duration_ms: self.config.audio.chunk_duration_ms,
+            buffer_capacity_secs: 2,
+            agc: self
+                .config
+                .audio
+                .agc
+                .enabled
+                .then(|| self.config.audio.agc.clone()),
+        }
+    }
+
+    /// Why recording must not start now: a privacy pause, or a focused app
+    /// on the `[privacy]` blocklist
+    async fn privacy_block(&self) -> Option<String> {
+        if self.privacy.is_paused() {
+            return Some("capture is paused (onevox privacy resume)".to_string());
+        }
+        let blocklist = self.config.privacy.blocked_apps.clone();
+        if blocklist.is_empty() {
+            return None;
+        }
+        tokio::task::spawn_blocking(move || {
+            let app = focus::focused_app();
+            let title = focus::focused_window_title();
+            privacy::blocked_app(&blocklist, app.as_deref(), title.as_deref())
+                .map(|entry| format!("the focused app matches blocked app '{}'", entry))
+        })
+        .await
+        .unwrap_or_default()
+    }
+
     /// Start dictation session
     pub async fn start_dictation(&mut self) -> Result<()> {
+        self.start_session(HotkeyAction::Dictate).await
+    }
+
+    /// Start a session for a hotkey action (dictate, translate, hands-free,
+    /// or command)
+    async fn start_session(&mut self, action: HotkeyAction) -> Result<()> {
         if self.is_dictating.load(Ordering::SeqCst) {
             warn!("Already dictating, ignoring start request");
             return Ok(());
         }
+        if let Some(reason) = self.privacy_block().await {
+            info!("🔒 Not recording: {}", reason);
+            return Ok(());
+        }
 
-        info!("🎤 Starting dictation");
+        info!("🎤 Starting dictation ({})", action);
+        self.session = action;
+        // Every segment until stop is grouped under this in history
+        let session_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
         self.is_dictating.store(true, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
         self.indicator.recording();
 
-        // Start audio capture
-        let capture_config = CaptureConfig {
-            sample_rate: self.config.audio.sample_rate,
-            device_name: self.config.audio.device.clone(),
-            chunk_duration_ms: self.config.audio.chunk_duration_ms,
-            buffer_capacity_secs: 2,
-        };
+        self.apply_language_profile().await;
+        self.apply_translate(action == HotkeyAction::DictateTranslate);
+
+        // Silence notifications so their sounds stay out of the recording
+        if self.config.dictation.auto_dnd {
+            self.dnd = Some(tokio::task::spawn_blocking(dnd::enable));
+        }
 
-        let audio_rx = match self.audio_engine.start_capture(capture_config) {
+        // Start audio capture (or take over the pre-buffer's)
+        let audio_rx = match self.audio_engine.start_capture(self.capture_config()) {
             Ok(rx) => rx,
             Err(e) => {
                 // Failed to start - clean up state
@@ -290,29 +1011,98 @@ impl DictationEngine {
 
         let mut audio_rx = audio_rx;
 
+        // Capture moves to another microphone when one is unplugged or
+        // comes back; let subscribers know
+        if let Some(mut changes) = self.audio_engine.device_changes() {
+            let events = self.events.clone();
+            tokio::spawn(async move {
+                while let Some(change) = changes.recv().await {
+                    events.publish(Event::AudioDeviceChanged {
+                        from: change.from,
+                        to: change.to,
+                    });
+                }
+            });
+        }
+
+        // Recording consent notice
+        let consent = self.config.consent.clone();
+        if consent.start_beep {
+            self.tone_player.play(consent.start_tone());
+        }
+        if consent.reminder_interval_secs > 0 {
+            let is_dictating = Arc::clone(&self.is_dictating);
+            let tone_player = self.tone_player.clone();
+            let interval = tokio::time::Duration::from_secs(consent.reminder_interval_secs as u64);
+            tokio::spawn(async move {
+                let mut elapsed = tokio::time::Duration::ZERO;
+                let tick = tokio::time::Duration::from_millis(100);
+                while is_dictating.load(Ordering::SeqCst) {
+                    tokio::time::sleep(tick).await;
+                    elapsed += tick;
+                    if elapsed >= interval && is_dictating.load(Ordering::SeqCst) {
+                        debug!("🔔 Recording reminder tone");
+                        tone_player.play(consent.reminder_tone());
+                        elapsed = tokio::time::Duration::ZERO;
+                    }
+                }
+            });
+        }
+
         // Clone needed values for the processing task
         let is_dictating = Arc::clone(&self.is_dictating);
-        let injector = self.text_injector.clone();
+        let paused = Arc::clone(&self.paused);
         let model = Arc::clone(&self.model);
         let model_name = self.config.model.model_path.clone();
         let history_manager = Arc::clone(&self.history_manager);
-        let vad_enabled = self.config.vad.enabled;
+        // Hands-free listening relies on the VAD to find speech
+        let hands_free =
+            self.mode() == DictationMode::HandsFree || action == HotkeyAction::HandsFree;
+        if hands_free && !self.config.vad.enabled {
+            warn!("Hands-free mode needs the VAD; enabling it for this session");
+        }
+        let vad_enabled = self.config.vad.enabled || hands_free;
         let indicator = Arc::clone(&self.indicator);
         let focus_settle_ms = self.config.injection.focus_settle_ms;
+        let delivery = self.delivery.clone();
+        let gain_hints = Arc::clone(&self.gain_hints);
+        let postprocessor = self.postprocessor.clone();
+        let commands = self.commands.clone();
+        let command_only = action == HotkeyAction::Command;
+        let last_transcript = Arc::clone(&self.last_transcript);
+        let events = self.events.clone();
+        let metrics = self.metrics.clone();
+        let gain_meter = self.audio_engine.gain_meter();
+        let mut denoiser = self
+            .config
+            .audio
+            .denoise
+            .then(|| Denoiser::new(self.config.audio.sample_rate));
+        events.publish(Event::DictationStarted);
+
+        // Remember the target app so text can be held if it loses focus
+        let target_query = tokio::task::spawn_blocking(focus::focused_app);
 
         if vad_enabled {
             // VAD-based processing: detect speech segments and transcribe them
             info!("🔊 VAD enabled - using speech detection");
 
-            // Create VAD processor
-            let vad_config = self.config.vad.to_energy_vad_config();
-            let processor_config = self.config.vad.to_processor_config();
-            let detector: Box<dyn VadDetector> = Box::new(EnergyVad::new(vad_config));
+            // Create VAD processor; push-to-talk ends when the key is let go,
+            // so only toggle and hands-free sessions stop on silence
+            let mut processor_config = self.config.vad.to_processor_config();
+            if self.mode() == DictationMode::Hold && !hands_free {
+                processor_config.auto_stop_silence_ms = 0;
+            }
+            let auto_stop = Arc::clone(&self.auto_stop);
+            auto_stop.store(false, Ordering::SeqCst);
+            let detector = crate::vad::create_detector(&self.config.vad).await;
             let mut vad_processor = VadProcessor::new(processor_config, detector);
+            let mut duplicates = DuplicateGuard::new(self.config.dictation.duplicate_window_ms);
 
             // Spawn audio processing task
             tokio::spawn(async move {
                 info!("📡 Audio processing task started (VAD mode)");
+                let target_app = target_query.await.unwrap_or_default();
 
                 loop {
                     match tokio::time::timeout(
@@ -321,61 +1111,158 @@ impl DictationEngine {
                     )
                     .await
                     {
-                        Ok(Some(chunk)) => {
+                        Ok(Some(mut chunk)) => {
+                            if paused.load(Ordering::SeqCst) {
+                                continue;
+                            }
+                            if let Some(denoiser) = denoiser.as_mut() {
+                                denoiser.process_chunk(&mut chunk);
+                            }
+
                             // Process through VAD
-                            match vad_processor.process(chunk) {
-                                Ok(Some(segment)) => {
+                            let (rms, peak) = (chunk.rms(), chunk.peak());
+                            let was_in_speech = vad_processor.is_in_speech();
+                            let result = vad_processor.process(chunk);
+                            if !was_in_speech && vad_processor.is_in_speech() {
+                                events.publish(Event::SpeechStarted);
+                            }
+                            events.publish(Event::AudioLevel {
+                                rms,
+                                peak,
+                                speech: Some(vad_processor.is_in_speech()),
+                                gain_db: gain_meter.as_ref().map(GainMeter::db),
+                            });
+                            if vad_processor.auto_stop_due() {
+                                auto_stop.store(true, Ordering::SeqCst);
+                            }
+                            match result {
+                                Ok(Some(mut segment)) => {
                                     info!("🎯 Speech segment detected ({} chunks)", segment.len());
+                                    let speech_end = std::time::Instant::now();
+                                    metrics.record_segment();
+                                    events.publish(Event::SpeechDetected {
+                                        chunks: segment.len(),
+                                    });
                                     indicator.processing();
 
                                     // Transcribe
                                     let model_clone = Arc::clone(&model);
                                     let model_name_clone = model_name.clone();
                                     let history_clone = Arc::clone(&history_manager);
+                                    let audio_hint = Self::gain_hint(&gain_hints, &mut segment);
+                                    let sample_rate = segment.sample_rate();
+                                    let audio_ms =
+                                        Self::audio_ms(segment.get_samples(), sample_rate);
+                                    let recording = history_clone
+                                        .saves_recordings()
+                                        .then(|| (segment.get_samples().to_vec(), sample_rate));
 
                                     match Self::transcribe_with_model(model_clone, segment).await {
-                                        Ok(transcript) => {
-                                            info!("📝 Transcription: {}", transcript.text);
-
-                                            // Record to history
-                                            let history_entry = HistoryEntry::new(
-                                                transcript.text.clone(),
-                                                model_name_clone,
+                                        Ok(mut transcript) => {
+                                            metrics.record_transcription(
+                                                &model_name_clone,
+                                                audio_ms,
                                                 transcript.processing_time_ms,
-                                                transcript.confidence,
                                             );
-
-                                            if let Err(e) =
-                                                history_clone.add_entry(history_entry).await
-                                            {
-                                                error!("Failed to record history: {}", e);
+                                            postprocessor.process(&mut transcript);
+                                            info!(
+                                                "📝 Transcription: {} chars",
+                                                transcript.text.chars().count()
+                                            );
+                                            debug!("Transcript: {}", transcript.text);
+                                            events.publish(Event::TranscriptionComplete {
+                                                text: transcript.text.clone(),
+                                                duration_ms: transcript.processing_time_ms,
+                                                confidence: transcript.confidence,
+                                            });
+
+                                            // Overlapping pre/post-roll can repeat the
+                                            // previous sentence; keep it in history only
+                                            let duplicate = duplicates.is_duplicate(
+                                                &transcript.text,
+                                                std::time::Instant::now(),
+                                            );
+                                            if duplicate {
+                                                info!("🔁 Skipping near-duplicate segment");
                                             }
 
-                                            // Hide overlay before injection so target app keeps focus.
-                                            indicator.hide();
-                                            if focus_settle_ms > 0 {
-                                                tokio::time::sleep(
-                                                    tokio::time::Duration::from_millis(
-                                                        focus_settle_ms as u64,
-                                                    ),
-                                                )
-                                                .await;
+                                            let mut not_injected = None;
+                                            if !duplicate {
+                                                // Hide overlay before injection so target app keeps focus.
+                                                indicator.hide();
+                                                if focus_settle_ms > 0 {
+                                                    tokio::time::sleep(
+                                                        tokio::time::Duration::from_millis(
+                                                            focus_settle_ms as u64,
+                                                        ),
+                                                    )
+                                                    .await;
+                                                }
+
+                                                // Apply voice commands, then inject into the
+                                                // target application
+                                                let interpreted = Self::interpret(
+                                                    &commands,
+                                                    &transcript.text,
+                                                    command_only,
+                                                );
+                                                for _ in 0..interpreted.scratch {
+                                                    delivery.scratch().await;
+                                                }
+                                                let typed = !interpreted.text.is_empty();
+                                                not_injected = delivery
+                                                    .deliver(
+                                                        interpreted.text,
+                                                        target_app.clone(),
+                                                        transcript.confidence,
+                                                    )
+                                                    .await;
+                                                if typed && not_injected.is_none() {
+                                                    metrics.record_latency(speech_end.elapsed());
+                                                }
                                             }
 
-                                            // Inject text into active application
-                                            if let Err(e) = injector.inject(&transcript.text) {
-                                                error!("Failed to inject text: {}", e);
-                                            } else {
-                                                info!("✅ Text injected successfully");
+                                            Self::remember_transcript(
+                                                &last_transcript,
+                                                &transcript,
+                                                &model_name_clone,
+                                                audio_ms,
+                                                recording.clone(),
+                                            )
+                                            .await;
+
+                                            // Record to history
+                                            if let Err(e) = Self::record_history(
+                                                &history_clone,
+                                                &transcript,
+                                                &model_name_clone,
+                                                SegmentNotes {
+                                                    audio_hint,
+                                                    duplicate,
+                                                    not_injected,
+                                                    audio_ms,
+                                                    session_id,
+                                                },
+                                                recording,
+                                            )
+                                            .await
+                                            {
+                                                error!("Failed to record history: {}", e);
                                             }
                                         }
                                         Err(e) => {
                                             error!("Transcription failed: {}", e);
+                                            events
+                                                .publish(Event::TranscriptionFailed { message: e });
                                         }
                                     }
 
                                     if is_dictating.load(Ordering::SeqCst) {
-                                        indicator.recording();
+                                        if paused.load(Ordering::SeqCst) {
+                                            indicator.paused();
+                                        } else {
+                                            indicator.recording();
+                                        }
                                     }
                                 }
                                 Ok(None) => {
@@ -417,8 +1304,20 @@ impl DictationEngine {
                     )
                     .await
                     {
-                        Ok(Some(chunk)) => {
+                        Ok(Some(mut chunk)) => {
+                            if paused.load(Ordering::SeqCst) {
+                                continue;
+                            }
                             debug!("Collected audio chunk: {} samples", chunk.samples.len());
+                            if let Some(denoiser) = denoiser.as_mut() {
+                                denoiser.process_chunk(&mut chunk);
+                            }
+                            events.publish(Event::AudioLevel {
+                                rms: chunk.rms(),
+                                peak: chunk.peak(),
+                                speech: None,
+                                gain_db: gain_meter.as_ref().map(GainMeter::db),
+                            });
                             collected_chunks.push(chunk);
                         }
                         Ok(None) => {
@@ -434,6 +1333,7 @@ impl DictationEngine {
                 }
 
                 // Hotkey released - transcribe all collected audio
+                let speech_end = std::time::Instant::now();
                 if !collected_chunks.is_empty() {
                     info!(
                         "🎤 Hotkey released - transcribing {} chunks",
@@ -467,22 +1367,31 @@ impl DictationEngine {
                         100.0 * non_zero_samples as f32 / samples.len() as f32
                     );
 
+                    let audio_hint = Self::gain_hint(&gain_hints, &mut segment);
+                    let audio_ms = Self::audio_ms(segment.get_samples(), sample_rate);
+                    let recording = history_manager
+                        .saves_recordings()
+                        .then(|| (segment.get_samples().to_vec(), sample_rate));
+
                     // Transcribe
                     match Self::transcribe_with_model(Arc::clone(&model), segment).await {
-                        Ok(transcript) => {
-                            info!("📝 Transcription: {}", transcript.text);
-
-                            // Record to history
-                            let history_entry = HistoryEntry::new(
-                                transcript.text.clone(),
-                                model_name,
+                        Ok(mut transcript) => {
+                            metrics.record_transcription(
+                                &model_name,
+                                audio_ms,
                                 transcript.processing_time_ms,
-                                transcript.confidence,
                             );
-
-                            if let Err(e) = history_manager.add_entry(history_entry).await {
-                                error!("Failed to record history: {}", e);
-                            }
+                            postprocessor.process(&mut transcript);
+                            info!(
+                                "📝 Transcription: {} chars",
+                                transcript.text.chars().count()
+                            );
+                            debug!("Transcript: {}", transcript.text);
+                            events.publish(Event::TranscriptionComplete {
+                                text: transcript.text.clone(),
+                                duration_ms: transcript.processing_time_ms,
+                                confidence: transcript.confidence,
+                            });
 
                             // Hide overlay before injection so target app keeps focus.
                             indicator.hide();
@@ -493,15 +1402,52 @@ impl DictationEngine {
                                 .await;
                             }
 
-                            // Inject text into active application
-                            if let Err(e) = injector.inject(&transcript.text) {
-                                error!("Failed to inject text: {}", e);
-                            } else {
-                                info!("✅ Text injected successfully");
+                            // Apply voice commands, then inject into the target application
+                            let interpreted =
+                                Self::interpret(&commands, &transcript.text, command_only);
+                            for _ in 0..interpreted.scratch {
+                                delivery.scratch().await;
+                            }
+                            let target_app = target_query.await.unwrap_or_default();
+                            let typed = !interpreted.text.is_empty();
+                            let not_injected = delivery
+                                .deliver(interpreted.text, target_app, transcript.confidence)
+                                .await;
+                            if typed && not_injected.is_none() {
+                                metrics.record_latency(speech_end.elapsed());
+                            }
+
+                            Self::remember_transcript(
+                                &last_transcript,
+                                &transcript,
+                                &model_name,
+                                audio_ms,
+                                recording.clone(),
+                            )
+                            .await;
+
+                            // Record to history
+                            if let Err(e) = Self::record_history(
+                                &history_manager,
+                                &transcript,
+                                &model_name,
+                                SegmentNotes {
+                                    audio_hint,
+                                    duplicate: false,
+                                    not_injected,
+                                    audio_ms,
+                                    session_id,
+                                },
+                                recording,
+                            )
+                            .await
+                            {
+                                error!("Failed to record history: {}", e);
                             }
                         }
                         Err(e) => {
                             error!("Transcription failed: {}", e);
+                            events.publish(Event::TranscriptionFailed { message: e });
                         }
                     }
                 } else {
@@ -516,6 +1462,135 @@ impl DictationEngine {
         Ok(())
     }
 
+    /// Clipboard ring from `[clipboard_ring]`, if enabled
+    fn load_clipboard_ring(config: &Config) -> Option<Arc<parking_lot::Mutex<ClipboardRing>>> {
+        if !config.clipboard_ring.enabled {
+            return None;
+        }
+        match ClipboardRing::default_path() {
+            Ok(path) => Some(Arc::new(parking_lot::Mutex::new(ClipboardRing::load(
+                &path,
+                config.clipboard_ring.size,
+            )))),
+            Err(e) => {
+                warn!("Clipboard ring unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Show the clipboard ring picker and paste the selected transcript
+    fn open_picker(&self) {
+        if self.clipboard_ring.is_none() {
+            warn!("Clipboard ring is off; set clipboard_ring.enabled = true to use the picker");
+            return;
+        }
+
+        let injector = self.text_injector.clone();
+        let focus_settle_ms = self.config.injection.focus_settle_ms;
+        tokio::spawn(async move {
+            let text = match tokio::task::spawn_blocking(crate::indicator::pick_from_ring).await {
+                Ok(Ok(Some(text))) => text,
+                Ok(Ok(None)) => {
+                    debug!("Picker closed without a selection");
+                    return;
+                }
+                Ok(Err(e)) => {
+                    warn!("Clipboard picker failed: {}", e);
+                    return;
+                }
+                Err(e) => {
+                    error!("Clipboard picker task failed: {}", e);
+                    return;
+                }
+            };
+
+            // Let focus return to the app the picker was opened over
+            if focus_settle_ms > 0 {
+                tokio::time::sleep(tokio::time::Duration::from_millis(focus_settle_ms as u64))
+                    .await;
+            }
+            match tokio::task::spawn_blocking(move || injector.paste(&text)).await {
+                Ok(Ok(())) => info!("📋 Pasted transcript from the clipboard ring"),
+                Ok(Err(e)) => warn!("Failed to paste from the clipboard ring: {}", e),
+                Err(e) => error!("Paste task failed: {}", e),
+            }
+        });
+    }
+
+    /// Apply voice commands to a transcript; with `command_only` (the
+    /// command hotkey) anything that isn't a command is dropped
+    fn interpret(commands: &VoiceCommands, text: &str, command_only: bool) -> Interpreted {
+        if !command_only {
+            return commands.interpret(text);
+        }
+        commands.interpret_command(text).unwrap_or_else(|| {
+            info!("🗣️  No voice command in the transcript; nothing typed");
+            debug!("Transcript: {}", text);
+            Interpreted::default()
+        })
+    }
+
+    /// Switch the model between transcribing and translating to English
+    fn apply_translate(&self, translate: bool) {
+        match self.model.lock() {
+            Ok(mut model) => {
+                if !model.set_translate(translate) && translate {
+                    warn!(
+                        "The {} backend can't translate; transcribing instead",
+                        model.info().backend
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to set translation: {}", e),
+        }
+    }
+
+    /// Pick the decoding language from the profile matching the focused
+    /// app or keyboard input source
+    async fn apply_language_profile(&self) {
+        let language = if self.config.profiles.is_empty() {
+            self.config.model.language()
+        } else {
+            let focus = tokio::task::spawn_blocking(FocusContext::current)
+                .await
+                .unwrap_or_default();
+            let (app, source) = (focus.app.as_deref(), focus.input_source.as_deref());
+            if let Some(profile) = self.config.matching_profile(app, source) {
+                info!(
+                    "🌐 Profile '{}' matched (app: {:?}, input source: {:?}) → language {}",
+                    profile.name, app, source, profile.language
+                );
+            }
+            self.config.language_for(app, source)
+        };
+
+        match self.model.lock() {
+            Ok(mut model) => model.set_language(language),
+            Err(e) => warn!("Failed to set decoding language: {}", e),
+        }
+    }
+
+    /// Transcribe an audio file (WAV, MP3, FLAC, OGG) with the loaded model
+    pub async fn transcribe_file(&self, path: std::path::PathBuf) -> Result<String> {
+        let model = Arc::clone(&self.model);
+        let mut transcription = tokio::task::spawn_blocking(move || -> Result<Transcription> {
+            let mut guard = model
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Model mutex poisoned"))?;
+            let sample_rate = guard.sample_rate();
+            let (samples, report) = crate::audio::convert::load_audio(&path, sample_rate)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            info!("📄 Transcribing {} ({})", path.display(), report);
+            Ok(guard.transcribe(&samples, sample_rate)?)
+        })
+        .await
+        .context("Transcription task failed")??;
+
+        self.postprocessor.process(&mut transcription);
+        Ok(transcription.text)
+    }
+
     /// Stop dictation session
     pub async fn stop_dictation(&mut self) -> Result<()> {
         if !self.is_dictating.load(Ordering::SeqCst) {
@@ -525,19 +1600,119 @@ impl DictationEngine {
 
         info!("🛑 Stopping dictation");
         self.is_dictating.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
         self.indicator.processing();
 
         // Stop audio capture
+        self.metrics
+            .record_dropped(self.audio_engine.dropped_chunks());
         self.audio_engine.stop_capture()?;
+        self.events.publish(Event::DictationStopped);
+
+        if self.config.consent.stop_beep {
+            self.tone_player.play(self.config.consent.stop_tone());
+        }
 
         // On macOS, give the audio system time to fully release the device
         // This prevents audio quality degradation issues specific to CoreAudio
         #[cfg(target_os = "macos")]
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
+        if let Some(handle) = self.dnd.take()
+            && let Ok(Some(guard)) = handle.await
+        {
+            tokio::task::spawn_blocking(move || guard.release());
+        }
+
         Ok(())
     }
 
+    /// Check a recording's levels, notifying when a gain hint is due
+    fn gain_hint(
+        gain_hints: &GainHints,
+        segment: &mut crate::vad::SpeechSegment,
+    ) -> Option<String> {
+        let sample_rate = segment.sample_rate();
+        let hint = gain_hints.check(segment.get_samples(), sample_rate)?.hint();
+        info!("🎚️  {}", hint);
+        notify::notify("Onevox microphone", hint);
+        Some(hint.to_string())
+    }
+
+    /// Record a transcription to history, tagged with any gain hint, with
+    /// why it wasn't typed, and with the length of its speech, along with
+    /// its audio when recordings are saved
+    async fn record_history(
+        history: &HistoryManager,
+        transcript: &Transcription,
+        model_name: &str,
+        notes: SegmentNotes,
+        audio: Option<(Vec<f32>, u32)>,
+    ) -> crate::Result<()> {
+        let mut entries = HistoryEntry::from_transcription(transcript, model_name);
+        for entry in &mut entries {
+            entry.audio_hint = notes.audio_hint.clone();
+            entry.duplicate = notes.duplicate;
+            entry.not_injected = notes.not_injected.clone();
+            // Split sentences already know theirs
+            entry.speech_ms.get_or_insert(notes.audio_ms);
+            entry.session_id = Some(notes.session_id);
+        }
+        history.add_recorded_entries(entries, audio).await
+    }
+
+    /// Keep the transcript for `GetLastTranscript`, with its segment audio
+    /// when recordings are enabled (`recording` is `None` otherwise)
+    ///
+    /// Called after delivery so the WAV write never delays typing.
+    async fn remember_transcript(
+        last_transcript: &parking_lot::Mutex<Option<LastTranscript>>,
+        transcript: &Transcription,
+        model_name: &str,
+        audio_ms: u64,
+        recording: Option<(Vec<f32>, u32)>,
+    ) {
+        let audio_path = match recording {
+            Some((samples, sample_rate)) => {
+                let saved = tokio::task::spawn_blocking(move || {
+                    let path = crate::platform::recordings_dir()?.join(LAST_TRANSCRIPT_AUDIO);
+                    crate::audio::convert::save_wav(&path, &samples, sample_rate)?;
+                    crate::Result::Ok(path)
+                })
+                .await;
+                match saved {
+                    Ok(Ok(path)) => Some(path.to_string_lossy().into_owned()),
+                    Ok(Err(e)) => {
+                        warn!("Failed to save segment audio: {}", e);
+                        None
+                    }
+                    Err(e) => {
+                        warn!("Segment audio task failed: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        *last_transcript.lock() = Some(LastTranscript {
+            text: transcript.text.clone(),
+            model: model_name.to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            processing_ms: transcript.processing_time_ms,
+            audio_ms,
+            audio_path,
+        });
+    }
+
+    /// Length of `samples` at `sample_rate` in milliseconds
+    fn audio_ms(samples: &[f32], sample_rate: u32) -> u64 {
+        samples.len() as u64 * 1000 / sample_rate.max(1) as u64
+    }
+
     async fn transcribe_with_model(
         model: Arc<Mutex<Box<dyn ModelRuntime>>>,
         mut segment: crate::vad::SpeechSegment,
@@ -585,6 +1760,7 @@ impl DictationEngine {
             let _ = self.audio_engine.stop_capture();
             self.is_dictating.store(false, Ordering::SeqCst);
         }
+        let _ = self.audio_engine.stop_pre_buffer();
         self.indicator.hide();
 
         if let Some(hotkey_manager) = self.hotkey_manager.as_mut()
@@ -595,6 +1771,7 @@ impl DictationEngine {
 
         if let Ok(mut model) = self.model.lock() {
             model.unload();
+            self.events.publish(Event::ModelUnloaded);
         } else {
             error!("Failed to acquire model lock during shutdown");
         }
@@ -0,0 +1,196 @@
+//! Daemon-Managed Model Downloads
+//!
+//! Downloads started over IPC (`DownloadModel`) run as background tasks in
+//! the daemon so front-ends like the TUI can follow them through
+//! `DownloadProgress` events instead of blocking a terminal. A download can
+//! be paused (its partial files are kept and resumed by the next
+//! `DownloadModel`) or cancelled (partial files are removed), and on
+//! completion the model can be made the active one.
+
+use crate::config::Config;
+use crate::daemon::events::EventBus;
+use crate::daemon::state::DaemonState;
+use crate::ipc::protocol::Event;
+use crate::models::{DownloadCancelled, ModelDownloader, ModelMetadata};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// A download running in the background
+struct ActiveDownload {
+    cancel: CancellationToken,
+    /// Remove partial files once stopped (cancel rather than pause)
+    discard: bool,
+}
+
+/// Background model downloads, keyed by model id
+///
+/// Cheap to clone; clones share the same downloads.
+#[derive(Clone, Default)]
+pub struct Downloads {
+    active: Arc<Mutex<HashMap<String, ActiveDownload>>>,
+}
+
+impl Downloads {
+    /// Start downloading `metadata` in the background
+    ///
+    /// With `activate`, the model becomes `model.model_path` once it is
+    /// complete and the daemon reloads its configuration to switch to it.
+    /// Attempts that get no data for `stall_timeout` are retried (`None`
+    /// waits indefinitely).
+    pub fn start(
+        &self,
+        metadata: ModelMetadata,
+        state: Arc<RwLock<DaemonState>>,
+        events: EventBus,
+        activate: bool,
+        stall_timeout: Option<Duration>,
+    ) -> crate::Result<()> {
+        let cancel = CancellationToken::new();
+        {
+            let mut active = self.active.lock();
+            if active.contains_key(&metadata.id) {
+                return Err(crate::Error::Other(format!(
+                    "Model '{}' is already downloading",
+                    metadata.id
+                )));
+            }
+            active.insert(
+                metadata.id.clone(),
+                ActiveDownload {
+                    cancel: cancel.clone(),
+                    discard: false,
+                },
+            );
+        }
+
+        let downloads = self.clone();
+        tokio::spawn(async move {
+            let model = metadata.id.clone();
+            let downloader = ModelDownloader::new().map(|downloader| {
+                downloader
+                    .with_events(events.clone())
+                    .with_cancel(cancel)
+                    .with_stall_timeout(stall_timeout)
+            });
+            let result = match &downloader {
+                Ok(downloader) => downloader.download(&metadata).await.map(|_| ()),
+                Err(e) => Err(anyhow::anyhow!("{}", e)),
+            };
+            let discard = downloads
+                .active
+                .lock()
+                .remove(&model)
+                .is_some_and(|download| download.discard);
+
+            match result {
+                Ok(()) if activate => {
+                    if let Err(e) = activate_model(&state, &model).await {
+                        warn!("Downloaded {} but could not switch to it: {}", model, e);
+                        events.publish(Event::Error {
+                            message: format!("Failed to switch to {}: {}", model, e),
+                        });
+                    }
+                }
+                Ok(()) => {}
+                Err(e) if e.is::<DownloadCancelled>() => {
+                    if discard
+                        && let Ok(downloader) = &downloader
+                        && let Err(e) = downloader.discard_partial(&metadata).await
+                    {
+                        warn!("Failed to remove partial download of {}: {}", model, e);
+                    }
+                    info!(
+                        "Download of {} {}",
+                        model,
+                        if discard { "cancelled" } else { "paused" }
+                    );
+                    events.publish(Event::DownloadStopped {
+                        model,
+                        discarded: discard,
+                    });
+                }
+                Err(e) => {
+                    warn!("Download of {} failed: {:#}", model, e);
+                    events.publish(Event::DownloadFailed {
+                        model,
+                        message: format!("{:#}", e),
+                    });
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Stop a running download, keeping its partial files unless `discard`
+    ///
+    /// Returns `false` when the model isn't downloading.
+    pub fn stop(&self, model_id: &str, discard: bool) -> bool {
+        match self.active.lock().get_mut(model_id) {
+            Some(download) => {
+                download.discard = discard;
+                download.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Ids of models currently downloading
+    pub fn active(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.active.lock().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Stop all downloads, keeping partial files for the next start
+    pub fn pause_all(&self) {
+        for download in self.active.lock().values() {
+            download.cancel.cancel();
+        }
+    }
+}
+
+/// Make `model_id` the configured model and reload the daemon config
+pub(crate) async fn activate_model(
+    state: &Arc<RwLock<DaemonState>>,
+    model_id: &str,
+) -> crate::Result<()> {
+    let path = Config::default_path();
+    let model_id = model_id.to_string();
+    let key_model = model_id.clone();
+    tokio::task::spawn_blocking(move || Config::set_key(&path, "model.model_path", &key_model))
+        .await
+        .map_err(|e| crate::Error::Other(e.to_string()))??;
+    state.write().await.reload_config()?;
+    info!("Switched to model {}", model_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_marks_discard() {
+        let downloads = Downloads::default();
+        assert!(!downloads.stop("ggml-base.en", false));
+
+        let cancel = CancellationToken::new();
+        downloads.active.lock().insert(
+            "ggml-base.en".to_string(),
+            ActiveDownload {
+                cancel: cancel.clone(),
+                discard: false,
+            },
+        );
+        assert_eq!(downloads.active(), vec!["ggml-base.en".to_string()]);
+        assert!(downloads.stop("ggml-base.en", true));
+        assert!(cancel.is_cancelled());
+        assert!(downloads.active.lock()["ggml-base.en"].discard);
+    }
+}
@@ -0,0 +1,120 @@
+//! Near-Duplicate Segment Detection
+//!
+//! Overlapping pre/post-roll around a pause can hand the model nearly the
+//! same audio twice, so the same sentence comes back as two consecutive VAD
+//! segments. A segment whose text is within a small normalized edit
+//! distance of the previous one, and arrives within
+//! `dictation.duplicate_window_ms` of it, is treated as a repeat: it is
+//! kept in history (marked as a duplicate) but not injected again.
+
+use std::time::{Duration, Instant};
+
+/// Largest normalized edit distance still treated as the same sentence
+const MAX_DISTANCE: f32 = 0.2;
+
+/// Tracks the last delivered segment of a dictation session
+pub struct DuplicateGuard {
+    window: Duration,
+    last: Option<(String, Instant)>,
+}
+
+impl DuplicateGuard {
+    /// Guard for repeats within `window_ms` (0 disables it)
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            window: Duration::from_millis(window_ms),
+            last: None,
+        }
+    }
+
+    /// Whether `text`, finished at `now`, repeats the previous segment
+    ///
+    /// Repeats don't move the window, so a run of copies is measured from
+    /// the original.
+    pub fn is_duplicate(&mut self, text: &str, now: Instant) -> bool {
+        let normalized = normalize(text);
+        if self.window.is_zero() || normalized.is_empty() {
+            return false;
+        }
+        if let Some((last, at)) = &self.last
+            && now.duration_since(*at) <= self.window
+            && normalized_distance(last, &normalized) <= MAX_DISTANCE
+        {
+            return true;
+        }
+        self.last = Some((normalized, now));
+        false
+    }
+}
+
+/// Lowercase words without punctuation, single-spaced
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein distance over characters divided by the longer length
+fn normalized_distance(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_duplicates_within_window() {
+        assert_eq!(normalized_distance("kitten", "sitting"), 3.0 / 7.0);
+        assert_eq!(normalize("  Send it, now! "), "send it now");
+
+        let start = Instant::now();
+        let mut guard = DuplicateGuard::new(2000);
+        assert!(!guard.is_duplicate("Let's ship it on Friday.", start));
+        assert!(guard.is_duplicate(
+            "let's ship it on friday",
+            start + Duration::from_millis(800)
+        ));
+        assert!(guard.is_duplicate(
+            "Lets ship it on Friday",
+            start + Duration::from_millis(1900)
+        ));
+        assert!(!guard.is_duplicate(
+            "Then review the release notes.",
+            start + Duration::from_millis(2500)
+        ));
+        // Same text after the window is dictated again on purpose
+        assert!(!guard.is_duplicate(
+            "Then review the release notes.",
+            start + Duration::from_millis(5000)
+        ));
+
+        let mut disabled = DuplicateGuard::new(0);
+        assert!(!disabled.is_duplicate("again", start));
+        assert!(!disabled.is_duplicate("again", start));
+    }
+}
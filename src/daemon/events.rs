@@ -0,0 +1,162 @@
+//! Daemon Event Bus
+//!
+//! In-process broadcast channel that subsystems (downloader, model loader,
+//! dictation, VAD, injection) publish to. IPC subscribers, logging, screen
+//! reader notifications, desktop notifications, sound cues, event metrics,
+//! and local telemetry all consume from it.
+
+use crate::audio::SoundFeedback;
+use crate::ipc::protocol::Event;
+use crate::platform::Announcer;
+use crate::platform::notify::Notifier;
+use crate::telemetry::TelemetryRecorder;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Events buffered per subscriber before slow subscribers start lagging
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Broadcast bus for daemon events
+///
+/// Cheap to clone; all clones publish to the same subscribers. Publishing
+/// never blocks and succeeds even when nobody is listening.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<Event>,
+    counts: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl EventBus {
+    /// Create a bus buffering up to `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            counts: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Publish an event to all current subscribers
+    pub fn publish(&self, event: Event) {
+        // An error only means there are no subscribers right now
+        let _ = self.tx.send(event);
+    }
+
+    /// Subscribe to events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.tx.subscribe()
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Events seen by the metrics consumer, by event name
+    pub fn counts(&self) -> BTreeMap<String, u64> {
+        self.counts.lock().clone()
+    }
+
+    /// Log every event under the `onevox::events` target
+    pub fn spawn_logger(&self) -> JoinHandle<()> {
+        self.spawn_consumer("logger", |event| match event {
+            Event::Error { message } => warn!(target: "onevox::events", "{}", message),
+            // Several a second while dictating
+            Event::AudioLevel { .. } => {}
+            event => debug!(target: "onevox::events", "{}", event),
+        })
+    }
+
+    /// Count events by name (reported in `onevox status --verbose`)
+    pub fn spawn_metrics(&self) -> JoinHandle<()> {
+        let counts = Arc::clone(&self.counts);
+        self.spawn_consumer("metrics", move |event| {
+            *counts.lock().entry(event.name().to_string()).or_insert(0) += 1;
+        })
+    }
+
+    /// Announce injection outcomes through the screen reader
+    pub fn spawn_announcer(&self, announcer: Announcer) -> JoinHandle<()> {
+        self.spawn_consumer("announcer", move |event| match event {
+            Event::InjectionComplete { text } => announcer.announce_injected(&text),
+            Event::InjectionFailed { message } | Event::TranscriptionFailed { message } => {
+                announcer.announce_failure(&message)
+            }
+            Event::InjectionDeferred { reason, .. } => announcer.announce_deferred(&reason),
+            _ => {}
+        })
+    }
+
+    /// Show desktop notifications for errors, permissions, and downloads
+    pub fn spawn_notifier(&self, mut notifier: Notifier) -> JoinHandle<()> {
+        self.spawn_consumer("notifier", move |event| notifier.handle(&event))
+    }
+
+    /// Play sound cues for dictation events
+    pub fn spawn_feedback(&self, feedback: SoundFeedback) -> JoinHandle<()> {
+        self.spawn_consumer("feedback", move |event| feedback.handle(&event))
+    }
+
+    /// Aggregate events into the local telemetry store
+    pub fn spawn_telemetry(&self, mut recorder: TelemetryRecorder) -> JoinHandle<()> {
+        self.spawn_consumer("telemetry", move |event| recorder.handle(&event))
+    }
+
+    fn spawn_consumer<F>(&self, name: &'static str, mut handle: F) -> JoinHandle<()>
+    where
+        F: FnMut(Event) + Send + 'static,
+    {
+        let mut rx = self.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => handle(event),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Event {} lagged, skipped {} events", name, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers_and_metrics() {
+        let bus = EventBus::default();
+        let mut rx = bus.subscribe();
+        let metrics = bus.spawn_metrics();
+
+        bus.publish(Event::DictationStarted);
+        bus.publish(Event::SpeechDetected { chunks: 4 });
+        bus.publish(Event::DictationStarted);
+
+        assert!(matches!(rx.recv().await.unwrap(), Event::DictationStarted));
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            Event::SpeechDetected { chunks: 4 }
+        ));
+
+        // Let the metrics consumer drain the channel
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let counts = bus.counts();
+        assert_eq!(counts["DictationStarted"], 2);
+        assert_eq!(counts["SpeechDetected"], 1);
+
+        metrics.abort();
+    }
+}
@@ -0,0 +1,434 @@
+//! HTTP API
+//!
+//! With `[api].enabled`, the daemon serves a small REST API on
+//! `127.0.0.1:<port>` for scripts and browser extensions that can't speak
+//! the IPC socket:
+//!
+//! - `GET /status` — daemon status
+//! - `POST /transcribe` — transcribe the audio file in the request body
+//! - `GET /history?limit=&q=` — recent entries, or search results for `q`
+//! - `POST /dictation/start`, `POST /dictation/stop`
+//! - `GET /events` — WebSocket of transcription events as JSON
+//!
+//! Requests run through the same handler as IPC commands. Only loopback
+//! `Host` headers are accepted (so a web page can't reach the API through
+//! DNS rebinding), browser requests need their `Origin` listed in
+//! `allowed_origins`, and the token must be sent as
+//! `Authorization: Bearer <token>` or, for WebSockets, `?token=<token>`.
+//! Without `[api].token`, a token is generated on first start and saved to
+//! `api-token` in the config directory, readable by the user only.
+
+use crate::config::ApiConfig;
+use crate::daemon::state::DaemonState;
+use crate::ipc::protocol::generate_request_id;
+use crate::ipc::server::IpcServer;
+use crate::ipc::{Command, Event, Response as IpcResponse};
+use crate::stream::TranscriptEvent;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{DefaultBodyLimit, Query, Request, State};
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Largest audio upload accepted by `/transcribe`
+const MAX_UPLOAD_BYTES: usize = 200 * 1024 * 1024;
+
+/// Client name HTTP requests are recorded under
+const CLIENT_NAME: &str = "http";
+
+/// File in the config directory holding the generated token
+const TOKEN_FILE: &str = "api-token";
+
+#[derive(Clone)]
+struct Api {
+    state: Arc<RwLock<DaemonState>>,
+    token: Arc<str>,
+    allowed_origins: Arc<[String]>,
+    requests: Arc<AtomicU64>,
+}
+
+/// Start the API server, or `None` when it is disabled or its token can't
+/// be resolved
+pub fn spawn(config: &ApiConfig, state: Arc<RwLock<DaemonState>>) -> Option<JoinHandle<()>> {
+    if !config.enabled {
+        return None;
+    }
+
+    let token = match config.token.trim() {
+        "" => generated_token(),
+        token => crate::secrets::resolve(token),
+    };
+    let token = match token {
+        Ok(token) => Arc::from(token),
+        Err(e) => {
+            error!("HTTP API disabled: {}", e);
+            return None;
+        }
+    };
+    let api = Api {
+        state,
+        token,
+        allowed_origins: config.allowed_origins.clone().into(),
+        requests: Arc::new(AtomicU64::new(0)),
+    };
+    let addr = SocketAddr::from(([127, 0, 0, 1], config.port));
+
+    Some(tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("HTTP API failed to listen on {}: {}", addr, e);
+                return;
+            }
+        };
+        info!("🌐 HTTP API listening on http://{}", addr);
+        if let Err(e) = axum::serve(listener, router(api)).await {
+            error!("HTTP API error: {}", e);
+        }
+    }))
+}
+
+fn router(api: Api) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/transcribe", post(transcribe))
+        .route("/history", get(history))
+        .route("/dictation/start", post(start_dictation))
+        .route("/dictation/stop", post(stop_dictation))
+        .route("/events", get(events))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .layer(middleware::from_fn_with_state(api.clone(), guard))
+        .with_state(api)
+}
+
+/// Check the host, origin, and token of a request, and add CORS headers
+/// for allowed origins
+async fn guard(State(api): State<Api>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let host = headers.get(header::HOST).and_then(|h| h.to_str().ok());
+    if !host.is_some_and(is_loopback_host) {
+        return error_response(StatusCode::FORBIDDEN, "Host must be localhost");
+    }
+
+    let origin = headers.get(header::ORIGIN).cloned();
+    if let Some(origin) = &origin {
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| api.allowed_origins.iter().any(|o| o == origin));
+        if !allowed {
+            return error_response(StatusCode::FORBIDDEN, "Origin not allowed");
+        }
+    }
+
+    // Preflights carry no credentials
+    let mut response = if request.method() == Method::OPTIONS {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, POST"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("authorization, content-type"),
+        );
+        response
+    } else {
+        if !token_matches(
+            request_token(request.headers(), request.uri().query()),
+            &api.token,
+        ) {
+            return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid token");
+        }
+        next.run(request).await
+    };
+
+    if let Some(origin) = origin {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+    }
+    response
+}
+
+async fn status(State(api): State<Api>) -> Response {
+    match api.dispatch(Command::GetStatus).await {
+        IpcResponse::Status(status) => Json(status).into_response(),
+        other => into_response(other),
+    }
+}
+
+async fn transcribe(State(api): State<Api>, headers: HeaderMap, body: Bytes) -> Response {
+    if body.is_empty() {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Request body must be an audio file",
+        );
+    }
+
+    // The decoder picks a format by extension
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok());
+    let upload = tempfile::Builder::new()
+        .prefix("onevox-upload-")
+        .suffix(&format!(".{}", audio_extension(content_type)))
+        .tempfile();
+    let upload = match upload {
+        Ok(upload) => upload,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+    if let Err(e) = tokio::fs::write(upload.path(), &body).await {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
+    }
+
+    let path = upload.path().to_string_lossy().into_owned();
+    match api.dispatch(Command::TranscribeFile { path }).await {
+        IpcResponse::Ok(text) => Json(serde_json::json!({ "text": text })).into_response(),
+        other => into_response(other),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    /// Most entries returned (newest first when not searching)
+    limit: Option<usize>,
+    /// Search text
+    q: Option<String>,
+}
+
+async fn history(State(api): State<Api>, Query(params): Query<HistoryParams>) -> Response {
+    let limit = params.limit.unwrap_or(0);
+    if let Some(text) = params.q.filter(|q| !q.is_empty()) {
+        let query = crate::history::HistoryQuery {
+            text,
+            limit,
+            ..Default::default()
+        };
+        return match api.dispatch(Command::SearchHistory { query }).await {
+            IpcResponse::HistoryMatches(matches) => Json(matches).into_response(),
+            other => into_response(other),
+        };
+    }
+
+    match api.dispatch(Command::GetHistory).await {
+        IpcResponse::History(mut entries) => {
+            entries.reverse();
+            if limit > 0 {
+                entries.truncate(limit);
+            }
+            Json(entries).into_response()
+        }
+        other => into_response(other),
+    }
+}
+
+async fn start_dictation(State(api): State<Api>) -> Response {
+    into_response(api.dispatch(Command::StartDictation).await)
+}
+
+async fn stop_dictation(State(api): State<Api>) -> Response {
+    into_response(api.dispatch(Command::StopDictation).await)
+}
+
+async fn events(State(api): State<Api>, ws: WebSocketUpgrade) -> Response {
+    let events = api.state.read().await.events().subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events))
+}
+
+/// Forward transcription events to a WebSocket until either side closes
+async fn stream_events(mut socket: WebSocket, mut events: broadcast::Receiver<Event>) {
+    debug!("HTTP API event subscriber connected");
+    loop {
+        tokio::select! {
+            received = events.recv() => {
+                let event = match received {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("HTTP API event subscriber lagged by {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let Some(event) = TranscriptEvent::from_event(&event) else {
+                    continue;
+                };
+                let Ok(json) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("HTTP API event subscriber disconnected");
+}
+
+impl Api {
+    /// Run a command through the IPC handler and record it
+    async fn dispatch(&self, command: Command) -> IpcResponse {
+        let name = command.name();
+        let sequence = self.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        let request_id = generate_request_id(sequence);
+        debug!("HTTP API request {}: {}", request_id, name);
+
+        let response = IpcServer::handle_command(command, &self.state).await;
+        let is_error = matches!(response, IpcResponse::Error(_));
+        self.state
+            .write()
+            .await
+            .record_request(CLIENT_NAME, 0, &request_id, name, is_error);
+        response
+    }
+}
+
+fn into_response(response: IpcResponse) -> Response {
+    match response {
+        IpcResponse::Success => Json(serde_json::json!({ "ok": true })).into_response(),
+        IpcResponse::Ok(message) => {
+            Json(serde_json::json!({ "ok": true, "message": message })).into_response()
+        }
+        IpcResponse::Error(message) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &message),
+        other => {
+            warn!("HTTP API got an unexpected response: {:?}", other);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Unexpected response")
+        }
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Whether a `Host` header names the loopback interface
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "localhost" | "127.0.0.1" | "::1"
+    )
+}
+
+/// Token sent as a bearer `Authorization` header or a `token` query
+/// parameter
+fn request_token<'a>(headers: &'a HeaderMap, query: Option<&'a str>) -> Option<&'a str> {
+    if let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+    {
+        return Some(token.trim());
+    }
+    query?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Whether the sent token is the API token, compared in constant time
+/// (digests are compared, so the length doesn't leak either)
+fn token_matches(sent: Option<&str>, token: &str) -> bool {
+    let Some(sent) = sent else {
+        return false;
+    };
+    let (sent, token) = (Sha256::digest(sent), Sha256::digest(token));
+    sent.iter()
+        .zip(token.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Token saved in the config directory, generated on first use
+fn generated_token() -> crate::Result<String> {
+    let path = crate::platform::paths::config_dir()?.join(TOKEN_FILE);
+    if let Ok(token) = std::fs::read_to_string(&path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| crate::Error::Other(format!("Failed to generate an API token: {}", e)))?;
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(token.as_bytes())?;
+    info!("🔑 Generated an HTTP API token in {:?}", path);
+    Ok(token)
+}
+
+/// File extension for an uploaded audio `Content-Type` (WAV when unknown)
+fn audio_extension(content_type: Option<&str>) -> &'static str {
+    let mime = content_type
+        .and_then(|c| c.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/ogg" | "audio/opus" => "ogg",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "video/mp4" => "mp4",
+        "video/quicktime" => "mov",
+        _ => "wav",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_checks() {
+        assert!(is_loopback_host("localhost:7863"));
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]:7863"));
+        assert!(!is_loopback_host("evil.example:7863"));
+        assert!(!is_loopback_host("127.0.0.1.evil.example"));
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            request_token(&headers, Some("limit=5&token=abc")),
+            Some("abc")
+        );
+        assert_eq!(request_token(&headers, Some("limit=5")), None);
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer xyz"),
+        );
+        assert_eq!(request_token(&headers, None), Some("xyz"));
+        assert!(token_matches(Some("xyz"), "xyz"));
+        assert!(!token_matches(Some("xy"), "xyz"));
+        assert!(!token_matches(None, "xyz"));
+
+        assert_eq!(audio_extension(Some("audio/mpeg")), "mp3");
+        assert_eq!(audio_extension(Some("audio/ogg; codecs=opus")), "ogg");
+        assert_eq!(audio_extension(None), "wav");
+    }
+}
@@ -2,13 +2,25 @@
 //!
 //! Handles daemon startup, shutdown, and lifecycle events.
 
+use crate::audio::SoundFeedback;
 use crate::config::Config;
 use crate::daemon::dictation::DictationEngine;
+use crate::daemon::startup::{self, Attempt, StartupPolicy};
 use crate::daemon::state::DaemonState;
-use crate::ipc::{IpcClient, IpcServer};
+use crate::daemon::upgrade;
+use crate::history::BackupManager;
+use crate::ipc::{Capabilities, Event, IpcClient, IpcServer};
+use crate::models::ModelRegistry;
+use crate::models::registry::REMOTE_MAX_AGE;
+use crate::platform::Announcer;
+use crate::platform::notify::Notifier;
+use crate::storage::StorageManager;
+use crate::telemetry::TelemetryRecorder;
 use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 use tokio::signal;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
@@ -36,15 +48,17 @@ impl Lifecycle {
     pub async fn start(&mut self) -> Result<()> {
         info!("🚀 Starting Onevox daemon v{}", env!("CARGO_PKG_VERSION"));
 
-        // Check if daemon is already running
-        if self.is_already_running().await {
+        // Check if daemon is already running (after a live upgrade the socket
+        // is ours but not yet accepting, so a ping would only time out)
+        if !upgrade::has_inherited_listener() && self.is_already_running().await {
             warn!("Daemon is already running");
             return Err(anyhow::anyhow!("Daemon is already running"));
         }
 
         // Initialize IPC server
         let socket_path = IpcClient::default_socket_path();
-        let mut ipc_server = IpcServer::new(socket_path.clone(), Arc::clone(&self.state));
+        let mut ipc_server = IpcServer::new(socket_path.clone(), Arc::clone(&self.state))
+            .with_max_connections(self.config.daemon.max_ipc_connections);
 
         ipc_server
             .start()
@@ -53,24 +67,64 @@ impl Lifecycle {
 
         info!("✅ IPC server started at {:?}", socket_path);
 
+        if let Err(e) = write_pid_file() {
+            warn!("Failed to write PID file: {}", e);
+        }
+
+        // Follow the focused widget from the start, so the first injection
+        // already knows whether it's headed for a password field
+        crate::platform::focus::track_focus();
+
         // Mark daemon as ready
         {
             let mut state = self.state.write().await;
             state.set_ready();
+            state.events().publish(Event::Started);
         }
 
         info!("✅ Onevox daemon is ready");
 
         // Run the event loop
-        self.run_event_loop(ipc_server).await?;
-
-        Ok(())
+        let result = self.run_event_loop(ipc_server).await;
+        if let Err(e) = remove_pid_file() {
+            warn!("Failed to remove PID file: {}", e);
+        }
+        result
     }
 
     /// Run the main event loop
     async fn run_event_loop(&self, mut ipc_server: IpcServer) -> Result<()> {
         info!("📡 Starting event loop");
 
+        // Internal event consumers: logging, metrics, screen reader,
+        // notifications, sound cues, telemetry
+        let events = self.state.read().await.events().clone();
+        let mut consumer_handles = vec![
+            events.spawn_logger(),
+            events.spawn_metrics(),
+            events.spawn_announcer(Announcer::new(&self.config.accessibility)),
+        ];
+        if self.config.notifications.enabled {
+            consumer_handles.push(events.spawn_notifier(Notifier::new(&self.config.notifications)));
+        }
+        if self.config.ui.sound_feedback.enabled {
+            consumer_handles
+                .push(events.spawn_feedback(SoundFeedback::new(&self.config.ui.sound_feedback)));
+        }
+        if self.config.telemetry.enabled {
+            match TelemetryRecorder::open(self.config.telemetry.retention_days) {
+                Ok(recorder) => consumer_handles.push(events.spawn_telemetry(recorder)),
+                Err(e) => warn!("Local telemetry disabled: {}", e),
+            }
+        }
+
+        // Kept for a live upgrade, which outlives the server task
+        let in_flight = ipc_server.in_flight();
+        let ipc_sessions = ipc_server.sessions();
+        let ipc_shutdown = ipc_server.shutdown_token();
+        #[cfg(unix)]
+        let listener_fd = ipc_server.listener_fd();
+
         // Spawn IPC server task
         let ipc_handle = tokio::spawn(async move {
             if let Err(e) = ipc_server.run().await {
@@ -78,19 +132,53 @@ impl Lifecycle {
             }
         });
 
+        // Back up history once a day (checked hourly for long-running daemons)
+        let backup_handle = self.spawn_history_backups();
+
+        // Keep retained audio, logs, and models within their storage caps
+        let storage_handle = self.spawn_storage_cleanup();
+
+        // Keep the cached remote model registry fresh
+        let registry_handle = self.spawn_registry_refresh();
+
+        // Transcribe new screen recordings into sidecar files
+        let recordings_handle = crate::daemon::screen_recordings::spawn(&self.config);
+
+        // Serve the localhost HTTP API
+        let api_handle = self.spawn_http_api();
+
         // Initialize and start dictation engine in the background
-        // We'll use a separate thread since HotkeyManager is not Send
+        // We'll use a separate thread since HotkeyManager is not Send.
+        // Subsystems whose `[startup]` policy is fail-fast report here.
+        let (fatal_tx, mut fatal_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
         let config = self.config.clone();
         let state_clone = Arc::clone(&self.state);
+        let dictation_events = events.clone();
         let _dictation_handle = std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
             rt.block_on(async {
                 // Get history manager from state
-                let history_manager = {
+                let (history_manager, undo_stack, held_injection, config_updates, last_transcript, startup, metrics, privacy) = {
                     let state = state_clone.read().await;
-                    Arc::clone(state.history_manager())
+                    (
+                        Arc::clone(state.history_manager()),
+                        Arc::clone(state.undo_stack()),
+                        Arc::clone(state.held_injection()),
+                        state.config_updates(),
+                        Arc::clone(state.last_transcript()),
+                        state.startup().clone(),
+                        state.metrics().clone(),
+                        state.privacy().clone(),
+                    )
                 };
 
+                if !Capabilities::current().can_dictate() {
+                    info!("🖥️  Headless build: dictation disabled (audio capture not compiled in)");
+                    startup.disabled(startup::MODEL);
+                    startup.disabled(startup::HOTKEYS);
+                    return;
+                }
+
                 // Create command channel for IPC control
                 let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -100,26 +188,57 @@ impl Lifecycle {
                     state.set_dictation_channel(cmd_tx);
                 }
 
-                // Try to initialize dictation engine with retries
-                let mut retry_count = 0;
-                let max_retries = 3;
+                // The whisper-server backend may need its binary installed
+                if let Err(e) = crate::models::server_binary::ensure(&config.model).await {
+                    warn!("Failed to install whisper-server: {:#}", e);
+                }
 
+                // Initialize the dictation engine, retrying per startup.model
                 loop {
-                    match DictationEngine::with_history(config.clone(), Arc::clone(&history_manager)) {
-                        Ok(mut engine) => {
+                    match DictationEngine::with_events(config.clone(), Arc::clone(&history_manager), dictation_events.clone()) {
+                        Ok(engine) => {
+                            let mut engine = engine
+                                .with_undo_stack(Arc::clone(&undo_stack))
+                                .with_held_injection(Arc::clone(&held_injection))
+                                .with_config_updates(config_updates.clone())
+                                .with_last_transcript(Arc::clone(&last_transcript))
+                                .with_startup(startup.clone())
+                                .with_metrics(metrics.clone())
+                                .with_privacy(privacy.clone());
+                            startup.ready(startup::MODEL);
                             info!("✅ Dictation engine initialized");
 
                             // Start the engine's hotkey listener in a background thread
                             // This engine instance handles hotkey events
                             let config_for_hotkey = config.clone();
                             let history_for_hotkey = Arc::clone(&history_manager);
+                            let events_for_hotkey = dictation_events.clone();
+                            let undo_for_hotkey = Arc::clone(&undo_stack);
+                            let held_for_hotkey = Arc::clone(&held_injection);
+                            let updates_for_hotkey = config_updates.clone();
+                            let last_for_hotkey = Arc::clone(&last_transcript);
+                            let startup_for_hotkey = startup.clone();
+                            let metrics_for_hotkey = metrics.clone();
+                            let privacy_for_hotkey = privacy.clone();
+                            let fatal_for_hotkey = fatal_tx.clone();
                             std::thread::spawn(move || {
                                 let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
                                 rt.block_on(async {
-                                    match DictationEngine::with_history(config_for_hotkey, history_for_hotkey) {
-                                        Ok(mut hotkey_engine) => {
+                                    match DictationEngine::with_events(config_for_hotkey, history_for_hotkey, events_for_hotkey) {
+                                        Ok(hotkey_engine) => {
+                                            let mut hotkey_engine = hotkey_engine
+                                                .with_undo_stack(undo_for_hotkey)
+                                                .with_held_injection(held_for_hotkey)
+                                                .with_config_updates(updates_for_hotkey)
+                                                .with_last_transcript(last_for_hotkey)
+                                                .with_startup(startup_for_hotkey.clone())
+                                                .with_metrics(metrics_for_hotkey)
+                                                .with_privacy(privacy_for_hotkey);
                                             if let Err(e) = hotkey_engine.start().await {
-                                                error!("Dictation engine hotkey listener error: {}", e);
+                                                error!("Dictation engine hotkey listener error: {:#}", e);
+                                                if startup_for_hotkey.policy(startup::HOTKEYS) == StartupPolicy::FailFast {
+                                                    let _ = fatal_for_hotkey.send(format!("{:#}", e));
+                                                }
                                             }
                                         }
                                         Err(e) => {
@@ -131,7 +250,19 @@ impl Lifecycle {
 
                             // Listen for IPC commands in the main loop
                             // This engine instance handles IPC commands
-                            while let Some(cmd) = cmd_rx.recv().await {
+                            loop {
+                                let cmd = tokio::select! {
+                                    cmd = cmd_rx.recv() => match cmd {
+                                        Some(cmd) => cmd,
+                                        None => break,
+                                    },
+                                    // Sessions started over IPC stop from the overlay too
+                                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
+                                        engine.enforce_overlay_stop().await;
+                                        continue;
+                                    }
+                                };
+                                engine.refresh_config();
                                 match cmd {
                                     crate::daemon::state::DictationCommand::Start => {
                                         info!("📡 IPC command: Start dictation");
@@ -145,12 +276,33 @@ impl Lifecycle {
                                             error!("Failed to stop dictation: {}", e);
                                         }
                                     }
+                                    crate::daemon::state::DictationCommand::Pause(paused) => {
+                                        info!("📡 IPC command: {} dictation", if paused { "Pause" } else { "Resume" });
+                                        if let Err(e) = engine.set_paused(paused) {
+                                            error!("Failed to pause dictation: {}", e);
+                                        }
+                                    }
+                                    crate::daemon::state::DictationCommand::PrivacyPaused => {
+                                        engine.enforce_privacy().await;
+                                    }
+                                    crate::daemon::state::DictationCommand::DeviceChanged => {
+                                        engine.follow_device();
+                                    }
+                                    crate::daemon::state::DictationCommand::TranscribeFile { path, reply } => {
+                                        info!("📡 IPC command: Transcribe {}", path.display());
+                                        let result = engine
+                                            .transcribe_file(path)
+                                            .await
+                                            .map_err(|e| crate::Error::Model(format!("{:#}", e)));
+                                        let _ = reply.send(result);
+                                    }
                                 }
                             }
                             break;
                         }
                         Err(e) => {
                             let error_msg = e.to_string();
+                            let attempt = startup.failed(startup::MODEL, &format!("{:#}", e));
 
                             // Check if this is a model-related error (missing model file)
                             let is_model_error = error_msg.contains("Model file not found")
@@ -158,49 +310,62 @@ impl Lifecycle {
                                 || error_msg.contains("Download GGML models")
                                 || error_msg.contains("Model download incomplete");
 
-                            if retry_count == 0 {
+                            if attempt == Attempt::Exit {
+                                error!("❌ Dictation engine failed to start: {:#}", e);
+                                error!("   startup.model = \"fail-fast\"; shutting down");
+                                let _ = fatal_tx.send(format!("{:#}", e));
+                                break;
+                            }
+
+                            let attempts = startup
+                                .snapshot()
+                                .iter()
+                                .find(|s| s.name == startup::MODEL)
+                                .map(|s| s.attempts)
+                                .unwrap_or(1);
+                            if attempts == 1 {
                                 error!("Failed to create dictation engine: {}", e);
 
-                                // Only show permission hints for non-model errors
-                                if !is_model_error {
-                                    error!("⚠️  This is usually a permission issue. Please grant:");
-                                    error!("   1. Input Monitoring permission");
-                                    error!("   2. Accessibility permission");
-                                    #[cfg(target_os = "macos")]
-                                    error!("   Then restart: launchctl kickstart -k gui/$(id -u)/com.onevox.daemon");
-                                    #[cfg(target_os = "linux")]
-                                    error!("   Then restart: systemctl --user restart onevox");
-                                    #[cfg(target_os = "windows")]
-                                    error!("   Then restart: onevox stop && onevox daemon --foreground");
+                                if is_model_error {
+                                    error!("   Download a model; the daemon keeps serving IPC meanwhile");
+                                } else {
+                                    // Only show permission hints for non-model errors
+                                    let denied: Vec<_> = crate::platform::check_required_permissions()
+                                        .into_iter()
+                                        .filter(|(_, status)| *status == crate::platform::PermissionStatus::Denied)
+                                        .collect();
+                                    for (permission, _) in &denied {
+                                        error!("⚠️  {} permission denied: {}", permission, permission.fix_hint());
+                                    }
+                                    if denied.is_empty() {
+                                        error!("   Run `onevox doctor` to diagnose the installation");
+                                    }
                                 }
                             }
 
-                            // Don't retry for model errors - they won't fix themselves
-                            if is_model_error {
-                                error!("❌ Cannot start without a valid model");
+                            if attempt == Attempt::GiveUp {
+                                error!("❌ Dictation engine failed after {} attempts", attempts);
                                 error!("   Daemon will continue running but dictation won't work");
-                                error!("   Download a model and restart the daemon");
-                                break;
-                            }
-
-                            retry_count += 1;
-                            if retry_count >= max_retries {
-                                error!("❌ Dictation engine failed after {} attempts", max_retries);
-                                error!("   Daemon will continue running but hotkeys won't work");
-                                error!("   Grant permissions and restart the daemon");
+                                #[cfg(target_os = "macos")]
+                                error!("   Fix the problem, then restart: launchctl kickstart -k gui/$(id -u)/com.onevox.daemon");
+                                #[cfg(target_os = "linux")]
+                                error!("   Fix the problem, then restart: systemctl --user restart onevox");
+                                #[cfg(target_os = "windows")]
+                                error!("   Fix the problem, then restart: onevox stop && onevox daemon --foreground");
                                 break;
                             }
 
                             // Wait before retry
-                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                            info!("🔄 Retrying dictation engine initialization ({}/{})", retry_count, max_retries);
+                            tokio::time::sleep(startup.retry_interval()).await;
+                            info!("🔄 Retrying dictation engine initialization (attempt {})", attempts + 1);
                         }
                     }
                 }
             });
         });
 
-        // Wait for shutdown signal
+        // Wait for shutdown signal (or a fail-fast startup failure)
+        let mut fatal = None;
         tokio::select! {
             _ = self.wait_for_shutdown_signal() => {
                 info!("Shutdown signal received");
@@ -208,6 +373,25 @@ impl Lifecycle {
             _ = self.wait_for_state_shutdown() => {
                 info!("Shutdown requested via IPC");
             }
+            Some(reason) = fatal_rx.recv() => {
+                error!("Fail-fast startup check failed; shutting down");
+                fatal = Some(reason);
+            }
+            binary = self.wait_for_upgrade_request() => {
+                // Stop accepting; new connections wait in the socket backlog
+                ipc_handle.abort();
+                info!("🔁 Live upgrade: draining before switching to {:?}", binary);
+                upgrade::drain(&in_flight, &events, upgrade::DRAIN_TIMEOUT).await;
+
+                #[cfg(unix)]
+                match &listener_fd {
+                    Some(fd) => {
+                        let e = upgrade::exec_successor(&binary, fd);
+                        error!("Live upgrade failed, shutting down instead: {}", e);
+                    }
+                    None => error!("Live upgrade failed: no listening socket to hand over"),
+                }
+            }
         }
 
         // Cleanup
@@ -215,16 +399,150 @@ impl Lifecycle {
         {
             let mut state = self.state.write().await;
             state.shutdown();
+            // Partial files stay behind so downloads resume after a restart
+            state.downloads().pause_all();
+        }
+        events.publish(Event::ShuttingDown);
+
+        // Tell connected clients and give them a moment to disconnect
+        ipc_shutdown.cancel();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(1);
+        while ipc_sessions.load(Ordering::SeqCst) > 0 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
         }
 
         // Abort tasks
         ipc_handle.abort();
+        for handle in consumer_handles {
+            handle.abort();
+        }
+        if let Some(handle) = backup_handle {
+            handle.abort();
+        }
+        if let Some(handle) = storage_handle {
+            handle.abort();
+        }
+        if let Some(handle) = registry_handle {
+            handle.abort();
+        }
+        if let Some(handle) = recordings_handle {
+            handle.abort();
+        }
+        if let Some(handle) = api_handle {
+            handle.abort();
+        }
         // Note: dictation_handle will be cleaned up when the thread exits
 
+        if let Some(reason) = fatal {
+            return Err(anyhow::anyhow!("Startup failed: {}", reason));
+        }
+
         info!("✅ Daemon stopped");
         Ok(())
     }
 
+    /// Spawn the HTTP API server if `[api]` enables it
+    fn spawn_http_api(&self) -> Option<tokio::task::JoinHandle<()>> {
+        #[cfg(feature = "http-api")]
+        {
+            crate::daemon::http_api::spawn(&self.config.api, Arc::clone(&self.state))
+        }
+
+        #[cfg(not(feature = "http-api"))]
+        {
+            if self.config.api.enabled {
+                warn!("[api] is enabled but this build lacks the http-api feature");
+            }
+            None
+        }
+    }
+
+    /// Spawn the daily history backup task
+    /// Refresh the remote model registry whenever its cache is a day old
+    /// (checked hourly)
+    fn spawn_registry_refresh(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let url = self.config.model.registry_url.clone()?;
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                if !ModelRegistry::remote_cache_stale(REMOTE_MAX_AGE) {
+                    continue;
+                }
+                if let Err(e) = ModelRegistry::refresh_remote(&url).await {
+                    warn!("Model registry refresh failed: {:#}", e);
+                }
+            }
+        }))
+    }
+
+    fn spawn_history_backups(&self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.config.backup.enabled {
+            return None;
+        }
+
+        let state = Arc::clone(&self.state);
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+
+                let (backup_config, history_manager) = {
+                    let state = state.read().await;
+                    (
+                        state.config().backup.clone(),
+                        Arc::clone(state.history_manager()),
+                    )
+                };
+
+                let entries = match history_manager.get_all().await {
+                    Ok(entries) if !entries.is_empty() => entries,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("History backup skipped: {}", e);
+                        continue;
+                    }
+                };
+
+                match BackupManager::from_config(&backup_config)
+                    .and_then(|backups| backups.backup_daily(&entries))
+                {
+                    Ok(Some(path)) => info!("💾 History backed up to {:?}", path),
+                    Ok(None) => {}
+                    Err(e) => warn!("History backup failed: {}", e),
+                }
+            }
+        }))
+    }
+
+    /// Spawn the periodic storage cap enforcement task
+    fn spawn_storage_cleanup(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let minutes = self.config.storage.cleanup_interval_minutes;
+        if minutes == 0 {
+            return None;
+        }
+
+        let state = Arc::clone(&self.state);
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(minutes * 60));
+            loop {
+                interval.tick().await;
+
+                let storage_config = state.read().await.config().storage.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    StorageManager::new(storage_config).enforce_caps()
+                })
+                .await;
+
+                match result {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => warn!("Storage cleanup failed: {}", e),
+                    Err(e) => warn!("Storage cleanup task panicked: {}", e),
+                }
+            }
+        }))
+    }
+
     /// Wait for OS shutdown signal (SIGTERM, SIGINT)
     async fn wait_for_shutdown_signal(&self) {
         #[cfg(unix)]
@@ -266,9 +584,19 @@ impl Lifecycle {
         }
     }
 
+    /// Wait for a live upgrade request from state
+    async fn wait_for_upgrade_request(&self) -> PathBuf {
+        loop {
+            if let Some(binary) = self.state.read().await.upgrade_request() {
+                return binary.to_path_buf();
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    }
+
     /// Check if daemon is already running
     async fn is_already_running(&self) -> bool {
-        let mut client = IpcClient::default();
+        let mut client = IpcClient::default().with_client_name("onevox-daemon");
         client.ping().await.unwrap_or(false)
     }
 
@@ -344,6 +672,29 @@ pub fn write_pid_file() -> Result<()> {
     Ok(())
 }
 
+/// Read the PID file
+pub fn read_pid_file() -> Option<u32> {
+    std::fs::read_to_string(pid_file_path())
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+}
+
+/// PID of the daemon in the PID file, if that process is still alive
+///
+/// A PID file whose process is gone is stale and gets removed.
+pub fn check_pid_file() -> Option<u32> {
+    let pid = read_pid_file()?;
+    let mut system = sysinfo::System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[sys_pid]), true);
+    if system.process(sys_pid).is_some() {
+        return Some(pid);
+    }
+    warn!("Removing stale PID file (pid {} is not running)", pid);
+    let _ = remove_pid_file();
+    None
+}
+
 /// Remove PID file
 pub fn remove_pid_file() -> Result<()> {
     let pid_path = pid_file_path();
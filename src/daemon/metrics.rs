@@ -0,0 +1,143 @@
+//! Pipeline Metrics
+//!
+//! Dictation performance for `onevox status`: end-to-end latency from the
+//! end of speech (the VAD closing a segment, or the hotkey release without
+//! the VAD) to the text being injected, VAD segments, audio chunks dropped
+//! because transcription fell behind, each model's real-time factor, and
+//! the memory loading the model added to the daemon. Both dictation
+//! engines (IPC and hotkeys) record into the daemon's shared copy.
+
+use crate::ipc::protocol::{ModelStats, PipelineStats};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// Latencies kept for the average and percentile
+const LATENCY_WINDOW: usize = 200;
+
+/// Shared pipeline counters
+///
+/// Cheap to clone; clones share the counters.
+#[derive(Clone, Default)]
+pub struct PipelineMetrics {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    latencies_ms: VecDeque<u64>,
+    vad_segments: u64,
+    dropped_chunks: u64,
+    models: BTreeMap<String, ModelStats>,
+    model_memory_bytes: Option<u64>,
+}
+
+impl PipelineMetrics {
+    /// Record the time from the end of speech to its text being injected
+    pub fn record_latency(&self, latency: Duration) {
+        let mut inner = self.inner.lock();
+        if inner.latencies_ms.len() == LATENCY_WINDOW {
+            inner.latencies_ms.pop_front();
+        }
+        inner.latencies_ms.push_back(latency.as_millis() as u64);
+    }
+
+    /// Count a speech segment closed by the VAD
+    pub fn record_segment(&self) {
+        self.inner.lock().vad_segments += 1;
+    }
+
+    /// Count audio chunks the capture dropped
+    pub fn record_dropped(&self, chunks: u64) {
+        self.inner.lock().dropped_chunks += chunks;
+    }
+
+    /// Record a transcription of `audio_ms` of audio by `model`
+    pub fn record_transcription(&self, model: &str, audio_ms: u64, processing_ms: u64) {
+        let mut inner = self.inner.lock();
+        let stats = inner
+            .models
+            .entry(model.to_string())
+            .or_insert_with(|| ModelStats {
+                model: model.to_string(),
+                ..Default::default()
+            });
+        stats.transcriptions += 1;
+        stats.audio_ms += audio_ms;
+        stats.processing_ms += processing_ms;
+    }
+
+    /// Memory the loaded model takes
+    pub fn set_model_memory(&self, bytes: u64) {
+        self.inner.lock().model_memory_bytes = Some(bytes);
+    }
+
+    /// Current figures
+    pub fn snapshot(&self) -> PipelineStats {
+        let inner = self.inner.lock();
+        let mut sorted: Vec<u64> = inner.latencies_ms.iter().copied().collect();
+        sorted.sort_unstable();
+        let (latency_avg_ms, latency_p95_ms) = if sorted.is_empty() {
+            (None, None)
+        } else {
+            let average = sorted.iter().sum::<u64>() / sorted.len() as u64;
+            // Nearest-rank percentile
+            let rank = (sorted.len() * 95).div_ceil(100).max(1);
+            (Some(average), Some(sorted[rank - 1]))
+        };
+        PipelineStats {
+            latency_avg_ms,
+            latency_p95_ms,
+            latency_samples: sorted.len(),
+            vad_segments: inner.vad_segments,
+            dropped_chunks: inner.dropped_chunks,
+            models: inner.models.values().cloned().collect(),
+            model_memory_bytes: inner.model_memory_bytes,
+        }
+    }
+}
+
+/// Resident memory of this process in bytes (0 if unavailable)
+pub fn process_memory() -> u64 {
+    let pid = Pid::from_u32(std::process::id());
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), false);
+    system.process(pid).map(|p| p.memory()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_metrics() {
+        let metrics = PipelineMetrics::default();
+        assert_eq!(metrics.snapshot().latency_avg_ms, None);
+
+        for ms in 1..=100 {
+            metrics.record_latency(Duration::from_millis(ms));
+        }
+        metrics.record_segment();
+        metrics.record_dropped(3);
+        metrics.record_transcription("ggml-base.en", 4000, 1000);
+        metrics.record_transcription("ggml-base.en", 2000, 500);
+
+        let stats = metrics.clone().snapshot();
+        assert_eq!(stats.latency_avg_ms, Some(50));
+        assert_eq!(stats.latency_p95_ms, Some(95));
+        assert_eq!(stats.latency_samples, 100);
+        assert_eq!(stats.vad_segments, 1);
+        assert_eq!(stats.dropped_chunks, 3);
+        assert_eq!(stats.models.len(), 1);
+        assert_eq!(stats.models[0].transcriptions, 2);
+        assert_eq!(stats.models[0].rtf(), 0.25);
+
+        // Only the most recent latencies count
+        for _ in 0..LATENCY_WINDOW {
+            metrics.record_latency(Duration::from_millis(10));
+        }
+        assert_eq!(metrics.snapshot().latency_p95_ms, Some(10));
+    }
+}
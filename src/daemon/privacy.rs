@@ -0,0 +1,189 @@
+//! Privacy Controls
+//!
+//! `onevox privacy pause` turns off all capture until `onevox privacy
+//! resume` or until the pause runs out: sessions in progress stop and new
+//! ones are refused. `[privacy].blocked_apps` keeps dictation out of chosen
+//! applications: while one of them is focused, dictation hotkeys are
+//! ignored and nothing is recorded. Entries match the focused app's name,
+//! bundle ID, or window class, or its window title, case-insensitively as
+//! substrings. The pause lives in daemon memory and ends with a restart.
+
+use crate::ipc::protocol::PrivacyStatus;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Shared privacy pause
+///
+/// Cheap to clone; clones share the pause.
+#[derive(Debug, Clone, Default)]
+pub struct Privacy {
+    pause: Arc<Mutex<Option<Pause>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pause {
+    /// When the pause ends by itself (`None` = until resumed)
+    until: Option<SystemTime>,
+}
+
+impl Privacy {
+    /// Pause capture for `duration`, or until resumed
+    pub fn pause(&self, duration: Option<Duration>) {
+        *self.pause.lock() = Some(Pause {
+            until: duration.map(|duration| SystemTime::now() + duration),
+        });
+    }
+
+    /// End the pause; returns whether one was active
+    pub fn resume(&self) -> bool {
+        let was_paused = self.is_paused();
+        *self.pause.lock() = None;
+        was_paused
+    }
+
+    /// Whether capture is paused right now
+    pub fn is_paused(&self) -> bool {
+        self.status().paused
+    }
+
+    /// Pause state for `onevox status`, clearing a pause that ran out
+    pub fn status(&self) -> PrivacyStatus {
+        let mut pause = self.pause.lock();
+        if let Some(Pause { until: Some(until) }) = *pause
+            && until <= SystemTime::now()
+        {
+            *pause = None;
+        }
+        PrivacyStatus {
+            paused: pause.is_some(),
+            resume_at: pause
+                .and_then(|pause| pause.until)
+                .and_then(|until| until.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_secs()),
+        }
+    }
+}
+
+/// The blocklist entry matching the focused app or window title, if any
+pub fn blocked_app<'a>(
+    blocklist: &'a [String],
+    app: Option<&str>,
+    title: Option<&str>,
+) -> Option<&'a str> {
+    let app = app.map(str::to_lowercase);
+    let title = title.map(str::to_lowercase);
+    blocklist
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .find(|entry| {
+            let entry = entry.to_lowercase();
+            [&app, &title]
+                .into_iter()
+                .flatten()
+                .any(|name| name.contains(&entry))
+        })
+}
+
+/// Parse a pause length: "90s", "30m", "2h", "1h30m", or bare minutes
+pub fn parse_duration(value: &str) -> crate::Result<Duration> {
+    let invalid = || {
+        crate::Error::Config(format!(
+            "Invalid duration '{}' (expected e.g. 90s, 30m, 2h, or 1h30m)",
+            value
+        ))
+    };
+    let value = value.trim().to_lowercase();
+    if let Ok(minutes) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(minutes * 60));
+    }
+
+    let mut secs = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return Err(invalid()),
+        };
+        secs += number.parse::<u64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    if !number.is_empty() || secs == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Pause length as "1h30m", "45m", or "90s"
+pub fn format_duration(secs: u64) -> String {
+    let (hours, minutes, secs) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    let mut text = String::new();
+    if hours > 0 {
+        text.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        text.push_str(&format!("{}m", minutes));
+    }
+    if secs > 0 || text.is_empty() {
+        text.push_str(&format!("{}s", secs));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_blocklist_and_durations() {
+        let privacy = Privacy::default();
+        assert!(!privacy.is_paused());
+        privacy.pause(Some(Duration::from_secs(60)));
+        let status = privacy.status();
+        assert!(status.paused);
+        assert!(status.resume_at.is_some());
+        assert!(privacy.resume());
+        assert!(!privacy.is_paused());
+
+        // A pause that ran out clears itself
+        privacy.pause(Some(Duration::ZERO));
+        assert!(!privacy.is_paused());
+        privacy.pause(None);
+        assert_eq!(privacy.status().resume_at, None);
+        assert!(privacy.is_paused());
+
+        let blocklist = vec!["1Password".to_string(), "Private Browsing".to_string()];
+        assert_eq!(
+            blocked_app(&blocklist, Some("com.1password.1password"), None),
+            Some("1Password")
+        );
+        assert_eq!(
+            blocked_app(
+                &blocklist,
+                Some("firefox"),
+                Some("Bank — Mozilla Firefox Private Browsing")
+            ),
+            Some("Private Browsing")
+        );
+        assert_eq!(
+            blocked_app(&blocklist, Some("Slack"), Some("general")),
+            None
+        );
+
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("15").unwrap(), Duration::from_secs(900));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert_eq!(format_duration(5400), "1h30m");
+        assert_eq!(format_duration(90), "1m30s");
+    }
+}
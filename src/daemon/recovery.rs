@@ -0,0 +1,679 @@
+//! Injection Recovery
+//!
+//! Text typed while the dictation target has lost focus either fails
+//! (secure input fields, screen recording prompts) or lands in the wrong
+//! window. Instead of dropping the transcript, delivery is deferred: the
+//! user is notified and the text is injected once the app that had focus
+//! when dictation started is focused again. Transcripts that wait longer
+//! than `injection.refocus_timeout_secs` are reported as failed.
+//!
+//! A focused password field is handled by `injection.secure_fields`: the
+//! transcript is blocked, held until `onevox confirm` types it anyway, or
+//! deferred like a focus change.
+//!
+//! A transcript the model scored below `injection.min_confidence` is
+//! handled by `injection.low_confidence`: held the same way and shown in a
+//! notification, or shown in a review window to insert, discard, or
+//! re-record.
+
+use crate::config::InjectionConfig;
+use crate::daemon::events::EventBus;
+use crate::history::ClipboardRing;
+use crate::indicator::ReviewChoice;
+use crate::ipc::Event;
+use crate::platform::{TextInjector, UndoStack, focus, notify, undo};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// How often focus is checked while a transcript is pending
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Why a transcript could not be injected right away
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeferReason {
+    /// Another app has focus
+    FocusChanged { focused: Option<String> },
+    /// Secure keyboard entry is active
+    SecureInput,
+}
+
+impl std::fmt::Display for DeferReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeferReason::FocusChanged { focused } => write!(
+                f,
+                "focus moved to {}",
+                focused.as_deref().unwrap_or("another app")
+            ),
+            DeferReason::SecureInput => write!(f, "secure input is active"),
+        }
+    }
+}
+
+/// Whether text meant for `target` can be injected given the current focus
+///
+/// An unknown target or focused app is not treated as a focus change, since
+/// focus queries are best-effort.
+pub fn check_target(
+    target: Option<&str>,
+    focused: Option<&str>,
+    secure_input: bool,
+) -> Option<DeferReason> {
+    if secure_input {
+        return Some(DeferReason::SecureInput);
+    }
+    match (target, focused) {
+        (Some(target), Some(focused)) if target != focused => Some(DeferReason::FocusChanged {
+            focused: Some(focused.to_string()),
+        }),
+        _ => None,
+    }
+}
+
+/// What happens to a transcript when a password field has focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecureFieldPolicy {
+    /// Drop it (it stays in history)
+    Block,
+    /// Hold it until `onevox confirm` types it anyway
+    #[default]
+    Confirm,
+    /// Wait for the field to lose focus, as for a focus change
+    Defer,
+}
+
+impl SecureFieldPolicy {
+    /// Parse `injection.secure_fields` ("block", "confirm", or "defer")
+    pub fn parse(policy: &str) -> crate::Result<Self> {
+        match policy.to_ascii_lowercase().as_str() {
+            "block" => Ok(SecureFieldPolicy::Block),
+            "confirm" => Ok(SecureFieldPolicy::Confirm),
+            "defer" => Ok(SecureFieldPolicy::Defer),
+            other => Err(crate::Error::Config(format!(
+                "Unknown secure field policy '{}' (expected block, confirm, or defer)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for SecureFieldPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecureFieldPolicy::Block => write!(f, "block"),
+            SecureFieldPolicy::Confirm => write!(f, "confirm"),
+            SecureFieldPolicy::Defer => write!(f, "defer"),
+        }
+    }
+}
+
+/// What happens to a transcript scored below `injection.min_confidence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LowConfidencePolicy {
+    /// Show it in a notification and hold it until `onevox confirm`
+    #[default]
+    Confirm,
+    /// Show it in a review window: insert, discard, or re-record
+    Review,
+}
+
+impl LowConfidencePolicy {
+    /// Parse `injection.low_confidence` ("confirm" or "review")
+    pub fn parse(policy: &str) -> crate::Result<Self> {
+        match policy.to_ascii_lowercase().as_str() {
+            "confirm" => Ok(LowConfidencePolicy::Confirm),
+            "review" => Ok(LowConfidencePolicy::Review),
+            other => Err(crate::Error::Config(format!(
+                "Unknown low confidence policy '{}' (expected confirm or review)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Whether a transcript's confidence is below `injection.min_confidence`
+/// (0 = off)
+pub fn is_unsure(confidence: f32, min_confidence: f32) -> bool {
+    confidence < min_confidence
+}
+
+/// Transcript kept out of a password field or scored as unsure, awaiting
+/// `onevox confirm`
+#[derive(Debug, Clone)]
+pub struct HeldTranscript {
+    /// Text to inject
+    pub text: String,
+    /// App focused when dictation started
+    pub app: Option<String>,
+    /// When the transcript was held
+    pub since: Instant,
+}
+
+/// Slot for the transcript awaiting confirmation, shared with the daemon
+/// so IPC can confirm it; a newer held transcript replaces an older one
+pub type HeldSlot = Arc<parking_lot::Mutex<Option<HeldTranscript>>>;
+
+/// Type the held transcript into the focused field, even a password field
+/// (`onevox confirm`), or drop it when `discard` is set (blocking)
+pub fn confirm_held(
+    held: &HeldSlot,
+    injector: &TextInjector,
+    undo_stack: &parking_lot::Mutex<UndoStack>,
+    discard: bool,
+) -> crate::Result<HeldTranscript> {
+    let transcript = held
+        .lock()
+        .take()
+        .ok_or_else(|| crate::Error::Other("No transcript is awaiting confirmation".to_string()))?;
+    if discard {
+        info!("🗑️  Discarded held transcript");
+        debug!("Transcript: {}", transcript.text);
+        return Ok(transcript);
+    }
+
+    injector.inject(&transcript.text)?;
+    info!("✅ Confirmed transcript injected");
+    let app = focus::focused_app();
+    if injector.plan_for(app.as_deref()).method.types_text() {
+        undo_stack.lock().push(&transcript.text, app);
+    }
+    Ok(transcript)
+}
+
+/// Outcome of one injection attempt
+enum Attempt {
+    /// Injected; carries the focused app
+    Injected(Option<String>),
+    /// Target not focused, retry later
+    Deferred(DeferReason),
+    /// Injection failed with the target focused
+    Failed(crate::Error),
+}
+
+/// Transcript waiting for its app to regain focus
+#[derive(Debug, Clone)]
+pub struct PendingInjection {
+    /// Text to inject
+    pub text: String,
+    /// App focused when dictation started
+    pub app: Option<String>,
+    /// When the transcript was deferred
+    pub since: Instant,
+}
+
+/// Injects transcripts, deferring them while their app is not focused
+///
+/// Cheap to clone; clones share the pending queue. Pending transcripts are
+/// delivered in order, and new ones queue behind them.
+#[derive(Clone)]
+pub struct InjectionDelivery {
+    injector: TextInjector,
+    undo_stack: Arc<parking_lot::Mutex<UndoStack>>,
+    events: EventBus,
+    timeout: Duration,
+    secure_fields: SecureFieldPolicy,
+    min_confidence: f32,
+    low_confidence: LowConfidencePolicy,
+    held: HeldSlot,
+    /// Set when a review asks to dictate again
+    rerecord: Arc<AtomicBool>,
+    pending: Arc<parking_lot::Mutex<VecDeque<PendingInjection>>>,
+    retrying: Arc<AtomicBool>,
+    /// Receives every transcript once it is injected
+    clipboard_ring: Option<Arc<parking_lot::Mutex<ClipboardRing>>>,
+}
+
+impl InjectionDelivery {
+    /// Create a delivery following `injection.refocus_timeout_secs` and
+    /// `injection.secure_fields`, holding confirmable transcripts in `held`
+    pub fn new(
+        injector: TextInjector,
+        undo_stack: Arc<parking_lot::Mutex<UndoStack>>,
+        held: HeldSlot,
+        events: EventBus,
+        config: &InjectionConfig,
+    ) -> Self {
+        let secure_fields = SecureFieldPolicy::parse(&config.secure_fields).unwrap_or_else(|e| {
+            warn!("{}; using confirm", e);
+            SecureFieldPolicy::Confirm
+        });
+        let low_confidence =
+            LowConfidencePolicy::parse(&config.low_confidence).unwrap_or_else(|e| {
+                warn!("{}; using confirm", e);
+                LowConfidencePolicy::Confirm
+            });
+        Self {
+            injector,
+            undo_stack,
+            events,
+            timeout: Duration::from_secs(config.refocus_timeout_secs),
+            secure_fields,
+            min_confidence: config.min_confidence,
+            low_confidence,
+            held,
+            rerecord: Arc::new(AtomicBool::new(false)),
+            pending: Arc::new(parking_lot::Mutex::new(VecDeque::new())),
+            retrying: Arc::new(AtomicBool::new(false)),
+            clipboard_ring: None,
+        }
+    }
+
+    /// Add injected transcripts to the clipboard ring (held, blocked and
+    /// failed ones are left out)
+    pub fn with_clipboard_ring(
+        mut self,
+        clipboard_ring: Option<Arc<parking_lot::Mutex<ClipboardRing>>>,
+    ) -> Self {
+        self.clipboard_ring = clipboard_ring;
+        self
+    }
+
+    /// Whether a review asked to dictate again since the last call
+    pub fn take_rerecord(&self) -> bool {
+        self.rerecord.swap(false, Ordering::SeqCst)
+    }
+
+    /// Number of transcripts waiting for focus
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    /// Inject `text` into `target` (the app focused when dictation started),
+    /// holding it instead when its `confidence` is too low
+    ///
+    /// Returns why the text was held, kept out of a password field, or not
+    /// typed at all, for history.
+    pub async fn deliver(
+        &self,
+        text: String,
+        target: Option<String>,
+        confidence: Option<f32>,
+    ) -> Option<String> {
+        // Nothing left after voice commands (e.g. "scratch that" alone)
+        if text.is_empty() {
+            return None;
+        }
+        // Backends without a score are never held
+        if let Some(confidence) = confidence
+            && is_unsure(confidence, self.min_confidence)
+        {
+            match self.low_confidence {
+                LowConfidencePolicy::Confirm => {
+                    return Some(self.hold_unsure(text, target, confidence));
+                }
+                LowConfidencePolicy::Review => {
+                    if let Some(outcome) = self.review(&text, &target, confidence).await {
+                        return Some(outcome);
+                    }
+                }
+            }
+        }
+        {
+            let mut pending = self.pending.lock();
+            if !pending.is_empty() {
+                debug!("Queueing transcript behind {} pending", pending.len());
+                pending.push_back(PendingInjection {
+                    text,
+                    app: target,
+                    since: Instant::now(),
+                });
+                return None;
+            }
+        }
+
+        let this = self.clone();
+        let (text_clone, target_clone) = (text.clone(), target.clone());
+        let result =
+            tokio::task::spawn_blocking(move || this.attempt(&text_clone, target_clone.as_deref()))
+                .await;
+
+        match result {
+            Ok(Attempt::Injected(focused)) => self.complete(text, focused),
+            Ok(Attempt::Deferred(DeferReason::SecureInput))
+                if self.secure_fields != SecureFieldPolicy::Defer =>
+            {
+                return Some(self.guard(text, target));
+            }
+            Ok(Attempt::Deferred(reason)) if !self.timeout.is_zero() => {
+                self.defer(text, target, reason)
+            }
+            Ok(Attempt::Deferred(reason)) => {
+                return Some(self.fail(format!("Text not injected: {}", reason)));
+            }
+            Ok(Attempt::Failed(e)) => return Some(self.fail(e.to_string())),
+            Err(e) => return Some(self.fail(format!("Injection task failed: {}", e))),
+        }
+        None
+    }
+
+    /// Remove the newest delivered transcript ("scratch that")
+    ///
+    /// A transcript still waiting for focus is dropped from the queue;
+    /// otherwise the last injection is erased from the focused app.
+    pub async fn scratch(&self) {
+        if let Some(dropped) = self.pending.lock().pop_back() {
+            info!("↩️  Dropped pending transcript");
+            debug!("Transcript: {}", dropped.text);
+            return;
+        }
+
+        let (undo_stack, injector) = (Arc::clone(&self.undo_stack), self.injector.clone());
+        let result =
+            tokio::task::spawn_blocking(move || undo::undo_injections(&undo_stack, &injector, 1))
+                .await;
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!("Scratch that failed: {}", e),
+            Err(e) => error!("Scratch task failed: {}", e),
+        }
+    }
+
+    /// Check focus and inject (blocking)
+    fn attempt(&self, text: &str, target: Option<&str>) -> Attempt {
+        let focused = focus::focused_app();
+        let secure = focus::secure_input_active(focused.as_deref());
+        if let Some(reason) = check_target(target, focused.as_deref(), secure) {
+            return Attempt::Deferred(reason);
+        }
+
+        if let Err(e) = self.injector.inject(text) {
+            // Focus may have been stolen mid-injection (e.g. a permission prompt)
+            let focused = focus::focused_app();
+            let secure = focus::secure_input_active(focused.as_deref());
+            return match check_target(target, focused.as_deref(), secure) {
+                Some(reason) => {
+                    warn!("Injection failed after focus loss: {}", e);
+                    Attempt::Deferred(reason)
+                }
+                None => Attempt::Failed(e),
+            };
+        }
+
+        Attempt::Injected(focused)
+    }
+
+    fn complete(&self, text: String, app: Option<String>) {
+        info!("✅ Text injected successfully");
+        // Text only copied to the clipboard has nothing to erase
+        if self.injector.plan_for(app.as_deref()).method.types_text() {
+            self.undo_stack.lock().push(&text, app);
+        }
+        if let Some(ring) = &self.clipboard_ring {
+            ring.lock().push(&text);
+        }
+        self.events.publish(Event::InjectionComplete { text });
+    }
+
+    /// Report a failed injection, returning what to note in history
+    fn fail(&self, message: String) -> String {
+        error!("Failed to inject text: {}", message);
+        let note = format!("failed: {}", message);
+        self.events.publish(Event::InjectionFailed { message });
+        note
+    }
+
+    /// Keep `text` out of the focused password field per
+    /// `injection.secure_fields`, returning what was done
+    fn guard(&self, text: String, app: Option<String>) -> String {
+        if self.secure_fields == SecureFieldPolicy::Block {
+            warn!("🔒 Password field focused, transcript blocked");
+            notify::notify(
+                "Dictation blocked",
+                "A password field has focus, so the text was not typed. It is kept in history.",
+            );
+            self.events.publish(Event::InjectionFailed {
+                message: "Blocked: a password field has focus".to_string(),
+            });
+            return "blocked: password field focused".to_string();
+        }
+
+        warn!("🔒 Password field focused, holding transcript for confirmation");
+        notify::notify(
+            "Dictation needs confirmation",
+            "A password field has focus. Run `onevox confirm` to type the text anyway.",
+        );
+        self.events.publish(Event::InjectionDeferred {
+            app: app.clone(),
+            reason: "awaiting confirmation: a password field has focus".to_string(),
+        });
+        if self
+            .held
+            .lock()
+            .replace(HeldTranscript {
+                text,
+                app,
+                since: Instant::now(),
+            })
+            .is_some()
+        {
+            debug!("Replaced an older transcript awaiting confirmation");
+        }
+        "held for confirmation: password field focused".to_string()
+    }
+
+    /// Show an unsure transcript in the review window; `None` when the user
+    /// accepts it, else what was done
+    async fn review(&self, text: &str, app: &Option<String>, confidence: f32) -> Option<String> {
+        let percent = (confidence * 100.0).round();
+        info!("🤔 Low confidence ({}%), asking for review", percent);
+        let shown = text.to_string();
+        let choice = tokio::task::spawn_blocking(move || {
+            crate::indicator::review_transcript(&shown, confidence)
+        })
+        .await
+        .map_err(|e| crate::Error::Other(e.to_string()))
+        .and_then(|choice| choice);
+        match choice {
+            Ok(ReviewChoice::Accept) => {
+                info!("✅ Reviewed transcript accepted");
+                None
+            }
+            Ok(ReviewChoice::Discard) => {
+                info!("🗑️  Reviewed transcript discarded");
+                Some(format!(
+                    "discarded in review: low confidence ({}%)",
+                    percent
+                ))
+            }
+            Ok(ReviewChoice::Rerecord) => {
+                info!("🔁 Reviewed transcript discarded to dictate again");
+                self.rerecord.store(true, Ordering::SeqCst);
+                Some(format!(
+                    "re-recorded in review: low confidence ({}%)",
+                    percent
+                ))
+            }
+            Err(e) => {
+                warn!("Review window unavailable ({}), holding instead", e);
+                Some(self.hold_unsure(text.to_string(), app.clone(), confidence))
+            }
+        }
+    }
+
+    /// Hold a transcript scored below `injection.min_confidence`, showing it
+    /// so the user can decide, returning what was done
+    fn hold_unsure(&self, text: String, app: Option<String>, confidence: f32) -> String {
+        let percent = (confidence * 100.0).round();
+        warn!(
+            "🤔 Low confidence ({}%), holding transcript for confirmation",
+            percent
+        );
+        notify::notify(
+            &format!("Dictation unsure ({}%)", percent),
+            &format!(
+                "\"{}\" was not typed. Run `onevox confirm` to type it or `onevox confirm --discard` to drop it.",
+                text
+            ),
+        );
+        self.events.publish(Event::InjectionDeferred {
+            app: app.clone(),
+            reason: format!("awaiting confirmation: low confidence ({}%)", percent),
+        });
+        if self
+            .held
+            .lock()
+            .replace(HeldTranscript {
+                text,
+                app,
+                since: Instant::now(),
+            })
+            .is_some()
+        {
+            debug!("Replaced an older transcript awaiting confirmation");
+        }
+        format!("held for confirmation: low confidence ({}%)", percent)
+    }
+
+    fn defer(&self, text: String, app: Option<String>, reason: DeferReason) {
+        let app_name = app.as_deref().unwrap_or("the original app").to_string();
+        warn!(
+            "⏸️  Holding transcript until {} is focused ({})",
+            app_name, reason
+        );
+        notify::notify(
+            "Dictation pending",
+            &format!(
+                "Text will be inserted when {} is focused again ({})",
+                app_name, reason
+            ),
+        );
+        self.events.publish(Event::InjectionDeferred {
+            app: app.clone(),
+            reason: reason.to_string(),
+        });
+
+        let mut pending = self.pending.lock();
+        pending.push_back(PendingInjection {
+            text,
+            app,
+            since: Instant::now(),
+        });
+        if !self.retrying.swap(true, Ordering::SeqCst) {
+            tokio::spawn(self.clone().retry_pending());
+        }
+    }
+
+    /// Re-inject pending transcripts as their app regains focus
+    async fn retry_pending(self) {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let front = {
+                let pending = self.pending.lock();
+                match pending.front() {
+                    Some(front) => front.clone(),
+                    None => {
+                        self.retrying.store(false, Ordering::SeqCst);
+                        return;
+                    }
+                }
+            };
+
+            if front.since.elapsed() >= self.timeout {
+                self.pending.lock().pop_front();
+                let app_name = front.app.as_deref().unwrap_or("the original app");
+                notify::notify(
+                    "Dictation dropped",
+                    &format!("{} was not focused again in time", app_name),
+                );
+                self.fail(format!(
+                    "Gave up waiting {}s for {} to regain focus",
+                    self.timeout.as_secs(),
+                    app_name
+                ));
+                continue;
+            }
+
+            let this = self.clone();
+            let (text, target) = (front.text.clone(), front.app.clone());
+            let result =
+                tokio::task::spawn_blocking(move || this.attempt(&text, target.as_deref())).await;
+
+            match result {
+                Ok(Attempt::Injected(focused)) => {
+                    self.pending.lock().pop_front();
+                    info!("▶️  Target regained focus, injecting pending transcript");
+                    self.complete(front.text, focused);
+                }
+                Ok(Attempt::Deferred(DeferReason::SecureInput))
+                    if self.secure_fields != SecureFieldPolicy::Defer =>
+                {
+                    self.pending.lock().pop_front();
+                    self.guard(front.text, front.app);
+                }
+                Ok(Attempt::Deferred(reason)) => debug!("Still waiting for focus: {}", reason),
+                Ok(Attempt::Failed(e)) => {
+                    self.pending.lock().pop_front();
+                    self.fail(e.to_string());
+                }
+                Err(e) => {
+                    self.pending.lock().pop_front();
+                    self.fail(format!("Injection task failed: {}", e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_target() {
+        assert_eq!(check_target(Some("Notes"), Some("Notes"), false), None);
+        assert_eq!(
+            check_target(Some("Notes"), Some("Safari"), false),
+            Some(DeferReason::FocusChanged {
+                focused: Some("Safari".into())
+            })
+        );
+        assert_eq!(
+            check_target(Some("Notes"), Some("Notes"), true),
+            Some(DeferReason::SecureInput)
+        );
+
+        // Unknown focus is not a focus change
+        assert_eq!(check_target(None, Some("Safari"), false), None);
+        assert_eq!(check_target(Some("Notes"), None, false), None);
+
+        assert_eq!(
+            SecureFieldPolicy::parse("BLOCK").unwrap(),
+            SecureFieldPolicy::Block
+        );
+        assert_eq!(
+            SecureFieldPolicy::parse(&InjectionConfig::default().secure_fields).unwrap(),
+            SecureFieldPolicy::Confirm
+        );
+        assert!(SecureFieldPolicy::parse("ask").is_err());
+    }
+
+    #[test]
+    fn test_confirm_held() {
+        let held: HeldSlot = Arc::default();
+        let injector = TextInjector::new(Default::default());
+        let undo_stack = parking_lot::Mutex::new(UndoStack::new(5));
+        assert!(confirm_held(&held, &injector, &undo_stack, false).is_err());
+
+        *held.lock() = Some(HeldTranscript {
+            text: "hunter2".to_string(),
+            app: None,
+            since: Instant::now(),
+        });
+        let discarded = confirm_held(&held, &injector, &undo_stack, true).unwrap();
+        assert_eq!(discarded.text, "hunter2");
+        assert!(held.lock().is_none());
+
+        assert_eq!(
+            LowConfidencePolicy::parse("Review").unwrap(),
+            LowConfidencePolicy::Review
+        );
+        assert!(LowConfidencePolicy::parse("ignore").is_err());
+        assert!(is_unsure(0.4, 0.6));
+        assert!(!is_unsure(0.8, 0.6));
+        assert!(!is_unsure(0.0, 0.0));
+    }
+}
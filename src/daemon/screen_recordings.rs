@@ -0,0 +1,298 @@
+//! Screen Recording Transcripts
+//!
+//! With `[screen_recordings]` enabled, the daemon watches the folders screen
+//! recorders save to. A new file matching one of the `globs` is transcribed
+//! once it stops growing, and the transcript is written next to it as a
+//! sidecar (`Demo.mov` → `Demo.srt`), so narrated demos come with
+//! subtitles. Recordings are handled one at a time with their own model
+//! instance, split at pauses by the VAD.
+
+use crate::config::Config;
+use crate::transcribe::{self, OutputFormat};
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// A recording glob: a literal directory and a file name pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchPattern {
+    pub dir: PathBuf,
+    pub name: String,
+}
+
+impl WatchPattern {
+    /// Parse a glob such as `~/Desktop/Screen Recording*.mov`
+    ///
+    /// Wildcards are only supported in the file name.
+    pub fn parse(glob: &str, home: &Path) -> Option<Self> {
+        let expanded = match glob.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(glob),
+        };
+        let name = expanded.file_name()?.to_str()?.to_string();
+        let dir = expanded.parent()?.to_path_buf();
+        if dir.to_string_lossy().contains(['*', '?']) {
+            return None;
+        }
+        Some(Self { dir, name })
+    }
+
+    /// Whether `path` is a file this pattern picks up
+    pub fn matches(&self, path: &Path) -> bool {
+        path.parent() == Some(self.dir.as_path())
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| wildcard_match(&self.name, name))
+    }
+}
+
+/// Match `name` against a pattern with `*` (any run) and `?` (any one
+/// character)
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Sidecar transcript path for a recording
+pub fn sidecar_path(recording: &Path, format: OutputFormat) -> PathBuf {
+    recording.with_extension(format.extension())
+}
+
+/// Start watching for recordings (`None` when disabled or nothing can be
+/// watched)
+pub fn spawn(config: &Config) -> Option<tokio::task::JoinHandle<()>> {
+    let settings = &config.screen_recordings;
+    if !settings.enabled {
+        return None;
+    }
+    let format = match OutputFormat::parse(&settings.format) {
+        Ok(format) => format,
+        Err(e) => {
+            warn!("Screen recording transcripts disabled: {}", e);
+            return None;
+        }
+    };
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    let patterns: Vec<WatchPattern> = settings
+        .globs
+        .iter()
+        .filter_map(|glob| {
+            let pattern = WatchPattern::parse(glob, &home);
+            if pattern.is_none() {
+                warn!("Ignoring screen recording glob '{}'", glob);
+            }
+            pattern
+        })
+        .collect();
+
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |event| {
+        let _ = event_tx.send(event);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("Cannot watch for screen recordings: {}", e);
+            return None;
+        }
+    };
+    let mut watching = 0;
+    for dir in patterns.iter().map(|pattern| &pattern.dir) {
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                info!("🎬 Watching {} for screen recordings", dir.display());
+                watching += 1;
+            }
+            Err(e) => warn!("Cannot watch {}: {}", dir.display(), e),
+        }
+    }
+    if watching == 0 {
+        return None;
+    }
+
+    let config = config.clone();
+    Some(tokio::spawn(async move {
+        // The watcher stops when dropped
+        let _watcher = watcher;
+        run(config, patterns, format, event_rx).await;
+    }))
+}
+
+/// Wait for matching files to settle, then transcribe them in order
+async fn run(
+    config: Config,
+    patterns: Vec<WatchPattern>,
+    format: OutputFormat,
+    mut events: mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+) {
+    let settle = Duration::from_secs(config.screen_recordings.settle_secs.max(1));
+    let mut seen = HashSet::new();
+    let (ready_tx, mut ready_rx) = mpsc::unbounded_channel::<PathBuf>();
+
+    let worker_config = config.clone();
+    tokio::spawn(async move {
+        while let Some(path) = ready_rx.recv().await {
+            match transcribe_recording(&worker_config, &path, format).await {
+                Ok(sidecar) => {
+                    info!("🎬 Transcript saved to {}", sidecar.display());
+                    crate::platform::notify::notify(
+                        "Onevox",
+                        &format!("Transcript saved: {}", file_name(&sidecar)),
+                    );
+                }
+                Err(e) => warn!("Failed to transcribe {}: {:#}", path.display(), e),
+            }
+        }
+    });
+
+    while let Some(event) = events.recv().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("Screen recording watch error: {}", e);
+                continue;
+            }
+        };
+        // Recorders either create the file in place or move it there
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            continue;
+        }
+        for path in event.paths {
+            let new = patterns.iter().any(|pattern| pattern.matches(&path))
+                && !sidecar_path(&path, format).exists()
+                && seen.insert(path.clone());
+            if new {
+                let ready_tx = ready_tx.clone();
+                tokio::spawn(async move {
+                    if wait_until_settled(&path, settle).await {
+                        let _ = ready_tx.send(path);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Wait until the file stops growing; `false` if it disappears
+async fn wait_until_settled(path: &Path, settle: Duration) -> bool {
+    let mut last_size = None;
+    loop {
+        tokio::time::sleep(settle).await;
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return false;
+        };
+        let size = metadata.len();
+        if size > 0 && last_size == Some(size) {
+            return true;
+        }
+        last_size = Some(size);
+    }
+}
+
+/// Transcribe one recording and write its sidecar
+async fn transcribe_recording(
+    config: &Config,
+    path: &Path,
+    format: OutputFormat,
+) -> Result<PathBuf> {
+    info!("🎬 Transcribing screen recording {}", path.display());
+    let mut vad = crate::vad::VadProcessor::new(
+        config.vad.to_processor_config(),
+        crate::vad::create_detector(&config.vad).await,
+    );
+
+    let config = config.clone();
+    let recording = path.to_path_buf();
+    let sidecar = sidecar_path(path, format);
+    tokio::task::spawn_blocking(move || -> Result<PathBuf> {
+        let mut model =
+            crate::models::backend::load_runtime(&config.model).context("Failed to load model")?;
+        let vocabulary = crate::vocabulary::Vocabulary::from_config(&config.vocabulary);
+        model.set_vocabulary(&vocabulary);
+        let (samples, report) = crate::audio::convert::load_audio(&recording, model.sample_rate())
+            .context("Failed to extract audio")?;
+        info!("🎬 {} ({})", recording.display(), report);
+
+        let mut transcription = transcribe::transcribe_samples(
+            model.as_mut(),
+            &samples,
+            Some(&mut vad),
+            config.audio.chunk_duration_ms,
+        )?;
+        crate::postprocess::PostProcessor::from_config(&config.post_processing)
+            .with_vocabulary(vocabulary)
+            .process(&mut transcription);
+
+        let duration_ms = (report.duration_secs * 1000.0) as u64;
+        std::fs::write(
+            &sidecar,
+            transcribe::render(&transcription, format, duration_ms),
+        )
+        .with_context(|| format!("Failed to write {}", sidecar.display()))?;
+        Ok(sidecar)
+    })
+    .await
+    .context("Transcription task failed")?
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_and_sidecars() {
+        let home = Path::new("/home/me");
+        let pattern = WatchPattern::parse("~/Desktop/Screen Recording*.mov", home).unwrap();
+        assert_eq!(pattern.dir, PathBuf::from("/home/me/Desktop"));
+        assert!(pattern.matches(Path::new(
+            "/home/me/Desktop/Screen Recording 2025-03-14 at 10.02.11.mov"
+        )));
+        assert!(!pattern.matches(Path::new("/home/me/Desktop/Screen Recording 1.mp4")));
+        assert!(!pattern.matches(Path::new("/home/me/Desktop/sub/Screen Recording.mov")));
+        assert!(WatchPattern::parse("~/*/clip.mov", home).is_none());
+
+        assert!(wildcard_match("clip-??.*", "clip-01.webm"));
+        assert!(wildcard_match("*a*b", "xaxxab"));
+        assert!(!wildcard_match("clip-??.*", "clip-1.webm"));
+
+        assert_eq!(
+            sidecar_path(Path::new("/tmp/Demo.mov"), OutputFormat::Srt),
+            PathBuf::from("/tmp/Demo.srt")
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Startup Policies
+//!
+//! Each subsystem that can fail at startup (the model / dictation engine
+//! and the global hotkeys) follows a policy from `[startup]`:
+//!
+//! - `fail-fast`: the daemon exits, so a supervisor or the user notices
+//! - `degrade`: the daemon keeps serving IPC without the subsystem and
+//!   retries it every `retry_interval_secs` (up to `max_retries`)
+//!
+//! The tracker records each subsystem's health for `onevox status`.
+
+use crate::config::StartupConfig;
+use crate::ipc::protocol::{SubsystemHealth, SubsystemStatus};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Model / dictation engine subsystem
+pub const MODEL: &str = "model";
+
+/// Global hotkeys subsystem
+pub const HOTKEYS: &str = "hotkeys";
+
+/// What the daemon does when a subsystem fails to start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartupPolicy {
+    /// Exit the daemon
+    FailFast,
+    /// Keep running without the subsystem and retry it
+    #[default]
+    Degrade,
+}
+
+impl StartupPolicy {
+    /// Parse a `[startup]` policy ("fail-fast" or "degrade")
+    pub fn parse(policy: &str) -> crate::Result<Self> {
+        match policy.to_ascii_lowercase().replace('_', "-").as_str() {
+            "fail-fast" | "failfast" | "exit" => Ok(StartupPolicy::FailFast),
+            "degrade" | "retry" => Ok(StartupPolicy::Degrade),
+            other => Err(crate::Error::Config(format!(
+                "Unknown startup policy '{}' (expected fail-fast or degrade)",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for StartupPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartupPolicy::FailFast => write!(f, "fail-fast"),
+            StartupPolicy::Degrade => write!(f, "degrade"),
+        }
+    }
+}
+
+/// Shared record of subsystem startup health
+///
+/// Cheap to clone; clones share the record.
+#[derive(Clone)]
+pub struct StartupTracker {
+    subsystems: Arc<Mutex<Vec<(StartupPolicy, SubsystemStatus)>>>,
+    retry_interval: Duration,
+    max_retries: u32,
+}
+
+impl StartupTracker {
+    /// Tracker for the subsystems in `[startup]`
+    pub fn new(config: &StartupConfig) -> Self {
+        let subsystem = |name: &str, policy: &str| {
+            let policy = StartupPolicy::parse(policy).unwrap_or_else(|e| {
+                tracing::warn!("{}; using degrade for {}", e, name);
+                StartupPolicy::Degrade
+            });
+            let status = SubsystemStatus {
+                name: name.to_string(),
+                policy: policy.to_string(),
+                health: SubsystemHealth::Starting,
+                attempts: 0,
+                error: None,
+            };
+            (policy, status)
+        };
+
+        Self {
+            subsystems: Arc::new(Mutex::new(vec![
+                subsystem(MODEL, &config.model),
+                subsystem(HOTKEYS, &config.hotkeys),
+            ])),
+            retry_interval: Duration::from_secs(config.retry_interval_secs.max(1)),
+            max_retries: config.max_retries,
+        }
+    }
+
+    /// Time to wait before retrying a degraded subsystem
+    pub fn retry_interval(&self) -> Duration {
+        self.retry_interval
+    }
+
+    /// Policy for a subsystem
+    pub fn policy(&self, name: &str) -> StartupPolicy {
+        self.subsystems
+            .lock()
+            .iter()
+            .find(|(_, status)| status.name == name)
+            .map(|(policy, _)| *policy)
+            .unwrap_or_default()
+    }
+
+    /// Mark a subsystem as running
+    pub fn ready(&self, name: &str) {
+        self.update(name, |_, status| {
+            status.health = SubsystemHealth::Ready;
+            status.error = None;
+        });
+    }
+
+    /// Mark a subsystem as not part of this build or configuration
+    pub fn disabled(&self, name: &str) {
+        self.update(name, |_, status| {
+            status.health = SubsystemHealth::Disabled;
+            status.error = None;
+        });
+    }
+
+    /// Record a failed start, returning the policy to apply
+    ///
+    /// A degraded subsystem that has used up `max_retries` is marked failed
+    /// and reported as [`Attempt::GiveUp`].
+    pub fn failed(&self, name: &str, error: &str) -> Attempt {
+        let max_retries = self.max_retries;
+        let mut attempt = Attempt::Retry;
+        self.update(name, |policy, status| {
+            status.attempts += 1;
+            status.error = Some(error.to_string());
+            attempt = match policy {
+                StartupPolicy::FailFast => Attempt::Exit,
+                // The first attempt isn't a retry
+                StartupPolicy::Degrade if max_retries > 0 && status.attempts > max_retries => {
+                    Attempt::GiveUp
+                }
+                StartupPolicy::Degrade => Attempt::Retry,
+            };
+            status.health = match attempt {
+                Attempt::Retry => SubsystemHealth::Degraded,
+                Attempt::Exit | Attempt::GiveUp => SubsystemHealth::Failed,
+            };
+        });
+        attempt
+    }
+
+    /// Current health of every subsystem
+    pub fn snapshot(&self) -> Vec<SubsystemStatus> {
+        self.subsystems
+            .lock()
+            .iter()
+            .map(|(_, status)| status.clone())
+            .collect()
+    }
+
+    fn update(&self, name: &str, apply: impl FnOnce(StartupPolicy, &mut SubsystemStatus)) {
+        if let Some((policy, status)) = self
+            .subsystems
+            .lock()
+            .iter_mut()
+            .find(|(_, status)| status.name == name)
+        {
+            apply(*policy, status);
+        }
+    }
+}
+
+impl Default for StartupTracker {
+    fn default() -> Self {
+        Self::new(&StartupConfig::default())
+    }
+}
+
+/// What to do after a failed start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attempt {
+    /// Keep running degraded and try again after the retry interval
+    Retry,
+    /// Keep running degraded, but stop retrying
+    GiveUp,
+    /// Exit the daemon (fail-fast)
+    Exit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::protocol::{DaemonStatus, DegradationLevel};
+
+    #[test]
+    fn test_policies_and_degradation() {
+        assert_eq!(
+            StartupPolicy::parse("fail_fast").unwrap(),
+            StartupPolicy::FailFast
+        );
+        assert!(StartupPolicy::parse("panic").is_err());
+
+        let config = StartupConfig {
+            hotkeys: "fail-fast".to_string(),
+            max_retries: 2,
+            ..Default::default()
+        };
+        let tracker = StartupTracker::new(&config);
+        let mut status = DaemonStatus::new(1, 0);
+
+        tracker.ready(MODEL);
+        assert_eq!(tracker.failed(HOTKEYS, "no display"), Attempt::Exit);
+        status.subsystems = tracker.snapshot();
+        assert_eq!(status.subsystems[1].health, SubsystemHealth::Failed);
+        assert_eq!(status.subsystems[1].policy, "fail-fast");
+        assert_eq!(status.degradation(), DegradationLevel::Partial);
+
+        // Degrade: retried up to max_retries, then given up on
+        assert_eq!(tracker.failed(MODEL, "missing"), Attempt::Retry);
+        assert_eq!(tracker.failed(MODEL, "missing"), Attempt::Retry);
+        assert_eq!(tracker.failed(MODEL, "missing"), Attempt::GiveUp);
+        status.subsystems = tracker.snapshot();
+        assert_eq!(status.subsystems[0].attempts, 3);
+        assert_eq!(status.degradation(), DegradationLevel::IpcOnly);
+
+        tracker.ready(MODEL);
+        tracker.ready(HOTKEYS);
+        status.subsystems = tracker.snapshot();
+        assert_eq!(status.subsystems[0].error, None);
+        assert_eq!(status.degradation(), DegradationLevel::Full);
+    }
+}
@@ -3,19 +3,53 @@
 //! Centralized state for the daemon process.
 
 use crate::config::Config;
-use crate::history::HistoryManager;
-use crate::ipc::protocol::{DaemonState as State, DaemonStatus};
+use crate::daemon::downloads::Downloads;
+use crate::daemon::events::EventBus;
+use crate::daemon::metrics::PipelineMetrics;
+use crate::daemon::privacy::Privacy;
+use crate::daemon::recovery::HeldSlot;
+use crate::daemon::startup::StartupTracker;
+use crate::history::{HistoryManager, Recordings};
+use crate::ipc::protocol::{
+    AuditEntry, Capabilities, ClientInfo, DaemonState as State, DaemonStatus, LastTranscript,
+};
+use crate::platform::UndoStack;
 use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 use sysinfo::{Pid, System};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+
+/// Maximum number of audit entries kept in memory
+const MAX_AUDIT_ENTRIES: usize = 100;
+
+/// Number of audit entries included in status responses
+const STATUS_AUDIT_ENTRIES: usize = 10;
+
+/// Maximum number of IPC clients tracked; client names come from the
+/// clients themselves, so the least recently seen is evicted beyond this
+const MAX_CLIENTS: usize = 64;
+
+/// Longest client name kept, in characters
+const MAX_CLIENT_NAME_CHARS: usize = 64;
 
 /// Message types for dictation control
 pub enum DictationCommand {
     Start,
     Stop,
+    /// Pause (`true`) or resume the session in progress
+    Pause(bool),
+    /// Capture was paused for privacy; end any session in progress
+    PrivacyPaused,
+    /// The input device changed; move a session in progress to it
+    DeviceChanged,
+    /// Transcribe a WAV file, replying with the text
+    TranscribeFile {
+        path: std::path::PathBuf,
+        reply: tokio::sync::oneshot::Sender<crate::Result<String>>,
+    },
 }
 
 /// Shared daemon state
@@ -52,6 +86,42 @@ pub struct DaemonState {
 
     /// Channel to send commands to dictation engine
     dictation_tx: Option<mpsc::UnboundedSender<DictationCommand>>,
+
+    /// Most recently seen IPC clients, keyed by client name
+    clients: HashMap<String, ClientInfo>,
+
+    /// Recent IPC requests (audit trail)
+    audit_log: VecDeque<AuditEntry>,
+
+    /// Event bus shared with all daemon subsystems
+    events: EventBus,
+
+    /// Recent injections that can be undone
+    undo: Arc<Mutex<UndoStack>>,
+
+    /// Binary to switch to (live upgrade requested)
+    upgrade_to: Option<std::path::PathBuf>,
+
+    /// Publishes reloaded configuration to the dictation engines
+    config_tx: watch::Sender<Config>,
+
+    /// Most recent finalized transcript, shared with the dictation engines
+    last_transcript: Arc<Mutex<Option<LastTranscript>>>,
+
+    /// Transcript kept out of a password field until `onevox confirm`
+    held_injection: HeldSlot,
+
+    /// Model downloads running in the background
+    downloads: Downloads,
+
+    /// Subsystem startup health (`[startup]` policies)
+    startup: StartupTracker,
+
+    /// Dictation latency and throughput, shared with the engines
+    metrics: PipelineMetrics,
+
+    /// Privacy pause, shared with the engines
+    privacy: Privacy,
 }
 
 impl DaemonState {
@@ -59,17 +129,21 @@ impl DaemonState {
     pub fn new(config: Config) -> Self {
         // Create history manager (using sync constructor, will not load from disk)
         let history_config = config.history.clone();
-        let history_manager = HistoryManager::new(history_config).unwrap_or_else(|e| {
-            tracing::warn!("Failed to create history manager: {}", e);
-            // Create a disabled history manager as fallback
-            HistoryManager::new(crate::config::HistoryConfig {
-                enabled: false,
-                max_entries: 0,
-                auto_save: false,
+        let history_manager = HistoryManager::new(history_config)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to create history manager: {}", e);
+                // Create a disabled history manager as fallback
+                HistoryManager::new(crate::config::HistoryConfig {
+                    enabled: false,
+                    max_entries: 0,
+                    auto_save: false,
+                })
+                .expect("Failed to create fallback history manager")
             })
-            .expect("Failed to create fallback history manager")
-        });
+            .with_recordings(Self::recordings(&config));
 
+        let undo_depth = config.injection.undo_depth;
+        let startup = StartupTracker::new(&config.startup);
         let pid = std::process::id();
         let mut sys_info = System::new_all();
 
@@ -77,6 +151,7 @@ impl DaemonState {
         sys_info.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
 
         Self {
+            config_tx: watch::Sender::new(config.clone()),
             config,
             start_time: Instant::now(),
             pid,
@@ -88,6 +163,17 @@ impl DaemonState {
             sys_info: Mutex::new(sys_info),
             history_manager: Arc::new(history_manager),
             dictation_tx: None,
+            clients: HashMap::new(),
+            audit_log: VecDeque::with_capacity(MAX_AUDIT_ENTRIES),
+            events: EventBus::default(),
+            undo: Arc::new(Mutex::new(UndoStack::new(undo_depth))),
+            upgrade_to: None,
+            last_transcript: Arc::new(Mutex::new(None)),
+            held_injection: HeldSlot::default(),
+            downloads: Downloads::default(),
+            startup,
+            metrics: PipelineMetrics::default(),
+            privacy: Privacy::default(),
         }
     }
 
@@ -106,8 +192,11 @@ impl DaemonState {
                     auto_save: false,
                 })
                 .expect("Failed to create fallback history manager")
-            });
+            })
+            .with_recordings(Self::recordings(&config));
 
+        let undo_depth = config.injection.undo_depth;
+        let startup = StartupTracker::new(&config.startup);
         let pid = std::process::id();
         let mut sys_info = System::new_all();
 
@@ -115,6 +204,7 @@ impl DaemonState {
         sys_info.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
 
         Self {
+            config_tx: watch::Sender::new(config.clone()),
             config,
             start_time: Instant::now(),
             pid,
@@ -126,9 +216,28 @@ impl DaemonState {
             sys_info: Mutex::new(sys_info),
             history_manager: Arc::new(history_manager),
             dictation_tx: None,
+            clients: HashMap::new(),
+            audit_log: VecDeque::with_capacity(MAX_AUDIT_ENTRIES),
+            events: EventBus::default(),
+            undo: Arc::new(Mutex::new(UndoStack::new(undo_depth))),
+            upgrade_to: None,
+            last_transcript: Arc::new(Mutex::new(None)),
+            held_injection: HeldSlot::default(),
+            downloads: Downloads::default(),
+            startup,
+            metrics: PipelineMetrics::default(),
+            privacy: Privacy::default(),
         }
     }
 
+    /// Recording store for history, when `[audio].save_recordings` is on
+    fn recordings(config: &Config) -> Option<Recordings> {
+        Recordings::from_config(&config.audio).unwrap_or_else(|e| {
+            tracing::warn!("Recordings unavailable: {}", e);
+            None
+        })
+    }
+
     /// Get current status
     pub fn status(&self) -> DaemonStatus {
         let uptime_secs = self.start_time.elapsed().as_secs();
@@ -143,9 +252,97 @@ impl DaemonState {
             is_dictating: self.is_dictating.load(Ordering::SeqCst),
             memory_usage_bytes: self.get_memory_usage(),
             cpu_usage_percent: self.get_cpu_usage(),
+            clients: self.clients(),
+            recent_requests: self
+                .audit_log
+                .iter()
+                .skip(self.audit_log.len().saturating_sub(STATUS_AUDIT_ENTRIES))
+                .cloned()
+                .collect(),
+            capabilities: Capabilities::current(),
+            event_counts: self.events.counts(),
+            event_subscribers: self.events.subscriber_count(),
+            subsystems: self.startup.snapshot(),
+            pipeline: self.metrics.snapshot(),
+            privacy: self.privacy.status(),
         }
     }
 
+    /// Record an IPC request for client attribution and auditing
+    pub fn record_request(
+        &mut self,
+        client_name: &str,
+        peer_id: u32,
+        request_id: &str,
+        command: &str,
+        is_error: bool,
+    ) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let client_name: String = client_name.chars().take(MAX_CLIENT_NAME_CHARS).collect();
+        if !self.clients.contains_key(&client_name)
+            && self.clients.len() >= MAX_CLIENTS
+            && let Some(idle) = self
+                .clients
+                .values()
+                .min_by_key(|c| c.last_seen)
+                .map(|c| c.name.clone())
+        {
+            self.clients.remove(&idle);
+        }
+
+        let client = self
+            .clients
+            .entry(client_name.clone())
+            .or_insert_with(|| ClientInfo {
+                name: client_name.clone(),
+                peer_id,
+                request_count: 0,
+                last_seen: now,
+                last_command: String::new(),
+            });
+        client.peer_id = peer_id;
+        client.request_count += 1;
+        client.last_seen = now;
+        client.last_command = command.to_string();
+
+        if self.audit_log.len() >= MAX_AUDIT_ENTRIES {
+            self.audit_log.pop_front();
+        }
+        self.audit_log.push_back(AuditEntry {
+            timestamp: now,
+            request_id: request_id.to_string(),
+            client_name: client_name.clone(),
+            command: command.to_string(),
+            is_error,
+        });
+
+        tracing::debug!(
+            target: "onevox::audit",
+            "request_id={} client={} peer={} command={} error={}",
+            request_id,
+            client_name,
+            peer_id,
+            command,
+            is_error
+        );
+    }
+
+    /// Clients seen so far, most recently active first
+    pub fn clients(&self) -> Vec<ClientInfo> {
+        let mut clients: Vec<ClientInfo> = self.clients.values().cloned().collect();
+        clients.sort_by_key(|c| std::cmp::Reverse(c.last_seen));
+        clients
+    }
+
+    /// Full in-memory audit trail (oldest first)
+    pub fn audit_log(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.audit_log.iter()
+    }
+
     /// Get configuration
     pub fn config(&self) -> &Config {
         &self.config
@@ -193,6 +390,16 @@ impl DaemonState {
         self.shutdown_requested.load(Ordering::SeqCst)
     }
 
+    /// Request a live upgrade to `binary` (see `daemon::upgrade`)
+    pub fn request_upgrade(&mut self, binary: std::path::PathBuf) {
+        self.upgrade_to = Some(binary);
+    }
+
+    /// Binary a live upgrade was requested to, if any
+    pub fn upgrade_request(&self) -> Option<&std::path::Path> {
+        self.upgrade_to.as_deref()
+    }
+
     /// Get shutdown signal for cloning
     pub fn shutdown_signal(&self) -> Arc<AtomicBool> {
         Arc::clone(&self.shutdown_requested)
@@ -226,9 +433,7 @@ impl DaemonState {
                 .map_err(|_| crate::Error::Other("Dictation engine not available".to_string()))?;
             Ok(())
         } else {
-            Err(crate::Error::Other(
-                "Dictation engine not initialized".to_string(),
-            ))
+            Err(Self::dictation_unavailable())
         }
     }
 
@@ -239,9 +444,44 @@ impl DaemonState {
                 .map_err(|_| crate::Error::Other("Dictation engine not available".to_string()))?;
             Ok(())
         } else {
-            Err(crate::Error::Other(
-                "Dictation engine not initialized".to_string(),
-            ))
+            Err(Self::dictation_unavailable())
+        }
+    }
+
+    /// Pause or resume dictation via IPC
+    pub fn pause_dictation(&self, paused: bool) -> crate::Result<()> {
+        if let Some(tx) = &self.dictation_tx {
+            tx.send(DictationCommand::Pause(paused))
+                .map_err(|_| crate::Error::Other("Dictation engine not available".to_string()))?;
+            Ok(())
+        } else {
+            Err(Self::dictation_unavailable())
+        }
+    }
+
+    /// Queue a WAV file for the dictation engine's model; the receiver
+    /// yields the transcript
+    pub fn transcribe_file(
+        &self,
+        path: std::path::PathBuf,
+    ) -> crate::Result<tokio::sync::oneshot::Receiver<crate::Result<String>>> {
+        let Some(tx) = &self.dictation_tx else {
+            return Err(Self::dictation_unavailable());
+        };
+        let (reply, response) = tokio::sync::oneshot::channel();
+        tx.send(DictationCommand::TranscribeFile { path, reply })
+            .map_err(|_| crate::Error::Other("Dictation engine not available".to_string()))?;
+        Ok(response)
+    }
+
+    fn dictation_unavailable() -> crate::Error {
+        if Capabilities::current().can_dictate() {
+            crate::Error::Other("Dictation engine not initialized".to_string())
+        } else {
+            crate::Error::Other(
+                "Dictation not available in this build (compiled without audio-capture)"
+                    .to_string(),
+            )
         }
     }
 
@@ -280,16 +520,107 @@ impl DaemonState {
         }
     }
 
-    /// Reload configuration
+    /// Reload configuration and push it to the dictation engines
     pub fn reload_config(&mut self) -> crate::Result<()> {
         let new_config = Config::load_default()?;
+        self.config_tx.send_replace(new_config.clone());
         self.config = new_config;
-        tracing::info!("Configuration reloaded - daemon will be restarted to apply changes");
+        tracing::info!("Configuration reloaded - applies from the next dictation");
+        Ok(())
+    }
+
+    /// Capture from `name` from now on, saving it to the config file
+    ///
+    /// The IPC engine's session in progress switches over right away (the
+    /// hotkey engine notices by itself).
+    pub fn set_device(&mut self, name: &str) -> crate::Result<()> {
+        Config::set_key(&Config::default_path(), "audio.device", name)?;
+        self.reload_config()?;
+        if let Some(tx) = &self.dictation_tx {
+            let _ = tx.send(DictationCommand::DeviceChanged);
+        }
         Ok(())
     }
 
+    /// Receiver for configuration reloads
+    pub fn config_updates(&self) -> watch::Receiver<Config> {
+        self.config_tx.subscribe()
+    }
+
+    /// Get the daemon event bus
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Get the shared last-transcript slot
+    pub fn last_transcript(&self) -> &Arc<Mutex<Option<LastTranscript>>> {
+        &self.last_transcript
+    }
+
+    /// Get the background model downloads
+    pub fn downloads(&self) -> &Downloads {
+        &self.downloads
+    }
+
+    /// Subsystem startup health
+    pub fn startup(&self) -> &StartupTracker {
+        &self.startup
+    }
+
+    /// Dictation pipeline metrics
+    pub fn metrics(&self) -> &PipelineMetrics {
+        &self.metrics
+    }
+
+    /// Privacy pause
+    pub fn privacy(&self) -> &Privacy {
+        &self.privacy
+    }
+
+    /// Pause capture for privacy, ending the IPC engine's session in
+    /// progress (the hotkey engine notices the pause by itself)
+    pub fn pause_privacy(&self, duration: Option<std::time::Duration>) {
+        self.privacy.pause(duration);
+        if let Some(tx) = &self.dictation_tx {
+            let _ = tx.send(DictationCommand::PrivacyPaused);
+        }
+    }
+
+    /// Get the slot for the transcript awaiting `onevox confirm`
+    pub fn held_injection(&self) -> &HeldSlot {
+        &self.held_injection
+    }
+
+    /// Get the shared undo stack
+    pub fn undo_stack(&self) -> &Arc<Mutex<UndoStack>> {
+        &self.undo
+    }
+
     /// Get reference to history manager
     pub fn history_manager(&self) -> &Arc<HistoryManager> {
         &self.history_manager
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_table_is_bounded() {
+        let mut state = DaemonState::new(Config::default());
+        for i in 0..MAX_CLIENTS + 10 {
+            state.record_request(&format!("client-{}", i), 1000, "req", "Ping", false);
+        }
+        state.record_request(&"x".repeat(10_000), 1000, "req", "Ping", false);
+
+        let clients = state.clients();
+        assert_eq!(clients.len(), MAX_CLIENTS);
+        assert!(
+            clients
+                .iter()
+                .all(|c| c.name.chars().count() <= MAX_CLIENT_NAME_CHARS)
+        );
+        assert_eq!(state.audit_log().count(), MAX_CLIENTS + 11);
+    }
+}
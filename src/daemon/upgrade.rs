@@ -0,0 +1,193 @@
+//! Live Upgrade
+//!
+//! `onevox self-update --live` swaps a running daemon for a newly installed
+//! binary without clients noticing. The daemon stops accepting connections
+//! (new ones queue in the socket backlog), lets in-flight requests and any
+//! active dictation finish, then `exec`s the new binary in place. The
+//! listening socket is inherited through `ONEVOX_LISTEN_FD`, so the socket
+//! path never disappears, and the PID stays the same, so launchd and systemd
+//! keep tracking the service. Queued clients are served as soon as the new
+//! daemon starts accepting.
+//!
+//! Event subscriptions (`onevox events`) are long-lived connections and are
+//! closed by the switch; subscribers need to reconnect.
+
+use crate::daemon::events::EventBus;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Environment variable carrying the inherited listening socket
+pub const LISTEN_FD_ENV: &str = "ONEVOX_LISTEN_FD";
+
+/// Longest time to wait for in-flight work before switching anyway
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time without dictation or transcription events before the daemon is idle
+const QUIET_PERIOD: Duration = Duration::from_secs(2);
+
+/// How often drain progress is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Listening socket claimed from the previous daemon, until the IPC server
+/// takes it
+#[cfg(unix)]
+static INHERITED_LISTENER: std::sync::Mutex<Option<std::os::fd::OwnedFd>> =
+    std::sync::Mutex::new(None);
+
+/// Claim the listening socket passed by the previous daemon, if any
+///
+/// Must run at the top of `main`, before the async runtime (or anything
+/// else) starts threads: it clears `ONEVOX_LISTEN_FD` so child processes
+/// don't inherit it, and changing the environment is only sound while the
+/// process is single-threaded.
+#[cfg(unix)]
+pub fn claim_inherited_listener() {
+    use std::os::fd::FromRawFd;
+
+    let Ok(value) = std::env::var(LISTEN_FD_ENV) else {
+        return;
+    };
+    // SAFETY: the process is still single-threaded (see above)
+    unsafe { std::env::remove_var(LISTEN_FD_ENV) };
+
+    let fd = match value.parse::<std::os::fd::RawFd>() {
+        // SAFETY: only checks that the descriptor is open
+        Ok(fd) if fd >= 0 && unsafe { libc::fcntl(fd, libc::F_GETFD) } >= 0 => fd,
+        _ => {
+            eprintln!("Ignoring invalid {}={}", LISTEN_FD_ENV, value);
+            return;
+        }
+    };
+    // SAFETY: the previous daemon handed this descriptor over exclusively
+    let listener = unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) };
+    *INHERITED_LISTENER.lock().unwrap_or_else(|e| e.into_inner()) = Some(listener);
+}
+
+/// Whether this process was started by a live upgrade
+pub fn has_inherited_listener() -> bool {
+    #[cfg(unix)]
+    let inherited = INHERITED_LISTENER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some();
+    #[cfg(not(unix))]
+    let inherited = false;
+    inherited
+}
+
+/// Take the listening socket claimed from the previous daemon, if any
+#[cfg(unix)]
+pub fn take_inherited_listener() -> Option<std::os::fd::OwnedFd> {
+    INHERITED_LISTENER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+/// Check that `binary` runs, returning its version string
+pub fn verify_binary(binary: &Path) -> crate::Result<String> {
+    let output = std::process::Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| crate::Error::Other(format!("Cannot run {}: {}", binary.display(), e)))?;
+    if !output.status.success() {
+        return Err(crate::Error::Other(format!(
+            "{} --version failed ({})",
+            binary.display(),
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether a dictation session is in progress, judging by event counts
+pub fn dictation_active(counts: &BTreeMap<String, u64>) -> bool {
+    let count = |name: &str| counts.get(name).copied().unwrap_or(0);
+    count("DictationStarted") > count("DictationStopped")
+}
+
+/// Events that indicate pipeline activity (everything except log lines)
+fn activity(counts: &BTreeMap<String, u64>) -> u64 {
+    counts
+        .iter()
+        .filter(|(name, _)| name.as_str() != "Log")
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Wait until no requests are in flight, no dictation is active, and the
+/// pipeline has been quiet for a moment; `false` when `timeout` ran out
+pub async fn drain(in_flight: &AtomicUsize, events: &EventBus, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut last_activity = activity(&events.counts());
+    let mut quiet_since = Instant::now();
+
+    loop {
+        let counts = events.counts();
+        let current = activity(&counts);
+        if current != last_activity {
+            last_activity = current;
+            quiet_since = Instant::now();
+        }
+
+        let requests = in_flight.load(Ordering::SeqCst);
+        if requests == 0 && !dictation_active(&counts) && quiet_since.elapsed() >= QUIET_PERIOD {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            warn!(
+                "Drain timed out ({} request(s) in flight, dictation active: {})",
+                requests,
+                dictation_active(&counts)
+            );
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Replace this process with `binary`, handing over `listener`
+///
+/// Keeps the original command-line arguments. Only returns on failure.
+#[cfg(unix)]
+pub fn exec_successor(binary: &Path, listener: &std::os::fd::OwnedFd) -> std::io::Error {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let fd = listener.as_raw_fd();
+    // SAFETY: clears close-on-exec on a descriptor this process owns
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) } < 0 {
+        return std::io::Error::last_os_error();
+    }
+
+    info!("🔁 Switching to {} (listener fd {})", binary.display(), fd);
+    std::process::Command::new(binary)
+        .args(std::env::args_os().skip(1))
+        .env(LISTEN_FD_ENV, fd.to_string())
+        .exec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_dictation() {
+        let mut counts = BTreeMap::new();
+        assert!(!dictation_active(&counts));
+        counts.insert("DictationStarted".to_string(), 3);
+        counts.insert("DictationStopped".to_string(), 2);
+        assert!(dictation_active(&counts));
+        counts.insert("DictationStopped".to_string(), 3);
+        assert!(!dictation_active(&counts));
+
+        // A request in flight keeps the drain from completing
+        let in_flight = AtomicUsize::new(1);
+        let events = EventBus::default();
+        assert!(!drain(&in_flight, &events, Duration::from_millis(200)).await);
+    }
+}
@@ -0,0 +1,252 @@
+//! Accuracy Evaluation
+//!
+//! Scores transcriptions against reference transcripts with word and
+//! character error rates (WER/CER). Used by `onevox eval` and the
+//! `tests/quality` suite to catch accuracy regressions from backend changes
+//! (tokenizer, features, decoding).
+//!
+//! A fixture directory holds `<name>.wav` files next to `<name>.txt`
+//! reference transcripts. Audio in other sample rates or channel layouts is
+//! converted to the model's format first.
+
+pub mod bench;
+
+use crate::audio::convert::load_wav;
+use crate::models::ModelRuntime;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::debug;
+
+/// Lowercase words with punctuation stripped, so formatting differences
+/// between backends do not count as errors
+pub fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Levenshtein distance between two sequences
+pub fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+
+    for (i, r) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(r != h);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[hypothesis.len()]
+}
+
+/// Edit counts for one reference/hypothesis pair
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorCounts {
+    /// Word-level edits
+    pub word_errors: usize,
+    /// Words in the reference
+    pub reference_words: usize,
+    /// Character-level edits (spaces between words included)
+    pub char_errors: usize,
+    /// Characters in the reference
+    pub reference_chars: usize,
+}
+
+impl ErrorCounts {
+    /// Score a hypothesis against a reference
+    pub fn score(reference: &str, hypothesis: &str) -> Self {
+        let reference = normalize_words(reference);
+        let hypothesis = normalize_words(hypothesis);
+        let reference_chars: Vec<char> = reference.join(" ").chars().collect();
+        let hypothesis_chars: Vec<char> = hypothesis.join(" ").chars().collect();
+
+        Self {
+            word_errors: edit_distance(&reference, &hypothesis),
+            reference_words: reference.len(),
+            char_errors: edit_distance(&reference_chars, &hypothesis_chars),
+            reference_chars: reference_chars.len(),
+        }
+    }
+
+    /// Word error rate
+    pub fn wer(&self) -> f64 {
+        rate(self.word_errors, self.reference_words)
+    }
+
+    /// Character error rate
+    pub fn cer(&self) -> f64 {
+        rate(self.char_errors, self.reference_chars)
+    }
+
+    /// Add another pair's counts (corpus-level rates)
+    pub fn add(&mut self, other: &ErrorCounts) {
+        self.word_errors += other.word_errors;
+        self.reference_words += other.reference_words;
+        self.char_errors += other.char_errors;
+        self.reference_chars += other.reference_chars;
+    }
+}
+
+fn rate(errors: usize, total: usize) -> f64 {
+    match (errors, total) {
+        (0, _) => 0.0,
+        (_, 0) => 1.0,
+        _ => errors as f64 / total as f64,
+    }
+}
+
+/// Word error rate of a hypothesis
+pub fn wer(reference: &str, hypothesis: &str) -> f64 {
+    ErrorCounts::score(reference, hypothesis).wer()
+}
+
+/// Character error rate of a hypothesis
+pub fn cer(reference: &str, hypothesis: &str) -> f64 {
+    ErrorCounts::score(reference, hypothesis).cer()
+}
+
+/// Audio file with its reference transcript
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    /// File stem
+    pub name: String,
+    /// WAV file
+    pub audio: PathBuf,
+    /// Expected transcript
+    pub reference: String,
+}
+
+/// Fixtures in `dir`: every `<name>.txt` with a matching `<name>.wav`
+///
+/// References without audio are skipped (fixtures can be generated on
+/// demand), sorted by name.
+pub fn load_fixtures(dir: &Path) -> crate::Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+        let audio = path.with_extension("wav");
+        if !audio.exists() {
+            debug!("Skipping {:?}: no audio", path);
+            continue;
+        }
+        fixtures.push(Fixture {
+            name: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            reference: std::fs::read_to_string(&path)?.trim().to_string(),
+            audio,
+        });
+    }
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// Result of one fixture
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureResult {
+    /// Fixture name
+    pub name: String,
+    /// Expected transcript
+    pub reference: String,
+    /// Model output
+    pub hypothesis: String,
+    /// Edit counts
+    pub counts: ErrorCounts,
+    /// Transcription time
+    pub latency_ms: u64,
+    /// Conversion applied to the fixture audio
+    pub conversion: String,
+}
+
+/// Results across all fixtures
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalReport {
+    /// Model that was evaluated
+    pub model: String,
+    /// Per-fixture results
+    pub results: Vec<FixtureResult>,
+    /// Corpus-level counts
+    pub totals: ErrorCounts,
+}
+
+impl EvalReport {
+    /// Corpus-level word error rate
+    pub fn wer(&self) -> f64 {
+        self.totals.wer()
+    }
+
+    /// Corpus-level character error rate
+    pub fn cer(&self) -> f64 {
+        self.totals.cer()
+    }
+}
+
+/// Transcribe every fixture with a loaded model and score the output
+pub fn evaluate(model: &mut dyn ModelRuntime, fixtures: &[Fixture]) -> crate::Result<EvalReport> {
+    let mut results = Vec::new();
+    let mut totals = ErrorCounts::default();
+
+    for fixture in fixtures {
+        let sample_rate = model.sample_rate();
+        let (samples, conversion) = load_wav(&fixture.audio, sample_rate)?;
+        let start = Instant::now();
+        let transcription = model.transcribe(&samples, sample_rate)?;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        let counts = ErrorCounts::score(&fixture.reference, &transcription.text);
+        debug!(
+            "{}: WER {:.3} ({:?})",
+            fixture.name,
+            counts.wer(),
+            transcription.text
+        );
+        totals.add(&counts);
+        results.push(FixtureResult {
+            name: fixture.name.clone(),
+            reference: fixture.reference.clone(),
+            hypothesis: transcription.text,
+            counts,
+            latency_ms,
+            conversion: conversion.to_string(),
+        });
+    }
+
+    Ok(EvalReport {
+        model: model.name().to_string(),
+        results,
+        totals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wer_and_cer() {
+        assert_eq!(wer("The cat sat.", "the cat sat"), 0.0);
+        // One substitution and one deletion over four words
+        assert_eq!(wer("the cat sat down", "the bat sat"), 0.5);
+        assert_eq!(wer("", ""), 0.0);
+        assert_eq!(wer("", "extra"), 1.0);
+        assert!((cer("hello", "hallo") - 0.2).abs() < 1e-9);
+
+        let mut totals = ErrorCounts::score("a b c d", "a b c d");
+        totals.add(&ErrorCounts::score("e f", "e g"));
+        assert!((totals.wer() - 1.0 / 6.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,205 @@
+//! Model Benchmarks
+//!
+//! Runs the same audio through several models so their speed, memory, and
+//! (with reference transcripts) accuracy can be compared side by side, as
+//! `onevox models bench` does. Each model is loaded, run over every input,
+//! and dropped before the next one starts.
+
+use super::{ErrorCounts, Fixture};
+use crate::audio::convert::load_audio;
+use crate::config::ModelConfig;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use tracing::{debug, warn};
+
+/// How often memory is sampled while a model runs
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Audio to benchmark on, optionally with its expected transcript
+#[derive(Debug, Clone)]
+pub struct BenchInput {
+    /// File stem
+    pub name: String,
+    /// Audio file (any format `load_audio` reads)
+    pub audio: PathBuf,
+    /// Expected transcript, for WER
+    pub reference: Option<String>,
+}
+
+impl From<Fixture> for BenchInput {
+    fn from(fixture: Fixture) -> Self {
+        Self {
+            name: fixture.name,
+            audio: fixture.audio,
+            reference: Some(fixture.reference),
+        }
+    }
+}
+
+/// One model's benchmark
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BenchResult {
+    /// Model ID
+    pub model: String,
+    /// Backend that ran it
+    pub backend: String,
+    /// Time to load the model
+    pub load_ms: u64,
+    /// Total length of the inputs
+    pub audio_ms: u64,
+    /// Total transcription time
+    pub processing_ms: u64,
+    /// Slowest single input
+    pub max_latency_ms: u64,
+    /// Peak resident memory of the process while the model ran
+    pub peak_memory_bytes: u64,
+    /// Edit counts, when every input has a reference
+    pub counts: Option<ErrorCounts>,
+    /// Why the model could not be benchmarked
+    pub error: Option<String>,
+}
+
+impl BenchResult {
+    /// Real-time factor: processing time per second of audio (lower is
+    /// faster; below 1.0 keeps up with live speech)
+    pub fn rtf(&self) -> f64 {
+        if self.audio_ms == 0 {
+            return 0.0;
+        }
+        self.processing_ms as f64 / self.audio_ms as f64
+    }
+
+    /// Mean transcription time per input
+    pub fn mean_latency_ms(&self, inputs: usize) -> u64 {
+        self.processing_ms / inputs.max(1) as u64
+    }
+}
+
+/// Benchmark one model on every input
+///
+/// Load and transcription failures are reported in the result rather than
+/// returned, so one broken model doesn't stop the comparison.
+pub fn bench_model(config: &ModelConfig, inputs: &[BenchInput]) -> BenchResult {
+    let mut result = BenchResult {
+        model: config.model_path.clone(),
+        ..Default::default()
+    };
+    let memory = MemorySampler::start();
+    if let Err(e) = run(config, inputs, &mut result) {
+        warn!("Benchmark of {} failed: {}", config.model_path, e);
+        result.error = Some(e.to_string());
+    }
+    result.peak_memory_bytes = memory.stop();
+    result
+}
+
+fn run(config: &ModelConfig, inputs: &[BenchInput], result: &mut BenchResult) -> crate::Result<()> {
+    let start = Instant::now();
+    let mut model = crate::models::backend::load_runtime(config)?;
+    result.load_ms = start.elapsed().as_millis() as u64;
+    result.backend = model.info().backend;
+
+    let mut totals = ErrorCounts::default();
+    let mut scored = 0;
+    for input in inputs {
+        let sample_rate = model.sample_rate();
+        let (samples, conversion) = load_audio(&input.audio, sample_rate)?;
+        let start = Instant::now();
+        let transcription = model.transcribe(&samples, sample_rate)?;
+        let latency_ms = start.elapsed().as_millis() as u64;
+        debug!(
+            "{} on {}: {}ms ({:?})",
+            config.model_path, input.name, latency_ms, transcription.text
+        );
+
+        result.audio_ms += (conversion.duration_secs * 1000.0) as u64;
+        result.processing_ms += latency_ms;
+        result.max_latency_ms = result.max_latency_ms.max(latency_ms);
+        if let Some(reference) = &input.reference {
+            totals.add(&ErrorCounts::score(reference, &transcription.text));
+            scored += 1;
+        }
+    }
+    result.counts = (scored > 0 && scored == inputs.len()).then_some(totals);
+    Ok(())
+}
+
+/// Tracks the process's peak resident memory from a background thread
+struct MemorySampler {
+    stop: Arc<AtomicBool>,
+    peak: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MemorySampler {
+    fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let peak = Arc::new(AtomicU64::new(0));
+        let handle = {
+            let stop = Arc::clone(&stop);
+            let peak = Arc::clone(&peak);
+            std::thread::Builder::new()
+                .name("bench-memory".to_string())
+                .spawn(move || {
+                    let pid = Pid::from_u32(std::process::id());
+                    let mut system = System::new();
+                    loop {
+                        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), false);
+                        if let Some(process) = system.process(pid) {
+                            peak.fetch_max(process.memory(), Ordering::Relaxed);
+                        }
+                        if stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        std::thread::sleep(MEMORY_SAMPLE_INTERVAL);
+                    }
+                })
+                .map_err(|e| warn!("Failed to start memory sampling: {}", e))
+                .ok()
+        };
+        Self { stop, peak, handle }
+    }
+
+    /// Stop sampling and return the peak in bytes (0 if unavailable)
+    fn stop(mut self) -> u64 {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_reports_failures_and_rtf() {
+        let input = BenchInput {
+            name: "missing".to_string(),
+            audio: PathBuf::from("/nonexistent/missing.wav"),
+            reference: Some("hello".to_string()),
+        };
+        let config = ModelConfig {
+            model_path: "no-such-model".to_string(),
+            ..Default::default()
+        };
+        let result = bench_model(&config, &[input]);
+        assert!(result.error.is_some());
+        assert!(result.counts.is_none());
+
+        let result = BenchResult {
+            audio_ms: 4000,
+            processing_ms: 1000,
+            ..Default::default()
+        };
+        assert_eq!(result.rtf(), 0.25);
+        assert_eq!(result.mean_latency_ms(2), 500);
+    }
+}
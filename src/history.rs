@@ -5,7 +5,30 @@
 //! - Delete specific entries
 //! - Clear all history
 //! - Configure history retention
-
+//! - Back up and restore history
+//! - Keep recent transcripts in a clipboard ring for quick pasting
+//! - Search past transcriptions
+//! - Keep the audio of dictated segments for playback
+//! - Export as a text log, subtitles (with word timings), or JSON, whole
+//!   or one document per dictation session
+//! - Summarize dictation volume, speed, and errors over time
+
+pub mod backup;
+pub mod clipboard_ring;
+pub mod export;
+pub mod recordings;
+pub mod search;
+pub mod stats;
+
+pub use backup::{BackupInfo, BackupManager, HistoryBackup, HistoryDiff};
+pub use clipboard_ring::ClipboardRing;
+pub use export::{export, export_session};
+pub use recordings::{Recordings, Retention};
+pub use search::{HistoryMatch, HistoryQuery};
+pub use stats::HistoryStats;
+
+use crate::meeting::Speaker;
+use crate::models::{TranscriptSegment, Transcription};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -15,6 +38,10 @@ use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
 /// A single transcription history entry
+///
+/// Entries are sent over IPC with bincode, which has no field names, so no
+/// field may be skipped when serializing; `default` only lets older
+/// history files load.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     /// Unique entry ID
@@ -34,6 +61,58 @@ pub struct HistoryEntry {
 
     /// Confidence score (0.0 to 1.0), if available
     pub confidence: Option<f32>,
+
+    /// Start of this sentence within the recording, when a long dictation
+    /// was split into several entries
+    #[serde(default)]
+    pub start_offset_ms: Option<u64>,
+
+    /// End of this sentence within the recording
+    #[serde(default)]
+    pub end_offset_ms: Option<u64>,
+
+    /// Mic gain or placement hint raised for this recording
+    #[serde(default)]
+    pub audio_hint: Option<String>,
+
+    /// Near-duplicate of the previous segment, kept here but not injected
+    #[serde(default)]
+    pub duplicate: bool,
+
+    /// Why the text was kept out of the focused field (a password field)
+    #[serde(default)]
+    pub not_injected: Option<String>,
+
+    /// Spoken language (ISO 639-1), reported by the model or detected
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// File name of the saved recording this entry was transcribed from
+    #[serde(default)]
+    pub recording: Option<String>,
+
+    /// Word timings within the recording, when the model provided them
+    #[serde(default)]
+    pub words: Vec<TranscriptSegment>,
+
+    /// Who spoke, in a meeting transcript (`onevox listen --meeting`)
+    #[serde(default)]
+    pub speaker: Option<Speaker>,
+
+    /// Number of words in `text` (filled in on load for entries saved
+    /// before it was recorded)
+    #[serde(default)]
+    pub word_count: usize,
+
+    /// Length of the speech this entry was transcribed from
+    #[serde(default)]
+    pub speech_ms: Option<u64>,
+
+    /// Dictation session the entry was dictated in (when the session
+    /// started, in Unix milliseconds), shared by every segment between
+    /// start and stop
+    #[serde(default)]
+    pub session_id: Option<u64>,
 }
 
 impl HistoryEntry {
@@ -47,14 +126,91 @@ impl HistoryEntry {
         Self {
             id: timestamp, // Use timestamp as ID for simplicity
             timestamp,
+            word_count: text.split_whitespace().count(),
             text,
             model,
             duration_ms,
             confidence,
+            start_offset_ms: None,
+            end_offset_ms: None,
+            audio_hint: None,
+            duplicate: false,
+            not_injected: None,
+            language: None,
+            recording: None,
+            words: Vec::new(),
+            speaker: None,
+            speech_ms: None,
+            session_id: None,
+        }
+    }
+
+    /// Count the words of an entry saved before `word_count` existed
+    fn backfill(&mut self) {
+        if self.word_count == 0 {
+            self.word_count = self.text.split_whitespace().count();
+        }
+    }
+
+    /// Create one entry per sentence of a transcription
+    ///
+    /// Falls back to a single entry when the backend produced no sentence
+    /// timestamps or the whole recording is one sentence.
+    pub fn from_transcription(transcription: &Transcription, model: &str) -> Vec<Self> {
+        let whole = || Self {
+            language: transcription.language.clone(),
+            words: transcription.words.clone(),
+            ..Self::new(
+                transcription.text.clone(),
+                model.to_string(),
+                transcription.processing_time_ms,
+                transcription.confidence,
+            )
+        };
+
+        if transcription.segments.len() <= 1 {
+            return vec![whole()];
         }
+
+        transcription
+            .segments
+            .iter()
+            .map(|segment| Self {
+                text: segment.text.clone(),
+                word_count: segment.text.split_whitespace().count(),
+                start_offset_ms: Some(segment.start_ms),
+                end_offset_ms: Some(segment.end_ms),
+                speech_ms: Some(segment.end_ms.saturating_sub(segment.start_ms)),
+                words: transcription
+                    .words
+                    .iter()
+                    .filter(|word| {
+                        word.start_ms >= segment.start_ms && word.end_ms <= segment.end_ms
+                    })
+                    .cloned()
+                    .collect(),
+                ..whole()
+            })
+            .collect()
     }
 }
 
+/// Parse a history file, filling in fields older files lack
+fn parse_history(contents: &str) -> crate::Result<Vec<HistoryEntry>> {
+    let mut entries: Vec<HistoryEntry> = serde_json::from_str(contents)
+        .map_err(|e| crate::Error::Other(format!("Failed to parse history file: {}", e)))?;
+    entries.iter_mut().for_each(HistoryEntry::backfill);
+    Ok(entries)
+}
+
+/// Split entries into dictation sessions: runs of consecutive entries
+/// sharing a session ID (an entry without one is a session of its own)
+pub fn sessions(entries: &[HistoryEntry]) -> Vec<&[HistoryEntry]> {
+    entries
+        .chunk_by(|a, b| a.session_id.is_some() && a.session_id == b.session_id)
+        .collect()
+}
+
 /// Manages transcription history
 pub struct HistoryManager {
     /// History configuration
@@ -65,6 +221,9 @@ pub struct HistoryManager {
 
     /// In-memory history entries
     entries: Arc<Mutex<Vec<HistoryEntry>>>,
+
+    /// Where segment audio is saved, when recordings are enabled
+    recordings: Option<Recordings>,
 }
 
 impl HistoryManager {
@@ -84,6 +243,7 @@ impl HistoryManager {
             config,
             history_path,
             entries: Arc::new(Mutex::new(Vec::new())),
+            recordings: None,
         })
     }
 
@@ -102,6 +262,7 @@ impl HistoryManager {
             config,
             history_path,
             entries: Arc::new(Mutex::new(Vec::new())),
+            recordings: None,
         };
 
         // Load existing history
@@ -114,6 +275,17 @@ impl HistoryManager {
         Ok(manager)
     }
 
+    /// Save the audio of recorded entries to `recordings`
+    pub fn with_recordings(mut self, recordings: Option<Recordings>) -> Self {
+        self.recordings = recordings;
+        self
+    }
+
+    /// Whether segment audio is saved with new entries
+    pub fn saves_recordings(&self) -> bool {
+        self.config.enabled && self.recordings.is_some()
+    }
+
     /// Get the default history file path
     pub fn default_history_path() -> PathBuf {
         crate::platform::paths::history_db_path()
@@ -122,6 +294,31 @@ impl HistoryManager {
 
     /// Add a new entry to history
     pub async fn add_entry(&self, entry: HistoryEntry) -> crate::Result<()> {
+        self.add_entries(vec![entry]).await
+    }
+
+    /// Add every sentence of a transcription to history
+    pub async fn add_transcription(
+        &self,
+        transcription: &Transcription,
+        model: &str,
+    ) -> crate::Result<()> {
+        self.add_entries(HistoryEntry::from_transcription(transcription, model))
+            .await
+    }
+
+    /// Add several entries to history with a single save
+    pub async fn add_entries(&self, new_entries: Vec<HistoryEntry>) -> crate::Result<()> {
+        self.add_recorded_entries(new_entries, None).await
+    }
+
+    /// Add entries transcribed from one segment, saving its audio
+    /// (`samples`, `sample_rate`) as their recording when enabled
+    pub async fn add_recorded_entries(
+        &self,
+        new_entries: Vec<HistoryEntry>,
+        audio: Option<(Vec<f32>, u32)>,
+    ) -> crate::Result<()> {
         if !self.config.enabled {
             debug!("History disabled, skipping entry");
             return Ok(());
@@ -129,21 +326,51 @@ impl HistoryManager {
 
         let mut entries = self.entries.lock().await;
 
-        entries.push(entry.clone());
-        info!(
-            "Added history entry #{}: {}",
-            entry.id,
-            if entry.text.len() > 50 {
-                format!("{}...", &entry.text[..50])
-            } else {
-                entry.text.clone()
+        // IDs are second timestamps, so entries from the same second
+        // (e.g. split sentences) are bumped past the newest ID
+        let mut new_entries = new_entries;
+        let mut max_id = entries.iter().map(|e| e.id).max();
+        for entry in &mut new_entries {
+            if let Some(max_id) = max_id
+                && entry.id <= max_id
+            {
+                entry.id = max_id + 1;
             }
-        );
+            max_id = Some(entry.id);
+        }
+
+        if let (Some(recordings), Some((samples, sample_rate)), Some(first)) =
+            (&self.recordings, audio, new_entries.first())
+        {
+            let recordings = recordings.clone();
+            let id = first.id;
+            let saved = tokio::task::spawn_blocking(move || {
+                let name = recordings.save(id, &samples, sample_rate)?;
+                recordings.prune(SystemTime::now());
+                crate::Result::Ok(name)
+            })
+            .await;
+            match saved {
+                Ok(Ok(name)) => {
+                    for entry in &mut new_entries {
+                        entry.recording = Some(name.clone());
+                    }
+                }
+                Ok(Err(e)) => warn!("Failed to save recording: {}", e),
+                Err(e) => warn!("Recording task failed: {}", e),
+            }
+        }
+
+        for entry in new_entries {
+            info!("Added history entry #{}", entry.id);
+            entries.push(entry);
+        }
 
         // Enforce max_entries limit
         if self.config.max_entries > 0 && entries.len() > self.config.max_entries {
             let excess = entries.len() - self.config.max_entries;
-            entries.drain(0..excess);
+            let removed: Vec<HistoryEntry> = entries.drain(0..excess).collect();
+            self.remove_recordings(&removed, &entries);
             debug!(
                 "Removed {} old entries to maintain max_entries limit",
                 excess
@@ -165,6 +392,12 @@ impl HistoryManager {
         Ok(entries.clone())
     }
 
+    /// Search entries, best matches first
+    pub async fn search(&self, query: &HistoryQuery) -> crate::Result<Vec<HistoryMatch>> {
+        let entries = self.entries.lock().await;
+        search::search(&entries, query)
+    }
+
     /// Get a specific entry by ID
     pub async fn get_entry(&self, id: u64) -> crate::Result<Option<HistoryEntry>> {
         let entries = self.entries.lock().await;
@@ -179,9 +412,10 @@ impl HistoryManager {
 
         let mut entries = self.entries.lock().await;
 
-        let original_len = entries.len();
+        let removed: Vec<HistoryEntry> = entries.iter().filter(|e| e.id == id).cloned().collect();
         entries.retain(|e| e.id != id);
-        let deleted = entries.len() < original_len;
+        self.remove_recordings(&removed, &entries);
+        let deleted = !removed.is_empty();
 
         if deleted {
             info!("Deleted history entry #{}", id);
@@ -203,7 +437,8 @@ impl HistoryManager {
         let mut entries = self.entries.lock().await;
 
         let count = entries.len();
-        entries.clear();
+        let removed = std::mem::take(&mut *entries);
+        self.remove_recordings(&removed, &entries);
         info!("Cleared {} history entries", count);
 
         drop(entries);
@@ -212,6 +447,51 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Replace all entries (used when restoring a backup)
+    pub async fn replace_all(&self, new_entries: Vec<HistoryEntry>) -> crate::Result<()> {
+        if !self.config.enabled {
+            return Err(crate::Error::Other("History is disabled".to_string()));
+        }
+
+        let mut entries = self.entries.lock().await;
+        *entries = new_entries;
+        entries.iter_mut().for_each(HistoryEntry::backfill);
+        entries.sort_by_key(|e| e.timestamp);
+
+        // A backup may hold more entries than the current limit allows
+        if self.config.max_entries > 0 && entries.len() > self.config.max_entries {
+            let excess = entries.len() - self.config.max_entries;
+            let removed: Vec<HistoryEntry> = entries.drain(0..excess).collect();
+            self.remove_recordings(&removed, &entries);
+            debug!(
+                "Dropped {} old restored entries to maintain max_entries limit",
+                excess
+            );
+        }
+        info!("Replaced history with {} entries", entries.len());
+
+        drop(entries);
+        self.save().await?;
+
+        Ok(())
+    }
+
+    /// Delete the recordings of `removed` entries that no `remaining`
+    /// entry links to
+    fn remove_recordings(&self, removed: &[HistoryEntry], remaining: &[HistoryEntry]) {
+        let Some(recordings) = &self.recordings else {
+            return;
+        };
+        for name in removed.iter().filter_map(|e| e.recording.as_deref()) {
+            if !remaining
+                .iter()
+                .any(|e| e.recording.as_deref() == Some(name))
+            {
+                recordings.remove(name);
+            }
+        }
+    }
+
     /// Get the number of entries
     pub fn count(&self) -> usize {
         // Use try_lock to avoid blocking
@@ -229,8 +509,7 @@ impl HistoryManager {
             .await
             .map_err(|e| crate::Error::Other(format!("Failed to read history file: {}", e)))?;
 
-        let loaded_entries: Vec<HistoryEntry> = serde_json::from_str(&contents)
-            .map_err(|e| crate::Error::Other(format!("Failed to parse history file: {}", e)))?;
+        let loaded_entries = parse_history(&contents)?;
 
         let mut entries = self.entries.lock().await;
 
@@ -284,6 +563,23 @@ mod tests {
         assert_eq!(entry.duration_ms, 1500);
         assert_eq!(entry.confidence, Some(0.95));
         assert!(entry.timestamp > 0);
+
+        // Optional fields survive the IPC encoding whether set or not
+        let tagged = HistoryEntry {
+            language: Some("es".to_string()),
+            ..entry.clone()
+        };
+        let bytes = bincode::serialize(&vec![entry, tagged]).unwrap();
+        let decoded: Vec<HistoryEntry> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded[0].language, None);
+        assert_eq!(decoded[1].language.as_deref(), Some("es"));
+
+        // Entries saved before word counts were recorded get them on load
+        let legacy = r#"[{"id": 1, "timestamp": 1, "text": "three old words",
+            "model": "whisper", "duration_ms": 10, "confidence": null}]"#;
+        let loaded = parse_history(legacy).unwrap();
+        assert_eq!(loaded[0].word_count, 3);
+        assert_eq!(HistoryStats::compute(&loaded, None).total.words, 3);
     }
 
     #[tokio::test]
@@ -365,4 +661,56 @@ mod tests {
         manager.clear().await.unwrap();
         assert_eq!(manager.count(), 0);
     }
+
+    #[tokio::test]
+    async fn test_add_transcription_splits_sentences() {
+        use crate::models::TranscriptSegment;
+
+        let config = crate::config::HistoryConfig {
+            enabled: true,
+            max_entries: 10,
+            auto_save: false,
+        };
+
+        let manager = HistoryManager::new(config).unwrap();
+
+        let mut transcription = Transcription::new("First one. Second one.".to_string());
+        transcription.words = vec![
+            TranscriptSegment {
+                text: "First".to_string(),
+                start_ms: 0,
+                end_ms: 300,
+            },
+            TranscriptSegment {
+                text: "one.".to_string(),
+                start_ms: 1600,
+                end_ms: 2000,
+            },
+        ];
+        transcription.segments = vec![
+            TranscriptSegment {
+                text: "First one.".to_string(),
+                start_ms: 0,
+                end_ms: 800,
+            },
+            TranscriptSegment {
+                text: "Second one.".to_string(),
+                start_ms: 1500,
+                end_ms: 2400,
+            },
+        ];
+
+        manager
+            .add_transcription(&transcription, "parakeet")
+            .await
+            .unwrap();
+
+        let entries = manager.get_all().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].text, "Second one.");
+        assert_eq!(entries[1].start_offset_ms, Some(1500));
+        assert_eq!(entries[0].words.len(), 1);
+        assert_eq!(entries[1].words[0].start_ms, 1600);
+        assert_ne!(entries[0].id, entries[1].id);
+    }
 }
@@ -0,0 +1,388 @@
+//! History Backups
+//!
+//! Rotating dated snapshots of the transcription history in the data
+//! directory, so an accidental `onevox history clear` can be undone with
+//! `onevox history restore --from <backup>`.
+//!
+//! Vocabulary and replacement lists from the config directory are included
+//! in each snapshot when they exist.
+
+use super::HistoryEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// File name prefix for backup archives
+const BACKUP_PREFIX: &str = "history-";
+
+/// Length of the `YYYY-MM-DD-HHMMSS-` stamp ahead of a snapshot's label
+const SNAPSHOT_STAMP_LEN: usize = 18;
+
+/// User data files (in the config dir) included in backups when present
+const EXTRA_FILES: &[&str] = &["vocabulary.txt", "replacements.toml"];
+
+/// A backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryBackup {
+    /// Creation time (Unix timestamp)
+    pub created_at: u64,
+
+    /// History entries at the time of the backup
+    pub entries: Vec<HistoryEntry>,
+
+    /// Extra user data files, keyed by file name
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// Backup archive on disk
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    /// Archive name (file stem, e.g. "history-2025-01-31")
+    pub name: String,
+
+    /// Archive path
+    pub path: PathBuf,
+
+    /// Last modification time (Unix timestamp)
+    pub modified: u64,
+
+    /// Archive size in bytes
+    pub size_bytes: u64,
+}
+
+/// Difference between the current history and a backup
+#[derive(Debug, Clone, Default)]
+pub struct HistoryDiff {
+    /// Entries in the backup that are missing from the current history
+    pub restored: Vec<HistoryEntry>,
+
+    /// Current entries that are not in the backup (lost on restore)
+    pub removed: Vec<HistoryEntry>,
+
+    /// Entries present in both
+    pub unchanged: usize,
+
+    /// Extra files the restore would overwrite
+    pub files: Vec<String>,
+}
+
+impl HistoryDiff {
+    /// Compare the current history with a backup
+    pub fn compute(current: &[HistoryEntry], backup: &HistoryBackup) -> Self {
+        let key = |e: &HistoryEntry| (e.id, e.text.clone());
+        let current_keys: HashSet<_> = current.iter().map(key).collect();
+        let backup_keys: HashSet<_> = backup.entries.iter().map(key).collect();
+
+        let restored: Vec<HistoryEntry> = backup
+            .entries
+            .iter()
+            .filter(|e| !current_keys.contains(&key(e)))
+            .cloned()
+            .collect();
+        let removed: Vec<HistoryEntry> = current
+            .iter()
+            .filter(|e| !backup_keys.contains(&key(e)))
+            .cloned()
+            .collect();
+
+        Self {
+            unchanged: backup.entries.len() - restored.len(),
+            restored,
+            removed,
+            files: backup.files.keys().cloned().collect(),
+        }
+    }
+
+    /// Whether restoring would change the history
+    pub fn is_empty(&self) -> bool {
+        self.restored.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Creates, rotates, and loads history backups
+pub struct BackupManager {
+    /// Directory holding the backup archives
+    dir: PathBuf,
+
+    /// Directory holding the extra user data files
+    extra_dir: PathBuf,
+
+    /// Number of archives to keep of each kind (daily, and each snapshot
+    /// label)
+    keep: usize,
+}
+
+impl BackupManager {
+    /// Create a backup manager for the given directories
+    pub fn new(dir: PathBuf, extra_dir: PathBuf, keep: usize) -> Self {
+        Self {
+            dir,
+            extra_dir,
+            keep,
+        }
+    }
+
+    /// Create a backup manager using the default data and config directories
+    pub fn from_config(config: &crate::config::BackupConfig) -> crate::Result<Self> {
+        Ok(Self::new(
+            crate::platform::paths::backups_dir()?,
+            crate::platform::paths::config_dir()?,
+            config.keep,
+        ))
+    }
+
+    /// Backup directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Take today's backup unless one already exists
+    ///
+    /// Returns the path of the new archive, or `None` if today is covered.
+    pub fn backup_daily(&self, entries: &[HistoryEntry]) -> crate::Result<Option<PathBuf>> {
+        let date = chrono::Local::now().format("%Y-%m-%d");
+        let path = self.dir.join(format!("{}{}.json", BACKUP_PREFIX, date));
+        if path.exists() {
+            debug!("Daily history backup already exists: {:?}", path);
+            return Ok(None);
+        }
+
+        self.write(&path, entries)?;
+        Ok(Some(path))
+    }
+
+    /// Take an extra labelled snapshot (e.g. before clearing history)
+    pub fn snapshot(&self, entries: &[HistoryEntry], label: &str) -> crate::Result<PathBuf> {
+        let stamp = chrono::Local::now().format("%Y-%m-%d-%H%M%S");
+        let path = self
+            .dir
+            .join(format!("{}{}-{}.json", BACKUP_PREFIX, stamp, label));
+        self.write(&path, entries)?;
+        Ok(path)
+    }
+
+    /// List backups, newest first
+    pub fn list(&self) -> crate::Result<Vec<BackupInfo>> {
+        let mut backups = Vec::new();
+        if !self.dir.exists() {
+            return Ok(backups);
+        }
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_stem().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !name.starts_with(BACKUP_PREFIX)
+                || path.extension().and_then(|e| e.to_str()) != Some("json")
+            {
+                continue;
+            }
+
+            let metadata = fs::metadata(&path)?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+
+            backups.push(BackupInfo {
+                name: name.to_string(),
+                path: path.clone(),
+                modified,
+                size_bytes: metadata.len(),
+            });
+        }
+
+        // Names embed the date, so ties on mtime still sort chronologically
+        backups.sort_by(|a, b| b.modified.cmp(&a.modified).then(b.name.cmp(&a.name)));
+        Ok(backups)
+    }
+
+    /// Resolve a backup from a path, archive name, or date (YYYY-MM-DD)
+    pub fn resolve(&self, from: &str) -> crate::Result<PathBuf> {
+        let direct = PathBuf::from(from);
+        if direct.is_file() {
+            return Ok(direct);
+        }
+
+        let stem = from.trim_end_matches(".json");
+        let candidates = [
+            self.dir.join(format!("{}.json", stem)),
+            self.dir.join(format!("{}{}.json", BACKUP_PREFIX, stem)),
+        ];
+
+        candidates
+            .into_iter()
+            .find(|p| p.is_file())
+            .ok_or_else(|| crate::Error::Other(format!("Backup not found: {}", from)))
+    }
+
+    /// Load a backup archive
+    pub fn load(path: &Path) -> crate::Result<HistoryBackup> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| crate::Error::Other(format!("Failed to read backup: {}", e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| crate::Error::Other(format!("Failed to parse backup: {}", e)))
+    }
+
+    /// Write the extra user data files from a backup back to the config dir
+    pub fn restore_files(&self, backup: &HistoryBackup) -> crate::Result<()> {
+        for (name, contents) in &backup.files {
+            // Only restore files we know about, never arbitrary paths
+            if !EXTRA_FILES.contains(&name.as_str()) {
+                warn!("Skipping unknown file in backup: {}", name);
+                continue;
+            }
+            fs::write(self.extra_dir.join(name), contents)?;
+            info!("Restored {} from backup", name);
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, entries: &[HistoryEntry]) -> crate::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut files = BTreeMap::new();
+        for name in EXTRA_FILES {
+            if let Ok(contents) = fs::read_to_string(self.extra_dir.join(name)) {
+                files.insert(name.to_string(), contents);
+            }
+        }
+
+        let backup = HistoryBackup {
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            entries: entries.to_vec(),
+            files,
+        };
+
+        let json = serde_json::to_string_pretty(&backup)
+            .map_err(|e| crate::Error::Other(format!("Failed to serialize backup: {}", e)))?;
+
+        // Write to a temp file first so a crash never leaves a truncated archive
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)?;
+
+        info!("Backed up {} history entries to {:?}", entries.len(), path);
+
+        self.rotate()
+    }
+
+    /// Remove the oldest archives beyond the configured limit
+    ///
+    /// Daily backups and each kind of snapshot are counted separately, so a
+    /// burst of clears can't rotate out the daily history.
+    fn rotate(&self) -> crate::Result<()> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        for backup in self.list()? {
+            let count = seen
+                .entry(archive_kind(&backup.name).to_string())
+                .or_default();
+            *count += 1;
+            if *count <= self.keep {
+                continue;
+            }
+            debug!("Rotating out history backup {:?}", backup.path);
+            if let Err(e) = fs::remove_file(&backup.path) {
+                warn!("Failed to remove old backup {:?}: {}", backup.path, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Kind of archive: the snapshot label, or "" for a daily backup
+fn archive_kind(name: &str) -> &str {
+    let stamped = name.strip_prefix(BACKUP_PREFIX).unwrap_or(name);
+    stamped.get(SNAPSHOT_STAMP_LEN..).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            timestamp: id,
+            ..HistoryEntry::new(text.to_string(), "whisper".to_string(), 100, None)
+        }
+    }
+
+    #[test]
+    fn test_daily_backup_and_restore_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = BackupManager::new(dir.path().join("backups"), dir.path().to_path_buf(), 7);
+        fs::write(dir.path().join("vocabulary.txt"), "onevox\n").unwrap();
+
+        let entries = vec![entry(1, "hello"), entry(2, "world")];
+        let path = manager.backup_daily(&entries).unwrap().unwrap();
+        assert!(manager.backup_daily(&entries).unwrap().is_none());
+
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(manager.resolve(&date).unwrap(), path);
+
+        let backup = BackupManager::load(&path).unwrap();
+        assert_eq!(backup.entries.len(), 2);
+        assert_eq!(backup.files["vocabulary.txt"], "onevox\n");
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = BackupManager::new(dir.path().to_path_buf(), dir.path().join("cfg"), 2);
+
+        for name in [
+            "history-2025-01-01",
+            "history-2025-01-02",
+            "history-2025-01-03-090000-before-clear",
+            "history-2025-01-03-100000-before-clear",
+            "history-2025-01-03-110000-before-restore",
+        ] {
+            fs::write(dir.path().join(format!("{}.json", name)), "{}").unwrap();
+        }
+        manager.snapshot(&[], "before-clear").unwrap();
+
+        // Each kind is rotated on its own
+        let names: Vec<String> = manager
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(names.len(), 5);
+        assert!(!names.contains(&"history-2025-01-03-090000-before-clear".to_string()));
+        assert!(names.contains(&"history-2025-01-01".to_string()));
+        assert!(names.contains(&"history-2025-01-03-110000-before-restore".to_string()));
+        assert_eq!(archive_kind("history-2025-01-01"), "");
+    }
+
+    #[test]
+    fn test_diff() {
+        let backup = HistoryBackup {
+            created_at: 0,
+            entries: vec![entry(1, "kept"), entry(2, "lost")],
+            files: BTreeMap::new(),
+        };
+        let current = vec![entry(1, "kept"), entry(3, "new")];
+
+        let diff = HistoryDiff::compute(&current, &backup);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.restored[0].text, "lost");
+        assert_eq!(diff.removed[0].text, "new");
+        assert!(!diff.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Transcript Clipboard Ring
+//!
+//! With `[clipboard_ring]` enabled, the last `size` injected transcripts are
+//! kept (newest first) in `clipboard_ring.json` in the data directory, so
+//! they survive restarts. The picker hotkey (`hotkey.picker`) opens an
+//! overlay listing them and pastes the one selected, which helps when
+//! dictating several snippets to paste in different places.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Ring file name in the data directory
+const RING_FILE: &str = "clipboard_ring.json";
+
+/// Recent transcripts, newest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClipboardRing {
+    entries: VecDeque<String>,
+    #[serde(skip)]
+    size: usize,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl ClipboardRing {
+    /// Ring holding up to `size` entries, not saved to disk
+    pub fn new(size: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(size),
+            size,
+            path: None,
+        }
+    }
+
+    /// Default ring file location
+    pub fn default_path() -> crate::Result<PathBuf> {
+        Ok(crate::platform::data_dir()?.join(RING_FILE))
+    }
+
+    /// Load the ring saved at `path` (empty when missing or unreadable)
+    pub fn load(path: &Path, size: usize) -> Self {
+        let mut ring = match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str::<Self>(&json).unwrap_or_else(|e| {
+                warn!(
+                    "Ignoring unreadable clipboard ring {}: {}",
+                    path.display(),
+                    e
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        ring.size = size;
+        ring.entries.truncate(size);
+        ring.path = Some(path.to_path_buf());
+        ring
+    }
+
+    /// Add a transcript at the front, moving it there if already present
+    ///
+    /// Saves the ring when it was loaded from a file.
+    pub fn push(&mut self, text: &str) {
+        let text = text.trim();
+        if self.size == 0 || text.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != text);
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(self.size);
+
+        if let Err(e) = self.save() {
+            warn!("Failed to save clipboard ring: {}", e);
+        }
+    }
+
+    /// Entries, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the ring is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::Error::Other(format!("Failed to encode clipboard ring: {}", e)))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_order_and_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RING_FILE);
+
+        let mut ring = ClipboardRing::load(&path, 3);
+        assert!(ring.is_empty());
+        for text in ["first", "second", "  ", "third", "fourth", "second"] {
+            ring.push(text);
+        }
+        assert_eq!(
+            ring.entries().collect::<Vec<_>>(),
+            ["second", "fourth", "third"]
+        );
+
+        // A smaller ring keeps the newest entries
+        let reloaded = ClipboardRing::load(&path, 2);
+        assert_eq!(reloaded.entries().collect::<Vec<_>>(), ["second", "fourth"]);
+
+        let mut unsaved = ClipboardRing::new(0);
+        unsaved.push("ignored");
+        assert_eq!(unsaved.len(), 0);
+    }
+}
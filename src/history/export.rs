@@ -0,0 +1,252 @@
+//! History Export
+//!
+//! Renders history entries for `onevox history export`: a plain-text log,
+//! SRT or WebVTT subtitles, or JSON with every field (word timings
+//! included). Subtitles put the entries on one timeline starting at the
+//! first entry: each entry sits at the second it was dictated plus its
+//! offset within the recording, split into cues of a few words at the
+//! model's word timestamps when it has them. Meeting entries are prefixed
+//! with their speaker ("Me: …").
+//!
+//! With `--per-session` each dictation session is a document of its own;
+//! as text, its sentences are joined back into one paragraph (a new one
+//! whenever the speaker changes).
+
+use super::HistoryEntry;
+use crate::models::{TranscriptSegment, Transcription};
+use crate::transcribe::OutputFormat;
+use std::fmt::Write;
+
+/// Most words in one subtitle cue
+const CUE_WORDS: usize = 10;
+
+/// Assumed speaking time per word for entries without timestamps
+const UNTIMED_WORD_MS: u64 = 400;
+
+/// Render entries, in chronological order, in `format`
+pub fn export(entries: &[HistoryEntry], format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => text_log(entries),
+        OutputFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(entries).unwrap_or_default()
+        ),
+        OutputFormat::Srt | OutputFormat::Vtt => {
+            let mut timeline = Transcription::new(String::new());
+            timeline.segments = cues(entries);
+            crate::transcribe::render(&timeline, format, 0)
+        }
+    }
+}
+
+/// Render one dictation session's entries, in chronological order, as a
+/// document of its own
+pub fn export_session(entries: &[HistoryEntry], format: OutputFormat) -> String {
+    if format != OutputFormat::Text {
+        return export(entries, format);
+    }
+    let Some(first) = entries.first() else {
+        return String::new();
+    };
+
+    let started = chrono::DateTime::from_timestamp(first.timestamp as i64, 0)
+        .unwrap_or(chrono::DateTime::UNIX_EPOCH)
+        .with_timezone(&chrono::Local);
+    let words: usize = entries.iter().map(|entry| entry.word_count).sum();
+    let mut document = String::new();
+    let _ = writeln!(document, "Onevox Dictation Session");
+    let _ = writeln!(document, "Started: {}", started.format("%Y-%m-%d %H:%M:%S"));
+    let _ = writeln!(document, "Model: {}", first.model);
+    let _ = writeln!(document, "Segments: {}, words: {}\n", entries.len(), words);
+
+    for turn in entries.chunk_by(|a, b| a.speaker == b.speaker) {
+        let text: Vec<&str> = turn
+            .iter()
+            .map(|entry| entry.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect();
+        if text.is_empty() {
+            continue;
+        }
+        match turn[0].speaker {
+            Some(speaker) => {
+                let _ = writeln!(document, "{}: {}\n", speaker, text.join(" "));
+            }
+            None => {
+                let _ = writeln!(document, "{}\n", text.join(" "));
+            }
+        }
+    }
+    document
+}
+
+/// Header and one timestamped paragraph per entry
+fn text_log(entries: &[HistoryEntry]) -> String {
+    let mut log = String::new();
+    let _ = writeln!(log, "Onevox Transcription History");
+    let _ = writeln!(
+        log,
+        "Generated: {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    let _ = writeln!(log, "Total entries: {}\n", entries.len());
+    let _ = writeln!(
+        log,
+        "============================================================\n"
+    );
+
+    for entry in entries {
+        let datetime = chrono::DateTime::from_timestamp(entry.timestamp as i64, 0)
+            .unwrap_or(chrono::DateTime::UNIX_EPOCH);
+        let _ = writeln!(
+            log,
+            "[{}] ({}ms) {}",
+            datetime.format("%Y-%m-%d %H:%M:%S"),
+            entry.duration_ms,
+            entry.model
+        );
+        let _ = writeln!(log, "{}\n", spoken_text(entry));
+    }
+    log
+}
+
+/// An entry's text, after its speaker in meeting transcripts
+fn spoken_text(entry: &HistoryEntry) -> String {
+    match entry.speaker {
+        Some(speaker) => format!("{}: {}", speaker, entry.text.trim()),
+        None => entry.text.trim().to_string(),
+    }
+}
+
+/// Subtitle cues for all entries, relative to the first one
+fn cues(entries: &[HistoryEntry]) -> Vec<TranscriptSegment> {
+    let Some(first) = entries.first() else {
+        return Vec::new();
+    };
+    let mut cues = Vec::new();
+    for entry in entries.iter().filter(|entry| !entry.text.trim().is_empty()) {
+        let origin = entry.timestamp.saturating_sub(first.timestamp) * 1000;
+        cues.extend(entry_cues(entry).into_iter().map(|mut cue| {
+            if let Some(speaker) = entry.speaker {
+                cue.text = format!("{}: {}", speaker, cue.text);
+            }
+            cue.shifted(origin)
+        }));
+    }
+    cues
+}
+
+/// Cues for one entry, relative to the start of its recording
+fn entry_cues(entry: &HistoryEntry) -> Vec<TranscriptSegment> {
+    let spoken: Vec<&str> = entry.text.split_whitespace().collect();
+
+    if entry.words.is_empty() {
+        let start_ms = entry.start_offset_ms.unwrap_or(0);
+        let end_ms = entry
+            .end_offset_ms
+            .unwrap_or(start_ms + (spoken.len() as u64 * UNTIMED_WORD_MS).max(1000));
+        return vec![TranscriptSegment {
+            text: entry.text.trim().to_string(),
+            start_ms,
+            end_ms,
+        }];
+    }
+
+    // Word timings carry the model's spelling; show the post-processed
+    // text instead when it still lines up word for word
+    let aligned = spoken.len() == entry.words.len();
+    entry
+        .words
+        .chunks(CUE_WORDS)
+        .enumerate()
+        .map(|(index, words)| {
+            let text = if aligned {
+                let start = index * CUE_WORDS;
+                spoken[start..start + words.len()].join(" ")
+            } else {
+                words
+                    .iter()
+                    .map(|word| word.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            TranscriptSegment {
+                text,
+                start_ms: words[0].start_ms,
+                end_ms: words[words.len() - 1].end_ms,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    #[test]
+    fn test_export_srt_uses_word_timings() {
+        let timed = HistoryEntry {
+            timestamp: 100,
+            words: vec![word("hello", 250, 600), word("world", 700, 1100)],
+            ..HistoryEntry::new("Hello world.".to_string(), "whisper".to_string(), 50, None)
+        };
+        let untimed = HistoryEntry {
+            timestamp: 103,
+            ..HistoryEntry::new("Second note".to_string(), "whisper".to_string(), 50, None)
+        };
+
+        let srt = export(&[timed, untimed], OutputFormat::Srt);
+        assert_eq!(
+            srt,
+            "1\n00:00:00,250 --> 00:00:01,100\nHello world.\n\n\
+             2\n00:00:03,000 --> 00:00:04,000\nSecond note\n\n"
+        );
+
+        // Meeting entries show who spoke
+        let reply = HistoryEntry {
+            speaker: Some(crate::meeting::Speaker::Others),
+            ..HistoryEntry::new("Agreed.".to_string(), "whisper".to_string(), 50, None)
+        };
+        assert!(export(&[reply], OutputFormat::Text).contains("\nOthers: Agreed.\n"));
+
+        // Long entries are split into cues at word boundaries
+        let many: Vec<TranscriptSegment> =
+            (0..12).map(|i| word("w", i * 100, i * 100 + 80)).collect();
+        let long = HistoryEntry {
+            words: many,
+            ..HistoryEntry::new("w ".repeat(11) + "w", "whisper".to_string(), 50, None)
+        };
+        let cues = entry_cues(&long);
+        assert_eq!(cues.len(), 2);
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (1000, 1180));
+
+        // A session's sentences read as one paragraph
+        let sentence = |text: &str, session_id: u64| HistoryEntry {
+            session_id: Some(session_id),
+            ..HistoryEntry::new(text.to_string(), "whisper".to_string(), 50, None)
+        };
+        let entries = [
+            sentence("First sentence.", 1),
+            sentence("Second one. ", 1),
+            sentence("Next session.", 2),
+            HistoryEntry::new("Loose.".to_string(), "whisper".to_string(), 50, None),
+            HistoryEntry::new("Entries.".to_string(), "whisper".to_string(), 50, None),
+        ];
+        let sessions = crate::history::sessions(&entries);
+        assert_eq!(
+            sessions.iter().map(|s| s.len()).collect::<Vec<_>>(),
+            vec![2, 1, 1, 1]
+        );
+        let document = export_session(sessions[0], OutputFormat::Text);
+        assert!(document.contains("Segments: 2, words: 4\n"));
+        assert!(document.ends_with("\nFirst sentence. Second one.\n\n"));
+    }
+}
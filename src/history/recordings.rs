@@ -0,0 +1,194 @@
+//! Dictation Recordings
+//!
+//! With `[audio].save_recordings` on, the audio of each dictated segment is
+//! kept as `<id>.wav`, named after the first history entry transcribed from
+//! it; every entry of the segment links to the file, and sentence entries
+//! play back just their part of it. Recordings are pruned oldest first to
+//! stay within the configured file count, age, and disk space.
+
+use crate::config::AudioConfig;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+const MB: u64 = 1024 * 1024;
+
+/// Limits on kept recordings (0 = no limit)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Retention {
+    pub max_files: usize,
+    pub max_days: u64,
+    pub max_mb: u64,
+}
+
+/// Directory of saved dictation recordings
+#[derive(Debug, Clone)]
+pub struct Recordings {
+    dir: PathBuf,
+    retention: Retention,
+}
+
+impl Recordings {
+    /// Create a store in `dir`
+    pub fn new(dir: PathBuf, retention: Retention) -> Self {
+        Self { dir, retention }
+    }
+
+    /// Store configured by `[audio]`, or `None` when recordings are off
+    pub fn from_config(config: &AudioConfig) -> crate::Result<Option<Self>> {
+        if !config.save_recordings {
+            return Ok(None);
+        }
+        let retention = Retention {
+            max_files: config.recordings_max_files,
+            max_days: config.recordings_max_days,
+            max_mb: config.recordings_max_mb,
+        };
+        Ok(Some(Self::new(default_dir()?, retention)))
+    }
+
+    /// Save a segment's audio for the entry `id`, returning the file name
+    pub fn save(&self, id: u64, samples: &[f32], sample_rate: u32) -> crate::Result<String> {
+        fs::create_dir_all(&self.dir)?;
+        let name = format!("{}.wav", id);
+        crate::audio::convert::save_wav(&self.dir.join(&name), samples, sample_rate)?;
+        debug!("Saved recording {}", name);
+        Ok(name)
+    }
+
+    /// Remove a recording, if it is still kept
+    pub fn remove(&self, name: &str) {
+        let Some(path) = recording_path(&self.dir, name) else {
+            return;
+        };
+        match fs::remove_file(&path) {
+            Ok(()) => debug!("Removed recording {}", name),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove recording {:?}: {}", path, e),
+        }
+    }
+
+    /// Delete the oldest recordings beyond the retention limits, returning
+    /// how many were removed
+    pub fn prune(&self, now: SystemTime) -> usize {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = dir
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|e| e == "wav"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((modified, metadata.len(), entry.path()))
+            })
+            .collect();
+        // Newest first, so the oldest are the ones past the limits
+        files.sort_by_key(|&(modified, _, _)| std::cmp::Reverse(modified));
+
+        let Retention {
+            max_files,
+            max_days,
+            max_mb,
+        } = self.retention;
+        let max_age = Duration::from_secs(max_days * 24 * 60 * 60);
+        let mut kept_bytes = 0;
+        let mut removed = 0;
+        for (index, (modified, size, path)) in files.into_iter().enumerate() {
+            kept_bytes += size;
+            let expired =
+                max_days > 0 && now.duration_since(modified).is_ok_and(|age| age > max_age);
+            if (max_files > 0 && index >= max_files)
+                || expired
+                || (max_mb > 0 && kept_bytes > max_mb * MB)
+            {
+                match fs::remove_file(&path) {
+                    Ok(()) => removed += 1,
+                    Err(e) => warn!("Failed to remove recording {:?}: {}", path, e),
+                }
+                kept_bytes -= size;
+            }
+        }
+        if removed > 0 {
+            debug!("Pruned {} old recordings", removed);
+        }
+        removed
+    }
+}
+
+/// Where dictation recordings are kept
+pub fn default_dir() -> crate::Result<PathBuf> {
+    Ok(crate::platform::paths::recordings_dir()?.join("history"))
+}
+
+/// Path of a recording named in a history entry
+///
+/// Names are plain file names; anything else is refused so a tampered
+/// history file cannot point outside the recordings directory.
+pub fn recording_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    let file_name = Path::new(name).file_name()?;
+    (file_name == name).then(|| dir.join(file_name))
+}
+
+/// The part of a recording between two offsets (`None` = its start or end)
+pub fn clip(
+    samples: &[f32],
+    sample_rate: u32,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+) -> &[f32] {
+    let index = |ms: u64| ((ms * sample_rate as u64 / 1000) as usize).min(samples.len());
+    let start = start_ms.map_or(0, index);
+    let end = end_ms.map_or(samples.len(), index).max(start);
+    &samples[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::FileTimes;
+
+    #[test]
+    fn test_prune_and_clip() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let recordings = Recordings::new(
+            dir.path().to_path_buf(),
+            Retention {
+                max_files: 2,
+                max_days: 7,
+                max_mb: 0,
+            },
+        );
+        for (id, age_days) in [(1, 10), (2, 3), (3, 2), (4, 1)] {
+            let name = recordings.save(id, &[0.1; 160], 16000).unwrap();
+            let time = now - Duration::from_secs(age_days * 24 * 60 * 60);
+            fs::File::options()
+                .write(true)
+                .open(dir.path().join(name))
+                .unwrap()
+                .set_times(FileTimes::new().set_accessed(time).set_modified(time))
+                .unwrap();
+        }
+
+        // 1 is too old and 2 is past the newest two
+        assert_eq!(recordings.prune(now), 2);
+        assert!(!dir.path().join("1.wav").exists());
+        assert!(!dir.path().join("2.wav").exists());
+        assert!(dir.path().join("4.wav").exists());
+
+        assert_eq!(recording_path(dir.path(), "../history.json"), None);
+        assert_eq!(
+            recording_path(dir.path(), "3.wav"),
+            Some(dir.path().join("3.wav"))
+        );
+
+        let samples: Vec<f32> = (0..16000).map(|i| i as f32).collect();
+        let part = clip(&samples, 16000, Some(250), Some(500));
+        assert_eq!(part.len(), 4000);
+        assert_eq!(part[0], 4000.0);
+        assert_eq!(clip(&samples, 16000, Some(900), Some(5000)).len(), 1600);
+        assert_eq!(clip(&samples, 16000, None, None).len(), 16000);
+    }
+}
@@ -0,0 +1,171 @@
+//! History Search
+//!
+//! Backs `onevox history search`: finds entries whose text contains the
+//! query (case-insensitive, or a regular expression with `regex`), within
+//! an optional date range and for an optional model, ranked by how well
+//! they match and then by recency.
+
+use super::HistoryEntry;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// What to search history for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    /// Text to find (a pattern when `regex` is set)
+    pub text: String,
+    /// Treat `text` as a regular expression
+    pub regex: bool,
+    /// Only entries at or after this Unix timestamp
+    pub since: Option<u64>,
+    /// Only entries before this Unix timestamp
+    pub until: Option<u64>,
+    /// Only entries whose model name contains this (case-insensitive)
+    pub model: Option<String>,
+    /// Maximum number of matches (0 = all)
+    pub limit: usize,
+}
+
+/// A history entry matching a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMatch {
+    pub entry: HistoryEntry,
+    /// Relevance; higher is better
+    pub score: f32,
+    /// Byte ranges of the matches in `entry.text`
+    pub ranges: Vec<(usize, usize)>,
+}
+
+impl HistoryQuery {
+    /// Case-insensitive matcher for the query text
+    fn matcher(&self) -> crate::Result<Regex> {
+        let pattern = if self.regex {
+            self.text.clone()
+        } else {
+            regex::escape(&self.text)
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| crate::Error::Other(format!("Invalid search pattern: {}", e)))
+    }
+
+    fn in_range(&self, entry: &HistoryEntry) -> bool {
+        self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self
+                .model
+                .as_ref()
+                .is_none_or(|model| entry.model.to_lowercase().contains(&model.to_lowercase()))
+    }
+}
+
+/// Entries matching `query`, best first
+///
+/// Each match scores a point, plus half a point when it is a whole word
+/// and a bonus for short entries where the match is most of the text;
+/// equal scores are ordered newest first.
+pub fn search(entries: &[HistoryEntry], query: &HistoryQuery) -> crate::Result<Vec<HistoryMatch>> {
+    let matcher = query.matcher()?;
+    let mut matches: Vec<HistoryMatch> = entries
+        .iter()
+        .filter(|entry| query.in_range(entry))
+        .filter_map(|entry| {
+            let text = &entry.text;
+            let ranges: Vec<(usize, usize)> = matcher
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect();
+            // An empty query only filters by date and model
+            if ranges.is_empty() && !query.text.is_empty() {
+                return None;
+            }
+
+            let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+            let whole_words = ranges
+                .iter()
+                .filter(|&&(start, end)| {
+                    is_boundary(text[..start].chars().next_back())
+                        && is_boundary(text[end..].chars().next())
+                })
+                .count();
+            let coverage = ranges.iter().map(|(start, end)| end - start).sum::<usize>() as f32
+                / text.len().max(1) as f32;
+            Some(HistoryMatch {
+                entry: entry.clone(),
+                score: ranges.len() as f32 + 0.5 * whole_words as f32 + coverage,
+                ranges,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.entry.timestamp.cmp(&a.entry.timestamp))
+    });
+    if query.limit > 0 {
+        matches.truncate(query.limit);
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, model: &str, timestamp: u64) -> HistoryEntry {
+        HistoryEntry {
+            id: timestamp,
+            timestamp,
+            ..HistoryEntry::new(text.to_string(), model.to_string(), 1000, None)
+        }
+    }
+
+    #[test]
+    fn test_search_ranking_and_filters() {
+        let entries = vec![
+            entry("Deploy the build on Friday", "ggml-base.en", 100),
+            entry("deploy deploy deploy", "parakeet-ctc-0.6b", 200),
+            entry("The redeployment failed", "ggml-base.en", 300),
+            entry("Lunch at noon", "ggml-base.en", 400),
+        ];
+        let mut query = HistoryQuery {
+            text: "DEPLOY".to_string(),
+            ..Default::default()
+        };
+
+        let found = search(&entries, &query).unwrap();
+        let ids: Vec<u64> = found.iter().map(|m| m.entry.id).collect();
+        assert_eq!(ids, vec![200, 100, 300]);
+        assert_eq!(found[1].ranges, vec![(0, 6)]);
+        assert_eq!(found[2].ranges, vec![(6, 12)]);
+
+        query.model = Some("GGML".to_string());
+        query.since = Some(150);
+        let ids: Vec<u64> = search(&entries, &query)
+            .unwrap()
+            .iter()
+            .map(|m| m.entry.id)
+            .collect();
+        assert_eq!(ids, vec![300]);
+
+        let query = HistoryQuery {
+            text: r"^(lunch|deploy)\b".to_string(),
+            regex: true,
+            limit: 1,
+            ..Default::default()
+        };
+        let found = search(&entries, &query).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].entry.id, 400);
+
+        let invalid = HistoryQuery {
+            text: "(".to_string(),
+            regex: true,
+            ..Default::default()
+        };
+        assert!(search(&entries, &invalid).is_err());
+    }
+}
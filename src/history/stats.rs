@@ -0,0 +1,211 @@
+//! History Statistics
+//!
+//! Backs `onevox stats`: words dictated per day and per week, total
+//! speaking time, speaking rate, the most used model, and how often
+//! dictated text never reached the focused app (failed, blocked, held, or
+//! discarded). Days and weeks (Monday to Sunday) are in local time. The
+//! daily rows can be exported as CSV.
+
+use super::HistoryEntry;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Totals for one day or week
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeriodStats {
+    /// First day of the period (`YYYY-MM-DD`)
+    pub start: String,
+    /// Dictated segments
+    pub entries: u64,
+    /// Words dictated
+    pub words: u64,
+    /// Speaking time
+    pub speech_ms: u64,
+    /// Words in entries with a known speech length
+    pub timed_words: u64,
+    /// Segments that weren't typed
+    pub errors: u64,
+}
+
+impl PeriodStats {
+    fn add(&mut self, entry: &HistoryEntry) {
+        self.entries += 1;
+        self.words += entry.word_count as u64;
+        if let Some(speech_ms) = entry.speech_ms {
+            self.speech_ms += speech_ms;
+            self.timed_words += entry.word_count as u64;
+        }
+        self.errors += entry.not_injected.is_some() as u64;
+    }
+
+    /// Average speaking rate over entries with a known speech length
+    pub fn words_per_minute(&self) -> Option<f64> {
+        (self.speech_ms > 0).then(|| self.timed_words as f64 * 60_000.0 / self.speech_ms as f64)
+    }
+
+    /// Share of segments that weren't typed
+    pub fn error_rate(&self) -> f64 {
+        if self.entries == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.entries as f64
+        }
+    }
+}
+
+/// Aggregates over transcription history
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryStats {
+    /// Totals over every entry
+    pub total: PeriodStats,
+    /// Entries per model
+    pub models: BTreeMap<String, u64>,
+    /// Per-day totals, oldest first (days without entries are left out)
+    pub days: Vec<PeriodStats>,
+    /// Per-week totals, oldest first
+    pub weeks: Vec<PeriodStats>,
+}
+
+impl HistoryStats {
+    /// Aggregate the entries made at or after the Unix timestamp `since`
+    /// (all of them when `None`)
+    pub fn compute(entries: &[HistoryEntry], since: Option<u64>) -> Self {
+        let mut stats = Self::default();
+        let mut days: BTreeMap<NaiveDate, PeriodStats> = BTreeMap::new();
+        let mut weeks: BTreeMap<NaiveDate, PeriodStats> = BTreeMap::new();
+
+        for entry in entries
+            .iter()
+            .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+            // Repeats of the previous segment weren't dictated twice
+            .filter(|entry| !entry.duplicate)
+        {
+            stats.total.add(entry);
+            *stats.models.entry(entry.model.clone()).or_insert(0) += 1;
+
+            let day = local_date(entry.timestamp);
+            days.entry(day).or_default().add(entry);
+            weeks.entry(week_start(day)).or_default().add(entry);
+        }
+
+        let rows = |periods: BTreeMap<NaiveDate, PeriodStats>| {
+            periods
+                .into_iter()
+                .map(|(start, period)| PeriodStats {
+                    start: start.format("%Y-%m-%d").to_string(),
+                    ..period
+                })
+                .collect()
+        };
+        stats.days = rows(days);
+        stats.weeks = rows(weeks);
+        stats.total.start = stats
+            .days
+            .first()
+            .map(|day| day.start.clone())
+            .unwrap_or_default();
+        stats
+    }
+
+    /// Model with the most entries (the first by name on a tie)
+    pub fn most_used_model(&self) -> Option<(&str, u64)> {
+        self.models.iter().fold(
+            None,
+            |best: Option<(&str, u64)>, (model, &count)| match best {
+                Some((_, most)) if most >= count => best,
+                _ => Some((model.as_str(), count)),
+            },
+        )
+    }
+
+    /// Daily rows as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,entries,words,speech_seconds,words_per_minute,errors\n");
+        for day in &self.days {
+            let wpm = day
+                .words_per_minute()
+                .map(|wpm| format!("{:.1}", wpm))
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{:.1},{},{}\n",
+                day.start,
+                day.entries,
+                day.words,
+                day.speech_ms as f64 / 1000.0,
+                wpm,
+                day.errors
+            ));
+        }
+        csv
+    }
+}
+
+fn local_date(timestamp: u64) -> NaiveDate {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or(DateTime::UNIX_EPOCH)
+        .with_timezone(&Local)
+        .date_naive()
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, model: &str, timestamp: u64, speech_ms: Option<u64>) -> HistoryEntry {
+        HistoryEntry {
+            id: timestamp,
+            timestamp,
+            speech_ms,
+            ..HistoryEntry::new(text.to_string(), model.to_string(), 200, None)
+        }
+    }
+
+    #[test]
+    fn test_stats_from_history() {
+        const DAY: u64 = 86_400;
+        // Noon UTC stays on one local day in nearly every time zone
+        let noon = 1_700_000_000 - 1_700_000_000 % DAY + DAY / 2;
+        let mut failed = entry("not typed at all", "parakeet", noon + DAY, Some(2_000));
+        failed.not_injected = Some("failed: no focus".to_string());
+        let mut repeat = entry("one two three four", "whisper", noon, Some(2_000));
+        repeat.duplicate = true;
+        let entries = vec![
+            entry("one two three four", "whisper", noon, Some(2_000)),
+            repeat,
+            entry("five six", "whisper", noon + 60, None),
+            failed,
+            entry("old", "whisper", noon - 30 * DAY, Some(1_000)),
+        ];
+
+        let stats = HistoryStats::compute(&entries, Some(noon - DAY));
+        assert_eq!(stats.total.entries, 3);
+        assert_eq!(stats.total.words, 10);
+        assert_eq!(stats.total.speech_ms, 4_000);
+        // Eight timed words in four seconds
+        assert_eq!(stats.total.words_per_minute(), Some(120.0));
+        assert!((stats.total.error_rate() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats.most_used_model(), Some(("whisper", 2)));
+        assert_eq!(stats.days.len(), 2);
+        assert_eq!(stats.days[0].words, 6);
+        assert_eq!(stats.days[1].errors, 1);
+        assert!(!stats.weeks.is_empty());
+
+        let csv = stats.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].ends_with(",2,6,2.0,120.0,0"));
+
+        assert_eq!(HistoryStats::compute(&entries, None).total.entries, 4);
+        assert!(
+            HistoryStats::compute(&[], None)
+                .total
+                .words_per_minute()
+                .is_none()
+        );
+    }
+}
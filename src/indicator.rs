@@ -1,18 +1,32 @@
 //! Desktop recording/processing overlay indicator.
+//!
+//! The overlay runs in a child process that polls a state file: the mode on
+//! the first line, then `key=value` lines (`started_ms`, the Unix time the
+//! recording timer counts from, or `elapsed_ms` while it is stopped). With
+//! `ui.overlay.click_to_stop`, a click leaves a stop request file that the
+//! engine picks up.
+//!
+//! The same child process also shows the clipboard ring picker and the
+//! review window for low-confidence transcripts.
 
+use crate::config::{OverlayConfig, UiConfig};
 #[cfg(target_os = "macos")]
 use objc::{msg_send, sel, sel_impl};
 #[cfg(target_os = "macos")]
 use raw_window_handle::{HasWindowHandle as _, RawWindowHandle};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IndicatorMode {
     Recording,
     Processing,
+    /// Session open, audio ignored until resumed
+    Paused,
 }
 
 impl IndicatorMode {
@@ -20,6 +34,7 @@ impl IndicatorMode {
         match value {
             "recording" => Some(Self::Recording),
             "processing" => Some(Self::Processing),
+            "paused" => Some(Self::Paused),
             _ => None,
         }
     }
@@ -28,6 +43,7 @@ impl IndicatorMode {
         match self {
             Self::Recording => "recording",
             Self::Processing => "processing",
+            Self::Paused => "paused",
         }
     }
 
@@ -35,6 +51,7 @@ impl IndicatorMode {
         match self {
             Self::Recording => "RECORDING",
             Self::Processing => "PROCESSING",
+            Self::Paused => "PAUSED",
         }
     }
 
@@ -42,10 +59,159 @@ impl IndicatorMode {
         match self {
             Self::Recording => 1.0,
             Self::Processing => 0.6,
+            Self::Paused => 0.0,
         }
     }
 }
 
+/// Screen edge or corner the overlay is placed against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl OverlayAnchor {
+    /// Parse a `ui.overlay.anchor` value
+    pub fn parse(value: &str) -> crate::Result<Self> {
+        match value.to_ascii_lowercase().replace('_', "-").as_str() {
+            "top-left" => Ok(Self::TopLeft),
+            "top-center" | "top" => Ok(Self::TopCenter),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-center" | "bottom" => Ok(Self::BottomCenter),
+            "bottom-right" => Ok(Self::BottomRight),
+            other => Err(crate::Error::Config(format!(
+                "Unknown overlay anchor '{}' (expected top-left, top-center, top-right, bottom-left, bottom-center, or bottom-right)",
+                other
+            ))),
+        }
+    }
+
+    /// Top-left corner of a `window`-sized overlay on a `screen`-sized
+    /// monitor, `offset` away from the anchored edges
+    pub fn position(
+        self,
+        screen: (f32, f32),
+        window: (f32, f32),
+        offset: (f32, f32),
+    ) -> (f32, f32) {
+        let x = match self {
+            Self::TopLeft | Self::BottomLeft => offset.0,
+            Self::TopCenter | Self::BottomCenter => (screen.0 - window.0) * 0.5 + offset.0,
+            Self::TopRight | Self::BottomRight => screen.0 - window.0 - offset.0,
+        };
+        let y = match self {
+            Self::TopLeft | Self::TopCenter | Self::TopRight => offset.1,
+            _ => screen.1 - window.1 - offset.1,
+        };
+        (
+            x.clamp(0.0, (screen.0 - window.0).max(0.0)),
+            y.clamp(0.0, (screen.1 - window.1).max(0.0)),
+        )
+    }
+}
+
+/// Overlay look and placement, resolved from `[ui.overlay]` and handed to
+/// the child process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayStyle {
+    pub anchor: OverlayAnchor,
+    pub offset: (f32, f32),
+    pub scale: f32,
+    pub background: [u8; 3],
+    pub foreground: [u8; 3],
+    pub opacity: f32,
+    pub timer: bool,
+    pub click_to_stop: bool,
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        Self::from_config(&OverlayConfig::default())
+    }
+}
+
+impl OverlayStyle {
+    /// Resolve the configured style; invalid values fall back to the
+    /// defaults with a warning
+    pub fn from_config(config: &OverlayConfig) -> Self {
+        let anchor = OverlayAnchor::parse(&config.anchor).unwrap_or_else(|e| {
+            tracing::warn!("{}; using bottom-center", e);
+            OverlayAnchor::BottomCenter
+        });
+        let color = |value: &str, fallback: [u8; 3]| {
+            parse_color(value).unwrap_or_else(|| {
+                tracing::warn!("Invalid overlay color '{}' (expected #RRGGBB)", value);
+                fallback
+            })
+        };
+        Self {
+            anchor,
+            offset: (config.offset_x, config.offset_y),
+            scale: if config.scale > 0.0 {
+                config.scale.clamp(0.5, 4.0)
+            } else {
+                1.0
+            },
+            background: color(&config.background, [0, 0, 0]),
+            foreground: color(&config.foreground, [255, 255, 255]),
+            opacity: config.opacity.clamp(0.1, 1.0),
+            timer: config.timer,
+            click_to_stop: config.click_to_stop,
+        }
+    }
+}
+
+/// Parse a "#RRGGBB" color
+fn parse_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Recording time shown by the overlay timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Elapsed {
+    /// Counting from this Unix time, in milliseconds
+    Since(u64),
+    /// Stopped at this many milliseconds
+    Frozen(u64),
+}
+
+impl Elapsed {
+    fn millis(self) -> u64 {
+        match self {
+            Self::Since(started) => unix_millis(SystemTime::now()).saturating_sub(started),
+            Self::Frozen(elapsed) => elapsed,
+        }
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Timer text: "0:07", "12:30", "1:02:03"
+fn format_elapsed(millis: u64) -> String {
+    let secs = millis / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
 struct ChildIndicator {
     child: Child,
 }
@@ -53,6 +219,37 @@ struct ChildIndicator {
 #[derive(Default)]
 struct IndicatorRuntime {
     child: Option<ChildIndicator>,
+    /// Recording time before the running stretch
+    recorded: Duration,
+    /// Start of the running stretch (none while paused or hidden)
+    running_since: Option<SystemTime>,
+}
+
+impl IndicatorRuntime {
+    /// Follow the session into `mode`, returning the timer to show
+    fn track(&mut self, mode: IndicatorMode) -> Elapsed {
+        let now = SystemTime::now();
+        match (mode, self.running_since) {
+            (IndicatorMode::Paused, Some(since)) => {
+                self.recorded += now.duration_since(since).unwrap_or_default();
+                self.running_since = None;
+            }
+            (IndicatorMode::Recording, None) => {
+                self.running_since = Some(now);
+            }
+            _ => {}
+        }
+        let recorded = self.recorded.as_millis() as u64;
+        match self.running_since {
+            Some(since) => Elapsed::Since(unix_millis(since).saturating_sub(recorded)),
+            None => Elapsed::Frozen(recorded),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.recorded = Duration::ZERO;
+        self.running_since = None;
+    }
 }
 
 /// Cross-platform indicator controller.
@@ -61,13 +258,15 @@ struct IndicatorRuntime {
 /// event loop on that process main thread.
 pub struct RecordingIndicator {
     enabled: bool,
+    style: OverlayStyle,
     runtime: Mutex<IndicatorRuntime>,
 }
 
 impl RecordingIndicator {
-    pub fn new(enabled: bool) -> Self {
+    pub fn new(config: &UiConfig) -> Self {
         Self {
-            enabled: enabled && cfg!(feature = "overlay-indicator"),
+            enabled: config.recording_overlay && cfg!(feature = "overlay-indicator"),
+            style: OverlayStyle::from_config(&config.overlay),
             runtime: Mutex::new(IndicatorRuntime::default()),
         }
     }
@@ -80,6 +279,10 @@ impl RecordingIndicator {
         self.show(IndicatorMode::Processing);
     }
 
+    pub fn paused(&self) {
+        self.show(IndicatorMode::Paused);
+    }
+
     pub fn hide(&self) {
         if !self.enabled {
             return;
@@ -91,21 +294,38 @@ impl RecordingIndicator {
             Ok(guard) => guard,
             Err(_) => return,
         };
+        guard.reset();
         stop_child(&mut guard.child);
     }
 
+    /// Whether the overlay was clicked to stop dictation since the last
+    /// check
+    pub fn take_stop_request(&self) -> bool {
+        self.enabled
+            && self.style.click_to_stop
+            && stop_request_path().is_some_and(|path| fs::remove_file(path).is_ok())
+    }
+
     fn show(&self, mode: IndicatorMode) {
         if !self.enabled {
             return;
         }
 
-        write_indicator_state(Some(mode));
-
         let mut guard = match self.runtime.lock() {
             Ok(guard) => guard,
             Err(_) => return,
         };
 
+        // A click left over from an earlier session doesn't stop this one
+        if guard.running_since.is_none()
+            && guard.recorded.is_zero()
+            && let Some(path) = stop_request_path()
+        {
+            let _ = fs::remove_file(path);
+        }
+        let elapsed = guard.track(mode);
+        write_indicator_state(Some((mode, elapsed)));
+
         if let Some(existing) = &mut guard.child {
             if let Ok(None) = existing.child.try_wait() {
                 return;
@@ -113,7 +333,7 @@ impl RecordingIndicator {
             stop_child(&mut guard.child);
         }
 
-        if let Some(child) = spawn_child(mode) {
+        if let Some(child) = spawn_child(mode, &self.style) {
             guard.child = Some(ChildIndicator { child });
         } else {
             tracing::warn!("Failed to start overlay indicator process");
@@ -128,12 +348,18 @@ fn stop_child(slot: &mut Option<ChildIndicator>) {
     }
 }
 
-fn spawn_child(mode: IndicatorMode) -> Option<Child> {
+fn spawn_child(mode: IndicatorMode, style: &OverlayStyle) -> Option<Child> {
     let exe = std::env::current_exe().ok()?;
-    Command::new(exe)
+    let mut command = Command::new(exe);
+    if let Some(dir) = crate::platform::paths::portable_dir() {
+        command.env(crate::platform::paths::PORTABLE_DIR_ENV, dir);
+    }
+    command
         .arg("indicator")
         .arg("--mode")
         .arg(mode.as_cli())
+        .arg("--style")
+        .arg(serde_json::to_string(style).ok()?)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -147,54 +373,93 @@ fn indicator_state_path() -> Option<PathBuf> {
         .map(|d| d.join("indicator.state"))
 }
 
-fn write_indicator_state(mode: Option<IndicatorMode>) {
+fn stop_request_path() -> Option<PathBuf> {
+    crate::platform::paths::cache_dir()
+        .ok()
+        .map(|d| d.join("indicator.stop"))
+}
+
+/// State file contents for a mode and timer (`None` = hidden)
+fn format_state(state: Option<(IndicatorMode, Elapsed)>) -> String {
+    match state {
+        Some((mode, Elapsed::Since(started))) => {
+            format!("{}\nstarted_ms={}\n", mode.as_cli(), started)
+        }
+        Some((mode, Elapsed::Frozen(elapsed))) => {
+            format!("{}\nelapsed_ms={}\n", mode.as_cli(), elapsed)
+        }
+        None => "hidden\n".to_string(),
+    }
+}
+
+/// Parse the state file; `None` when it is unreadable, `Some(None)` when
+/// the overlay should close
+fn parse_state(content: &str) -> Option<Option<(IndicatorMode, Option<Elapsed>)>> {
+    let mut lines = content.lines().map(str::trim);
+    let mode = match lines.next()? {
+        "hidden" => return Some(None),
+        mode => IndicatorMode::from_cli(mode)?,
+    };
+    let elapsed = lines.find_map(|line| match line.split_once('=')? {
+        ("started_ms", value) => value.parse().ok().map(Elapsed::Since),
+        ("elapsed_ms", value) => value.parse().ok().map(Elapsed::Frozen),
+        _ => None,
+    });
+    Some(Some((mode, elapsed)))
+}
+
+fn write_indicator_state(state: Option<(IndicatorMode, Elapsed)>) {
     let Some(path) = indicator_state_path() else {
         return;
     };
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
-    let value = match mode {
-        Some(IndicatorMode::Recording) => "recording",
-        Some(IndicatorMode::Processing) => "processing",
-        None => "hidden",
-    };
-    let _ = fs::write(path, value);
+    let _ = fs::write(path, format_state(state));
 }
 
-fn read_indicator_state() -> Option<Option<IndicatorMode>> {
+fn read_indicator_state() -> Option<Option<(IndicatorMode, Option<Elapsed>)>> {
     let path = indicator_state_path()?;
-    let content = fs::read_to_string(path).ok()?;
-    let value = content.trim();
-    let parsed = match value {
-        "recording" => Some(IndicatorMode::Recording),
-        "processing" => Some(IndicatorMode::Processing),
-        "hidden" => None,
-        _ => return None,
-    };
-    Some(parsed)
+    parse_state(&fs::read_to_string(path).ok()?)
 }
 
 /// Run overlay UI process.
 ///
 /// This must execute on the process main thread.
-pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
+pub fn run_indicator(mode: IndicatorMode, style: OverlayStyle) -> crate::Result<()> {
     #[cfg(not(feature = "overlay-indicator"))]
     {
-        let _ = mode;
-        return Ok(());
+        let _ = (mode, style);
+        Ok(())
     }
 
     #[cfg(feature = "overlay-indicator")]
     {
         use eframe::egui;
-        use std::time::{Duration, Instant};
+        use std::time::Instant;
         const WINDOW_WIDTH: f32 = 110.0;
         const WINDOW_HEIGHT: f32 = 36.0;
-        const BOTTOM_MARGIN: f32 = 20.0;
+        const TIMER_WIDTH: f32 = 44.0;
+
+        let scale = style.scale;
+        let window_size = egui::vec2(
+            (WINDOW_WIDTH + if style.timer { TIMER_WIDTH } else { 0.0 }) * scale,
+            WINDOW_HEIGHT * scale,
+        );
+        let alpha = (style.opacity * 255.0).round() as u8;
+        let [r, g, b] = style.background;
+        let background = egui::Color32::from_rgba_unmultiplied(r, g, b, alpha);
+        let [r, g, b] = style.foreground;
+        let foreground = egui::Color32::from_rgb(r, g, b);
 
         struct OverlayApp {
             mode: IndicatorMode,
+            style: OverlayStyle,
+            background: egui::Color32,
+            foreground: egui::Color32,
+            window_size: egui::Vec2,
+            elapsed: Option<Elapsed>,
+            stop_requested: bool,
             phase_start: Instant,
             positioned: bool,
             last_state_poll: Instant,
@@ -206,7 +471,7 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
         impl OverlayApp {
             #[cfg(target_os = "macos")]
             #[allow(unexpected_cfgs)]
-            fn elevate_macos_window_level(frame: &eframe::Frame) {
+            fn elevate_macos_window_level(frame: &eframe::Frame, ignore_mouse: bool) {
                 let Ok(window_handle) = frame.window_handle() else {
                     return;
                 };
@@ -231,25 +496,53 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
                     }
                     #[allow(unexpected_cfgs)]
                     let _: () = msg_send![ns_window, setLevel: NS_POPUP_MENU_WINDOW_LEVEL];
-                    // Ensure the overlay never captures pointer interactions on macOS.
+                    // Ensure the overlay never captures pointer interactions on macOS,
+                    // unless clicking it stops dictation.
                     #[allow(unexpected_cfgs)]
-                    let _: () = msg_send![ns_window, setIgnoresMouseEvents: true];
+                    let _: () = msg_send![ns_window, setIgnoresMouseEvents: ignore_mouse];
                 }
             }
 
-            fn draw_waveform(&self, ui: &mut egui::Ui, t: f32) {
+            /// Draw the waveform and timer, returning whether the overlay
+            /// was clicked
+            fn draw_waveform(&self, ui: &mut egui::Ui, t: f32) -> bool {
+                let scale = self.style.scale;
                 let desired = egui::vec2(ui.available_width(), ui.available_height());
-                let (rect, _) = ui.allocate_exact_size(desired, egui::Sense::hover());
-                let painter = ui.painter_at(rect);
+                let sense = if self.style.click_to_stop {
+                    egui::Sense::click()
+                } else {
+                    egui::Sense::hover()
+                };
+                let (full_rect, response) = ui.allocate_exact_size(desired, sense);
+                let painter = ui.painter_at(full_rect);
+
+                let mut rect = full_rect;
+                if self.style.timer {
+                    rect.set_right(full_rect.right() - TIMER_WIDTH * scale);
+                    let millis = self.elapsed.map_or(0, Elapsed::millis);
+                    painter.text(
+                        egui::pos2(full_rect.right() - 2.0 * scale, full_rect.center().y),
+                        egui::Align2::RIGHT_CENTER,
+                        format_elapsed(millis),
+                        egui::FontId::monospace(11.0 * scale),
+                        self.foreground,
+                    );
+                }
 
                 let center_y = rect.center().y;
                 let left = rect.left();
                 let width = rect.width();
                 let lane_count = 3usize;
+                let paused = self.mode == IndicatorMode::Paused;
+                let color = if paused {
+                    self.foreground.gamma_multiply(0.35)
+                } else {
+                    self.foreground
+                };
                 let points_per_lane = 70usize;
 
                 for lane in 0..lane_count {
-                    let lane_offset = (lane as f32 - 1.0) * 3.0;
+                    let lane_offset = (lane as f32 - 1.0) * 3.0 * scale;
                     let lane_phase = t * 3.6 + lane as f32 * 0.65;
                     let amplitude = self.mode.amplitude();
                     let mut points = Vec::with_capacity(points_per_lane);
@@ -261,23 +554,43 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
                         let signal = (x_norm * std::f32::consts::TAU * 2.0 + lane_phase).sin()
                             + 0.45
                                 * (x_norm * std::f32::consts::TAU * 4.5 - lane_phase * 1.5).sin();
-                        let y = center_y + lane_offset + signal * envelope * 4.5 * amplitude;
+                        let y =
+                            center_y + lane_offset + signal * envelope * 4.5 * scale * amplitude;
                         points.push(egui::pos2(x, y));
                     }
 
                     painter.add(egui::Shape::line(
                         points,
-                        egui::Stroke::new(1.2, egui::Color32::WHITE),
+                        egui::Stroke::new(1.2 * scale, color),
                     ));
                 }
+
+                // Pause bars over the flat lines
+                if paused {
+                    let amber = egui::Color32::from_rgb(255, 176, 32);
+                    for dx in [-4.0, 4.0] {
+                        let bar = egui::Rect::from_center_size(
+                            egui::pos2(rect.center().x + dx * scale, center_y),
+                            egui::vec2(4.0, 14.0) * scale,
+                        );
+                        painter.rect_filled(bar, 1.0, amber);
+                    }
+                }
+
+                response.clicked()
             }
         }
 
         impl eframe::App for OverlayApp {
+            fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+                // Let the translucent background show what is behind it
+                [0.0; 4]
+            }
+
             fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
                 #[cfg(target_os = "macos")]
                 if !self.macos_window_level_set {
-                    Self::elevate_macos_window_level(frame);
+                    Self::elevate_macos_window_level(frame, !self.style.click_to_stop);
                     self.macos_window_level_set = true;
                 }
 
@@ -285,10 +598,11 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
                     self.last_state_poll = Instant::now();
                     if let Some(state) = read_indicator_state() {
                         match state {
-                            Some(mode) => {
+                            Some((mode, elapsed)) => {
+                                self.elapsed = elapsed;
                                 if mode != self.mode {
-                                    // Mode changed - freeze the phase if switching to Processing
-                                    if mode == IndicatorMode::Processing {
+                                    // Mode changed - freeze the phase unless recording
+                                    if mode != IndicatorMode::Recording {
                                         self.frozen_phase =
                                             self.phase_start.elapsed().as_secs_f32();
                                     }
@@ -306,13 +620,13 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
                 // Use frozen phase for processing mode, live elapsed time for recording
                 let elapsed = match self.mode {
                     IndicatorMode::Recording => self.phase_start.elapsed().as_secs_f32(),
-                    IndicatorMode::Processing => self.frozen_phase,
+                    IndicatorMode::Processing | IndicatorMode::Paused => self.frozen_phase,
                 };
 
                 egui::CentralPanel::default()
                     .frame(
                         egui::Frame::new()
-                            .fill(egui::Color32::BLACK)
+                            .fill(self.background)
                             .stroke(egui::Stroke::NONE)
                             .corner_radius(0.0)
                             .inner_margin(egui::Margin::same(4))
@@ -322,15 +636,23 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
                         if !self.positioned
                             && let Some(size) = ctx.input(|i| i.viewport().monitor_size)
                         {
-                            let x = ((size.x - WINDOW_WIDTH) * 0.5).max(0.0);
-                            let y = (size.y - WINDOW_HEIGHT - BOTTOM_MARGIN).max(0.0);
+                            let (x, y) = self.style.anchor.position(
+                                (size.x, size.y),
+                                (self.window_size.x, self.window_size.y),
+                                self.style.offset,
+                            );
                             ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(
                                 egui::pos2(x, y),
                             ));
                             self.positioned = true;
                         }
 
-                        self.draw_waveform(ui, elapsed);
+                        if self.draw_waveform(ui, elapsed) && !self.stop_requested {
+                            // The engine checks for this file and stops the session
+                            if let Some(path) = stop_request_path() {
+                                self.stop_requested = fs::write(path, b"stop").is_ok();
+                            }
+                        }
                     });
 
                 ctx.request_repaint_after(Duration::from_millis(16));
@@ -341,11 +663,11 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
             .with_title("Onevox Indicator")
             .with_decorations(false)
             .with_resizable(false)
-            .with_transparent(false)
+            .with_transparent(style.opacity < 1.0)
             .with_active(false)
             .with_always_on_top()
-            .with_mouse_passthrough(true)
-            .with_inner_size([WINDOW_WIDTH, WINDOW_HEIGHT]);
+            .with_mouse_passthrough(!style.click_to_stop)
+            .with_inner_size(window_size);
 
         #[cfg_attr(not(target_os = "macos"), allow(unused_mut))]
         let mut native_options = eframe::NativeOptions {
@@ -365,19 +687,29 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
             "onevox-indicator",
             native_options,
             Box::new(move |cc| {
-                let mut style = (*cc.egui_ctx.style()).clone();
-                style.visuals.window_stroke = egui::Stroke::NONE;
-                style.visuals.window_fill = egui::Color32::BLACK;
-                style.visuals.panel_fill = egui::Color32::BLACK;
-                style.visuals.window_shadow = egui::epaint::Shadow::NONE;
-                style.visuals.popup_shadow = egui::epaint::Shadow::NONE;
-                style.spacing.window_margin = egui::Margin::ZERO;
-                cc.egui_ctx.set_style(style);
+                let mut egui_style = (*cc.egui_ctx.style()).clone();
+                egui_style.visuals.window_stroke = egui::Stroke::NONE;
+                egui_style.visuals.window_fill = background;
+                egui_style.visuals.panel_fill = background;
+                egui_style.visuals.window_shadow = egui::epaint::Shadow::NONE;
+                egui_style.visuals.popup_shadow = egui::epaint::Shadow::NONE;
+                egui_style.spacing.window_margin = egui::Margin::ZERO;
+                cc.egui_ctx.set_style(egui_style);
                 cc.egui_ctx
-                    .send_viewport_cmd(egui::ViewportCommand::MousePassthrough(true));
+                    .send_viewport_cmd(egui::ViewportCommand::MousePassthrough(
+                        !style.click_to_stop,
+                    ));
 
                 Ok(Box::new(OverlayApp {
                     mode,
+                    style,
+                    background,
+                    foreground,
+                    window_size,
+                    elapsed: read_indicator_state()
+                        .flatten()
+                        .and_then(|(_, elapsed)| elapsed),
+                    stop_requested: false,
                     phase_start: Instant::now(),
                     positioned: false,
                     last_state_poll: Instant::now(),
@@ -392,3 +724,549 @@ pub fn run_indicator(mode: IndicatorMode) -> crate::Result<()> {
         Ok(())
     }
 }
+
+/// Show the clipboard ring picker in a child process and wait for it
+///
+/// Returns the selected transcript, or `None` when the picker was closed
+/// without a selection. Blocks until the picker exits.
+pub fn pick_from_ring() -> crate::Result<Option<String>> {
+    if !cfg!(feature = "overlay-indicator") {
+        return Err(crate::Error::Platform(
+            "The clipboard picker needs the overlay-indicator feature".to_string(),
+        ));
+    }
+
+    let exe = std::env::current_exe()?;
+    let mut command = Command::new(exe);
+    if let Some(dir) = crate::platform::paths::portable_dir() {
+        command.env(crate::platform::paths::PORTABLE_DIR_ENV, dir);
+    }
+    let output = command
+        .arg("picker")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(crate::Error::Platform(format!(
+            "Clipboard picker exited with {}",
+            output.status
+        )));
+    }
+
+    let selected = String::from_utf8_lossy(&output.stdout).into_owned();
+    Ok((!selected.is_empty()).then_some(selected))
+}
+
+/// One line preview of a ring entry
+fn picker_preview(text: &str, max_chars: usize) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    let mut preview: String = line.chars().take(max_chars).collect();
+    if preview.len() < line.len() || text.lines().nth(1).is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+/// Run the clipboard ring picker UI and print the selected entry to stdout.
+///
+/// Keys 1-9 pick an entry directly; arrows and Enter move and select;
+/// Escape or clicking elsewhere closes without a selection. This must
+/// execute on the process main thread.
+pub fn run_picker() -> crate::Result<()> {
+    let path = crate::history::ClipboardRing::default_path()?;
+    let ring = crate::history::ClipboardRing::load(&path, usize::MAX);
+    let entries: Vec<String> = ring.entries().map(str::to_string).collect();
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(not(feature = "overlay-indicator"))]
+    {
+        Err(crate::Error::Platform(
+            "The clipboard picker needs the overlay-indicator feature".to_string(),
+        ))
+    }
+
+    #[cfg(feature = "overlay-indicator")]
+    {
+        use eframe::egui;
+        use std::io::Write;
+        use std::sync::Arc;
+
+        const WINDOW_WIDTH: f32 = 460.0;
+        const ROW_HEIGHT: f32 = 26.0;
+        const MAX_ROWS: usize = 12;
+        const PREVIEW_CHARS: usize = 60;
+
+        struct PickerApp {
+            entries: Vec<String>,
+            cursor: usize,
+            selected: Arc<Mutex<Option<String>>>,
+            positioned: bool,
+            was_focused: bool,
+        }
+
+        impl PickerApp {
+            fn choose(&self, ctx: &egui::Context, index: usize) {
+                if let (Some(entry), Ok(mut selected)) =
+                    (self.entries.get(index), self.selected.lock())
+                {
+                    *selected = Some(entry.clone());
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+
+        impl eframe::App for PickerApp {
+            fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+                const DIGITS: [egui::Key; 9] = [
+                    egui::Key::Num1,
+                    egui::Key::Num2,
+                    egui::Key::Num3,
+                    egui::Key::Num4,
+                    egui::Key::Num5,
+                    egui::Key::Num6,
+                    egui::Key::Num7,
+                    egui::Key::Num8,
+                    egui::Key::Num9,
+                ];
+
+                // Clicking another window dismisses the picker
+                match ctx.input(|i| i.viewport().focused) {
+                    Some(true) => self.was_focused = true,
+                    Some(false) if self.was_focused => {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        return;
+                    }
+                    _ => {}
+                }
+
+                let last = self.entries.len() - 1;
+                let (up, down, enter, escape, digit) = ctx.input(|i| {
+                    (
+                        i.key_pressed(egui::Key::ArrowUp),
+                        i.key_pressed(egui::Key::ArrowDown),
+                        i.key_pressed(egui::Key::Enter),
+                        i.key_pressed(egui::Key::Escape),
+                        DIGITS.iter().position(|&key| i.key_pressed(key)),
+                    )
+                });
+                if escape {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    return;
+                }
+                if let Some(index) = digit.filter(|&index| index <= last) {
+                    self.choose(ctx, index);
+                    return;
+                }
+                if enter {
+                    self.choose(ctx, self.cursor);
+                    return;
+                }
+                if up {
+                    self.cursor = self.cursor.saturating_sub(1);
+                }
+                if down {
+                    self.cursor = (self.cursor + 1).min(last);
+                }
+
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if !self.positioned
+                        && let Some(size) = ctx.input(|i| i.viewport().monitor_size)
+                    {
+                        let height = ROW_HEIGHT * (self.entries.len().min(MAX_ROWS) as f32 + 1.0);
+                        let x = ((size.x - WINDOW_WIDTH) * 0.5).max(0.0);
+                        let y = ((size.y - height) * 0.4).max(0.0);
+                        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(
+                            x, y,
+                        )));
+                        self.positioned = true;
+                    }
+
+                    ui.label("Paste a recent transcript (1-9, ↑↓ Enter, Esc)");
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        let mut clicked = None;
+                        for (index, entry) in self.entries.iter().enumerate() {
+                            let number = if index < DIGITS.len() {
+                                format!("{}", index + 1)
+                            } else {
+                                " ".to_string()
+                            };
+                            let row = ui.selectable_label(
+                                index == self.cursor,
+                                format!("{}  {}", number, picker_preview(entry, PREVIEW_CHARS)),
+                            );
+                            if index == self.cursor {
+                                row.scroll_to_me(None);
+                            }
+                            if row.clicked() {
+                                clicked = Some(index);
+                            }
+                        }
+                        if let Some(index) = clicked {
+                            self.choose(ctx, index);
+                        }
+                    });
+                });
+            }
+        }
+
+        let rows = entries.len().min(MAX_ROWS) as f32 + 1.0;
+        let viewport = egui::ViewportBuilder::default()
+            .with_title("Onevox Clipboard")
+            .with_decorations(false)
+            .with_resizable(false)
+            .with_always_on_top()
+            .with_active(true)
+            .with_inner_size([WINDOW_WIDTH, ROW_HEIGHT * rows + 16.0]);
+
+        #[cfg_attr(not(target_os = "macos"), allow(unused_mut))]
+        let mut native_options = eframe::NativeOptions {
+            viewport,
+            ..Default::default()
+        };
+
+        #[cfg(target_os = "macos")]
+        {
+            use winit::platform::macos::{ActivationPolicy, EventLoopBuilderExtMacOS};
+            native_options.event_loop_builder = Some(Box::new(|builder| {
+                builder.with_activation_policy(ActivationPolicy::Accessory);
+            }));
+        }
+
+        let selected = Arc::new(Mutex::new(None));
+        let app_selected = Arc::clone(&selected);
+        eframe::run_native(
+            "onevox-picker",
+            native_options,
+            Box::new(move |_cc| {
+                Ok(Box::new(PickerApp {
+                    entries,
+                    cursor: 0,
+                    selected: app_selected,
+                    positioned: false,
+                    was_focused: false,
+                }))
+            }),
+        )
+        .map_err(|e| crate::Error::Other(format!("Clipboard picker failed: {}", e)))?;
+
+        let selected = selected.lock().ok().and_then(|mut s| s.take());
+        if let Some(text) = selected {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(text.as_bytes())?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// What the user chose in the review window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewChoice {
+    /// Inject the transcript
+    Accept,
+    /// Drop it (it stays in history)
+    Discard,
+    /// Drop it and dictate again
+    Rerecord,
+}
+
+impl ReviewChoice {
+    fn as_cli(self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Discard => "discard",
+            Self::Rerecord => "rerecord",
+        }
+    }
+
+    /// Parse the review process output; anything else, including nothing
+    /// (the window was closed), discards
+    fn from_output(output: &str) -> Self {
+        match output.trim() {
+            "accept" => Self::Accept,
+            "rerecord" => Self::Rerecord,
+            _ => Self::Discard,
+        }
+    }
+}
+
+/// Show `text` in the review window in a child process and wait for the
+/// user's choice (blocking)
+pub fn review_transcript(text: &str, confidence: f32) -> crate::Result<ReviewChoice> {
+    use std::io::Write;
+
+    if !cfg!(feature = "overlay-indicator") {
+        return Err(crate::Error::Platform(
+            "The review window needs the overlay-indicator feature".to_string(),
+        ));
+    }
+
+    let exe = std::env::current_exe()?;
+    let mut command = Command::new(exe);
+    if let Some(dir) = crate::platform::paths::portable_dir() {
+        command.env(crate::platform::paths::PORTABLE_DIR_ENV, dir);
+    }
+    let mut child = command
+        .arg("review")
+        .arg("--confidence")
+        .arg(confidence.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // The transcript goes through stdin, out of the process list
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(crate::Error::Platform(format!(
+            "Review window exited with {}",
+            output.status
+        )));
+    }
+    Ok(ReviewChoice::from_output(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Run the review window for the transcript on stdin and print the choice
+/// to stdout.
+///
+/// Enter accepts, Escape discards, R re-records; clicking elsewhere
+/// discards. This must execute on the process main thread.
+pub fn run_review(confidence: f32) -> crate::Result<()> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(not(feature = "overlay-indicator"))]
+    {
+        let _ = confidence;
+        Err(crate::Error::Platform(
+            "The review window needs the overlay-indicator feature".to_string(),
+        ))
+    }
+
+    #[cfg(feature = "overlay-indicator")]
+    {
+        use eframe::egui;
+        use std::io::Write;
+        use std::sync::Arc;
+
+        const WINDOW_WIDTH: f32 = 460.0;
+        const WINDOW_HEIGHT: f32 = 160.0;
+
+        struct ReviewApp {
+            text: String,
+            confidence: f32,
+            choice: Arc<Mutex<Option<ReviewChoice>>>,
+            positioned: bool,
+            was_focused: bool,
+        }
+
+        impl ReviewApp {
+            fn choose(&self, ctx: &egui::Context, choice: ReviewChoice) {
+                if let Ok(mut slot) = self.choice.lock() {
+                    *slot = Some(choice);
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+
+        impl eframe::App for ReviewApp {
+            fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+                // Clicking another window discards the transcript
+                match ctx.input(|i| i.viewport().focused) {
+                    Some(true) => self.was_focused = true,
+                    Some(false) if self.was_focused => {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        return;
+                    }
+                    _ => {}
+                }
+
+                let (enter, escape, rerecord) = ctx.input(|i| {
+                    (
+                        i.key_pressed(egui::Key::Enter),
+                        i.key_pressed(egui::Key::Escape),
+                        i.key_pressed(egui::Key::R),
+                    )
+                });
+                if enter {
+                    self.choose(ctx, ReviewChoice::Accept);
+                    return;
+                }
+                if escape {
+                    self.choose(ctx, ReviewChoice::Discard);
+                    return;
+                }
+                if rerecord {
+                    self.choose(ctx, ReviewChoice::Rerecord);
+                    return;
+                }
+
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if !self.positioned
+                        && let Some(size) = ctx.input(|i| i.viewport().monitor_size)
+                    {
+                        let x = ((size.x - WINDOW_WIDTH) * 0.5).max(0.0);
+                        let y = ((size.y - WINDOW_HEIGHT) * 0.4).max(0.0);
+                        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(
+                            x, y,
+                        )));
+                        self.positioned = true;
+                    }
+
+                    ui.label(format!(
+                        "Not sure I heard that right ({:.0}% confidence)",
+                        self.confidence * 100.0
+                    ));
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(WINDOW_HEIGHT - 70.0)
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(&self.text).strong());
+                        });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Insert (Enter)").clicked() {
+                            self.choose(ctx, ReviewChoice::Accept);
+                        }
+                        if ui.button("Discard (Esc)").clicked() {
+                            self.choose(ctx, ReviewChoice::Discard);
+                        }
+                        if ui.button("Re-record (R)").clicked() {
+                            self.choose(ctx, ReviewChoice::Rerecord);
+                        }
+                    });
+                });
+            }
+        }
+
+        let viewport = egui::ViewportBuilder::default()
+            .with_title("Onevox Review")
+            .with_decorations(false)
+            .with_resizable(false)
+            .with_always_on_top()
+            .with_active(true)
+            .with_inner_size([WINDOW_WIDTH, WINDOW_HEIGHT]);
+
+        #[cfg_attr(not(target_os = "macos"), allow(unused_mut))]
+        let mut native_options = eframe::NativeOptions {
+            viewport,
+            ..Default::default()
+        };
+
+        #[cfg(target_os = "macos")]
+        {
+            use winit::platform::macos::{ActivationPolicy, EventLoopBuilderExtMacOS};
+            native_options.event_loop_builder = Some(Box::new(|builder| {
+                builder.with_activation_policy(ActivationPolicy::Accessory);
+            }));
+        }
+
+        let choice = Arc::new(Mutex::new(None));
+        let app_choice = Arc::clone(&choice);
+        let text = text.trim().to_string();
+        eframe::run_native(
+            "onevox-review",
+            native_options,
+            Box::new(move |_cc| {
+                Ok(Box::new(ReviewApp {
+                    text,
+                    confidence,
+                    choice: app_choice,
+                    positioned: false,
+                    was_focused: false,
+                }))
+            }),
+        )
+        .map_err(|e| crate::Error::Other(format!("Review window failed: {}", e)))?;
+
+        let choice = choice.lock().ok().and_then(|mut c| c.take());
+        if let Some(choice) = choice {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(choice.as_cli().as_bytes())?;
+            stdout.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_state_and_placement() {
+        let state = Some((IndicatorMode::Recording, Elapsed::Since(1_700_000_000_000)));
+        assert_eq!(
+            parse_state(&format_state(state)),
+            Some(Some((
+                IndicatorMode::Recording,
+                Some(Elapsed::Since(1_700_000_000_000))
+            )))
+        );
+        let paused = Some((IndicatorMode::Paused, Elapsed::Frozen(65_000)));
+        assert_eq!(
+            parse_state(&format_state(paused)),
+            Some(Some((IndicatorMode::Paused, Some(Elapsed::Frozen(65_000)))))
+        );
+        assert_eq!(parse_state(&format_state(None)), Some(None));
+        // Files written before the timer existed
+        assert_eq!(
+            parse_state("processing"),
+            Some(Some((IndicatorMode::Processing, None)))
+        );
+        assert_eq!(parse_state("garbage"), None);
+
+        // Pausing stops the timer and resuming carries on from there
+        let mut runtime = IndicatorRuntime::default();
+        assert!(matches!(
+            runtime.track(IndicatorMode::Recording),
+            Elapsed::Since(_)
+        ));
+        runtime.running_since = Some(SystemTime::now() - Duration::from_secs(5));
+        let Elapsed::Frozen(frozen) = runtime.track(IndicatorMode::Paused) else {
+            panic!("timer still running while paused");
+        };
+        assert!((5000..6000).contains(&frozen));
+        assert!(runtime.track(IndicatorMode::Recording).millis() >= 5000);
+
+        assert_eq!(format_elapsed(7_400), "0:07");
+        assert_eq!(format_elapsed(3_723_000), "1:02:03");
+
+        let screen = (1920.0, 1080.0);
+        let window = (154.0, 36.0);
+        assert_eq!(
+            OverlayAnchor::BottomCenter.position(screen, window, (0.0, 20.0)),
+            (883.0, 1024.0)
+        );
+        assert_eq!(
+            OverlayAnchor::parse("top_right")
+                .unwrap()
+                .position(screen, window, (10.0, 10.0)),
+            (1756.0, 10.0)
+        );
+        assert!(OverlayAnchor::parse("middle").is_err());
+
+        assert_eq!(parse_color("#1e90FF"), Some([0x1e, 0x90, 0xff]));
+        assert_eq!(parse_color("blue"), None);
+        let config = OverlayConfig {
+            anchor: "sideways".to_string(),
+            background: "#12".to_string(),
+            ..OverlayConfig::default()
+        };
+        let style = OverlayStyle::from_config(&config);
+        assert_eq!(style.anchor, OverlayAnchor::BottomCenter);
+        assert_eq!(style.background, [0, 0, 0]);
+    }
+}
@@ -3,10 +3,17 @@
 //! IPC server and protocol for controlling the daemon.
 
 pub mod client;
+pub mod error;
 pub mod protocol;
+pub mod query;
 pub mod server;
 
 // Re-export commonly used types
-pub use client::IpcClient;
-pub use protocol::{Command, DaemonStatus, Message, Payload, Response};
+pub use client::{EventSubscription, IpcClient, IpcSession};
+pub use error::{IpcError, IpcResult};
+pub use protocol::{
+    AuditEntry, Capabilities, ClientInfo, Command, DaemonStatus, DegradationLevel, Event,
+    Handshake, LastTranscript, Message, PROTOCOL_VERSION, Payload, Response, SubsystemHealth,
+    SubsystemStatus,
+};
 pub use server::IpcServer;
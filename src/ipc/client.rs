@@ -1,20 +1,127 @@
 //! IPC Client Library
 //!
 //! Client for communicating with the daemon via IPC.
+//!
+//! Failures are reported as typed `IpcError`s. Transient ones (rate
+//! limiting, timeouts, dropped connections) are retried a bounded number of
+//! times; commands with side effects are only resent when the daemon cannot
+//! have acted on them.
 
-use super::protocol::{Command, Message, Payload, Response};
-use anyhow::{Context, Result};
+use super::error::{IpcError, IpcResult};
+use super::protocol::{
+    Command, Event, Handshake, LastTranscript, Message, PROTOCOL_VERSION, Payload, Response,
+};
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 #[cfg(unix)]
 use tokio::net::UnixStream;
 #[cfg(windows)]
-use tokio::net::windows::named_pipe::ClientOptions;
+use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient};
+
+/// Client name used when none is set explicitly
+pub const DEFAULT_CLIENT_NAME: &str = "onevox-cli";
+
+/// Default time allowed to connect to the daemon
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default time allowed for the daemon to answer a request
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of attempts per request (first try included)
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubled for each further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+#[cfg(unix)]
+type IpcStream = UnixStream;
+#[cfg(windows)]
+type IpcStream = NamedPipeClient;
+
+/// Stream of events from a `subscribe` call
+pub struct EventSubscription {
+    stream: Box<dyn AsyncRead + Unpin + Send>,
+}
+
+impl EventSubscription {
+    /// Wait for the next event (`None` once the daemon closes the stream)
+    pub async fn next(&mut self) -> IpcResult<Option<Event>> {
+        let mut len_bytes = [0u8; 4];
+        match self.stream.read_exact(&mut len_bytes).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut buf = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+        self.stream.read_exact(&mut buf).await?;
+
+        match decode_message(&buf)?.payload {
+            Payload::Event(event) => Ok(Some(event)),
+            _ => Err(IpcError::ProtocolMismatch(
+                "unexpected message in event stream".to_string(),
+            )),
+        }
+    }
+}
+
+/// Connection kept open across requests
+///
+/// Saves a connect per request for clients that send many commands (the
+/// TUI, scripts polling status). If the daemon closes the session (idle
+/// timeout, restart), the next request reconnects under the client's
+/// retry rules.
+pub struct IpcSession<'a> {
+    client: &'a mut IpcClient,
+    stream: Option<IpcStream>,
+}
+
+impl IpcSession<'_> {
+    /// Send a command on the session and wait for its response
+    pub async fn send(&mut self, command: Command) -> IpcResult<Response> {
+        let mut attempt = 1;
+        loop {
+            let result = match self.stream.take() {
+                Some(stream) => Ok(stream),
+                None => self.client.connect().await,
+            };
+            let result = match result {
+                Ok(mut stream) => {
+                    let result = self.client.exchange(&mut stream, command.clone()).await;
+                    // The connection is still in sync after these
+                    if matches!(result, Ok(_) | Err(IpcError::RateLimited)) {
+                        self.stream = Some(stream);
+                    }
+                    result
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Err(e) if self.client.should_retry(&e, &command, attempt) => {
+                    self.client.backoff(&e, &command, attempt).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Whether the session currently holds an open connection
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+}
 
 /// IPC client
 pub struct IpcClient {
     socket_path: PathBuf,
     next_id: u64,
+    client_name: String,
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    max_attempts: u32,
 }
 
 impl Default for IpcClient {
@@ -29,9 +136,37 @@ impl IpcClient {
         Self {
             socket_path,
             next_id: 1,
+            client_name: DEFAULT_CLIENT_NAME.to_string(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
         }
     }
 
+    /// Set the client name reported to the daemon (shown in `status --verbose`)
+    pub fn with_client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = name.into();
+        self
+    }
+
+    /// Set the connect and response timeouts
+    pub fn with_timeouts(mut self, connect: Duration, read: Duration) -> Self {
+        self.connect_timeout = connect;
+        self.read_timeout = read;
+        self
+    }
+
+    /// Set the number of attempts per request (1 = no retries)
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Client name reported to the daemon
+    pub fn client_name(&self) -> &str {
+        &self.client_name
+    }
+
     /// Get default socket path
     pub fn default_socket_path() -> PathBuf {
         crate::platform::paths::ipc_socket_path().unwrap_or_else(|_| {
@@ -42,43 +177,137 @@ impl IpcClient {
         })
     }
 
-    /// Send a command and wait for response
-    pub async fn send_command(&mut self, command: Command) -> Result<Response> {
+    /// Send a command and wait for response, retrying transient failures
+    ///
+    /// A rate-limited reply is returned as `IpcError::RateLimited`; any other
+    /// `Response::Error` is passed through for the caller to interpret.
+    pub async fn send_command(&mut self, command: Command) -> IpcResult<Response> {
+        let mut attempt = 1;
+        loop {
+            let result = match self.connect().await {
+                Ok(mut stream) => self.exchange(&mut stream, command.clone()).await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Err(e) if self.should_retry(&e, &command, attempt) => {
+                    self.backoff(&e, &command, attempt).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Open a session that sends every request over one connection
+    pub async fn session(&mut self) -> IpcResult<IpcSession<'_>> {
+        let stream = self.connect().await?;
+        Ok(IpcSession {
+            client: self,
+            stream: Some(stream),
+        })
+    }
+
+    /// Subscribe to daemon events (empty filter = all events)
+    pub async fn subscribe(&mut self, events: Vec<String>) -> IpcResult<EventSubscription> {
+        let command = Command::Subscribe { events };
+        let mut attempt = 1;
+        loop {
+            let result = match self.connect().await {
+                Ok(mut stream) => match self.exchange(&mut stream, command.clone()).await {
+                    Ok(Response::Success) => Ok(EventSubscription {
+                        stream: Box::new(stream),
+                    }),
+                    Ok(response) => Err(unexpected(response)),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Err(e) if self.should_retry(&e, &command, attempt) => {
+                    self.backoff(&e, &command, attempt).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Whether a failed attempt should be retried
+    fn should_retry(&self, error: &IpcError, command: &Command, attempt: u32) -> bool {
+        attempt < self.max_attempts
+            && error.is_transient()
+            && (command.is_read_only() || error.request_not_delivered())
+    }
+
+    /// Wait before the next attempt (exponential backoff)
+    async fn backoff(&self, error: &IpcError, command: &Command, attempt: u32) {
+        let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+        tracing::debug!(
+            "IPC {} attempt {}/{} failed ({}), retrying in {:?}",
+            command.name(),
+            attempt,
+            self.max_attempts,
+            error,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    /// Connect to the daemon and agree on the protocol version, within the
+    /// connect timeout
+    async fn connect(&self) -> IpcResult<IpcStream> {
+        let mut stream = self.open().await?;
+        tokio::time::timeout(self.connect_timeout, handshake(&mut stream))
+            .await
+            .map_err(|_| IpcError::connect_timeout(self.connect_timeout))??;
+        Ok(stream)
+    }
+
+    /// Open the socket or pipe
+    async fn open(&self) -> IpcResult<IpcStream> {
         #[cfg(unix)]
         {
-            let stream = UnixStream::connect(&self.socket_path)
+            tokio::time::timeout(self.connect_timeout, UnixStream::connect(&self.socket_path))
                 .await
-                .context("Failed to connect to daemon. Is it running?")?;
-            return self.send_with_stream(stream, command).await;
+                .map_err(|_| IpcError::connect_timeout(self.connect_timeout))?
+                .map_err(|e| IpcError::from_connect(e, self.socket_path.clone()))
         }
 
         #[cfg(windows)]
         {
-            let pipe_name = self
-                .socket_path
-                .to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid Windows pipe path"))?;
-            let stream = ClientOptions::new()
+            let pipe_name = self.socket_path.to_str().ok_or_else(|| {
+                IpcError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Invalid Windows pipe path",
+                ))
+            })?;
+            ClientOptions::new()
                 .open(pipe_name)
-                .context("Failed to connect to daemon. Is it running?")?;
-            return self.send_with_stream(stream, command).await;
+                .map_err(|e| IpcError::from_connect(e, self.socket_path.clone()))
         }
-
-        #[allow(unreachable_code)]
-        Err(anyhow::anyhow!("Unsupported platform for IPC"))
     }
 
-    async fn send_with_stream<S>(&mut self, mut stream: S, command: Command) -> Result<Response>
+    /// Send one request on an open stream and read its response
+    async fn exchange<S>(&mut self, stream: &mut S, command: Command) -> IpcResult<Response>
     where
         S: AsyncRead + AsyncWrite + Unpin,
     {
         // Create message
         let id = self.next_id;
         self.next_id += 1;
-        let message = Message::request(id, command);
+        let message = Message::request(id, command).with_client_name(self.client_name.clone());
+        tracing::debug!(
+            "IPC request {} ({}) from {}",
+            message.request_id,
+            id,
+            self.client_name
+        );
 
         // Serialize message
-        let message_bytes = bincode::serialize(&message)?;
+        let message_bytes = bincode::serialize(&message)
+            .map_err(|e| IpcError::ProtocolMismatch(format!("failed to encode request: {}", e)))?;
         let len = message_bytes.len() as u32;
 
         // Send length + message
@@ -86,27 +315,35 @@ impl IpcClient {
         stream.write_all(&message_bytes).await?;
         stream.flush().await?;
 
-        // Read response length
-        let mut len_bytes = [0u8; 4];
-        stream.read_exact(&mut len_bytes).await?;
-        let response_len = u32::from_le_bytes(len_bytes) as usize;
-
-        // Read response data
-        let mut response_buf = vec![0u8; response_len];
-        stream.read_exact(&mut response_buf).await?;
+        // Read response length + data
+        let response_buf = tokio::time::timeout(self.read_timeout, async {
+            let mut len_bytes = [0u8; 4];
+            stream.read_exact(&mut len_bytes).await?;
+            let response_len = u32::from_le_bytes(len_bytes) as usize;
 
-        // Deserialize response
-        let response_msg: Message = bincode::deserialize(&response_buf)?;
+            let mut response_buf = vec![0u8; response_len];
+            stream.read_exact(&mut response_buf).await?;
+            Ok::<_, std::io::Error>(response_buf)
+        })
+        .await
+        .map_err(|_| IpcError::response_timeout(self.read_timeout))??;
 
         // Extract response payload
-        match response_msg.payload {
+        match decode_message(&response_buf)?.payload {
+            Payload::Response(Response::Error(e)) if e.starts_with("Rate limited") => {
+                Err(IpcError::RateLimited)
+            }
             Payload::Response(response) => Ok(response),
-            _ => Err(anyhow::anyhow!("Invalid response type")),
+            // Sent instead of a response when the daemon stops
+            Payload::Event(Event::ShuttingDown) => Err(IpcError::ShuttingDown),
+            _ => Err(IpcError::ProtocolMismatch(
+                "expected a response message".to_string(),
+            )),
         }
     }
 
     /// Check if daemon is running
-    pub async fn ping(&mut self) -> Result<bool> {
+    pub async fn ping(&mut self) -> IpcResult<bool> {
         match self.send_command(Command::Ping).await {
             Ok(Response::Pong) => Ok(true),
             Ok(_) => Ok(false),
@@ -115,91 +352,333 @@ impl IpcClient {
     }
 
     /// Get daemon status
-    pub async fn get_status(&mut self) -> Result<super::protocol::DaemonStatus> {
+    pub async fn get_status(&mut self) -> IpcResult<super::protocol::DaemonStatus> {
         match self.send_command(Command::GetStatus).await? {
-            Response::Status(status) => Ok(status),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Status(status) => Ok(*status),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Shutdown the daemon
-    pub async fn shutdown(&mut self) -> Result<()> {
+    pub async fn shutdown(&mut self) -> IpcResult<()> {
         match self.send_command(Command::Shutdown).await? {
             Response::Success => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Get daemon configuration
-    pub async fn get_config(&mut self) -> Result<String> {
+    pub async fn get_config(&mut self) -> IpcResult<String> {
         match self.send_command(Command::GetConfig).await? {
             Response::Config(config) => Ok(config),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Get transcription history
-    pub async fn get_history(&mut self) -> Result<Vec<crate::history::HistoryEntry>> {
+    pub async fn get_history(&mut self) -> IpcResult<Vec<crate::history::HistoryEntry>> {
         match self.send_command(Command::GetHistory).await? {
             Response::History(entries) => Ok(entries),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Aggregate history made at or after the Unix timestamp `since`
+    pub async fn get_stats(
+        &mut self,
+        since: Option<u64>,
+    ) -> IpcResult<crate::history::HistoryStats> {
+        match self.send_command(Command::GetStats { since }).await? {
+            Response::Stats(stats) => Ok(stats),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Search transcription history, best matches first
+    pub async fn search_history(
+        &mut self,
+        query: crate::history::HistoryQuery,
+    ) -> IpcResult<Vec<crate::history::HistoryMatch>> {
+        match self.send_command(Command::SearchHistory { query }).await? {
+            Response::HistoryMatches(matches) => Ok(matches),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Delete a specific history entry
-    pub async fn delete_history_entry(&mut self, id: u64) -> Result<()> {
+    pub async fn delete_history_entry(&mut self, id: u64) -> IpcResult<()> {
         match self
             .send_command(Command::DeleteHistoryEntry { id })
             .await?
         {
             Response::Ok(_) => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Clear all history
-    pub async fn clear_history(&mut self) -> Result<()> {
+    pub async fn clear_history(&mut self) -> IpcResult<()> {
         match self.send_command(Command::ClearHistory).await? {
             Response::Ok(_) => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Restore history from a backup archive
+    pub async fn restore_history(&mut self, from: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::RestoreHistory {
+                from: from.to_string(),
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Add entries transcribed outside the daemon to its history
+    pub async fn add_history(
+        &mut self,
+        entries: Vec<crate::history::HistoryEntry>,
+    ) -> IpcResult<String> {
+        match self.send_command(Command::AddHistory { entries }).await? {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Undo the last `count` injections
+    pub async fn undo(&mut self, count: usize) -> IpcResult<String> {
+        match self.send_command(Command::Undo { count }).await? {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Type (or with `discard`, drop) the transcript held back from a
+    /// password field
+    pub async fn confirm_injection(&mut self, discard: bool) -> IpcResult<String> {
+        match self
+            .send_command(Command::ConfirmInjection { discard })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Turn off all capture for `duration_secs`, or until resumed
+    pub async fn privacy_pause(&mut self, duration_secs: Option<u64>) -> IpcResult<String> {
+        match self
+            .send_command(Command::PrivacyPause { duration_secs })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// End a privacy pause
+    pub async fn privacy_resume(&mut self) -> IpcResult<String> {
+        match self.send_command(Command::PrivacyResume).await? {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Names of the daemon's audio input devices
+    pub async fn list_devices(&mut self) -> IpcResult<Vec<String>> {
+        match self.send_command(Command::ListDevices).await? {
+            Response::List(devices) => Ok(devices),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Capture from another input device, saved to the config
+    pub async fn set_device(&mut self, name: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::SetDevice {
+                name: name.to_string(),
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Evaluate a query expression, returning the result as JSON
+    pub async fn query(&mut self, expr: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::Query {
+                expr: expr.to_string(),
+            })
+            .await?
+        {
+            Response::Json(json) => Ok(json),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Transcribe a WAV file with the daemon's loaded model
+    pub async fn transcribe_file(&mut self, path: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::TranscribeFile {
+                path: path.to_string(),
+            })
+            .await?
+        {
+            Response::Ok(text) => Ok(text),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Ask the daemon to switch to `binary` without dropping its socket
+    pub async fn upgrade(&mut self, binary: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::Upgrade {
+                binary: binary.to_string(),
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Most recent finalized transcript (`None` before the first one)
+    pub async fn get_last_transcript(&mut self) -> IpcResult<Option<LastTranscript>> {
+        match self.send_command(Command::GetLastTranscript).await? {
+            Response::LastTranscript(last) => Ok(last),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Start downloading a model in the daemon
+    ///
+    /// `stall_timeout_secs` overrides the stall timeout (0 = none).
+    pub async fn download_model(
+        &mut self,
+        model_id: &str,
+        activate: bool,
+        stall_timeout_secs: Option<u64>,
+    ) -> IpcResult<String> {
+        match self
+            .send_command(Command::DownloadModel {
+                model_id: model_id.to_string(),
+                activate,
+                stall_timeout_secs,
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Pause (or with `discard`, cancel) a daemon download
+    pub async fn stop_download(&mut self, model_id: &str, discard: bool) -> IpcResult<String> {
+        match self
+            .send_command(Command::StopDownload {
+                model_id: model_id.to_string(),
+                discard,
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Start dictation
-    pub async fn start_dictation(&mut self) -> Result<()> {
+    pub async fn start_dictation(&mut self) -> IpcResult<()> {
         match self.send_command(Command::StartDictation).await? {
             Response::Success | Response::Ok(_) => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Stop dictation
-    pub async fn stop_dictation(&mut self) -> Result<()> {
+    pub async fn stop_dictation(&mut self) -> IpcResult<()> {
         match self.send_command(Command::StopDictation).await? {
             Response::Success | Response::Ok(_) => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Pause the session in progress
+    pub async fn pause_dictation(&mut self) -> IpcResult<()> {
+        match self.send_command(Command::PauseDictation).await? {
+            Response::Success | Response::Ok(_) => Ok(()),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Resume a paused session
+    pub async fn resume_dictation(&mut self) -> IpcResult<()> {
+        match self.send_command(Command::ResumeDictation).await? {
+            Response::Success | Response::Ok(_) => Ok(()),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Ids of the downloaded models
+    pub async fn list_models(&mut self) -> IpcResult<Vec<String>> {
+        match self.send_command(Command::ListModels).await? {
+            Response::List(models) => Ok(models),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
+        }
+    }
+
+    /// Switch the daemon to another model
+    pub async fn load_model(&mut self, model: &str) -> IpcResult<String> {
+        match self
+            .send_command(Command::LoadModel {
+                path: model.to_string(),
+            })
+            .await?
+        {
+            Response::Ok(message) => Ok(message),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Reload daemon configuration
-    pub async fn reload_config(&mut self) -> Result<()> {
+    pub async fn reload_config(&mut self) -> IpcResult<()> {
         match self.send_command(Command::ReloadConfig).await? {
             Response::Success | Response::Ok(_) => Ok(()),
-            Response::Error(e) => Err(anyhow::anyhow!("Error: {}", e)),
-            _ => Err(anyhow::anyhow!("Unexpected response")),
+            Response::Error(e) => Err(IpcError::Daemon(e)),
+            response => Err(unexpected(response)),
         }
     }
 
     /// Restart daemon to apply configuration changes
-    pub async fn restart_daemon(&mut self) -> Result<()> {
+    pub async fn restart_daemon(&mut self) -> IpcResult<()> {
         // First, shutdown the daemon
         self.shutdown().await?;
 
@@ -210,3 +689,63 @@ impl IpcClient {
         Ok(())
     }
 }
+
+/// Send this client's protocol version and check the daemon's answer
+async fn handshake<S>(stream: &mut S) -> IpcResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let hello = bincode::serialize(&Handshake::current())
+        .map_err(|e| IpcError::ProtocolMismatch(format!("failed to encode handshake: {}", e)))?;
+    stream
+        .write_all(&(hello.len() as u32).to_le_bytes())
+        .await?;
+    stream.write_all(&hello).await?;
+    stream.flush().await?;
+
+    let mut len_bytes = [0u8; 4];
+    match stream.read_exact(&mut len_bytes).await {
+        Ok(_) => {}
+        // Daemons from before the handshake drop the connection when they
+        // fail to decode it
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            return Err(IpcError::ProtocolMismatch(format!(
+                "daemon closed the connection during the handshake; it predates protocol version {}. Restart it with this version",
+                PROTOCOL_VERSION
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    }
+    let mut buf = vec![0u8; u32::from_le_bytes(len_bytes) as usize];
+    stream.read_exact(&mut buf).await?;
+
+    match bincode::deserialize::<Handshake>(&buf) {
+        Ok(reply) if reply.is_valid() && reply.version == PROTOCOL_VERSION => Ok(()),
+        Ok(reply) if reply.is_valid() => Err(IpcError::ProtocolMismatch(format!(
+            "daemon speaks protocol version {}, this client {}. Restart the daemon with the same onevox version",
+            reply.version, PROTOCOL_VERSION
+        ))),
+        _ => Err(IpcError::ProtocolMismatch(
+            "daemon did not answer the handshake; are the CLI and daemon the same version?"
+                .to_string(),
+        )),
+    }
+}
+
+/// Decode a message, reporting failures as a protocol mismatch
+fn decode_message(buf: &[u8]) -> IpcResult<Message> {
+    bincode::deserialize(buf).map_err(|e| {
+        IpcError::ProtocolMismatch(format!(
+            "failed to decode daemon message ({}); are the CLI and daemon the same version?",
+            e
+        ))
+    })
+}
+
+/// Error for a response of the wrong kind
+fn unexpected(response: Response) -> IpcError {
+    match response {
+        Response::Error(e) => IpcError::Daemon(e),
+        response => IpcError::ProtocolMismatch(format!("unexpected response: {:?}", response)),
+    }
+}
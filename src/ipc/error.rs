@@ -0,0 +1,138 @@
+//! IPC Client Errors
+//!
+//! Typed failures returned by `IpcClient`, so callers can tell a stopped
+//! daemon from a transient hiccup worth retrying.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Result type for IPC client operations
+pub type IpcResult<T> = std::result::Result<T, IpcError>;
+
+/// Error returned by `IpcClient`
+#[derive(Debug, thiserror::Error)]
+pub enum IpcError {
+    /// Nothing is listening on the socket
+    #[error("Daemon is not running (no listener at {0:?})")]
+    DaemonNotRunning(PathBuf),
+
+    /// The daemon rejected the request because the client sent too fast
+    #[error("Rate limited by the daemon")]
+    RateLimited,
+
+    /// The daemon answered with something this client cannot decode,
+    /// usually because the CLI and daemon versions differ
+    #[error("Protocol mismatch: {0}")]
+    ProtocolMismatch(String),
+
+    /// An operation did not finish in time
+    #[error("Timed out after {after:?} while {operation}")]
+    Timeout {
+        /// What the client was doing ("connecting", "waiting for a response")
+        operation: &'static str,
+        /// Configured timeout
+        after: Duration,
+    },
+
+    /// The daemon closed the connection because it is shutting down
+    #[error("Daemon is shutting down")]
+    ShuttingDown,
+
+    /// The daemon handled the request and reported an error
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+
+    /// Socket I/O failed
+    #[error("IPC I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IpcError {
+    /// Timeout while connecting
+    pub(crate) fn connect_timeout(after: Duration) -> Self {
+        IpcError::Timeout {
+            operation: "connecting",
+            after,
+        }
+    }
+
+    /// Timeout while waiting for a response
+    pub(crate) fn response_timeout(after: Duration) -> Self {
+        IpcError::Timeout {
+            operation: "waiting for a response",
+            after,
+        }
+    }
+
+    /// Whether retrying the same request may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            IpcError::RateLimited | IpcError::Timeout { .. } => true,
+            IpcError::Io(e) => matches!(
+                e.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+                    | ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+            ),
+            IpcError::DaemonNotRunning(_)
+            | IpcError::ProtocolMismatch(_)
+            | IpcError::ShuttingDown
+            | IpcError::Daemon(_) => false,
+        }
+    }
+
+    /// Whether the daemon cannot have acted on the request
+    ///
+    /// Only these errors are retried for commands with side effects.
+    pub fn request_not_delivered(&self) -> bool {
+        match self {
+            IpcError::RateLimited | IpcError::DaemonNotRunning(_) => true,
+            IpcError::Timeout { operation, .. } => *operation == "connecting",
+            _ => false,
+        }
+    }
+
+    /// Whether the daemon is not running
+    pub fn is_daemon_not_running(&self) -> bool {
+        matches!(self, IpcError::DaemonNotRunning(_))
+    }
+
+    /// Classify a connection failure
+    pub(crate) fn from_connect(error: std::io::Error, socket_path: PathBuf) -> Self {
+        match error.kind() {
+            ErrorKind::NotFound | ErrorKind::ConnectionRefused => {
+                IpcError::DaemonNotRunning(socket_path)
+            }
+            _ => IpcError::Io(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_classification() {
+        let not_running = IpcError::from_connect(
+            std::io::Error::from(ErrorKind::NotFound),
+            PathBuf::from("/tmp/onevox.sock"),
+        );
+        assert!(not_running.is_daemon_not_running());
+        assert!(!not_running.is_transient());
+
+        assert!(IpcError::RateLimited.is_transient());
+        assert!(IpcError::RateLimited.request_not_delivered());
+
+        let read_timeout = IpcError::response_timeout(Duration::from_secs(1));
+        assert!(read_timeout.is_transient());
+        assert!(!read_timeout.request_not_delivered());
+        assert!(IpcError::connect_timeout(Duration::from_secs(1)).request_not_delivered());
+
+        assert!(!IpcError::ProtocolMismatch("bad".into()).is_transient());
+    }
+}
@@ -3,13 +3,55 @@
 //! Binary message protocol using bincode for efficient serialization.
 
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the wire format, exchanged in the [`Handshake`]
+///
+/// bincode encodes fields by position, without names or defaults, so bump
+/// this whenever `Message` or a payload type changes shape. Connections
+/// between a CLI and a daemon of different versions are then refused with
+/// a clear error instead of failing to decode.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// First frame of every connection: the client sends its version and the
+/// daemon answers with its own, closing the connection if they differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// Always [`Handshake::MAGIC`], which tells a handshake apart from a
+    /// request sent by a client that predates it
+    pub magic: u64,
+    /// Sender's [`PROTOCOL_VERSION`]
+    pub version: u32,
+}
+
+impl Handshake {
+    /// "onevox" padded to eight bytes
+    pub const MAGIC: u64 = u64::from_le_bytes(*b"onevox\0\0");
+
+    /// Handshake for this build
+    pub fn current() -> Self {
+        Self {
+            magic: Self::MAGIC,
+            version: PROTOCOL_VERSION,
+        }
+    }
+
+    /// Whether the frame is a handshake at all
+    pub fn is_valid(&self) -> bool {
+        self.magic == Self::MAGIC
+    }
+}
 
 /// IPC message envelope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     /// Unique request ID for correlation
     pub id: u64,
+    /// Trace ID shared by a request and its response (appears in daemon logs)
+    pub request_id: String,
+    /// Name of the client that sent the request (e.g. "onevox-cli")
+    pub client_name: Option<String>,
     /// Message payload
     pub payload: Payload,
 }
@@ -26,6 +68,8 @@ pub enum Payload {
 }
 
 /// Commands that can be sent to the daemon
+///
+/// Add new variants at the end; see [`Event`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Command {
     /// Check if daemon is running
@@ -69,6 +113,76 @@ pub enum Command {
 
     /// Clear all history
     ClearHistory,
+
+    /// Replace history with the contents of a backup archive
+    RestoreHistory { from: String },
+
+    /// Keep the connection open and stream events (empty filter = all)
+    Subscribe { events: Vec<String> },
+
+    /// Erase the last `count` injections from the focused app
+    Undo { count: usize },
+
+    /// Evaluate a path expression against daemon state (see `ipc::query`)
+    Query { expr: String },
+
+    /// Transcribe an audio file (WAV, MP3, FLAC, OGG) with the loaded model
+    TranscribeFile { path: String },
+
+    /// Drain and re-exec the daemon as `binary`, keeping the socket (Unix)
+    Upgrade { binary: String },
+
+    /// Get the most recent finalized transcript
+    GetLastTranscript,
+
+    /// Download a registry model in the background (progress arrives as
+    /// events), optionally switching to it once complete
+    ///
+    /// `stall_timeout_secs` overrides how long an attempt may go without
+    /// data before it is retried (0 = wait indefinitely).
+    DownloadModel {
+        model_id: String,
+        activate: bool,
+        stall_timeout_secs: Option<u64>,
+    },
+
+    /// Stop a background download: pause keeps partial files for resuming,
+    /// `discard` removes them
+    StopDownload { model_id: String, discard: bool },
+
+    /// Search transcription history, best matches first
+    SearchHistory { query: crate::history::HistoryQuery },
+
+    /// Type the transcript held back from a password field anyway, or
+    /// drop it with `discard`
+    ConfirmInjection { discard: bool },
+
+    /// Turn off all capture for `duration_secs`, or until resumed
+    PrivacyPause { duration_secs: Option<u64> },
+
+    /// End a privacy pause
+    PrivacyResume,
+
+    /// Capture from another input device ("default" for the system
+    /// default), saved to the config; a session in progress switches over
+    SetDevice { name: String },
+
+    /// Add entries transcribed outside the daemon (`onevox listen
+    /// --meeting`) to history
+    AddHistory {
+        entries: Vec<crate::history::HistoryEntry>,
+    },
+
+    /// Aggregate history made at or after the Unix timestamp `since` (all
+    /// of it when `None`)
+    GetStats { since: Option<u64> },
+
+    /// Pause the session in progress: the microphone stays open but its
+    /// audio is ignored until resumed
+    PauseDictation,
+
+    /// Resume a paused session
+    ResumeDictation,
 }
 
 /// Responses from the daemon
@@ -83,8 +197,8 @@ pub enum Response {
     /// Operation failed
     Error(String),
 
-    /// Daemon status
-    Status(DaemonStatus),
+    /// Daemon status (boxed, it is by far the largest response)
+    Status(Box<DaemonStatus>),
 
     /// Configuration data
     Config(String), // TOML-serialized config
@@ -97,6 +211,37 @@ pub enum Response {
 
     /// History entries
     History(Vec<crate::history::HistoryEntry>),
+
+    /// JSON-encoded query result
+    Json(String),
+
+    /// Most recent finalized transcript (`None` before the first one)
+    LastTranscript(Option<LastTranscript>),
+
+    /// History search results, best first
+    HistoryMatches(Vec<crate::history::HistoryMatch>),
+
+    /// Aggregates over history
+    Stats(crate::history::HistoryStats),
+}
+
+/// The last finalized transcript, kept in daemon memory so front-ends can
+/// re-fetch it (e.g. after injection into a flaky app failed)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastTranscript {
+    /// Transcribed text after post-processing
+    pub text: String,
+    /// Model that produced it
+    pub model: String,
+    /// Unix timestamp (seconds) when it was finalized
+    pub timestamp: u64,
+    /// Transcription time in milliseconds
+    pub processing_ms: u64,
+    /// Length of the segment audio in milliseconds
+    pub audio_ms: u64,
+    /// WAV file with the segment audio, when `audio.save_recordings` is on
+    /// (overwritten by the next transcript)
+    pub audio_path: Option<String>,
 }
 
 /// Daemon status information
@@ -128,6 +273,246 @@ pub struct DaemonStatus {
 
     /// CPU usage percentage (0-100)
     pub cpu_usage_percent: f32,
+
+    /// Clients that have talked to the daemon recently
+    pub clients: Vec<ClientInfo>,
+
+    /// Most recent requests (newest last)
+    pub recent_requests: Vec<AuditEntry>,
+
+    /// Features available in this daemon build
+    pub capabilities: Capabilities,
+
+    /// Events published since startup, by event name
+    pub event_counts: BTreeMap<String, u64>,
+
+    /// Active event subscribers (IPC and internal consumers)
+    pub event_subscribers: usize,
+
+    /// Startup health of each subsystem and its `[startup]` policy
+    pub subsystems: Vec<SubsystemStatus>,
+
+    /// Dictation latency and throughput since startup
+    pub pipeline: PipelineStats,
+
+    /// Privacy pause (`onevox privacy pause`)
+    pub privacy: PrivacyStatus,
+}
+
+/// Whether capture is paused for privacy
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyStatus {
+    /// All capture is off
+    pub paused: bool,
+
+    /// Unix timestamp (seconds) when the pause ends (`None` = until resumed)
+    pub resume_at: Option<u64>,
+}
+
+/// Dictation pipeline performance since startup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStats {
+    /// Mean time from the end of speech to its text being injected, over
+    /// recent transcripts
+    pub latency_avg_ms: Option<u64>,
+
+    /// 95th percentile of the same
+    pub latency_p95_ms: Option<u64>,
+
+    /// Transcripts the latency figures cover
+    pub latency_samples: usize,
+
+    /// Speech segments closed by the VAD
+    pub vad_segments: u64,
+
+    /// Audio chunks dropped because transcription fell behind
+    pub dropped_chunks: u64,
+
+    /// Transcription speed of each model used
+    pub models: Vec<ModelStats>,
+
+    /// Memory loading the model added to the daemon
+    pub model_memory_bytes: Option<u64>,
+}
+
+/// Transcription totals for one model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelStats {
+    /// Model ID
+    pub model: String,
+
+    /// Transcriptions run
+    pub transcriptions: u64,
+
+    /// Audio transcribed in milliseconds
+    pub audio_ms: u64,
+
+    /// Time spent transcribing in milliseconds
+    pub processing_ms: u64,
+}
+
+impl ModelStats {
+    /// Real-time factor: processing time per second of audio (below 1.0
+    /// keeps up with speech)
+    pub fn rtf(&self) -> f64 {
+        if self.audio_ms == 0 {
+            return 0.0;
+        }
+        self.processing_ms as f64 / self.audio_ms as f64
+    }
+}
+
+/// Features compiled into the daemon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Microphone capture (dictation)
+    pub audio_capture: bool,
+
+    /// Global hotkeys
+    pub global_hotkeys: bool,
+
+    /// Text injection into the focused application
+    pub text_injection: bool,
+
+    /// Recording overlay indicator
+    pub overlay_indicator: bool,
+
+    /// Compiled model backends
+    pub model_backends: Vec<String>,
+}
+
+impl Capabilities {
+    /// Capabilities of the running binary, based on enabled cargo features
+    pub fn current() -> Self {
+        let mut model_backends = Vec::new();
+        if cfg!(feature = "whisper-cpp") {
+            model_backends.push("whisper-cpp".to_string());
+        }
+        if cfg!(feature = "onnx") {
+            model_backends.push("onnx".to_string());
+        }
+        if cfg!(feature = "candle") {
+            model_backends.push("candle".to_string());
+        }
+
+        Self {
+            audio_capture: cfg!(feature = "audio-capture"),
+            global_hotkeys: cfg!(feature = "hotkeys"),
+            text_injection: cfg!(feature = "injection"),
+            overlay_indicator: cfg!(feature = "overlay-indicator"),
+            model_backends,
+        }
+    }
+
+    /// Whether live dictation is possible (otherwise this is a headless build)
+    pub fn can_dictate(&self) -> bool {
+        self.audio_capture
+    }
+}
+
+/// Startup state of a daemon subsystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    /// Subsystem name ("model", "hotkeys")
+    pub name: String,
+
+    /// Startup policy: "fail-fast" or "degrade"
+    pub policy: String,
+
+    /// Current health
+    pub health: SubsystemHealth,
+
+    /// Failed start attempts so far
+    pub attempts: u32,
+
+    /// Most recent start error, while not ready
+    pub error: Option<String>,
+}
+
+/// Health of a daemon subsystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubsystemHealth {
+    /// Not started yet
+    Starting,
+    /// Running
+    Ready,
+    /// Unavailable; the daemon keeps running and retries
+    Degraded,
+    /// Unavailable and no longer retried
+    Failed,
+    /// Not part of this build
+    Disabled,
+}
+
+impl std::fmt::Display for SubsystemHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubsystemHealth::Starting => write!(f, "Starting"),
+            SubsystemHealth::Ready => write!(f, "Ready"),
+            SubsystemHealth::Degraded => write!(f, "Degraded (retrying)"),
+            SubsystemHealth::Failed => write!(f, "Failed"),
+            SubsystemHealth::Disabled => write!(f, "Disabled"),
+        }
+    }
+}
+
+/// How much of the daemon is working
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DegradationLevel {
+    /// Every subsystem is up
+    Full,
+    /// Dictation works, but e.g. only over IPC because hotkeys are down
+    Partial,
+    /// No model: only IPC commands like status and history work
+    IpcOnly,
+}
+
+impl std::fmt::Display for DegradationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DegradationLevel::Full => write!(f, "Full"),
+            DegradationLevel::Partial => write!(f, "Partial"),
+            DegradationLevel::IpcOnly => write!(f, "IPC only"),
+        }
+    }
+}
+
+/// IPC client seen by the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientInfo {
+    /// Client name reported in the message envelope
+    pub name: String,
+
+    /// Peer UID (Unix) or process ID (Windows)
+    pub peer_id: u32,
+
+    /// Number of requests received from this client
+    pub request_count: u64,
+
+    /// Last request time (Unix timestamp)
+    pub last_seen: u64,
+
+    /// Last command issued
+    pub last_command: String,
+}
+
+/// Audit record of a single IPC request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Request time (Unix timestamp)
+    pub timestamp: u64,
+
+    /// Trace ID of the request
+    pub request_id: String,
+
+    /// Client name
+    pub client_name: String,
+
+    /// Command name
+    pub command: String,
+
+    /// Whether the daemon answered with an error
+    pub is_error: bool,
 }
 
 /// Daemon operational state
@@ -150,6 +535,9 @@ pub enum DaemonState {
 }
 
 /// Events emitted by the daemon
+///
+/// Add new variants at the end: bincode encodes the variant index, so
+/// reordering breaks clients built against an older daemon.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Event {
     /// Daemon started successfully
@@ -164,8 +552,14 @@ pub enum Event {
     /// Model unloaded
     ModelUnloaded,
 
-    /// Transcription completed
-    TranscriptionComplete { text: String, duration_ms: u64 },
+    /// Transcription completed, with the model's confidence (0-1) when it
+    /// reports one
+    TranscriptionComplete {
+        text: String,
+        duration_ms: u64,
+        /// Model confidence (0-1), when the backend reports one
+        confidence: Option<f32>,
+    },
 
     /// Error occurred
     Error { message: String },
@@ -176,13 +570,309 @@ pub enum Event {
         message: String,
         timestamp: SystemTime,
     },
+
+    /// Model download progress
+    DownloadProgress {
+        model: String,
+        file: String,
+        downloaded_bytes: u64,
+        total_bytes: u64,
+    },
+
+    /// Model download finished
+    DownloadComplete { model: String },
+
+    /// Model is being loaded
+    ModelLoading { name: String },
+
+    /// Dictation session started
+    DictationStarted,
+
+    /// Dictation session stopped
+    DictationStopped,
+
+    /// Dictation session paused
+    DictationPaused,
+
+    /// Paused dictation session resumed
+    DictationResumed,
+
+    /// VAD detected a complete speech segment
+    SpeechDetected { chunks: usize },
+
+    /// Transcription failed
+    TranscriptionFailed { message: String },
+
+    /// Text injected into the focused application
+    InjectionComplete { text: String },
+
+    /// Text injection failed
+    InjectionFailed { message: String },
+
+    /// Target app lost focus; the text is injected once it regains focus
+    InjectionDeferred { app: Option<String>, reason: String },
+
+    /// A permission the daemon needs is denied, with how to grant it
+    PermissionDenied { permission: String, hint: String },
+
+    /// VAD detected the start of speech
+    SpeechStarted,
+
+    /// Interim transcript of the speech segment in progress
+    PartialTranscription { text: String },
+
+    /// Model download paused (or cancelled, when `discarded`)
+    DownloadStopped { model: String, discarded: bool },
+
+    /// Model download failed
+    DownloadFailed { model: String, message: String },
+
+    /// Input level of the latest audio chunk while dictating, whether the
+    /// VAD hears speech (`None` with VAD off), and the gain applied by the
+    /// AGC (`None` with AGC off)
+    ///
+    /// Sent several times a second, so only to subscribers that ask for it
+    /// by name.
+    AudioLevel {
+        rms: f32,
+        peak: f32,
+        speech: Option<bool>,
+        gain_db: Option<f32>,
+    },
+
+    /// Capture moved to another input device (the previous one was
+    /// unplugged, or came back); `to` is `None` while no input device is
+    /// available
+    AudioDeviceChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl Event {
+    /// Event name used for subscription filters and metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Started => "Started",
+            Event::ShuttingDown => "ShuttingDown",
+            Event::DownloadProgress { .. } => "DownloadProgress",
+            Event::DownloadComplete { .. } => "DownloadComplete",
+            Event::ModelLoading { .. } => "ModelLoading",
+            Event::ModelLoaded { .. } => "ModelLoaded",
+            Event::ModelUnloaded => "ModelUnloaded",
+            Event::DictationStarted => "DictationStarted",
+            Event::DictationStopped => "DictationStopped",
+            Event::DictationPaused => "DictationPaused",
+            Event::DictationResumed => "DictationResumed",
+            Event::SpeechDetected { .. } => "SpeechDetected",
+            Event::TranscriptionComplete { .. } => "TranscriptionComplete",
+            Event::TranscriptionFailed { .. } => "TranscriptionFailed",
+            Event::InjectionComplete { .. } => "InjectionComplete",
+            Event::InjectionFailed { .. } => "InjectionFailed",
+            Event::InjectionDeferred { .. } => "InjectionDeferred",
+            Event::Error { .. } => "Error",
+            Event::PermissionDenied { .. } => "PermissionDenied",
+            Event::Log { .. } => "Log",
+            Event::SpeechStarted => "SpeechStarted",
+            Event::PartialTranscription { .. } => "PartialTranscription",
+            Event::DownloadStopped { .. } => "DownloadStopped",
+            Event::DownloadFailed { .. } => "DownloadFailed",
+            Event::AudioLevel { .. } => "AudioLevel",
+            Event::AudioDeviceChanged { .. } => "AudioDeviceChanged",
+        }
+    }
+
+    /// All event names, for validating subscription filters
+    pub const NAMES: &'static [&'static str] = &[
+        "Started",
+        "ShuttingDown",
+        "ModelLoaded",
+        "ModelUnloaded",
+        "TranscriptionComplete",
+        "Error",
+        "Log",
+        "DownloadProgress",
+        "DownloadComplete",
+        "ModelLoading",
+        "DictationStarted",
+        "DictationStopped",
+        "DictationPaused",
+        "DictationResumed",
+        "SpeechDetected",
+        "TranscriptionFailed",
+        "InjectionComplete",
+        "InjectionFailed",
+        "InjectionDeferred",
+        "PermissionDenied",
+        "SpeechStarted",
+        "PartialTranscription",
+        "DownloadStopped",
+        "DownloadFailed",
+        "AudioLevel",
+        "AudioDeviceChanged",
+    ];
+
+    /// Whether the event passes a subscription filter (empty = everything
+    /// but audio levels)
+    pub fn matches(&self, filter: &[String]) -> bool {
+        if filter.is_empty() {
+            return !matches!(self, Event::AudioLevel { .. });
+        }
+        filter.iter().any(|f| f.eq_ignore_ascii_case(self.name()))
+    }
+}
+
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::DownloadProgress {
+                model,
+                file,
+                downloaded_bytes,
+                total_bytes,
+            } => write!(
+                f,
+                "DownloadProgress {}/{}: {}/{} bytes",
+                model, file, downloaded_bytes, total_bytes
+            ),
+            Event::DownloadComplete { model }
+            | Event::ModelLoading { name: model }
+            | Event::ModelLoaded { name: model } => write!(f, "{} {}", self.name(), model),
+            Event::SpeechDetected { chunks } => write!(f, "SpeechDetected ({} chunks)", chunks),
+            Event::TranscriptionComplete {
+                text,
+                duration_ms,
+                confidence: Some(confidence),
+            } => write!(
+                f,
+                "TranscriptionComplete ({}ms, {:.0}%): {}",
+                duration_ms,
+                confidence * 100.0,
+                text
+            ),
+            Event::TranscriptionComplete {
+                text, duration_ms, ..
+            } => write!(f, "TranscriptionComplete ({}ms): {}", duration_ms, text),
+            Event::InjectionComplete { text } => {
+                write!(f, "InjectionComplete ({} chars)", text.chars().count())
+            }
+            Event::InjectionDeferred { app, reason } => write!(
+                f,
+                "InjectionDeferred ({}): {}",
+                app.as_deref().unwrap_or("unknown app"),
+                reason
+            ),
+            Event::TranscriptionFailed { message }
+            | Event::InjectionFailed { message }
+            | Event::Error { message } => write!(f, "{}: {}", self.name(), message),
+            Event::PermissionDenied { permission, hint } => {
+                write!(f, "PermissionDenied {}: {}", permission, hint)
+            }
+            Event::Log { level, message, .. } => write!(f, "Log [{}] {}", level, message),
+            Event::PartialTranscription { text } => write!(f, "PartialTranscription: {}", text),
+            Event::DownloadStopped { model, discarded } => write!(
+                f,
+                "DownloadStopped {} ({})",
+                model,
+                if *discarded { "cancelled" } else { "paused" }
+            ),
+            Event::DownloadFailed { model, message } => {
+                write!(f, "DownloadFailed {}: {}", model, message)
+            }
+            Event::AudioLevel {
+                rms,
+                peak,
+                speech,
+                gain_db,
+            } => {
+                write!(f, "AudioLevel rms {:.3} peak {:.3}", rms, peak)?;
+                if let Some(gain_db) = gain_db {
+                    write!(f, " gain {:+.1} dB", gain_db)?;
+                }
+                if *speech == Some(true) {
+                    write!(f, " (speech)")?;
+                }
+                Ok(())
+            }
+            Event::AudioDeviceChanged { from, to } => write!(
+                f,
+                "AudioDeviceChanged: {}",
+                crate::audio::DeviceChange {
+                    from: from.clone(),
+                    to: to.clone(),
+                }
+            ),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+impl Command {
+    /// Short command name for logs and audit entries
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Ping => "Ping",
+            Command::GetStatus => "GetStatus",
+            Command::Shutdown => "Shutdown",
+            Command::ReloadConfig => "ReloadConfig",
+            Command::GetConfig => "GetConfig",
+            Command::StartDictation => "StartDictation",
+            Command::StopDictation => "StopDictation",
+            Command::PauseDictation => "PauseDictation",
+            Command::ResumeDictation => "ResumeDictation",
+            Command::ListDevices => "ListDevices",
+            Command::ListModels => "ListModels",
+            Command::LoadModel { .. } => "LoadModel",
+            Command::UnloadModel => "UnloadModel",
+            Command::GetHistory => "GetHistory",
+            Command::DeleteHistoryEntry { .. } => "DeleteHistoryEntry",
+            Command::ClearHistory => "ClearHistory",
+            Command::RestoreHistory { .. } => "RestoreHistory",
+            Command::Subscribe { .. } => "Subscribe",
+            Command::Undo { .. } => "Undo",
+            Command::Query { .. } => "Query",
+            Command::TranscribeFile { .. } => "TranscribeFile",
+            Command::Upgrade { .. } => "Upgrade",
+            Command::GetLastTranscript => "GetLastTranscript",
+            Command::DownloadModel { .. } => "DownloadModel",
+            Command::StopDownload { .. } => "StopDownload",
+            Command::SearchHistory { .. } => "SearchHistory",
+            Command::ConfirmInjection { .. } => "ConfirmInjection",
+            Command::PrivacyPause { .. } => "PrivacyPause",
+            Command::PrivacyResume => "PrivacyResume",
+            Command::SetDevice { .. } => "SetDevice",
+            Command::AddHistory { .. } => "AddHistory",
+            Command::GetStats { .. } => "GetStats",
+        }
+    }
+
+    /// Whether the command has no side effects and can safely be resent
+    /// when a response was lost
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Command::Ping
+                | Command::GetStatus
+                | Command::GetConfig
+                | Command::ListDevices
+                | Command::ListModels
+                | Command::GetHistory
+                | Command::Query { .. }
+                | Command::Subscribe { .. }
+                | Command::GetLastTranscript
+                | Command::SearchHistory { .. }
+                | Command::GetStats { .. }
+        )
+    }
 }
 
 impl Message {
-    /// Create a new request message
+    /// Create a new request message with a fresh trace ID
     pub fn request(id: u64, command: Command) -> Self {
         Self {
             id,
+            request_id: generate_request_id(id),
+            client_name: None,
             payload: Payload::Request(command),
         }
     }
@@ -191,6 +881,18 @@ impl Message {
     pub fn response(id: u64, response: Response) -> Self {
         Self {
             id,
+            request_id: String::new(),
+            client_name: None,
+            payload: Payload::Response(response),
+        }
+    }
+
+    /// Create a response that carries the trace ID of the request it answers
+    pub fn response_to(request: &Message, response: Response) -> Self {
+        Self {
+            id: request.id,
+            request_id: request.request_id.clone(),
+            client_name: request.client_name.clone(),
             payload: Payload::Response(response),
         }
     }
@@ -199,9 +901,26 @@ impl Message {
     pub fn event(id: u64, event: Event) -> Self {
         Self {
             id,
+            request_id: String::new(),
+            client_name: None,
             payload: Payload::Event(event),
         }
     }
+
+    /// Attach the sending client's name
+    pub fn with_client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = Some(name.into());
+        self
+    }
+}
+
+/// Generate a short trace ID: `<pid>-<time>-<sequence>` in hex
+pub fn generate_request_id(sequence: u64) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() ^ (d.as_secs() as u32))
+        .unwrap_or(0);
+    format!("{:x}-{:08x}-{:x}", std::process::id(), nanos, sequence)
 }
 
 impl DaemonStatus {
@@ -217,6 +936,32 @@ impl DaemonStatus {
             is_dictating: false,
             memory_usage_bytes: 0,
             cpu_usage_percent: 0.0,
+            clients: Vec::new(),
+            recent_requests: Vec::new(),
+            capabilities: Capabilities::current(),
+            event_counts: BTreeMap::new(),
+            event_subscribers: 0,
+            subsystems: Vec::new(),
+            pipeline: PipelineStats::default(),
+            privacy: PrivacyStatus::default(),
+        }
+    }
+
+    /// How much of the daemon is working, from the subsystem health
+    pub fn degradation(&self) -> DegradationLevel {
+        let down = |subsystem: &&SubsystemStatus| {
+            !matches!(
+                subsystem.health,
+                SubsystemHealth::Ready | SubsystemHealth::Disabled
+            )
+        };
+        let mut down = self.subsystems.iter().filter(down).peekable();
+        if down.peek().is_none() {
+            DegradationLevel::Full
+        } else if down.any(|subsystem| subsystem.name == "model") {
+            DegradationLevel::IpcOnly
+        } else {
+            DegradationLevel::Partial
         }
     }
 }
@@ -232,3 +977,66 @@ impl std::fmt::Display for DaemonState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_indices_are_stable() {
+        // Reordering variants would silently change their meaning for any
+        // peer that shares the protocol version
+        let index = |event: &Event| {
+            let bytes = bincode::serialize(event).unwrap();
+            u32::from_le_bytes(bytes[..4].try_into().unwrap())
+        };
+        assert_eq!(index(&Event::Started), 0);
+        assert_eq!(index(&Event::ModelUnloaded), 3);
+        assert_eq!(
+            index(&Event::Error {
+                message: String::new()
+            }),
+            5
+        );
+        assert_eq!(
+            index(&Event::DownloadProgress {
+                model: String::new(),
+                file: String::new(),
+                downloaded_bytes: 0,
+                total_bytes: 0,
+            }),
+            7
+        );
+        assert_eq!(Event::NAMES[5], "Error");
+
+        let index = |command: &Command| {
+            let bytes = bincode::serialize(command).unwrap();
+            u32::from_le_bytes(bytes[..4].try_into().unwrap())
+        };
+        assert_eq!(index(&Command::Ping), 0);
+        assert_eq!(
+            index(&Command::LoadModel {
+                path: String::new()
+            }),
+            9
+        );
+        assert_eq!(index(&Command::ClearHistory), 13);
+        assert_eq!(
+            index(&Command::RestoreHistory {
+                from: String::new()
+            }),
+            14
+        );
+        assert_eq!(index(&Command::GetStats { since: None }), 29);
+        assert_eq!(index(&Command::PauseDictation), 30);
+        assert_eq!(index(&Command::ResumeDictation), 31);
+
+        // The handshake leads with the magic, so a pre-handshake request
+        // (which leads with its ID) is never mistaken for one
+        let bytes = bincode::serialize(&Handshake::current()).unwrap();
+        assert_eq!(&bytes[..8], b"onevox\0\0");
+        let request = bincode::serialize(&Message::request(1, Command::Ping)).unwrap();
+        let decoded: Handshake = bincode::deserialize(&request).unwrap();
+        assert!(!decoded.is_valid());
+    }
+}
@@ -0,0 +1,180 @@
+//! State Queries
+//!
+//! Path expressions evaluated against daemon state for `onevox query`, so
+//! shell scripts can read single values without parsing full status output:
+//!
+//! ```text
+//! status.model_name
+//! config.hotkey.trigger
+//! metrics.event_counts.TranscriptionComplete
+//! last_transcription.text
+//! status.recent_requests[-1].command
+//! ```
+//!
+//! A path is a root name followed by `.field` and `[index]` segments
+//! (negative indices count from the end). Missing fields evaluate to `null`.
+
+use serde_json::Value;
+
+/// Top-level objects a query can start from
+pub const ROOTS: &[&str] = &["status", "config", "metrics", "last_transcription"];
+
+/// One step of a query path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Object field
+    Field(String),
+    /// Array element (negative counts from the end)
+    Index(i64),
+}
+
+/// Parsed query expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// Root object, or `None` for the whole state
+    pub root: Option<String>,
+    /// Path below the root
+    pub path: Vec<Segment>,
+}
+
+impl Query {
+    /// Parse a path expression (`.` or empty selects everything)
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expr = expr.strip_prefix('.').unwrap_or(expr);
+
+        let mut segments = Vec::new();
+        let mut chars = expr.chars().peekable();
+        let mut field = String::new();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if field.is_empty() {
+                        return Err(format!("Empty field name in '{}'", expr));
+                    }
+                    segments.push(Segment::Field(std::mem::take(&mut field)));
+                }
+                '[' => {
+                    if !field.is_empty() {
+                        segments.push(Segment::Field(std::mem::take(&mut field)));
+                    }
+                    let index: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let index = index
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_| format!("Invalid index '[{}]' in '{}'", index, expr))?;
+                    segments.push(Segment::Index(index));
+                    // Allow `a[0].b` as well as `a[0][1]`
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        if chars.peek().is_none() {
+                            return Err(format!("Trailing '.' in '{}'", expr));
+                        }
+                    }
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '-' => field.push(c),
+                other => return Err(format!("Unexpected '{}' in '{}'", other, expr)),
+            }
+        }
+        if !field.is_empty() {
+            segments.push(Segment::Field(field));
+        } else if expr.ends_with('.') {
+            return Err(format!("Trailing '.' in '{}'", expr));
+        }
+
+        let mut segments = segments.into_iter();
+        let root = match segments.next() {
+            None => None,
+            Some(Segment::Field(root)) if ROOTS.contains(&root.as_str()) => Some(root),
+            Some(Segment::Field(root)) => {
+                return Err(format!(
+                    "Unknown root '{}'. Available: {}",
+                    root,
+                    ROOTS.join(", ")
+                ));
+            }
+            Some(Segment::Index(_)) => {
+                return Err(format!(
+                    "Query must start with one of: {}",
+                    ROOTS.join(", ")
+                ));
+            }
+        };
+
+        Ok(Self {
+            root,
+            path: segments.collect(),
+        })
+    }
+
+    /// Evaluate the path below the root (`null` when it does not exist)
+    pub fn select(&self, root: &Value) -> Value {
+        let mut current = root;
+        for segment in &self.path {
+            let next = match (segment, current) {
+                (Segment::Field(name), Value::Object(map)) => map.get(name),
+                (Segment::Index(index), Value::Array(items)) => {
+                    let position = if *index < 0 {
+                        items.len().checked_sub(index.unsigned_abs() as usize)
+                    } else {
+                        Some(*index as usize)
+                    };
+                    position.and_then(|i| items.get(i))
+                }
+                _ => None,
+            };
+            match next {
+                Some(value) => current = value,
+                None => return Value::Null,
+            }
+        }
+        current.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_select() {
+        let state = json!({
+            "model_name": "whisper-base",
+            "recent_requests": [{"command": "Ping"}, {"command": "GetStatus"}],
+            "nested": {"matrix": [[1, 2], [3, 4]]}
+        });
+
+        let query = Query::parse("status.model_name").unwrap();
+        assert_eq!(query.root.as_deref(), Some("status"));
+        assert_eq!(query.select(&state), json!("whisper-base"));
+
+        let query = Query::parse(".status.recent_requests[-1].command").unwrap();
+        assert_eq!(query.select(&state), json!("GetStatus"));
+        assert_eq!(
+            Query::parse("status.nested.matrix[1][0]")
+                .unwrap()
+                .select(&state),
+            json!(3)
+        );
+
+        // Missing fields and out-of-range indices are null
+        assert_eq!(
+            Query::parse("status.missing.field").unwrap().select(&state),
+            Value::Null
+        );
+        assert_eq!(
+            Query::parse("status.recent_requests[-5]")
+                .unwrap()
+                .select(&state),
+            Value::Null
+        );
+
+        assert_eq!(Query::parse(".").unwrap().root, None);
+        assert!(Query::parse("bogus.field").is_err());
+        assert!(Query::parse("status..model").is_err());
+        assert!(Query::parse("status.model.").is_err());
+        assert!(Query::parse("status[x]").is_err());
+    }
+}
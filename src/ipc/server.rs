@@ -2,31 +2,108 @@
 //!
 //! Platform-specific IPC server for handling daemon commands.
 
-use super::protocol::{Command, Message, Payload, Response};
+use super::protocol::{Command, Event, Handshake, Message, PROTOCOL_VERSION, Payload, Response};
+use super::query::{self, Query};
+use crate::daemon::privacy::format_duration;
+use crate::daemon::recovery;
 use crate::daemon::state::DaemonState as DaemonStateManager;
+use crate::daemon::upgrade;
+use crate::history::BackupManager;
+use crate::models::{DEFAULT_STALL_TIMEOUT, ModelRegistry, stall_timeout_from_secs};
+use crate::platform::{InjectorConfig, TextInjector, undo};
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 #[cfg(windows)]
 use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
 #[cfg(unix)]
 use tokio::net::{UnixListener, UnixStream};
-#[cfg(windows)]
-use tokio::sync::Semaphore;
-use tokio::sync::{Mutex, RwLock};
-use tracing::{debug, error, info, warn};
+use tokio::sync::{Mutex, RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, debug, error, info, warn};
+
+/// How long a session may sit idle between requests before it is closed
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Largest request accepted (1MB)
+const MAX_MESSAGE_LEN: usize = 1_000_000;
+
+/// State shared by every connection
+#[derive(Clone)]
+struct Shared {
+    state: Arc<RwLock<DaemonStateManager>>,
+    request_limiter: Arc<Mutex<HashMap<u32, Instant>>>,
+    min_request_interval: Duration,
+    in_flight: Arc<AtomicUsize>,
+    sessions: Arc<AtomicUsize>,
+    shutdown: CancellationToken,
+}
+
+impl Shared {
+    fn new(state: Arc<RwLock<DaemonStateManager>>) -> Self {
+        Self {
+            state,
+            request_limiter: Arc::new(Mutex::new(HashMap::new())),
+            min_request_interval: Duration::from_millis(10), // Reduced from 50ms to allow faster commands
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            sessions: Arc::new(AtomicUsize::new(0)),
+            shutdown: CancellationToken::new(),
+        }
+    }
+}
+
+/// State of one client connection, which may carry many requests
+struct Session {
+    /// Connection number, for logs
+    id: u64,
+    /// Peer UID (Unix) or process ID (Windows)
+    peer: u32,
+    /// Name the client last identified itself with
+    client_name: String,
+    /// Requests handled on this connection
+    requests: u64,
+    open: Arc<AtomicUsize>,
+}
+
+impl Session {
+    fn open(peer: u32, open: &Arc<AtomicUsize>) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+        open.fetch_add(1, Ordering::SeqCst);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            peer,
+            client_name: "unknown".to_string(),
+            requests: 0,
+            open: Arc::clone(open),
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.open.fetch_sub(1, Ordering::SeqCst);
+        debug!(
+            "IPC session {} closed ({} requests from {})",
+            self.id, self.requests, self.client_name
+        );
+    }
+}
+
+/// Bus receiver and filter for a client that subscribed to events
+type Subscription = (tokio::sync::broadcast::Receiver<Event>, Vec<String>);
 
 /// IPC server
 pub struct IpcServer {
     socket_path: PathBuf,
     #[cfg(unix)]
     listener: Option<UnixListener>,
-    state: Arc<RwLock<DaemonStateManager>>,
-    request_limiter: Arc<Mutex<HashMap<u32, Instant>>>,
-    min_request_interval: Duration,
+    shared: Shared,
+    max_connections: usize,
 }
 
 impl IpcServer {
@@ -36,14 +113,58 @@ impl IpcServer {
             socket_path,
             #[cfg(unix)]
             listener: None,
-            state,
-            request_limiter: Arc::new(Mutex::new(HashMap::new())),
-            min_request_interval: Duration::from_millis(10), // Reduced from 50ms to allow faster commands
+            shared: Shared::new(state),
+            max_connections: 32,
         }
     }
 
+    /// Limit how many clients may be connected at once
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Requests currently being handled (excludes open event streams)
+    pub fn in_flight(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.shared.in_flight)
+    }
+
+    /// Client connections currently open
+    pub fn sessions(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.shared.sessions)
+    }
+
+    /// Token that stops accepting and tells connected clients the daemon is
+    /// shutting down
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shared.shutdown.clone()
+    }
+
+    /// Duplicate of the listening socket, kept open across a live upgrade
+    #[cfg(unix)]
+    pub fn listener_fd(&self) -> Option<std::os::fd::OwnedFd> {
+        use std::os::fd::AsFd;
+
+        self.listener
+            .as_ref()
+            .and_then(|listener| listener.as_fd().try_clone_to_owned().ok())
+    }
+
     /// Start the IPC server
     pub async fn start(&mut self) -> Result<()> {
+        #[cfg(unix)]
+        if let Some(fd) = upgrade::take_inherited_listener() {
+            // Live upgrade: keep serving the socket the previous daemon bound
+            let listener = std::os::unix::net::UnixListener::from(fd);
+            listener.set_nonblocking(true)?;
+            self.listener = Some(UnixListener::from_std(listener)?);
+            info!(
+                "IPC server listening on {:?} (inherited from previous daemon)",
+                self.socket_path
+            );
+            return Ok(());
+        }
+
         #[cfg(unix)]
         {
             // Remove existing socket file if it exists
@@ -98,23 +219,31 @@ impl IpcServer {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Server not started"))?;
 
-        info!("IPC server accepting connections");
+        info!(
+            "IPC server accepting connections (max {})",
+            self.max_connections
+        );
+
+        // Limit concurrent connections; extra clients wait in the backlog
+        let semaphore = Arc::new(Semaphore::new(self.max_connections));
 
         loop {
-            match listener.accept().await {
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit?,
+                _ = self.shared.shutdown.cancelled() => return Ok(()),
+            };
+
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = self.shared.shutdown.cancelled() => return Ok(()),
+            };
+
+            match accepted {
                 Ok((stream, _addr)) => {
-                    let state = Arc::clone(&self.state);
-                    let request_limiter = Arc::clone(&self.request_limiter);
-                    let min_request_interval = self.min_request_interval;
+                    let shared = self.shared.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = Self::handle_unix_client(
-                            stream,
-                            state,
-                            request_limiter,
-                            min_request_interval,
-                        )
-                        .await
-                        {
+                        let _permit = permit; // Hold permit until the session ends
+                        if let Err(e) = Self::handle_unix_client(stream, shared).await {
                             error!("Error handling client: {}", e);
                         }
                     });
@@ -134,19 +263,25 @@ impl IpcServer {
             .ok_or_else(|| anyhow::anyhow!("Invalid Windows named pipe path"))?
             .to_string();
 
-        info!("IPC server accepting named pipe connections");
+        info!(
+            "IPC server accepting named pipe connections (max {})",
+            self.max_connections
+        );
 
         // Limit concurrent connections to prevent resource exhaustion
-        let semaphore = Arc::new(Semaphore::new(10));
+        let semaphore = Arc::new(Semaphore::new(self.max_connections));
 
         loop {
             // Acquire permit for new connection
-            let permit = match semaphore.clone().acquire_owned().await {
-                Ok(p) => p,
-                Err(e) => {
-                    error!("Failed to acquire connection permit: {}", e);
-                    continue;
-                }
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => match permit {
+                    Ok(p) => p,
+                    Err(e) => {
+                        error!("Failed to acquire connection permit: {}", e);
+                        continue;
+                    }
+                },
+                _ = self.shared.shutdown.cancelled() => return Ok(()),
             };
 
             // Create new pipe instance
@@ -165,26 +300,20 @@ impl IpcServer {
             };
 
             // Wait for client connection
-            if let Err(e) = server.connect().await {
+            let connected = tokio::select! {
+                connected = server.connect() => connected,
+                _ = self.shared.shutdown.cancelled() => return Ok(()),
+            };
+            if let Err(e) = connected {
                 error!("Error accepting named pipe connection: {}", e);
                 drop(permit);
                 continue;
             }
 
-            let state = Arc::clone(&self.state);
-            let request_limiter = Arc::clone(&self.request_limiter);
-            let min_request_interval = self.min_request_interval;
-
+            let shared = self.shared.clone();
             tokio::spawn(async move {
-                let _permit = permit; // Hold permit until handler completes
-                if let Err(e) = Self::handle_windows_client(
-                    server,
-                    state,
-                    request_limiter,
-                    min_request_interval,
-                )
-                .await
-                {
+                let _permit = permit; // Hold permit until the session ends
+                if let Err(e) = Self::handle_windows_client(server, shared).await {
                     error!("Error handling client: {}", e);
                 }
             });
@@ -266,62 +395,122 @@ impl IpcServer {
 
     /// Handle a Unix client connection
     #[cfg(unix)]
-    async fn handle_unix_client(
-        stream: UnixStream,
-        state: Arc<RwLock<DaemonStateManager>>,
-        request_limiter: Arc<Mutex<HashMap<u32, Instant>>>,
-        min_request_interval: Duration,
-    ) -> Result<()> {
+    async fn handle_unix_client(stream: UnixStream, shared: Shared) -> Result<()> {
         // SECURITY: Verify client credentials first
         let client_uid = Self::verify_client_credentials(&stream)?;
-        Self::handle_client(
-            stream,
-            state,
-            request_limiter,
-            min_request_interval,
-            client_uid,
-        )
-        .await
+        Self::handle_client(stream, shared, client_uid).await
     }
 
     /// Handle a Windows named-pipe client connection
     #[cfg(windows)]
-    async fn handle_windows_client(
-        stream: NamedPipeServer,
-        state: Arc<RwLock<DaemonStateManager>>,
-        request_limiter: Arc<Mutex<HashMap<u32, Instant>>>,
-        min_request_interval: Duration,
-    ) -> Result<()> {
+    async fn handle_windows_client(stream: NamedPipeServer, shared: Shared) -> Result<()> {
         let client_uid = Self::verify_client_identity(&stream)?;
-        Self::handle_client(
-            stream,
-            state,
-            request_limiter,
-            min_request_interval,
-            client_uid,
-        )
-        .await
+        Self::handle_client(stream, shared, client_uid).await
     }
 
-    async fn handle_client<S>(
-        mut stream: S,
-        state: Arc<RwLock<DaemonStateManager>>,
-        request_limiter: Arc<Mutex<HashMap<u32, Instant>>>,
-        min_request_interval: Duration,
-        client_uid: u32,
-    ) -> Result<()>
+    /// Serve requests on one connection until the client disconnects, goes
+    /// idle, subscribes to events, or the daemon shuts down
+    async fn handle_client<S>(mut stream: S, shared: Shared, client_uid: u32) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        match tokio::time::timeout(SESSION_IDLE_TIMEOUT, Self::handshake(&mut stream)).await {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) | Err(_) => return Ok(()),
+            Ok(Err(e)) => return Err(e),
+        }
+
+        let mut session = Session::open(client_uid, &shared.sessions);
+        debug!("IPC session {} opened (peer {})", session.id, client_uid);
+
+        loop {
+            let read = tokio::select! {
+                read = tokio::time::timeout(SESSION_IDLE_TIMEOUT, Self::read_message(&mut stream)) => read,
+                _ = shared.shutdown.cancelled() => {
+                    // Tell the client why the connection is closing
+                    let notice = Message::event(0, Event::ShuttingDown);
+                    let _ = Self::write_message(&mut stream, &notice).await;
+                    return Ok(());
+                }
+            };
+
+            let message = match read {
+                Ok(Ok(Some(message))) => message,
+                // Client closed the connection between requests
+                Ok(Ok(None)) => return Ok(()),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {
+                    debug!("IPC session {} idle, closing", session.id);
+                    return Ok(());
+                }
+            };
+
+            if let Some((rx, filter)) =
+                Self::handle_message(&mut stream, message, &shared, &mut session).await?
+            {
+                return Self::stream_events(stream, rx, filter, shared.shutdown).await;
+            }
+        }
+    }
+
+    /// Answer the client's handshake with the daemon's protocol version,
+    /// returning false if the client speaks another one
+    async fn handshake<S>(stream: &mut S) -> Result<bool>
     where
         S: AsyncRead + AsyncWrite + Unpin,
     {
-        debug!("New IPC client connected");
+        let Some(frame) = Self::read_frame(stream).await? else {
+            return Ok(false);
+        };
+        let hello = bincode::deserialize::<Handshake>(&frame)
+            .ok()
+            .filter(Handshake::is_valid);
+        Self::write_frame(stream, &Handshake::current()).await?;
+
+        match hello {
+            Some(hello) if hello.version == PROTOCOL_VERSION => Ok(true),
+            Some(hello) => {
+                warn!(
+                    "Rejecting IPC client speaking protocol version {} (daemon: {})",
+                    hello.version, PROTOCOL_VERSION
+                );
+                Ok(false)
+            }
+            None => {
+                warn!("Rejecting IPC client without a protocol handshake (older onevox CLI?)");
+                Ok(false)
+            }
+        }
+    }
 
+    /// Read a length-prefixed message, or `None` if the client closed the
+    /// connection instead of sending one
+    async fn read_message<S>(stream: &mut S) -> Result<Option<Message>>
+    where
+        S: AsyncRead + Unpin,
+    {
+        match Self::read_frame(stream).await? {
+            Some(frame) => Ok(Some(bincode::deserialize(&frame)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Read one length-prefixed frame, or `None` at end of stream
+    async fn read_frame<S>(stream: &mut S) -> Result<Option<Vec<u8>>>
+    where
+        S: AsyncRead + Unpin,
+    {
         // Read message length (4 bytes)
         let mut len_bytes = [0u8; 4];
-        stream.read_exact(&mut len_bytes).await?;
+        match stream.read_exact(&mut len_bytes).await {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
         let message_len = u32::from_le_bytes(len_bytes) as usize;
 
-        // Sanity check message size (max 1MB)
-        if message_len > 1_000_000 {
+        // Sanity check message size
+        if message_len > MAX_MESSAGE_LEN {
             warn!("Rejecting oversized message: {} bytes", message_len);
             return Err(anyhow::anyhow!("Message too large"));
         }
@@ -329,53 +518,188 @@ impl IpcServer {
         // Read message data
         let mut message_buf = vec![0u8; message_len];
         stream.read_exact(&mut message_buf).await?;
+        Ok(Some(message_buf))
+    }
 
-        // Deserialize message
-        let message: Message = bincode::deserialize(&message_buf)?;
+    /// Answer one request, returning the subscription if the client
+    /// switched the connection to an event stream
+    async fn handle_message<S>(
+        stream: &mut S,
+        message: Message,
+        shared: &Shared,
+        session: &mut Session,
+    ) -> Result<Option<Subscription>>
+    where
+        S: AsyncWrite + Unpin,
+    {
+        shared.in_flight.fetch_add(1, Ordering::SeqCst);
         debug!("Received message: {:?}", message);
 
+        if let Some(name) = &message.client_name {
+            session.client_name.clone_from(name);
+        }
+        session.requests += 1;
+        let client_name = session.client_name.clone();
+        let client_uid = session.peer;
+        let span = tracing::info_span!(
+            "ipc",
+            request_id = %message.request_id,
+            client = %client_name,
+            session = session.id
+        );
+
+        // Subscribe before answering so no event published in between is lost
+        let subscription = match &message.payload {
+            Payload::Request(Command::Subscribe { events }) => Some((
+                shared.state.read().await.events().subscribe(),
+                events.clone(),
+            )),
+            _ => None,
+        };
+
         // Check rate limit based on command type
         let response = match &message.payload {
             Payload::Request(command) => {
+                debug!(
+                    parent: &span,
+                    "{} request from {} (peer {})",
+                    command.name(),
+                    client_name,
+                    client_uid
+                );
+
                 // Check rate limit (skips for critical commands)
-                if let Err(e) = Self::check_rate_limit(
-                    &request_limiter,
+                let response = if let Err(e) = Self::check_rate_limit(
+                    &shared.request_limiter,
                     client_uid,
-                    min_request_interval,
+                    shared.min_request_interval,
                     command,
                 )
                 .await
                 {
                     Response::Error(format!("Rate limited: {}", e))
                 } else {
-                    Self::handle_command(command.clone(), &state).await
+                    Self::handle_command(command.clone(), &shared.state)
+                        .instrument(span.clone())
+                        .await
+                };
+
+                // Record the request for `status --verbose`
+                {
+                    let mut state = shared.state.write().await;
+                    state.record_request(
+                        &client_name,
+                        client_uid,
+                        &message.request_id,
+                        command.name(),
+                        matches!(response, Response::Error(_)),
+                    );
                 }
+
+                response
             }
             _ => Response::Error("Invalid message type".to_string()),
         };
 
         // Send response
-        let response_msg = Message::response(message.id, response);
-        let response_bytes = bincode::serialize(&response_msg)?;
+        let subscribed = matches!(response, Response::Success);
+        let response_msg = Message::response_to(&message, response);
+        let written = Self::write_message(stream, &response_msg).await;
+        shared.in_flight.fetch_sub(1, Ordering::SeqCst);
+        written?;
+
+        debug!(parent: &span, "Response sent");
+
+        Ok(subscription.filter(|_| subscribed))
+    }
+
+    /// Write a length-prefixed message
+    async fn write_message<S>(stream: &mut S, message: &Message) -> Result<()>
+    where
+        S: AsyncWrite + Unpin,
+    {
+        Self::write_frame(stream, message).await
+    }
 
-        // Write response length + data
-        let len = response_bytes.len() as u32;
+    /// Write any value as a length-prefixed frame
+    async fn write_frame<S, T>(stream: &mut S, value: &T) -> Result<()>
+    where
+        S: AsyncWrite + Unpin,
+        T: serde::Serialize,
+    {
+        let bytes = bincode::serialize(value)?;
+        let len = bytes.len() as u32;
         stream.write_all(&len.to_le_bytes()).await?;
-        stream.write_all(&response_bytes).await?;
+        stream.write_all(&bytes).await?;
         stream.flush().await?;
+        Ok(())
+    }
 
-        debug!("Response sent");
+    /// Forward bus events to a subscribed client until it disconnects or the
+    /// daemon shuts down
+    async fn stream_events<S>(
+        mut stream: S,
+        mut rx: tokio::sync::broadcast::Receiver<Event>,
+        filter: Vec<String>,
+        shutdown: CancellationToken,
+    ) -> Result<()>
+    where
+        S: AsyncWrite + Unpin,
+    {
+        info!("Event subscriber connected (filter: {:?})", filter);
+        let mut sequence = 0u64;
+
+        loop {
+            let received = tokio::select! {
+                received = rx.recv() => received,
+                _ = shutdown.cancelled() => {
+                    // Subscribers that filtered out ShuttingDown still learn why
+                    // the stream ends
+                    sequence += 1;
+                    let notice = Message::event(sequence, Event::ShuttingDown);
+                    let _ = Self::write_message(&mut stream, &notice).await;
+                    break;
+                }
+            };
+            let event = match received {
+                Ok(event) => event,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Event subscriber lagged, skipped {} events", skipped);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            if !event.matches(&filter) {
+                continue;
+            }
+
+            let shutting_down = matches!(event, Event::ShuttingDown);
+            sequence += 1;
+            if Self::write_message(&mut stream, &Message::event(sequence, event))
+                .await
+                .is_err()
+                || shutting_down
+            {
+                break;
+            }
+        }
+
+        info!("Event subscriber disconnected");
         Ok(())
     }
 
-    /// Handle a command and generate response
-    async fn handle_command(command: Command, state: &Arc<RwLock<DaemonStateManager>>) -> Response {
+    /// Handle a command and generate response (also used by the HTTP API)
+    pub(crate) async fn handle_command(
+        command: Command,
+        state: &Arc<RwLock<DaemonStateManager>>,
+    ) -> Response {
         match command {
             Command::Ping => Response::Pong,
 
             Command::GetStatus => {
                 let state = state.read().await;
-                Response::Status(state.status())
+                Response::Status(Box::new(state.status()))
             }
 
             Command::Shutdown => {
@@ -426,20 +750,74 @@ impl IpcServer {
                 }
             }
 
+            Command::PauseDictation => {
+                info!("Pause dictation command received");
+                let state = state.read().await;
+                match state.pause_dictation(true) {
+                    Ok(()) => Response::Success,
+                    Err(e) => Response::Error(format!("Failed to pause dictation: {}", e)),
+                }
+            }
+
+            Command::ResumeDictation => {
+                info!("Resume dictation command received");
+                let state = state.read().await;
+                match state.pause_dictation(false) {
+                    Ok(()) => Response::Success,
+                    Err(e) => Response::Error(format!("Failed to resume dictation: {}", e)),
+                }
+            }
+
             Command::ListDevices => {
-                // TODO: Implement device listing
-                Response::List(vec!["default".to_string()])
+                let devices = tokio::task::spawn_blocking(|| {
+                    crate::audio::AudioDeviceManager::new().list_input_devices()
+                })
+                .await;
+                match devices {
+                    Ok(Ok(devices)) => {
+                        Response::List(devices.into_iter().map(|device| device.name).collect())
+                    }
+                    Ok(Err(e)) => Response::Error(format!("Failed to list devices: {}", e)),
+                    Err(e) => Response::Error(format!("Device listing failed: {}", e)),
+                }
+            }
+
+            Command::SetDevice { name } => {
+                info!("Set device command: {}", name);
+                let lookup = name.clone();
+                let checked = tokio::task::spawn_blocking(move || {
+                    crate::audio::devices::check_input_device(&lookup)
+                })
+                .await
+                .map_err(|e| crate::Error::Other(e.to_string()))
+                .and_then(|checked| checked);
+                let result = match checked {
+                    Ok(()) => state.write().await.set_device(&name),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => Response::Ok(format!("Capturing from {}", name)),
+                    Err(e) => Response::Error(format!("Failed to switch to {}: {}", name, e)),
+                }
             }
 
             Command::ListModels => {
-                // TODO: Implement model listing
-                Response::List(vec![])
+                let downloaded = match crate::models::ModelDownloader::new() {
+                    Ok(downloader) => downloader.list_downloaded().await,
+                    Err(e) => Err(e),
+                };
+                match downloaded {
+                    Ok(models) => Response::List(models),
+                    Err(e) => Response::Error(format!("Failed to list models: {}", e)),
+                }
             }
 
             Command::LoadModel { path } => {
                 info!("Load model command: {}", path);
-                // TODO: Implement model loading (backend auto-detected from path)
-                Response::Ok(format!("Model loaded (not yet implemented): {}", path))
+                match crate::daemon::downloads::activate_model(state, &path).await {
+                    Ok(()) => Response::Ok(format!("Switched to {}", path)),
+                    Err(e) => Response::Error(format!("Failed to switch to {}: {}", path, e)),
+                }
             }
 
             Command::UnloadModel => {
@@ -457,6 +835,16 @@ impl IpcServer {
                 }
             }
 
+            Command::SearchHistory { query } => {
+                info!("Search history command received");
+                debug!("Search query: {:?}", query.text);
+                let state = state.read().await;
+                match state.history_manager().search(&query).await {
+                    Ok(matches) => Response::HistoryMatches(matches),
+                    Err(e) => Response::Error(format!("Failed to search history: {}", e)),
+                }
+            }
+
             Command::DeleteHistoryEntry { id } => {
                 info!("Delete history entry command received: {}", id);
                 let state = state.read().await;
@@ -467,17 +855,322 @@ impl IpcServer {
                 }
             }
 
+            Command::GetStats { since } => {
+                info!("Get stats command received");
+                let state = state.read().await;
+                match state.history_manager().get_all().await {
+                    Ok(entries) => {
+                        Response::Stats(crate::history::HistoryStats::compute(&entries, since))
+                    }
+                    Err(e) => Response::Error(format!("Failed to get history: {}", e)),
+                }
+            }
+
+            Command::AddHistory { entries } => {
+                info!("Add history command received: {} entries", entries.len());
+                let count = entries.len();
+                let state = state.read().await;
+                match state.history_manager().add_entries(entries).await {
+                    Ok(()) => Response::Ok(format!("Added {} entries to history", count)),
+                    Err(e) => Response::Error(format!("Failed to add history: {}", e)),
+                }
+            }
+
             Command::ClearHistory => {
                 info!("Clear history command received");
                 let state = state.read().await;
+                Self::snapshot_history(&state, "before-clear").await;
                 match state.history_manager().clear().await {
                     Ok(()) => Response::Ok("History cleared".to_string()),
                     Err(e) => Response::Error(format!("Failed to clear history: {}", e)),
                 }
             }
+
+            Command::Subscribe { events } => {
+                match events
+                    .iter()
+                    .find(|name| !Event::NAMES.iter().any(|n| n.eq_ignore_ascii_case(name)))
+                {
+                    Some(unknown) => Response::Error(format!(
+                        "Unknown event '{}'. Available: {}",
+                        unknown,
+                        Event::NAMES.join(", ")
+                    )),
+                    None => Response::Success,
+                }
+            }
+
+            Command::RestoreHistory { from } => {
+                info!("Restore history command received: {}", from);
+                let state = state.read().await;
+                match Self::restore_history(&state, &from).await {
+                    Ok(message) => Response::Ok(message),
+                    Err(e) => Response::Error(format!("Failed to restore history: {}", e)),
+                }
+            }
+
+            Command::Undo { count } => {
+                info!("Undo command received: {} injections", count);
+                let (undo_stack, injector) = {
+                    let state = state.read().await;
+                    let injector =
+                        TextInjector::new(InjectorConfig::from_config(&state.config().injection));
+                    (Arc::clone(state.undo_stack()), injector)
+                };
+                match tokio::task::spawn_blocking(move || {
+                    undo::undo_injections(&undo_stack, &injector, count)
+                })
+                .await
+                {
+                    Ok(Ok(outcome)) => Response::Ok(format!(
+                        "Undid {} injection(s) ({} characters)",
+                        outcome.injections, outcome.chars
+                    )),
+                    Ok(Err(e)) => Response::Error(format!("Failed to undo: {}", e)),
+                    Err(e) => Response::Error(format!("Undo task failed: {}", e)),
+                }
+            }
+
+            Command::PrivacyPause { duration_secs } => {
+                info!("Privacy pause command received ({:?}s)", duration_secs);
+                let state = state.read().await;
+                state.pause_privacy(duration_secs.map(Duration::from_secs));
+                Response::Ok(match duration_secs {
+                    Some(secs) => format!("Capture paused for {}", format_duration(secs)),
+                    None => "Capture paused until resumed".to_string(),
+                })
+            }
+
+            Command::PrivacyResume => {
+                info!("Privacy resume command received");
+                let state = state.read().await;
+                if state.privacy().resume() {
+                    Response::Ok("Capture resumed".to_string())
+                } else {
+                    Response::Ok("Capture was not paused".to_string())
+                }
+            }
+
+            Command::ConfirmInjection { discard } => {
+                info!("Confirm injection command received (discard: {})", discard);
+                let (held, undo_stack, injector) = {
+                    let state = state.read().await;
+                    let injector =
+                        TextInjector::new(InjectorConfig::from_config(&state.config().injection));
+                    (
+                        Arc::clone(state.held_injection()),
+                        Arc::clone(state.undo_stack()),
+                        injector,
+                    )
+                };
+                match tokio::task::spawn_blocking(move || {
+                    recovery::confirm_held(&held, &injector, &undo_stack, discard)
+                })
+                .await
+                {
+                    Ok(Ok(held)) if discard => Response::Ok(format!(
+                        "Discarded held transcript ({} characters)",
+                        held.text.chars().count()
+                    )),
+                    Ok(Ok(held)) => Response::Ok(format!(
+                        "Typed held transcript ({} characters)",
+                        held.text.chars().count()
+                    )),
+                    Ok(Err(e)) => Response::Error(e.to_string()),
+                    Err(e) => Response::Error(format!("Confirm task failed: {}", e)),
+                }
+            }
+
+            Command::Query { expr } => {
+                let query = match Query::parse(&expr) {
+                    Ok(query) => query,
+                    Err(e) => return Response::Error(e),
+                };
+                let state = state.read().await;
+                let roots: Vec<&str> = match &query.root {
+                    Some(root) => vec![root.as_str()],
+                    None => query::ROOTS.to_vec(),
+                };
+
+                let mut values = serde_json::Map::new();
+                for root in roots {
+                    match Self::query_root(&state, root).await {
+                        Ok(value) => {
+                            values.insert(root.to_string(), value);
+                        }
+                        Err(e) => {
+                            return Response::Error(format!("Failed to read {}: {}", root, e));
+                        }
+                    }
+                }
+
+                let result = match &query.root {
+                    Some(root) => query.select(&values[root]),
+                    None => serde_json::Value::Object(values),
+                };
+                match serde_json::to_string(&result) {
+                    Ok(json) => Response::Json(json),
+                    Err(e) => Response::Error(format!("Failed to encode query result: {}", e)),
+                }
+            }
+
+            Command::TranscribeFile { path } => {
+                info!("Transcribe file command received: {}", path);
+                // Release the state lock while the model runs
+                let queued = state.read().await.transcribe_file(PathBuf::from(&path));
+                let result = match queued {
+                    Ok(response) => response.await.unwrap_or_else(|_| {
+                        Err(crate::Error::Other("Dictation engine stopped".to_string()))
+                    }),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(text) => Response::Ok(text),
+                    Err(e) => Response::Error(format!("Failed to transcribe {}: {}", path, e)),
+                }
+            }
+
+            Command::GetLastTranscript => {
+                let last = state.read().await.last_transcript().lock().clone();
+                Response::LastTranscript(last)
+            }
+
+            Command::DownloadModel {
+                model_id,
+                activate,
+                stall_timeout_secs,
+            } => {
+                info!("Download model command received: {}", model_id);
+                let Some(metadata) = ModelRegistry::new().get_model(&model_id).cloned() else {
+                    return Response::Error(format!("Model not found: {}", model_id));
+                };
+                let (downloads, events) = {
+                    let state = state.read().await;
+                    (state.downloads().clone(), state.events().clone())
+                };
+                let stall_timeout =
+                    stall_timeout_secs.map_or(Some(DEFAULT_STALL_TIMEOUT), stall_timeout_from_secs);
+                match downloads.start(metadata, Arc::clone(state), events, activate, stall_timeout)
+                {
+                    Ok(()) => Response::Ok(format!("Downloading {}", model_id)),
+                    Err(e) => Response::Error(e.to_string()),
+                }
+            }
+
+            Command::StopDownload { model_id, discard } => {
+                info!(
+                    "{} download command received: {}",
+                    if discard { "Cancel" } else { "Pause" },
+                    model_id
+                );
+                if state.read().await.downloads().stop(&model_id, discard) {
+                    Response::Ok(format!(
+                        "{} download of {}",
+                        if discard { "Cancelling" } else { "Pausing" },
+                        model_id
+                    ))
+                } else {
+                    Response::Error(format!("{} is not downloading", model_id))
+                }
+            }
+
+            Command::Upgrade { binary } => {
+                info!("Live upgrade requested: {}", binary);
+                if !cfg!(unix) {
+                    return Response::Error(
+                        "Live upgrade is only supported on Unix platforms".to_string(),
+                    );
+                }
+                let binary = PathBuf::from(binary);
+                let check = binary.clone();
+                match tokio::task::spawn_blocking(move || upgrade::verify_binary(&check)).await {
+                    Ok(Ok(version)) => {
+                        state.write().await.request_upgrade(binary);
+                        Response::Ok(version)
+                    }
+                    Ok(Err(e)) => Response::Error(e.to_string()),
+                    Err(e) => Response::Error(format!("Upgrade check failed: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Daemon state exposed under a query root
+    async fn query_root(
+        state: &DaemonStateManager,
+        root: &str,
+    ) -> crate::Result<serde_json::Value> {
+        let encode = |e: serde_json::Error| crate::Error::Other(e.to_string());
+        match root {
+            "status" => serde_json::to_value(state.status()).map_err(encode),
+            "config" => serde_json::to_value(state.config()).map_err(encode),
+            "metrics" => {
+                let status = state.status();
+                Ok(serde_json::json!({
+                    "uptime_secs": status.uptime_secs,
+                    "memory_usage_bytes": status.memory_usage_bytes,
+                    "cpu_usage_percent": status.cpu_usage_percent,
+                    "event_counts": status.event_counts,
+                    "event_subscribers": status.event_subscribers,
+                    "clients": status.clients.len(),
+                    "history_entries": state.history_manager().count(),
+                }))
+            }
+            "last_transcription" => {
+                let entries = state.history_manager().get_all().await?;
+                serde_json::to_value(entries.last()).map_err(encode)
+            }
+            other => Err(crate::Error::Other(format!(
+                "Unknown query root '{}'",
+                other
+            ))),
+        }
+    }
+
+    /// Snapshot the current history before a destructive operation
+    async fn snapshot_history(state: &DaemonStateManager, label: &str) {
+        if !state.config().backup.enabled {
+            return;
+        }
+
+        let result = match (
+            BackupManager::from_config(&state.config().backup),
+            state.history_manager().get_all().await,
+        ) {
+            (Ok(backups), Ok(entries)) if !entries.is_empty() => {
+                backups.snapshot(&entries, label).map(|_| ())
+            }
+            (Err(e), _) | (_, Err(e)) => Err(e),
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to back up history ({}): {}", label, e);
         }
     }
 
+    /// Replace history with a backup, snapshotting the current history first
+    async fn restore_history(state: &DaemonStateManager, from: &str) -> crate::Result<String> {
+        let backups = BackupManager::from_config(&state.config().backup)?;
+        let path = backups.resolve(from)?;
+        let backup = BackupManager::load(&path)?;
+
+        Self::snapshot_history(state, "before-restore").await;
+
+        let count = backup.entries.len();
+        state
+            .history_manager()
+            .replace_all(backup.entries.clone())
+            .await?;
+        backups.restore_files(&backup)?;
+
+        Ok(format!(
+            "Restored {} entries from {}",
+            count,
+            path.display()
+        ))
+    }
+
     /// Stop the server and clean up
     pub fn stop(&mut self) -> Result<()> {
         #[cfg(unix)]
@@ -496,3 +1189,62 @@ impl Drop for IpcServer {
         let _ = self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn test_session_serves_requests_until_shutdown() {
+        let state = DaemonStateManager::new(Config::default());
+        let shared = Shared::new(Arc::new(RwLock::new(state)));
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let session = tokio::spawn(IpcServer::handle_client(server, shared.clone(), 1000));
+
+        IpcServer::write_frame(&mut client, &Handshake::current())
+            .await
+            .unwrap();
+        let frame = IpcServer::read_frame(&mut client).await.unwrap().unwrap();
+        let reply: Handshake = bincode::deserialize(&frame).unwrap();
+        assert_eq!(reply, Handshake::current());
+
+        // Several requests over one connection
+        for id in 1..=3 {
+            let request = Message::request(id, Command::Ping).with_client_name("test");
+            IpcServer::write_message(&mut client, &request)
+                .await
+                .unwrap();
+            let reply = IpcServer::read_message(&mut client).await.unwrap().unwrap();
+            assert_eq!(reply.id, id);
+            assert!(matches!(reply.payload, Payload::Response(Response::Pong)));
+        }
+        assert_eq!(shared.sessions.load(Ordering::SeqCst), 1);
+        assert_eq!(shared.in_flight.load(Ordering::SeqCst), 0);
+
+        // Connected clients are told when the daemon stops
+        shared.shutdown.cancel();
+        let notice = IpcServer::read_message(&mut client).await.unwrap().unwrap();
+        assert!(matches!(
+            notice.payload,
+            Payload::Event(Event::ShuttingDown)
+        ));
+        session.await.unwrap().unwrap();
+        assert_eq!(shared.sessions.load(Ordering::SeqCst), 0);
+
+        // A client on another protocol version learns the daemon's and is
+        // disconnected
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let session = tokio::spawn(IpcServer::handle_client(server, shared.clone(), 1000));
+        let older = Handshake {
+            version: PROTOCOL_VERSION - 1,
+            ..Handshake::current()
+        };
+        IpcServer::write_frame(&mut client, &older).await.unwrap();
+        let frame = IpcServer::read_frame(&mut client).await.unwrap().unwrap();
+        let reply: Handshake = bincode::deserialize(&frame).unwrap();
+        assert_eq!(reply.version, PROTOCOL_VERSION);
+        assert!(IpcServer::read_frame(&mut client).await.unwrap().is_none());
+        session.await.unwrap().unwrap();
+    }
+}
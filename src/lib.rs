@@ -5,21 +5,33 @@
 #![allow(dead_code, unused_variables)]
 
 pub mod audio;
+pub mod automation;
 pub mod config;
 pub mod daemon;
+pub mod eval;
 pub mod health;
 pub mod history;
 pub mod indicator;
 pub mod ipc;
+pub mod logging;
+pub mod meeting;
 pub mod models;
 pub mod platform;
+pub mod postprocess;
+pub mod secrets;
+pub mod storage;
+pub mod stream;
+pub mod telemetry;
+pub mod transcribe;
 pub mod tui;
 pub mod vad;
+pub mod vocabulary;
 
 // Re-export commonly used types
 pub use config::Config;
 pub use daemon::Daemon;
 pub use health::{HealthCheck, HealthChecker, HealthMonitor, HealthStatus};
+pub use stream::{AudioSource, TranscriptEvent, transcribe_stream};
 
 /// Result type alias for onevox operations
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,264 @@
+//! Logging
+//!
+//! Commands log to stderr. The daemon also writes JSON lines under the
+//! platform log directory (`[daemon].log_file`), one file per day named
+//! `onevox.YYYY-MM-DD.log`, keeping the last `log_retention_days` files.
+//! `log_level` sets the level and `log_modules` overrides it per module
+//! (`"onevox::models" = "debug"`); `RUST_LOG` overrides both. A daemon
+//! started in the background, or by launchd, logs to the file only.
+//! `onevox logs` reads the files back as text. `--debug-inference` adds
+//! debug logs from the model backends.
+
+use crate::config::DaemonConfig;
+use crate::models::inference_debug;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Log file names start with this
+pub const LOG_PREFIX: &str = "onevox";
+
+/// Log file extension
+pub const LOG_SUFFIX: &str = "log";
+
+/// Set to "1" to keep the daemon's logs out of stderr when it logs to a file
+pub const FILE_ONLY_ENV: &str = "ONEVOX_LOG_FILE_ONLY";
+
+/// Log stderr at info level (RUST_LOG overrides it)
+pub fn init_cli() {
+    let mut filter = EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into());
+    if inference_debug::enabled()
+        && let Ok(directive) = inference_debug::LOG_DIRECTIVE.parse()
+    {
+        filter = filter.add_directive(directive);
+    }
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+/// Log the daemon to stderr and the log file
+///
+/// Keep the returned guard alive for as long as the daemon runs; dropping
+/// it flushes the file.
+pub fn init_daemon(config: &DaemonConfig) -> Option<WorkerGuard> {
+    let file = if config.log_file {
+        match crate::platform::paths::log_dir().map(|dir| appender(&dir, config)) {
+            Ok(Ok(appender)) => Some(tracing_appender::non_blocking(appender)),
+            Ok(Err(e)) => {
+                eprintln!("⚠️  Logging to stderr only: {}", e);
+                None
+            }
+            Err(e) => {
+                eprintln!("⚠️  Logging to stderr only: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let file_only = file.is_some() && std::env::var(FILE_ONLY_ENV).is_ok_and(|value| value == "1");
+
+    let (file_writer, guard) = file.unzip();
+    let file_layer = file_writer.map(|writer| {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(writer)
+            .with_ansi(false)
+    });
+    let stderr_layer = (!file_only).then(tracing_subscriber::fmt::layer);
+    tracing_subscriber::registry()
+        .with(filter(config))
+        .with(file_layer)
+        .with(stderr_layer)
+        .init();
+    guard
+}
+
+fn appender(
+    dir: &Path,
+    config: &DaemonConfig,
+) -> Result<RollingFileAppender, tracing_appender::rolling::InitError> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix(LOG_SUFFIX)
+        .max_log_files(config.log_retention_days.max(1) as usize)
+        .build(dir)
+}
+
+/// Filter from `RUST_LOG`, or else `log_level` and `log_modules`
+pub fn filter(config: &DaemonConfig) -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    let directives = directives(config);
+    EnvFilter::try_new(&directives).unwrap_or_else(|e| {
+        eprintln!(
+            "⚠️  Invalid log level \"{}\" ({}), using info",
+            directives, e
+        );
+        EnvFilter::new("info")
+    })
+}
+
+/// `log_level` followed by the `log_modules` overrides, as filter directives
+fn directives(config: &DaemonConfig) -> String {
+    std::iter::once(config.log_level.clone())
+        .chain(
+            config
+                .log_modules
+                .iter()
+                .map(|(module, level)| format!("{}={}", module, level)),
+        )
+        .chain(inference_debug::enabled().then(|| inference_debug::LOG_DIRECTIVE.to_string()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Daemon log files in `dir`, oldest first
+pub fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    let name = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or("");
+                    name.starts_with(&format!("{}.", LOG_PREFIX))
+                        && name.ends_with(&format!(".{}", LOG_SUFFIX))
+                        // The date: onevox.2026-01-31.log
+                        && name.len() == LOG_PREFIX.len() + LOG_SUFFIX.len() + 12
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Dates sort as text
+    files.sort();
+    files
+}
+
+/// The daemon log file written to most recently
+pub fn latest_log_file() -> Option<PathBuf> {
+    let dir = crate::platform::paths::log_dir().ok()?;
+    log_files(&dir).pop()
+}
+
+/// Complete lines appended to `path` after `offset`, with the offset to
+/// continue from (a file that shrank is read from the start)
+pub fn read_new_lines(path: &Path, offset: u64) -> std::io::Result<(Vec<String>, u64)> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let offset = if file.metadata()?.len() < offset {
+        0
+    } else {
+        offset
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    // A partly written last line waits for the next read
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let lines = String::from_utf8_lossy(&bytes[..complete])
+        .lines()
+        .map(str::to_string)
+        .collect();
+    Ok((lines, offset + complete as u64))
+}
+
+/// A JSON line from the log file
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogRecord {
+    #[serde(default)]
+    pub timestamp: String,
+    pub level: String,
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogRecord {
+    /// Parse a log line; `None` for lines that aren't log records
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+
+    /// Parsed level
+    pub fn level(&self) -> Option<tracing::Level> {
+        self.level.parse().ok()
+    }
+
+    /// As `2026-01-31 14:03:12.512  INFO onevox::daemon: message key=value`
+    pub fn to_text(&self) -> String {
+        let time = self
+            .timestamp
+            .get(..23)
+            .unwrap_or(&self.timestamp)
+            .replace('T', " ");
+        let mut text = format!("{} {:>5} {}:", time, self.level, self.target);
+        if let Some(message) = self.fields.get("message").and_then(|value| value.as_str()) {
+            text.push(' ');
+            text.push_str(message);
+        }
+        for (key, value) in self.fields.iter().filter(|(key, _)| *key != "message") {
+            let value = value
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string());
+            text.push_str(&format!(" {}={}", key, value));
+        }
+        text
+    }
+}
+
+/// Whether a record at `level` is shown with `min` as the lowest level
+/// (levels order from error, the most severe, to trace)
+pub fn shown_at(level: tracing::Level, min: tracing::Level) -> bool {
+    level <= min
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_files_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "onevox.2026-01-02.log",
+            "onevox.2025-12-31.log",
+            "onevox-stderr.log",
+            "whisper-server.log",
+        ] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        let files = log_files(dir.path());
+        assert_eq!(files.len(), 2);
+        assert!(files[1].ends_with("onevox.2026-01-02.log"));
+
+        let line = r#"{"timestamp":"2026-01-02T14:03:12.512345Z","level":"WARN","fields":{"message":"Slow model","ms":812},"target":"onevox::models"}"#;
+        let record = LogRecord::parse(line).unwrap();
+        assert_eq!(
+            record.to_text(),
+            "2026-01-02 14:03:12.512  WARN onevox::models: Slow model ms=812"
+        );
+        let level = record.level().unwrap();
+        assert!(shown_at(level, tracing::Level::INFO));
+        assert!(!shown_at(level, tracing::Level::ERROR));
+        assert!(LogRecord::parse("thread 'main' panicked").is_none());
+
+        let config = DaemonConfig {
+            log_level: "warn".to_string(),
+            log_modules: [("onevox::models".to_string(), "debug".to_string())].into(),
+            ..crate::Config::default().daemon
+        };
+        assert_eq!(directives(&config), "warn,onevox::models=debug");
+        assert!(EnvFilter::try_new(directives(&config)).is_ok());
+    }
+}
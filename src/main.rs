@@ -9,6 +9,16 @@ use onevox::{Config, Result};
 #[command(about = "Ultra-fast local speech-to-text daemon", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Keep config, models, history, logs, and sockets under one directory
+    /// (also set by ONEVOX_PORTABLE_DIR)
+    #[arg(long, global = true, value_name = "DIR")]
+    portable: Option<std::path::PathBuf>,
+
+    /// Log model internals and save features, logits, and token stats
+    /// under the log directory (also set by ONEVOX_DEBUG_INFERENCE=1)
+    #[arg(long, global = true)]
+    debug_inference: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -29,27 +39,99 @@ enum Commands {
     /// Stop the daemon
     Stop,
 
+    /// Switch the running daemon to the installed onevox binary (run after upgrading)
+    SelfUpdate {
+        /// Hand the socket over without dropping clients (Unix)
+        #[arg(long)]
+        live: bool,
+    },
+
     /// Check daemon status
-    Status,
+    Status {
+        /// Show connected clients and recent requests
+        #[arg(short, long)]
+        verbose: bool,
+    },
 
     /// Reload daemon configuration
     ReloadConfig,
 
+    /// Stream daemon events (dictation, VAD, model, injection, downloads)
+    Events {
+        /// Only show these events (e.g. TranscriptionComplete); default is all
+        names: Vec<String>,
+    },
+
+    /// Read daemon state as JSON (e.g. status.model_name, last_transcription.text)
+    Query {
+        /// Path expression rooted at status, config, metrics or last_transcription
+        #[arg(default_value = ".")]
+        expr: String,
+
+        /// Print string results without JSON quotes
+        #[arg(short, long)]
+        raw: bool,
+    },
+
     /// Configure onevox
     Config {
         #[command(subcommand)]
         action: ConfigAction,
     },
 
+    /// Guided first-run setup: config, microphone self-test and model check
+    Setup {
+        /// Skip playing the recording back
+        #[arg(long)]
+        no_playback: bool,
+    },
+
+    /// Diagnose the installation (backends, permissions, ONNX Runtime)
+    Doctor {
+        #[command(subcommand)]
+        check: Option<DoctorCheck>,
+
+        /// Repair what can be repaired (download the ONNX Runtime, correct
+        /// audio config)
+        #[arg(long, global = true)]
+        fix: bool,
+    },
+
+    /// Measure room noise and your speech level, and set the VAD threshold
+    /// and noise floor from them
+    Calibrate {
+        /// Seconds of silence to record
+        #[arg(long, default_value = "3")]
+        silence: u64,
+
+        /// Seconds of speech to record
+        #[arg(long, default_value = "4")]
+        speech: u64,
+
+        /// Show the measured values without writing them to the config
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Open TUI monitor
     Tui,
 
+    /// Live daemon dashboard: input level, VAD, transcripts; start/stop
+    /// dictation and switch models from the keyboard (needs the tui feature)
+    Monitor,
+
     /// List audio devices
     Devices {
         #[command(subcommand)]
         action: DeviceAction,
     },
 
+    /// Dictation triggers beyond the keyboard hotkey (foot pedals, macro pads)
+    Hotkey {
+        #[command(subcommand)]
+        action: HotkeyAction,
+    },
+
     /// Manage models
     Models {
         #[command(subcommand)]
@@ -62,6 +144,138 @@ enum Commands {
         action: HistoryAction,
     },
 
+    /// Show weekly dictation trends from local telemetry
+    Dashboard {
+        /// Number of weeks to show
+        #[arg(short, long, default_value = "8")]
+        weeks: usize,
+
+        /// Print a table instead of the interactive page
+        #[arg(long)]
+        plain: bool,
+    },
+
+    /// Show dictation stats from history: words per day and week, speaking
+    /// time and rate, models, and errors
+    Stats {
+        /// Number of days to include (0 = all history)
+        #[arg(short, long, default_value = "30")]
+        days: u64,
+
+        /// Write the daily rows to a CSV file
+        #[arg(long)]
+        csv: Option<std::path::PathBuf>,
+    },
+
+    /// Measure transcription accuracy (WER/CER) on reference recordings
+    Eval {
+        /// Directory of <name>.wav files with <name>.txt references
+        #[arg(short, long, default_value = "tests/quality/fixtures")]
+        fixtures: std::path::PathBuf,
+
+        /// Model to evaluate (defaults to the configured model)
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Exit with an error when the corpus WER exceeds this (e.g. 0.15)
+        #[arg(long)]
+        max_wer: Option<f64>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Show the audio conversion applied to each fixture
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Transcribe an audio file (WAV, MP3, FLAC, OGG, MP4/MOV; others via ffmpeg)
+    Transcribe {
+        /// Audio file
+        file: std::path::PathBuf,
+
+        /// Output format: text, srt, vtt, or json (default: from the
+        /// --output extension, else text)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Write the transcript to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+
+        /// Split the audio at pauses with the VAD and transcribe each part
+        #[arg(long)]
+        vad: bool,
+
+        /// Model to use (defaults to the configured model)
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Transcribe live audio from the microphone, the system, or a single
+    /// application
+    Listen {
+        /// Capture what this application plays instead of the microphone
+        /// (app or executable name, e.g. "zoom"; Linux with PipeWire)
+        #[arg(long, conflicts_with = "system")]
+        app: Option<String>,
+
+        /// Capture everything the computer plays (a loopback source)
+        #[arg(long)]
+        system: bool,
+
+        /// Capture the microphone and system audio together, tagging turns
+        /// "Me" and "Others"; the transcript is saved to history
+        #[arg(long, conflicts_with_all = ["app", "system"])]
+        meeting: bool,
+
+        /// List the applications playing audio and exit
+        #[arg(long)]
+        list_apps: bool,
+
+        /// Stop after this many seconds (default: until Ctrl+C)
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Model to use (defaults to the configured model)
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Dictate in the foreground without the daemon, typing into the
+    /// focused app or printing each utterance to stdout
+    Dictate {
+        /// Print finalized utterances as lines on stdout instead of typing
+        /// them (the default with injection.method = "stdout")
+        #[arg(long)]
+        stdout: bool,
+
+        /// Print one JSON object per utterance (implies --stdout)
+        #[arg(long)]
+        json: bool,
+
+        /// Stop after this many seconds (default: until Ctrl+C)
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Model to use (defaults to the configured model)
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Show and limit disk usage
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    /// Manage secrets stored in the OS keychain (referenced as keyring:<name>)
+    Secret {
+        #[command(subcommand)]
+        action: SecretAction,
+    },
+
     /// Test audio capture (dev tool)
     TestAudio {
         /// Duration in seconds
@@ -76,6 +290,25 @@ enum Commands {
         duration: u64,
     },
 
+    /// Compare audio with and without noise suppression (`audio.denoise`)
+    TestDenoise {
+        /// Recording duration in seconds
+        #[arg(short, long, default_value = "6")]
+        duration: u64,
+
+        /// Use an audio file instead of recording
+        #[arg(short, long)]
+        file: Option<std::path::PathBuf>,
+
+        /// Also transcribe both versions with the configured model
+        #[arg(short, long)]
+        transcribe: bool,
+
+        /// Skip playing both versions back
+        #[arg(long)]
+        no_playback: bool,
+    },
+
     /// Test full transcription pipeline (dev tool)
     TestTranscribe {
         /// Duration in seconds
@@ -96,15 +329,108 @@ enum Commands {
     /// Stop dictation (for Wayland/manual triggering)
     StopDictation,
 
+    /// Pause dictation, keeping the microphone open (e.g. for a phone call)
+    PauseDictation,
+
+    /// Resume paused dictation
+    ResumeDictation,
+
+    /// Run an onevox:// automation URL (start, stop, transcribe?file=..., last)
+    OpenUrl {
+        /// URL such as onevox://start or onevox://transcribe?file=/path/memo.wav
+        url: String,
+    },
+
+    /// Print the most recent transcript (e.g. to paste it again by hand)
+    Last {
+        /// Print the transcript and its metadata as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Print the path of the segment audio instead of the text
+        #[arg(long)]
+        audio: bool,
+    },
+
+    /// Erase the most recently injected text
+    Undo {
+        /// Number of injections to undo
+        #[arg(short, long, default_value = "1")]
+        count: usize,
+    },
+
+    /// Type the transcript held back because a password field had focus
+    Confirm {
+        /// Drop the held transcript instead
+        #[arg(long)]
+        discard: bool,
+    },
+
+    /// Pause all capture, or resume it
+    Privacy {
+        #[command(subcommand)]
+        action: PrivacyAction,
+    },
+
+    /// Show the daemon log
+    Logs {
+        /// Keep printing new lines as they are logged
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Lowest level shown: error, warn, info, debug, or trace
+        #[arg(long)]
+        level: Option<String>,
+
+        /// Lines shown from the end of the log
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+
+        /// Print the JSON records as stored
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Start the daemon at login (launchd, systemd, or Task Scheduler)
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+
     /// Internal overlay indicator process
     #[command(hide = true)]
     Indicator {
-        /// Indicator mode: recording or processing
+        /// Indicator mode: recording, processing or paused
         #[arg(long)]
         mode: String,
+
+        /// Overlay style (JSON)
+        #[arg(long)]
+        style: Option<String>,
+    },
+
+    /// Internal clipboard ring picker process
+    #[command(hide = true)]
+    Picker,
+
+    /// Internal low-confidence review process (transcript on stdin)
+    #[command(hide = true)]
+    Review {
+        /// Transcript confidence (0-1)
+        #[arg(long)]
+        confidence: f32,
     },
 }
 
+#[derive(Subcommand)]
+enum DoctorCheck {
+    /// Trace the sample rate from the device to the model backend
+    Audio,
+    /// Report the OS permissions hotkeys, injection and capture need
+    /// (with --fix, show the system prompts and open the settings)
+    Permissions,
+}
+
 #[derive(Subcommand)]
 enum ConfigAction {
     /// Show current configuration
@@ -113,9 +439,9 @@ enum ConfigAction {
     /// Initialize default configuration file
     Init,
 
-    /// Set a configuration value
+    /// Set a configuration value (e.g. `vad.threshold 0.02`)
     Set {
-        /// Configuration key
+        /// Dotted configuration key (e.g. hotkey.trigger)
         key: String,
         /// Configuration value
         value: String,
@@ -123,7 +449,7 @@ enum ConfigAction {
 
     /// Get a configuration value
     Get {
-        /// Configuration key
+        /// Dotted configuration key (e.g. vad.threshold)
         key: String,
     },
 }
@@ -132,6 +458,41 @@ enum ConfigAction {
 enum DeviceAction {
     /// List available audio devices
     List,
+
+    /// Capture from a device (name or number from `devices list`, or
+    /// "default"); saved to the config, and a running daemon switches now
+    Use {
+        /// Device name or number
+        device: String,
+    },
+
+    /// Record a sample and check levels, noise, clipping and echo
+    Test {
+        /// Device to test (name or number; defaults to the configured one)
+        device: Option<String>,
+
+        /// Recording duration in seconds
+        #[arg(short, long, default_value = "4")]
+        duration: u64,
+
+        /// Skip playing the recording back
+        #[arg(long)]
+        no_playback: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HotkeyAction {
+    /// List HID devices (foot pedals, macro pads) usable as triggers
+    Devices {
+        /// Listen to device N from the list and print the buttons pressed
+        #[arg(long)]
+        probe: Option<usize>,
+
+        /// How long to listen with --probe, in seconds
+        #[arg(short, long, default_value = "10")]
+        duration: u64,
+    },
 }
 
 #[derive(Subcommand)]
@@ -146,6 +507,92 @@ enum ModelAction {
     Download {
         /// Model ID (e.g., "whisper-tiny.en", "whisper-base.en")
         model_id: String,
+
+        /// Download into a new version, keeping the current one for rollback
+        #[arg(long)]
+        new_version: bool,
+
+        /// Download in the running daemon (resumable; follow with `onevox events`)
+        #[arg(long, conflicts_with = "new_version")]
+        background: bool,
+
+        /// Switch to the model once the background download completes
+        #[arg(long, requires = "background")]
+        activate: bool,
+
+        /// Retry an attempt after this many seconds without data (0 = wait
+        /// indefinitely)
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+
+        /// Cancel the model's background download and remove its partial files
+        #[arg(long, conflicts_with_all = ["new_version", "background", "timeout"])]
+        cancel: bool,
+    },
+
+    /// Pause a background download (resume by downloading again)
+    Pause {
+        /// Model ID
+        model_id: String,
+    },
+
+    /// Cancel a background download and remove its partial files
+    Cancel {
+        /// Model ID
+        model_id: String,
+    },
+
+    /// List downloaded versions of a model
+    Versions {
+        /// Model ID
+        model_id: String,
+    },
+
+    /// Switch a model back to a previously downloaded version
+    Rollback {
+        /// Model ID
+        model_id: String,
+
+        /// Version label to activate (defaults to the previous version)
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Re-hash a downloaded model's files and check their SHA-256 checksums
+    Verify {
+        /// Model ID
+        model_id: String,
+    },
+
+    /// Compare downloaded models on the same audio: speed (RTF, latency),
+    /// peak memory, and WER when references are available
+    Bench {
+        /// Audio file to benchmark on (defaults to the eval fixtures)
+        #[arg(short, long)]
+        audio: Option<std::path::PathBuf>,
+
+        /// Reference transcript for --audio (defaults to <audio>.txt if present)
+        #[arg(short, long, requires = "audio")]
+        reference: Option<std::path::PathBuf>,
+
+        /// Models to compare, comma-separated (defaults to all downloaded)
+        #[arg(short, long, value_delimiter = ',')]
+        models: Vec<String>,
+
+        /// Directory of <name>.wav files with <name>.txt references
+        #[arg(short, long, default_value = "tests/quality/fixtures")]
+        fixtures: std::path::PathBuf,
+
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Fetch the remote model registry (`model.registry_url`) into the cache
+    Refresh {
+        /// Registry URL to fetch instead of the configured one
+        #[arg(long)]
+        url: Option<String>,
     },
 
     /// Remove a downloaded model
@@ -165,11 +612,51 @@ enum ModelAction {
 enum HistoryAction {
     /// List all transcription history
     List {
-        /// Number of recent entries to show (0 = all)
+        /// Number of recent entries (or sessions) to show (0 = all)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Collapse each dictation session into one entry
+        #[arg(short, long)]
+        sessions: bool,
+    },
+
+    /// Search history (case-insensitive), best matches first
+    Search {
+        /// Text to find (a regular expression with --regex)
+        query: String,
+
+        /// Treat the query as a regular expression
+        #[arg(short, long)]
+        regex: bool,
+
+        /// Only entries from this date on (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only entries up to and including this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only entries transcribed by a model whose name contains this
+        #[arg(short, long)]
+        model: Option<String>,
+
+        /// Maximum number of matches (0 = all)
         #[arg(short, long, default_value = "20")]
         limit: usize,
     },
 
+    /// Play the recording of a history entry ([audio].save_recordings)
+    Play {
+        /// Entry ID
+        id: u64,
+
+        /// Write the audio to this WAV file instead of playing it
+        #[arg(short, long)]
+        export: Option<std::path::PathBuf>,
+    },
+
     /// Delete a specific history entry
     Delete {
         /// Entry ID to delete
@@ -188,22 +675,139 @@ enum HistoryAction {
         /// Output file path
         #[arg(short, long, default_value = "transcription-history.txt")]
         output: String,
+
+        /// Output format: text, srt, vtt, or json (default: from the
+        /// --output extension, else text)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Write one document per dictation session into the --output
+        /// directory
+        #[arg(long)]
+        per_session: bool,
+    },
+
+    /// List history backups
+    Backups,
+
+    /// Restore history from a backup
+    Restore {
+        /// Backup name, date (YYYY-MM-DD), or path
+        #[arg(long)]
+        from: String,
+
+        /// Show what would change without restoring
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrivacyAction {
+    /// Turn off all capture, ending any dictation in progress
+    Pause {
+        /// Resume by itself after this long (e.g. 90s, 30m, 2h; default:
+        /// until `onevox privacy resume`)
+        #[arg(long = "for", value_name = "DURATION")]
+        duration: Option<String>,
+    },
+
+    /// Turn capture back on
+    Resume,
+
+    /// Show whether capture is paused
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Register this binary to start the daemon at login, and start it
+    Install,
+
+    /// Stop the daemon and remove the login registration
+    Uninstall,
+
+    /// Show whether the daemon starts at login and is running
+    Status,
+}
+
+#[derive(Subcommand)]
+enum StorageAction {
+    /// Show disk usage of models, history, backups, audio, and logs
+    Status,
+
+    /// Enforce the configured storage caps now
+    Cleanup,
+}
+
+#[derive(Subcommand)]
+enum SecretAction {
+    /// Store a secret (prompts for the value when not given)
+    Set {
+        /// Secret name, referenced from config as keyring:<name>
+        name: String,
+
+        /// Secret value (prefer the prompt; arguments end up in shell history)
+        value: Option<String>,
+    },
+
+    /// Print a stored secret
+    Get {
+        /// Secret name
+        name: String,
+    },
+
+    /// Delete a stored secret
+    Remove {
+        /// Secret name
+        name: String,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
+fn main() -> Result<()> {
+    // A live upgrade passes the listening socket in the environment, which
+    // may only be changed before the runtime starts its worker threads
+    #[cfg(unix)]
+    onevox::daemon::upgrade::claim_inherited_listener();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run())
+}
+
+async fn run() -> Result<()> {
+    // Opened through the onevox:// URL scheme (macOS app bundle)
+    if onevox::platform::url_events::launched_from_bundle() {
+        onevox::logging::init_cli();
+        handle_scheme_urls().await;
+        return Ok(());
+    }
 
     let cli = Cli::parse();
+    if let Some(dir) = &cli.portable {
+        onevox::platform::paths::set_portable_dir(dir)?;
+    }
+    if cli.debug_inference {
+        onevox::models::inference_debug::enable();
+    }
     let command = cli.command.unwrap_or(Commands::Tui);
 
+    // The daemon process also logs to a file; flushed when dropped
+    let _log_guard = match &command {
+        Commands::Daemon {
+            foreground: true, ..
+        } => onevox::logging::init_daemon(&Config::load_default().unwrap_or_default().daemon),
+        _ => {
+            onevox::logging::init_cli();
+            None
+        }
+    };
+
     match command {
         Commands::Daemon { dev, foreground } => {
             tracing::info!("Starting onevox daemon...");
@@ -216,9 +820,24 @@ async fn main() -> Result<()> {
             }
 
             if !foreground {
+                if onevox::daemon::detach::is_running().await {
+                    println!("✅ Onevox daemon is already running");
+                    return Ok(());
+                }
                 println!("🎙️  Starting Onevox daemon in background...");
-                println!("    Use 'onevox status' to check status");
-                println!("    Use 'onevox stop' to stop the daemon");
+                match onevox::daemon::detach::spawn(dev).await {
+                    Ok(pid) => {
+                        println!("✅ Daemon running (pid {})", pid);
+                        println!("    Use 'onevox logs --follow' to watch its log");
+                        println!("    Use 'onevox status' to check status");
+                        println!("    Use 'onevox stop' to stop the daemon");
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                }
             }
 
             // Create and start daemon
@@ -242,15 +861,71 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Status => match onevox::Daemon::status().await {
-            Ok(status) => {
-                println!("📊 Onevox Daemon Status\n");
-                println!("  Version:     {}", status.version);
-                println!("  PID:         {}", status.pid);
-                println!("  State:       {}", status.state);
-                println!("  Uptime:      {}s", status.uptime_secs);
-                println!(
-                    "  Model:       {}",
+        Commands::SelfUpdate { live } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            let before = match client.get_status().await {
+                Ok(status) => status,
+                Err(e) => {
+                    eprintln!("❌ Daemon is not running: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            if !live {
+                client
+                    .shutdown()
+                    .await
+                    .map_err(|e| onevox::Error::Ipc(e.to_string()))?;
+                println!("✅ Stopped daemon v{} (pid {})", before.version, before.pid);
+                println!("💡 Start the new version with: onevox daemon");
+                println!("💡 Use --live to switch without interrupting clients");
+                return Ok(());
+            }
+
+            let binary = std::env::current_exe()?;
+            println!(
+                "🔁 Handing over daemon v{} (pid {})...",
+                before.version, before.pid
+            );
+            let version = match client.upgrade(&binary.to_string_lossy()).await {
+                Ok(version) => version,
+                Err(e) => {
+                    eprintln!("❌ Live upgrade refused: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            println!("   Draining in-flight work, then switching to {}", version);
+
+            // The process image is replaced in place; a restarted uptime
+            // means the new binary is serving
+            let started = std::time::Instant::now();
+            while started.elapsed() < std::time::Duration::from_secs(120) {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                if let Ok(status) = client.get_status().await
+                    && status.uptime_secs < before.uptime_secs + started.elapsed().as_secs()
+                {
+                    println!(
+                        "✅ Daemon now running v{} (pid {})",
+                        status.version, status.pid
+                    );
+                    return Ok(());
+                }
+            }
+            eprintln!("❌ Timed out waiting for the upgraded daemon; check: onevox status");
+            std::process::exit(1);
+        }
+
+        Commands::Status { verbose } => match onevox::Daemon::status().await {
+            Ok(status) => {
+                let degradation = status.degradation();
+                println!("📊 Onevox Daemon Status\n");
+                println!("  Version:     {}", status.version);
+                println!("  PID:         {}", status.pid);
+                println!("  State:       {}", status.state);
+                println!("  Uptime:      {}s", status.uptime_secs);
+                println!(
+                    "  Model:       {}",
                     status.model_name.unwrap_or_else(|| "None".to_string())
                 );
                 println!(
@@ -262,6 +937,106 @@ async fn main() -> Result<()> {
                     status.memory_usage_bytes / 1_000_000
                 );
                 println!("  CPU:         {:.1}%", status.cpu_usage_percent);
+                if status.privacy.paused {
+                    println!("  Privacy:     {}", privacy_pause_text(&status.privacy));
+                }
+
+                let caps = &status.capabilities;
+                let mark = |enabled: bool| if enabled { "✅" } else { "—" };
+                println!("\n🧩 Capabilities\n");
+                println!("  Audio capture:  {}", mark(caps.audio_capture));
+                println!("  Global hotkeys: {}", mark(caps.global_hotkeys));
+                println!("  Text injection: {}", mark(caps.text_injection));
+                println!("  Overlay:        {}", mark(caps.overlay_indicator));
+                println!("  Backends:       {}", caps.model_backends.join(", "));
+                if !caps.can_dictate() {
+                    println!("  (headless build: file/API transcription only)");
+                }
+
+                println!("\n🚦 Startup ({})\n", degradation);
+                for subsystem in &status.subsystems {
+                    println!(
+                        "  {:<8} {:<20} policy: {:<10} attempts: {}",
+                        subsystem.name,
+                        subsystem.health.to_string(),
+                        subsystem.policy,
+                        subsystem.attempts
+                    );
+                    if let Some(error) = &subsystem.error {
+                        println!("           last error: {}", error);
+                    }
+                }
+
+                let pipeline = &status.pipeline;
+                println!("\n⏱️  Pipeline\n");
+                match (pipeline.latency_avg_ms, pipeline.latency_p95_ms) {
+                    (Some(avg), Some(p95)) => println!(
+                        "  Latency:     {} ms avg, {} ms p95 (speech end → text, last {})",
+                        avg, p95, pipeline.latency_samples
+                    ),
+                    _ => println!("  Latency:     no dictations yet"),
+                }
+                println!("  Segments:    {}", pipeline.vad_segments);
+                println!("  Dropped:     {} audio chunks", pipeline.dropped_chunks);
+                if let Some(bytes) = pipeline.model_memory_bytes {
+                    println!("  Model RAM:   {} MB", bytes / 1_000_000);
+                }
+                for model in &pipeline.models {
+                    println!(
+                        "  {:<24} RTF {:.2}  ({} transcriptions, {:.1}s audio)",
+                        model.model,
+                        model.rtf(),
+                        model.transcriptions,
+                        model.audio_ms as f64 / 1000.0
+                    );
+                }
+
+                if verbose {
+                    let format_time = |ts: u64| {
+                        chrono::DateTime::from_timestamp(ts as i64, 0)
+                            .map(|dt| {
+                                dt.with_timezone(&chrono::Local)
+                                    .format("%H:%M:%S")
+                                    .to_string()
+                            })
+                            .unwrap_or_else(|| "-".to_string())
+                    };
+
+                    println!("\n🔌 Clients ({})\n", status.clients.len());
+                    if status.clients.is_empty() {
+                        println!("  No clients seen yet");
+                    }
+                    for client in &status.clients {
+                        println!(
+                            "  {:<16} peer {:<8} {:>5} requests  last: {} at {}",
+                            client.name,
+                            client.peer_id,
+                            client.request_count,
+                            client.last_command,
+                            format_time(client.last_seen)
+                        );
+                    }
+
+                    println!("\n📜 Recent requests\n");
+                    for entry in &status.recent_requests {
+                        println!(
+                            "  {} [{}] {:<16} {}{}",
+                            format_time(entry.timestamp),
+                            entry.request_id,
+                            entry.client_name,
+                            entry.command,
+                            if entry.is_error { " ❌" } else { "" }
+                        );
+                    }
+
+                    println!("\n📣 Events ({} subscribers)\n", status.event_subscribers);
+                    if status.event_counts.is_empty() {
+                        println!("  No events published yet");
+                    }
+                    for (name, count) in &status.event_counts {
+                        println!("  {:<24} {:>6}", name, count);
+                    }
+                }
                 Ok(())
             }
             Err(e) => {
@@ -271,6 +1046,35 @@ async fn main() -> Result<()> {
             }
         },
 
+        Commands::Events { names } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            let mut subscription = match client.subscribe(names).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    eprintln!("❌ Failed to subscribe to events: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            println!("📣 Listening for daemon events (Ctrl+C to stop)\n");
+            loop {
+                match subscription.next().await {
+                    Ok(Some(event)) => {
+                        println!("{} {}", chrono::Local::now().format("%H:%M:%S"), event)
+                    }
+                    Ok(None) => {
+                        println!("🔌 Daemon closed the event stream");
+                        break Ok(());
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Event stream error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+
         Commands::ReloadConfig => {
             println!("🔄 Reloading daemon configuration...");
             let mut client = onevox::ipc::IpcClient::default();
@@ -282,7 +1086,30 @@ async fn main() -> Result<()> {
                 }
                 Err(e) => {
                     eprintln!("❌ Failed to reload configuration: {}", e);
-                    eprintln!("💡 Is the daemon running? Try: onevox daemon --foreground");
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Query { expr, raw } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.query(&expr).await {
+                Ok(json) => {
+                    let value: serde_json::Value = serde_json::from_str(&json).map_err(|e| {
+                        onevox::Error::Other(format!("Invalid query result: {}", e))
+                    })?;
+                    match value {
+                        serde_json::Value::String(text) if raw => println!("{}", text),
+                        value => {
+                            println!("{}", serde_json::to_string_pretty(&value).unwrap_or(json))
+                        }
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("❌ Query failed: {}", e);
+                    print_ipc_hint(&e);
                     std::process::exit(1);
                 }
             }
@@ -316,19 +1143,345 @@ async fn main() -> Result<()> {
                 Ok(())
             }
             ConfigAction::Set { key, value } => {
-                println!("Setting {key} = {value}");
-                println!("⚠️  Not yet implemented - this is a placeholder");
+                let config_path = Config::default_path();
+                if let Err(e) = Config::set_key(&config_path, &key, &value) {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+                println!("✅ {} = {}", key, value);
+
+                // Apply to the running daemon, if any
+                let mut client = onevox::ipc::IpcClient::default();
+                match client.reload_config().await {
+                    Ok(_) => println!("🔄 Daemon reloaded the configuration"),
+                    Err(_) => println!("💡 Daemon not running; applies on next start"),
+                }
                 Ok(())
             }
             ConfigAction::Get { key } => {
-                println!("Getting {key}");
-                println!("⚠️  Not yet implemented - this is a placeholder");
+                let config = Config::load_default()?;
+                match config.get_key(&key) {
+                    Ok(toml::Value::String(text)) => println!("{}", text),
+                    Ok(toml::Value::Table(table)) => {
+                        print!("{}", toml::to_string_pretty(&table).unwrap_or_default())
+                    }
+                    Ok(value) => println!("{}", value),
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                }
                 Ok(())
             }
         },
 
+        Commands::Setup { no_playback } => {
+            println!("🛠️  onevox setup\n");
+
+            // Step 1: configuration
+            let config_path = Config::default_path();
+            if config_path.exists() {
+                println!("✅ Config found at {:?}", config_path);
+            } else {
+                Config::default().save_default()?;
+                println!("✅ Created default config at {:?}", config_path);
+            }
+            let config = Config::load_default()?;
+
+            // Step 2: microphone
+            println!("\n🎤 Microphone self-test");
+            let mic_ok = match run_mic_self_test(&config, 4, !no_playback).await {
+                Ok(ok) => ok,
+                Err(e) => {
+                    eprintln!("❌ Microphone test failed: {}", e);
+                    false
+                }
+            };
+
+            // Step 3: model
+            println!("\n🤖 Model");
+            let downloader = onevox::models::ModelDownloader::new()
+                .map_err(|e| onevox::Error::Other(e.to_string()))?;
+            let downloaded = downloader
+                .list_downloaded()
+                .await
+                .map_err(|e| onevox::Error::Other(e.to_string()))?;
+            let model_id = &config.model.model_path;
+            let model_ok = downloaded.iter().any(|id| id == model_id);
+            if model_ok {
+                println!("✅ Model {} is downloaded", model_id);
+            } else {
+                println!("⚠️  Model {} is not downloaded", model_id);
+                println!("💡 Download it with: onevox models download {}", model_id);
+            }
+
+            println!();
+            if mic_ok && model_ok {
+                println!("🎉 Setup complete. Start dictating with: onevox daemon");
+            } else {
+                println!(
+                    "⚠️  Setup finished with warnings; fix the items above and re-run: onevox setup"
+                );
+            }
+            Ok(())
+        }
+
+        Commands::Doctor {
+            check: Some(DoctorCheck::Audio),
+            fix,
+        } => {
+            use onevox::audio::sample_path::{SamplePath, Severity};
+
+            println!("🩺 onevox doctor audio\n");
+            let config = Config::load_default()?;
+            let path = match SamplePath::trace(&config) {
+                Ok(path) => path,
+                Err(e) => {
+                    eprintln!("❌ Failed to trace the audio path: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            println!("{}\n", path);
+
+            let findings = path.findings();
+            if findings.is_empty() {
+                println!("✅ Sample rates line up");
+                return Ok(());
+            }
+            let mut fixes: Vec<(&str, String)> = Vec::new();
+            for finding in &findings {
+                let icon = match finding.severity {
+                    Severity::Error => "❌",
+                    Severity::Warning => "⚠️ ",
+                };
+                println!("{} {}", icon, finding.message);
+                if let Some((key, value)) = &finding.fix {
+                    println!("   Fix: onevox config set {} {}", key, value);
+                    if !fixes.iter().any(|(k, _)| k == key) {
+                        fixes.push((key, value.clone()));
+                    }
+                }
+            }
+
+            let errors = findings
+                .iter()
+                .any(|finding| finding.severity == Severity::Error);
+            if !fix {
+                if !fixes.is_empty() {
+                    println!("\n💡 Apply the fixes with: onevox doctor audio --fix");
+                }
+                if errors {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            if fixes.is_empty() {
+                if errors {
+                    println!(
+                        "\n💡 No config fix available; pick another input with onevox config set audio.device <name>"
+                    );
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            println!();
+            let config_path = Config::default_path();
+            for (key, value) in &fixes {
+                if let Err(e) = Config::set_key(&config_path, key, value) {
+                    eprintln!("❌ Failed to set {}: {}", key, e);
+                    std::process::exit(1);
+                }
+                println!("✅ {} = {}", key, value);
+            }
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.reload_config().await {
+                Ok(_) => println!("🔄 Daemon reloaded the configuration"),
+                Err(_) => println!("💡 Daemon not running; applies on next start"),
+            }
+
+            let remaining = SamplePath::trace(&Config::load_default()?)
+                .map(|path| path.findings())
+                .unwrap_or_default();
+            if remaining
+                .iter()
+                .any(|finding| finding.severity == Severity::Error)
+            {
+                println!("⚠️  Problems remain; run onevox doctor audio again for details");
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Commands::Doctor {
+            check: Some(DoctorCheck::Permissions),
+            fix,
+        } => {
+            println!("🔐 Permissions\n");
+            if print_permission_report(fix) {
+                Ok(())
+            } else {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Doctor { check: None, fix } => {
+            use onevox::models::onnx_dylib::{self, RuntimeSource};
+
+            println!("🩺 onevox doctor\n");
+            println!("📦 Version: {}", env!("CARGO_PKG_VERSION"));
+            let backends: Vec<&str> = [
+                (cfg!(feature = "whisper-cpp"), "whisper-cpp"),
+                (cfg!(feature = "onnx"), "onnx"),
+                (cfg!(feature = "onnx-dynamic"), "onnx-dynamic"),
+                (cfg!(feature = "candle"), "candle"),
+            ]
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect();
+            println!("🧩 Backends: {}", backends.join(", "));
+
+            println!("\n🔐 Permissions");
+            print_permission_report(false);
+
+            println!("\n🧠 ONNX Runtime");
+            if !cfg!(feature = "onnx") {
+                println!("   Not compiled in (build with --features onnx)");
+                return Ok(());
+            }
+
+            let mut source = onnx_dylib::resolve();
+            if let RuntimeSource::Missing(reason) = &source {
+                println!("⚠️  Library {}", reason);
+                if !fix {
+                    std::process::exit(1);
+                }
+                println!(
+                    "⬇️  Downloading ONNX Runtime {} (pinned)...",
+                    onnx_dylib::ORT_VERSION
+                );
+                if let Err(e) = onnx_dylib::install().await {
+                    eprintln!("❌ Failed to install ONNX Runtime: {:#}", e);
+                    std::process::exit(1);
+                }
+                source = onnx_dylib::resolve();
+            }
+            println!("✅ Source: {}", source);
+
+            #[cfg(feature = "onnx")]
+            match onevox::models::onnx_runtime::runtime_info() {
+                Ok(info) => println!("✅ Loaded: {}", info.trim()),
+                Err(e) => {
+                    eprintln!("❌ Failed to load ONNX Runtime: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+
+        Commands::Calibrate {
+            silence,
+            speech,
+            dry_run,
+        } => {
+            use onevox::audio::{AudioEngine, CaptureConfig, selftest};
+            use onevox::vad::calibrate::Calibration;
+
+            let config = Config::load_default()?;
+            let capture = CaptureConfig {
+                device_name: config.audio.device.clone(),
+                sample_rate: config.audio.sample_rate,
+                chunk_duration_ms: config.audio.chunk_duration_ms,
+                ..CaptureConfig::default()
+            };
+            let mut engine = AudioEngine::new();
+
+            println!(
+                "🤫 Stay quiet for {} seconds so onevox can hear the room...",
+                silence
+            );
+            let quiet = selftest::record(
+                &mut engine,
+                capture.clone(),
+                std::time::Duration::from_secs(silence),
+            )
+            .await?;
+            println!(
+                "🎤 Now speak normally for {} seconds (read anything aloud)...",
+                speech
+            );
+            let spoken =
+                selftest::record(&mut engine, capture, std::time::Duration::from_secs(speech))
+                    .await?;
+            if quiet.is_empty() || spoken.is_empty() {
+                eprintln!("❌ No audio captured");
+                std::process::exit(1);
+            }
+
+            let calibration = Calibration::measure(
+                &quiet,
+                &spoken,
+                config.audio.sample_rate,
+                config.audio.chunk_duration_ms,
+            );
+            println!(
+                "\n{:<14} {:>8.4} ({:.0} dBFS)",
+                "Noise floor",
+                calibration.noise_floor,
+                selftest::dbfs(calibration.noise_floor)
+            );
+            println!(
+                "{:<14} {:>8.4} ({:.0} dBFS)",
+                "Speech level",
+                calibration.speech_level,
+                selftest::dbfs(calibration.speech_level)
+            );
+            println!(
+                "{:<14} {:>8.4} ({:.0} dBFS)",
+                "Threshold",
+                calibration.threshold,
+                selftest::dbfs(calibration.threshold)
+            );
+            if !calibration.is_usable() {
+                eprintln!(
+                    "\n❌ Speech was only {:.0} dB above the noise; speak closer to the microphone or reduce background noise, then try again",
+                    calibration.margin_db()
+                );
+                std::process::exit(1);
+            }
+
+            let threshold = calibration.vad_threshold(config.vad.adaptive);
+            if dry_run {
+                println!(
+                    "\n💡 Would set vad.threshold = {:.4} and vad.noise_floor = {:.4}",
+                    threshold, calibration.noise_floor
+                );
+                return Ok(());
+            }
+            let config_path = Config::default_path();
+            for (key, value) in [
+                ("vad.noise_floor", calibration.noise_floor),
+                ("vad.threshold", threshold),
+            ] {
+                Config::set_key(&config_path, key, &format!("{:.4}", value))?;
+                println!("✅ {} = {:.4}", key, value);
+            }
+            let mut client = onevox::ipc::IpcClient::default();
+            if client.reload_config().await.is_ok() {
+                println!("🔄 Daemon reloaded the configuration");
+            }
+            if !config.vad.track_noise_floor {
+                println!(
+                    "💡 For long sessions in changing noise: onevox config set vad.track_noise_floor true"
+                );
+            }
+            Ok(())
+        }
+
         Commands::Tui => onevox::tui::launch(),
 
+        Commands::Monitor => onevox::tui::run_monitor().await,
+
         Commands::Devices { action } => match action {
             DeviceAction::List => {
                 println!("🎤 Available audio input devices:\n");
@@ -342,6 +1495,15 @@ async fn main() -> Result<()> {
                                 println!("  {}. {}", i + 1, device);
                             }
                         }
+                        // Sources for `[audio].source = "system"`
+                        if let Ok(loopbacks) = audio_engine.list_loopback_devices()
+                            && !loopbacks.is_empty()
+                        {
+                            println!("\n🔊 System audio (loopback) sources:\n");
+                            for name in loopbacks {
+                                println!("  - {}", name);
+                            }
+                        }
                         Ok(())
                     }
                     Err(e) => {
@@ -350,6 +1512,121 @@ async fn main() -> Result<()> {
                     }
                 }
             }
+            DeviceAction::Use { device } => {
+                let name = match resolve_input_device(&device) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                let mut client = onevox::ipc::IpcClient::default().with_client_name("onevox-cli");
+                match client.set_device(&name).await {
+                    Ok(message) => println!("✅ {}", message),
+                    Err(onevox::ipc::IpcError::Daemon(e)) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                    Err(_) => {
+                        if let Err(e) =
+                            Config::set_key(&Config::default_path(), "audio.device", &name)
+                        {
+                            eprintln!("❌ {}", e);
+                            std::process::exit(1);
+                        }
+                        println!("✅ audio.device = {}", name);
+                        println!("💡 Daemon not running; applies on next start");
+                    }
+                }
+                Ok(())
+            }
+            DeviceAction::Test {
+                device,
+                duration,
+                no_playback,
+            } => {
+                let mut config = Config::load_default()?;
+                if let Some(device) = device {
+                    match resolve_input_device(&device) {
+                        Ok(name) => config.audio.device = name,
+                        Err(e) => {
+                            eprintln!("❌ {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                match run_mic_self_test(&config, duration, !no_playback).await {
+                    Ok(true) => Ok(()),
+                    Ok(false) => std::process::exit(1),
+                    Err(e) => {
+                        eprintln!("❌ Microphone test failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+
+        Commands::Hotkey { action } => match action {
+            HotkeyAction::Devices { probe, duration } => {
+                use onevox::platform::hid;
+
+                let devices = match hid::list_devices() {
+                    Ok(devices) => devices,
+                    Err(e) => {
+                        eprintln!("❌ Failed to list HID devices: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+
+                let Some(index) = probe else {
+                    println!("🦶 Input devices with buttons:\n");
+                    if devices.is_empty() {
+                        println!("  No devices found (is your user in the `input` group?)");
+                    }
+                    for (i, device) in devices.iter().enumerate() {
+                        println!("  {}. {} [{}]", i + 1, device.name, device.id());
+                        println!(
+                            "     {} ({} buttons)",
+                            device.path.display(),
+                            device.buttons
+                        );
+                    }
+                    println!("\n💡 Find a button's name with: onevox hotkey devices --probe <N>");
+                    return Ok(());
+                };
+
+                let Some(device) = index.checked_sub(1).and_then(|i| devices.get(i)) else {
+                    eprintln!("❌ No device #{} (see: onevox hotkey devices)", index);
+                    std::process::exit(1);
+                };
+                println!(
+                    "🦶 Press buttons on {} for {} seconds...\n",
+                    device.name, duration
+                );
+                let mut pressed = Vec::new();
+                let result = hid::probe(device, std::time::Duration::from_secs(duration), |code| {
+                    let name = hid::button_name(code);
+                    println!("  ▶ {} (code {})", name, code);
+                    if !pressed.contains(&name) {
+                        pressed.push(name);
+                    }
+                });
+                if let Err(e) = result {
+                    eprintln!("❌ Failed to read {}: {}", device.path.display(), e);
+                    std::process::exit(1);
+                }
+
+                if let Some(button) = pressed.first() {
+                    println!("\nAdd to config.toml:\n");
+                    println!("[[hotkey.hid]]");
+                    println!("device = \"{}\"", device.id());
+                    println!("button = \"{}\"", button);
+                    println!("action = \"dictate\"");
+                } else {
+                    println!("No buttons pressed");
+                }
+                Ok(())
+            }
         },
 
         Commands::Models { action } => match action {
@@ -408,8 +1685,49 @@ async fn main() -> Result<()> {
                 Ok(())
             }
 
-            ModelAction::Download { model_id } => {
-                use onevox::models::{ModelDownloader, ModelRegistry};
+            ModelAction::Download {
+                model_id,
+                cancel: true,
+                ..
+            } => stop_download(&model_id, true).await,
+
+            ModelAction::Download {
+                model_id,
+                background: true,
+                activate,
+                timeout,
+                ..
+            } => {
+                let mut client = onevox::ipc::IpcClient::default();
+                match client.download_model(&model_id, activate, timeout).await {
+                    Ok(message) => {
+                        println!("📥 {}", message);
+                        println!("💡 Follow progress with: onevox events DownloadProgress");
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to start download: {}", e);
+                        print_ipc_hint(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            ModelAction::Pause { model_id } => stop_download(&model_id, false).await,
+
+            ModelAction::Cancel { model_id } => stop_download(&model_id, true).await,
+
+            ModelAction::Download {
+                model_id,
+                new_version,
+                timeout,
+                ..
+            } => {
+                use onevox::models::{
+                    DEFAULT_STALL_TIMEOUT, DownloadCancelled, ModelDownloader, ModelRegistry,
+                    stall_timeout_from_secs,
+                };
+                use tokio_util::sync::CancellationToken;
 
                 println!("📥 Downloading model: {}\n", model_id);
 
@@ -418,12 +1736,19 @@ async fn main() -> Result<()> {
                     onevox::Error::Config(format!("Model not found: {}", model_id))
                 })?;
 
-                let downloader =
-                    ModelDownloader::new().map_err(|e| onevox::Error::Other(e.to_string()))?;
+                // Ctrl+C stops the download and removes its partial files
+                let cancel = CancellationToken::new();
+                let downloader = ModelDownloader::new()
+                    .map_err(|e| onevox::Error::Other(e.to_string()))?
+                    .with_cancel(cancel.clone())
+                    .with_stall_timeout(
+                        timeout.map_or(Some(DEFAULT_STALL_TIMEOUT), stall_timeout_from_secs),
+                    );
 
                 // Check if already downloaded
-                if downloader.is_downloaded(metadata).await {
+                if !new_version && downloader.is_downloaded(metadata).await {
                     println!("✅ Model already downloaded!");
+                    println!("💡 Use --new-version to fetch a fresh copy alongside this one");
                     println!("💡 Location: {:?}", downloader.model_dir(&model_id));
                     return Ok(());
                 }
@@ -436,13 +1761,55 @@ async fn main() -> Result<()> {
                 println!("Files: {} files", metadata.files.len());
                 println!();
 
-                // Download
-                let model_dir = downloader
-                    .download(metadata)
-                    .await
-                    .map_err(|e| onevox::Error::Other(e.to_string()))?;
-
-                println!("\n✅ Model downloaded successfully!");
+                // Check free space before starting a large download
+                {
+                    use onevox::storage::{SpaceCheck, StorageManager};
+
+                    let config = Config::load_default()?;
+                    let models_dir = onevox::platform::paths::models_dir()?;
+                    let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+                    match StorageManager::new(config.storage)
+                        .check_space(&models_dir, metadata.size_bytes)
+                    {
+                        SpaceCheck::Insufficient { free_bytes } => {
+                            eprintln!(
+                                "❌ Not enough disk space: {:.1} MB needed, {:.1} MB free",
+                                mb(metadata.size_bytes),
+                                mb(free_bytes)
+                            );
+                            eprintln!("💡 Free space with: onevox storage cleanup");
+                            std::process::exit(1);
+                        }
+                        SpaceCheck::Low { free_bytes } => {
+                            println!(
+                                "⚠️  Low disk space: only {:.1} MB will be left after this download",
+                                mb(free_bytes - metadata.size_bytes)
+                            );
+                            println!("💡 Check usage with: onevox storage status\n");
+                        }
+                        SpaceCheck::Ok | SpaceCheck::Unknown => {}
+                    }
+                }
+
+                // Download
+                tokio::spawn(async move {
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        cancel.cancel();
+                    }
+                });
+                let model_dir = match downloader.download_version(metadata, new_version).await {
+                    Ok(model_dir) => model_dir,
+                    Err(e) if e.is::<DownloadCancelled>() => {
+                        if let Err(e) = downloader.discard_partial(metadata).await {
+                            eprintln!("⚠️  Failed to remove partial files: {}", e);
+                        }
+                        eprintln!("\n🛑 Download cancelled, partial files removed");
+                        std::process::exit(130);
+                    }
+                    Err(e) => return Err(onevox::Error::Other(e.to_string())),
+                };
+
+                println!("\n✅ Model downloaded successfully!");
                 println!("📂 Location: {:?}", model_dir);
                 println!("💡 Update your config to use this model");
 
@@ -466,6 +1833,249 @@ async fn main() -> Result<()> {
                 Ok(())
             }
 
+            ModelAction::Versions { model_id } => {
+                use onevox::models::ModelDownloader;
+
+                let downloader =
+                    ModelDownloader::new().map_err(|e| onevox::Error::Other(e.to_string()))?;
+                let versions = downloader
+                    .list_versions(&model_id)
+                    .await
+                    .map_err(|e| onevox::Error::Other(e.to_string()))?;
+
+                if versions.is_empty() {
+                    if downloader.model_root(&model_id).exists() {
+                        println!("📦 {} uses the legacy single-version layout", model_id);
+                        println!("💡 It will be migrated on the next download or rollback");
+                    } else {
+                        println!("❌ Model not downloaded: {}", model_id);
+                    }
+                    return Ok(());
+                }
+
+                println!("📦 Versions of {}\n", model_id);
+                for version in versions {
+                    println!(
+                        "  {} {} ({:.1} MB)",
+                        if version.is_current { "→" } else { " " },
+                        version.label,
+                        version.size_bytes as f64 / 1024.0 / 1024.0
+                    );
+                }
+
+                Ok(())
+            }
+
+            ModelAction::Rollback { model_id, to } => {
+                use onevox::models::ModelDownloader;
+
+                let downloader =
+                    ModelDownloader::new().map_err(|e| onevox::Error::Other(e.to_string()))?;
+                match downloader.rollback(&model_id, to.as_deref()).await {
+                    Ok(label) => {
+                        println!("✅ {} rolled back to version {}", model_id, label);
+                        println!("💡 Restart the daemon to load it: onevox stop && onevox daemon");
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to roll back {}: {}", model_id, e);
+                        eprintln!("💡 List versions with: onevox models versions {}", model_id);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            ModelAction::Verify { model_id } => {
+                use onevox::models::{FileStatus, ModelDownloader, ModelRegistry};
+
+                let registry = ModelRegistry::new();
+                let metadata = registry.get_model(&model_id).ok_or_else(|| {
+                    onevox::Error::Config(format!("Model not found: {}", model_id))
+                })?;
+                let downloader =
+                    ModelDownloader::new().map_err(|e| onevox::Error::Other(e.to_string()))?;
+                if !downloader.is_downloaded(metadata).await {
+                    eprintln!("❌ {} is not downloaded", model_id);
+                    eprintln!("💡 Download with: onevox models download {}", model_id);
+                    std::process::exit(1);
+                }
+
+                println!("🔍 Verifying {}...\n", model_id);
+                let checks = downloader
+                    .verify(metadata)
+                    .await
+                    .map_err(|e| onevox::Error::Other(e.to_string()))?;
+                for check in &checks {
+                    let sha256 = check.sha256.as_deref().unwrap_or("-");
+                    match &check.status {
+                        FileStatus::Verified => {
+                            println!("  ✅ {} {} (registry checksum)", check.file, sha256)
+                        }
+                        FileStatus::MatchesRecorded => {
+                            println!("  ✅ {} {} (checksum from download)", check.file, sha256)
+                        }
+                        FileStatus::Unchecked => {
+                            println!("  ⚠️  {} {} (no checksum to compare)", check.file, sha256)
+                        }
+                        FileStatus::Mismatch { expected } => {
+                            println!("  ❌ {} {} (expected {})", check.file, sha256, expected)
+                        }
+                        FileStatus::Missing => println!("  ❌ {} missing", check.file),
+                    }
+                }
+
+                if checks.iter().all(|check| check.is_ok()) {
+                    println!("\n✅ {} is intact", model_id);
+                    Ok(())
+                } else {
+                    eprintln!("\n❌ {} is corrupted or incomplete", model_id);
+                    eprintln!(
+                        "💡 Re-download with: onevox models remove {0} && onevox models download {0}",
+                        model_id
+                    );
+                    std::process::exit(1);
+                }
+            }
+
+            ModelAction::Bench {
+                audio,
+                reference,
+                models,
+                fixtures,
+                json,
+            } => {
+                use onevox::eval::bench::{BenchInput, bench_model};
+
+                let inputs: Vec<BenchInput> = match audio {
+                    Some(audio) => {
+                        let reference_path = reference.or_else(|| {
+                            Some(audio.with_extension("txt")).filter(|path| path.exists())
+                        });
+                        let reference = match reference_path {
+                            Some(path) => Some(std::fs::read_to_string(path)?.trim().to_string()),
+                            None => None,
+                        };
+                        vec![BenchInput {
+                            name: audio
+                                .file_stem()
+                                .map(|s| s.to_string_lossy().into_owned())
+                                .unwrap_or_default(),
+                            audio,
+                            reference,
+                        }]
+                    }
+                    None => match onevox::eval::load_fixtures(&fixtures) {
+                        Ok(fixture_list) => fixture_list.into_iter().map(Into::into).collect(),
+                        Err(_) => Vec::new(),
+                    },
+                };
+                if inputs.is_empty() {
+                    eprintln!("❌ No audio to benchmark on in {}", fixtures.display());
+                    eprintln!("💡 Pass --audio <file>, or run tests/quality/generate_fixtures.sh");
+                    std::process::exit(1);
+                }
+
+                let models = if models.is_empty() {
+                    let downloader = onevox::models::ModelDownloader::new()
+                        .map_err(|e| onevox::Error::Other(e.to_string()))?;
+                    let mut downloaded = downloader
+                        .list_downloaded()
+                        .await
+                        .map_err(|e| onevox::Error::Other(e.to_string()))?;
+                    downloaded.sort();
+                    downloaded
+                } else {
+                    models
+                };
+                if models.is_empty() {
+                    eprintln!("❌ No downloaded models to benchmark");
+                    eprintln!("💡 Download one with: onevox models download whisper-base.en");
+                    std::process::exit(1);
+                }
+
+                let base = Config::load_default()?.model;
+                if !json {
+                    println!(
+                        "⏱️  Benchmarking {} models on {} files...\n",
+                        models.len(),
+                        inputs.len()
+                    );
+                }
+                let mut results = Vec::new();
+                for model in &models {
+                    let mut model_config = onevox::config::ModelConfig {
+                        model_path: model.clone(),
+                        backend: "auto".to_string(),
+                        ..base.clone()
+                    };
+                    model_config.ensemble.enabled = false;
+                    if !json {
+                        println!("   Running {}...", model);
+                    }
+                    results.push(bench_model(&model_config, &inputs));
+                }
+
+                if json {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&results)
+                            .map_err(|e| onevox::Error::Other(e.to_string()))?
+                    );
+                    return Ok(());
+                }
+
+                println!(
+                    "\n{:<24} {:<12} {:>6} {:>9} {:>9} {:>8} {:>7}",
+                    "Model", "Backend", "RTF", "Latency", "Load", "Memory", "WER"
+                );
+                for result in &results {
+                    if let Some(error) = &result.error {
+                        println!("{:<24} ❌ {}", result.model, error);
+                        continue;
+                    }
+                    let wer = result
+                        .counts
+                        .map(|counts| format!("{:.1}%", counts.wer() * 100.0))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!(
+                        "{:<24} {:<12} {:>6.2} {:>7}ms {:>7}ms {:>6}MB {:>7}",
+                        result.model,
+                        result.backend,
+                        result.rtf(),
+                        result.mean_latency_ms(inputs.len()),
+                        result.load_ms,
+                        result.peak_memory_bytes / 1024 / 1024,
+                        wer
+                    );
+                }
+                println!("\n💡 RTF is processing time per second of audio (lower is faster)");
+                Ok(())
+            }
+
+            ModelAction::Refresh { url } => {
+                use onevox::models::ModelRegistry;
+
+                let Some(url) = url.or(Config::load_default()?.model.registry_url) else {
+                    eprintln!("❌ No model registry configured");
+                    eprintln!("💡 Set model.registry_url in config.toml, or pass --url");
+                    std::process::exit(1);
+                };
+                match ModelRegistry::refresh_remote(&url).await {
+                    Ok(count) => {
+                        println!(
+                            "✅ Model registry refreshed ({} models from {})",
+                            count, url
+                        );
+                        println!("💡 See them with: onevox models list");
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to refresh model registry: {:#}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
             ModelAction::Info { model_id } => {
                 use onevox::models::{ModelDownloader, ModelRegistry};
 
@@ -510,7 +2120,7 @@ async fn main() -> Result<()> {
         },
 
         Commands::History { action } => match action {
-            HistoryAction::List { limit } => {
+            HistoryAction::List { limit, sessions } => {
                 let mut client = onevox::ipc::IpcClient::default();
 
                 match client.get_history().await {
@@ -524,6 +2134,39 @@ async fn main() -> Result<()> {
                         // Sort by timestamp, newest first
                         entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
 
+                        if sessions {
+                            let sessions = onevox::history::sessions(&entries);
+                            let to_show = if limit == 0 { sessions.len() } else { limit };
+                            println!(
+                                "📝 Transcription History ({} sessions, {} entries)\n",
+                                sessions.len(),
+                                entries.len()
+                            );
+                            for (i, session) in sessions.iter().take(to_show).enumerate() {
+                                // Newest first, so the session started at its last entry
+                                let first = &session[session.len() - 1];
+                                let datetime =
+                                    chrono::DateTime::from_timestamp(first.timestamp as i64, 0)
+                                        .unwrap_or(chrono::DateTime::UNIX_EPOCH);
+                                let text: Vec<&str> =
+                                    session.iter().rev().map(|e| e.text.trim()).collect();
+                                let words: usize = session.iter().map(|e| e.word_count).sum();
+
+                                println!("─────────────────────────────────────────");
+                                println!("#{} [IDs: {}–{}]", i + 1, first.id, session[0].id);
+                                println!("📅 {}", datetime.format("%Y-%m-%d %H:%M:%S"));
+                                println!("🤖 Model: {}", first.model);
+                                println!("🧩 {} segments, {} words", session.len(), words);
+                                println!("\n💬 \"{}\"", text.join(" "));
+                                println!();
+                            }
+                            if sessions.len() > to_show {
+                                println!("... and {} more sessions", sessions.len() - to_show);
+                                println!("💡 Use --limit 0 to show all sessions");
+                            }
+                            return Ok(());
+                        }
+
                         // Apply limit
                         let to_show = if limit == 0 || limit >= entries.len() {
                             entries.len()
@@ -546,10 +2189,37 @@ async fn main() -> Result<()> {
                             println!("#{} [ID: {}]", i + 1, entry.id);
                             println!("📅 {}", formatted_time);
                             println!("🤖 Model: {}", entry.model);
+                            if let Some(language) = &entry.language {
+                                println!("🌐 Language: {}", language);
+                            }
                             println!("⏱️  Duration: {}ms", entry.duration_ms);
                             if let Some(conf) = entry.confidence {
                                 println!("📊 Confidence: {:.1}%", conf * 100.0);
                             }
+                            if let (Some(start), Some(end)) =
+                                (entry.start_offset_ms, entry.end_offset_ms)
+                            {
+                                println!(
+                                    "🎞️  Offset: {:.1}s – {:.1}s",
+                                    start as f64 / 1000.0,
+                                    end as f64 / 1000.0
+                                );
+                            }
+                            if let Some(hint) = &entry.audio_hint {
+                                println!("🎚️  {}", hint);
+                            }
+                            if entry.recording.is_some() {
+                                println!("🔊 Recording: onevox history play {}", entry.id);
+                            }
+                            if entry.duplicate {
+                                println!("🔁 Repeat of the previous segment, not injected");
+                            }
+                            if let Some(reason) = &entry.not_injected {
+                                println!("🔒 Not injected: {}", reason);
+                            }
+                            if let Some(speaker) = entry.speaker {
+                                println!("🗣️  Speaker: {}", speaker);
+                            }
                             println!("\n💬 \"{}\"", entry.text);
                             println!();
                         }
@@ -563,10 +2233,128 @@ async fn main() -> Result<()> {
                     }
                     Err(e) => {
                         eprintln!("❌ Failed to get history: {}", e);
-                        eprintln!("💡 Is the daemon running? Try: onevox daemon --foreground");
+                        print_ipc_hint(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            HistoryAction::Search {
+                query,
+                regex,
+                since,
+                until,
+                model,
+                limit,
+            } => {
+                // Dates are local days; `until` includes its whole day
+                let day_start = |date: &str, days_after: i64| {
+                    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .map_err(|e| {
+                            onevox::Error::Config(format!("Invalid date '{}' ({})", date, e))
+                        })
+                        .map(|day| {
+                            (day + chrono::Duration::days(days_after))
+                                .and_hms_opt(0, 0, 0)
+                                .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+                                .map_or(0, |t| t.timestamp().max(0) as u64)
+                        })
+                };
+                let query = onevox::history::HistoryQuery {
+                    text: query,
+                    regex,
+                    since: since.as_deref().map(|d| day_start(d, 0)).transpose()?,
+                    until: until.as_deref().map(|d| day_start(d, 1)).transpose()?,
+                    model,
+                    limit,
+                };
+
+                let mut client = onevox::ipc::IpcClient::default();
+                match client.search_history(query.clone()).await {
+                    Ok(matches) if matches.is_empty() => {
+                        println!("🔍 No history entries match \"{}\"", query.text);
+                        Ok(())
+                    }
+                    Ok(matches) => {
+                        println!("🔍 {} matches for \"{}\"\n", matches.len(), query.text);
+                        for found in &matches {
+                            let entry = &found.entry;
+                            let time = chrono::DateTime::from_timestamp(entry.timestamp as i64, 0)
+                                .unwrap_or(chrono::DateTime::UNIX_EPOCH)
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M");
+
+                            // Bracket the matched parts
+                            let mut highlighted = String::with_capacity(entry.text.len());
+                            let mut last = 0;
+                            for &(start, end) in &found.ranges {
+                                highlighted.push_str(&entry.text[last..start]);
+                                highlighted.push_str(&format!("[{}]", &entry.text[start..end]));
+                                last = end;
+                            }
+                            highlighted.push_str(&entry.text[last..]);
+
+                            println!("#{} · {} · {}", entry.id, time, entry.model);
+                            println!("  {}\n", highlighted);
+                        }
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to search history: {}", e);
+                        print_ipc_hint(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            HistoryAction::Play { id, export } => {
+                let mut client = onevox::ipc::IpcClient::default();
+                let entries = match client.get_history().await {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        eprintln!("❌ Failed to get history: {}", e);
+                        print_ipc_hint(&e);
                         std::process::exit(1);
                     }
+                };
+                let Some(entry) = entries.into_iter().find(|e| e.id == id) else {
+                    eprintln!("❌ No history entry #{}", id);
+                    std::process::exit(1);
+                };
+                let Some(name) = &entry.recording else {
+                    eprintln!("❌ Entry #{} has no recording", id);
+                    println!("💡 Set save_recordings = true under [audio] to keep audio");
+                    std::process::exit(1);
+                };
+
+                let dir = onevox::history::recordings::default_dir()?;
+                let path = onevox::history::recordings::recording_path(&dir, name)
+                    .filter(|path| path.exists());
+                let Some(path) = path else {
+                    eprintln!("❌ The recording of entry #{} is no longer kept", id);
+                    std::process::exit(1);
+                };
+                let audio = onevox::audio::convert::decode_wav(&path)?;
+                let samples = onevox::history::recordings::clip(
+                    &audio.samples,
+                    audio.sample_rate,
+                    entry.start_offset_ms,
+                    entry.end_offset_ms,
+                );
+
+                if let Some(export) = export {
+                    onevox::audio::convert::save_wav(&export, samples, audio.sample_rate)?;
+                    println!("✅ Saved recording of #{} to {}", id, export.display());
+                } else {
+                    println!(
+                        "🔊 Playing #{} ({:.1}s): {}",
+                        id,
+                        samples.len() as f64 / audio.sample_rate.max(1) as f64,
+                        entry.text
+                    );
+                    onevox::audio::tones::play_samples_blocking(samples, audio.sample_rate)?;
                 }
+                Ok(())
             }
 
             HistoryAction::Delete { id } => {
@@ -619,9 +2407,143 @@ async fn main() -> Result<()> {
                 }
             }
 
-            HistoryAction::Export { output } => {
-                use std::fs::File;
-                use std::io::Write;
+            HistoryAction::Backups => {
+                let config = Config::load_default()?;
+                let backups = onevox::history::BackupManager::from_config(&config.backup)?;
+                let list = backups.list()?;
+
+                if list.is_empty() {
+                    println!("💾 No history backups yet");
+                    println!("💡 The daemon takes a backup once a day while running");
+                    return Ok(());
+                }
+
+                println!("💾 History backups in {}\n", backups.dir().display());
+                for backup in &list {
+                    let modified = chrono::DateTime::from_timestamp(backup.modified as i64, 0)
+                        .map(|dt| {
+                            dt.with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string()
+                        })
+                        .unwrap_or_else(|| "-".to_string());
+                    println!(
+                        "  {:<40} {}  {:>8} KB",
+                        backup.name,
+                        modified,
+                        backup.size_bytes.div_ceil(1024)
+                    );
+                }
+                println!("\n💡 Restore with: onevox history restore --from <name> --dry-run");
+                Ok(())
+            }
+
+            HistoryAction::Restore { from, dry_run, yes } => {
+                let config = Config::load_default()?;
+                let backups = onevox::history::BackupManager::from_config(&config.backup)?;
+                let path = match backups.resolve(&from) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        eprintln!("💡 List backups with: onevox history backups");
+                        std::process::exit(1);
+                    }
+                };
+                let backup = onevox::history::BackupManager::load(&path)?;
+
+                let mut client = onevox::ipc::IpcClient::default();
+                let current = match client.get_history().await {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        eprintln!("❌ Failed to get history: {}", e);
+                        print_ipc_hint(&e);
+                        std::process::exit(1);
+                    }
+                };
+
+                let diff = onevox::history::HistoryDiff::compute(&current, &backup);
+                let preview = |text: &str| {
+                    if text.chars().count() > 60 {
+                        format!("{}...", text.chars().take(60).collect::<String>())
+                    } else {
+                        text.to_string()
+                    }
+                };
+
+                println!("💾 Restore from {}\n", path.display());
+                println!("  {} entries unchanged", diff.unchanged);
+                println!("  {} entries restored", diff.restored.len());
+                for entry in diff.restored.iter().take(10) {
+                    println!("    + [{}] {}", entry.id, preview(&entry.text));
+                }
+                println!("  {} entries removed", diff.removed.len());
+                for entry in diff.removed.iter().take(10) {
+                    println!("    - [{}] {}", entry.id, preview(&entry.text));
+                }
+                for file in &diff.files {
+                    println!("  {} will be overwritten", file);
+                }
+
+                if dry_run {
+                    println!("\n(dry run, nothing changed)");
+                    return Ok(());
+                }
+
+                if diff.is_empty() && diff.files.is_empty() {
+                    println!("\n✅ History already matches this backup");
+                    return Ok(());
+                }
+
+                if !yes {
+                    print!("\nRestore this backup? (y/N): ");
+                    use std::io::{self, Write};
+                    if let Err(e) = io::stdout().flush() {
+                        eprintln!("Warning: Failed to flush stdout: {}", e);
+                    }
+
+                    let mut input = String::new();
+                    if let Err(e) = io::stdin().read_line(&mut input) {
+                        eprintln!("❌ Failed to read input: {}", e);
+                        std::process::exit(1);
+                    }
+
+                    if !input.trim().eq_ignore_ascii_case("y") {
+                        println!("Cancelled.");
+                        return Ok(());
+                    }
+                }
+
+                match client.restore_history(&path.to_string_lossy()).await {
+                    Ok(message) => {
+                        println!("✅ {}", message);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to restore history: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            HistoryAction::Export {
+                output,
+                format,
+                per_session,
+            } => {
+                use onevox::transcribe::OutputFormat;
+
+                let format = match format {
+                    Some(format) => OutputFormat::parse(&format),
+                    None => Ok(OutputFormat::from_path(std::path::Path::new(&output))
+                        .unwrap_or(OutputFormat::Text)),
+                };
+                let format = match format {
+                    Ok(format) => format,
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                };
 
                 let mut client = onevox::ipc::IpcClient::default();
 
@@ -635,56 +2557,773 @@ async fn main() -> Result<()> {
                         // Sort by timestamp
                         entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
 
-                        // Write to file
-                        let mut file = File::create(&output).map_err(|e| {
-                            onevox::Error::Other(format!("Failed to create file: {}", e))
-                        })?;
-
-                        writeln!(file, "Onevox Transcription History")
-                            .map_err(|e| onevox::Error::Other(format!("Failed to write: {}", e)))?;
-                        writeln!(
-                            file,
-                            "Generated: {}",
-                            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-                        )
-                        .map_err(|e| onevox::Error::Other(format!("Failed to write: {}", e)))?;
-                        writeln!(file, "Total entries: {}\n", entries.len())
-                            .map_err(|e| onevox::Error::Other(format!("Failed to write: {}", e)))?;
-                        writeln!(
-                            file,
-                            "============================================================\n"
-                        )
-                        .map_err(|e| onevox::Error::Other(format!("Failed to write: {}", e)))?;
-
-                        let entry_count = entries.len();
-                        for entry in entries {
-                            let datetime =
-                                chrono::DateTime::from_timestamp(entry.timestamp as i64, 0)
-                                    .or_else(|| chrono::DateTime::from_timestamp(0, 0))
-                                    .unwrap_or(chrono::DateTime::UNIX_EPOCH);
-                            let formatted_time = datetime.format("%Y-%m-%d %H:%M:%S");
+                        if per_session {
+                            let dir = std::path::Path::new(&output);
+                            std::fs::create_dir_all(dir)?;
+                            let sessions = onevox::history::sessions(&entries);
+                            for session in &sessions {
+                                let started = chrono::DateTime::from_timestamp(
+                                    session[0].timestamp as i64,
+                                    0,
+                                )
+                                .unwrap_or(chrono::DateTime::UNIX_EPOCH)
+                                .with_timezone(&chrono::Local);
+                                let path = dir.join(format!(
+                                    "session-{}-{}.{}",
+                                    started.format("%Y%m%d-%H%M%S"),
+                                    session[0].id,
+                                    format.extension()
+                                ));
+                                std::fs::write(
+                                    &path,
+                                    onevox::history::export_session(session, format),
+                                )
+                                .map_err(|e| {
+                                    onevox::Error::Other(format!(
+                                        "Failed to write {}: {}",
+                                        path.display(),
+                                        e
+                                    ))
+                                })?;
+                            }
+                            println!(
+                                "✅ Exported {} sessions ({} entries) to {}",
+                                sessions.len(),
+                                entries.len(),
+                                output
+                            );
+                            return Ok(());
+                        }
+
+                        std::fs::write(&output, onevox::history::export(&entries, format))
+                            .map_err(|e| {
+                                onevox::Error::Other(format!("Failed to write {}: {}", output, e))
+                            })?;
+
+                        println!("✅ Exported {} entries to {}", entries.len(), output);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to get history: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+
+        Commands::Transcribe {
+            file,
+            format,
+            output,
+            vad,
+            model,
+        } => {
+            use onevox::models::backend;
+            use onevox::transcribe::{self, OutputFormat};
+
+            let format = match format {
+                Some(format) => OutputFormat::parse(&format),
+                None => Ok(output
+                    .as_deref()
+                    .and_then(OutputFormat::from_path)
+                    .unwrap_or(OutputFormat::Text)),
+            };
+            let format = match format {
+                Ok(format) => format,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let config = Config::load_default()?;
+            let mut model_config = config.model.clone();
+            if let Some(model) = model {
+                model_config.model_path = model;
+            }
+
+            // Progress goes to stderr so stdout carries only the transcript
+            eprintln!("🤖 Loading {}...", model_config.model_path);
+            let mut runtime = match backend::load_runtime(&model_config) {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    eprintln!("❌ Failed to load model: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            runtime.set_vocabulary(&onevox::vocabulary::Vocabulary::from_config(
+                &config.vocabulary,
+            ));
+
+            let (samples, report) =
+                match onevox::audio::convert::load_audio(&file, runtime.sample_rate()) {
+                    Ok(audio) => audio,
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                };
+            eprintln!("📄 {} ({})", file.display(), report);
+
+            let mut vad_processor = if vad {
+                Some(onevox::vad::VadProcessor::new(
+                    config.vad.to_processor_config(),
+                    onevox::vad::create_detector(&config.vad).await,
+                ))
+            } else {
+                None
+            };
+
+            let mut transcription = match transcribe::transcribe_samples(
+                runtime.as_mut(),
+                &samples,
+                vad_processor.as_mut(),
+                config.audio.chunk_duration_ms,
+            ) {
+                Ok(transcription) => transcription,
+                Err(e) => {
+                    eprintln!("❌ Transcription failed: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            onevox::postprocess::PostProcessor::from_config(&config.post_processing)
+                .with_vocabulary(onevox::vocabulary::Vocabulary::from_config(
+                    &config.vocabulary,
+                ))
+                .process(&mut transcription);
+            eprintln!(
+                "⏱️  Transcribed in {:.1}s",
+                transcription.processing_time_ms as f64 / 1000.0
+            );
+
+            let duration_ms = (report.duration_secs * 1000.0) as u64;
+            let rendered = transcribe::render(&transcription, format, duration_ms);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)?;
+                    eprintln!("✅ Wrote {}", path.display());
+                }
+                None => print!("{}", rendered),
+            }
+            Ok(())
+        }
+
+        Commands::Listen {
+            app,
+            system,
+            meeting,
+            list_apps,
+            duration,
+            model,
+        } => {
+            use onevox::audio::{AppCapture, CaptureSource};
+            use onevox::models::backend;
+
+            if list_apps {
+                match onevox::audio::app_capture::list_apps() {
+                    Ok(streams) if streams.is_empty() => {
+                        println!("No applications are playing audio");
+                    }
+                    Ok(streams) => {
+                        println!("🔊 Applications playing audio\n");
+                        for stream in &streams {
+                            println!("  {}", stream);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+
+            let config = Config::load_default()?;
+            let mut model_config = config.model.clone();
+            if let Some(model) = model {
+                model_config.model_path = model;
+            }
+
+            // Progress goes to stderr so stdout carries only the transcript
+            eprintln!("🤖 Loading {}...", model_config.model_path);
+            let mut runtime = match backend::load_runtime(&model_config) {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    eprintln!("❌ Failed to load model: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            runtime.set_vocabulary(&onevox::vocabulary::Vocabulary::from_config(
+                &config.vocabulary,
+            ));
+            let sample_rate = runtime.sample_rate();
+            let chunk_duration_ms = config.audio.chunk_duration_ms;
+
+            if meeting {
+                return listen_to_meeting(&config, runtime.as_mut(), duration).await;
+            }
+
+            // Both stop capturing when dropped
+            let mut microphone = onevox::audio::AudioEngine::new();
+            let mut app_capture = None;
+            let started = match &app {
+                Some(app) => AppCapture::start(app, sample_rate, chunk_duration_ms).map(
+                    |(capture, chunk_rx)| {
+                        eprintln!("👂 Listening to {}", capture.stream());
+                        app_capture = Some(capture);
+                        chunk_rx
+                    },
+                ),
+                None => {
+                    let source = if system {
+                        CaptureSource::System
+                    } else {
+                        config.audio.capture_source()
+                    };
+                    // A configured device names a source of the configured kind
+                    let device_name = if source == config.audio.capture_source() {
+                        config.audio.device.clone()
+                    } else {
+                        "default".to_string()
+                    };
+                    microphone
+                        .start_capture(onevox::audio::CaptureConfig {
+                            source,
+                            device_name,
+                            sample_rate,
+                            chunk_duration_ms,
+                            buffer_capacity_secs: 2,
+                            agc: config.audio.agc.enabled.then(|| config.audio.agc.clone()),
+                        })
+                        .inspect(|_| match source {
+                            CaptureSource::Microphone => {
+                                eprintln!("👂 Listening to the microphone")
+                            }
+                            CaptureSource::System => eprintln!("👂 Listening to system audio"),
+                        })
+                }
+            };
+            let mut chunk_rx = match started {
+                Ok(chunk_rx) => chunk_rx,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            };
+            eprintln!("   Press Ctrl+C to stop\n");
+
+            transcribe_live(
+                &config,
+                runtime.as_mut(),
+                &mut chunk_rx,
+                duration,
+                |transcription| {
+                    println!(
+                        "[{}] {}",
+                        chrono::Local::now().format("%H:%M:%S"),
+                        transcription.text.trim()
+                    );
+                },
+            )
+            .await?;
+
+            drop(app_capture);
+            microphone.stop_capture()?;
+            eprintln!("\n✅ Stopped listening");
+            Ok(())
+        }
+
+        Commands::Dictate {
+            stdout,
+            json,
+            duration,
+            model,
+        } => {
+            use onevox::platform::{InjectionMethod, InjectorConfig, TextInjector};
+
+            let config = Config::load_default()?;
+            let mut model_config = config.model.clone();
+            if let Some(model) = model {
+                model_config.model_path = model;
+            }
+            let mut injector_config = InjectorConfig::from_config(&config.injection);
+            if stdout || json {
+                injector_config.method = InjectionMethod::Stdout;
+            }
+            let to_stdout = injector_config.method == InjectionMethod::Stdout;
+            let injector = TextInjector::new(injector_config);
+
+            // Progress goes to stderr so stdout carries only the transcript
+            eprintln!("🤖 Loading {}...", model_config.model_path);
+            let mut runtime = match onevox::models::backend::load_runtime(&model_config) {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    eprintln!("❌ Failed to load model: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            runtime.set_vocabulary(&onevox::vocabulary::Vocabulary::from_config(
+                &config.vocabulary,
+            ));
+
+            let mut microphone = onevox::audio::AudioEngine::new();
+            let mut chunk_rx = match microphone.start_capture(onevox::audio::CaptureConfig {
+                source: config.audio.capture_source(),
+                device_name: config.audio.device.clone(),
+                sample_rate: runtime.sample_rate(),
+                chunk_duration_ms: config.audio.chunk_duration_ms,
+                buffer_capacity_secs: 2,
+                agc: config.audio.agc.enabled.then(|| config.audio.agc.clone()),
+            }) {
+                Ok(chunk_rx) => chunk_rx,
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if to_stdout {
+                eprintln!("🎤 Dictating to stdout (Ctrl+C to stop)");
+            } else {
+                eprintln!("🎤 Dictating into the focused app (Ctrl+C to stop)");
+            }
+
+            transcribe_live(
+                &config,
+                runtime.as_mut(),
+                &mut chunk_rx,
+                duration,
+                |transcription| {
+                    let text = transcription.text.trim();
+                    if json {
+                        let line = serde_json::json!({
+                            "text": text,
+                            "timestamp": chrono::Local::now().to_rfc3339(),
+                            "language": transcription.language,
+                            "processing_ms": transcription.processing_time_ms,
+                        });
+                        println!("{}", line);
+                    } else if let Err(e) = injector.inject(text) {
+                        eprintln!("⚠️  Injection failed: {}", e);
+                    }
+                },
+            )
+            .await?;
+
+            microphone.stop_capture()?;
+            eprintln!("✅ Stopped dictating");
+            Ok(())
+        }
+
+        Commands::Eval {
+            fixtures,
+            model,
+            max_wer,
+            json,
+            verbose,
+        } => {
+            let fixture_list = onevox::eval::load_fixtures(&fixtures)?;
+            if fixture_list.is_empty() {
+                eprintln!("❌ No fixtures with audio in {}", fixtures.display());
+                eprintln!(
+                    "💡 Run tests/quality/generate_fixtures.sh or add <name>.wav + <name>.txt pairs"
+                );
+                std::process::exit(1);
+            }
+
+            let mut model_config = Config::load_default()?.model;
+            if let Some(model) = model {
+                model_config.model_path = model;
+            }
+
+            if !json {
+                println!(
+                    "🧪 Evaluating {} on {} fixtures...\n",
+                    model_config.model_path,
+                    fixture_list.len()
+                );
+            }
+            let mut runtime = onevox::models::backend::load_runtime(&model_config)?;
+            let report = onevox::eval::evaluate(runtime.as_mut(), &fixture_list)?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report)
+                        .map_err(|e| onevox::Error::Other(e.to_string()))?
+                );
+            } else {
+                println!(
+                    "{:<20} {:>7} {:>7} {:>9}",
+                    "Fixture", "WER", "CER", "Latency"
+                );
+                for result in &report.results {
+                    println!(
+                        "{:<20} {:>6.1}% {:>6.1}% {:>7}ms",
+                        result.name,
+                        result.counts.wer() * 100.0,
+                        result.counts.cer() * 100.0,
+                        result.latency_ms
+                    );
+                    if verbose {
+                        println!("  audio:    {}", result.conversion);
+                    }
+                    if result.counts.word_errors > 0 {
+                        println!("  expected: {}", result.reference);
+                        println!("  got:      {}", result.hypothesis);
+                    }
+                }
+                println!(
+                    "\n📊 Corpus WER {:.1}%, CER {:.1}% ({} words)",
+                    report.wer() * 100.0,
+                    report.cer() * 100.0,
+                    report.totals.reference_words
+                );
+            }
+
+            if let Some(max_wer) = max_wer
+                && report.wer() > max_wer
+            {
+                eprintln!(
+                    "❌ WER {:.1}% exceeds the {:.1}% threshold",
+                    report.wer() * 100.0,
+                    max_wer * 100.0
+                );
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Commands::Dashboard { weeks, plain } => {
+            use onevox::telemetry::TelemetryStore;
+
+            let config = Config::load_default()?;
+            let store = TelemetryStore::load(&TelemetryStore::default_path()?)?;
+
+            if store.days.is_empty() {
+                println!("📈 No telemetry recorded yet.");
+                if !config.telemetry.enabled {
+                    println!(
+                        "💡 Enable local analytics with [telemetry] enabled = true in config.toml"
+                    );
+                }
+                return Ok(());
+            }
+
+            let summaries = store.weekly(weeks.max(1));
+
+            #[cfg(feature = "tui")]
+            if !plain && std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+                return onevox::tui::run_dashboard(
+                    summaries,
+                    store.totals(),
+                    config.telemetry.enabled,
+                );
+            }
+            #[cfg(not(feature = "tui"))]
+            let _ = plain;
+
+            println!("📈 Dictation Dashboard (last {} weeks)\n", summaries.len());
+            println!(
+                "  {:<10}  {:>8}  {:>11}  {:>7}  {:>11}  {:>9}  {:>6}",
+                "Week of", "Sessions", "Transcripts", "Words", "Avg latency", "Median", "Errors"
+            );
+            for week in &summaries {
+                let stats = &week.stats;
+                let avg = stats
+                    .avg_latency_ms()
+                    .map(|ms| format!("{} ms", ms))
+                    .unwrap_or_else(|| "-".to_string());
+                let median = stats
+                    .median_latency_ms()
+                    .map(|ms| format!("≤{} ms", ms))
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "  {:<10}  {:>8}  {:>11}  {:>7}  {:>11}  {:>9}  {:>5.1}%",
+                    week.week_start.format("%Y-%m-%d"),
+                    stats.sessions,
+                    stats.transcriptions,
+                    stats.words,
+                    avg,
+                    median,
+                    stats.error_rate() * 100.0
+                );
+            }
+
+            // Trends, oldest to newest
+            let active = |week: &&onevox::telemetry::WeekSummary| week.stats.transcriptions > 0;
+            println!();
+            println!(
+                "  Latency  {}",
+                sparkline(
+                    &summaries
+                        .iter()
+                        .map(|w| w.stats.avg_latency_ms().map(|ms| ms as f64))
+                        .collect::<Vec<_>>()
+                )
+            );
+            println!(
+                "  Words    {}",
+                sparkline(
+                    &summaries
+                        .iter()
+                        .map(|w| Some(w.stats.words as f64))
+                        .collect::<Vec<_>>()
+                )
+            );
+            println!(
+                "  Errors   {}",
+                sparkline(
+                    &summaries
+                        .iter()
+                        .map(|w| active(&w).then(|| w.stats.error_rate()))
+                        .collect::<Vec<_>>()
+                )
+            );
+
+            // Compare the last two active weeks
+            let mut active_weeks = summaries.iter().filter(active).rev();
+            if let (Some(latest), Some(previous)) = (active_weeks.next(), active_weeks.next())
+                && let (Some(now), Some(before)) = (
+                    latest.stats.avg_latency_ms(),
+                    previous.stats.avg_latency_ms(),
+                )
+                && before > 0
+            {
+                let change = (now as f64 - before as f64) / before as f64 * 100.0;
+                println!(
+                    "\n  Latency {} {:.0}% vs week of {} ({} → {} ms)",
+                    if change <= 0.0 { "⬇️ " } else { "⬆️ " },
+                    change.abs(),
+                    previous.week_start.format("%Y-%m-%d"),
+                    before,
+                    now
+                );
+            }
+
+            let totals = store.totals();
+            if !totals.models.is_empty() {
+                let models: Vec<String> = totals
+                    .models
+                    .iter()
+                    .map(|(model, count)| format!("{} ({})", model, count))
+                    .collect();
+                println!("\n  Models: {}", models.join(", "));
+            }
+
+            if !config.telemetry.enabled {
+                println!("\n⚠️  Telemetry is disabled; no new data is being recorded");
+            }
+
+            Ok(())
+        }
+
+        Commands::Stats { days, csv } => {
+            // Whole local days, today included
+            let since = (days > 0).then(|| {
+                let today = chrono::Local::now().date_naive();
+                (today - chrono::Duration::days(days as i64 - 1))
+                    .and_hms_opt(0, 0, 0)
+                    .and_then(|start| start.and_local_timezone(chrono::Local).earliest())
+                    .map(|start| start.timestamp().max(0) as u64)
+                    .unwrap_or(0)
+            });
+
+            let mut client = onevox::ipc::IpcClient::default();
+            let stats = match client.get_stats(since).await {
+                Ok(stats) => stats,
+                Err(e) => {
+                    eprintln!("❌ Failed to get stats: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            if let Some(path) = &csv {
+                std::fs::write(path, stats.to_csv())?;
+                println!("💾 Wrote {} days to {}", stats.days.len(), path.display());
+            }
+            if stats.total.entries == 0 {
+                println!("📊 No dictation in history for this period");
+                return Ok(());
+            }
+
+            let total = &stats.total;
+            let period = if days > 0 {
+                format!("last {} days", days)
+            } else {
+                format!("since {}", total.start)
+            };
+            let wpm = |period: &onevox::history::stats::PeriodStats| {
+                period
+                    .words_per_minute()
+                    .map(|wpm| format!("{:.0}", wpm))
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            println!("📊 Dictation Stats ({})\n", period);
+            println!("  Words dictated:   {}", total.words);
+            println!("  Segments:         {}", total.entries);
+            println!(
+                "  Speaking time:    {}",
+                onevox::daemon::privacy::format_duration(total.speech_ms / 1000)
+            );
+            println!("  Words per minute: {}", wpm(total));
+            println!(
+                "  Per active day:   {:.0} words",
+                total.words as f64 / stats.days.len().max(1) as f64
+            );
+            if let Some((model, count)) = stats.most_used_model() {
+                println!("  Most used model:  {} ({} segments)", model, count);
+            }
+            println!(
+                "  Not typed:        {} ({:.1}%)",
+                total.errors,
+                total.error_rate() * 100.0
+            );
+
+            println!(
+                "\n  {:<10}  {:>8}  {:>7}  {:>9}  {:>5}  {:>6}",
+                "Week of", "Segments", "Words", "Speaking", "WPM", "Errors"
+            );
+            for week in &stats.weeks {
+                println!(
+                    "  {:<10}  {:>8}  {:>7}  {:>9}  {:>5}  {:>5.1}%",
+                    week.start,
+                    week.entries,
+                    week.words,
+                    onevox::daemon::privacy::format_duration(week.speech_ms / 1000),
+                    wpm(week),
+                    week.error_rate() * 100.0
+                );
+            }
+            println!(
+                "\n  Words per day  {}",
+                sparkline(
+                    &stats
+                        .days
+                        .iter()
+                        .map(|day| Some(day.words as f64))
+                        .collect::<Vec<_>>()
+                )
+            );
+
+            Ok(())
+        }
+
+        Commands::Storage { action } => {
+            use onevox::storage::StorageManager;
+
+            let config = Config::load_default()?;
+            let storage = StorageManager::new(config.storage);
+            let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+
+            match action {
+                StorageAction::Status => {
+                    let report = storage.status()?;
+
+                    println!("💾 Storage Usage\n");
+                    for usage in &report.categories {
+                        let cap = match usage.cap_bytes {
+                            Some(cap) => format!("{:.1} MB cap", mb(cap)),
+                            None => "no cap".to_string(),
+                        };
+                        let marker = if usage.over_cap() { "⚠️ " } else { "  " };
+                        println!(
+                            "{}{:<8} {:>10.1} MB  {:>6} files  ({})",
+                            marker,
+                            usage.category.name(),
+                            mb(usage.size_bytes),
+                            usage.files,
+                            cap
+                        );
+                        println!("           {}", usage.path.display());
+                    }
+
+                    println!("\n  Total    {:>10.1} MB", mb(report.total_bytes()));
+                    if let Some(free) = report.free_bytes {
+                        println!("  Free     {:>10.1} MB", mb(free));
+                    }
+
+                    if report.categories.iter().any(|usage| usage.over_cap()) {
+                        println!("\n💡 Run 'onevox storage cleanup' to enforce the caps now");
+                    }
+                }
+                StorageAction::Cleanup => {
+                    let results = storage.enforce_caps()?;
+
+                    let mut removed = 0;
+                    for (category, result) in &results {
+                        if result.removed > 0 {
+                            println!(
+                                "🧹 {}: removed {} items ({:.1} MB)",
+                                category.name(),
+                                result.removed,
+                                mb(result.freed_bytes)
+                            );
+                        }
+                        removed += result.removed;
+                    }
+
+                    if removed == 0 {
+                        println!("✅ Everything is within the storage caps");
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        Commands::Secret { action } => {
+            use onevox::secrets;
 
-                            writeln!(
-                                file,
-                                "[{}] ({}ms) {}",
-                                formatted_time, entry.duration_ms, entry.model
-                            )
-                            .map_err(|e| onevox::Error::Other(format!("Failed to write: {}", e)))?;
-                            writeln!(file, "{}\n", entry.text).map_err(|e| {
-                                onevox::Error::Other(format!("Failed to write: {}", e))
-                            })?;
+            match action {
+                SecretAction::Set { name, value } => {
+                    let value = match value {
+                        Some(value) => value,
+                        None => {
+                            eprint!("Value for '{}': ", name);
+                            let mut line = String::new();
+                            std::io::stdin().read_line(&mut line)?;
+                            line.trim_end_matches(['\r', '\n']).to_string()
                         }
+                    };
+                    if value.is_empty() {
+                        eprintln!("❌ Secret value is empty");
+                        std::process::exit(1);
+                    }
 
-                        println!("✅ Exported {} entries to {}", entry_count, output);
-                        Ok(())
+                    if let Err(e) = secrets::set(&name, &value) {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                    println!("🔐 Stored '{}' in the keychain", name);
+                    println!("💡 Reference it from config as \"keyring:{}\"", name);
+                }
+                SecretAction::Get { name } => match secrets::get(&name) {
+                    Ok(Some(value)) => println!("{}", value),
+                    Ok(None) => {
+                        eprintln!("❌ Secret '{}' not found", name);
+                        std::process::exit(1);
                     }
                     Err(e) => {
-                        eprintln!("❌ Failed to get history: {}", e);
+                        eprintln!("❌ {}", e);
                         std::process::exit(1);
                     }
-                }
+                },
+                SecretAction::Remove { name } => match secrets::remove(&name) {
+                    Ok(true) => println!("🗑️  Removed '{}'", name),
+                    Ok(false) => {
+                        eprintln!("❌ Secret '{}' not found", name);
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        eprintln!("❌ {}", e);
+                        std::process::exit(1);
+                    }
+                },
             }
-        },
+
+            Ok(())
+        }
 
         Commands::TestAudio { duration } => {
             println!("🎤 Testing audio capture for {} seconds...", duration);
@@ -743,16 +3382,24 @@ async fn main() -> Result<()> {
             let mut chunk_rx = engine.start_capture(audio_config)?;
 
             // Create VAD processor
-            let energy_config = config.vad.to_energy_vad_config();
             let processor_config = config.vad.to_processor_config();
-            let detector = Box::new(onevox::vad::EnergyVad::new(energy_config));
+            let detector = onevox::vad::create_detector(&config.vad).await;
             let mut vad_processor = onevox::vad::VadProcessor::new(processor_config, detector);
 
             println!("VAD Configuration:");
             println!("  Detector: {}", vad_processor.detector_name());
             println!("  Threshold: {}", config.vad.threshold);
             println!("  Pre-roll: {}ms", config.vad.pre_roll_ms);
-            println!("  Post-roll: {}ms", config.vad.post_roll_ms);
+            if config.vad.adaptive_post_roll {
+                println!(
+                    "  Post-roll: adaptive {}-{}ms (starting at {}ms)",
+                    config.vad.min_post_roll_ms,
+                    config.vad.max_post_roll_ms,
+                    config.vad.post_roll_ms
+                );
+            } else {
+                println!("  Post-roll: {}ms", config.vad.post_roll_ms);
+            }
             println!("  Adaptive: {}\n", config.vad.adaptive);
 
             let start = std::time::Instant::now();
@@ -796,6 +3443,94 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
+        Commands::TestDenoise {
+            duration,
+            file,
+            transcribe,
+            no_playback,
+        } => {
+            use onevox::audio::{AudioEngine, CaptureConfig, denoise, selftest, tones};
+
+            let config = Config::load_default()?;
+            let sample_rate = config.audio.sample_rate;
+            let original = match file {
+                Some(path) => onevox::audio::convert::load_audio(&path, sample_rate)?.0,
+                None => {
+                    println!(
+                        "🎤 Recording {} seconds: stay quiet for a moment, then speak with your usual background noise",
+                        duration
+                    );
+                    let capture = CaptureConfig {
+                        device_name: config.audio.device.clone(),
+                        sample_rate,
+                        chunk_duration_ms: config.audio.chunk_duration_ms,
+                        ..CaptureConfig::default()
+                    };
+                    let mut engine = AudioEngine::new();
+                    selftest::record(
+                        &mut engine,
+                        capture,
+                        std::time::Duration::from_secs(duration),
+                    )
+                    .await?
+                }
+            };
+            if original.is_empty() {
+                eprintln!("❌ No audio captured");
+                std::process::exit(1);
+            }
+            let denoised = denoise::denoise(&original, sample_rate);
+
+            let before = selftest::analyze(&original, sample_rate);
+            let after = selftest::analyze(&denoised, sample_rate);
+            println!("\n{:<14} {:>10} {:>10}", "", "Original", "Denoised");
+            println!(
+                "{:<14} {:>6.0} dBFS {:>6.0} dBFS",
+                "Speech level", before.speech_dbfs, after.speech_dbfs
+            );
+            println!(
+                "{:<14} {:>6.0} dBFS {:>6.0} dBFS",
+                "Noise floor", before.noise_dbfs, after.noise_dbfs
+            );
+            println!(
+                "{:<14} {:>8.0} dB {:>8.0} dB",
+                "SNR", before.snr_db, after.snr_db
+            );
+
+            if transcribe {
+                let mut model = onevox::models::backend::load_runtime(&config.model)?;
+                let model_rate = model.sample_rate();
+                for (label, samples) in [("Original", &original), ("Denoised", &denoised)] {
+                    let samples =
+                        onevox::audio::convert::resample(samples, sample_rate, model_rate)?;
+                    let text = model.transcribe(&samples, model_rate)?.text;
+                    println!("\n{}: {}", label, text.trim());
+                }
+            }
+
+            if !no_playback {
+                for (label, samples) in [("original", original), ("denoised", denoised)] {
+                    println!("\n🔊 Playing the {} audio...", label);
+                    let played = tokio::task::spawn_blocking(move || {
+                        tones::play_samples_blocking(&samples, sample_rate)
+                    })
+                    .await
+                    .map_err(|e| onevox::Error::Other(e.to_string()))?;
+                    if let Err(e) = played {
+                        println!("⚠️  Playback unavailable: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            if after.snr_db - before.snr_db >= 3.0 {
+                println!("\n💡 Noise suppression helps here: onevox config set audio.denoise true");
+            } else {
+                println!("\n💡 Little difference here; audio.denoise can stay off");
+            }
+            Ok(())
+        }
+
         Commands::TestTranscribe { duration } => {
             println!(
                 "🎤 Testing full transcription pipeline for {} seconds...",
@@ -821,16 +3556,24 @@ async fn main() -> Result<()> {
             let mut chunk_rx = engine.start_capture(audio_config)?;
 
             // Create VAD processor
-            let energy_config = config.vad.to_energy_vad_config();
             let processor_config = config.vad.to_processor_config();
-            let detector = Box::new(onevox::vad::EnergyVad::new(energy_config));
+            let detector = onevox::vad::create_detector(&config.vad).await;
             let mut vad_processor = onevox::vad::VadProcessor::new(processor_config, detector);
 
             println!("VAD Configuration:");
             println!("  Detector: {}", vad_processor.detector_name());
             println!("  Threshold: {}", config.vad.threshold);
             println!("  Pre-roll: {}ms", config.vad.pre_roll_ms);
-            println!("  Post-roll: {}ms\n", config.vad.post_roll_ms);
+            if config.vad.adaptive_post_roll {
+                println!(
+                    "  Post-roll: adaptive {}-{}ms (starting at {}ms)\n",
+                    config.vad.min_post_roll_ms,
+                    config.vad.max_post_roll_ms,
+                    config.vad.post_roll_ms
+                );
+            } else {
+                println!("  Post-roll: {}ms\n", config.vad.post_roll_ms);
+            }
 
             let start = std::time::Instant::now();
             let mut transcription_count = 0;
@@ -893,7 +3636,7 @@ async fn main() -> Result<()> {
                 }
                 Err(e) => {
                     eprintln!("❌ Failed to start dictation: {}", e);
-                    eprintln!("💡 Is the daemon running? Try: onevox daemon --foreground");
+                    print_ipc_hint(&e);
                     std::process::exit(1);
                 }
             }
@@ -909,22 +3652,332 @@ async fn main() -> Result<()> {
                 }
                 Err(e) => {
                     eprintln!("❌ Failed to stop dictation: {}", e);
-                    eprintln!("💡 Is the daemon running? Try: onevox daemon --foreground");
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::PauseDictation => {
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.pause_dictation().await {
+                Ok(_) => {
+                    println!("⏸️  Dictation paused");
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to pause dictation: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::ResumeDictation => {
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.resume_dictation().await {
+                Ok(_) => {
+                    println!("▶️  Dictation resumed");
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to resume dictation: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::OpenUrl { url } => {
+            let action = onevox::automation::Action::from_url(&url)?;
+            match action.run().await {
+                Ok(Some(text)) => {
+                    println!("{}", text);
+                    Ok(())
+                }
+                Ok(None) => Ok(()),
+                Err(e) => {
+                    eprintln!("❌ {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Last { json, audio } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            let last = match client.get_last_transcript().await {
+                Ok(Some(last)) => last,
+                Ok(None) => {
+                    eprintln!("❌ No transcript yet since the daemon started");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to get the last transcript: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&last).map_err(|e| onevox::Error::Other(
+                        format!("Failed to encode transcript: {}", e)
+                    ))?
+                );
+            } else if audio {
+                match &last.audio_path {
+                    Some(path) => println!("{}", path),
+                    None => {
+                        eprintln!("❌ No audio saved for the last transcript");
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                println!("{}", last.text);
+            }
+            Ok(())
+        }
+
+        Commands::Undo { count } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.undo(count).await {
+                Ok(message) => {
+                    println!("↩️  {}", message);
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to undo: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Confirm { discard } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            match client.confirm_injection(discard).await {
+                Ok(message) => {
+                    println!("🔓 {}", message);
+                    Ok(())
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to confirm: {}", e);
+                    print_ipc_hint(&e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Privacy { action } => {
+            let mut client = onevox::ipc::IpcClient::default();
+            let result = match action {
+                PrivacyAction::Pause { duration } => {
+                    let duration = match duration
+                        .as_deref()
+                        .map(onevox::daemon::privacy::parse_duration)
+                        .transpose()
+                    {
+                        Ok(duration) => duration,
+                        Err(e) => {
+                            eprintln!("❌ {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    client
+                        .privacy_pause(duration.map(|duration| duration.as_secs()))
+                        .await
+                        .map(|message| println!("🔒 {}", message))
+                }
+                PrivacyAction::Resume => client
+                    .privacy_resume()
+                    .await
+                    .map(|message| println!("🎤 {}", message)),
+                PrivacyAction::Status => client.get_status().await.map(|status| {
+                    if status.privacy.paused {
+                        println!("🔒 {}", privacy_pause_text(&status.privacy));
+                    } else {
+                        println!("🎤 Capture is on");
+                    }
+                    let blocked = Config::load_default()
+                        .map(|config| config.privacy.blocked_apps)
+                        .unwrap_or_default();
+                    if !blocked.is_empty() {
+                        println!("   Blocked apps: {}", blocked.join(", "));
+                    }
+                }),
+            };
+            if let Err(e) = result {
+                eprintln!("❌ Privacy command failed: {}", e);
+                print_ipc_hint(&e);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        Commands::Logs {
+            follow,
+            level,
+            lines,
+            json,
+        } => {
+            use onevox::logging::{self, LogRecord};
+
+            let min_level = match level.as_deref().map(str::parse::<tracing::Level>) {
+                None => None,
+                Some(Ok(level)) => Some(level),
+                Some(Err(_)) => {
+                    eprintln!("❌ Unknown level (expected error, warn, info, debug, or trace)");
                     std::process::exit(1);
                 }
+            };
+            let Some(mut path) = logging::latest_log_file() else {
+                let dir = onevox::platform::paths::log_dir()?;
+                eprintln!("❌ No daemon log in {} yet", dir.display());
+                eprintln!("💡 The daemon writes one once started: onevox daemon");
+                std::process::exit(1);
+            };
+            let show = |line: &str| -> Option<String> {
+                match LogRecord::parse(line) {
+                    Some(record) => {
+                        if let (Some(min), Some(level)) = (min_level, record.level())
+                            && !logging::shown_at(level, min)
+                        {
+                            return None;
+                        }
+                        Some(if json {
+                            line.to_string()
+                        } else {
+                            record.to_text()
+                        })
+                    }
+                    None => {
+                        (min_level.is_none() && !line.trim().is_empty()).then(|| line.to_string())
+                    }
+                }
+            };
+
+            let (all, mut offset) = logging::read_new_lines(&path, 0)?;
+            let shown: Vec<String> = all.iter().filter_map(|line| show(line)).collect();
+            for line in &shown[shown.len().saturating_sub(lines)..] {
+                println!("{}", line);
+            }
+
+            if !follow {
+                return Ok(());
+            }
+            loop {
+                tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                // A new day starts a new file
+                if let Some(latest) = logging::latest_log_file()
+                    && latest != path
+                {
+                    path = latest;
+                    offset = 0;
+                }
+                let Ok((new, next)) = logging::read_new_lines(&path, offset) else {
+                    continue;
+                };
+                offset = next;
+                for line in new.iter().filter_map(|line| show(line)) {
+                    println!("{}", line);
+                }
+            }
+        }
+
+        Commands::Service { action } => {
+            use onevox::daemon::detach;
+            use onevox::platform::service;
+
+            match action {
+                ServiceAction::Install => {
+                    let binary = std::env::current_exe()?;
+                    if detach::is_running().await {
+                        println!("🛑 Stopping the running daemon so the service can take over...");
+                        let _ = onevox::Daemon::stop().await;
+                        for _ in 0..50 {
+                            if !detach::is_running().await {
+                                break;
+                            }
+                            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                        }
+                    }
+                    match service::install(&binary) {
+                        Ok(status) => {
+                            println!(
+                                "✅ Installed {} service: {}",
+                                status.manager, status.location
+                            );
+                            println!("   Starts {} at login", binary.display());
+                            println!("   Use 'onevox service uninstall' to remove it");
+                        }
+                        Err(e) => {
+                            eprintln!("❌ Failed to install service: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                ServiceAction::Uninstall => {
+                    match service::uninstall() {
+                        Ok(true) => println!("✅ Removed the login service"),
+                        Ok(false) => println!("ℹ️  No login service installed"),
+                        Err(e) => {
+                            eprintln!("❌ Failed to uninstall service: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    // Started by hand or by a task that only launches it
+                    if detach::is_running().await {
+                        let _ = onevox::Daemon::stop().await;
+                    }
+                }
+                ServiceAction::Status => {
+                    let status = match service::status() {
+                        Ok(status) => status,
+                        Err(e) => {
+                            eprintln!("❌ {}", e);
+                            std::process::exit(1);
+                        }
+                    };
+                    if status.installed {
+                        println!(
+                            "✅ Starts at login ({}: {})",
+                            status.manager, status.location
+                        );
+                    } else {
+                        println!("⭕ Not installed (run 'onevox service install')");
+                    }
+                    if detach::is_running().await {
+                        println!("   Daemon: running");
+                    } else {
+                        println!("   Daemon: not running");
+                    }
+                }
             }
+            Ok(())
         }
 
-        Commands::Indicator { mode } => {
+        Commands::Indicator { mode, style } => {
             let parsed = onevox::indicator::IndicatorMode::from_cli(&mode).ok_or_else(|| {
                 onevox::Error::Config(format!(
-                    "Invalid indicator mode '{}', expected 'recording' or 'processing'",
+                    "Invalid indicator mode '{}', expected 'recording', 'processing' or 'paused'",
                     mode
                 ))
             })?;
-            onevox::indicator::run_indicator(parsed)
+            let style = match style {
+                Some(style) => serde_json::from_str(&style).map_err(|e| {
+                    onevox::Error::Config(format!("Invalid indicator style: {}", e))
+                })?,
+                None => onevox::indicator::OverlayStyle::default(),
+            };
+            onevox::indicator::run_indicator(parsed, style)
         }
 
+        Commands::Picker => onevox::indicator::run_picker(),
+
+        Commands::Review { confidence } => onevox::indicator::run_review(confidence),
+
         Commands::TestHotkey { hotkey } => {
             println!("🎹 Testing hotkey detection...");
             println!("Hotkey: {}", hotkey);
@@ -967,6 +4020,30 @@ async fn main() -> Result<()> {
                         onevox::platform::HotkeyEvent::Released => {
                             println!("🔴 RELEASED - Hotkey released! (event #{})", event_count);
                         }
+                        onevox::platform::HotkeyEvent::Undo => {
+                            println!(
+                                "↩️  UNDO     - Undo hotkey pressed (event #{})",
+                                event_count
+                            );
+                        }
+                        onevox::platform::HotkeyEvent::Picker => {
+                            println!(
+                                "📋 PICKER   - Picker hotkey pressed (event #{})",
+                                event_count
+                            );
+                        }
+                        onevox::platform::HotkeyEvent::Pause => {
+                            println!(
+                                "⏸️  PAUSE    - Pause hotkey pressed (event #{})",
+                                event_count
+                            );
+                        }
+                        onevox::platform::HotkeyEvent::ActionPressed(action) => {
+                            println!("🟢 PRESSED  - {} hotkey (event #{})", action, event_count);
+                        }
+                        onevox::platform::HotkeyEvent::ActionReleased(action) => {
+                            println!("🔴 RELEASED - {} hotkey (event #{})", action, event_count);
+                        }
                     }
                 }
 
@@ -985,3 +4062,396 @@ async fn main() -> Result<()> {
         }
     }
 }
+
+/// Record a sample, report levels and advice, play it back and check for echo
+///
+/// Returns whether the microphone looks usable for dictation.
+/// Input device named by `arg`: "default", a device name, or its number in
+/// `onevox devices list`
+fn resolve_input_device(arg: &str) -> onevox::Result<String> {
+    if let Ok(number) = arg.parse::<usize>() {
+        let devices = onevox::audio::AudioDeviceManager::new().list_input_devices()?;
+        return number
+            .checked_sub(1)
+            .and_then(|index| devices.get(index))
+            .map(|device| device.name.clone())
+            .ok_or_else(|| {
+                onevox::Error::Audio(format!("No device #{} (see: onevox devices list)", number))
+            });
+    }
+    onevox::audio::devices::check_input_device(arg)?;
+    Ok(arg.to_string())
+}
+
+async fn run_mic_self_test(config: &Config, duration: u64, playback: bool) -> Result<bool> {
+    use onevox::audio::selftest::{self, DeviceCheck, EchoReport};
+    use onevox::audio::{AudioEngine, CaptureConfig, Tone, tones};
+
+    let mut engine = AudioEngine::new();
+    let devices = engine.list_devices()?;
+    let check = DeviceCheck::new(&config.audio.device, &devices);
+    println!(
+        "  Device: {}",
+        check.effective().unwrap_or("(no input device found)")
+    );
+    if let Some(warning) = check.warning() {
+        println!("  ⚠️  {}", warning);
+    }
+
+    let capture = CaptureConfig {
+        device_name: config.audio.device.clone(),
+        sample_rate: config.audio.sample_rate,
+        chunk_duration_ms: config.audio.chunk_duration_ms,
+        ..CaptureConfig::default()
+    };
+    let sample_rate = capture.sample_rate;
+
+    println!(
+        "\n  Speak normally for {} seconds, e.g. \"The quick brown fox jumps over the lazy dog\"",
+        duration
+    );
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    println!("  🔴 Recording...");
+    let samples = selftest::record(
+        &mut engine,
+        capture.clone(),
+        std::time::Duration::from_secs(duration),
+    )
+    .await?;
+
+    let report = selftest::analyze(&samples, sample_rate);
+    println!("\n  Duration:     {:.1}s", report.duration_secs);
+    println!("  Speech level: {:.0} dBFS", report.speech_dbfs);
+    println!("  Noise floor:  {:.0} dBFS", report.noise_dbfs);
+    println!("  SNR:          {:.0} dB", report.snr_db);
+    println!("  Clipping:     {:.2}%", report.clipped_ratio * 100.0);
+
+    let advice = report.recommendations();
+    if advice.is_empty() {
+        println!("\n  ✅ Levels look good");
+    } else {
+        for line in &advice {
+            println!("  ⚠️  {}", line);
+        }
+    }
+
+    if playback && !samples.is_empty() {
+        println!("\n  🔊 Playing back your recording...");
+        let clip = samples.clone();
+        let played =
+            tokio::task::spawn_blocking(move || tones::play_samples_blocking(&clip, sample_rate))
+                .await
+                .map_err(|e| onevox::Error::Other(e.to_string()))?;
+        if let Err(e) = played {
+            println!("  ⚠️  Playback unavailable: {}", e);
+        }
+
+        // Echo check: a quiet baseline, then a tone on the speakers
+        let tone = Tone {
+            frequency_hz: selftest::ECHO_TONE_HZ,
+            duration_ms: 1000,
+            volume: 0.2,
+        };
+        let window = std::time::Duration::from_millis(800);
+        let baseline = selftest::record(&mut engine, capture.clone(), window).await?;
+        let player = tokio::task::spawn_blocking(move || tones::play_blocking(tone));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let during = selftest::record(&mut engine, capture, window).await?;
+        let _ = player.await;
+
+        let echo = EchoReport::new(&baseline, &during, sample_rate);
+        if echo.echo_detected() {
+            println!(
+                "  ⚠️  Speaker output reaches the microphone (+{:.0} dB): use headphones or enable echo cancellation",
+                echo.rise_db
+            );
+        } else {
+            println!("  ✅ No echo from speakers detected");
+        }
+    }
+
+    Ok(advice.is_empty() && check.found)
+}
+
+/// Run URLs delivered by the OS; results and errors are reported as
+/// notifications since there is no terminal
+/// Run live audio through the VAD and the model until the capture ends,
+/// Ctrl+C, or `duration` seconds, passing each non-empty, post-processed
+/// utterance to `on_text`
+async fn transcribe_live(
+    config: &Config,
+    runtime: &mut dyn onevox::models::ModelRuntime,
+    chunk_rx: &mut tokio::sync::mpsc::Receiver<onevox::audio::AudioChunk>,
+    duration: Option<u64>,
+    mut on_text: impl FnMut(&onevox::models::Transcription),
+) -> Result<()> {
+    let mut vad_processor = onevox::vad::VadProcessor::new(
+        config.vad.to_processor_config(),
+        onevox::vad::create_detector(&config.vad).await,
+    );
+    let postprocessor = onevox::postprocess::PostProcessor::from_config(&config.post_processing)
+        .with_vocabulary(onevox::vocabulary::Vocabulary::from_config(
+            &config.vocabulary,
+        ));
+    let stop_after = async {
+        match duration {
+            Some(secs) => tokio::time::sleep(std::time::Duration::from_secs(secs)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(stop_after);
+
+    loop {
+        let chunk = tokio::select! {
+            chunk = chunk_rx.recv() => chunk,
+            _ = tokio::signal::ctrl_c() => None,
+            _ = &mut stop_after => None,
+        };
+        let finished = chunk.is_none();
+        let segment = match chunk {
+            Some(chunk) => vad_processor.process(chunk)?,
+            None => vad_processor.flush(),
+        };
+
+        if let Some(mut segment) = segment {
+            match runtime.transcribe_segment(&mut segment) {
+                Ok(mut transcription) => {
+                    postprocessor.process(&mut transcription);
+                    if !transcription.text.trim().is_empty() {
+                        on_text(&transcription);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Transcription failed: {}", e),
+            }
+        }
+        if finished {
+            return Ok(());
+        }
+    }
+}
+
+/// `onevox listen --meeting`: transcribe the microphone and system audio
+/// as "Me" and "Others", then save the transcript to history
+async fn listen_to_meeting(
+    config: &Config,
+    runtime: &mut dyn onevox::models::ModelRuntime,
+    duration: Option<u64>,
+) -> Result<()> {
+    use onevox::audio::{AudioEngine, CaptureConfig, CaptureSource};
+
+    let capture = |source| CaptureConfig {
+        source,
+        device_name: if source == config.audio.capture_source() {
+            config.audio.device.clone()
+        } else {
+            "default".to_string()
+        },
+        sample_rate: runtime.sample_rate(),
+        chunk_duration_ms: config.audio.chunk_duration_ms,
+        agc: (source == CaptureSource::Microphone && config.audio.agc.enabled)
+            .then(|| config.audio.agc.clone()),
+        ..CaptureConfig::default()
+    };
+    let mic_config = capture(CaptureSource::Microphone);
+    let system_config = capture(CaptureSource::System);
+
+    // Both captures stop with the engine
+    let mut engine = AudioEngine::new();
+    let started = engine
+        .start_capture(mic_config)
+        .and_then(|me| engine.add_capture(system_config).map(|others| (me, others)));
+    let (me, others) = match started {
+        Ok(receivers) => receivers,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+    eprintln!("👂 Listening to the microphone (Me) and system audio (Others)");
+    eprintln!("   Press Ctrl+C to stop\n");
+
+    let stop = async {
+        match duration {
+            Some(secs) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(std::time::Duration::from_secs(secs)) => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+            }
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    let model = runtime.name().to_string();
+    let mut entries = Vec::new();
+    onevox::meeting::transcribe_meeting(config, runtime, me, others, stop, |turn| {
+        let started_at: chrono::DateTime<chrono::Local> = turn.started_at().into();
+        println!(
+            "[{}] {}: {}",
+            started_at.format("%H:%M:%S"),
+            turn.speaker,
+            turn.transcription.text.trim()
+        );
+        entries.extend(turn.history_entries(&model));
+    })
+    .await?;
+    engine.stop_capture()?;
+
+    eprintln!("\n✅ Stopped listening");
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if !config.history.enabled {
+        eprintln!("💡 History is disabled; the transcript was not saved");
+        return Ok(());
+    }
+
+    // A running daemon owns the history file
+    let count = entries.len();
+    let mut client = onevox::ipc::IpcClient::default().with_client_name("onevox-cli");
+    match client.add_history(entries.clone()).await {
+        Ok(message) => eprintln!("📝 {} (onevox history export)", message),
+        Err(onevox::ipc::IpcError::Daemon(e)) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        Err(_) => {
+            let history =
+                onevox::history::HistoryManager::new_async(config.history.clone()).await?;
+            history.add_entries(entries).await?;
+            history.manual_save().await?;
+            eprintln!(
+                "📝 Added {} entries to history (onevox history export)",
+                count
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn handle_scheme_urls() {
+    use onevox::automation::Action;
+    use onevox::platform::{injector, notify};
+
+    for url in onevox::platform::url_events::receive_urls(std::time::Duration::from_secs(5)) {
+        tracing::info!("Automation URL: {}", url);
+        let result = match Action::from_url(&url) {
+            Ok(action) => action.run().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(Some(text)) => match injector::copy_to_clipboard(&text) {
+                Ok(()) => notify::notify("Onevox", "Transcript copied to the clipboard"),
+                Err(e) => notify::notify("Onevox", &format!("Could not copy transcript: {}", e)),
+            },
+            Ok(None) => {}
+            Err(e) => notify::notify("Onevox automation failed", &e),
+        }
+    }
+}
+
+/// Pause (or with `discard`, cancel) a download running in the daemon
+async fn stop_download(model_id: &str, discard: bool) -> Result<()> {
+    let mut client = onevox::ipc::IpcClient::default();
+    match client.stop_download(model_id, discard).await {
+        Ok(message) => {
+            println!("⏸️  {}", message);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            print_ipc_hint(&e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print each permission's status with how to grant the missing ones
+///
+/// With `fix`, missing permissions get the system prompt (where the OS has
+/// one) and their settings pane is opened. Returns whether none is denied.
+fn print_permission_report(fix: bool) -> bool {
+    use onevox::platform::{self, Permission, PermissionStatus};
+
+    let mut ok = true;
+    for (permission, mut status) in platform::check_required_permissions() {
+        if fix && status.is_missing() {
+            status = platform::request_permission(permission);
+            if status.is_missing() {
+                let opened = match permission {
+                    Permission::InputMonitoring => Some(platform::open_input_monitoring_settings()),
+                    Permission::Accessibility => Some(platform::open_accessibility_settings()),
+                    Permission::Microphone | Permission::ScreenRecording => None,
+                };
+                if let Some(Err(e)) = opened {
+                    eprintln!("   {}", e);
+                }
+            }
+        }
+        let icon = match status {
+            PermissionStatus::Granted => "✅",
+            PermissionStatus::NotApplicable => "➖",
+            PermissionStatus::NotDetermined => "❔",
+            PermissionStatus::Denied => "❌",
+        };
+        println!("   {} {}: {}", icon, permission, status);
+        if status.is_missing() {
+            println!("      → {}", permission.fix_hint());
+        }
+        ok &= status != PermissionStatus::Denied;
+    }
+    if !ok && !fix {
+        println!("   💡 Show the system prompts with: onevox doctor permissions --fix");
+    }
+    ok
+}
+
+/// Print a hint matching an IPC failure
+/// "Paused until 14:30 (25m left)" or "Paused until resumed"
+fn privacy_pause_text(privacy: &onevox::ipc::protocol::PrivacyStatus) -> String {
+    let Some(resume_at) = privacy.resume_at else {
+        return "Paused until resumed".to_string();
+    };
+    let left = resume_at.saturating_sub(chrono::Utc::now().timestamp().max(0) as u64);
+    let time = chrono::DateTime::from_timestamp(resume_at as i64, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%H:%M").to_string())
+        .unwrap_or_default();
+    format!(
+        "Paused until {} ({} left)",
+        time,
+        onevox::daemon::privacy::format_duration(left)
+    )
+}
+
+fn print_ipc_hint(error: &onevox::ipc::IpcError) {
+    use onevox::ipc::IpcError;
+
+    match error {
+        IpcError::DaemonNotRunning(_) => {
+            eprintln!("💡 Is the daemon running? Try: onevox daemon --foreground")
+        }
+        IpcError::ProtocolMismatch(_) => {
+            eprintln!("💡 Restart the daemon after upgrading: onevox stop && onevox daemon")
+        }
+        IpcError::Timeout { .. } | IpcError::RateLimited => {
+            eprintln!("💡 The daemon is busy; try again in a moment")
+        }
+        IpcError::ShuttingDown | IpcError::Daemon(_) | IpcError::Io(_) => {}
+    }
+}
+
+/// Render values as a unicode sparkline (blank for missing values)
+fn sparkline(values: &[Option<f64>]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values.iter().flatten().copied().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|value| match value {
+            Some(_) if max <= 0.0 => BARS[0],
+            Some(v) => BARS[((v / max) * (BARS.len() - 1) as f64).round() as usize],
+            None => ' ',
+        })
+        .collect()
+}
@@ -0,0 +1,283 @@
+//! Meeting Transcripts
+//!
+//! `onevox listen --meeting` captures the microphone and system audio at
+//! once, as two pipelines with their own VAD, and transcribes both with one
+//! model. What the microphone hears is "Me", what the computer plays is
+//! "Others". Turns are written out in the order they were spoken, which is
+//! not always the order they finish (a long turn from one side ends after
+//! a short reply from the other), so each is held until the other side
+//! can no longer produce an earlier one. History entries carry the speaker,
+//! and exports show it.
+
+use crate::audio::{AudioChunk, CaptureSource};
+use crate::config::Config;
+use crate::history::HistoryEntry;
+use crate::models::{ModelRuntime, Transcription};
+use crate::postprocess::PostProcessor;
+use crate::vad::{SpeechSegment, VadProcessor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Who spoke a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Speaker {
+    /// The user, on the microphone
+    Me,
+    /// Everyone else, in the system audio
+    Others,
+}
+
+impl Speaker {
+    /// Who is heard on `source`
+    pub fn of(source: CaptureSource) -> Self {
+        match source {
+            CaptureSource::Microphone => Speaker::Me,
+            CaptureSource::System => Speaker::Others,
+        }
+    }
+}
+
+impl fmt::Display for Speaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Speaker::Me => write!(f, "Me"),
+            Speaker::Others => write!(f, "Others"),
+        }
+    }
+}
+
+/// A transcribed turn
+#[derive(Debug, Clone)]
+pub struct Turn {
+    pub speaker: Speaker,
+    /// When the turn's audio was captured
+    pub start: Instant,
+    pub transcription: Transcription,
+}
+
+impl Turn {
+    /// Wall-clock time the turn started
+    pub fn started_at(&self) -> SystemTime {
+        SystemTime::now()
+            .checked_sub(self.start.elapsed())
+            .unwrap_or(UNIX_EPOCH)
+    }
+
+    /// History entries for the turn, tagged with the speaker
+    pub fn history_entries(&self, model: &str) -> Vec<HistoryEntry> {
+        let timestamp = self
+            .started_at()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+        HistoryEntry::from_transcription(&self.transcription, model)
+            .into_iter()
+            .map(|entry| HistoryEntry {
+                id: timestamp,
+                timestamp,
+                speaker: Some(self.speaker),
+                ..entry
+            })
+            .collect()
+    }
+}
+
+/// Holds turns until no pipeline can still produce an earlier one
+#[derive(Debug, Default)]
+pub struct Interleaver {
+    pending: Vec<Turn>,
+}
+
+impl Interleaver {
+    pub fn push(&mut self, turn: Turn) {
+        self.pending.push(turn);
+    }
+
+    /// Turns that started before `watermark` (all of them for `None`), in
+    /// the order they started
+    pub fn release(&mut self, watermark: Option<Instant>) -> Vec<Turn> {
+        self.pending.sort_by_key(|turn| turn.start);
+        let ready = match watermark {
+            Some(watermark) => self
+                .pending
+                .iter()
+                .take_while(|turn| turn.start < watermark)
+                .count(),
+            None => self.pending.len(),
+        };
+        self.pending.drain(..ready).collect()
+    }
+}
+
+/// One source's capture and VAD
+struct Pipeline {
+    speaker: Speaker,
+    chunks: mpsc::Receiver<AudioChunk>,
+    vad: VadProcessor,
+    open: bool,
+    /// Capture time of the last chunk processed
+    last_chunk: Instant,
+}
+
+impl Pipeline {
+    /// No turn still to come from this pipeline starts before this (`None`
+    /// once its audio has ended)
+    fn watermark(&self) -> Option<Instant> {
+        self.open
+            .then(|| self.vad.earliest_pending().unwrap_or(self.last_chunk))
+    }
+
+    /// Feed a chunk (`None` at the end of the audio), returning a finished
+    /// segment
+    fn feed(&mut self, chunk: Option<AudioChunk>) -> crate::Result<Option<SpeechSegment>> {
+        match chunk {
+            Some(chunk) => {
+                self.last_chunk = chunk.timestamp;
+                self.vad.process(chunk)
+            }
+            None => {
+                self.open = false;
+                Ok(self.vad.flush())
+            }
+        }
+    }
+}
+
+/// Transcribe the microphone (`me`) and system audio (`others`) until both
+/// end or `stop` completes, passing each turn to `on_turn` in spoken order
+pub async fn transcribe_meeting(
+    config: &Config,
+    runtime: &mut dyn ModelRuntime,
+    me: mpsc::Receiver<AudioChunk>,
+    others: mpsc::Receiver<AudioChunk>,
+    stop: impl Future<Output = ()>,
+    mut on_turn: impl FnMut(&Turn),
+) -> crate::Result<()> {
+    let postprocessor = PostProcessor::from_config(&config.post_processing).with_vocabulary(
+        crate::vocabulary::Vocabulary::from_config(&config.vocabulary),
+    );
+    let mut pipelines = Vec::new();
+    for (speaker, chunks) in [(Speaker::Me, me), (Speaker::Others, others)] {
+        pipelines.push(Pipeline {
+            speaker,
+            chunks,
+            vad: VadProcessor::new(
+                config.vad.to_processor_config(),
+                crate::vad::create_detector(&config.vad).await,
+            ),
+            open: true,
+            last_chunk: Instant::now(),
+        });
+    }
+    let mut interleaver = Interleaver::default();
+    tokio::pin!(stop);
+
+    loop {
+        let (index, chunk) = {
+            let [me, others] = pipelines.as_mut_slice() else {
+                unreachable!("two pipelines");
+            };
+            tokio::select! {
+                chunk = me.chunks.recv(), if me.open => (0, chunk),
+                chunk = others.chunks.recv(), if others.open => (1, chunk),
+                _ = &mut stop => break,
+            }
+        };
+        let pipeline = &mut pipelines[index];
+        if let Some(mut segment) = pipeline.feed(chunk)? {
+            let speaker = pipeline.speaker;
+            transcribe(
+                runtime,
+                &postprocessor,
+                speaker,
+                &mut segment,
+                &mut interleaver,
+            );
+        }
+
+        if pipelines.iter().all(|pipeline| !pipeline.open) {
+            break;
+        }
+        let watermark = pipelines.iter().filter_map(Pipeline::watermark).min();
+        interleaver.release(watermark).iter().for_each(&mut on_turn);
+    }
+
+    for pipeline in &mut pipelines {
+        if pipeline.open
+            && let Some(mut segment) = pipeline.feed(None)?
+        {
+            let speaker = pipeline.speaker;
+            transcribe(
+                runtime,
+                &postprocessor,
+                speaker,
+                &mut segment,
+                &mut interleaver,
+            );
+        }
+    }
+    interleaver.release(None).iter().for_each(&mut on_turn);
+    Ok(())
+}
+
+fn transcribe(
+    runtime: &mut dyn ModelRuntime,
+    postprocessor: &PostProcessor,
+    speaker: Speaker,
+    segment: &mut SpeechSegment,
+    interleaver: &mut Interleaver,
+) {
+    match runtime.transcribe_segment(segment) {
+        Ok(mut transcription) => {
+            postprocessor.process(&mut transcription);
+            if !transcription.text.trim().is_empty() {
+                interleaver.push(Turn {
+                    speaker,
+                    start: segment.start_time,
+                    transcription,
+                });
+            }
+        }
+        Err(e) => warn!("Transcription of {} failed: {}", speaker, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(speaker: Speaker, start: Instant, text: &str) -> Turn {
+        Turn {
+            speaker,
+            start,
+            transcription: Transcription::new(text.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_turns_released_in_spoken_order() {
+        let t0 = Instant::now();
+        let at = |ms| t0 + Duration::from_millis(ms);
+        let mut interleaver = Interleaver::default();
+
+        // A long turn from the others finishes after a short reply of mine
+        interleaver.push(turn(Speaker::Me, at(3000), "Sounds good"));
+        interleaver.push(turn(Speaker::Others, at(1000), "Shall we ship it"));
+        // The microphone may still have speech from 2s on
+        let released = interleaver.release(Some(at(2000)));
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].speaker, Speaker::Others);
+
+        let released = interleaver.release(None);
+        assert_eq!(released[0].transcription.text, "Sounds good");
+        assert!(interleaver.release(None).is_empty());
+
+        let entries = turn(Speaker::Me, at(0), "Hello").history_entries("model");
+        assert_eq!(entries[0].speaker, Some(Speaker::Me));
+        assert_eq!(Speaker::of(CaptureSource::System).to_string(), "Others");
+    }
+}
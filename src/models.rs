@@ -3,28 +3,49 @@
 //! Unified interface for multiple transcription backends.
 //!
 //! Primary backend: whisper.cpp (native bindings)
-//! ONNX backend: ONNX Runtime (production-ready, supports Parakeet and other models)
+//! Server backend: whisper.cpp `whisper-server` child process over HTTP
+//! ONNX backend: ONNX Runtime (production-ready, supports Parakeet CTC and TDT models),
+//! linked at build time or loaded at runtime (`onnx-dynamic`)
+//! Ensemble: several backends per segment, reconciled by confidence or voting
 //! Optional backend: Candle (pure Rust, experimental)
 
+pub mod backend;
+pub mod chunking;
+pub mod confidence;
+pub mod ctc_beam;
 pub mod downloader;
+pub mod ensemble;
+pub mod inference_debug;
+pub mod mel;
 pub mod mock;
+pub mod onnx_dylib;
+pub mod onnx_ep;
 pub mod onnx_runtime;
 pub mod registry;
+pub mod rescoring;
 pub mod runtime;
+pub mod server_binary;
 pub mod tokenizer;
+pub mod transducer;
 pub mod whisper_cpp;
+pub mod whisper_server;
 
 #[cfg(feature = "candle")]
 pub mod whisper_candle;
 
 // Re-export commonly used types
-pub use downloader::ModelDownloader;
+pub use downloader::{
+    DEFAULT_STALL_TIMEOUT, DownloadCancelled, FileCheck, FileStatus, ModelDownloader, ModelVersion,
+    stall_timeout_from_secs,
+};
 pub use mock::MockModel;
+pub use onnx_ep::ExecutionProvider;
 pub use onnx_runtime::OnnxRuntime;
 pub use registry::{ModelMetadata, ModelRegistry, ModelSize, ModelVariant};
-pub use runtime::{ModelConfig, ModelInfo, ModelRuntime, Transcription};
+pub use runtime::{ModelConfig, ModelInfo, ModelRuntime, TranscriptSegment, Transcription};
 pub use tokenizer::SimpleTokenizer;
 pub use whisper_cpp::WhisperCpp;
+pub use whisper_server::WhisperServer;
 
 #[cfg(feature = "candle")]
 pub use whisper_candle::WhisperCandle;
@@ -0,0 +1,146 @@
+//! Backend Selection
+//!
+//! Builds the model runtime named by the `[model]` config section, so the
+//! dictation engine and offline tools (`onevox eval`) pick backends the
+//! same way.
+
+#[cfg(feature = "onnx")]
+use super::OnnxRuntime;
+use super::chunking::ChunkedRuntime;
+use super::ensemble::{EnsembleRuntime, EnsembleStrategy};
+use super::registry::ModelFormat;
+use super::{ModelConfig, ModelRuntime, WhisperCpp, WhisperServer};
+use tracing::info;
+
+/// Backend for a model config: the explicit `backend`, or one detected from
+/// the registry entry or model path when it is "auto"
+pub fn resolve_backend(config: &crate::config::ModelConfig) -> &str {
+    let model_path = &config.model_path;
+    match config.backend.as_str() {
+        "auto" => {
+            // Models added through a manifest can have any name
+            match super::ModelRegistry::new().get_model(model_path) {
+                Some(model) if model.format == ModelFormat::ONNX => return "onnx",
+                Some(model) if model.format == ModelFormat::GGML => return "whisper-cpp",
+                _ => {}
+            }
+            let is_onnx_model = model_path.contains("parakeet")
+                || model_path.ends_with(".onnx")
+                || model_path.contains("onnx");
+            if is_onnx_model { "onnx" } else { "whisper-cpp" }
+        }
+        backend => backend,
+    }
+}
+
+/// Create (but do not load) the runtime for a model config
+///
+/// With `[model.ensemble]` enabled this is an ensemble of the configured
+/// model and the extra ensemble models. With `[model.chunking]` enabled
+/// (the default) long recordings are split to fit the model's window.
+pub fn create_runtime(config: &crate::config::ModelConfig) -> crate::Result<Box<dyn ModelRuntime>> {
+    // Reject an unknown `decoding` whichever backend is picked
+    config.decoding_config()?;
+    let runtime = create_ensemble(config)?;
+    if !config.chunking.enabled {
+        return Ok(runtime);
+    }
+    Ok(Box::new(ChunkedRuntime::new(
+        runtime,
+        config.chunking.clone(),
+    )))
+}
+
+/// Create the ensemble, or the single backend when there is none
+fn create_ensemble(config: &crate::config::ModelConfig) -> crate::Result<Box<dyn ModelRuntime>> {
+    let ensemble = &config.ensemble;
+    if !ensemble.enabled || ensemble.models.is_empty() {
+        return create_backend(config);
+    }
+
+    let strategy = EnsembleStrategy::parse(&ensemble.strategy).ok_or_else(|| {
+        crate::Error::Config(format!(
+            "Unknown ensemble strategy '{}' (expected confidence or vote)",
+            ensemble.strategy
+        ))
+    })?;
+
+    let mut members = vec![(config.model_path.clone(), create_backend(config)?)];
+    for model_path in &ensemble.models {
+        let member = crate::config::ModelConfig {
+            model_path: model_path.clone(),
+            backend: "auto".to_string(),
+            ..config.clone()
+        };
+        members.push((model_path.clone(), create_backend(&member)?));
+    }
+    info!(
+        "Using {:?} ensemble of {} backends",
+        strategy,
+        members.len()
+    );
+    Ok(Box::new(EnsembleRuntime::new(members, strategy)))
+}
+
+/// Create a single backend, ignoring the ensemble settings
+fn create_backend(config: &crate::config::ModelConfig) -> crate::Result<Box<dyn ModelRuntime>> {
+    let model_path = &config.model_path;
+    // Only say the backend was detected when it wasn't set explicitly
+    let how = if config.backend == "auto" {
+        "detected"
+    } else {
+        "configured"
+    };
+    match resolve_backend(config) {
+        "onnx" => {
+            #[cfg(feature = "onnx")]
+            {
+                info!("Using ONNX Runtime backend ({}) for: {}", how, model_path);
+                Ok(Box::new(
+                    OnnxRuntime::new()?
+                        .with_rescoring(config.decoding_config()?)
+                        .with_execution_provider(&config.execution_provider),
+                ))
+            }
+            #[cfg(not(feature = "onnx"))]
+            {
+                Err(crate::Error::Model(format!(
+                    "ONNX model requires --features onnx build. Model: {}",
+                    model_path
+                )))
+            }
+        }
+        "whisper-server" => {
+            info!("Using whisper-server backend ({}) for: {}", how, model_path);
+            Ok(Box::new(WhisperServer::new(config.server.clone())?))
+        }
+        "whisper-cpp" | "whisper-native" => {
+            info!("Using whisper.cpp backend ({}) for: {}", how, model_path);
+            Ok(Box::new(WhisperCpp::new()?))
+        }
+        other => Err(crate::Error::Config(format!(
+            "Unknown model backend '{}' (expected auto, whisper-cpp, whisper-native, onnx, or whisper-server)",
+            other
+        ))),
+    }
+}
+
+/// Runtime load options for a model config
+pub fn runtime_config(config: &crate::config::ModelConfig) -> ModelConfig {
+    ModelConfig {
+        model_path: config.model_path.clone(),
+        use_gpu: config.device == "gpu" || config.device == "auto",
+        language: config.language().map(str::to_string),
+        beam_size: config
+            .decoding_config()
+            .map_or(1, |decoding| decoding.beam_width.max(1) as u32),
+        ..Default::default()
+    }
+}
+
+/// Create and load the runtime for a model config
+pub fn load_runtime(config: &crate::config::ModelConfig) -> crate::Result<Box<dyn ModelRuntime>> {
+    let mut model = create_runtime(config)?;
+    model.load(runtime_config(config))?;
+    Ok(model)
+}
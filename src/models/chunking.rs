@@ -0,0 +1,335 @@
+//! Long-Audio Chunking
+//!
+//! Whisper decodes at most 30 seconds of audio at a time and silently
+//! drops the rest, so long dictations are split before they reach the
+//! model. [`ChunkedRuntime`] wraps any backend: audio longer than
+//! `[model.chunking].max_chunk_secs` is cut at the quietest pause in the
+//! second half of each chunk, or, when there is no pause, at the chunk
+//! length with `overlap_ms` of audio shared with the next chunk. Chunks are
+//! transcribed in order with the end of the text so far as the decoding
+//! prompt (on backends that take one), and stitched into one transcription:
+//! words repeated across an overlap are dropped, and timestamps are moved
+//! to the position of their chunk.
+
+use super::{ModelConfig, ModelInfo, ModelRuntime, TranscriptSegment, Transcription};
+use crate::config::ChunkingConfig;
+use crate::vocabulary::Vocabulary;
+use tracing::debug;
+
+/// Frame length for finding pauses
+const FRAME_MS: usize = 30;
+
+/// A frame quieter than this fraction of the chunk's mean level is a pause
+const PAUSE_RATIO: f32 = 0.3;
+
+/// Words of earlier text passed as the prompt for the next chunk
+const PROMPT_WORDS: usize = 32;
+
+/// Most words compared when removing a repeat across an overlap
+const MAX_REPEAT_WORDS: usize = 8;
+
+/// A model runtime that transcribes long audio in chunks
+pub struct ChunkedRuntime {
+    inner: Box<dyn ModelRuntime>,
+    config: ChunkingConfig,
+}
+
+impl ChunkedRuntime {
+    /// Wrap `inner`, chunking audio as `config` says
+    pub fn new(inner: Box<dyn ModelRuntime>, config: ChunkingConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+/// A piece of the audio to transcribe on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Chunk {
+    start: usize,
+    end: usize,
+    /// Shares audio with the previous chunk
+    overlaps: bool,
+}
+
+/// Split `samples` into chunks of at most `max_len`, cutting at pauses
+fn plan_chunks(samples: &[f32], sample_rate: u32, max_len: usize, overlap: usize) -> Vec<Chunk> {
+    let frame = (sample_rate as usize * FRAME_MS / 1000).max(1);
+    let overlap = overlap.min(max_len / 2);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut overlaps = false;
+
+    while samples.len() - start > max_len {
+        let end = start + max_len;
+        match quietest_pause(&samples[start..end], frame) {
+            Some(pause) => {
+                let cut = start + pause;
+                chunks.push(Chunk {
+                    start,
+                    end: cut,
+                    overlaps,
+                });
+                start = cut;
+                overlaps = false;
+            }
+            None => {
+                chunks.push(Chunk {
+                    start,
+                    end,
+                    overlaps,
+                });
+                start = end - overlap;
+                overlaps = overlap > 0;
+            }
+        }
+    }
+    chunks.push(Chunk {
+        start,
+        end: samples.len(),
+        overlaps,
+    });
+    chunks
+}
+
+/// Middle of the quietest frame in the second half of `samples`, if it is
+/// quiet enough to be a pause
+fn quietest_pause(samples: &[f32], frame: usize) -> Option<usize> {
+    let levels: Vec<f32> = samples
+        .chunks(frame)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect();
+    let mean = levels.iter().sum::<f32>() / levels.len().max(1) as f32;
+    let (index, level) = levels
+        .iter()
+        .enumerate()
+        .skip(levels.len() / 2)
+        .min_by(|a, b| a.1.total_cmp(b.1))?;
+    (*level < mean * PAUSE_RATIO).then_some(index * frame + frame / 2)
+}
+
+/// Words at the start of `next` that repeat the end of `previous`
+fn repeated_words(previous: &[&str], next: &[&str]) -> usize {
+    let normalize = |word: &str| {
+        word.trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase()
+    };
+    (1..=MAX_REPEAT_WORDS.min(previous.len()).min(next.len()))
+        .rev()
+        .find(|&count| {
+            previous[previous.len() - count..]
+                .iter()
+                .zip(&next[..count])
+                .all(|(a, b)| normalize(a) == normalize(b))
+        })
+        .unwrap_or(0)
+}
+
+/// Last words of `text`, as a decoding prompt
+fn prompt_from(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words[words.len().saturating_sub(PROMPT_WORDS)..].join(" ")
+}
+
+impl ModelRuntime for ChunkedRuntime {
+    fn load(&mut self, config: ModelConfig) -> crate::Result<()> {
+        self.inner.load(config)
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.inner.is_loaded()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn transcribe(&mut self, samples: &[f32], sample_rate: u32) -> crate::Result<Transcription> {
+        let max_len = (self.config.max_chunk_secs as usize * sample_rate as usize).max(1);
+        if !self.config.enabled || samples.len() <= max_len {
+            return self.inner.transcribe(samples, sample_rate);
+        }
+
+        let overlap = self.config.overlap_ms as usize * sample_rate as usize / 1000;
+        let chunks = plan_chunks(samples, sample_rate, max_len, overlap);
+        debug!(
+            "Transcribing {:.1}s of audio in {} chunks",
+            samples.len() as f32 / sample_rate as f32,
+            chunks.len()
+        );
+        let ms = |sample: usize| sample as u64 * 1000 / sample_rate as u64;
+
+        let mut result = Transcription::new(String::new());
+        let mut texts: Vec<String> = Vec::new();
+        let mut prompted = false;
+        for (index, chunk) in chunks.iter().enumerate() {
+            let transcription = self
+                .inner
+                .transcribe(&samples[chunk.start..chunk.end], sample_rate);
+            let transcription = match transcription {
+                Ok(transcription) => transcription,
+                Err(e) => {
+                    if prompted {
+                        self.inner.set_prompt(None);
+                    }
+                    return Err(e);
+                }
+            };
+            result.processing_time_ms += transcription.processing_time_ms;
+            result.language = result.language.or(transcription.language);
+            result.tokens = match (result.tokens, transcription.tokens) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+
+            // Across an overlap, each side keeps the words that start in
+            // its half of the shared audio
+            let offset = ms(chunk.start);
+            let keep_from = if chunk.overlaps {
+                offset + ms(chunks[index - 1].end - chunk.start) / 2
+            } else {
+                0
+            };
+            if let Some(next) = chunks.get(index + 1).filter(|next| next.overlaps) {
+                let keep_until = ms(next.start) + ms(chunk.end - next.start) / 2;
+                let before = |piece: &TranscriptSegment| piece.start_ms < keep_until;
+                let shifted = transcription.words.iter().map(|w| w.shifted(offset));
+                result
+                    .words
+                    .extend(shifted.filter(|w| w.start_ms >= keep_from).filter(before));
+                let shifted = transcription.segments.iter().map(|s| s.shifted(offset));
+                result
+                    .segments
+                    .extend(shifted.filter(|s| s.start_ms >= keep_from).filter(before));
+            } else {
+                let shifted = transcription.words.iter().map(|w| w.shifted(offset));
+                result
+                    .words
+                    .extend(shifted.filter(|w| w.start_ms >= keep_from));
+                let shifted = transcription.segments.iter().map(|s| s.shifted(offset));
+                result
+                    .segments
+                    .extend(shifted.filter(|s| s.start_ms >= keep_from));
+            }
+
+            let words: Vec<&str> = transcription.text.split_whitespace().collect();
+            let skip = if chunk.overlaps {
+                let joined = texts.join(" ");
+                let previous: Vec<&str> = joined.split_whitespace().collect();
+                repeated_words(&previous, &words)
+            } else {
+                0
+            };
+            if words.len() > skip {
+                texts.push(words[skip..].join(" "));
+            }
+
+            if index + 1 < chunks.len() {
+                prompted |= self.inner.set_prompt(Some(&prompt_from(&texts.join(" "))));
+            }
+        }
+        if prompted {
+            self.inner.set_prompt(None);
+        }
+
+        result.text = texts.join(" ");
+        Ok(result)
+    }
+
+    fn set_language(&mut self, language: Option<&str>) {
+        self.inner.set_language(language);
+    }
+
+    fn set_translate(&mut self, translate: bool) -> bool {
+        self.inner.set_translate(translate)
+    }
+
+    fn set_prompt(&mut self, prompt: Option<&str>) -> bool {
+        self.inner.set_prompt(prompt)
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: &Vocabulary) -> bool {
+        self.inner.set_vocabulary(vocabulary)
+    }
+
+    fn unload(&mut self) {
+        self.inner.unload();
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn info(&self) -> ModelInfo {
+        self.inner.info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns one word per second of audio it was given
+    struct Counter;
+
+    impl ModelRuntime for Counter {
+        fn load(&mut self, _config: ModelConfig) -> crate::Result<()> {
+            Ok(())
+        }
+        fn is_loaded(&self) -> bool {
+            true
+        }
+        fn transcribe(&mut self, samples: &[f32], rate: u32) -> crate::Result<Transcription> {
+            let secs = samples.len() / rate as usize;
+            let text = (0..secs).map(|i| format!("w{}", i)).collect::<Vec<_>>();
+            Ok(Transcription::new(text.join(" ")))
+        }
+        fn unload(&mut self) {}
+        fn name(&self) -> &str {
+            "counter"
+        }
+        fn info(&self) -> ModelInfo {
+            ModelInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_chunks_cut_at_pauses_and_overlap() {
+        let rate = 1000;
+        // Speech with a pause at 7.5 s
+        let mut samples = vec![0.5f32; 12 * rate];
+        samples[7400..7600].fill(0.0);
+        let chunks = plan_chunks(&samples, rate as u32, 10 * rate, 1000);
+        assert_eq!(chunks.len(), 2);
+        assert!((7400..7600).contains(&chunks[0].end));
+        assert!(!chunks[1].overlaps);
+
+        // No pause: fixed windows sharing a second of audio
+        let samples = vec![0.5f32; 25 * rate];
+        let chunks = plan_chunks(&samples, rate as u32, 10 * rate, 1000);
+        assert_eq!(
+            chunks.iter().map(|c| (c.start, c.end)).collect::<Vec<_>>(),
+            vec![(0, 10_000), (9_000, 19_000), (18_000, 25_000)]
+        );
+        assert!(chunks[2].overlaps);
+
+        assert_eq!(
+            repeated_words(&["the", "quick", "fox."], &["Fox", "jumps"]),
+            1
+        );
+        assert_eq!(repeated_words(&["a", "b"], &["c"]), 0);
+
+        // Short audio goes straight through; long audio is stitched
+        let config = ChunkingConfig {
+            max_chunk_secs: 10,
+            ..ChunkingConfig::default()
+        };
+        let mut runtime = ChunkedRuntime::new(Box::new(Counter), config);
+        let short = runtime
+            .transcribe(&vec![0.5; 5 * rate], rate as u32)
+            .unwrap();
+        assert_eq!(short.text, "w0 w1 w2 w3 w4");
+        let long = runtime
+            .transcribe(&vec![0.5; 25 * rate], rate as u32)
+            .unwrap();
+        assert_eq!(long.text.split_whitespace().count(), 27);
+    }
+}
@@ -0,0 +1,81 @@
+//! Transcription Confidence
+//!
+//! Backends report how sure they are of a transcription as a probability
+//! between 0 and 1:
+//!
+//! - CTC: the mean softmax probability of the token chosen at each frame
+//!   that isn't blank (blank frames are confident about silence, and would
+//!   lift every score)
+//! - Whisper: the geometric mean of the chosen tokens' probabilities, i.e.
+//!   the exponent of their average log-probability
+//!
+//! Both weigh every token alike, so one misheard word in a long sentence
+//! lowers the score only a little. Transducer models report none.
+
+/// Mean probability of the best token over the non-blank frames of
+/// `[time, vocab]` logits (log-probabilities work too); `None` when every
+/// frame is blank
+pub fn ctc(logits: &[f32], vocab_size: usize, blank_id: usize) -> Option<f32> {
+    if vocab_size == 0 {
+        return None;
+    }
+    let (mut sum, mut frames) = (0.0f64, 0usize);
+    for frame in logits.chunks_exact(vocab_size) {
+        let (best, max) = frame.iter().copied().enumerate().fold(
+            (0, f32::NEG_INFINITY),
+            |(best, max), (i, value)| {
+                if value > max { (i, value) } else { (best, max) }
+            },
+        );
+        if best == blank_id {
+            continue;
+        }
+        // Softmax of the best token: 1 / Σ exp(l - max)
+        let total: f64 = frame.iter().map(|&l| ((l - max) as f64).exp()).sum();
+        sum += 1.0 / total;
+        frames += 1;
+    }
+    (frames > 0).then(|| (sum / frames as f64) as f32)
+}
+
+/// Probability from the average of token log-probabilities; `None` without
+/// tokens
+pub fn from_log_probs(log_probs: impl IntoIterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = log_probs
+        .into_iter()
+        .filter(|p| p.is_finite())
+        .fold((0.0f64, 0usize), |(sum, count), p| {
+            (sum + p as f64, count + 1)
+        });
+    (count > 0).then(|| ((sum / count as f64).exp() as f32).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_scores() {
+        // Vocab of three with blank last: a sure token, a blank frame, and
+        // an unsure token
+        let ln = |p: f32| p.ln();
+        let logits = [
+            ln(0.9),
+            ln(0.05),
+            ln(0.05),
+            ln(0.1),
+            ln(0.1),
+            ln(0.8),
+            ln(0.3),
+            ln(0.5),
+            ln(0.2),
+        ];
+        let confidence = ctc(&logits, 3, 2).unwrap();
+        assert!((confidence - 0.7).abs() < 1e-4);
+        assert!(ctc(&logits[3..6], 3, 2).is_none());
+
+        let whisper = from_log_probs([ln(0.9), ln(0.4)]).unwrap();
+        assert!((whisper - 0.6).abs() < 1e-4);
+        assert!(from_log_probs([]).is_none());
+    }
+}
@@ -0,0 +1,247 @@
+//! CTC Prefix Beam Search
+//!
+//! Greedy CTC decoding keeps only the best token per frame. The prefix beam
+//! search here follows the `beam_width` most likely label sequences through
+//! the frames instead, merging alignments that collapse to the same labels,
+//! and returns an n-best list that a language model can reorder (see
+//! `rescoring`). A language model can also be fused into the search itself:
+//! a score for each prefix's completed words is added when beams are
+//! pruned, so likely word sequences survive to the end of the audio.
+
+use std::collections::HashMap;
+
+/// Live prefixes with their scores and emission frames
+type Beams = HashMap<Vec<i64>, (PrefixScore, Vec<usize>)>;
+
+/// A decoded label sequence with its acoustic score
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtcHypothesis {
+    /// Token ids after collapsing repeats and dropping blanks
+    pub tokens: Vec<i64>,
+    /// Frame each token was first emitted at
+    pub frames: Vec<usize>,
+    /// Log probability of the sequence under the acoustic model
+    pub score: f32,
+}
+
+impl CtcHypothesis {
+    /// Frame-aligned token ids (`blank` between emissions), the shape greedy
+    /// decoding produces
+    pub fn frame_tokens(&self, time_steps: usize, blank: i64) -> Vec<i64> {
+        let mut aligned = vec![blank; time_steps];
+        for (&token, &frame) in self.tokens.iter().zip(&self.frames) {
+            if let Some(slot) = aligned.get_mut(frame) {
+                *slot = token;
+            }
+        }
+        aligned
+    }
+}
+
+/// Probabilities of a prefix ending in a blank and in its last token
+#[derive(Debug, Clone, Copy)]
+struct PrefixScore {
+    blank: f32,
+    non_blank: f32,
+}
+
+impl PrefixScore {
+    const EMPTY: Self = Self {
+        blank: f32::NEG_INFINITY,
+        non_blank: f32::NEG_INFINITY,
+    };
+
+    fn total(&self) -> f32 {
+        log_add(self.blank, self.non_blank)
+    }
+}
+
+/// `ln(e^a + e^b)` without overflow
+fn log_add(a: f32, b: f32) -> f32 {
+    if a == f32::NEG_INFINITY {
+        return b;
+    }
+    if b == f32::NEG_INFINITY {
+        return a;
+    }
+    let (max, min) = if a > b { (a, b) } else { (b, a) };
+    max + (min - max).exp().ln_1p()
+}
+
+/// Log-softmax of one frame's logits (a no-op shift for log probabilities)
+fn log_softmax(logits: &[f32]) -> Vec<f32> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|&x| (x - max).exp()).sum();
+    let log_sum = max + sum.ln();
+    logits.iter().map(|&x| x - log_sum).collect()
+}
+
+/// Prefix beam search over `[time_steps, vocab_size]` logits
+///
+/// Returns up to `n_best` hypotheses, most likely first. Only the
+/// `beam_width` best tokens of each frame are considered for extension.
+/// `prefix_bonus` is added to each prefix's acoustic score when ranking
+/// beams (language model fusion; `|_| 0.0` for none). Hypothesis scores
+/// stay acoustic.
+pub fn beam_search(
+    logits: &[f32],
+    vocab_size: usize,
+    blank: i64,
+    beam_width: usize,
+    n_best: usize,
+    prefix_bonus: impl Fn(&[i64]) -> f32,
+) -> Vec<CtcHypothesis> {
+    let beam_width = beam_width.max(1);
+    let blank_index = blank as usize;
+
+    let mut beams: Beams = HashMap::new();
+    beams.insert(
+        Vec::new(),
+        (
+            PrefixScore {
+                blank: 0.0,
+                non_blank: f32::NEG_INFINITY,
+            },
+            Vec::new(),
+        ),
+    );
+
+    for (frame, frame_logits) in logits.chunks_exact(vocab_size.max(1)).enumerate() {
+        let log_probs = log_softmax(frame_logits);
+        let mut candidates: Vec<usize> = (0..vocab_size).filter(|&v| v != blank_index).collect();
+        if candidates.len() > beam_width {
+            candidates
+                .select_nth_unstable_by(beam_width, |&a, &b| log_probs[b].total_cmp(&log_probs[a]));
+            candidates.truncate(beam_width);
+        }
+
+        let mut next: Beams = HashMap::new();
+        for (prefix, (score, frames)) in &beams {
+            // Blank: the prefix stays the same and now ends in a blank
+            if let Some(&p_blank) = log_probs.get(blank_index) {
+                let entry = prefix_entry(&mut next, prefix.clone(), frames.clone());
+                entry.blank = log_add(entry.blank, score.total() + p_blank);
+            }
+
+            for &token_index in &candidates {
+                let token = token_index as i64;
+                let p = log_probs[token_index];
+
+                if prefix.last() == Some(&token) {
+                    // Repeat without a blank in between collapses into the prefix
+                    let entry = prefix_entry(&mut next, prefix.clone(), frames.clone());
+                    entry.non_blank = log_add(entry.non_blank, score.non_blank + p);
+                    // Only a blank-separated repeat is a new token
+                    extend(&mut next, prefix, frames, token, frame, score.blank + p);
+                } else {
+                    extend(&mut next, prefix, frames, token, frame, score.total() + p);
+                }
+            }
+        }
+
+        let mut ranked: Vec<_> = next
+            .into_iter()
+            .map(|(prefix, entry)| (entry.0.total() + prefix_bonus(&prefix), prefix, entry))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.truncate(beam_width);
+        beams = ranked
+            .into_iter()
+            .map(|(_, prefix, entry)| (prefix, entry))
+            .collect();
+    }
+
+    let mut hypotheses: Vec<(f32, CtcHypothesis)> = beams
+        .into_iter()
+        .map(|(tokens, (score, frames))| {
+            let ranking = score.total() + prefix_bonus(&tokens);
+            let hypothesis = CtcHypothesis {
+                tokens,
+                frames,
+                score: score.total(),
+            };
+            (ranking, hypothesis)
+        })
+        .collect();
+    hypotheses.sort_by(|a, b| b.0.total_cmp(&a.0));
+    hypotheses.truncate(n_best.max(1));
+    hypotheses.into_iter().map(|(_, h)| h).collect()
+}
+
+/// Add `log_p` to the prefix extended by `token` at `frame`
+fn extend(
+    next: &mut Beams,
+    prefix: &[i64],
+    frames: &[usize],
+    token: i64,
+    frame: usize,
+    log_p: f32,
+) {
+    if log_p == f32::NEG_INFINITY {
+        return;
+    }
+    let mut extended = prefix.to_vec();
+    extended.push(token);
+    let mut frames = frames.to_vec();
+    frames.push(frame);
+    let entry = prefix_entry(next, extended, frames);
+    entry.non_blank = log_add(entry.non_blank, log_p);
+}
+
+/// Score of `prefix` in `next`, keeping the earliest emission frames of
+/// the alignments merged into it
+fn prefix_entry(next: &mut Beams, prefix: Vec<i64>, frames: Vec<usize>) -> &mut PrefixScore {
+    let entry = next
+        .entry(prefix)
+        .or_insert_with(|| (PrefixScore::EMPTY, frames.clone()));
+    if frames < entry.1 {
+        entry.1 = frames;
+    }
+    &mut entry.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_merges_alignments() {
+        // Vocab: 0 = "a", 1 = "b", 2 = blank. Greedy picks blank, blank
+        // ("" at 0.36), but "a" is more likely once its three alignments
+        // (a·, ·a, aa) are added up (0.64)
+        let probs = [[0.4f32, 0.0, 0.6], [0.4, 0.0, 0.6]];
+        let logits: Vec<f32> = probs
+            .iter()
+            .flatten()
+            .map(|&p| if p > 0.0 { p.ln() } else { -1e9 })
+            .collect();
+
+        let hypotheses = beam_search(&logits, 3, 2, 4, 3, |_| 0.0);
+        assert_eq!(hypotheses[0].tokens, vec![0]);
+        assert_eq!(hypotheses[0].frames, vec![0]);
+        assert!((hypotheses[0].score.exp() - 0.64).abs() < 1e-4);
+        assert_eq!(hypotheses[1].tokens, Vec::<i64>::new());
+        assert!((hypotheses[1].score.exp() - 0.36).abs() < 1e-4);
+        assert!(hypotheses.iter().all(|h| h.tokens != vec![0, 0]));
+
+        assert_eq!(hypotheses[0].frame_tokens(2, 2), vec![0, 2]);
+        assert_eq!(beam_search(&logits, 3, 2, 4, 1, |_| 0.0).len(), 1);
+
+        // A language model bonus for "b" ranks it first although the
+        // acoustics prefer "a"
+        let probs = [[0.5f32, 0.4, 0.1]];
+        let logits: Vec<f32> = probs.iter().flatten().map(|p| p.ln()).collect();
+        let fused = beam_search(
+            &logits,
+            3,
+            2,
+            2,
+            1,
+            |prefix| {
+                if prefix == [1] { 1.0 } else { 0.0 }
+            },
+        );
+        assert_eq!(fused[0].tokens, vec![1]);
+        assert!((fused[0].score.exp() - 0.4).abs() < 1e-4);
+    }
+}
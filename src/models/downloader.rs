@@ -1,20 +1,125 @@
 //! Model Downloader
 //!
 //! Downloads Whisper models from Hugging Face with progress tracking.
-
+//! Partial files are kept as `<file>.tmp` and resumed with an HTTP range
+//! request, so a paused or interrupted download picks up where it stopped.
+//!
+//! There is no limit on a download's total time, which large models on slow
+//! links would exceed. Instead each response and each chunk must arrive
+//! within the stall timeout; a stalled attempt is retried from the bytes
+//! already received.
+//!
+//! Each downloaded file is hashed (SHA-256) and checked against the
+//! registry's checksum when it has one. The hashes are recorded in the
+//! version's `checksums.json`, so `onevox models verify` can detect later
+//! corruption even for files the registry has no checksum for.
+
+use crate::daemon::EventBus;
+use crate::ipc::Event;
+use crate::models::onnx_dylib::sha256_file;
 use crate::models::registry::ModelMetadata;
 use anyhow::{Context, Result};
 use futures::StreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+/// Minimum bytes between download progress events
+const PROGRESS_EVENT_BYTES: u64 = 1024 * 1024;
+
+/// Default time to wait for the next chunk before retrying
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Stall timeout from a number of seconds, where 0 means none
+pub fn stall_timeout_from_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Name given to files migrated from the pre-versioning flat layout
+const LEGACY_VERSION: &str = "00000000-legacy";
+
+/// Checksums recorded in a version directory as its files are downloaded
+const CHECKSUMS_FILE: &str = "checksums.json";
+
+/// Error returned when a download is stopped through its cancellation token
+///
+/// Partial files are left in place; remove them with
+/// [`ModelDownloader::discard_partial`] or resume by downloading again.
+#[derive(Debug)]
+pub struct DownloadCancelled;
+
+impl std::fmt::Display for DownloadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Download cancelled")
+    }
+}
+
+impl std::error::Error for DownloadCancelled {}
+
+/// A downloaded version of a model
+#[derive(Debug, Clone)]
+pub struct ModelVersion {
+    /// Version label (download timestamp, e.g. "20250101-120000")
+    pub label: String,
+    /// Directory containing the version's files
+    pub path: PathBuf,
+    /// Whether this is the active version
+    pub is_current: bool,
+    /// Size on disk in bytes
+    pub size_bytes: u64,
+}
+
+/// Outcome of re-hashing a model file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Matches the registry checksum
+    Verified,
+    /// Matches the checksum recorded when it was downloaded
+    MatchesRecorded,
+    /// No checksum to compare against
+    Unchecked,
+    /// Differs from the expected checksum
+    Mismatch { expected: String },
+    /// Not on disk
+    Missing,
+}
+
+/// Result of verifying one file of a downloaded model
+#[derive(Debug, Clone)]
+pub struct FileCheck {
+    /// File name, as listed in the registry
+    pub file: String,
+    /// SHA-256 of the file on disk
+    pub sha256: Option<String>,
+    pub status: FileStatus,
+}
+
+impl FileCheck {
+    /// Whether the file is present and not known to be corrupt
+    pub fn is_ok(&self) -> bool {
+        !matches!(
+            self.status,
+            FileStatus::Mismatch { .. } | FileStatus::Missing
+        )
+    }
+}
+
 /// Model downloader
+///
+/// Models are stored as `<model_id>/versions/<label>/` with a `current`
+/// symlink (or `CURRENT` pointer file) selecting the active version, so a
+/// regressed upstream update can be rolled back.
 pub struct ModelDownloader {
     cache_dir: PathBuf,
     client: reqwest::Client,
+    events: Option<EventBus>,
+    cancel: Option<CancellationToken>,
+    stall_timeout: Option<Duration>,
 }
 
 impl ModelDownloader {
@@ -24,7 +129,6 @@ impl ModelDownloader {
 
         let client = reqwest::Client::builder()
             .user_agent("onevox/0.1.0")
-            .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout
             .connect_timeout(std::time::Duration::from_secs(30)) // 30 second connect timeout
             .pool_max_idle_per_host(10) // Connection pooling
             .pool_idle_timeout(std::time::Duration::from_secs(90))
@@ -32,7 +136,57 @@ impl ModelDownloader {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { cache_dir, client })
+        Ok(Self {
+            cache_dir,
+            client,
+            events: None,
+            cancel: None,
+            stall_timeout: Some(DEFAULT_STALL_TIMEOUT),
+        })
+    }
+
+    /// Publish download progress to an event bus
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Stop downloading when `cancel` is triggered
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Give up on an attempt when no data arrives for `timeout`
+    /// (`None` waits indefinitely)
+    pub fn with_stall_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.stall_timeout = timeout;
+        self
+    }
+
+    /// Wait for `future`, failing once the stall timeout passes
+    async fn within_stall_timeout<T>(
+        &self,
+        future: impl std::future::Future<Output = T>,
+    ) -> Result<T> {
+        match self.stall_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| {
+                anyhow::anyhow!("Download stalled: no data for {}s", timeout.as_secs_f32())
+            }),
+            None => Ok(future.await),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_cancelled())
+    }
+
+    fn publish(&self, event: Event) {
+        if let Some(events) = &self.events {
+            events.publish(event);
+        }
     }
 
     /// Get the model cache directory
@@ -40,11 +194,20 @@ impl ModelDownloader {
         crate::platform::models_dir().context("Failed to get models directory")
     }
 
-    /// Get the directory for a specific model
+    /// Get the directory holding the active version of a model
     pub fn model_dir(&self, model_id: &str) -> PathBuf {
+        crate::platform::paths::active_model_dir(&self.model_root(model_id))
+    }
+
+    /// Get the top-level directory for a model (all versions)
+    pub fn model_root(&self, model_id: &str) -> PathBuf {
         self.cache_dir.join(model_id)
     }
 
+    fn versions_dir(&self, model_id: &str) -> PathBuf {
+        self.model_root(model_id).join("versions")
+    }
+
     /// Check if a model is already downloaded
     pub async fn is_downloaded(&self, metadata: &ModelMetadata) -> bool {
         let model_dir = self.model_dir(&metadata.id);
@@ -60,11 +223,36 @@ impl ModelDownloader {
         true
     }
 
-    /// Download a model
+    /// Download a model (resumes into the active version if one exists)
     pub async fn download(&self, metadata: &ModelMetadata) -> Result<PathBuf> {
-        let model_dir = self.model_dir(&metadata.id);
+        self.download_version(metadata, false).await
+    }
 
-        info!("Downloading model: {} to {:?}", metadata.name, model_dir);
+    /// Download a model, optionally into a fresh version directory
+    ///
+    /// With `new_version`, files are fetched into a new version directory and
+    /// the previous version is kept for `onevox models rollback`.
+    pub async fn download_version(
+        &self,
+        metadata: &ModelMetadata,
+        new_version: bool,
+    ) -> Result<PathBuf> {
+        self.migrate_legacy_layout(&metadata.id).await?;
+
+        let label = match self.current_version(&metadata.id).await {
+            Some(current) if !new_version => current,
+            // Resume a paused download instead of starting a new version
+            _ => match self.partial_version(metadata).await {
+                Some(partial) => partial,
+                None => chrono::Local::now().format("%Y%m%d-%H%M%S").to_string(),
+            },
+        };
+        let model_dir = self.versions_dir(&metadata.id).join(&label);
+
+        info!(
+            "Downloading model: {} (version {}) to {:?}",
+            metadata.name, label, model_dir
+        );
 
         // Note: GGML models now use native whisper.cpp bindings (no CLI needed)
         // The whisper-rs crate provides direct library access
@@ -74,6 +262,8 @@ impl ModelDownloader {
             .await
             .context("Failed to create model directory")?;
 
+        let mut checksums = Self::read_checksums(&model_dir).await;
+
         // Download each file
         let urls = metadata.download_urls();
         for (file, url) in urls {
@@ -87,25 +277,18 @@ impl ModelDownloader {
                     .context("Failed to create file parent directory")?;
             }
 
-            // Clean stale temporary artifact before proceeding.
-            if temp_path.exists()
-                && let Err(e) = fs::remove_file(&temp_path).await
-            {
-                warn!(
-                    "Could not remove stale temporary model file {}: {}",
-                    temp_path.display(),
-                    e
-                );
-            }
-
             // Skip if already exists
             if file_path.exists() {
                 info!("File already exists: {}", file);
+                Self::cleanup_temp_file(&temp_path).await;
                 continue;
             }
 
             info!("Downloading: {} from {}", file, url);
-            if let Err(e) = self.download_file(&url, &file_path).await {
+            if let Err(e) = self.download_file(&metadata.id, &url, &file_path).await {
+                if e.is::<DownloadCancelled>() {
+                    return Err(e);
+                }
                 return Err(anyhow::anyhow!(
                     "Failed to download '{}' from '{}': {}. \
                      You can manually download this file and place it at '{}'.",
@@ -117,22 +300,230 @@ impl ModelDownloader {
             }
 
             // Verify downloaded artifact when checksum is available.
+            let actual = Self::hash_file(&file_path).await?;
             if let Some(expected_sha) = metadata.file_sha256.get(&file) {
-                if let Err(e) = self.verify_checksum(&file_path, expected_sha).await {
+                if !actual.eq_ignore_ascii_case(expected_sha) {
                     // Remove corrupted final artifact so future runs can re-download cleanly.
                     let _ = fs::remove_file(&file_path).await;
-                    return Err(e);
+                    anyhow::bail!(
+                        "Checksum mismatch for {}. expected={}, actual={}",
+                        file_path.display(),
+                        expected_sha.to_ascii_lowercase(),
+                        actual
+                    );
                 }
                 info!("Checksum verified: {}", file);
             }
+            checksums.insert(file, actual);
+            Self::write_checksums(&model_dir, &checksums).await?;
+        }
+
+        self.set_current_version(&metadata.id, &label).await?;
+        self.publish(Event::DownloadComplete {
+            model: metadata.id.clone(),
+        });
+
+        info!(
+            "✅ Model downloaded successfully: {} (version {})",
+            metadata.id, label
+        );
+        Ok(self.model_dir(&metadata.id))
+    }
+
+    /// Newest non-active version holding partially downloaded files
+    async fn partial_version(&self, metadata: &ModelMetadata) -> Option<String> {
+        let versions = self.list_versions(&metadata.id).await.ok()?;
+        versions
+            .into_iter()
+            .rev()
+            .filter(|version| !version.is_current)
+            .find(|version| {
+                metadata
+                    .files
+                    .iter()
+                    .any(|file| Self::temp_path(&version.path.join(file)).exists())
+            })
+            .map(|version| version.label)
+    }
+
+    /// Remove partially downloaded files left by a paused download
+    pub async fn discard_partial(&self, metadata: &ModelMetadata) -> Result<()> {
+        for version in self.list_versions(&metadata.id).await? {
+            for file in &metadata.files {
+                Self::cleanup_temp_file(&Self::temp_path(&version.path.join(file))).await;
+            }
+            // Versions that never finished have nothing else in them
+            if !version.is_current && !Self::has_files(&version.path, &metadata.files) {
+                let _ = fs::remove_dir_all(&version.path).await;
+            }
+        }
+        if self.list_versions(&metadata.id).await?.is_empty() {
+            let _ = fs::remove_dir_all(self.model_root(&metadata.id)).await;
+        }
+        Ok(())
+    }
+
+    fn has_files(dir: &Path, files: &[String]) -> bool {
+        files.iter().any(|file| dir.join(file).exists())
+    }
+
+    /// Label of the active version, if the model uses the versioned layout
+    pub async fn current_version(&self, model_id: &str) -> Option<String> {
+        let label = fs::read_to_string(self.model_root(model_id).join("CURRENT"))
+            .await
+            .ok()?;
+        let label = label.trim().to_string();
+        self.versions_dir(model_id)
+            .join(&label)
+            .is_dir()
+            .then_some(label)
+    }
+
+    /// List downloaded versions of a model, oldest first
+    pub async fn list_versions(&self, model_id: &str) -> Result<Vec<ModelVersion>> {
+        let versions_dir = self.versions_dir(model_id);
+        if !versions_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let current = self.current_version(model_id).await;
+        let mut versions = vec![];
+        let mut entries = fs::read_dir(&versions_dir)
+            .await
+            .context("Failed to read model versions directory")?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            let Some(label) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let path = entry.path();
+            versions.push(ModelVersion {
+                is_current: current.as_deref() == Some(label.as_str()),
+                size_bytes: Self::dir_size(&path).await.unwrap_or(0),
+                label,
+                path,
+            });
         }
 
-        info!("✅ Model downloaded successfully: {}", metadata.id);
-        Ok(model_dir)
+        versions.sort_by(|a, b| a.label.cmp(&b.label));
+        Ok(versions)
+    }
+
+    /// Make `label` the active version of a model
+    pub async fn set_current_version(&self, model_id: &str, label: &str) -> Result<()> {
+        let root = self.model_root(model_id);
+        if !self.versions_dir(model_id).join(label).is_dir() {
+            anyhow::bail!("Version '{}' of model '{}' not found", label, model_id);
+        }
+
+        // Pointer file works everywhere; the symlink is a convenience on Unix.
+        fs::write(root.join("CURRENT"), label)
+            .await
+            .context("Failed to write current version pointer")?;
+
+        #[cfg(unix)]
+        {
+            let link = root.join("current");
+            let temp_link = root.join("current.tmp");
+            let _ = fs::remove_file(&temp_link).await;
+            std::os::unix::fs::symlink(Path::new("versions").join(label), &temp_link)
+                .context("Failed to create current version symlink")?;
+            // rename() atomically replaces the previous symlink
+            fs::rename(&temp_link, &link)
+                .await
+                .context("Failed to activate current version symlink")?;
+        }
+
+        info!("Model {} now using version {}", model_id, label);
+        Ok(())
+    }
+
+    /// Switch back to an earlier version of a model
+    ///
+    /// Without `to`, selects the version immediately preceding the active one.
+    /// Returns the label of the newly active version.
+    pub async fn rollback(&self, model_id: &str, to: Option<&str>) -> Result<String> {
+        self.migrate_legacy_layout(model_id).await?;
+
+        let versions = self.list_versions(model_id).await?;
+        if versions.is_empty() {
+            anyhow::bail!("Model '{}' is not downloaded", model_id);
+        }
+
+        let target = match to {
+            Some(label) => versions
+                .iter()
+                .find(|v| v.label == label)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Version '{}' of model '{}' not found", label, model_id)
+                })?
+                .label
+                .clone(),
+            None => {
+                let current_idx = versions
+                    .iter()
+                    .position(|v| v.is_current)
+                    .unwrap_or(versions.len() - 1);
+                if current_idx == 0 {
+                    anyhow::bail!(
+                        "No older version of '{}' to roll back to (only {} downloaded)",
+                        model_id,
+                        versions[current_idx].label
+                    );
+                }
+                versions[current_idx - 1].label.clone()
+            }
+        };
+
+        self.set_current_version(model_id, &target).await?;
+        Ok(target)
+    }
+
+    /// Move files from the old flat layout into a versioned directory
+    async fn migrate_legacy_layout(&self, model_id: &str) -> Result<()> {
+        let root = self.model_root(model_id);
+        if !root.is_dir() || self.versions_dir(model_id).exists() {
+            return Ok(());
+        }
+
+        let mut entries = fs::read_dir(&root).await?;
+        let mut legacy_entries = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            legacy_entries.push(entry.path());
+        }
+        if legacy_entries.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Migrating model {} to versioned layout ({} entries)",
+            model_id,
+            legacy_entries.len()
+        );
+
+        let legacy_dir = self.versions_dir(model_id).join(LEGACY_VERSION);
+        fs::create_dir_all(&legacy_dir)
+            .await
+            .context("Failed to create legacy version directory")?;
+        for path in legacy_entries {
+            if let Some(name) = path.file_name() {
+                fs::rename(&path, legacy_dir.join(name))
+                    .await
+                    .with_context(|| format!("Failed to migrate {}", path.display()))?;
+            }
+        }
+
+        self.set_current_version(model_id, LEGACY_VERSION).await
     }
 
     /// Download a single file with progress bar and retry logic
-    async fn download_file(&self, url: &str, dest: &Path) -> Result<()> {
+    ///
+    /// Retries continue from the bytes already received. A cancelled
+    /// download keeps its partial file; a failed one removes it.
+    async fn download_file(&self, model_id: &str, url: &str, dest: &Path) -> Result<()> {
         const MAX_RETRIES: u32 = 3;
         const INITIAL_BACKOFF: u64 = 1000; // 1 second
 
@@ -140,11 +531,11 @@ impl ModelDownloader {
         let temp_path = Self::temp_path(dest);
 
         for attempt in 1..=MAX_RETRIES {
-            match self.download_file_attempt(url, dest).await {
+            match self.download_file_attempt(model_id, url, dest).await {
                 Ok(()) => return Ok(()),
+                Err(e) if e.is::<DownloadCancelled>() => return Err(e),
                 Err(e) => {
                     last_error = Some(e);
-                    Self::cleanup_temp_file(&temp_path).await;
 
                     if attempt < MAX_RETRIES {
                         let backoff_ms = INITIAL_BACKOFF * 2u64.pow(attempt - 1);
@@ -164,22 +555,50 @@ impl ModelDownloader {
             .unwrap_or_else(|| anyhow::anyhow!("Download failed after {} retries", MAX_RETRIES)))
     }
 
-    /// Single download attempt
-    async fn download_file_attempt(&self, url: &str, dest: &Path) -> Result<()> {
-        // Send request
-        let response = self
-            .client
-            .get(url)
-            .send()
+    /// Single download attempt, resuming from a partial temp file
+    async fn download_file_attempt(&self, model_id: &str, url: &str, dest: &Path) -> Result<()> {
+        let temp_path = Self::temp_path(dest);
+        let mut resume_from = fs::metadata(&temp_path)
             .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+
+        // Send request
+        let mut request = self.client.get(url);
+        if resume_from > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+        }
+        let mut response = self
+            .within_stall_timeout(request.send())
+            .await?
             .context("Failed to send download request")?;
 
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file doesn't fit the remote one; start over
+            fs::remove_file(&temp_path)
+                .await
+                .context("Failed to remove stale temporary file")?;
+            resume_from = 0;
+            response = self
+                .within_stall_timeout(self.client.get(url).send())
+                .await?
+                .context("Failed to send download request")?;
+        }
+
         if !response.status().is_success() {
             anyhow::bail!("Download failed with status: {}", response.status());
         }
 
+        // Servers that ignore the range send the whole file again
+        if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+            resume_from = 0;
+        }
+
         // Get file size for progress bar
-        let total_size = response.content_length().unwrap_or(0);
+        let total_size = response
+            .content_length()
+            .map(|remaining| remaining + resume_from)
+            .unwrap_or(0);
 
         // Create progress bar
         let pb = ProgressBar::new(total_size);
@@ -196,28 +615,68 @@ impl ModelDownloader {
                 .unwrap_or_else(|| "model".to_string())
         ));
 
-        // Create temporary file in the same directory for atomic finalize.
-        let temp_path = Self::temp_path(dest);
-        if temp_path.exists() {
-            fs::remove_file(&temp_path)
+        // Temporary file in the same directory for atomic finalize, appended
+        // to when resuming
+        let mut file = if resume_from > 0 {
+            info!("Resuming {} at {} bytes", dest.display(), resume_from);
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&temp_path)
                 .await
-                .context("Failed to remove stale temporary file")?;
-        }
-        let mut file = fs::File::create(&temp_path)
-            .await
-            .context("Failed to create temporary file")?;
+                .context("Failed to open temporary file")?
+        } else {
+            fs::File::create(&temp_path)
+                .await
+                .context("Failed to create temporary file")?
+        };
 
         // Download with progress
-        let mut downloaded: u64 = 0;
+        let mut downloaded: u64 = resume_from;
+        let mut last_event: u64 = 0;
+        pb.set_position(downloaded);
         let mut stream = response.bytes_stream();
-
-        while let Some(chunk) = stream.next().await {
+        let file_name = dest
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let cancel = self.cancel.clone().unwrap_or_default();
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = self.within_stall_timeout(stream.next()) => chunk,
+                _ = cancel.cancelled() => Ok(None),
+            };
+            if self.is_cancelled() {
+                file.flush().await.ok();
+                pb.abandon_with_message(format!("Stopped {}", file_name));
+                return Err(DownloadCancelled.into());
+            }
+            let chunk = match chunk {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    // Keep what arrived for the retry to resume from
+                    file.flush().await.ok();
+                    pb.abandon_with_message(format!("Stalled {}", file_name));
+                    return Err(e);
+                }
+            };
             let chunk = chunk.context("Failed to read download chunk")?;
             file.write_all(&chunk)
                 .await
                 .context("Failed to write to file")?;
             downloaded += chunk.len() as u64;
             pb.set_position(downloaded);
+
+            if downloaded - last_event >= PROGRESS_EVENT_BYTES || downloaded == total_size {
+                last_event = downloaded;
+                self.publish(Event::DownloadProgress {
+                    model: model_id.to_string(),
+                    file: file_name.clone(),
+                    downloaded_bytes: downloaded,
+                    total_bytes: total_size,
+                });
+            }
         }
         file.flush()
             .await
@@ -271,57 +730,81 @@ impl ModelDownloader {
         }
     }
 
-    async fn verify_checksum(&self, path: &Path, expected_sha256: &str) -> Result<()> {
-        let file = path.to_path_buf();
-        let expected = expected_sha256.to_ascii_lowercase();
-
-        let actual = tokio::task::spawn_blocking(move || -> Result<String> {
-            let file_str = file.to_string_lossy().to_string();
+    /// SHA-256 of a file, hashed off the async runtime
+    async fn hash_file(path: &Path) -> Result<String> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || sha256_file(&path)).await?
+    }
 
-            let candidates: [(&str, Vec<&str>); 3] = [
-                ("sha256sum", vec![&file_str]),
-                ("shasum", vec!["-a", "256", &file_str]),
-                ("openssl", vec!["dgst", "-sha256", &file_str]),
-            ];
+    /// Checksums recorded for a version's files
+    async fn read_checksums(version_dir: &Path) -> BTreeMap<String, String> {
+        match fs::read_to_string(version_dir.join(CHECKSUMS_FILE)).await {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!(
+                    "Ignoring invalid {} in {:?}: {}",
+                    CHECKSUMS_FILE, version_dir, e
+                );
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        }
+    }
 
-            for (bin, args) in candidates {
-                let output = std::process::Command::new(bin).args(args).output();
-                let Ok(output) = output else {
-                    continue;
-                };
+    async fn write_checksums(
+        version_dir: &Path,
+        checksums: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        fs::write(
+            version_dir.join(CHECKSUMS_FILE),
+            serde_json::to_string_pretty(checksums)?,
+        )
+        .await
+        .context("Failed to record checksums")
+    }
 
-                if !output.status.success() {
-                    continue;
-                }
+    /// Re-hash the active version's files against the registry checksums,
+    /// falling back to the ones recorded at download time
+    pub async fn verify(&self, metadata: &ModelMetadata) -> Result<Vec<FileCheck>> {
+        let model_dir = self.model_dir(&metadata.id);
+        let recorded = Self::read_checksums(&model_dir).await;
 
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                if let Some(hash) = parse_sha256_from_output(&stdout) {
-                    return Ok(hash.to_ascii_lowercase());
-                }
+        let mut checks = Vec::with_capacity(metadata.files.len());
+        for file in &metadata.files {
+            let path = model_dir.join(file);
+            if !path.is_file() {
+                checks.push(FileCheck {
+                    file: file.clone(),
+                    sha256: None,
+                    status: FileStatus::Missing,
+                });
+                continue;
             }
 
-            anyhow::bail!(
-                "No checksum tool available (tried: sha256sum, shasum, openssl). \
-                 Install one to enable model integrity verification."
-            )
-        })
-        .await??;
-
-        if !actual.eq_ignore_ascii_case(expected_sha256) {
-            anyhow::bail!(
-                "Checksum mismatch for {}. expected={}, actual={}",
-                path.display(),
-                expected,
-                actual
-            );
+            let actual = Self::hash_file(&path).await?;
+            // A registry checksum wins over the recorded one
+            let (expected, matched) = match metadata.file_sha256.get(file) {
+                Some(expected) => (Some(expected), FileStatus::Verified),
+                None => (recorded.get(file), FileStatus::MatchesRecorded),
+            };
+            let status = match expected {
+                Some(expected) if expected.eq_ignore_ascii_case(&actual) => matched,
+                Some(expected) => FileStatus::Mismatch {
+                    expected: expected.to_ascii_lowercase(),
+                },
+                None => FileStatus::Unchecked,
+            };
+            checks.push(FileCheck {
+                file: file.clone(),
+                sha256: Some(actual),
+                status,
+            });
         }
-
-        Ok(())
+        Ok(checks)
     }
 
     /// Remove a downloaded model
     pub async fn remove(&self, model_id: &str) -> Result<()> {
-        let model_dir = self.model_dir(model_id);
+        let model_dir = self.model_root(model_id);
 
         if model_dir.exists() {
             info!("Removing model: {} from {:?}", model_id, model_dir);
@@ -361,9 +844,9 @@ impl ModelDownloader {
         Ok(models)
     }
 
-    /// Get the size of a downloaded model
+    /// Get the size of a downloaded model (all versions)
     pub async fn model_size(&self, model_id: &str) -> Result<u64> {
-        let model_dir = self.model_dir(model_id);
+        let model_dir = self.model_root(model_id);
 
         if !model_dir.exists() {
             return Ok(0);
@@ -395,22 +878,6 @@ impl ModelDownloader {
     }
 }
 
-fn parse_sha256_from_output(output: &str) -> Option<String> {
-    if let Some(first) = output.split_whitespace().next()
-        && first.len() == 64
-        && first.chars().all(|c| c.is_ascii_hexdigit())
-    {
-        return Some(first.to_string());
-    }
-
-    output
-        .split('=')
-        .nth(1)
-        .map(str::trim)
-        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
-        .map(ToOwned::to_owned)
-}
-
 impl Default for ModelDownloader {
     fn default() -> Self {
         Self::new().expect("Failed to create model downloader")
@@ -427,4 +894,135 @@ mod tests {
         assert!(cache_dir.to_string_lossy().contains("onevox"));
         assert!(cache_dir.to_string_lossy().contains("models"));
     }
+
+    #[tokio::test]
+    async fn test_partial_downloads() {
+        let cache = tempfile::tempdir().unwrap();
+        let downloader = ModelDownloader {
+            cache_dir: cache.path().to_path_buf(),
+            client: reqwest::Client::new(),
+            events: None,
+            cancel: None,
+            stall_timeout: None,
+        };
+        let metadata = crate::models::ModelRegistry::new()
+            .get_model("ggml-base.en")
+            .unwrap()
+            .clone();
+
+        // A paused download leaves a temp file in a version that isn't active
+        let version = downloader
+            .versions_dir(&metadata.id)
+            .join("20250101-120000");
+        std::fs::create_dir_all(&version).unwrap();
+        let partial = ModelDownloader::temp_path(&version.join(&metadata.files[0]));
+        std::fs::write(&partial, b"partial").unwrap();
+        assert_eq!(
+            downloader.partial_version(&metadata).await.as_deref(),
+            Some("20250101-120000")
+        );
+
+        downloader.discard_partial(&metadata).await.unwrap();
+        assert!(!partial.exists());
+        assert!(!downloader.model_root(&metadata.id).exists());
+        assert_eq!(downloader.partial_version(&metadata).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksums() {
+        let cache = tempfile::tempdir().unwrap();
+        let downloader = ModelDownloader {
+            cache_dir: cache.path().to_path_buf(),
+            client: reqwest::Client::new(),
+            events: None,
+            cancel: None,
+            stall_timeout: None,
+        };
+        let mut metadata = crate::models::ModelRegistry::new()
+            .get_model("parakeet-ctc-0.6b")
+            .unwrap()
+            .clone();
+        let version = downloader
+            .versions_dir(&metadata.id)
+            .join("20250101-120000");
+        std::fs::create_dir_all(&version).unwrap();
+        std::fs::write(version.join("model.int8.onnx"), b"model").unwrap();
+        std::fs::write(version.join("vocab.txt"), b"vocab").unwrap();
+        downloader
+            .set_current_version(&metadata.id, "20250101-120000")
+            .await
+            .unwrap();
+
+        let vocab = sha256_file(&version.join("vocab.txt")).unwrap();
+        let recorded = BTreeMap::from([
+            ("vocab.txt".to_string(), vocab),
+            ("model.int8.onnx".to_string(), "0".repeat(64)),
+        ]);
+        ModelDownloader::write_checksums(&version, &recorded)
+            .await
+            .unwrap();
+        // "hello" in the registry, which wins over the recorded checksum
+        metadata.file_sha256.insert(
+            "model.int8.onnx".to_string(),
+            "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824".to_string(),
+        );
+
+        let checks = downloader.verify(&metadata).await.unwrap();
+        assert_eq!(
+            checks[0].status,
+            FileStatus::Mismatch {
+                expected: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                    .to_string()
+            }
+        );
+        assert_eq!(checks[1].status, FileStatus::MatchesRecorded);
+        assert_eq!(checks[2].status, FileStatus::Missing);
+        assert!(!checks[0].is_ok() && checks[1].is_ok());
+
+        std::fs::write(version.join("model.int8.onnx"), b"hello").unwrap();
+        std::fs::write(version.join("config.json"), b"{}").unwrap();
+        let checks = downloader.verify(&metadata).await.unwrap();
+        assert_eq!(checks[0].status, FileStatus::Verified);
+        assert_eq!(checks[2].status, FileStatus::Unchecked);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_download_keeps_partial_file() {
+        use tokio::io::AsyncWriteExt;
+
+        // Sends the headers and part of the body, then goes quiet
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = tokio::io::AsyncReadExt::read(&mut socket, &mut request).await;
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc")
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.bin");
+        let downloader = ModelDownloader {
+            cache_dir: dir.path().to_path_buf(),
+            client: reqwest::Client::builder().no_proxy().build().unwrap(),
+            events: None,
+            cancel: None,
+            stall_timeout: Some(Duration::from_millis(200)),
+        };
+
+        let error = downloader
+            .download_file_attempt("test", &url, &dest)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("stalled"), "{}", error);
+        assert!(!dest.exists());
+        assert_eq!(
+            std::fs::read(ModelDownloader::temp_path(&dest)).unwrap(),
+            b"abc"
+        );
+    }
 }
@@ -0,0 +1,405 @@
+//! Ensemble Backend
+//!
+//! Runs several backends on the same segment and reconciles their output,
+//! trading latency for accuracy (legal/medical dictation). Two strategies:
+//!
+//! - `confidence`: keep the hypothesis with the highest confidence
+//! - `vote`: ROVER-style word voting. Every hypothesis is aligned to the
+//!   most confident one and each word position goes to the candidate with
+//!   the most confidence-weighted votes. With two backends a disagreement
+//!   is a tie, which the more confident hypothesis wins.
+
+use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, Transcription};
+use crate::vocabulary::Vocabulary;
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+/// How member hypotheses are reconciled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnsembleStrategy {
+    /// Highest-confidence hypothesis wins
+    #[default]
+    Confidence,
+    /// Word-level voting across hypotheses
+    Vote,
+}
+
+impl EnsembleStrategy {
+    /// Parse a config value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "confidence" => Some(Self::Confidence),
+            "vote" | "rover" => Some(Self::Vote),
+            _ => None,
+        }
+    }
+}
+
+/// Backend that transcribes with every member and reconciles the results
+pub struct EnsembleRuntime {
+    /// Members with the model path each one loads
+    members: Vec<(String, Box<dyn ModelRuntime>)>,
+    strategy: EnsembleStrategy,
+    name: String,
+}
+
+impl EnsembleRuntime {
+    /// Create an ensemble; the first member is the primary backend
+    pub fn new(members: Vec<(String, Box<dyn ModelRuntime>)>, strategy: EnsembleStrategy) -> Self {
+        let name = format!(
+            "ensemble({})",
+            members
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>()
+                .join("+")
+        );
+        Self {
+            members,
+            strategy,
+            name,
+        }
+    }
+}
+
+impl ModelRuntime for EnsembleRuntime {
+    fn load(&mut self, config: ModelConfig) -> crate::Result<()> {
+        for (model_path, model) in &mut self.members {
+            info!("Loading ensemble member {}", model_path);
+            model.load(ModelConfig {
+                model_path: model_path.clone(),
+                ..config.clone()
+            })?;
+        }
+        Ok(())
+    }
+
+    fn is_loaded(&self) -> bool {
+        !self.members.is_empty() && self.members.iter().all(|(_, m)| m.is_loaded())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.members
+            .first()
+            .map(|(_, m)| m.sample_rate())
+            .unwrap_or(16000)
+    }
+
+    fn transcribe(&mut self, samples: &[f32], sample_rate: u32) -> crate::Result<Transcription> {
+        let start = Instant::now();
+
+        // Members run in parallel; each owns its own model state
+        let results: Vec<(String, crate::Result<Transcription>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .members
+                .iter_mut()
+                .map(|(path, model)| {
+                    let path = path.clone();
+                    scope.spawn(move || (path, model.transcribe(samples, sample_rate)))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        (
+                            "unknown".to_string(),
+                            Err(crate::Error::Model("Ensemble member panicked".to_string())),
+                        )
+                    })
+                })
+                .collect()
+        });
+
+        let mut hypotheses = Vec::new();
+        let mut first_error = None;
+        for (path, result) in results {
+            match result {
+                Ok(transcription) => {
+                    debug!(
+                        "{} (confidence {:?}): {}",
+                        path, transcription.confidence, transcription.text
+                    );
+                    hypotheses.push(transcription);
+                }
+                Err(e) => {
+                    warn!("Ensemble member {} failed: {}", path, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if hypotheses.is_empty() {
+            return Err(first_error
+                .unwrap_or_else(|| crate::Error::Model("Ensemble has no members".to_string())));
+        }
+
+        let mut transcription = match self.strategy {
+            EnsembleStrategy::Confidence => pick_confident(hypotheses),
+            EnsembleStrategy::Vote => vote(hypotheses),
+        };
+        transcription.processing_time_ms = start.elapsed().as_millis() as u64;
+        Ok(transcription)
+    }
+
+    fn set_language(&mut self, language: Option<&str>) {
+        for (_, model) in &mut self.members {
+            model.set_language(language);
+        }
+    }
+
+    fn set_translate(&mut self, translate: bool) -> bool {
+        self.members.iter_mut().fold(true, |all, (_, model)| {
+            model.set_translate(translate) && all
+        })
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: &Vocabulary) -> bool {
+        self.members.iter_mut().fold(false, |any, (_, model)| {
+            model.set_vocabulary(vocabulary) || any
+        })
+    }
+
+    fn set_prompt(&mut self, prompt: Option<&str>) -> bool {
+        self.members
+            .iter_mut()
+            .fold(false, |any, (_, model)| model.set_prompt(prompt) || any)
+    }
+
+    fn unload(&mut self) {
+        for (_, model) in &mut self.members {
+            model.unload();
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> ModelInfo {
+        let infos: Vec<ModelInfo> = self.members.iter().map(|(_, m)| m.info()).collect();
+        ModelInfo {
+            name: self.name.clone(),
+            size_bytes: infos.iter().map(|i| i.size_bytes).sum(),
+            model_type: "ensemble".to_string(),
+            backend: infos
+                .iter()
+                .map(|i| i.backend.as_str())
+                .collect::<Vec<_>>()
+                .join("+"),
+            gpu_enabled: infos.iter().any(|i| i.gpu_enabled),
+        }
+    }
+}
+
+/// Voting weight of a hypothesis (backends without confidence count as 1)
+fn weight(transcription: &Transcription) -> f32 {
+    transcription.confidence.unwrap_or(1.0).max(0.01)
+}
+
+/// Index of the most confident hypothesis (earliest on ties)
+fn most_confident(hypotheses: &[Transcription]) -> usize {
+    hypotheses.iter().enumerate().fold(0, |best, (i, h)| {
+        if weight(h) > weight(&hypotheses[best]) {
+            i
+        } else {
+            best
+        }
+    })
+}
+
+/// Keep the most confident hypothesis
+pub fn pick_confident(mut hypotheses: Vec<Transcription>) -> Transcription {
+    let best = most_confident(&hypotheses);
+    hypotheses.swap_remove(best)
+}
+
+/// Comparison key for a word (case and punctuation ignored)
+fn word_key(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Alignment step of a hypothesis against the pivot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Hypothesis word `j` sits at pivot position `i`
+    Aligned(usize, usize),
+    /// Pivot word `i` is missing from the hypothesis
+    Deleted(usize),
+    /// Hypothesis word `j` is inserted before pivot position `i`
+    Inserted(usize, usize),
+}
+
+/// Minimum-edit alignment of `hypothesis` to `pivot`
+fn align(pivot: &[String], hypothesis: &[String]) -> Vec<Step> {
+    let (n, m) = (pivot.len(), hypothesis.len());
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = cost[i - 1][j - 1] + usize::from(pivot[i - 1] != hypothesis[j - 1]);
+            cost[i][j] = substitution.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+
+    let mut steps = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0
+            && j > 0
+            && cost[i][j] == cost[i - 1][j - 1] + usize::from(pivot[i - 1] != hypothesis[j - 1])
+        {
+            steps.push(Step::Aligned(i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            steps.push(Step::Deleted(i - 1));
+            i -= 1;
+        } else {
+            steps.push(Step::Inserted(i, j - 1));
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    steps
+}
+
+/// Candidate words for one position: key → (display form, votes)
+type Candidates = HashMap<String, (String, f32)>;
+
+fn add_vote(candidates: &mut Candidates, display: String, votes: f32) {
+    let entry = candidates
+        .entry(word_key(&display))
+        .or_insert((display, 0.0));
+    entry.1 += votes;
+}
+
+/// Winning candidate; the pivot's own word (added first) wins ties
+fn winner(candidates: &Candidates, pivot_key: &str) -> Option<String> {
+    let pivot = candidates.get(pivot_key)?;
+    let best = candidates
+        .iter()
+        .filter(|(key, (_, votes))| key.as_str() != pivot_key && *votes > pivot.1)
+        .max_by(|a, b| a.1.1.total_cmp(&b.1.1))
+        .map(|(_, candidate)| candidate)
+        .unwrap_or(pivot);
+    Some(best.0.clone())
+}
+
+/// Reconcile hypotheses with confidence-weighted word voting
+pub fn vote(mut hypotheses: Vec<Transcription>) -> Transcription {
+    if hypotheses.len() < 2 {
+        return pick_confident(hypotheses);
+    }
+
+    let pivot_index = most_confident(&hypotheses);
+    let pivot = hypotheses.swap_remove(pivot_index);
+    let pivot_words: Vec<String> = pivot.text.split_whitespace().map(str::to_string).collect();
+    let pivot_keys: Vec<String> = pivot_words.iter().map(|w| word_key(w)).collect();
+    let total: f32 = weight(&pivot) + hypotheses.iter().map(weight).sum::<f32>();
+
+    // Substitution votes per pivot word, insertion votes per gap
+    let mut slots: Vec<Candidates> = pivot_words
+        .iter()
+        .map(|word| {
+            let mut candidates = Candidates::new();
+            add_vote(&mut candidates, word.clone(), weight(&pivot));
+            candidates
+        })
+        .collect();
+    let mut gaps: Vec<Candidates> = vec![Candidates::new(); pivot_words.len() + 1];
+
+    for hypothesis in &hypotheses {
+        let words: Vec<&str> = hypothesis.text.split_whitespace().collect();
+        let keys: Vec<String> = words.iter().map(|w| word_key(w)).collect();
+        let w = weight(hypothesis);
+
+        let mut inserted: Vec<Vec<&str>> = vec![Vec::new(); pivot_words.len() + 1];
+        for step in align(&pivot_keys, &keys) {
+            match step {
+                Step::Aligned(i, j) => add_vote(&mut slots[i], words[j].to_string(), w),
+                Step::Deleted(i) => add_vote(&mut slots[i], String::new(), w),
+                Step::Inserted(i, j) => inserted[i].push(words[j]),
+            }
+        }
+        for (gap, words) in gaps.iter_mut().zip(inserted) {
+            if !words.is_empty() {
+                add_vote(gap, words.join(" "), w);
+            }
+        }
+    }
+
+    let mut output: Vec<String> = Vec::new();
+    for i in 0..=pivot_words.len() {
+        // Insertions need a majority of the total weight
+        if let Some((words, _)) = gaps[i]
+            .values()
+            .filter(|(_, votes)| *votes > total / 2.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        {
+            output.push(words.clone());
+        }
+        if i < pivot_words.len()
+            && let Some(word) = winner(&slots[i], &pivot_keys[i])
+            && !word.is_empty()
+        {
+            output.push(word);
+        }
+    }
+
+    let text = output.join(" ");
+    let changed = text != pivot.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    Transcription {
+        // Timestamps belong to the pivot's wording
+        segments: if changed { Vec::new() } else { pivot.segments },
+        words: if changed { Vec::new() } else { pivot.words },
+        text,
+        ..pivot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hypothesis(text: &str, confidence: Option<f32>) -> Transcription {
+        Transcription {
+            confidence,
+            ..Transcription::new(text.to_string())
+        }
+    }
+
+    #[test]
+    fn test_reconcile_hypotheses() {
+        let picked = pick_confident(vec![
+            hypothesis("the bat sat", Some(0.6)),
+            hypothesis("the cat sat", Some(0.9)),
+        ]);
+        assert_eq!(picked.text, "the cat sat");
+
+        // Each backend makes a different mistake; voting fixes all of them
+        let voted = vote(vec![
+            hypothesis("The patient was given 5 mg.", None),
+            hypothesis("The patient was driven 5 mg.", None),
+            hypothesis("The patient was given 5 mg daily.", None),
+            hypothesis("patient was given 5 mg.", None),
+        ]);
+        assert_eq!(voted.text, "The patient was given 5 mg.");
+
+        let voted = vote(vec![
+            hypothesis("dose of ten milligrams", Some(0.4)),
+            hypothesis("a dose of two milligrams", Some(0.5)),
+            hypothesis("a dose of ten milligrams", Some(0.3)),
+        ]);
+        assert_eq!(voted.text, "a dose of ten milligrams");
+    }
+}
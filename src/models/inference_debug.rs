@@ -0,0 +1,164 @@
+//! Inference Debugging
+//!
+//! `--debug-inference` (or `ONEVOX_DEBUG_INFERENCE=1`) turns on debug logs
+//! for the model backends and saves what the ONNX backend computes for each
+//! transcription under `inference-debug/<start time>/` in the log
+//! directory, numbered in order:
+//!
+//! - `0001-mel.npy`: normalized log-mel features, `[n_mels, frames]`
+//! - `0001-logits.npy`: CTC logits, `[frames, vocab]`
+//! - `0001-stats.json`: audio and feature statistics and the decoded tokens
+//!
+//! `.npy` files open with `numpy.load`. Without the flag nothing is written
+//! and the statistics are only logged at debug level.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use tracing::{info, warn};
+
+/// Set to "1" to debug inference; pass it to spawned onevox processes
+pub const DEBUG_INFERENCE_ENV: &str = "ONEVOX_DEBUG_INFERENCE";
+
+/// Directory in the log directory that holds the sessions
+pub const DEBUG_DIR: &str = "inference-debug";
+
+/// Log filter directive for the model backends' debug logs
+pub const LOG_DIRECTIVE: &str = "onevox::models=debug";
+
+/// Turned on from the command line
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// This process's session directory, created on first use
+static SESSION_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Transcriptions dumped so far
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Debug inference in this process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether inference debugging is on
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+        || std::env::var(DEBUG_INFERENCE_ENV).is_ok_and(|value| value == "1")
+}
+
+/// Files for one transcription
+pub struct InferenceDump {
+    dir: PathBuf,
+    index: u32,
+}
+
+impl InferenceDump {
+    /// Start dumping the next transcription, if debugging is on
+    pub fn next() -> Option<Self> {
+        if !enabled() {
+            return None;
+        }
+        let dir = SESSION_DIR.get_or_init(create_session_dir).clone()?;
+        let index = COUNTER.fetch_add(1, Ordering::Relaxed) + 1;
+        Some(Self { dir, index })
+    }
+
+    /// Dump into `dir` instead of the session directory
+    pub fn in_dir(dir: &Path, index: u32) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            index,
+        }
+    }
+
+    /// Path of one of this transcription's files
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{:04}-{}", self.index, name))
+    }
+
+    /// Save a row-major `f32` matrix as `<name>.npy`
+    pub fn matrix(&self, name: &str, rows: usize, cols: usize, data: &[f32]) {
+        let path = self.path(&format!("{}.npy", name));
+        if let Err(e) = std::fs::write(&path, npy(rows, cols, data)) {
+            warn!("Failed to write {}: {}", path.display(), e);
+        }
+    }
+
+    /// Save `value` as `<name>.json`
+    pub fn json(&self, name: &str, value: &impl Serialize) {
+        let path = self.path(&format!("{}.json", name));
+        let result = serde_json::to_vec_pretty(value)
+            .map_err(std::io::Error::other)
+            .and_then(|bytes| std::fs::write(&path, bytes));
+        if let Err(e) = result {
+            warn!("Failed to write {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn create_session_dir() -> Option<PathBuf> {
+    let dir = crate::platform::paths::log_dir()
+        .map(|dir| {
+            dir.join(DEBUG_DIR)
+                .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string())
+        })
+        .and_then(|dir| Ok(std::fs::create_dir_all(&dir).map(|_| dir)?));
+    match dir {
+        Ok(dir) => {
+            info!("Saving inference debug files to {}", dir.display());
+            Some(dir)
+        }
+        Err(e) => {
+            warn!("Inference debug files disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// A `[rows, cols]` little-endian `f32` array in NumPy's `.npy` format
+fn npy(rows: usize, cols: usize, data: &[f32]) -> Vec<u8> {
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, cols
+    );
+    // Magic, version, and header length take 10 bytes; the data starts at
+    // a multiple of 64 after a newline
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut bytes = Vec::with_capacity(10 + header.len() + data.len() * 4);
+    bytes.extend_from_slice(b"\x93NUMPY\x01\x00");
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for value in data {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_files() {
+        let bytes = npy(2, 3, &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'shape': (2, 3)"));
+        assert!(header.ends_with('\n'));
+        assert_eq!(bytes.len(), 10 + header_len + 6 * 4);
+        assert_eq!(&bytes[bytes.len() - 4..], &5.0f32.to_le_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        let dump = InferenceDump::in_dir(dir.path(), 7);
+        dump.matrix("mel", 2, 3, &[0.0; 6]);
+        dump.json("stats", &serde_json::json!({ "frames": 3 }));
+        assert!(dir.path().join("0007-mel.npy").exists());
+        let stats = std::fs::read_to_string(dir.path().join("0007-stats.json")).unwrap();
+        assert!(stats.contains("\"frames\": 3"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Mel Spectrogram Features
+//!
+//! Log-mel feature extraction shared by the ONNX backends. The FFT and
+//! filterbank product run through Apple's Accelerate framework (vDSP) on
+//! macOS when the `accelerate` feature is enabled, and through rustfft
+//! everywhere else. Both paths produce the same features (up to float
+//! rounding).
+
+use std::f32::consts::PI;
+
+/// Window length in samples (25ms at 16kHz)
+pub const WINDOW_SIZE: usize = 400;
+/// Hop length in samples (10ms at 16kHz)
+pub const HOP_SIZE: usize = 160;
+/// FFT size (window zero-padded)
+pub const FFT_SIZE: usize = 512;
+/// Number of FFT bins kept (DC to Nyquist)
+pub const N_FFT_BINS: usize = FFT_SIZE / 2 + 1;
+
+const SAMPLE_RATE: f32 = 16000.0;
+const MEL_MIN_HZ: f32 = 0.0;
+const MEL_MAX_HZ: f32 = 8000.0;
+
+/// Log-mel feature extractor for 16kHz audio
+pub struct MelExtractor {
+    n_mels: usize,
+    window: Vec<f32>,
+    /// Filterbank, [n_fft_bins][n_mels] row-major
+    filterbank: Vec<f32>,
+}
+
+impl MelExtractor {
+    /// Create an extractor producing `n_mels` bins per frame
+    pub fn new(n_mels: usize) -> Self {
+        let window = (0..WINDOW_SIZE)
+            .map(|i| 0.5 * (1.0 - ((2.0 * PI * i as f32) / (WINDOW_SIZE as f32 - 1.0)).cos()))
+            .collect();
+
+        // Transposed so each frame's power spectrum multiplies it directly
+        let filters = create_mel_filterbank(n_mels, FFT_SIZE, MEL_MIN_HZ, MEL_MAX_HZ, SAMPLE_RATE);
+        let mut filterbank = vec![0.0f32; N_FFT_BINS * n_mels];
+        for (mel, filter) in filters.iter().enumerate() {
+            for (bin, &weight) in filter.iter().enumerate() {
+                filterbank[bin * n_mels + mel] = weight;
+            }
+        }
+
+        Self {
+            n_mels,
+            window,
+            filterbank,
+        }
+    }
+
+    /// Name of the DSP backend in use
+    pub fn backend() -> &'static str {
+        if cfg!(all(target_os = "macos", feature = "accelerate")) {
+            "accelerate"
+        } else {
+            "rustfft"
+        }
+    }
+
+    /// Number of frames produced for `len` samples
+    pub fn num_frames(len: usize) -> usize {
+        if len < WINDOW_SIZE {
+            0
+        } else {
+            (len - WINDOW_SIZE) / HOP_SIZE + 1
+        }
+    }
+
+    /// Extract log-mel features
+    ///
+    /// Returns `[num_frames][n_mels]` row-major.
+    pub fn extract(&self, samples: &[f32]) -> crate::Result<Vec<f32>> {
+        if samples.len() < WINDOW_SIZE {
+            return Err(crate::Error::Model(format!(
+                "Audio too short: {} samples (need at least {})",
+                samples.len(),
+                WINDOW_SIZE
+            )));
+        }
+
+        let num_frames = Self::num_frames(samples.len());
+        let power = self.power_spectra(samples, num_frames);
+        let mut features = self.apply_filterbank(&power, num_frames);
+
+        // Log scale (small epsilon avoids log(0))
+        for value in &mut features {
+            *value = (*value + 1e-10).ln();
+        }
+        Ok(features)
+    }
+
+    /// Power spectrum of every frame, `[num_frames][N_FFT_BINS]`
+    #[cfg(not(all(target_os = "macos", feature = "accelerate")))]
+    fn power_spectra(&self, samples: &[f32], num_frames: usize) -> Vec<f32> {
+        use rustfft::FftPlanner;
+        use rustfft::num_complex::Complex;
+
+        let fft = FftPlanner::new().plan_fft_forward(FFT_SIZE);
+        let mut buffer = vec![Complex::new(0.0f32, 0.0); FFT_SIZE];
+        let mut scratch = vec![Complex::new(0.0f32, 0.0); fft.get_inplace_scratch_len()];
+        let mut power = Vec::with_capacity(num_frames * N_FFT_BINS);
+
+        for frame in 0..num_frames {
+            let start = frame * HOP_SIZE;
+            for (i, value) in buffer.iter_mut().enumerate() {
+                *value = match self.window.get(i) {
+                    Some(&w) => Complex::new(samples[start + i] * w, 0.0),
+                    None => Complex::new(0.0, 0.0),
+                };
+            }
+            fft.process_with_scratch(&mut buffer, &mut scratch);
+            power.extend(buffer.iter().take(N_FFT_BINS).map(|c| c.norm_sqr()));
+        }
+
+        power
+    }
+
+    /// Power spectrum of every frame, `[num_frames][N_FFT_BINS]`
+    #[cfg(all(target_os = "macos", feature = "accelerate"))]
+    fn power_spectra(&self, samples: &[f32], num_frames: usize) -> Vec<f32> {
+        accelerate::power_spectra(samples, &self.window, num_frames)
+    }
+
+    /// Multiply power spectra by the filterbank, `[num_frames][n_mels]`
+    #[cfg(not(all(target_os = "macos", feature = "accelerate")))]
+    fn apply_filterbank(&self, power: &[f32], num_frames: usize) -> Vec<f32> {
+        let mut mel = vec![0.0f32; num_frames * self.n_mels];
+        for (spectrum, out) in power
+            .chunks_exact(N_FFT_BINS)
+            .zip(mel.chunks_exact_mut(self.n_mels))
+        {
+            for (&p, weights) in spectrum
+                .iter()
+                .zip(self.filterbank.chunks_exact(self.n_mels))
+            {
+                if p == 0.0 {
+                    continue;
+                }
+                for (o, &w) in out.iter_mut().zip(weights) {
+                    *o += p * w;
+                }
+            }
+        }
+        mel
+    }
+
+    /// Multiply power spectra by the filterbank, `[num_frames][n_mels]`
+    #[cfg(all(target_os = "macos", feature = "accelerate"))]
+    fn apply_filterbank(&self, power: &[f32], num_frames: usize) -> Vec<f32> {
+        accelerate::matmul(power, &self.filterbank, num_frames, N_FFT_BINS, self.n_mels)
+    }
+}
+
+/// Create mel filterbank matrix
+/// Returns [n_mels][fft_bins] matrix
+pub fn create_mel_filterbank(
+    n_mels: usize,
+    fft_size: usize,
+    min_hz: f32,
+    max_hz: f32,
+    sample_rate: f32,
+) -> Vec<Vec<f32>> {
+    let n_fft_bins = fft_size / 2 + 1;
+
+    // Helper: Hz to Mel
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+
+    // Helper: Mel to Hz
+    let mel_to_hz = |mel: f32| 700.0 * (10.0f32.powf(mel / 2595.0) - 1.0);
+
+    // Create mel scale
+    let min_mel = hz_to_mel(min_hz);
+    let max_mel = hz_to_mel(max_hz);
+    let mel_points: Vec<f32> = (0..=n_mels + 1)
+        .map(|i| mel_to_hz(min_mel + (max_mel - min_mel) * i as f32 / (n_mels + 1) as f32))
+        .collect();
+
+    // Convert mel points to FFT bin indices
+    let bin_points: Vec<f32> = mel_points
+        .iter()
+        .map(|&hz| (fft_size as f32 * hz / sample_rate).floor())
+        .collect();
+
+    // Create filterbank
+    let mut filterbank = vec![vec![0.0f32; n_fft_bins]; n_mels];
+
+    for mel_idx in 0..n_mels {
+        let left = bin_points[mel_idx] as usize;
+        let center = bin_points[mel_idx + 1] as usize;
+        let right = bin_points[mel_idx + 2] as usize;
+
+        // Rising slope
+        for (bin, value) in filterbank[mel_idx]
+            .iter_mut()
+            .enumerate()
+            .take(center)
+            .skip(left)
+        {
+            *value = (bin as f32 - left as f32) / (center as f32 - left as f32);
+        }
+
+        // Falling slope
+        for (bin, value) in filterbank[mel_idx]
+            .iter_mut()
+            .enumerate()
+            .take(right.min(n_fft_bins))
+            .skip(center)
+        {
+            *value = (right as f32 - bin as f32) / (right as f32 - center as f32);
+        }
+    }
+
+    filterbank
+}
+
+/// vDSP bindings (Accelerate.framework)
+#[cfg(all(target_os = "macos", feature = "accelerate"))]
+mod accelerate {
+    use super::{FFT_SIZE, HOP_SIZE, N_FFT_BINS};
+    use std::os::raw::{c_int, c_long, c_ulong, c_void};
+
+    type FftSetup = *mut c_void;
+
+    #[repr(C)]
+    struct DspSplitComplex {
+        realp: *mut f32,
+        imagp: *mut f32,
+    }
+
+    const FFT_RADIX2: c_int = 0;
+    const FFT_FORWARD: c_int = 1;
+
+    #[link(name = "Accelerate", kind = "framework")]
+    unsafe extern "C" {
+        fn vDSP_create_fftsetup(log2n: c_ulong, radix: c_int) -> FftSetup;
+        fn vDSP_destroy_fftsetup(setup: FftSetup);
+        fn vDSP_vmul(
+            a: *const f32,
+            ia: c_long,
+            b: *const f32,
+            ib: c_long,
+            c: *mut f32,
+            ic: c_long,
+            n: c_ulong,
+        );
+        fn vDSP_ctoz(c: *const f32, ic: c_long, z: *const DspSplitComplex, iz: c_long, n: c_ulong);
+        fn vDSP_fft_zrip(
+            setup: FftSetup,
+            c: *const DspSplitComplex,
+            ic: c_long,
+            log2n: c_ulong,
+            direction: c_int,
+        );
+        fn vDSP_zvmags(a: *const DspSplitComplex, ia: c_long, c: *mut f32, ic: c_long, n: c_ulong);
+        fn vDSP_mmul(
+            a: *const f32,
+            ia: c_long,
+            b: *const f32,
+            ib: c_long,
+            c: *mut f32,
+            ic: c_long,
+            m: c_ulong,
+            n: c_ulong,
+            p: c_ulong,
+        );
+    }
+
+    /// Windowed real FFT power spectra of all frames
+    pub(super) fn power_spectra(samples: &[f32], window: &[f32], num_frames: usize) -> Vec<f32> {
+        let log2n = FFT_SIZE.trailing_zeros() as c_ulong;
+        let half = FFT_SIZE / 2;
+
+        let mut frame = vec![0.0f32; FFT_SIZE];
+        let mut real = vec![0.0f32; half];
+        let mut imag = vec![0.0f32; half];
+        let mut power = vec![0.0f32; num_frames * N_FFT_BINS];
+
+        // SAFETY: every buffer outlives the calls and is at least as long as
+        // the element counts passed; the setup is destroyed before returning.
+        unsafe {
+            let setup = vDSP_create_fftsetup(log2n, FFT_RADIX2);
+            let split = DspSplitComplex {
+                realp: real.as_mut_ptr(),
+                imagp: imag.as_mut_ptr(),
+            };
+
+            for (index, out) in power.chunks_exact_mut(N_FFT_BINS).enumerate() {
+                let start = index * HOP_SIZE;
+                vDSP_vmul(
+                    samples[start..].as_ptr(),
+                    1,
+                    window.as_ptr(),
+                    1,
+                    frame.as_mut_ptr(),
+                    1,
+                    window.len() as c_ulong,
+                );
+
+                // Pack even/odd samples as the real/imaginary parts
+                vDSP_ctoz(frame.as_ptr(), 2, &split, 1, half as c_ulong);
+                vDSP_fft_zrip(setup, &split, 1, log2n, FFT_FORWARD);
+
+                // DC and Nyquist are packed into realp[0] and imagp[0]
+                let nyquist = imag[0];
+                imag[0] = 0.0;
+                vDSP_zvmags(&split, 1, out.as_mut_ptr(), 1, half as c_ulong);
+                out[half] = nyquist * nyquist;
+
+                // zrip output is scaled by 2, so power by 4
+                for value in out.iter_mut() {
+                    *value *= 0.25;
+                }
+            }
+
+            vDSP_destroy_fftsetup(setup);
+        }
+
+        power
+    }
+
+    /// `a` ([m][p]) times `b` ([p][n]), row-major
+    pub(super) fn matmul(a: &[f32], b: &[f32], m: usize, p: usize, n: usize) -> Vec<f32> {
+        let mut c = vec![0.0f32; m * n];
+        // SAFETY: `a` holds m*p, `b` p*n, and `c` m*n elements
+        unsafe {
+            vDSP_mmul(
+                a.as_ptr(),
+                1,
+                b.as_ptr(),
+                1,
+                c.as_mut_ptr(),
+                1,
+                m as c_ulong,
+                n as c_ulong,
+                p as c_ulong,
+            );
+        }
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sine_peaks_in_matching_mel_bin() {
+        let n_mels = 80;
+        let samples: Vec<f32> = (0..16000)
+            .map(|i| (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+
+        let features = MelExtractor::new(n_mels).extract(&samples).unwrap();
+        let num_frames = MelExtractor::num_frames(samples.len());
+        assert_eq!(features.len(), num_frames * n_mels);
+
+        // The strongest bin of a middle frame is the filter covering 1kHz
+        let frame = &features[(num_frames / 2) * n_mels..][..n_mels];
+        let peak = (0..n_mels)
+            .max_by(|&a, &b| frame[a].total_cmp(&frame[b]))
+            .unwrap();
+        let filters = create_mel_filterbank(n_mels, FFT_SIZE, MEL_MIN_HZ, MEL_MAX_HZ, SAMPLE_RATE);
+        let bin_1khz = (1000.0 * FFT_SIZE as f32 / SAMPLE_RATE).round() as usize;
+        assert!(filters[peak][bin_1khz] > 0.0);
+
+        assert!(MelExtractor::new(n_mels).extract(&samples[..100]).is_err());
+    }
+}
@@ -74,6 +74,8 @@ impl ModelRuntime for MockModel {
             confidence: Some(0.95),
             processing_time_ms: processing_time.as_millis() as u64,
             tokens: Some((duration_secs * 2.0) as usize), // Fake: ~2 tokens per second
+            segments: Vec::new(),
+            words: Vec::new(),
         })
     }
 
@@ -0,0 +1,336 @@
+//! ONNX Runtime Library Resolution
+//!
+//! The default `onnx` build links ONNX Runtime in at compile time. Builds
+//! with the `onnx-dynamic` feature load the shared library at startup
+//! instead, so one binary works wherever a compatible runtime is found:
+//!
+//! 1. `ORT_DYLIB_PATH`, if set
+//! 2. The pinned release in the cache dir (`onevox doctor --fix` downloads
+//!    it from the official GitHub release, verifies the archive against the
+//!    SHA-256 digest GitHub publishes for it, and records the library's
+//!    checksum, which is re-checked before every load)
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// ONNX Runtime release downloaded by `onevox doctor --fix` (matches the
+/// API version `ort` is built against)
+pub const ORT_VERSION: &str = "1.23.2";
+
+/// Environment variable overriding the library path
+pub const DYLIB_ENV: &str = "ORT_DYLIB_PATH";
+
+/// Manifest written next to a downloaded library
+const MANIFEST_FILE: &str = "runtime.json";
+
+/// Release archive and the library inside it for one platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeAsset {
+    /// Release asset name, e.g. `onnxruntime-linux-x64-1.23.2.tgz`
+    pub archive: String,
+    /// Library path inside the archive
+    pub library: String,
+}
+
+impl RuntimeAsset {
+    /// Asset for an OS / architecture pair (`std::env::consts` names)
+    pub fn for_platform(os: &str, arch: &str) -> Option<Self> {
+        let (platform, library) = match (os, arch) {
+            ("linux", "x86_64") => ("linux-x64", format!("libonnxruntime.so.{}", ORT_VERSION)),
+            ("linux", "aarch64") => (
+                "linux-aarch64",
+                format!("libonnxruntime.so.{}", ORT_VERSION),
+            ),
+            ("macos", "aarch64") => ("osx-arm64", format!("libonnxruntime.{}.dylib", ORT_VERSION)),
+            ("macos", "x86_64") => (
+                "osx-x86_64",
+                format!("libonnxruntime.{}.dylib", ORT_VERSION),
+            ),
+            _ => return None,
+        };
+        let dir = format!("onnxruntime-{}-{}", platform, ORT_VERSION);
+        Some(Self {
+            archive: format!("{}.tgz", dir),
+            library: format!("{}/lib/{}", dir, library),
+        })
+    }
+
+    /// Asset for the running platform
+    pub fn current() -> Option<Self> {
+        Self::for_platform(std::env::consts::OS, std::env::consts::ARCH)
+    }
+
+    /// Library file name
+    pub fn library_name(&self) -> &str {
+        self.library.rsplit('/').next().unwrap_or(&self.library)
+    }
+
+    fn download_url(&self) -> String {
+        format!(
+            "https://github.com/microsoft/onnxruntime/releases/download/v{}/{}",
+            ORT_VERSION, self.archive
+        )
+    }
+}
+
+/// Details of a downloaded runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeManifest {
+    /// ONNX Runtime version
+    pub version: String,
+    /// Library file name in the same directory
+    pub library: String,
+    /// SHA-256 of the library file
+    pub sha256: String,
+    /// Archive the library was extracted from
+    pub source: String,
+}
+
+/// Where the ONNX Runtime library comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeSource {
+    /// Linked in at build time
+    Static,
+    /// Set through `ORT_DYLIB_PATH`
+    Env(PathBuf),
+    /// Downloaded pinned release with a verified checksum
+    Cached { path: PathBuf, version: String },
+    /// Dynamic loading, but no usable library was found
+    Missing(String),
+}
+
+impl std::fmt::Display for RuntimeSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuntimeSource::Static => write!(f, "linked at build time"),
+            RuntimeSource::Env(path) => write!(f, "{} (from {})", path.display(), DYLIB_ENV),
+            RuntimeSource::Cached { path, version } => {
+                write!(f, "{} {} (verified)", path.display(), version)
+            }
+            RuntimeSource::Missing(reason) => write!(f, "missing: {}", reason),
+        }
+    }
+}
+
+/// Cache directory for the pinned runtime
+pub fn runtime_dir() -> crate::Result<PathBuf> {
+    Ok(crate::platform::cache_dir()?
+        .join("onnxruntime")
+        .join(ORT_VERSION))
+}
+
+/// Find the library to load
+pub fn resolve() -> RuntimeSource {
+    if !cfg!(feature = "onnx-dynamic") {
+        return RuntimeSource::Static;
+    }
+    if let Some(path) = std::env::var_os(DYLIB_ENV) {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            RuntimeSource::Env(path)
+        } else {
+            RuntimeSource::Missing(format!("{} points to {}", DYLIB_ENV, path.display()))
+        };
+    }
+
+    match runtime_dir()
+        .map_err(anyhow::Error::from)
+        .and_then(|dir| verify_installed(&dir))
+    {
+        Ok((path, manifest)) => RuntimeSource::Cached {
+            path,
+            version: manifest.version,
+        },
+        Err(e) => RuntimeSource::Missing(format!("{:#} (run `onevox doctor --fix`)", e)),
+    }
+}
+
+/// Check a downloaded runtime against its manifest
+pub fn verify_installed(dir: &Path) -> Result<(PathBuf, RuntimeManifest)> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: RuntimeManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("No runtime installed in {}", dir.display()))?,
+    )
+    .with_context(|| format!("Invalid {}", manifest_path.display()))?;
+
+    let library = dir.join(&manifest.library);
+    let actual = sha256_file(&library)?;
+    if actual != manifest.sha256 {
+        anyhow::bail!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            library.display(),
+            manifest.sha256,
+            actual
+        );
+    }
+    Ok((library, manifest))
+}
+
+/// SHA-256 of a file as lowercase hex
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parse GitHub's `sha256:<hex>` asset digest for `archive` from a release
+/// API response
+fn published_digest(release_json: &str, archive: &str) -> Option<String> {
+    let release: serde_json::Value = serde_json::from_str(release_json).ok()?;
+    release["assets"]
+        .as_array()?
+        .iter()
+        .find(|asset| asset["name"] == archive)?["digest"]
+        .as_str()?
+        .strip_prefix("sha256:")
+        .map(str::to_ascii_lowercase)
+}
+
+/// Download, verify, and unpack the pinned runtime into the cache dir
+pub async fn install() -> Result<PathBuf> {
+    let asset = RuntimeAsset::current().with_context(|| {
+        format!(
+            "No prebuilt ONNX Runtime download for {}-{}; build with the default `onnx` feature to link it statically",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let dir = runtime_dir()?;
+    if let Ok((path, _)) = verify_installed(&dir) {
+        return Ok(path);
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("onevox/0.1.0")
+        .build()?;
+
+    let release_url = format!(
+        "https://api.github.com/repos/microsoft/onnxruntime/releases/tags/v{}",
+        ORT_VERSION
+    );
+    let release = client
+        .get(&release_url)
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to fetch ONNX Runtime release details")?
+        .text()
+        .await?;
+    let expected = published_digest(&release, &asset.archive)
+        .with_context(|| format!("No published checksum for {}", asset.archive))?;
+
+    info!("Downloading {}", asset.download_url());
+    let archive = client
+        .get(asset.download_url())
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to download ONNX Runtime")?
+        .bytes()
+        .await?;
+
+    let actual = hex(&Sha256::digest(&archive));
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            asset.archive,
+            expected,
+            actual
+        );
+    }
+
+    fs::create_dir_all(&dir)?;
+    let library_path = dir.join(asset.library_name());
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(&archive[..]));
+    let mut found = false;
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() == asset.library {
+            entry.unpack(&library_path)?;
+            found = true;
+            break;
+        }
+    }
+    if !found {
+        anyhow::bail!("{} not found in {}", asset.library, asset.archive);
+    }
+
+    let manifest = RuntimeManifest {
+        version: ORT_VERSION.to_string(),
+        library: asset.library_name().to_string(),
+        sha256: sha256_file(&library_path)?,
+        source: asset.download_url(),
+    };
+    fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    info!(
+        "ONNX Runtime {} installed in {}",
+        ORT_VERSION,
+        dir.display()
+    );
+    Ok(library_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assets_and_verification() {
+        let asset = RuntimeAsset::for_platform("linux", "x86_64").unwrap();
+        assert_eq!(asset.archive, "onnxruntime-linux-x64-1.23.2.tgz");
+        assert_eq!(asset.library_name(), "libonnxruntime.so.1.23.2");
+        assert!(RuntimeAsset::for_platform("freebsd", "x86_64").is_none());
+
+        let release =
+            r#"{"assets":[{"name":"onnxruntime-linux-x64-1.23.2.tgz","digest":"sha256:ABC123"}]}"#;
+        assert_eq!(
+            published_digest(release, &asset.archive).as_deref(),
+            Some("abc123")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("lib.so"), b"runtime").unwrap();
+        let mut manifest = RuntimeManifest {
+            version: ORT_VERSION.to_string(),
+            library: "lib.so".to_string(),
+            sha256: sha256_file(&dir.path().join("lib.so")).unwrap(),
+            source: String::new(),
+        };
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        assert!(verify_installed(dir.path()).is_ok());
+
+        manifest.sha256 = "0".repeat(64);
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        assert!(verify_installed(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! ONNX Runtime Execution Providers
+//!
+//! ONNX sessions run on the CPU unless a hardware execution provider is
+//! registered with them. `model.execution_provider` names one (CoreML on
+//! macOS, CUDA or TensorRT on Linux, DirectML on Windows); "auto" tries the
+//! platform's GPU provider whenever `model.device` allows the GPU.
+//!
+//! Providers need ONNX Runtime built with them: the `coreml`, `cuda`,
+//! `tensorrt`, and `directml` features, or an `onnx-dynamic` library that
+//! includes them. A provider that is missing or fails to initialise falls
+//! back to the CPU, and `ModelInfo::gpu_enabled` reports what was used.
+
+#[cfg(feature = "onnx")]
+use ort::session::builder::SessionBuilder;
+#[cfg(feature = "onnx")]
+use tracing::{info, warn};
+
+/// Hardware an ONNX session runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionProvider {
+    #[default]
+    Cpu,
+    /// Apple Neural Engine / GPU (macOS)
+    CoreMl,
+    /// NVIDIA GPUs (Linux, Windows)
+    Cuda,
+    /// NVIDIA TensorRT (Linux, Windows); slow first load while it builds
+    /// its engine
+    TensorRt,
+    /// Any DirectX 12 GPU (Windows)
+    DirectMl,
+}
+
+impl ExecutionProvider {
+    /// Parse a `model.execution_provider` name (other than "auto")
+    pub fn parse(name: &str) -> crate::Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cpu" => Ok(ExecutionProvider::Cpu),
+            "coreml" => Ok(ExecutionProvider::CoreMl),
+            "cuda" => Ok(ExecutionProvider::Cuda),
+            "tensorrt" => Ok(ExecutionProvider::TensorRt),
+            "directml" | "dml" => Ok(ExecutionProvider::DirectMl),
+            other => Err(crate::Error::Config(format!(
+                "Unknown execution provider '{}' (expected auto, cpu, coreml, cuda, tensorrt, or directml)",
+                other
+            ))),
+        }
+    }
+
+    /// Providers to try, in order, before falling back to the CPU
+    ///
+    /// `use_gpu` is false for `model.device = "cpu"`, which wins over an
+    /// explicit provider.
+    pub fn candidates(name: &str, use_gpu: bool) -> crate::Result<Vec<Self>> {
+        let providers = if name.eq_ignore_ascii_case("auto") {
+            Self::platform_defaults().to_vec()
+        } else {
+            match Self::parse(name)? {
+                ExecutionProvider::Cpu => Vec::new(),
+                provider => vec![provider],
+            }
+        };
+        Ok(if use_gpu { providers } else { Vec::new() })
+    }
+
+    /// GPU providers "auto" tries on this platform
+    fn platform_defaults() -> &'static [Self] {
+        if cfg!(target_os = "macos") {
+            &[ExecutionProvider::CoreMl]
+        } else if cfg!(target_os = "windows") {
+            &[ExecutionProvider::DirectMl, ExecutionProvider::Cuda]
+        } else {
+            &[ExecutionProvider::Cuda]
+        }
+    }
+
+    /// Whether the provider runs on a GPU or accelerator
+    pub fn is_gpu(&self) -> bool {
+        *self != ExecutionProvider::Cpu
+    }
+}
+
+impl std::fmt::Display for ExecutionProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionProvider::Cpu => write!(f, "CPU"),
+            ExecutionProvider::CoreMl => write!(f, "CoreML"),
+            ExecutionProvider::Cuda => write!(f, "CUDA"),
+            ExecutionProvider::TensorRt => write!(f, "TensorRT"),
+            ExecutionProvider::DirectMl => write!(f, "DirectML"),
+        }
+    }
+}
+
+/// Register the first of `candidates` that initialises on `builder`,
+/// returning the provider the session will use
+#[cfg(feature = "onnx")]
+pub fn register(
+    builder: &mut SessionBuilder,
+    candidates: &[ExecutionProvider],
+) -> ExecutionProvider {
+    use ort::ep::{self, ExecutionProvider as _};
+
+    for &provider in candidates {
+        let (supported, result) = match provider {
+            ExecutionProvider::Cpu => return ExecutionProvider::Cpu,
+            ExecutionProvider::CoreMl => {
+                let ep = ep::CoreML::default();
+                (ep.supported_by_platform(), ep.register(builder))
+            }
+            ExecutionProvider::Cuda => {
+                let ep = ep::CUDA::default();
+                (ep.supported_by_platform(), ep.register(builder))
+            }
+            ExecutionProvider::TensorRt => {
+                let ep = ep::TensorRT::default();
+                (ep.supported_by_platform(), ep.register(builder))
+            }
+            ExecutionProvider::DirectMl => {
+                let ep = ep::DirectML::default();
+                (ep.supported_by_platform(), ep.register(builder))
+            }
+        };
+        if !supported {
+            warn!(
+                "{} execution provider is not available on this platform",
+                provider
+            );
+            continue;
+        }
+        match result {
+            Ok(()) => {
+                info!("Using {} execution provider", provider);
+                return provider;
+            }
+            Err(e) => warn!("{} execution provider unavailable: {}", provider, e),
+        }
+    }
+
+    if !candidates.is_empty() {
+        warn!("Falling back to CPU inference");
+    }
+    ExecutionProvider::Cpu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        assert_eq!(
+            ExecutionProvider::candidates("CUDA", true).unwrap(),
+            vec![ExecutionProvider::Cuda]
+        );
+        assert!(
+            ExecutionProvider::candidates("cpu", true)
+                .unwrap()
+                .is_empty()
+        );
+        // model.device = "cpu" overrides the provider
+        assert!(
+            ExecutionProvider::candidates("cuda", false)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            !ExecutionProvider::candidates("auto", true)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(ExecutionProvider::candidates("vulkan", true).is_err());
+
+        assert!(ExecutionProvider::DirectMl.is_gpu());
+        assert!(!ExecutionProvider::default().is_gpu());
+    }
+}
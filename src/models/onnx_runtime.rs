@@ -2,62 +2,253 @@
 //!
 //! High-performance ASR using ONNX Runtime with support for CTC-based models like NVIDIA Parakeet.
 //! Designed for production use with cross-platform support and robust error handling.
+//!
+//! Transducer models (Parakeet TDT, RNN-T) ship a second graph with the
+//! prediction and joint networks (`decoder_joint-model.onnx`); when it is
+//! present the encoder output is decoded with the greedy transducer loop
+//! instead of CTC.
+//!
+//! Feature, logit, and token statistics are logged at debug level; with
+//! `--debug-inference` the features and logits are also saved for offline
+//! analysis (see [`super::inference_debug`]).
 
 #[cfg(feature = "onnx")]
-use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, Transcription};
+use super::ctc_beam::{self, CtcHypothesis};
+#[cfg(feature = "onnx")]
+use super::inference_debug::InferenceDump;
+#[cfg(feature = "onnx")]
+use super::onnx_ep::{self, ExecutionProvider};
+#[cfg(feature = "onnx")]
+use super::rescoring::{self, LanguageModel};
+#[cfg(feature = "onnx")]
+use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, TranscriptSegment, Transcription};
+#[cfg(feature = "onnx")]
+use super::transducer::{self, TransducerToken};
+#[cfg(feature = "onnx")]
+use crate::config::{DEFAULT_BEAM_WIDTH, RescoringConfig};
+#[cfg(feature = "onnx")]
+use crate::vocabulary::Vocabulary;
+#[cfg(feature = "onnx")]
+use std::cell::RefCell;
+#[cfg(feature = "onnx")]
+use std::collections::HashMap;
 #[cfg(feature = "onnx")]
 use std::path::{Path, PathBuf};
 #[cfg(feature = "onnx")]
-use tracing::{debug, info, warn};
+use tracing::{debug, info, trace, warn};
 
 #[cfg(feature = "onnx")]
 use ort::{session::Session, session::builder::GraphOptimizationLevel, value::Value};
 
 // Initialize ONNX Runtime environment once at module load
 #[cfg(feature = "onnx")]
-use std::sync::Once;
+static INIT_ORT: std::sync::OnceLock<Result<(), String>> = std::sync::OnceLock::new();
+
+#[cfg(feature = "onnx")]
+pub(crate) fn init_ort_environment() -> crate::Result<()> {
+    INIT_ORT
+        .get_or_init(|| {
+            #[cfg(feature = "onnx-dynamic")]
+            {
+                use super::onnx_dylib::{self, RuntimeSource};
+
+                let path = match onnx_dylib::resolve() {
+                    RuntimeSource::Env(path) | RuntimeSource::Cached { path, .. } => path,
+                    RuntimeSource::Missing(reason) => {
+                        return Err(format!("ONNX Runtime library {}", reason));
+                    }
+                    RuntimeSource::Static => unreachable!("onnx-dynamic loads at runtime"),
+                };
+                info!("Loading ONNX Runtime from {}", path.display());
+                let builder = ort::init_from(&path)
+                    .map_err(|e| format!("Failed to load {}: {}", path.display(), e))?;
+                let _ = builder.with_name("onevox").commit();
+            }
+
+            #[cfg(not(feature = "onnx-dynamic"))]
+            {
+                // Statically linked library; nothing to locate
+                let _ = ort::init().with_name("onevox").commit();
+            }
+
+            info!("ONNX Runtime environment initialized");
+            Ok(())
+        })
+        .clone()
+        .map_err(crate::Error::Model)
+}
+
+/// Build information of the ONNX Runtime in use (loads it if needed)
+#[cfg(feature = "onnx")]
+pub fn runtime_info() -> crate::Result<String> {
+    init_ort_environment()?;
+    Ok(ort::info().to_string())
+}
 
+/// A decoded word with the encoder frames it spans
 #[cfg(feature = "onnx")]
-static INIT_ORT: Once = Once::new();
+#[derive(Debug, Clone)]
+struct CtcWord {
+    text: String,
+    first_frame: usize,
+    last_frame: usize,
+}
 
+/// Frames the CTC decoder emitted tokens at
 #[cfg(feature = "onnx")]
-fn init_ort_environment() {
-    INIT_ORT.call_once(|| {
-        // Initialize ONNX Runtime environment
-        // This triggers the dylib loading with the statically-linked library
-        let _ = ort::init().with_name("onevox").commit();
-        info!("ONNX Runtime environment initialized");
-    });
+#[derive(Debug, Clone, serde::Serialize)]
+struct TokenStats {
+    frames: usize,
+    non_blank: usize,
+    blank_id: i64,
+    /// First non-blank tokens
+    sample: Vec<String>,
 }
 
 /// ONNX Runtime model backend
 #[cfg(feature = "onnx")]
 pub struct OnnxRuntime {
     encoder_session: Option<Session>,
+    /// Prediction and joint networks of a transducer model
+    decoder_session: Option<Session>,
     vocab: Option<Vec<String>>,
     config: Option<ModelConfig>,
     model_dir: Option<PathBuf>,
     n_mel_bins: usize, // Number of mel bins (80 for Parakeet CTC, 128 for TDT)
+    rescoring: RescoringConfig,
+    language_model: Option<Box<dyn LanguageModel>>,
+    /// Terms the beam search is biased towards
+    vocabulary: Vocabulary,
+    /// `model.execution_provider` ("auto", "cpu", "cuda", ...)
+    execution_provider: String,
+    /// Provider the loaded session runs on
+    provider: ExecutionProvider,
 }
 
 #[cfg(feature = "onnx")]
 impl OnnxRuntime {
     /// Create a new ONNX Runtime backend
+    ///
+    /// The runtime library itself is located and loaded by `load`, so a
+    /// backend can be created before a dynamic runtime is installed.
     pub fn new() -> crate::Result<Self> {
         info!("Initializing ONNX Runtime backend");
 
-        // Initialize the ONNX Runtime environment
-        init_ort_environment();
-
         Ok(Self {
             encoder_session: None,
+            decoder_session: None,
             vocab: None,
             config: None,
             model_dir: None,
             n_mel_bins: 80, // Default to 80 for Parakeet CTC
+            rescoring: RescoringConfig::default(),
+            language_model: None,
+            vocabulary: Vocabulary::default(),
+            execution_provider: "auto".to_string(),
+            provider: ExecutionProvider::Cpu,
         })
     }
 
+    /// Run on the named execution provider (`model.execution_provider`)
+    pub fn with_execution_provider(mut self, execution_provider: &str) -> Self {
+        self.execution_provider = execution_provider.to_string();
+        self
+    }
+
+    /// Create the encoder session on the first provider that works
+    ///
+    /// A session that fails to build on a GPU provider (e.g. an operator
+    /// it doesn't support) is rebuilt on the CPU.
+    fn create_session(
+        config: &ModelConfig,
+        model_bytes: &[u8],
+        candidates: &[ExecutionProvider],
+    ) -> crate::Result<(Session, ExecutionProvider)> {
+        let mut builder = Session::builder()
+            .map_err(|e| crate::Error::Model(format!("Failed to create session builder: {}", e)))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| crate::Error::Model(format!("Failed to set optimization level: {}", e)))?
+            .with_intra_threads(config.n_threads as usize)
+            .map_err(|e| crate::Error::Model(format!("Failed to set thread count: {}", e)))?;
+
+        let provider = onnx_ep::register(&mut builder, candidates);
+        if provider == ExecutionProvider::DirectMl {
+            // DirectML doesn't support memory patterns
+            builder = builder
+                .with_memory_pattern(false)
+                .map_err(|e| crate::Error::Model(format!("Failed to configure DirectML: {}", e)))?;
+        }
+
+        match builder.commit_from_memory(model_bytes) {
+            Ok(session) => Ok((session, provider)),
+            Err(e) if provider.is_gpu() => {
+                warn!(
+                    "Failed to load ONNX model on {}, falling back to CPU: {}",
+                    provider, e
+                );
+                Self::create_session(config, model_bytes, &[])
+            }
+            Err(e) => Err(crate::Error::Model(format!(
+                "Failed to load ONNX model: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Decode with a CTC beam search and language model rescoring
+    pub fn with_rescoring(mut self, rescoring: RescoringConfig) -> Self {
+        self.rescoring = rescoring;
+        self
+    }
+
+    /// Beam width for CTC decoding; a custom vocabulary needs a beam to
+    /// bias, so greedy decoding widens to the default beam
+    fn beam_width(&self) -> usize {
+        if self.vocabulary.is_empty() {
+            self.rescoring.beam_width
+        } else {
+            self.rescoring.beam_width.max(DEFAULT_BEAM_WIDTH)
+        }
+    }
+
+    /// Pick the best of the beam's hypotheses after language model
+    /// rescoring, as frame-aligned token ids
+    fn select_hypothesis(
+        &self,
+        hypotheses: Vec<CtcHypothesis>,
+        time_steps: usize,
+    ) -> crate::Result<Vec<i64>> {
+        let blank = self.vocab.as_ref().map_or(0, |vocab| vocab.len() - 1) as i64;
+        let mut best = 0;
+        if hypotheses.len() > 1 && (self.language_model.is_some() || !self.vocabulary.is_empty()) {
+            let scored = hypotheses
+                .iter()
+                .map(|h| {
+                    let text = self.decode_ctc_tokens(&h.frame_tokens(time_steps, blank))?;
+                    let words: Vec<&str> = text.split_whitespace().collect();
+                    let bias = self.vocabulary.bonus(&words, None);
+                    Ok((text, h.score + bias))
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            best = match &self.language_model {
+                Some(lm) => rescoring::rank(&scored, lm.as_ref(), self.rescoring.weight)[0],
+                None => (0..scored.len())
+                    .max_by(|&a, &b| scored[a].1.total_cmp(&scored[b].1))
+                    .unwrap_or(0),
+            };
+            if best != 0 {
+                debug!(
+                    "Rescoring picked \"{}\" over \"{}\"",
+                    scored[best].0, scored[0].0
+                );
+            }
+        }
+        Ok(hypotheses
+            .get(best)
+            .map(|h| h.frame_tokens(time_steps, blank))
+            .unwrap_or_else(|| vec![blank; time_steps]))
+    }
+
     /// Resolve model directory from cache
     fn resolve_model_dir(&self, model_id: &str) -> crate::Result<PathBuf> {
         // Check if it's an absolute path
@@ -79,8 +270,8 @@ impl OnnxRuntime {
         let models_dir =
             crate::platform::paths::models_dir().unwrap_or_else(|_| PathBuf::from("./models"));
 
-        // Model directory structure: models/<model_id>/
-        let model_dir = models_dir.join(model_id);
+        // Model directory structure: models/<model_id>/ (or its active version)
+        let model_dir = crate::platform::paths::active_model_dir(&models_dir.join(model_id));
 
         if !model_dir.exists() {
             warn!("Model directory not found at: {:?}", model_dir);
@@ -117,6 +308,14 @@ impl OnnxRuntime {
         )))
     }
 
+    /// Find the transducer decoder/joint model, if this is a transducer
+    fn find_decoder_path(&self, model_dir: &Path) -> Option<PathBuf> {
+        ["decoder_joint-model.int8.onnx", "decoder_joint-model.onnx"]
+            .iter()
+            .map(|name| model_dir.join(name))
+            .find(|path| path.is_file())
+    }
+
     /// Load vocabulary file
     fn load_vocab(&self, model_dir: &Path) -> crate::Result<Vec<String>> {
         let vocab_path = model_dir.join("vocab.txt");
@@ -185,26 +384,33 @@ impl OnnxRuntime {
 
     /// Decode CTC token IDs to text using greedy decoding
     fn decode_ctc_tokens(&self, token_ids: &[i64]) -> crate::Result<String> {
+        let words = self.decode_ctc_words(token_ids)?;
+        let text: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        Ok(text.join(" ").trim().to_string())
+    }
+
+    /// Decode CTC token IDs into words with their first and last frame index
+    fn decode_ctc_words(&self, token_ids: &[i64]) -> crate::Result<Vec<CtcWord>> {
         let vocab = self
             .vocab
             .as_ref()
             .ok_or_else(|| crate::Error::Model("Vocabulary not loaded".to_string()))?;
 
         let blank_token_id = (vocab.len() - 1) as i64; // CTC blank is typically the last token
-        eprintln!(
-            "🔍 decode_ctc_tokens: {} tokens, blank_id={}",
+        trace!(
+            "Decoding {} CTC tokens (blank ID {})",
             token_ids.len(),
             blank_token_id
         );
 
-        let mut result = String::new();
+        let mut words: Vec<CtcWord> = Vec::new();
         let mut prev_token_id: Option<i64> = None;
         let mut skipped_blank = 0;
         let mut skipped_repeat = 0;
         let mut skipped_special = 0;
         let mut kept_tokens = 0;
 
-        for &token_id in token_ids {
+        for (frame, &token_id) in token_ids.iter().enumerate() {
             // Validate token ID
             if token_id < 0 || token_id as usize >= vocab.len() {
                 debug!("Skipping invalid token ID: {}", token_id);
@@ -220,6 +426,10 @@ impl OnnxRuntime {
 
             if Some(token_id) == prev_token_id {
                 skipped_repeat += 1;
+                // A repeated token still extends the current word in time
+                if let Some(word) = words.last_mut() {
+                    word.last_frame = frame;
+                }
                 continue; // Skip repeated tokens
             }
 
@@ -234,27 +444,71 @@ impl OnnxRuntime {
             }
 
             kept_tokens += 1;
-
-            // Handle SentencePiece subword tokens (▁ indicates word boundary)
-            if let Some(stripped) = token.strip_prefix('▁') {
-                if !result.is_empty() {
-                    result.push(' ');
-                }
-                // Remove the ▁ marker
-                result.push_str(stripped); // ▁ is 3 bytes in UTF-8
-            } else {
-                result.push_str(token);
-            }
+            push_piece(&mut words, token, frame);
 
             prev_token_id = Some(token_id);
         }
 
-        eprintln!(
-            "🔍 Decoding summary: skipped {} blank, {} repeat, {} special → kept {} tokens → result: '{}'",
-            skipped_blank, skipped_repeat, skipped_special, kept_tokens, result
+        debug!(
+            "CTC decoding skipped {} blank, {} repeat, {} special tokens; kept {} tokens in {} words",
+            skipped_blank,
+            skipped_repeat,
+            skipped_special,
+            kept_tokens,
+            words.len()
         );
 
-        Ok(result.trim().to_string())
+        Ok(words)
+    }
+
+    /// Decode transducer tokens into words with their first and last frame
+    fn decode_transducer_words(&self, tokens: &[TransducerToken]) -> crate::Result<Vec<CtcWord>> {
+        let vocab = self
+            .vocab
+            .as_ref()
+            .ok_or_else(|| crate::Error::Model("Vocabulary not loaded".to_string()))?;
+
+        let mut words = Vec::new();
+        for token in tokens {
+            match vocab.get(token.id as usize) {
+                Some(piece) if !(piece.starts_with('<') && piece.ends_with('>')) => {
+                    push_piece(&mut words, piece, token.frame)
+                }
+                Some(_) => {}
+                None => debug!("Skipping invalid token ID: {}", token.id),
+            }
+        }
+        Ok(words)
+    }
+
+    /// How many frames the CTC decoder emitted tokens at, logged at debug
+    /// level
+    fn token_stats(&self, token_ids: &[i64]) -> Option<TokenStats> {
+        let vocab = self.vocab.as_ref()?;
+        let blank_id = (vocab.len() - 1) as i64;
+        let blank = token_ids.iter().filter(|&&id| id == blank_id).count();
+        let stats = TokenStats {
+            frames: token_ids.len(),
+            non_blank: token_ids.len() - blank,
+            blank_id,
+            // The first non-blank tokens show whether the model hears speech
+            sample: token_ids
+                .iter()
+                .filter(|&&id| id != blank_id)
+                .take(20)
+                .map(|&id| {
+                    vocab
+                        .get(id as usize)
+                        .cloned()
+                        .unwrap_or_else(|| format!("<id:{}>", id))
+                })
+                .collect(),
+        };
+        debug!(
+            "CTC tokens: {} of {} frames non-blank (blank ID {}), first: {:?}",
+            stats.non_blank, stats.frames, stats.blank_id, stats.sample
+        );
+        Some(stats)
     }
 
     /// Normalize audio samples
@@ -270,153 +524,18 @@ impl OnnxRuntime {
     }
 
     /// Extract mel spectrogram features from audio
-    /// Returns [num_mel_bins, num_frames] shaped feature matrix
+    /// Returns [num_frames, num_mel_bins] shaped feature matrix
     fn extract_mel_features(&self, samples: &[f32], _sample_rate: u32) -> crate::Result<Vec<f32>> {
-        use rustfft::FftPlanner;
-        use rustfft::num_complex::Complex;
-        use std::f32::consts::PI;
-
-        // Feature extraction parameters
-        let n_mel_bins = self.n_mel_bins;
-        const WINDOW_SIZE: usize = 400; // 25ms at 16kHz
-        const HOP_SIZE: usize = 160; // 10ms at 16kHz
-        const FFT_SIZE: usize = 512;
-        const SAMPLE_RATE: f32 = 16000.0;
-        const MEL_MIN_HZ: f32 = 0.0;
-        const MEL_MAX_HZ: f32 = 8000.0;
-
-        if samples.len() < WINDOW_SIZE {
-            return Err(crate::Error::Model(format!(
-                "Audio too short: {} samples (need at least {})",
-                samples.len(),
-                WINDOW_SIZE
-            )));
-        }
-
-        // Calculate number of frames
-        let num_frames = (samples.len() - WINDOW_SIZE) / HOP_SIZE + 1;
-
-        // Create Hann window
-        let window: Vec<f32> = (0..WINDOW_SIZE)
-            .map(|i| 0.5 * (1.0 - ((2.0 * PI * i as f32) / (WINDOW_SIZE as f32 - 1.0)).cos()))
-            .collect();
-
-        // Create mel filterbank
-        let mel_filters =
-            Self::create_mel_filterbank(n_mel_bins, FFT_SIZE, MEL_MIN_HZ, MEL_MAX_HZ, SAMPLE_RATE);
-
-        // Setup FFT
-        let mut planner = FftPlanner::new();
-        let fft = planner.plan_fft_forward(FFT_SIZE);
-
-        // Extract features for each frame
-        let mut features = Vec::with_capacity(n_mel_bins * num_frames);
-
-        for frame_idx in 0..num_frames {
-            let start = frame_idx * HOP_SIZE;
-            let end = start + WINDOW_SIZE;
-
-            // Get frame and apply window
-            let mut frame_complex: Vec<Complex<f32>> = samples[start..end]
-                .iter()
-                .zip(window.iter())
-                .map(|(&s, &w)| Complex::new(s * w, 0.0))
-                .collect();
-
-            // Zero-pad to FFT size
-            frame_complex.resize(FFT_SIZE, Complex::new(0.0, 0.0));
-
-            // Compute FFT
-            fft.process(&mut frame_complex);
-
-            // Compute power spectrum
-            let power_spectrum: Vec<f32> = frame_complex
-                .iter()
-                .take(FFT_SIZE / 2 + 1)
-                .map(|c| c.norm_sqr())
-                .collect();
-
-            // Apply mel filterbank
-            for mel_filter in mel_filters.iter().take(n_mel_bins) {
-                let mut mel_energy = 0.0f32;
-                for (freq_bin, &power) in power_spectrum.iter().enumerate() {
-                    mel_energy += power * mel_filter[freq_bin];
-                }
-
-                // Apply log scale (add small epsilon to avoid log(0))
-                let log_mel = (mel_energy + 1e-10).ln();
-                features.push(log_mel);
-            }
-        }
+        let features = super::mel::MelExtractor::new(self.n_mel_bins).extract(samples)?;
 
         debug!(
-            "Extracted mel features: {} bins x {} frames",
-            n_mel_bins, num_frames
+            "Extracted mel features: {} bins x {} frames ({})",
+            self.n_mel_bins,
+            features.len() / self.n_mel_bins,
+            super::mel::MelExtractor::backend()
         );
         Ok(features)
     }
-
-    /// Create mel filterbank matrix
-    /// Returns [n_mels][fft_bins] matrix
-    fn create_mel_filterbank(
-        n_mels: usize,
-        fft_size: usize,
-        min_hz: f32,
-        max_hz: f32,
-        sample_rate: f32,
-    ) -> Vec<Vec<f32>> {
-        let n_fft_bins = fft_size / 2 + 1;
-
-        // Helper: Hz to Mel
-        let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
-
-        // Helper: Mel to Hz
-        let mel_to_hz = |mel: f32| 700.0 * (10.0f32.powf(mel / 2595.0) - 1.0);
-
-        // Create mel scale
-        let min_mel = hz_to_mel(min_hz);
-        let max_mel = hz_to_mel(max_hz);
-        let mel_points: Vec<f32> = (0..=n_mels + 1)
-            .map(|i| mel_to_hz(min_mel + (max_mel - min_mel) * i as f32 / (n_mels + 1) as f32))
-            .collect();
-
-        // Convert mel points to FFT bin indices
-        let bin_points: Vec<f32> = mel_points
-            .iter()
-            .map(|&hz| (fft_size as f32 * hz / sample_rate).floor())
-            .collect();
-
-        // Create filterbank
-        let mut filterbank = vec![vec![0.0f32; n_fft_bins]; n_mels];
-
-        for mel_idx in 0..n_mels {
-            let left = bin_points[mel_idx] as usize;
-            let center = bin_points[mel_idx + 1] as usize;
-            let right = bin_points[mel_idx + 2] as usize;
-
-            // Rising slope
-            for (bin, value) in filterbank[mel_idx]
-                .iter_mut()
-                .enumerate()
-                .take(center)
-                .skip(left)
-            {
-                *value = (bin as f32 - left as f32) / (center as f32 - left as f32);
-            }
-
-            // Falling slope
-            for (bin, value) in filterbank[mel_idx]
-                .iter_mut()
-                .enumerate()
-                .take(right.min(n_fft_bins))
-                .skip(center)
-            {
-                *value = (right as f32 - bin as f32) / (right as f32 - center as f32);
-            }
-        }
-
-        filterbank
-    }
 }
 
 #[cfg(feature = "onnx")]
@@ -431,6 +550,9 @@ impl ModelRuntime for OnnxRuntime {
     fn load(&mut self, config: ModelConfig) -> crate::Result<()> {
         info!("Loading ONNX Runtime model: {}", config.model_path);
 
+        // Initialize the ONNX Runtime environment
+        init_ort_environment()?;
+
         // Resolve model directory
         let model_dir = self.resolve_model_dir(&config.model_path)?;
 
@@ -480,26 +602,50 @@ impl ModelRuntime for OnnxRuntime {
         info!("Model file size: {} MB", model_bytes.len() / (1024 * 1024));
 
         // Configure ONNX Runtime session
-        let encoder_session = Session::builder()
-            .map_err(|e| crate::Error::Model(format!("Failed to create session builder: {}", e)))?
-            .with_optimization_level(GraphOptimizationLevel::Level3)
-            .map_err(|e| crate::Error::Model(format!("Failed to set optimization level: {}", e)))?
-            .with_intra_threads(config.n_threads as usize)
-            .map_err(|e| crate::Error::Model(format!("Failed to set thread count: {}", e)))?
-            .commit_from_memory(&model_bytes)
-            .map_err(|e| crate::Error::Model(format!("Failed to load ONNX model: {}", e)))?;
+        let candidates = ExecutionProvider::candidates(&self.execution_provider, config.use_gpu)?;
+        let (encoder_session, provider) = Self::create_session(&config, &model_bytes, &candidates)?;
+
+        // Transducer models decode with a second graph. It runs once per
+        // token on tiny inputs, where GPU transfers cost more than they
+        // save, so it stays on the CPU
+        let decoder_session = match self.find_decoder_path(&model_dir) {
+            Some(decoder_path) => {
+                info!("Loading transducer decoder from: {:?}", decoder_path);
+                let decoder_bytes = std::fs::read(&decoder_path).map_err(|e| {
+                    crate::Error::Model(format!("Failed to read decoder model file: {}", e))
+                })?;
+                Some(Self::create_session(&config, &decoder_bytes, &[])?.0)
+            }
+            None => None,
+        };
 
         info!("✅ ONNX Runtime model loaded successfully");
         info!("   Model directory: {:?}", model_dir);
         info!("   Vocabulary size: {}", vocab.len());
         info!("   Mel bins: {}", n_mel_bins);
+        info!(
+            "   Decoder: {}",
+            if decoder_session.is_some() {
+                "transducer"
+            } else {
+                "CTC"
+            }
+        );
         info!("   Thread count: {}", config.n_threads);
+        info!("   Execution provider: {}", provider);
 
         self.encoder_session = Some(encoder_session);
+        self.decoder_session = decoder_session;
+        self.provider = provider;
         self.vocab = Some(vocab);
         self.config = Some(config);
         self.model_dir = Some(model_dir);
         self.n_mel_bins = n_mel_bins;
+        self.language_model = if self.rescoring.beam_width > 1 {
+            rescoring::from_config(&self.rescoring)?
+        } else {
+            None
+        };
 
         Ok(())
     }
@@ -534,17 +680,18 @@ impl ModelRuntime for OnnxRuntime {
             audio_duration
         );
 
+        let dump = InferenceDump::next();
+
         // Normalize audio
         let normalized_audio = self.normalize_audio(samples);
 
-        // Debug: check audio statistics
         let max_audio = normalized_audio
             .iter()
             .map(|&x| x.abs())
             .fold(0.0f32, f32::max);
         let mean_audio = normalized_audio.iter().sum::<f32>() / normalized_audio.len() as f32;
-        eprintln!(
-            "🔍 Audio stats: max={:.4}, mean={:.4}, samples={}",
+        debug!(
+            "Audio: max={:.4}, mean={:.4}, samples={}",
             max_audio,
             mean_audio,
             normalized_audio.len()
@@ -574,16 +721,15 @@ impl ModelRuntime for OnnxRuntime {
             }
         }
 
-        // Debug: check mel feature statistics BEFORE normalization
         let mel_min = mel_features.iter().copied().fold(f32::INFINITY, f32::min);
         let mel_max = mel_features
             .iter()
             .copied()
             .fold(f32::NEG_INFINITY, f32::max);
         let mel_mean = mel_features.iter().sum::<f32>() / mel_features.len() as f32;
-        eprintln!(
-            "🔍 Mel features (before norm): {} frames, min={:.2}, max={:.2}, mean={:.2}",
-            n_frames, mel_min, mel_max, mel_mean
+        debug!(
+            "Mel features before normalization: min={:.2}, max={:.2}, mean={:.2}",
+            mel_min, mel_max, mel_mean
         );
 
         // Normalize mel features to mean=0, std=1 (per-utterance normalization)
@@ -603,17 +749,10 @@ impl ModelRuntime for OnnxRuntime {
             }
         }
 
-        // Debug: check mel feature statistics AFTER normalization
-        let mel_min_norm = mel_features.iter().copied().fold(f32::INFINITY, f32::min);
-        let mel_max_norm = mel_features
-            .iter()
-            .copied()
-            .fold(f32::NEG_INFINITY, f32::max);
-        let mel_mean_norm = mel_features.iter().sum::<f32>() / mel_features.len() as f32;
-        eprintln!(
-            "🔍 Mel features (after norm): min={:.2}, max={:.2}, mean={:.2}, std={:.2}",
-            mel_min_norm, mel_max_norm, mel_mean_norm, mel_std
-        );
+        trace!("Mel features normalized with std={:.2}", mel_std);
+        if let Some(dump) = &dump {
+            dump.matrix("mel", n_mel_bins, n_frames, &mel_features);
+        }
 
         // Prepare ONNX Runtime inputs
         // Parakeet expects shape: [batch_size=1, features, time_frames]
@@ -637,8 +776,18 @@ impl ModelRuntime for OnnxRuntime {
             "length" => length_value
         ];
 
+        // Greedy path, the beam's n-best for rescoring once the session is
+        // released, or transducer tokens with the encoder frame count
+        enum Decoded {
+            Greedy(Vec<i64>),
+            Beam(Vec<CtcHypothesis>, usize),
+            Transducer(Vec<TransducerToken>, usize),
+        }
+
         // Run inference
-        let token_ids = {
+        let beam_width = self.beam_width();
+        let mut confidence = None;
+        let decoded = 'decode: {
             let session = self.encoder_session.as_mut().ok_or_else(|| {
                 crate::Error::Model("Encoder session not initialized".to_string())
             })?;
@@ -647,6 +796,17 @@ impl ModelRuntime for OnnxRuntime {
                 .run(inputs)
                 .map_err(|e| crate::Error::Model(format!("Inference failed: {}", e)))?;
 
+            if let Some(decoder) = self.decoder_session.as_mut() {
+                let vocab_size = self.vocab.as_ref().map_or(0, Vec::len);
+                let (tokens, frames) = decode_transducer(decoder, &outputs, vocab_size)?;
+                debug!(
+                    "Transducer emitted {} tokens over {} frames",
+                    tokens.len(),
+                    frames
+                );
+                break 'decode Decoded::Transducer(tokens, frames);
+            }
+
             // Extract logits output
             // Expected shape: [batch_size=1, time_steps, vocab_size]
             // Try common output names: "outputs", "logits", "output", "logprobs"
@@ -682,166 +842,182 @@ impl ModelRuntime for OnnxRuntime {
             let time_steps = shape[1] as usize;
             let vocab_size = shape[2] as usize;
 
-            debug!("Logits shape: {:?}", shape);
-            debug!("Time steps: {}, Vocab size: {}", time_steps, vocab_size);
-
-            eprintln!(
-                "🔍 Model output: shape={:?}, time_steps={}, vocab_size={}",
+            debug!(
+                "Logits shape {:?}: {} time steps, vocab size {}",
                 shape, time_steps, vocab_size
             );
-
-            // Debug: check first timestep logits
-            let first_10_logits: Vec<f32> = data.iter().take(10).copied().collect();
-            eprintln!("🔍 First 10 logits at t=0: {:?}", first_10_logits);
-
-            // Debug: check blank token value at t=0
-            let blank_idx = vocab_size - 1;
-            if blank_idx < data.len() {
-                eprintln!(
-                    "🔍 Blank token (ID {}) at t=0: {:.4}",
-                    blank_idx, data[blank_idx]
-                );
-            }
-
-            // Debug: check top 5 values at t=0
-            let mut t0_values: Vec<(usize, f32)> = data
-                .iter()
-                .take(vocab_size)
-                .enumerate()
-                .map(|(i, &v)| (i, v))
-                .collect();
-            t0_values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-            eprintln!("🔍 Top 5 tokens at t=0:");
-            for (i, &(token_id, value)) in t0_values.iter().enumerate().take(5.min(t0_values.len()))
-            {
-                eprintln!("  #{}: ID {}, value {:.4}", i + 1, token_id, value);
+            if tracing::enabled!(tracing::Level::TRACE) {
+                let mut t0: Vec<(usize, f32)> =
+                    data.iter().take(vocab_size).copied().enumerate().collect();
+                t0.sort_by(|a, b| b.1.total_cmp(&a.1));
+                t0.truncate(5);
+                trace!("Top tokens at t=0: {:?}", t0);
             }
-
-            // Debug: check top 5 values at t=0
-            let mut t0_values: Vec<(usize, f32)> = data
-                .iter()
-                .take(vocab_size)
-                .enumerate()
-                .map(|(i, &v)| (i, v))
-                .collect();
-            t0_values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-            eprintln!("🔍 Top 5 tokens at t=0:");
-            for &(token_id, value) in t0_values.iter().take(5.min(t0_values.len())) {
-                eprintln!("  ID {}: {:.4}", token_id, value);
+            if let Some(dump) = &dump {
+                let len = (time_steps * vocab_size).min(data.len());
+                dump.matrix("logits", len / vocab_size.max(1), vocab_size, &data[..len]);
             }
+            // With beam search the best token per frame stands in for the
+            // chosen path
+            confidence = super::confidence::ctc(
+                &data[..(time_steps * vocab_size).min(data.len())],
+                vocab_size,
+                vocab_size.saturating_sub(1),
+            );
 
-            // Debug: check if blank token (8192) has high probability at any timestep
-            let blank_id = 8192;
-            if blank_id < vocab_size {
-                let mut blank_max = f32::NEG_INFINITY;
-                for t in 0..time_steps.min(10) {
-                    let idx = t * vocab_size + blank_id;
-                    if idx < data.len() {
-                        blank_max = blank_max.max(data[idx]);
-                    }
-                }
-                eprintln!(
-                    "🔍 Blank token (ID {}) max value in first 10 steps: {:.4}",
-                    blank_id, blank_max
+            if beam_width > 1 {
+                // Fuse the language model and the custom vocabulary into the
+                // search as words are spelled
+                let vocab = self.vocab.as_deref().unwrap_or_default();
+                let fusion = Fusion {
+                    language_model: self.language_model.as_deref(),
+                    weight: self.rescoring.weight,
+                    vocabulary: &self.vocabulary,
+                    cache: RefCell::new(HashMap::new()),
+                };
+                let hypotheses = ctc_beam::beam_search(
+                    &data[..(time_steps * vocab_size).min(data.len())],
+                    vocab_size,
+                    (vocab_size - 1) as i64,
+                    beam_width,
+                    self.rescoring.n_best,
+                    |prefix| fusion.bonus(vocab, prefix),
                 );
-            }
-
-            // Greedy CTC decoding: argmax over vocab dimension for each timestep
-            let mut token_ids = Vec::with_capacity(time_steps);
-
-            for t in 0..time_steps {
-                let mut max_idx = 0;
-                let mut max_val = f32::NEG_INFINITY;
-
-                for v in 0..vocab_size {
-                    let idx = t * vocab_size + v;
-                    if idx >= data.len() {
-                        return Err(crate::Error::Model(format!(
-                            "Index out of bounds: {} >= {}",
-                            idx,
-                            data.len()
-                        )));
-                    }
-                    let val = data[idx];
-                    if val > max_val {
-                        max_val = val;
-                        max_idx = v as i64;
+                debug!("Beam search kept {} hypotheses", hypotheses.len());
+                Decoded::Beam(hypotheses, time_steps)
+            } else {
+                // Greedy CTC decoding: argmax over vocab dimension for each timestep
+                let mut token_ids = Vec::with_capacity(time_steps);
+
+                for t in 0..time_steps {
+                    let mut max_idx = 0;
+                    let mut max_val = f32::NEG_INFINITY;
+
+                    for v in 0..vocab_size {
+                        let idx = t * vocab_size + v;
+                        if idx >= data.len() {
+                            return Err(crate::Error::Model(format!(
+                                "Index out of bounds: {} >= {}",
+                                idx,
+                                data.len()
+                            )));
+                        }
+                        let val = data[idx];
+                        if val > max_val {
+                            max_val = val;
+                            max_idx = v as i64;
+                        }
                     }
-                }
-
-                token_ids.push(max_idx);
 
-                // Debug first few timesteps
-                if t < 5 {
-                    eprintln!("🔍 t={}: max_idx={}, max_val={:.4}", t, max_idx, max_val);
+                    token_ids.push(max_idx);
                 }
-            }
 
-            token_ids
+                Decoded::Greedy(token_ids)
+            }
         }; // Drop session borrow here
 
-        // Debug: log token statistics
-        let vocab = self.vocab.as_ref().unwrap();
-        let blank_token_id = (vocab.len() - 1) as i64;
-        let num_blank = token_ids.iter().filter(|&&id| id == blank_token_id).count();
-        let num_non_blank = token_ids.len() - num_blank;
-        eprintln!(
-            "🔍 Token stats: {} total, {} non-blank ({:.1}%), {} blank (ID={})",
-            token_ids.len(),
-            num_non_blank,
-            (num_non_blank as f32 / token_ids.len() as f32) * 100.0,
-            num_blank,
-            blank_token_id
-        );
-
-        // Sample first 20 non-blank tokens for debugging
-        let sample_tokens: Vec<(i64, String)> = token_ids
+        // Decode tokens to words, then use frame indices to split sentences
+        let (words, time_steps, n_tokens, token_stats) = match decoded {
+            Decoded::Transducer(tokens, time_steps) => (
+                self.decode_transducer_words(&tokens)?,
+                time_steps,
+                tokens.len(),
+                None,
+            ),
+            Decoded::Greedy(token_ids) => {
+                let stats = self.token_stats(&token_ids);
+                let words = self.decode_ctc_words(&token_ids)?;
+                (words, token_ids.len(), token_ids.len(), stats)
+            }
+            Decoded::Beam(hypotheses, time_steps) => {
+                let token_ids = self.select_hypothesis(hypotheses, time_steps)?;
+                let stats = self.token_stats(&token_ids);
+                let words = self.decode_ctc_words(&token_ids)?;
+                (words, token_ids.len(), token_ids.len(), stats)
+            }
+        };
+        let text = words
             .iter()
-            .filter(|&&id| id != blank_token_id)
-            .take(20)
-            .map(|&id| {
-                (
-                    id,
-                    vocab
-                        .get(id as usize)
-                        .cloned()
-                        .unwrap_or_else(|| format!("<id:{}>", id)),
-                )
+            .map(|w| w.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim()
+            .to_string();
+        let ms_per_frame = audio_duration * 1000.0 / time_steps.max(1) as f32;
+        let timed_words: Vec<TranscriptSegment> = words
+            .iter()
+            .map(|w| TranscriptSegment {
+                text: w.text.clone(),
+                start_ms: (w.first_frame as f32 * ms_per_frame) as u64,
+                end_ms: ((w.last_frame + 1) as f32 * ms_per_frame) as u64,
             })
             .collect();
-        if !sample_tokens.is_empty() {
-            eprintln!("🔍 Sample non-blank tokens: {:?}", sample_tokens);
-        } else {
-            eprintln!("⚠️  NO non-blank tokens found!");
-        }
-
-        // Decode tokens to text
-        let text = self.decode_ctc_tokens(&token_ids)?;
+        let segments = TranscriptSegment::split_sentences(&timed_words);
 
         let processing_time = start_time.elapsed();
         let processing_ms = processing_time.as_millis() as u64;
 
         info!(
-            "✅ Transcription complete: \"{}\" ({} ms, {:.1}x real-time)",
-            text,
+            "✅ Transcription complete: {} chars ({} ms, {:.1}x real-time)",
+            text.chars().count(),
             processing_ms,
             audio_duration * 1000.0 / processing_ms as f32
         );
+        debug!("Transcript: {}", text);
+
+        if let Some(dump) = &dump {
+            dump.json(
+                "stats",
+                &serde_json::json!({
+                    "audio": {
+                        "samples": samples.len(),
+                        "sample_rate": sample_rate,
+                        "max": max_audio,
+                        "mean": mean_audio,
+                    },
+                    "mel": {
+                        "bins": n_mel_bins,
+                        "frames": n_frames,
+                        "min": mel_min,
+                        "max": mel_max,
+                        "mean": mel_mean,
+                        "std": mel_std,
+                    },
+                    "beam_width": beam_width,
+                    "time_steps": time_steps,
+                    "tokens": n_tokens,
+                    "ctc_tokens": token_stats,
+                    "confidence": confidence,
+                    "text": text,
+                    "processing_ms": processing_ms,
+                }),
+            );
+        }
 
         // ONNX models (like Parakeet) support multilingual transcription
         // Language is auto-detected by the model
         Ok(Transcription {
             text,
-            language: None,   // Auto-detected by model
-            confidence: None, // CTC models don't easily provide confidence scores
+            language: None, // Auto-detected by model
+            confidence,
             processing_time_ms: processing_ms,
-            tokens: Some(token_ids.len()),
+            tokens: Some(n_tokens),
+            segments,
+            words: timed_words,
         })
     }
 
+    fn set_vocabulary(&mut self, vocabulary: &Vocabulary) -> bool {
+        self.vocabulary = vocabulary.clone();
+        // Transducers decode greedily, with nothing to bias
+        self.decoder_session.is_none()
+    }
+
     fn unload(&mut self) {
         info!("Unloading ONNX Runtime model");
         self.encoder_session = None;
+        self.decoder_session = None;
+        self.provider = ExecutionProvider::Cpu;
         self.vocab = None;
         self.config = None;
         self.model_dir = None;
@@ -878,8 +1054,178 @@ impl ModelRuntime for OnnxRuntime {
                 .map(|c| c.model_path.clone())
                 .unwrap_or_else(|| "unknown".to_string()),
             backend: "onnx-runtime".to_string(),
-            gpu_enabled: config.map(|c| c.use_gpu).unwrap_or(false),
+            gpu_enabled: self.provider.is_gpu(),
+        }
+    }
+}
+
+/// Greedy transducer decoding of the encoder output with the decoder/joint
+/// session; returns the tokens and the number of encoder frames
+///
+/// Follows the NeMo export layout: encoder `outputs` are
+/// `[batch, features, frames]`, and the decoder/joint graph takes one
+/// encoder frame, the last token, and the two prediction network states.
+#[cfg(feature = "onnx")]
+fn decode_transducer(
+    decoder: &mut Session,
+    encoder_outputs: &ort::session::SessionOutputs<'_>,
+    vocab_size: usize,
+) -> crate::Result<(Vec<TransducerToken>, usize)> {
+    let encoded = encoder_outputs
+        .get("outputs")
+        .ok_or_else(|| crate::Error::Model("Encoder output 'outputs' not found".to_string()))?;
+    let (shape, encoded) = encoded
+        .try_extract_tensor::<f32>()
+        .map_err(|e| crate::Error::Model(format!("Failed to extract encoder output: {}", e)))?;
+    if shape.len() != 3 {
+        return Err(crate::Error::Model(format!(
+            "Expected 3D encoder output [batch, features, frames], got shape: {:?}",
+            shape
+        )));
+    }
+    let (dims, time_steps) = (shape[1] as usize, shape[2] as usize);
+    let frames = encoder_outputs
+        .get("encoded_lengths")
+        .and_then(|lengths| lengths.try_extract_tensor::<i64>().ok())
+        .and_then(|(_, lengths)| lengths.first().copied())
+        .map_or(time_steps, |length| {
+            (length.max(0) as usize).min(time_steps)
+        });
+
+    // Prediction network states are [layers, batch, hidden]
+    let state_shape = |name: &str| {
+        decoder
+            .inputs()
+            .iter()
+            .find(|input| input.name() == name)
+            .and_then(|input| input.dtype().tensor_shape())
+            .filter(|shape| shape.len() == 3 && shape[0] > 0 && shape[2] > 0)
+            .map(|shape| vec![shape[0] as usize, 1, shape[2] as usize])
+            .ok_or_else(|| {
+                crate::Error::Model(format!("Decoder input '{}' has no fixed shape", name))
+            })
+    };
+    let shapes = (
+        state_shape("input_states_1")?,
+        state_shape("input_states_2")?,
+    );
+    let initial_state = (
+        vec![0.0f32; shapes.0.iter().product()],
+        vec![0.0f32; shapes.1.iter().product()],
+    );
+    let blank = vocab_size as i64 - 1;
+    let input_error =
+        |e: ort::Error| crate::Error::Model(format!("Failed to create decoder input: {}", e));
+
+    let tokens = transducer::greedy_decode(
+        frames,
+        vocab_size,
+        blank,
+        initial_state,
+        |frame, last_token, state| {
+            let frame_features: Box<[f32]> = (0..dims)
+                .map(|dim| encoded[dim * time_steps + frame])
+                .collect();
+            let frame_value = Value::from_array(([1, dims, 1].as_slice(), frame_features))
+                .map_err(input_error)?;
+            let target: Box<[i32]> = vec![last_token as i32].into_boxed_slice();
+            let target_value =
+                Value::from_array(([1usize, 1].as_slice(), target)).map_err(input_error)?;
+            let target_length: Box<[i32]> = vec![1].into_boxed_slice();
+            let target_length_value =
+                Value::from_array(([1usize].as_slice(), target_length)).map_err(input_error)?;
+            let state_values = (
+                Value::from_array((shapes.0.as_slice(), state.0.clone().into_boxed_slice()))
+                    .map_err(input_error)?,
+                Value::from_array((shapes.1.as_slice(), state.1.clone().into_boxed_slice()))
+                    .map_err(input_error)?,
+            );
+
+            let inputs = ort::inputs![
+                "encoder_outputs" => frame_value,
+                "targets" => target_value,
+                "target_length" => target_length_value,
+                "input_states_1" => state_values.0,
+                "input_states_2" => state_values.1
+            ];
+            let outputs = decoder
+                .run(inputs)
+                .map_err(|e| crate::Error::Model(format!("Decoder inference failed: {}", e)))?;
+            let output = |name: &str| {
+                outputs
+                    .get(name)
+                    .and_then(|value| value.try_extract_tensor::<f32>().ok())
+                    .map(|(_, data)| data.to_vec())
+                    .ok_or_else(|| {
+                        crate::Error::Model(format!("Decoder output '{}' not found", name))
+                    })
+            };
+            Ok((
+                output("outputs")?,
+                (output("output_states_1")?, output("output_states_2")?),
+            ))
+        },
+    )?;
+    Ok((tokens, frames))
+}
+
+/// Shallow fusion for the CTC beam search: the language model scores
+/// completed words (the last word may still grow), weighted by `weight`,
+/// and the custom vocabulary adds its term boosts
+#[cfg(feature = "onnx")]
+struct Fusion<'a> {
+    language_model: Option<&'a dyn LanguageModel>,
+    weight: f32,
+    vocabulary: &'a Vocabulary,
+    /// Language model scores by completed words
+    cache: RefCell<HashMap<Vec<String>, f32>>,
+}
+
+#[cfg(feature = "onnx")]
+impl Fusion<'_> {
+    /// Bonus for a beam prefix
+    fn bonus(&self, vocab: &[String], prefix: &[i64]) -> f32 {
+        let mut words: Vec<CtcWord> = Vec::new();
+        for piece in prefix.iter().filter_map(|&id| vocab.get(id as usize)) {
+            if !(piece.starts_with('<') && piece.ends_with('>')) {
+                push_piece(&mut words, piece, 0);
+            }
+        }
+        let partial = words.pop();
+        let completed: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        let bias = self
+            .vocabulary
+            .bonus(&completed, partial.as_ref().map(|word| word.text.as_str()));
+        let Some(lm) = self.language_model.filter(|_| !words.is_empty()) else {
+            return bias;
+        };
+        let words: Vec<String> = words.into_iter().map(|word| word.text).collect();
+        let score = *self
+            .cache
+            .borrow_mut()
+            .entry(words)
+            .or_insert_with_key(|words| {
+                let words: Vec<&str> = words.iter().map(String::as_str).collect();
+                self.weight * lm.score(&words)
+            });
+        score + bias
+    }
+}
+
+/// Add a SentencePiece token to the words, starting a new word at `▁`
+#[cfg(feature = "onnx")]
+fn push_piece(words: &mut Vec<CtcWord>, token: &str, frame: usize) {
+    match (token.strip_prefix('▁'), words.last_mut()) {
+        (None, Some(word)) => {
+            word.text.push_str(token);
+            word.last_frame = frame;
         }
+        (stripped, _) => words.push(CtcWord {
+            // Remove the ▁ marker
+            text: stripped.unwrap_or(token).to_string(),
+            first_frame: frame,
+            last_frame: frame,
+        }),
     }
 }
 
@@ -963,6 +1309,7 @@ mod tests {
     fn test_decode_empty_tokens() {
         let backend = OnnxRuntime {
             encoder_session: None,
+            decoder_session: None,
             vocab: Some(vec![
                 "<blank>".to_string(),
                 "a".to_string(),
@@ -971,6 +1318,11 @@ mod tests {
             config: None,
             model_dir: None,
             n_mel_bins: 80,
+            rescoring: RescoringConfig::default(),
+            language_model: None,
+            vocabulary: Vocabulary::default(),
+            execution_provider: "auto".to_string(),
+            provider: ExecutionProvider::Cpu,
         };
 
         let result = backend.decode_ctc_tokens(&[]);
@@ -1,28 +1,56 @@
 //! Model Registry
 //!
 //! Central registry of available Whisper models with metadata.
+//!
+//! The built-in models can be extended or overridden without recompiling:
+//! definitions from the cached remote registry (`model.registry_url`,
+//! refreshed with `onevox models refresh`) replace built-ins with the same
+//! ID, and the user manifest (`models.toml` in the config dir) replaces
+//! both.
 
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// User manifest of extra or overriding models, in the config dir
+pub const MANIFEST_FILE: &str = "models.toml";
+
+/// Cached copy of the remote registry, in the cache dir
+pub const REMOTE_CACHE_FILE: &str = "registry.json";
+
+/// Age after which the daemon refreshes the cached remote registry
+pub const REMOTE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Model format/backend
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ModelFormat {
     /// GGML format (whisper.cpp)
+    #[serde(alias = "ggml")]
     GGML,
     /// ONNX format
+    #[serde(alias = "onnx")]
     ONNX,
     /// PyTorch format
+    #[serde(alias = "pytorch")]
     PyTorch,
 }
 
 /// Available Whisper model sizes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ModelSize {
+    #[serde(alias = "tiny")]
     Tiny,
+    #[default]
+    #[serde(alias = "base")]
     Base,
+    #[serde(alias = "small")]
     Small,
+    #[serde(alias = "medium")]
     Medium,
+    #[serde(alias = "large")]
     Large,
 }
 
@@ -39,9 +67,12 @@ impl ModelSize {
 }
 
 /// Model variant (multilingual vs English-only)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ModelVariant {
+    #[default]
+    #[serde(alias = "multilingual")]
     Multilingual,
+    #[serde(alias = "english-only", alias = "english")]
     EnglishOnly,
 }
 
@@ -51,19 +82,23 @@ pub struct ModelMetadata {
     /// Model identifier (e.g., "whisper-tiny.en")
     pub id: String,
 
-    /// Display name
+    /// Display name (defaults to the ID in manifests)
+    #[serde(default)]
     pub name: String,
 
     /// Model size category
+    #[serde(default)]
     pub size: ModelSize,
 
     /// Model variant
+    #[serde(default)]
     pub variant: ModelVariant,
 
     /// Model format/backend
     pub format: ModelFormat,
 
     /// Approximate size in bytes
+    #[serde(default)]
     pub size_bytes: u64,
 
     /// Hugging Face repository
@@ -77,15 +112,22 @@ pub struct ModelMetadata {
     pub file_sha256: HashMap<String, String>,
 
     /// Speed factor (relative to real-time, 1.0 = real-time)
+    #[serde(default = "default_speed_factor")]
     pub speed_factor: f32,
 
     /// Memory requirements in MB
+    #[serde(default)]
     pub memory_mb: u32,
 
     /// Description
+    #[serde(default)]
     pub description: String,
 }
 
+fn default_speed_factor() -> f32 {
+    1.0
+}
+
 impl ModelMetadata {
     /// Get download URLs for all required files
     pub fn download_urls(&self) -> Vec<(String, String)> {
@@ -100,6 +142,80 @@ impl ModelMetadata {
             })
             .collect()
     }
+
+    /// Check a definition from a manifest, filling in a missing name
+    ///
+    /// IDs and file names become paths under the models dir, so anything
+    /// that could escape it is rejected.
+    fn validate(&mut self) -> Result<(), String> {
+        let is_plain = |path: &str| {
+            !path.is_empty()
+                && Path::new(path)
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)))
+        };
+
+        if !is_plain(&self.id) || self.id.contains(['/', '\\']) {
+            return Err(format!("invalid model ID '{}'", self.id));
+        }
+        if self.files.is_empty() {
+            return Err(format!("model '{}' lists no files", self.id));
+        }
+        if let Some(file) = self.files.iter().find(|file| !is_plain(file)) {
+            return Err(format!("model '{}' has invalid file '{}'", self.id, file));
+        }
+        if self
+            .hf_repo
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .count()
+            != 2
+        {
+            return Err(format!(
+                "model '{}' needs hf_repo as \"owner/name\"",
+                self.id
+            ));
+        }
+        if self.name.is_empty() {
+            self.name = self.id.clone();
+        }
+        Ok(())
+    }
+}
+
+/// Model definitions from `models.toml` or a remote registry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelManifest {
+    #[serde(default)]
+    pub models: Vec<ModelMetadata>,
+}
+
+impl ModelManifest {
+    /// Parse a TOML manifest (`[[models]]` tables)
+    pub fn from_toml(toml: &str) -> crate::Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| crate::Error::Config(format!("Invalid model manifest: {}", e)))
+    }
+
+    /// Parse a JSON manifest (`{"models": [...]}`)
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| crate::Error::Config(format!("Invalid model registry: {}", e)))
+    }
+
+    /// Definitions that pass validation; the rest are logged and dropped
+    fn into_valid(self, source: &str) -> Vec<ModelMetadata> {
+        self.models
+            .into_iter()
+            .filter_map(|mut model| match model.validate() {
+                Ok(()) => Some(model),
+                Err(e) => {
+                    warn!("Skipping model from {}: {}", source, e);
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 /// Model registry with all available models
@@ -108,8 +224,87 @@ pub struct ModelRegistry {
 }
 
 impl ModelRegistry {
-    /// Create a new model registry with all available models
+    /// Built-in models, extended by the cached remote registry and the
+    /// user manifest
     pub fn new() -> Self {
+        let mut registry = Self::builtin();
+
+        if let Ok(path) = crate::platform::paths::cache_dir().map(|d| d.join(REMOTE_CACHE_FILE))
+            && let Ok(json) = std::fs::read_to_string(&path)
+        {
+            match ModelManifest::from_json(&json) {
+                Ok(manifest) => registry.merge(manifest.into_valid("remote registry")),
+                Err(e) => warn!("Ignoring cached registry {}: {}", path.display(), e),
+            }
+        }
+
+        if let Ok(path) = crate::platform::paths::config_dir().map(|d| d.join(MANIFEST_FILE))
+            && let Ok(toml) = std::fs::read_to_string(&path)
+        {
+            match ModelManifest::from_toml(&toml) {
+                Ok(manifest) => registry.merge(manifest.into_valid(MANIFEST_FILE)),
+                Err(e) => warn!("Ignoring {}: {}", path.display(), e),
+            }
+        }
+
+        registry
+    }
+
+    /// Add models, replacing any with the same ID
+    pub fn merge(&mut self, models: Vec<ModelMetadata>) {
+        for model in models {
+            match self.models.iter_mut().find(|m| m.id == model.id) {
+                Some(existing) => *existing = model,
+                None => self.models.push(model),
+            }
+        }
+    }
+
+    /// Fetch the registry at `url` into the local cache, returning how many
+    /// valid models it defines
+    ///
+    /// Only HTTPS URLs are accepted. The cache is left untouched if the
+    /// download or parse fails.
+    pub async fn refresh_remote(url: &str) -> anyhow::Result<usize> {
+        if !url.starts_with("https://") {
+            anyhow::bail!("Model registry URL must use https: {}", url);
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("onevox/0.1.0")
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        let json = client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to fetch model registry")?
+            .text()
+            .await?;
+        let count = ModelManifest::from_json(&json)?
+            .into_valid("remote registry")
+            .len();
+
+        let path = crate::platform::paths::cache_dir()?.join(REMOTE_CACHE_FILE);
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Model registry refreshed from {} ({} models)", url, count);
+        Ok(count)
+    }
+
+    /// Whether the cached remote registry is missing or older than `max_age`
+    pub fn remote_cache_stale(max_age: Duration) -> bool {
+        crate::platform::paths::cache_dir()
+            .and_then(|dir| Ok(std::fs::metadata(dir.join(REMOTE_CACHE_FILE))?.modified()?))
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age > max_age)
+    }
+
+    /// Models compiled into this build
+    pub fn builtin() -> Self {
         Self {
             models: vec![
                 // ============================================================
@@ -316,6 +511,50 @@ impl ModelRegistry {
                     description: "High-performance multilingual ASR (INT8 quantized). Supports 100+ languages with CTC architecture. Optimized for CPU inference."
                         .to_string(),
                 },
+
+                // Parakeet TDT 0.6B v2 - English (INT8 Quantized)
+                ModelMetadata {
+                    id: "parakeet-tdt-0.6b-v2".to_string(),
+                    name: "NVIDIA Parakeet TDT 0.6B v2 (English)".to_string(),
+                    size: ModelSize::Base,
+                    variant: ModelVariant::EnglishOnly,
+                    format: ModelFormat::ONNX,
+                    size_bytes: 661 * 1024 * 1024, // ~661 MB (INT8 quantized)
+                    hf_repo: "istupakov/parakeet-tdt-0.6b-v2-onnx".to_string(),
+                    files: vec![
+                        "encoder-model.int8.onnx".to_string(),
+                        "decoder_joint-model.int8.onnx".to_string(),
+                        "vocab.txt".to_string(),
+                        "config.json".to_string(),
+                    ],
+                    file_sha256: HashMap::new(),
+                    speed_factor: 50.0,
+                    memory_mb: 500,
+                    description: "Token-and-duration transducer (INT8 quantized). More accurate than the CTC model, with punctuation and capitalization."
+                        .to_string(),
+                },
+
+                // Parakeet TDT 0.6B v3 - European languages (INT8 Quantized)
+                ModelMetadata {
+                    id: "parakeet-tdt-0.6b-v3".to_string(),
+                    name: "NVIDIA Parakeet TDT 0.6B v3 (Multilingual)".to_string(),
+                    size: ModelSize::Base,
+                    variant: ModelVariant::Multilingual,
+                    format: ModelFormat::ONNX,
+                    size_bytes: 670 * 1024 * 1024, // ~670 MB (INT8 quantized)
+                    hf_repo: "istupakov/parakeet-tdt-0.6b-v3-onnx".to_string(),
+                    files: vec![
+                        "encoder-model.int8.onnx".to_string(),
+                        "decoder_joint-model.int8.onnx".to_string(),
+                        "vocab.txt".to_string(),
+                        "config.json".to_string(),
+                    ],
+                    file_sha256: HashMap::new(),
+                    speed_factor: 50.0,
+                    memory_mb: 500,
+                    description: "Token-and-duration transducer for 25 European languages (INT8 quantized), with punctuation and capitalization."
+                        .to_string(),
+                },
             ],
         }
     }
@@ -353,9 +592,61 @@ mod tests {
         assert!(!registry.list_models().is_empty());
         assert!(registry.get_model("ggml-base.en").is_some());
         assert!(registry.get_model("parakeet-ctc-0.6b").is_some());
+        assert!(registry.get_model("parakeet-tdt-0.6b-v2").is_some());
         assert!(registry.get_model("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_manifest_merge() {
+        let manifest = ModelManifest::from_toml(
+            r#"
+            [[models]]
+            id = "ggml-distil-large-v3"
+            format = "ggml"
+            size = "large"
+            hf_repo = "distil-whisper/distil-large-v3-ggml"
+            files = ["ggml-distil-large-v3.bin"]
+
+            [[models]]
+            id = "ggml-base.en"
+            name = "Pinned base.en"
+            format = "GGML"
+            variant = "english-only"
+            hf_repo = "example/whisper-mirror"
+            files = ["ggml-base.en.bin"]
+
+            [[models]]
+            id = "escape"
+            format = "onnx"
+            hf_repo = "example/escape"
+            files = ["../../.bashrc"]
+            "#,
+        )
+        .unwrap();
+
+        let mut registry = ModelRegistry::builtin();
+        let builtin = registry.list_models().len();
+        registry.merge(manifest.into_valid("test"));
+        assert_eq!(registry.list_models().len(), builtin + 1);
+
+        let added = registry.get_model("ggml-distil-large-v3").unwrap();
+        assert_eq!(added.name, "ggml-distil-large-v3");
+        assert_eq!(added.size, ModelSize::Large);
+        assert_eq!(added.speed_factor, 1.0);
+
+        let overridden = registry.get_model("ggml-base.en").unwrap();
+        assert_eq!(overridden.name, "Pinned base.en");
+        assert_eq!(overridden.variant, ModelVariant::EnglishOnly);
+        assert!(
+            overridden.download_urls()[0]
+                .1
+                .contains("example/whisper-mirror")
+        );
+        assert!(registry.get_model("escape").is_none());
+
+        assert!(ModelManifest::from_json(r#"{"models": [{"id": "x"}]}"#).is_err());
+    }
+
     #[test]
     fn test_download_urls() {
         let registry = ModelRegistry::new();
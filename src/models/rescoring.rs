@@ -0,0 +1,332 @@
+//! N-Best Rescoring
+//!
+//! When the CTC beam search (`ctc_beam`) is enabled, its n-best hypotheses
+//! are reordered by adding a weighted language model score to each
+//! acoustic score before the best one is picked. That settles ambiguous
+//! audio ("their" / "there", split words) without touching the acoustic
+//! model. Language models implement [`LanguageModel`]; a word-frequency
+//! model is built in and an ARPA n-gram model (e.g. from KenLM) can be
+//! loaded from `model.lm_path` or `model.rescoring.lm_path`. The same model
+//! is fused into the beam search while it runs (see `ctc_beam`).
+
+use crate::config::RescoringConfig;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Scores word sequences for rescoring
+pub trait LanguageModel: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &str;
+
+    /// Natural log probability of `words` (higher is more likely)
+    fn score(&self, words: &[&str]) -> f32;
+}
+
+/// Common English words, most frequent first, for the built-in model
+const COMMON_WORDS: &str = "the of and to a in is you that it he was for on are as with his they \
+     i at be this have from or one had by word but not what all were we when your can said there \
+     use an each which she do how their if will up other about out many then them these so some \
+     her would make like him into time has look two more write go see number no way could people \
+     my than first water been call who oil its now find long down day did get come made may part \
+     over new sound take only little work know place year live me back give most very after thing \
+     our just name good sentence man think say great where help through much before line right too \
+     mean old any same tell boy follow came want show also around form three small set put end \
+     does another well large must big even such because turn here why ask went men read need land \
+     different home us move try kind hand picture again change off play spell air away animal house \
+     point page letter mother answer found study still learn should world";
+
+/// Log probability of a word the model has never seen
+const UNKNOWN_LOG_PROB: f32 = -16.0;
+
+/// Unigram model: each word scores its relative frequency
+pub struct WordFrequencyModel {
+    log_probs: HashMap<String, f32>,
+    unknown: f32,
+}
+
+impl WordFrequencyModel {
+    /// Build from word counts
+    pub fn from_counts(counts: impl IntoIterator<Item = (String, f64)>) -> Self {
+        let counts: Vec<(String, f64)> = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 0.0)
+            .map(|(word, count)| (word.to_lowercase(), count))
+            .collect();
+        let total: f64 = counts.iter().map(|(_, count)| count).sum();
+        let mut log_probs = HashMap::new();
+        for (word, count) in counts {
+            *log_probs.entry(word).or_insert(0.0) += count;
+        }
+        let log_probs: HashMap<String, f32> = log_probs
+            .into_iter()
+            .map(|(word, count)| (word, (count / total).ln() as f32))
+            .collect();
+        // Unseen words rank below the rarest known one
+        let rarest = log_probs.values().copied().fold(0.0f32, f32::min);
+        Self {
+            log_probs,
+            unknown: (rarest - 2.0).min(UNKNOWN_LOG_PROB),
+        }
+    }
+
+    /// Common English words weighted by rank (Zipf's law)
+    pub fn builtin() -> Self {
+        Self::from_counts(
+            COMMON_WORDS
+                .split_whitespace()
+                .enumerate()
+                .map(|(rank, word)| (word.to_string(), 1.0 / (rank + 1) as f64)),
+        )
+    }
+
+    /// Load a word list: "word count" per line, or just "word" (count 1)
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let counts = text.lines().filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let word = parts.next()?;
+            let count = parts.next().and_then(|c| c.parse().ok()).unwrap_or(1.0);
+            Some((word.to_string(), count))
+        });
+        Ok(Self::from_counts(counts))
+    }
+}
+
+impl LanguageModel for WordFrequencyModel {
+    fn name(&self) -> &str {
+        "word-frequency"
+    }
+
+    fn score(&self, words: &[&str]) -> f32 {
+        words
+            .iter()
+            .map(|word| {
+                self.log_probs
+                    .get(&word.to_lowercase())
+                    .copied()
+                    .unwrap_or(self.unknown)
+            })
+            .sum()
+    }
+}
+
+/// Start of KenLM's `build_binary` output
+const KENLM_BINARY_MAGIC: &[u8] = b"mmap lm ";
+
+/// Back-off n-gram model read from an ARPA file
+pub struct ArpaModel {
+    order: usize,
+    /// N-gram → (log probability, back-off weight), both natural log
+    ngrams: HashMap<Vec<String>, (f32, f32)>,
+}
+
+impl ArpaModel {
+    /// Parse ARPA text (`\data\`, `\N-grams:` sections, `\end\`)
+    pub fn parse(text: &str) -> crate::Result<Self> {
+        let ln_10 = std::f32::consts::LN_10;
+        let mut ngrams = HashMap::new();
+        let mut order = 0;
+        let mut section = None;
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(n) = line
+                .strip_prefix('\\')
+                .and_then(|rest| rest.strip_suffix("-grams:"))
+            {
+                let n: usize = n.parse().map_err(|_| {
+                    crate::Error::Model(format!("Invalid ARPA section header '{}'", line))
+                })?;
+                order = order.max(n);
+                section = Some(n);
+                continue;
+            }
+            if line.starts_with('\\') {
+                section = None;
+                continue;
+            }
+            let Some(n) = section else {
+                continue;
+            };
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < n + 1 {
+                return Err(crate::Error::Model(format!(
+                    "Invalid {}-gram line '{}'",
+                    n, line
+                )));
+            }
+            let log_prob: f32 = fields[0]
+                .parse()
+                .map_err(|_| crate::Error::Model(format!("Invalid probability in '{}'", line)))?;
+            let backoff: f32 = fields
+                .get(n + 1)
+                .and_then(|b| b.parse().ok())
+                .unwrap_or(0.0);
+            let words = fields[1..=n].iter().map(|w| w.to_lowercase()).collect();
+            ngrams.insert(words, (log_prob * ln_10, backoff * ln_10));
+        }
+
+        if ngrams.is_empty() {
+            return Err(crate::Error::Model("ARPA model has no n-grams".to_string()));
+        }
+        Ok(Self { order, ngrams })
+    }
+
+    /// Load an ARPA file
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        if bytes.starts_with(KENLM_BINARY_MAGIC) {
+            return Err(crate::Error::Model(format!(
+                "{} is a KenLM binary model; point lm_path at the ARPA file it was built from",
+                path.display()
+            )));
+        }
+        let text = String::from_utf8(bytes).map_err(|_| {
+            crate::Error::Model(format!("{} is not an ARPA text file", path.display()))
+        })?;
+        Self::parse(&text)
+    }
+
+    /// `ln P(word | history)`, backing off to shorter histories
+    fn log_prob(&self, history: &[String], word: &str) -> f32 {
+        let mut backoff = 0.0;
+        let longest = history.len().min(self.order.saturating_sub(1));
+        for n in (0..=longest).rev() {
+            let context = &history[history.len() - n..];
+            let mut key = context.to_vec();
+            key.push(word.to_string());
+            if let Some((log_prob, _)) = self.ngrams.get(&key) {
+                return backoff + log_prob;
+            }
+            if let Some((_, weight)) = self.ngrams.get(context) {
+                backoff += weight;
+            }
+        }
+        let unknown = ["<unk>".to_string()];
+        backoff
+            + self
+                .ngrams
+                .get(unknown.as_slice())
+                .map(|(log_prob, _)| *log_prob)
+                .unwrap_or(UNKNOWN_LOG_PROB)
+    }
+}
+
+impl LanguageModel for ArpaModel {
+    fn name(&self) -> &str {
+        "arpa"
+    }
+
+    fn score(&self, words: &[&str]) -> f32 {
+        let mut history = vec!["<s>".to_string()];
+        let mut total = 0.0;
+        for word in words
+            .iter()
+            .map(|w| w.to_lowercase())
+            .chain(["</s>".to_string()])
+        {
+            total += self.log_prob(&history, &word);
+            history.push(word);
+        }
+        total
+    }
+}
+
+/// Language model for `model.rescoring` (`None` when `lm = "none"`)
+pub fn from_config(config: &RescoringConfig) -> crate::Result<Option<Box<dyn LanguageModel>>> {
+    let path = config.lm_path.as_deref().map(Path::new);
+    let model: Box<dyn LanguageModel> = match config.lm.to_ascii_lowercase().as_str() {
+        "none" => return Ok(None),
+        "word-frequency" | "word_frequency" => match path {
+            Some(path) => Box::new(WordFrequencyModel::load(path)?),
+            None => Box::new(WordFrequencyModel::builtin()),
+        },
+        "arpa" => {
+            let path = path.ok_or_else(|| {
+                crate::Error::Config(
+                    "model.rescoring.lm = \"arpa\" needs model.rescoring.lm_path".to_string(),
+                )
+            })?;
+            Box::new(ArpaModel::load(path)?)
+        }
+        other => {
+            return Err(crate::Error::Config(format!(
+                "Unknown rescoring language model '{}' (expected word-frequency, arpa, or none)",
+                other
+            )));
+        }
+    };
+    info!(
+        "Rescoring n-best hypotheses with the {} model",
+        model.name()
+    );
+    Ok(Some(model))
+}
+
+/// Order `(text, acoustic score)` hypotheses by acoustic score plus
+/// `weight` × language model score, returning their indices best first
+pub fn rank(hypotheses: &[(String, f32)], lm: &dyn LanguageModel, weight: f32) -> Vec<usize> {
+    let totals: Vec<f32> = hypotheses
+        .iter()
+        .map(|(text, acoustic)| {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            acoustic + weight * lm.score(&words)
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..hypotheses.len()).collect();
+    order.sort_by(|&a, &b| totals[b].total_cmp(&totals[a]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescoring_reorders_hypotheses() {
+        let unigrams = WordFrequencyModel::builtin();
+        assert!(unigrams.score(&["the"]) > unigrams.score(&["world"]));
+        assert!(unigrams.score(&["world"]) > unigrams.score(&["wurld"]));
+
+        // Acoustically "wurld" wins narrowly; the word model flips it
+        let hypotheses = vec![
+            ("hello wurld".to_string(), -1.0),
+            ("hello world".to_string(), -1.2),
+        ];
+        assert_eq!(rank(&hypotheses, &unigrams, 0.3), vec![1, 0]);
+        assert_eq!(rank(&hypotheses, &unigrams, 0.0), vec![0, 1]);
+
+        let arpa = ArpaModel::parse(
+            "\\data\\\nngram 1=4\nngram 2=2\n\n\
+             \\1-grams:\n-1.0\t<s>\t-0.3\n-1.0\t</s>\n-1.5\tthere\t-0.2\n-1.5\ttheir\t-0.2\n\n\
+             \\2-grams:\n-0.2\t<s> there\n-0.1\tthere </s>\n\n\\end\\\n",
+        )
+        .unwrap();
+        assert_eq!(arpa.order, 2);
+        assert!(arpa.score(&["There"]) > arpa.score(&["their"]));
+
+        let mut config = RescoringConfig::default();
+        assert!(from_config(&config).unwrap().is_some());
+        config.lm = "arpa".to_string();
+        assert!(from_config(&config).is_err());
+        config.lm = "none".to_string();
+        assert!(from_config(&config).unwrap().is_none());
+
+        // model.decoding / model.lm_path switch on the beam and the n-gram model
+        let mut model = crate::config::ModelConfig {
+            decoding: "beam".to_string(),
+            lm_path: Some("en.arpa".to_string()),
+            ..Default::default()
+        };
+        let decoding = model.decoding_config().unwrap();
+        assert!(decoding.beam_width > 1);
+        assert_eq!(decoding.lm, "arpa");
+        assert_eq!(decoding.lm_path.as_deref(), Some("en.arpa"));
+        model.decoding = "fast".to_string();
+        assert!(model.decoding_config().is_err());
+    }
+}
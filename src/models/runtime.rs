@@ -4,6 +4,8 @@
 
 use crate::audio::buffer::AudioChunk;
 use crate::vad::SpeechSegment;
+use crate::vocabulary::Vocabulary;
+use serde::{Deserialize, Serialize};
 
 /// Transcription result
 #[derive(Debug, Clone)]
@@ -18,6 +20,12 @@ pub struct Transcription {
     pub processing_time_ms: u64,
     /// Number of tokens generated
     pub tokens: Option<usize>,
+    /// Sentence-level segments with audio offsets
+    /// (empty when the backend does not provide timestamps)
+    pub segments: Vec<TranscriptSegment>,
+    /// Word-level timings, as the model spelled the words before
+    /// post-processing (empty when the backend does not provide them)
+    pub words: Vec<TranscriptSegment>,
 }
 
 impl Transcription {
@@ -29,6 +37,8 @@ impl Transcription {
             confidence: None,
             processing_time_ms: 0,
             tokens: None,
+            segments: Vec::new(),
+            words: Vec::new(),
         }
     }
 
@@ -38,6 +48,96 @@ impl Transcription {
     }
 }
 
+/// Pause between words long enough to end a sentence without punctuation
+const SENTENCE_PAUSE_MS: u64 = 700;
+
+/// Timed piece of a transcription (a word or a sentence)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// Segment text
+    pub text: String,
+    /// Start offset from the beginning of the audio in milliseconds
+    pub start_ms: u64,
+    /// End offset from the beginning of the audio in milliseconds
+    pub end_ms: u64,
+}
+
+impl TranscriptSegment {
+    /// The same segment moved `offset_ms` later
+    pub fn shifted(&self, offset_ms: u64) -> Self {
+        Self {
+            text: self.text.trim().to_string(),
+            start_ms: self.start_ms + offset_ms,
+            end_ms: self.end_ms + offset_ms,
+        }
+    }
+
+    /// Join timed subword tokens into words
+    ///
+    /// A token starting with whitespace begins a new word; any other token
+    /// (a word piece or punctuation) continues the previous one.
+    pub fn join_tokens<'a>(
+        tokens: impl IntoIterator<Item = (&'a str, u64, u64)>,
+    ) -> Vec<TranscriptSegment> {
+        let mut words: Vec<TranscriptSegment> = Vec::new();
+        for (piece, start_ms, end_ms) in tokens {
+            let text = piece.trim();
+            if text.is_empty() {
+                continue;
+            }
+            match words.last_mut() {
+                Some(word) if !piece.starts_with(char::is_whitespace) => {
+                    word.text.push_str(text);
+                    word.end_ms = end_ms.max(word.end_ms);
+                }
+                _ => words.push(TranscriptSegment {
+                    text: text.to_string(),
+                    start_ms,
+                    end_ms: end_ms.max(start_ms),
+                }),
+            }
+        }
+        words
+    }
+
+    /// Group timed words into sentences
+    ///
+    /// A sentence ends at terminal punctuation, or at a long pause for
+    /// models that do not emit punctuation.
+    pub fn split_sentences(words: &[TranscriptSegment]) -> Vec<TranscriptSegment> {
+        let mut sentences = Vec::new();
+        let mut current: Option<TranscriptSegment> = None;
+
+        for word in words.iter().filter(|w| !w.text.is_empty()) {
+            if let Some(sentence) =
+                current.take_if(|s| word.start_ms.saturating_sub(s.end_ms) >= SENTENCE_PAUSE_MS)
+            {
+                sentences.push(sentence);
+            }
+
+            let sentence = current.get_or_insert_with(|| TranscriptSegment {
+                text: String::new(),
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+            });
+            if !sentence.text.is_empty() {
+                sentence.text.push(' ');
+            }
+            sentence.text.push_str(&word.text);
+            sentence.end_ms = word.end_ms;
+
+            if word.text.ends_with(['.', '!', '?'])
+                && let Some(sentence) = current.take()
+            {
+                sentences.push(sentence);
+            }
+        }
+
+        sentences.extend(current);
+        sentences
+    }
+}
+
 /// Model runtime configuration
 #[derive(Debug, Clone)]
 pub struct ModelConfig {
@@ -49,6 +149,12 @@ pub struct ModelConfig {
     pub n_threads: u32,
     /// Beam size for decoding (higher = better quality, slower)
     pub beam_size: u32,
+    /// Decoding language (ISO 639-1), `None` for auto-detection
+    pub language: Option<String>,
+    /// Translate the speech to English instead of transcribing it
+    pub translate: bool,
+    /// Text the decoder continues from (earlier context, vocabulary)
+    pub prompt: Option<String>,
 }
 
 impl Default for ModelConfig {
@@ -57,7 +163,10 @@ impl Default for ModelConfig {
             model_path: "models/ggml-base.en.bin".to_string(),
             use_gpu: true,
             n_threads: default_thread_count(),
-            beam_size: 5,
+            beam_size: 1,
+            language: None,
+            translate: false,
+            prompt: None,
         }
     }
 }
@@ -77,6 +186,11 @@ pub trait ModelRuntime: Send + Sync {
     /// Check if model is loaded
     fn is_loaded(&self) -> bool;
 
+    /// Sample rate the model expects input at (mono)
+    fn sample_rate(&self) -> u32 {
+        16000
+    }
+
     /// Transcribe raw audio samples
     /// Samples should be mono, f32, 16kHz
     fn transcribe(&mut self, samples: &[f32], sample_rate: u32) -> crate::Result<Transcription>;
@@ -93,6 +207,36 @@ pub trait ModelRuntime: Send + Sync {
         self.transcribe(samples, sample_rate)
     }
 
+    /// Set the decoding language for subsequent transcriptions
+    ///
+    /// `None` restores auto-detection. Backends without language
+    /// selection ignore this.
+    fn set_language(&mut self, _language: Option<&str>) {}
+
+    /// Translate subsequent transcriptions to English (or stop)
+    ///
+    /// Returns whether the backend can translate; those that can't keep
+    /// transcribing.
+    fn set_translate(&mut self, _translate: bool) -> bool {
+        false
+    }
+
+    /// Set the text subsequent transcriptions continue from
+    ///
+    /// `None` clears it. Returns whether the backend takes a prompt; those
+    /// that don't ignore it.
+    fn set_prompt(&mut self, _prompt: Option<&str>) -> bool {
+        false
+    }
+
+    /// Bias subsequent transcriptions towards the user's vocabulary
+    ///
+    /// Returns whether the backend biases its decoder; the post-processing
+    /// replacement applies either way.
+    fn set_vocabulary(&mut self, _vocabulary: &Vocabulary) -> bool {
+        false
+    }
+
     /// Unload the model and free resources
     fn unload(&mut self);
 
@@ -129,3 +273,57 @@ impl Default for ModelInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+        }
+    }
+
+    #[test]
+    fn test_split_sentences_on_punctuation_and_pauses() {
+        let words = vec![
+            word("Hello", 0, 300),
+            word("there.", 320, 600),
+            word("How", 700, 800),
+            word("are", 820, 900),
+            word("you", 920, 1100),
+            // Long pause without punctuation starts a new sentence
+            word("next", 2500, 2700),
+            word("thought", 2720, 3000),
+        ];
+
+        let sentences = TranscriptSegment::split_sentences(&words);
+        assert_eq!(
+            sentences,
+            vec![
+                word("Hello there.", 0, 600),
+                word("How are you", 700, 1100),
+                word("next thought", 2500, 3000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_tokens_into_words() {
+        let tokens = [
+            (" Hel", 0, 150),
+            ("lo", 150, 300),
+            (",", 300, 310),
+            (" world", 400, 700),
+            (" ", 700, 700),
+            ("!", 700, 720),
+        ];
+        assert_eq!(
+            TranscriptSegment::join_tokens(tokens),
+            vec![word("Hello,", 0, 310), word("world!", 400, 720)]
+        );
+        assert_eq!(word("hi", 10, 20).shifted(1000), word("hi", 1010, 1020));
+    }
+}
@@ -0,0 +1,354 @@
+//! whisper-server Binary
+//!
+//! The `whisper-server` backend needs whisper.cpp's server binary. One on
+//! PATH (or the configured path) is used as is; otherwise the daemon
+//! installs a pinned release into the cache dir before loading the model
+//! (`[model.server] auto_install`):
+//!
+//! 1. A prebuilt binary for the OS and architecture from the onevox
+//!    release bucket, verified against the SHA-256 pinned in this source
+//! 2. Failing that (or with no digest pinned for the platform), whisper.cpp
+//!    cloned and built with git and cmake
+//!
+//! Either way the binary's checksum is recorded and re-checked before it is
+//! run.
+
+use super::onnx_dylib::sha256_file;
+use crate::config::ModelConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{info, warn};
+
+/// whisper.cpp release installed by the daemon
+pub const WHISPER_CPP_VERSION: &str = "1.7.6";
+
+/// Release bucket holding the prebuilt binaries
+const RELEASE_BASE_URL: &str = "https://github.com/kssgarcia/onevox/releases/download";
+
+/// Repository built from when no prebuilt binary is available
+const SOURCE_REPO: &str = "https://github.com/ggml-org/whisper.cpp";
+
+/// Manifest written next to an installed binary
+const MANIFEST_FILE: &str = "binary.json";
+
+/// SHA-256 of each prebuilt archive of [`WHISPER_CPP_VERSION`]
+///
+/// Pinned here instead of fetched from the release, so whoever can change
+/// the release can't vouch for their own files. Update it with the version
+/// when the archives are published; platforms without an entry build from
+/// source.
+const PINNED_DIGESTS: &[(&str, &str)] = &[];
+
+/// Binary file name on this platform
+fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "whisper-server.exe"
+    } else {
+        "whisper-server"
+    }
+}
+
+/// Prebuilt archive for an OS / architecture pair (`std::env::consts`
+/// names), e.g. `whisper-server-linux-x86_64.tar.gz`
+pub fn archive_for_platform(os: &str, arch: &str) -> Option<String> {
+    match (os, arch) {
+        ("linux" | "macos" | "windows", "x86_64" | "aarch64") => {
+            Some(format!("whisper-server-{}-{}.tar.gz", os, arch))
+        }
+        _ => None,
+    }
+}
+
+fn archive_url(archive: &str) -> String {
+    format!(
+        "{}/whisper.cpp-v{}/{}",
+        RELEASE_BASE_URL, WHISPER_CPP_VERSION, archive
+    )
+}
+
+/// Details of an installed binary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryManifest {
+    /// whisper.cpp version
+    pub version: String,
+    /// Binary file name in the same directory
+    pub binary: String,
+    /// SHA-256 of the binary
+    pub sha256: String,
+    /// Archive URL, or the repository it was built from
+    pub source: String,
+}
+
+/// Cache directory for the pinned release
+pub fn install_dir() -> crate::Result<PathBuf> {
+    Ok(crate::platform::cache_dir()?
+        .join("whisper.cpp")
+        .join(WHISPER_CPP_VERSION))
+}
+
+/// The configured binary (a name on PATH or a path), else the installed one
+/// if its checksum still matches
+pub fn resolve(binary: &str) -> Option<PathBuf> {
+    find_on_path(binary).or_else(|| {
+        let dir = install_dir().ok()?;
+        verify_installed(&dir).ok().map(|(path, _)| path)
+    })
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(binary);
+    if path.components().count() > 1 {
+        return path.is_file().then_some(path);
+    }
+
+    let names = if cfg!(windows) {
+        vec![binary.to_string(), format!("{}.exe", binary)]
+    } else {
+        vec![binary.to_string()]
+    };
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Check an installed binary against its manifest
+pub fn verify_installed(dir: &Path) -> Result<(PathBuf, BinaryManifest)> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: BinaryManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("No whisper-server installed in {}", dir.display()))?,
+    )
+    .with_context(|| format!("Invalid {}", manifest_path.display()))?;
+
+    let binary = dir.join(&manifest.binary);
+    let actual = sha256_file(&binary)?;
+    if actual != manifest.sha256 {
+        anyhow::bail!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            binary.display(),
+            manifest.sha256,
+            actual
+        );
+    }
+    Ok((binary, manifest))
+}
+
+/// Pinned SHA-256 of a prebuilt archive
+fn pinned_digest(archive: &str) -> Option<&'static str> {
+    PINNED_DIGESTS
+        .iter()
+        .find(|(name, _)| *name == archive)
+        .map(|(_, digest)| *digest)
+}
+
+/// Install whisper-server when the config runs it and none is found
+pub async fn ensure(config: &ModelConfig) -> Result<()> {
+    if super::backend::resolve_backend(config) != "whisper-server"
+        || !config.server.auto_install
+        || resolve(&config.server.binary).is_some()
+    {
+        return Ok(());
+    }
+    info!(
+        "whisper-server binary '{}' not found; installing whisper.cpp {}",
+        config.server.binary, WHISPER_CPP_VERSION
+    );
+    install().await.map(|_| ())
+}
+
+/// Install the pinned release into the cache dir: the prebuilt binary, or
+/// a build from source when there is none for this platform or it fails
+pub async fn install() -> Result<PathBuf> {
+    let dir = install_dir()?;
+    if let Ok((path, _)) = verify_installed(&dir) {
+        return Ok(path);
+    }
+
+    let prebuilt = match archive_for_platform(std::env::consts::OS, std::env::consts::ARCH) {
+        Some(archive) => download(&archive, &dir).await,
+        None => Err(anyhow::anyhow!(
+            "No prebuilt whisper-server for {}-{}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )),
+    };
+    let source = match prebuilt {
+        Ok(source) => source,
+        Err(e) => {
+            warn!("{:#}; building whisper.cpp from source", e);
+            let build_dir = dir.clone();
+            tokio::task::spawn_blocking(move || build_from_source(&build_dir)).await??
+        }
+    };
+
+    let binary = dir.join(binary_name());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o755))?;
+    }
+    let manifest = BinaryManifest {
+        version: WHISPER_CPP_VERSION.to_string(),
+        binary: binary_name().to_string(),
+        sha256: sha256_file(&binary)?,
+        source,
+    };
+    fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    info!(
+        "whisper-server {} installed in {}",
+        WHISPER_CPP_VERSION,
+        dir.display()
+    );
+    Ok(binary)
+}
+
+/// Download and verify the prebuilt archive and unpack it into `dir`,
+/// returning its URL
+async fn download(archive: &str, dir: &Path) -> Result<String> {
+    let expected = pinned_digest(archive)
+        .with_context(|| format!("No checksum pinned for {} in this build", archive))?;
+    let client = reqwest::Client::builder()
+        .user_agent("onevox/0.1.0")
+        .build()?;
+    let url = archive_url(archive);
+
+    info!("Downloading {}", url);
+    let bytes = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Failed to download {}", archive))?
+        .bytes()
+        .await?;
+
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            archive,
+            expected,
+            actual
+        );
+    }
+
+    // The archive holds the binary and any libraries it loads
+    fs::create_dir_all(dir)?;
+    tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..])).unpack(dir)?;
+    if !dir.join(binary_name()).is_file() {
+        anyhow::bail!("{} not found in {}", binary_name(), archive);
+    }
+    Ok(url)
+}
+
+/// Clone and build whisper.cpp, copying the server binary into `dir`,
+/// returning the repository it was built from
+fn build_from_source(dir: &Path) -> Result<String> {
+    let checkout = dir.join("source");
+    if checkout.exists() {
+        fs::remove_dir_all(&checkout)?;
+    }
+    fs::create_dir_all(dir)?;
+
+    let tag = format!("v{}", WHISPER_CPP_VERSION);
+    let build = checkout.join("build");
+    run(Command::new("git")
+        .args(["clone", "--depth", "1", "--branch", &tag, SOURCE_REPO])
+        .arg(&checkout))?;
+    run(Command::new("cmake")
+        .arg("-S")
+        .arg(&checkout)
+        .arg("-B")
+        .arg(&build)
+        .args([
+            "-DCMAKE_BUILD_TYPE=Release",
+            "-DBUILD_SHARED_LIBS=OFF",
+            "-DWHISPER_BUILD_TESTS=OFF",
+        ]))?;
+    run(Command::new("cmake").arg("--build").arg(&build).args([
+        "--config",
+        "Release",
+        "--target",
+        "whisper-server",
+        "--parallel",
+    ]))?;
+
+    // Multi-config generators (Visual Studio) add the configuration
+    let built = [
+        build.join("bin").join(binary_name()),
+        build.join("bin").join("Release").join(binary_name()),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .context("The build did not produce whisper-server")?;
+    fs::copy(&built, dir.join(binary_name()))?;
+    fs::remove_dir_all(&checkout)?;
+    Ok(format!("{} ({})", SOURCE_REPO, tag))
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .output()
+        .with_context(|| format!("{} is required to build whisper.cpp", program))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "{} failed ({}): {}",
+            program,
+            output.status,
+            stderr.lines().last().unwrap_or("").trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prebuilt_assets_and_verification() {
+        assert_eq!(
+            archive_for_platform("macos", "aarch64").as_deref(),
+            Some("whisper-server-macos-aarch64.tar.gz")
+        );
+        assert!(archive_for_platform("freebsd", "x86_64").is_none());
+
+        // Every pinned digest names a known archive and is lowercase hex
+        for (archive, digest) in PINNED_DIGESTS {
+            assert!(archive.starts_with("whisper-server-") && archive.ends_with(".tar.gz"));
+            assert!(
+                digest.len() == 64 && digest.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+            );
+        }
+        assert!(pinned_digest("whisper-server-freebsd-x86_64.tar.gz").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(verify_installed(dir.path()).is_err());
+        fs::write(dir.path().join(binary_name()), b"server").unwrap();
+        let manifest = BinaryManifest {
+            version: WHISPER_CPP_VERSION.to_string(),
+            binary: binary_name().to_string(),
+            sha256: sha256_file(&dir.path().join(binary_name())).unwrap(),
+            source: String::new(),
+        };
+        fs::write(
+            dir.path().join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+        assert!(verify_installed(dir.path()).is_ok());
+
+        fs::write(dir.path().join(binary_name()), b"tampered").unwrap();
+        assert!(verify_installed(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Transducer Decoding
+//!
+//! Transducer models (RNN-T, and NVIDIA's Token-and-Duration Transducer
+//! used by Parakeet TDT) pair the encoder with a prediction network over the
+//! tokens emitted so far and a joint network that combines the two. Greedy
+//! decoding walks the encoder frames: at each frame the joint scores the
+//! next token; a token is emitted and fed back to the prediction network,
+//! a blank moves on to the next frame. TDT joints also score how many
+//! frames to advance, so silence and long tokens are skipped in one step.
+
+/// A token emitted at an encoder frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransducerToken {
+    pub id: i64,
+    pub frame: usize,
+}
+
+/// Tokens emitted at one frame before it is forced to advance
+pub const MAX_SYMBOLS_PER_FRAME: usize = 10;
+
+/// Index of the largest value
+fn argmax(values: &[f32]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(index, _)| index)
+}
+
+/// Greedy transducer decoding over `frames` encoder frames
+///
+/// `joint(frame, last_token, state)` runs the prediction and joint networks
+/// and returns the joint output with the prediction network's next state.
+/// The output holds `vocab_size` token scores, followed for TDT models by
+/// one score per duration (0, 1, 2, ... frames). `last_token` is `blank`
+/// before the first emission. The state only advances when a token is
+/// emitted.
+pub fn greedy_decode<S>(
+    frames: usize,
+    vocab_size: usize,
+    blank: i64,
+    initial_state: S,
+    mut joint: impl FnMut(usize, i64, &S) -> crate::Result<(Vec<f32>, S)>,
+) -> crate::Result<Vec<TransducerToken>> {
+    let mut tokens: Vec<TransducerToken> = Vec::new();
+    let mut state = initial_state;
+    let mut frame = 0;
+    let mut emitted = 0;
+
+    while frame < frames {
+        let last = tokens.last().map_or(blank, |token| token.id);
+        let (output, next_state) = joint(frame, last, &state)?;
+        if output.len() < vocab_size {
+            return Err(crate::Error::Model(format!(
+                "Joint network returned {} scores for a vocabulary of {}",
+                output.len(),
+                vocab_size
+            )));
+        }
+
+        let token = argmax(&output[..vocab_size]) as i64;
+        let duration = if output.len() > vocab_size {
+            argmax(&output[vocab_size..])
+        } else {
+            0
+        };
+
+        if token != blank {
+            tokens.push(TransducerToken { id: token, frame });
+            state = next_state;
+            emitted += 1;
+        }
+        if duration > 0 {
+            frame += duration;
+            emitted = 0;
+        } else if token == blank || emitted == MAX_SYMBOLS_PER_FRAME {
+            frame += 1;
+            emitted = 0;
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greedy_decode_follows_durations() {
+        // Vocab: 0 = "a", 1 = "b", 2 = blank; durations 0..=2. The state
+        // counts emissions, so each call can be checked against it
+        let script = [
+            // frame 0: "a", stay; "b", skip two frames
+            (0, 0, [0.9, 0.0, 0.1], [0.8, 0.1, 0.1]),
+            (0, 1, [0.0, 0.9, 0.1], [0.1, 0.1, 0.8]),
+            // frame 2: blank, skip one; frame 3: "a", done
+            (2, 2, [0.0, 0.1, 0.9], [0.1, 0.8, 0.1]),
+            (3, 2, [0.9, 0.0, 0.1], [0.1, 0.8, 0.1]),
+        ];
+        let mut call = 0;
+        let tokens = greedy_decode(4, 3, 2, 0usize, |frame, _last, &emitted| {
+            let (expected_frame, expected_emitted, tokens, durations) = script[call];
+            assert_eq!((frame, emitted), (expected_frame, expected_emitted));
+            call += 1;
+            let output = tokens.iter().chain(&durations).copied().collect();
+            Ok((output, emitted + 1))
+        })
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TransducerToken { id: 0, frame: 0 },
+                TransducerToken { id: 1, frame: 0 },
+                TransducerToken { id: 0, frame: 3 },
+            ]
+        );
+
+        // RNN-T: no durations, a frame that keeps emitting is cut off
+        let tokens = greedy_decode(2, 2, 1, (), |_, _, _| Ok((vec![1.0, 0.0], ()))).unwrap();
+        assert_eq!(tokens.len(), 2 * MAX_SYMBOLS_PER_FRAME);
+        assert!(greedy_decode(1, 3, 2, (), |_, _, _| Ok((vec![1.0], ()))).is_err());
+    }
+}
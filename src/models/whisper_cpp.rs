@@ -2,26 +2,37 @@
 //!
 //! High-performance local speech recognition using whisper.cpp native bindings.
 //! This is the primary production backend for cross-platform stability.
+//!
+//! Runs in-process (feature `whisper-cpp`, alias `whisper-native`): the GGML
+//! model and one decoder state are loaded once and kept resident, and
+//! segments are transcribed straight from memory, with no subprocess or
+//! temporary WAV files.
 
 #[cfg(feature = "whisper-cpp")]
-use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, Transcription};
+use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, TranscriptSegment, Transcription};
 #[cfg(not(feature = "whisper-cpp"))]
 use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, Transcription};
 
 #[cfg(feature = "whisper-cpp")]
+use crate::vocabulary::Vocabulary;
 use std::path::PathBuf;
-#[cfg(feature = "whisper-cpp")]
 use tracing::{debug, info, warn};
 
 #[cfg(feature = "whisper-cpp")]
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
 
 /// Whisper.cpp model backend
 #[cfg(feature = "whisper-cpp")]
 pub struct WhisperCpp {
     ctx: Option<WhisperContext>,
+    /// Decoder state (KV cache and work buffers), reused across segments
+    state: Option<WhisperState>,
     config: Option<ModelConfig>,
     model_path: Option<PathBuf>,
+    /// Glossary of the user's vocabulary, put ahead of the prompt
+    vocabulary_prompt: Option<String>,
 }
 
 #[cfg(feature = "whisper-cpp")]
@@ -32,54 +43,16 @@ impl WhisperCpp {
 
         Ok(Self {
             ctx: None,
+            state: None,
             config: None,
             model_path: None,
+            vocabulary_prompt: None,
         })
     }
 
     /// Get the model path from cache
     fn resolve_model_path(&self, model_id: &str) -> crate::Result<PathBuf> {
-        // If it's already an absolute path that exists, use it directly
-        let direct_path = PathBuf::from(model_id);
-        if direct_path.is_absolute() && direct_path.exists() {
-            info!("Using absolute model path: {:?}", direct_path);
-            return Ok(direct_path);
-        }
-
-        // Get the models directory
-        let models_dir =
-            crate::platform::paths::models_dir().unwrap_or_else(|_| PathBuf::from("./models"));
-
-        // Try different possible locations in order of likelihood
-        let possible_paths = vec![
-            // 1. Direct file in models directory (most common for direct .bin files)
-            models_dir.join(model_id),
-            // 2. In subdirectory: models/model-id/model-id.bin
-            models_dir.join(model_id).join(format!("{}.bin", model_id)),
-            // 3. In subdirectory with .bin extension: models/model-id.bin/model-id.bin
-            models_dir
-                .join(format!("{}.bin", model_id))
-                .join(format!("{}.bin", model_id)),
-            // 4. Standard naming: model_id/ggml-model.bin
-            models_dir.join(model_id).join("ggml-model.bin"),
-            // 5. If model_id already has .bin, try as-is in subdirectory
-            models_dir.join(model_id).join(model_id),
-        ];
-
-        for path in &possible_paths {
-            if path.exists() && path.is_file() {
-                info!("Found model at: {:?}", path);
-                return Ok(path.clone());
-            }
-        }
-
-        // Return the most likely expected path for a helpful error message
-        let expected = models_dir.join(model_id).join(format!("{}.bin", model_id));
-        warn!("Model not found at any expected location");
-        debug!("Searched paths: {:?}", possible_paths);
-        debug!("Expected path: {:?}", expected);
-
-        Ok(expected)
+        Ok(resolve_ggml_model_path(model_id))
     }
 }
 
@@ -119,9 +92,15 @@ impl ModelRuntime for WhisperCpp {
         )
         .map_err(|e| crate::Error::Model(format!("Failed to load whisper.cpp model: {}", e)))?;
 
+        // Allocate the decoder state up front so the first segment doesn't pay for it
+        let state = ctx
+            .create_state()
+            .map_err(|e| crate::Error::Model(format!("Failed to create state: {}", e)))?;
+
         info!("✅ Whisper.cpp model loaded successfully");
 
         self.ctx = Some(ctx);
+        self.state = Some(state);
         self.config = Some(config);
         self.model_path = Some(model_path);
 
@@ -133,9 +112,9 @@ impl ModelRuntime for WhisperCpp {
     }
 
     fn transcribe(&mut self, samples: &[f32], sample_rate: u32) -> crate::Result<Transcription> {
-        let ctx = self
-            .ctx
-            .as_ref()
+        let state = self
+            .state
+            .as_mut()
             .ok_or_else(|| crate::Error::Model("Model not loaded".to_string()))?;
 
         let config = self
@@ -163,27 +142,42 @@ impl ModelRuntime for WhisperCpp {
         );
 
         // Create transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let strategy = if config.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: config.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let mut params = FullParams::new(strategy);
 
         // Configure parameters from ModelConfig
         params.set_n_threads(config.n_threads as i32);
-        // Auto-detect language (None = auto-detection enabled)
-        params.set_language(None);
-        params.set_translate(false); // Always transcribe, never translate
+        // Profile-selected language, or auto-detection when None
+        params.set_language(config.language.as_deref());
+        // Whisper's built-in task: speech in any language, English text
+        params.set_translate(config.translate);
+        // Vocabulary glossary, then earlier text when continuing a long
+        // recording
+        let prompt = [self.vocabulary_prompt.as_deref(), config.prompt.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !prompt.is_empty() {
+            params.set_initial_prompt(&prompt);
+        }
         params.set_print_progress(false);
         params.set_print_special(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
-        params.set_token_timestamps(false);
+        // Per-token offsets for word timings
+        params.set_token_timestamps(true);
         params.set_suppress_blank(true);
         params.set_suppress_nst(true); // Suppress non-speech tokens
 
-        // Create a state for this transcription (whisper-rs 0.14+ API)
-        let mut state = ctx
-            .create_state()
-            .map_err(|e| crate::Error::Model(format!("Failed to create state: {}", e)))?;
-
-        // Run transcription
+        // Run transcription on the resident state
         state
             .full(params, samples)
             .map_err(|e| crate::Error::Model(format!("Transcription failed: {}", e)))?;
@@ -191,36 +185,98 @@ impl ModelRuntime for WhisperCpp {
         // Extract results using the new iterator API
         let mut full_text = String::new();
         let mut num_segments = 0;
+        // (piece, start, end); whisper timestamps are in 10 ms units
+        let mut tokens: Vec<(String, u64, u64)> = Vec::new();
+        let mut log_probs = Vec::new();
 
         for segment in state.as_iter() {
             num_segments += 1;
             let segment_text = segment.to_string();
             full_text.push_str(&segment_text);
+
+            for index in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(index) else {
+                    continue;
+                };
+                let Ok(piece) = token.to_str_lossy() else {
+                    continue;
+                };
+                // Special tokens ([_BEG_], <|endoftext|>, timestamps)
+                if piece.starts_with("[_") || piece.starts_with("<|") {
+                    continue;
+                }
+                let data = token.token_data();
+                log_probs.push(data.plog);
+                tokens.push((
+                    piece.into_owned(),
+                    data.t0.max(0) as u64 * 10,
+                    data.t1.max(0) as u64 * 10,
+                ));
+            }
         }
+        let words = TranscriptSegment::join_tokens(
+            tokens
+                .iter()
+                .map(|(piece, start, end)| (piece.as_str(), *start, *end)),
+        );
 
         let processing_time = start.elapsed();
 
         info!(
-            "Transcription complete: \"{}\" ({} ms)",
-            full_text.trim(),
+            "Transcription complete: {} chars ({} ms)",
+            full_text.trim().chars().count(),
             processing_time.as_millis()
         );
-
-        // Detect language from the model (whisper models detect language automatically)
-        // Language will be auto-detected by the model when set_language(None) is used
-        let detected_language = None; // We could extract this from whisper state if needed
+        debug!("Transcript: {}", full_text.trim());
+
+        // The forced language, or the one whisper detected for this segment
+        let detected_language = config.language.clone().or_else(|| {
+            whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(str::to_string)
+        });
+        if config.language.is_none() {
+            debug!("Detected language: {:?}", detected_language);
+        }
 
         Ok(Transcription {
             text: full_text.trim().to_string(),
             language: detected_language,
-            confidence: None, // whisper-rs doesn't expose confidence easily
+            confidence: super::confidence::from_log_probs(log_probs),
             processing_time_ms: processing_time.as_millis() as u64,
             tokens: Some(num_segments),
+            segments: TranscriptSegment::split_sentences(&words),
+            words,
         })
     }
 
+    fn set_language(&mut self, language: Option<&str>) {
+        if let Some(config) = self.config.as_mut() {
+            debug!("Decoding language set to {:?}", language);
+            config.language = language.map(str::to_string);
+        }
+    }
+
+    fn set_translate(&mut self, translate: bool) -> bool {
+        if let Some(config) = self.config.as_mut() {
+            config.translate = translate;
+        }
+        true
+    }
+
+    fn set_prompt(&mut self, prompt: Option<&str>) -> bool {
+        if let Some(config) = self.config.as_mut() {
+            config.prompt = prompt.map(str::to_string);
+        }
+        true
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: &Vocabulary) -> bool {
+        self.vocabulary_prompt = vocabulary.prompt();
+        true
+    }
+
     fn unload(&mut self) {
         info!("Unloading Whisper.cpp model");
+        self.state = None;
         self.ctx = None;
         self.config = None;
         self.model_path = None;
@@ -251,6 +307,67 @@ impl ModelRuntime for WhisperCpp {
     }
 }
 
+/// Resolve a GGML model ID (or absolute path) to the model file
+///
+/// Returns the most likely expected path when no candidate exists, so the
+/// caller can report a helpful error.
+pub(crate) fn resolve_ggml_model_path(model_id: &str) -> PathBuf {
+    // If it's already an absolute path that exists, use it directly
+    let direct_path = PathBuf::from(model_id);
+    if direct_path.is_absolute() && direct_path.exists() {
+        info!("Using absolute model path: {:?}", direct_path);
+        return direct_path;
+    }
+
+    // Get the models directory
+    let models_dir =
+        crate::platform::paths::models_dir().unwrap_or_else(|_| PathBuf::from("./models"));
+
+    // Active version directory (versioned layout) or the flat model directory
+    let model_dir = crate::platform::paths::active_model_dir(&models_dir.join(model_id));
+
+    // Try different possible locations in order of likelihood
+    let possible_paths = vec![
+        // 1. Direct file in models directory (most common for direct .bin files)
+        models_dir.join(model_id),
+        // 2. In subdirectory: models/model-id/model-id.bin
+        model_dir.join(format!("{}.bin", model_id)),
+        // 3. In subdirectory with .bin extension: models/model-id.bin/model-id.bin
+        models_dir
+            .join(format!("{}.bin", model_id))
+            .join(format!("{}.bin", model_id)),
+        // 4. Standard naming: model_id/ggml-model.bin
+        model_dir.join("ggml-model.bin"),
+        // 5. If model_id already has .bin, try as-is in subdirectory
+        model_dir.join(model_id),
+    ];
+    // 6. The file the registry (or a model manifest) names for this ID
+    let possible_paths: Vec<PathBuf> = possible_paths
+        .into_iter()
+        .chain(
+            crate::models::ModelRegistry::new()
+                .get_model(model_id)
+                .and_then(|model| model.files.first())
+                .map(|file| model_dir.join(file)),
+        )
+        .collect();
+
+    for path in &possible_paths {
+        if path.exists() && path.is_file() {
+            info!("Found model at: {:?}", path);
+            return path.clone();
+        }
+    }
+
+    // Return the most likely expected path for a helpful error message
+    let expected = model_dir.join(format!("{}.bin", model_id));
+    warn!("Model not found at any expected location");
+    debug!("Searched paths: {:?}", possible_paths);
+    debug!("Expected path: {:?}", expected);
+
+    expected
+}
+
 // Stub implementation when feature is disabled
 #[cfg(not(feature = "whisper-cpp"))]
 pub struct WhisperCpp;
@@ -0,0 +1,533 @@
+//! Whisper.cpp Server Backend
+//!
+//! Runs whisper.cpp's `whisper-server` as a child process and sends each
+//! segment to it over HTTP on 127.0.0.1. The model stays loaded in the
+//! server between segments, so per-request overhead is much lower than
+//! spawning the CLI, and no Rust bindings are needed at build time.
+
+use super::runtime::{ModelConfig, ModelInfo, ModelRuntime, TranscriptSegment, Transcription};
+use super::server_binary;
+use super::whisper_cpp::resolve_ggml_model_path;
+use crate::config::WhisperServerConfig;
+use crate::vocabulary::Vocabulary;
+use reqwest::blocking::{Client, multipart};
+use std::io::Cursor;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio::runtime::RuntimeFlavor;
+use tracing::{debug, info, warn};
+
+/// Whisper.cpp server backend
+pub struct WhisperServer {
+    settings: WhisperServerConfig,
+    child: Option<Child>,
+    port: u16,
+    /// HTTP client, created with the first request
+    client: Option<Client>,
+    config: Option<ModelConfig>,
+    model_path: Option<PathBuf>,
+    /// Glossary of the user's vocabulary, put ahead of the prompt
+    vocabulary_prompt: Option<String>,
+}
+
+impl WhisperServer {
+    /// Create a new whisper-server backend
+    pub fn new(settings: WhisperServerConfig) -> crate::Result<Self> {
+        info!("Initializing whisper-server backend");
+
+        Ok(Self {
+            settings,
+            child: None,
+            port: 0,
+            client: None,
+            config: None,
+            model_path: None,
+            vocabulary_prompt: None,
+        })
+    }
+
+    /// Address the server listens on
+    fn addr(&self) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::LOCALHOST, self.port))
+    }
+
+    /// POST an inference request and return the status code and body
+    fn post_inference(&mut self, form: multipart::Form) -> crate::Result<(u16, Vec<u8>)> {
+        let timeout = Duration::from_secs(self.settings.request_timeout_secs);
+        let url = format!("http://{}/inference", self.addr());
+        let client = &mut self.client;
+
+        blocking(|| {
+            let client = match client {
+                Some(client) => client,
+                None => client.insert(
+                    Client::builder()
+                        .connect_timeout(Duration::from_secs(2))
+                        // The server is local; never send it through a proxy
+                        .no_proxy()
+                        .build()
+                        .map_err(|e| {
+                            crate::Error::Model(format!("Failed to create HTTP client: {}", e))
+                        })?,
+                ),
+            };
+
+            let failed = |e: reqwest::Error| {
+                if e.is_timeout() {
+                    crate::Error::Model(format!(
+                        "whisper-server did not answer within {:?}",
+                        timeout
+                    ))
+                } else {
+                    crate::Error::Model(format!("whisper-server request failed: {}", e))
+                }
+            };
+            let response = client
+                .post(&url)
+                .timeout(timeout)
+                .multipart(form)
+                .send()
+                .map_err(failed)?;
+            let status = response.status().as_u16();
+            let body = response.bytes().map_err(failed)?;
+            Ok((status, body.to_vec()))
+        })
+    }
+
+    /// Spawn the server and wait until it accepts connections
+    fn start_server(&mut self) -> crate::Result<()> {
+        let (Some(config), Some(model_path)) = (&self.config, &self.model_path) else {
+            return Err(crate::Error::Model("Model not loaded".to_string()));
+        };
+
+        let binary = server_binary::resolve(&self.settings.binary).ok_or_else(|| {
+            crate::Error::Model(format!(
+                "whisper-server binary '{}' not found. Install whisper.cpp, set [model.server] binary, or turn on auto_install",
+                self.settings.binary
+            ))
+        })?;
+
+        let port = match self.settings.port {
+            0 => free_port()?,
+            port => port,
+        };
+
+        check_extra_args(&self.settings.extra_args)?;
+
+        let mut command = Command::new(&binary);
+        command
+            .arg("--model")
+            .arg(model_path)
+            .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+            .args(["--threads", &config.n_threads.to_string()])
+            // The server defaults to English; requests override this
+            .args(["--language", config.language.as_deref().unwrap_or("auto")])
+            .args(&self.settings.extra_args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null());
+        if !config.use_gpu {
+            command.arg("--no-gpu");
+        }
+
+        // Keep server output for troubleshooting
+        let log_path = crate::platform::paths::log_dir()?.join("whisper-server.log");
+        command.stderr(std::fs::File::create(&log_path)?);
+
+        info!(
+            "Starting {:?} on port {} (log: {:?})",
+            binary, port, log_path
+        );
+        let child = command
+            .spawn()
+            .map_err(|e| crate::Error::Model(format!("Failed to start whisper-server: {}", e)))?;
+
+        self.child = Some(child);
+        self.port = port;
+
+        if let Err(e) = self.wait_ready() {
+            self.stop_server();
+            return Err(e);
+        }
+
+        info!("✅ whisper-server ready on port {}", port);
+        Ok(())
+    }
+
+    /// Wait for the server to load the model and start listening
+    fn wait_ready(&mut self) -> crate::Result<()> {
+        let timeout = Duration::from_secs(self.settings.startup_timeout_secs);
+        let deadline = Instant::now() + timeout;
+        let addr = self.addr();
+
+        loop {
+            if let Some(child) = self.child.as_mut()
+                && let Ok(Some(status)) = child.try_wait()
+            {
+                return Err(crate::Error::Model(format!(
+                    "whisper-server exited during startup ({}); see whisper-server.log",
+                    status
+                )));
+            }
+
+            // The server loads the model before it binds the port
+            if TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok() {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(crate::Error::Model(format!(
+                    "whisper-server did not start within {:?}",
+                    timeout
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Restart the server if it has exited since the last request
+    fn ensure_running(&mut self) -> crate::Result<()> {
+        let exited = match self.child.as_mut() {
+            Some(child) => child.try_wait().ok().flatten(),
+            None => None,
+        };
+
+        if let Some(status) = exited {
+            warn!("whisper-server exited ({}), restarting", status);
+            self.child = None;
+        }
+
+        if self.child.is_none() {
+            self.start_server()?;
+        }
+        Ok(())
+    }
+
+    fn stop_server(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            debug!("Stopping whisper-server (pid {})", child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for WhisperServer {
+    fn drop(&mut self) {
+        self.stop_server();
+    }
+}
+
+impl ModelRuntime for WhisperServer {
+    fn load(&mut self, config: ModelConfig) -> crate::Result<()> {
+        info!("Loading model into whisper-server: {:?}", config.model_path);
+
+        let model_path = resolve_ggml_model_path(&config.model_path);
+        if !model_path.exists() {
+            return Err(crate::Error::Model(format!(
+                "Model file not found: {:?}\nDownload GGML models with: onevox models download {}",
+                model_path, config.model_path
+            )));
+        }
+
+        self.stop_server();
+        self.config = Some(config);
+        self.model_path = Some(model_path);
+
+        if let Err(e) = self.start_server() {
+            self.config = None;
+            self.model_path = None;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.config.is_some() && self.child.is_some()
+    }
+
+    fn transcribe(&mut self, samples: &[f32], sample_rate: u32) -> crate::Result<Transcription> {
+        if self.config.is_none() {
+            return Err(crate::Error::Model("Model not loaded".to_string()));
+        }
+
+        if sample_rate != 16000 {
+            return Err(crate::Error::Model(
+                "Sample rate must be 16kHz. Please resample audio.".to_string(),
+            ));
+        }
+
+        self.ensure_running()?;
+
+        let start = Instant::now();
+        let language = self
+            .config
+            .as_ref()
+            .and_then(|c| c.language.clone())
+            .unwrap_or_else(|| "auto".to_string());
+        let translate = self.config.as_ref().is_some_and(|c| c.translate);
+        let context = self.config.as_ref().and_then(|c| c.prompt.as_deref());
+        let prompt = [self.vocabulary_prompt.as_deref(), context]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let beam_size = self.config.as_ref().map_or(1, |c| c.beam_size).to_string();
+
+        let wav = multipart::Part::bytes(encode_wav(samples, sample_rate)?)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| crate::Error::Model(format!("Invalid request: {}", e)))?;
+        let mut form = multipart::Form::new()
+            .text("temperature", "0.0")
+            .text("response_format", "verbose_json")
+            .text("language", language)
+            .text("translate", translate.to_string())
+            .text("beam_size", beam_size)
+            .part("file", wav);
+        if !prompt.is_empty() {
+            form = form.text("prompt", prompt);
+        }
+
+        let (status, response) = self.post_inference(form)?;
+
+        if status != 200 {
+            return Err(crate::Error::Model(format!(
+                "whisper-server returned HTTP {}: {}",
+                status,
+                String::from_utf8_lossy(&response).trim()
+            )));
+        }
+
+        let mut transcription = parse_inference_response(&response)?;
+        transcription.processing_time_ms = start.elapsed().as_millis() as u64;
+
+        info!(
+            "Transcription complete: {} chars ({} ms)",
+            transcription.text.chars().count(),
+            transcription.processing_time_ms
+        );
+        debug!("Transcript: {}", transcription.text);
+
+        Ok(transcription)
+    }
+
+    fn set_language(&mut self, language: Option<&str>) {
+        if let Some(config) = self.config.as_mut() {
+            debug!("Decoding language set to {:?}", language);
+            config.language = language.map(str::to_string);
+        }
+    }
+
+    fn set_translate(&mut self, translate: bool) -> bool {
+        if let Some(config) = self.config.as_mut() {
+            config.translate = translate;
+        }
+        true
+    }
+
+    fn set_prompt(&mut self, prompt: Option<&str>) -> bool {
+        if let Some(config) = self.config.as_mut() {
+            config.prompt = prompt.map(str::to_string);
+        }
+        true
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: &Vocabulary) -> bool {
+        self.vocabulary_prompt = vocabulary.prompt();
+        true
+    }
+
+    fn unload(&mut self) {
+        info!("Unloading whisper-server model");
+        self.stop_server();
+        self.config = None;
+        self.model_path = None;
+    }
+
+    fn name(&self) -> &str {
+        "whisper-server"
+    }
+
+    fn info(&self) -> ModelInfo {
+        let config = self.config.as_ref();
+
+        ModelInfo {
+            name: self.name().to_string(),
+            size_bytes: self
+                .model_path
+                .as_ref()
+                .and_then(|p| std::fs::metadata(p).ok())
+                .map(|m| m.len())
+                .unwrap_or(0),
+            model_type: config
+                .map(|c| c.model_path.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+            backend: "whisper-server".to_string(),
+            gpu_enabled: config.map(|c| c.use_gpu).unwrap_or(false),
+        }
+    }
+}
+
+/// Ask the OS for a free local port
+fn free_port() -> crate::Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Encode samples as a 16-bit PCM mono WAV file
+fn encode_wav(samples: &[f32], sample_rate: u32) -> crate::Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut buffer, spec)
+        .map_err(|e| crate::Error::Audio(format!("Failed to encode WAV: {}", e)))?;
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer
+            .write_sample(value)
+            .map_err(|e| crate::Error::Audio(format!("Failed to encode WAV: {}", e)))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| crate::Error::Audio(format!("Failed to encode WAV: {}", e)))?;
+
+    Ok(buffer.into_inner())
+}
+
+/// Run blocking HTTP work, stepping out of the async runtime first when
+/// called on one of its workers (the CLI transcribes files there)
+fn blocking<T>(work: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(work)
+        }
+        _ => work(),
+    }
+}
+
+/// Parse a `json` or `verbose_json` inference response
+fn parse_inference_response(body: &[u8]) -> crate::Result<Transcription> {
+    let json: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| crate::Error::Model(format!("Invalid JSON from whisper-server: {}", e)))?;
+
+    if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
+        return Err(crate::Error::Model(format!("whisper-server: {}", error)));
+    }
+
+    let text = json
+        .get("text")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| crate::Error::Model("whisper-server response has no text".to_string()))?
+        .trim()
+        .to_string();
+
+    // verbose_json segments carry start/end offsets in seconds
+    let segments = json
+        .get("segments")
+        .and_then(|s| s.as_array())
+        .map(|segments| {
+            segments
+                .iter()
+                .filter_map(|segment| {
+                    Some(TranscriptSegment {
+                        text: segment.get("text")?.as_str()?.trim().to_string(),
+                        start_ms: (segment.get("start")?.as_f64()? * 1000.0) as u64,
+                        end_ms: (segment.get("end")?.as_f64()? * 1000.0) as u64,
+                    })
+                })
+                .filter(|segment| !segment.text.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Word timings: per segment from whisper.cpp, top-level in the OpenAI
+    // format
+    let timed = |word: &serde_json::Value| {
+        Some(TranscriptSegment {
+            text: word.get("word")?.as_str()?.trim().to_string(),
+            start_ms: (word.get("start")?.as_f64()? * 1000.0) as u64,
+            end_ms: (word.get("end")?.as_f64()? * 1000.0) as u64,
+        })
+    };
+    let words = json
+        .get("segments")
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|segment| segment.get("words")?.as_array())
+        .chain(json.get("words").and_then(|w| w.as_array()))
+        .flatten()
+        .filter_map(timed)
+        .filter(|word| !word.text.is_empty())
+        .collect();
+
+    // verbose_json segments carry their tokens' average log-probability
+    let log_probs: Vec<f32> = json
+        .get("segments")
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|segment| Some(segment.get("avg_logprob")?.as_f64()? as f32))
+        .collect();
+
+    let mut transcription = Transcription::new(text);
+    transcription.confidence = super::confidence::from_log_probs(log_probs);
+    transcription.language = json
+        .get("language")
+        .and_then(|l| l.as_str())
+        .map(str::to_string);
+    transcription.segments = segments;
+    transcription.words = words;
+    Ok(transcription)
+}
+
+/// Refuse keychain references in the server's arguments: a resolved
+/// secret would sit on its command line, readable by any local user via `ps`
+fn check_extra_args(args: &[String]) -> crate::Result<()> {
+    match args
+        .iter()
+        .find_map(|arg| crate::secrets::reference_name(arg))
+    {
+        Some(name) => Err(crate::Error::Config(format!(
+            "model.server.extra_args can't reference keychain secret '{}': the server's arguments are visible in the process list",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extra_args_reject_secrets() {
+        assert!(check_extra_args(&["--flash-attn".to_string()]).is_ok());
+        assert!(check_extra_args(&["--api-key".to_string(), "keyring:asr".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_inference_response() {
+        let body = r#"{"language":"es","text":" Hola mundo.","segments":[{"text":" Hola mundo.","start":0.0,"end":1.24,"words":[{"word":" Hola","start":0.0,"end":0.5},{"word":" mundo.","start":0.6,"end":1.24}]}]}"#;
+
+        let transcription = parse_inference_response(body.as_bytes()).unwrap();
+        assert_eq!(transcription.text, "Hola mundo.");
+        assert_eq!(transcription.language.as_deref(), Some("es"));
+        assert_eq!(transcription.segments.len(), 1);
+        assert_eq!(transcription.segments[0].end_ms, 1240);
+        assert_eq!(transcription.words.len(), 2);
+        assert_eq!(transcription.words[1].text, "mundo.");
+        assert_eq!(transcription.words[1].start_ms, 600);
+        assert!(transcription.confidence.is_none());
+
+        let error = parse_inference_response(br#"{"error":"failed to read WAV file"}"#);
+        assert!(error.is_err());
+    }
+}
@@ -2,19 +2,38 @@
 //!
 //! OS-specific code for hotkeys, text injection, etc.
 
+pub mod announcer;
+pub mod ax;
+pub mod dnd;
+pub mod focus;
+pub mod hid;
 pub mod hotkey;
 pub mod injector;
+pub mod notify;
 pub mod paths;
 pub mod permissions;
+pub mod quirks;
+pub mod service;
+pub mod undo;
+pub mod unicode_input;
+pub mod url_events;
 
 // Re-export commonly used types
-pub use hotkey::{HotkeyConfig, HotkeyEvent, HotkeyManager};
+pub use announcer::Announcer;
+pub use focus::FocusContext;
+pub use hotkey::{
+    DictationMode, HotkeyAction, HotkeyBackend, HotkeyConfig, HotkeyEvent, HotkeyManager,
+};
 pub use injector::{InjectionStrategy, InjectorConfig, TextInjector};
 pub use paths::{
-    cache_dir, config_dir, config_file_path, data_dir, ensure_directories, history_db_path,
-    ipc_socket_path, log_dir, model_path, models_dir,
+    backups_dir, cache_dir, config_dir, config_file_path, data_dir, ensure_directories,
+    history_db_path, ipc_socket_path, log_dir, model_path, models_dir, recordings_dir,
 };
 pub use permissions::{
-    Permission, PermissionStatus, check_accessibility_permission, check_required_permissions,
-    open_accessibility_settings, prompt_accessibility_permission, verify_permissions,
+    Permission, PermissionStatus, check_accessibility_permission,
+    check_input_monitoring_permission, check_permission, check_required_permissions,
+    open_accessibility_settings, open_input_monitoring_settings, prompt_accessibility_permission,
+    request_permission, verify_permissions,
 };
+pub use quirks::{InjectionMethod, InjectionPlan, QuirksTable};
+pub use undo::{UndoOutcome, UndoStack};
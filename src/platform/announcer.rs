@@ -0,0 +1,249 @@
+//! Screen Reader Announcements
+//!
+//! Speaks injection results through the platform's assistive technology so
+//! screen-reader users know whether their dictation landed.
+//!
+//! - macOS: VoiceOver (via AppleScript), falling back to `say`
+//! - Linux: speech-dispatcher (`spd-say`), the AT-SPI speech backend
+//! - Windows: SAPI via PowerShell (heard alongside Narrator)
+
+use crate::config::AccessibilityConfig;
+use std::process::{Command, Stdio};
+use tracing::{debug, warn};
+
+/// How much detail announcements contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Only "Inserted" / "Dictation failed"
+    Minimal,
+    /// Include a word count
+    #[default]
+    Normal,
+    /// Read back the injected text
+    Verbose,
+}
+
+impl Verbosity {
+    /// Parse a verbosity from config (unknown values fall back to normal)
+    pub fn from_str_lossy(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "minimal" => Self::Minimal,
+            "verbose" => Self::Verbose,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// Announces dictation outcomes to screen readers
+#[derive(Debug, Clone)]
+pub struct Announcer {
+    enabled: bool,
+    verbosity: Verbosity,
+}
+
+impl Announcer {
+    /// Create a new announcer from config
+    pub fn new(config: &AccessibilityConfig) -> Self {
+        Self {
+            enabled: config.announce,
+            verbosity: Verbosity::from_str_lossy(&config.verbosity),
+        }
+    }
+
+    /// Whether announcements are enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Announce a successful injection
+    pub fn announce_injected(&self, text: &str) {
+        if self.enabled {
+            speak(&self.injected_message(text));
+        }
+    }
+
+    /// Announce a failed injection or transcription
+    pub fn announce_failure(&self, reason: &str) {
+        if self.enabled {
+            speak(&self.failure_message(reason));
+        }
+    }
+
+    /// Announce a transcript held until its app regains focus
+    pub fn announce_deferred(&self, reason: &str) {
+        if self.enabled {
+            speak(&self.deferred_message(reason));
+        }
+    }
+
+    fn injected_message(&self, text: &str) -> String {
+        let words = text.split_whitespace().count();
+        match self.verbosity {
+            Verbosity::Minimal => "Inserted".to_string(),
+            Verbosity::Normal => {
+                if words == 1 {
+                    "Inserted 1 word".to_string()
+                } else {
+                    format!("Inserted {} words", words)
+                }
+            }
+            Verbosity::Verbose => format!("Inserted: {}", text),
+        }
+    }
+
+    fn failure_message(&self, reason: &str) -> String {
+        match self.verbosity {
+            Verbosity::Minimal | Verbosity::Normal => "Dictation failed".to_string(),
+            Verbosity::Verbose => format!("Dictation failed: {}", reason),
+        }
+    }
+
+    fn deferred_message(&self, reason: &str) -> String {
+        match self.verbosity {
+            Verbosity::Minimal | Verbosity::Normal => "Dictation pending".to_string(),
+            Verbosity::Verbose => format!("Dictation pending: {}", reason),
+        }
+    }
+}
+
+/// Speak a message without blocking the dictation pipeline
+fn speak(message: &str) {
+    debug!("Announcing: {}", message);
+    let mut command = match speech_command(message) {
+        Ok(command) => command,
+        Err(e) => {
+            warn!("Screen reader announcement failed: {}", e);
+            return;
+        }
+    };
+    command.stdout(Stdio::null()).stderr(Stdio::null());
+
+    // Wait on a thread so the child is reaped without holding up dictation
+    std::thread::spawn(move || match command.status() {
+        Ok(status) if !status.success() => {
+            debug!("{:?} exited with {}", command.get_program(), status);
+        }
+        Ok(_) => {}
+        Err(e) => warn!(
+            "Screen reader announcement failed: could not run {:?}: {}",
+            command.get_program(),
+            e
+        ),
+    });
+}
+
+/// Quote text as an AppleScript string literal
+#[cfg(any(target_os = "macos", test))]
+pub(crate) fn applescript_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(target_os = "macos")]
+fn speech_command(message: &str) -> crate::Result<Command> {
+    let quoted = applescript_string(message);
+    let script = format!(
+        "if application \"VoiceOver\" is running then\n\
+         tell application \"VoiceOver\" to output {}\n\
+         else\n\
+         say {}\n\
+         end if",
+        quoted, quoted
+    );
+
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    Ok(command)
+}
+
+/// Needs speech-dispatcher
+#[cfg(target_os = "linux")]
+fn speech_command(message: &str) -> crate::Result<Command> {
+    let mut command = Command::new("spd-say");
+    command
+        .args(["--application-name", "onevox", "--"])
+        .arg(message);
+    Ok(command)
+}
+
+#[cfg(target_os = "windows")]
+fn speech_command(message: &str) -> crate::Result<Command> {
+    let escaped = message.replace('\'', "''");
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; \
+         (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+        escaped
+    );
+
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn speech_command(_message: &str) -> crate::Result<Command> {
+    Err(crate::Error::Platform(
+        "Screen reader announcements are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcer(verbosity: &str) -> Announcer {
+        Announcer::new(&AccessibilityConfig {
+            announce: true,
+            verbosity: verbosity.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_verbosity_messages() {
+        assert_eq!(
+            announcer("minimal").injected_message("hello there"),
+            "Inserted"
+        );
+        assert_eq!(
+            announcer("normal").injected_message("hello there"),
+            "Inserted 2 words"
+        );
+        assert_eq!(
+            announcer("normal").injected_message("hello"),
+            "Inserted 1 word"
+        );
+        assert_eq!(
+            announcer("verbose").injected_message("hello there"),
+            "Inserted: hello there"
+        );
+        assert_eq!(
+            announcer("verbose").failure_message("no focus"),
+            "Dictation failed: no focus"
+        );
+        assert_eq!(
+            announcer("minimal").deferred_message("secure input"),
+            "Dictation pending"
+        );
+    }
+
+    #[test]
+    fn test_unknown_verbosity_defaults_to_normal() {
+        assert_eq!(Verbosity::from_str_lossy("chatty"), Verbosity::Normal);
+        assert_eq!(
+            applescript_string("say \"hi\"\nC:\\dir"),
+            r#""say \"hi\"\nC:\\dir""#
+        );
+    }
+}
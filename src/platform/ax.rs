@@ -0,0 +1,221 @@
+//! Accessibility API Editing
+//!
+//! Edits the focused text element through the macOS Accessibility API
+//! instead of sending keystrokes. `injection.method = "ax"` inserts text by
+//! replacing the element's (empty) selection, which works the same under
+//! any keyboard layout or input method. Undo selects the injected text right
+//! before the cursor and deletes it in one step, after checking that the
+//! text there is still exactly what onevox typed. Both are checked by
+//! reading the element back; elements that don't expose their value and
+//! selection (terminals, many web views) and other platforms fall back to
+//! keystrokes.
+
+/// Delete `text` from just before the cursor in the focused element
+///
+/// Returns false, changing nothing, when the element doesn't expose its
+/// text, something is selected, or the text before the cursor isn't `text`.
+pub fn delete_before_cursor(text: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::delete_before_cursor(text)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = text;
+        false
+    }
+}
+
+/// Insert `text` at the cursor of the focused element, replacing any
+/// selection
+///
+/// Returns false when the element doesn't expose its text, or its text is
+/// unchanged afterwards (some apps report success without inserting
+/// anything).
+pub fn insert_text(text: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        macos::insert_text(text)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = text;
+        false
+    }
+}
+
+/// Start of `text` in `value` (UTF-16 offsets, as the Accessibility API
+/// counts) when it ends exactly at `cursor`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn start_before(value: &str, cursor: usize, text: &str) -> Option<usize> {
+    let value: Vec<u16> = value.encode_utf16().collect();
+    let text: Vec<u16> = text.encode_utf16().collect();
+    let start = cursor.checked_sub(text.len())?;
+    (value.get(start..cursor)? == text.as_slice() && !text.is_empty()).then_some(start)
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+
+    /// kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+    /// kAXValueCFRangeType
+    const AX_VALUE_CF_RANGE: u32 = 4;
+
+    #[repr(C)]
+    #[derive(Debug, Default, Clone, Copy)]
+    struct CFRange {
+        location: isize,
+        length: isize,
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+        fn AXUIElementSetAttributeValue(
+            element: CFTypeRef,
+            attribute: CFStringRef,
+            value: CFTypeRef,
+        ) -> i32;
+        fn AXValueCreate(kind: u32, value: *const c_void) -> CFTypeRef;
+        fn AXValueGetValue(value: CFTypeRef, kind: u32, out: *mut c_void) -> bool;
+    }
+
+    fn copy_attribute(element: &CFType, name: &str) -> Option<CFType> {
+        let name = CFString::new(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        // SAFETY: the element and name are live CF objects; on success the
+        // value is returned retained, and owned by the CFType from then on
+        let error = unsafe {
+            AXUIElementCopyAttributeValue(
+                element.as_CFTypeRef(),
+                name.as_concrete_TypeRef(),
+                &mut value,
+            )
+        };
+        (error == AX_SUCCESS && !value.is_null())
+            .then(|| unsafe { CFType::wrap_under_create_rule(value) })
+    }
+
+    fn set_attribute(element: &CFType, name: &str, value: &CFType) -> bool {
+        let name = CFString::new(name);
+        // SAFETY: all three are live CF objects for the duration of the call
+        unsafe {
+            AXUIElementSetAttributeValue(
+                element.as_CFTypeRef(),
+                name.as_concrete_TypeRef(),
+                value.as_CFTypeRef(),
+            ) == AX_SUCCESS
+        }
+    }
+
+    fn range_value(range: CFRange) -> CFType {
+        // SAFETY: AXValueCreate copies the range it points to and returns a
+        // retained value
+        unsafe {
+            CFType::wrap_under_create_rule(AXValueCreate(
+                AX_VALUE_CF_RANGE,
+                &range as *const CFRange as *const c_void,
+            ))
+        }
+    }
+
+    /// The focused element
+    fn focused_element() -> Option<CFType> {
+        // SAFETY: returns a retained element for the whole system
+        let system = unsafe { CFType::wrap_under_create_rule(AXUIElementCreateSystemWide()) };
+        copy_attribute(&system, "AXFocusedUIElement")
+    }
+
+    /// The element's selection (the cursor when its length is 0)
+    fn selection(element: &CFType) -> Option<CFRange> {
+        let selected = copy_attribute(element, "AXSelectedTextRange")?;
+        let mut selection = CFRange::default();
+        // SAFETY: the out pointer is a CFRange, the type asked for
+        let read = unsafe {
+            AXValueGetValue(
+                selected.as_CFTypeRef(),
+                AX_VALUE_CF_RANGE,
+                &mut selection as *mut CFRange as *mut c_void,
+            )
+        };
+        (read && selection.location >= 0 && selection.length >= 0).then_some(selection)
+    }
+
+    /// Where `text` starts if it ends at the element's cursor
+    fn text_before_cursor(element: &CFType, text: &str) -> Option<(usize, CFRange)> {
+        let selection = selection(element)?;
+        if selection.length != 0 {
+            return None;
+        }
+        let start = super::start_before(&value(element)?, selection.location as usize, text)?;
+        Some((start, selection))
+    }
+
+    /// The element's text
+    fn value(element: &CFType) -> Option<String> {
+        copy_attribute(element, "AXValue")?
+            .downcast_into::<CFString>()
+            .map(|value| value.to_string())
+    }
+
+    pub fn insert_text(text: &str) -> bool {
+        let Some(focused) = focused_element() else {
+            return false;
+        };
+        // Without a readable value and selection the insertion can't be
+        // checked
+        let Some(before) = value(&focused).filter(|_| selection(&focused).is_some()) else {
+            return false;
+        };
+        if !set_attribute(&focused, "AXSelectedText", &CFString::new(text).as_CFType()) {
+            return false;
+        }
+        // Text that changed some other way was still inserted; typing it
+        // again would duplicate it
+        text_before_cursor(&focused, text).is_some()
+            || value(&focused).is_some_and(|after| after != before)
+    }
+
+    pub fn delete_before_cursor(text: &str) -> bool {
+        let Some(focused) = focused_element() else {
+            return false;
+        };
+        let Some((start, selection)) = text_before_cursor(&focused, text) else {
+            return false;
+        };
+
+        let injected = range_value(CFRange {
+            location: start as isize,
+            length: selection.location - start as isize,
+        });
+        set_attribute(&focused, "AXSelectedTextRange", &injected)
+            && set_attribute(&focused, "AXSelectedText", &CFString::new("").as_CFType())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_injected_text_found_before_cursor() {
+        let value = "Dear Ana, see you 🎉 tomorrow";
+        // The cursor after "🎉", which takes two UTF-16 units
+        let cursor = "Dear Ana, see you 🎉".encode_utf16().count();
+        assert_eq!(start_before(value, cursor, "see you 🎉"), Some(10));
+        assert_eq!(start_before(value, cursor, "see you"), None);
+        assert_eq!(start_before(value, 3, "Dear"), None);
+        assert_eq!(start_before(value, cursor, ""), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! Do Not Disturb
+//!
+//! With `dictation.auto_dnd = true`, notifications are silenced while a
+//! dictation session records so their sounds don't end up in the audio. The
+//! prior state is restored afterwards; if Do Not Disturb was already on, it
+//! is left alone.
+//!
+//! - macOS: Focus has no public API, so two Shortcuts named
+//!   `onevox-dnd-on` / `onevox-dnd-off` (each with a "Set Focus" action) are
+//!   run. The current state is read from the Focus assertions database.
+//! - Linux: SwayNC, dunst, or GNOME notification banners.
+
+use std::process::{Command, Stdio};
+use tracing::{debug, info, warn};
+
+/// Shortcut run to turn Focus on (macOS)
+pub const MACOS_SHORTCUT_ON: &str = "onevox-dnd-on";
+
+/// Shortcut run to turn Focus off (macOS)
+pub const MACOS_SHORTCUT_OFF: &str = "onevox-dnd-off";
+
+/// A way of toggling notifications: a query and the commands to flip it
+#[derive(Debug)]
+struct Backend {
+    name: &'static str,
+    /// Prints the current state
+    query: &'static [&'static str],
+    /// Query output meaning Do Not Disturb is on
+    active_output: &'static str,
+    enable: &'static [&'static str],
+    disable: &'static [&'static str],
+}
+
+#[cfg(target_os = "linux")]
+const BACKENDS: &[Backend] = &[
+    Backend {
+        name: "SwayNC",
+        query: &["swaync-client", "--get-dnd"],
+        active_output: "true",
+        enable: &["swaync-client", "--dnd-on"],
+        disable: &["swaync-client", "--dnd-off"],
+    },
+    Backend {
+        name: "dunst",
+        query: &["dunstctl", "is-paused"],
+        active_output: "true",
+        enable: &["dunstctl", "set-paused", "true"],
+        disable: &["dunstctl", "set-paused", "false"],
+    },
+    Backend {
+        name: "GNOME",
+        query: &[
+            "gsettings",
+            "get",
+            "org.gnome.desktop.notifications",
+            "show-banners",
+        ],
+        active_output: "false",
+        enable: &[
+            "gsettings",
+            "set",
+            "org.gnome.desktop.notifications",
+            "show-banners",
+            "false",
+        ],
+        disable: &[
+            "gsettings",
+            "set",
+            "org.gnome.desktop.notifications",
+            "show-banners",
+            "true",
+        ],
+    },
+];
+
+#[cfg(target_os = "macos")]
+const BACKENDS: &[Backend] = &[Backend {
+    name: "Focus",
+    query: &[],
+    active_output: "",
+    enable: &["shortcuts", "run", MACOS_SHORTCUT_ON],
+    disable: &["shortcuts", "run", MACOS_SHORTCUT_OFF],
+}];
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+const BACKENDS: &[Backend] = &[];
+
+impl Backend {
+    /// Whether Do Not Disturb is on (`None` when this backend isn't available)
+    fn is_active(&self) -> Option<bool> {
+        #[cfg(target_os = "macos")]
+        {
+            if !macos_shortcuts_installed() {
+                return None;
+            }
+            let path = directories::BaseDirs::new()?
+                .home_dir()
+                .join("Library/DoNotDisturb/DB/Assertions.json");
+            // Unreadable without Full Disk Access; assume Focus is off
+            let json = std::fs::read_to_string(path).unwrap_or_default();
+            Some(focus_assertions_active(&json))
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            if self.name == "GNOME" && !desktop_is("GNOME") {
+                return None;
+            }
+            let (program, args) = self.query.split_first()?;
+            let output = super::focus::command_output(program, args)?;
+            Some(output.trim() == self.active_output)
+        }
+    }
+
+    fn run(&self, command: &[&str]) -> bool {
+        let Some((program, args)) = command.split_first() else {
+            return false;
+        };
+        match Command::new(program)
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+        {
+            Ok(status) if status.success() => true,
+            Ok(status) => {
+                warn!("{} exited with {}", program, status);
+                false
+            }
+            Err(e) => {
+                warn!("Failed to run {}: {}", program, e);
+                false
+            }
+        }
+    }
+}
+
+/// Do Not Disturb turned on by onevox; turned off again when released or
+/// dropped
+#[derive(Debug)]
+pub struct DndGuard {
+    backend: &'static Backend,
+}
+
+impl DndGuard {
+    /// Restore notifications
+    pub fn release(self) {
+        // Restored by Drop
+    }
+}
+
+impl Drop for DndGuard {
+    fn drop(&mut self) {
+        if self.backend.run(self.backend.disable) {
+            info!("🔔 Notifications restored ({})", self.backend.name);
+        }
+    }
+}
+
+/// Turn Do Not Disturb on for the duration of the returned guard
+///
+/// Returns `None` when it is already on (nothing to restore) or no
+/// supported notification service was found. Blocks while the toggle
+/// commands run.
+pub fn enable() -> Option<DndGuard> {
+    for backend in BACKENDS {
+        match backend.is_active() {
+            None => continue,
+            Some(true) => {
+                debug!("Do Not Disturb already on ({})", backend.name);
+                return None;
+            }
+            Some(false) => {
+                if !backend.run(backend.enable) {
+                    return None;
+                }
+                info!("🔕 Notifications silenced ({})", backend.name);
+                return Some(DndGuard { backend });
+            }
+        }
+    }
+
+    debug!("No supported Do Not Disturb control found");
+    None
+}
+
+/// Whether the Focus assertions database (macOS
+/// `~/Library/DoNotDisturb/DB/Assertions.json`) records an active Focus
+pub fn focus_assertions_active(json: &str) -> bool {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return false;
+    };
+    value["data"].as_array().into_iter().flatten().any(|store| {
+        store["storeAssertionRecords"]
+            .as_array()
+            .is_some_and(|records| !records.is_empty())
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn macos_shortcuts_installed() -> bool {
+    super::focus::command_output("shortcuts", &["list"])
+        .map(|list| {
+            let names: Vec<&str> = list.lines().map(str::trim).collect();
+            names.contains(&MACOS_SHORTCUT_ON) && names.contains(&MACOS_SHORTCUT_OFF)
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn desktop_is(name: &str) -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .map(|desktops| desktops.split(':').any(|d| d.eq_ignore_ascii_case(name)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_assertions() {
+        assert!(!focus_assertions_active(""));
+        assert!(!focus_assertions_active(
+            r#"{"data":[{"storeAssertionRecords":[]}]}"#
+        ));
+        assert!(focus_assertions_active(
+            r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":{"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#
+        ));
+    }
+}
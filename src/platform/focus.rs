@@ -0,0 +1,461 @@
+//! Focus Context
+//!
+//! Queries the focused application and the active keyboard input source so
+//! profiles can pick a decoding language when a dictation session starts.
+//! Every query is best-effort: a missing tool or unsupported desktop yields
+//! `None` and the default language is used.
+
+use std::process::Command;
+use tracing::debug;
+
+/// Focused application and keyboard input source at a point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FocusContext {
+    /// Focused application (name, bundle ID, or window class)
+    pub app: Option<String>,
+    /// Active keyboard input source / layout
+    pub input_source: Option<String>,
+}
+
+impl FocusContext {
+    /// Query the current focus context from the platform
+    pub fn current() -> Self {
+        let context = Self {
+            app: focused_app(),
+            input_source: input_source(),
+        };
+        debug!("Focus context: {:?}", context);
+        context
+    }
+}
+
+/// Name of the focused application, if it can be determined
+pub fn focused_app() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        // Name and bundle ID, so profiles can match on either
+        command_output(
+            "osascript",
+            &[
+                "-e",
+                "tell application \"System Events\" to get {name, bundle identifier} of first application process whose frontmost is true",
+            ],
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
+            && let Some(json) = command_output("hyprctl", &["activewindow", "-j"])
+        {
+            return serde_json::from_str::<serde_json::Value>(&json)
+                .ok()?
+                .get("class")?
+                .as_str()
+                .map(str::to_string);
+        }
+
+        command_output("xdotool", &["getactivewindow", "getwindowclassname"])
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Title of the focused window, if it can be determined
+pub fn focused_window_title() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        // Needs Accessibility access for System Events
+        command_output(
+            "osascript",
+            &[
+                "-e",
+                "tell application \"System Events\" to get name of front window of (first application process whose frontmost is true)",
+            ],
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
+            && let Some(json) = command_output("hyprctl", &["activewindow", "-j"])
+        {
+            return serde_json::from_str::<serde_json::Value>(&json)
+                .ok()?
+                .get("title")?
+                .as_str()
+                .map(str::to_string);
+        }
+
+        command_output("xdotool", &["getactivewindow", "getwindowname"])
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Whether secure keyboard entry is active (a password field or an app
+/// that enabled it), which makes the OS drop synthetic keystrokes
+///
+/// macOS reports `EnableSecureEventInput`; Windows checks whether the
+/// focused control is a password edit box; Linux tracks the focused
+/// widget's AT-SPI role (see `track_focus`), which needs accessibility
+/// enabled on the desktop and the `atspi-focus` feature. On Linux the last
+/// AT-SPI report only counts while it is recent and came from
+/// `focused_app` (the app as returned by [`focused_app`]), since apps
+/// without AT-SPI support never report focus. Password fields none of these
+/// expose (e.g. drawn by a browser) are not detected.
+pub fn secure_input_active(focused_app: Option<&str>) -> bool {
+    #[cfg(not(all(target_os = "linux", feature = "atspi-focus")))]
+    let _ = focused_app;
+
+    #[cfg(target_os = "macos")]
+    {
+        #[link(name = "Carbon", kind = "framework")]
+        unsafe extern "C" {
+            fn IsSecureEventInputEnabled() -> u8;
+        }
+
+        // SAFETY: takes no arguments and only reads global input state
+        unsafe { IsSecureEventInputEnabled() != 0 }
+    }
+
+    #[cfg(windows)]
+    {
+        windows_password_focused()
+    }
+
+    #[cfg(all(target_os = "linux", feature = "atspi-focus"))]
+    {
+        atspi_focus::password_focused(focused_app)
+    }
+
+    #[cfg(not(any(
+        target_os = "macos",
+        all(target_os = "linux", feature = "atspi-focus"),
+        windows
+    )))]
+    {
+        false
+    }
+}
+
+/// Start following focus changes where the platform has no query for the
+/// focused widget (AT-SPI on Linux); called once at daemon startup, so the
+/// first check doesn't come before the first report
+pub fn track_focus() {
+    #[cfg(all(target_os = "linux", feature = "atspi-focus"))]
+    atspi_focus::watch();
+}
+
+/// Whether the focused control of the foreground window is an edit box
+/// with `ES_PASSWORD`
+#[cfg(windows)]
+fn windows_password_focused() -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GUITHREADINFO, GWL_STYLE, GetClassNameW, GetForegroundWindow, GetGUIThreadInfo,
+        GetWindowLongW, GetWindowThreadProcessId,
+    };
+
+    const ES_PASSWORD: i32 = 0x20;
+
+    // SAFETY: plain Win32 queries on window handles; GUITHREADINFO is
+    // initialised with its size as the API requires
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.is_invalid() {
+            return false;
+        }
+        let thread = GetWindowThreadProcessId(foreground, None);
+        let mut info = GUITHREADINFO {
+            cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        if GetGUIThreadInfo(thread, &mut info).is_err() || info.hwndFocus.is_invalid() {
+            return false;
+        }
+
+        let mut class = [0u16; 64];
+        let len = GetClassNameW(info.hwndFocus, &mut class).max(0) as usize;
+        let class = String::from_utf16_lossy(&class[..len]).to_ascii_lowercase();
+        // The style bit means something else for other control classes
+        class.contains("edit") && GetWindowLongW(info.hwndFocus, GWL_STYLE) & ES_PASSWORD != 0
+    }
+}
+
+/// How long an AT-SPI focus report is trusted without a newer one
+#[cfg(any(all(target_os = "linux", feature = "atspi-focus"), test))]
+const FOCUS_REPORT_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Focus change reported by AT-SPI
+#[cfg(any(all(target_os = "linux", feature = "atspi-focus"), test))]
+#[derive(Debug, Clone)]
+struct FocusReport {
+    /// Application the focused widget belongs to
+    app: Option<String>,
+    /// Whether the focused widget is a password field
+    password: bool,
+    /// When the report arrived
+    at: std::time::Instant,
+}
+
+#[cfg(any(all(target_os = "linux", feature = "atspi-focus"), test))]
+impl FocusReport {
+    /// Whether the report shows a password field focused in `focused_app`
+    /// at `now`
+    ///
+    /// A report from another app is stale: focus moved to an app that
+    /// doesn't report to AT-SPI (most terminals, Electron without
+    /// accessibility). When the focused app can't be determined, only the
+    /// report's age is checked.
+    fn password_in(&self, focused_app: Option<&str>, now: std::time::Instant) -> bool {
+        if !self.password || now.saturating_duration_since(self.at) > FOCUS_REPORT_MAX_AGE {
+            return false;
+        }
+        match (focused_app, &self.app) {
+            (Some(focused), Some(app)) => same_app(focused, app),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Whether an AT-SPI application name and a window class or app name
+/// refer to the same app (e.g. "gnome-terminal-server" and
+/// "Gnome-terminal")
+#[cfg(any(all(target_os = "linux", feature = "atspi-focus"), test))]
+fn same_app(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    !a.is_empty() && !b.is_empty() && (a.contains(&b) || b.contains(&a))
+}
+
+/// Focused widget, tracked from AT-SPI focus events
+///
+/// AT-SPI has no "what is focused" query, so a task follows
+/// `object:state-changed:focused` events from daemon startup onwards.
+#[cfg(all(target_os = "linux", feature = "atspi-focus"))]
+mod atspi_focus {
+    use super::FocusReport;
+    use atspi::events::object::ObjectEvents;
+    use atspi::proxy::accessible::ObjectRefExt;
+    use atspi::{AccessibilityConnection, Event, Role, State};
+    use futures::StreamExt;
+    use std::sync::{Mutex, Once};
+    use std::time::Instant;
+    use tracing::{debug, warn};
+
+    /// Latest report; `None` until AT-SPI reports a focus change, or while
+    /// it can't be reached
+    static REPORT: Mutex<Option<FocusReport>> = Mutex::new(None);
+    static WATCH: Once = Once::new();
+
+    /// Whether a password field in `focused_app` has focus
+    pub fn password_focused(focused_app: Option<&str>) -> bool {
+        REPORT
+            .lock()
+            .ok()
+            .and_then(|report| {
+                report
+                    .as_ref()
+                    .map(|r| r.password_in(focused_app, Instant::now()))
+            })
+            .unwrap_or(false)
+    }
+
+    fn set_report(report: Option<FocusReport>) {
+        if let Ok(mut slot) = REPORT.lock() {
+            *slot = report;
+        }
+    }
+
+    /// Start following focus events on the tokio runtime (once)
+    pub fn watch() {
+        WATCH.call_once(|| {
+            tokio::spawn(async {
+                if let Err(e) = follow().await {
+                    warn!(
+                        "AT-SPI focus tracking unavailable ({}); password fields won't be detected",
+                        e
+                    );
+                }
+                set_report(None);
+            });
+        });
+    }
+
+    async fn follow() -> Result<(), Box<dyn std::error::Error>> {
+        let atspi = AccessibilityConnection::new().await?;
+        atspi.register_event::<ObjectEvents>().await?;
+        let events = atspi.event_stream();
+        futures::pin_mut!(events);
+
+        while let Some(event) = events.next().await {
+            let Ok(Event::Object(ObjectEvents::StateChanged(change))) = event else {
+                continue;
+            };
+            if change.state != State::Focused || change.enabled != 1 {
+                continue;
+            }
+            let (role, app) = match change.item.as_accessible_proxy(atspi.connection()).await {
+                Ok(accessible) => {
+                    let role = accessible.get_role().await.ok();
+                    let app = match accessible.get_application().await {
+                        Ok(app) => match app.as_accessible_proxy(atspi.connection()).await {
+                            Ok(app) => app.name().await.ok(),
+                            Err(_) => None,
+                        },
+                        Err(_) => None,
+                    };
+                    (role, app)
+                }
+                Err(_) => (None, None),
+            };
+            debug!("AT-SPI focus: {:?} in {:?}", role, app);
+            set_report(Some(FocusReport {
+                app,
+                password: role == Some(Role::PasswordText),
+                at: Instant::now(),
+            }));
+        }
+        Ok(())
+    }
+}
+
+/// Active keyboard input source, if it can be determined
+pub fn input_source() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        command_output(
+            "defaults",
+            &["read", "com.apple.HIToolbox", "AppleSelectedInputSources"],
+        )
+        .and_then(|plist| parse_hitoolbox_source(&plist))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // GNOME keeps the most recently used source first
+        if let Some(sources) = command_output(
+            "gsettings",
+            &["get", "org.gnome.desktop.input-sources", "mru-sources"],
+        ) && let Some(source) = parse_gnome_sources(&sources)
+        {
+            return Some(source);
+        }
+
+        command_output("setxkbmap", &["-query"]).and_then(|query| parse_xkb_layout(&query))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Run a command and return its trimmed stdout on success
+pub(crate) fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        debug!("{} exited with {}", program, output.status);
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+/// Extract the layout name from `defaults read com.apple.HIToolbox`
+///
+/// Prefers the input method (e.g. Japanese IME) over the keyboard layout.
+fn parse_hitoolbox_source(plist: &str) -> Option<String> {
+    let value = |key: &str| {
+        plist.lines().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim().trim_matches('"') == key)
+                .then(|| v.trim().trim_end_matches(';').trim_matches('"').to_string())
+        })
+    };
+
+    value("Input Mode").or_else(|| value("KeyboardLayout Name"))
+}
+
+/// Extract the first source from GNOME's `[('xkb', 'es'), ('xkb', 'us')]`
+fn parse_gnome_sources(sources: &str) -> Option<String> {
+    let first = sources.split(')').next()?;
+    let source = first.split(',').nth(1)?.trim().trim_matches('\'');
+    (!source.is_empty()).then(|| source.to_string())
+}
+
+/// Extract the first layout from `setxkbmap -query`
+fn parse_xkb_layout(query: &str) -> Option<String> {
+    query.lines().find_map(|line| {
+        let layouts = line.strip_prefix("layout:")?;
+        layouts
+            .trim()
+            .split(',')
+            .next()
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input_sources() {
+        let plist = r#"(
+        {
+        InputSourceKind = "Keyboard Layout";
+        "KeyboardLayout ID" = 87;
+        "KeyboardLayout Name" = Spanish;
+    }
+)"#;
+        assert_eq!(parse_hitoolbox_source(plist).as_deref(), Some("Spanish"));
+
+        assert_eq!(
+            parse_gnome_sources("[('xkb', 'es'), ('xkb', 'us')]").as_deref(),
+            Some("es")
+        );
+        assert_eq!(parse_gnome_sources("@a(ss) []"), None);
+
+        let query = "rules:      evdev\nmodel:      pc105\nlayout:     de,us\n";
+        assert_eq!(parse_xkb_layout(query).as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_focus_report_transitions() {
+        let now = std::time::Instant::now();
+        let report = |app: Option<&str>, password: bool| FocusReport {
+            app: app.map(str::to_string),
+            password,
+            at: now,
+        };
+
+        // A password field in the focused app
+        let prompt = report(Some("gnome-terminal-server"), true);
+        assert!(prompt.password_in(Some("Gnome-terminal"), now));
+        assert!(prompt.password_in(None, now));
+        // Focus moved on within the app, or to an app without AT-SPI
+        assert!(
+            !report(Some("gnome-terminal-server"), false).password_in(Some("Gnome-terminal"), now)
+        );
+        assert!(!prompt.password_in(Some("Alacritty"), now));
+        assert!(!report(None, true).password_in(Some("kitty"), now));
+        // Old reports expire
+        let later = now + FOCUS_REPORT_MAX_AGE + std::time::Duration::from_secs(1);
+        assert!(!prompt.password_in(Some("Gnome-terminal"), later));
+    }
+}
@@ -0,0 +1,385 @@
+//! HID Triggers
+//!
+//! Foot pedals and macro pads as dictation triggers. Buttons listed in
+//! `[[hotkey.hid]]` are read straight from the device, so they work
+//! alongside (or instead of) the keyboard hotkey, and the device can be
+//! grabbed so its buttons don't also type.
+//!
+//! Linux only (evdev); reading `/dev/input` needs the user in the `input`
+//! group.
+
+use super::hotkey::HotkeyEvent;
+use crate::config::HidTriggerConfig;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// An input device that can act as a trigger
+#[derive(Debug, Clone)]
+pub struct HidDevice {
+    pub path: PathBuf,
+    pub name: String,
+    pub vendor: u16,
+    pub product: u16,
+    /// Number of keys/buttons the device reports
+    pub buttons: usize,
+}
+
+impl HidDevice {
+    /// USB id as "vendor:product" in hex
+    pub fn id(&self) -> String {
+        format!("{:04x}:{:04x}", self.vendor, self.product)
+    }
+}
+
+/// What a trigger button does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HidAction {
+    /// Start/stop dictation like the keyboard hotkey
+    Dictate,
+    /// Undo the last injection
+    Undo,
+    /// Open the clipboard ring picker
+    Picker,
+    /// Pause or resume the running session
+    Pause,
+}
+
+impl HidAction {
+    fn parse(action: &str) -> crate::Result<Self> {
+        match action.to_ascii_lowercase().as_str() {
+            "dictate" => Ok(HidAction::Dictate),
+            "undo" => Ok(HidAction::Undo),
+            "picker" => Ok(HidAction::Picker),
+            "pause" => Ok(HidAction::Pause),
+            other => Err(crate::Error::Config(format!(
+                "Unknown HID action '{}' (expected dictate, undo, picker, or pause)",
+                other
+            ))),
+        }
+    }
+
+    /// Hotkey event for a key event value (1 = press, 0 = release, 2 = repeat)
+    fn event(&self, value: i32) -> Option<HotkeyEvent> {
+        match (self, value) {
+            (HidAction::Dictate, 1) => Some(HotkeyEvent::Pressed),
+            (HidAction::Dictate, 0) => Some(HotkeyEvent::Released),
+            (HidAction::Undo, 1) => Some(HotkeyEvent::Undo),
+            (HidAction::Picker, 1) => Some(HotkeyEvent::Picker),
+            (HidAction::Pause, 1) => Some(HotkeyEvent::Pause),
+            _ => None,
+        }
+    }
+}
+
+/// How a configured device is identified
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeviceMatch {
+    Id { vendor: u16, product: u16 },
+    Name(String),
+}
+
+impl DeviceMatch {
+    fn parse(device: &str) -> Self {
+        let id = device.split_once(':').and_then(|(vendor, product)| {
+            Some(DeviceMatch::Id {
+                vendor: u16::from_str_radix(vendor, 16).ok()?,
+                product: u16::from_str_radix(product, 16).ok()?,
+            })
+        });
+        id.unwrap_or_else(|| DeviceMatch::Name(device.to_lowercase()))
+    }
+
+    fn matches(&self, device: &HidDevice) -> bool {
+        match self {
+            DeviceMatch::Id { vendor, product } => {
+                device.vendor == *vendor && device.product == *product
+            }
+            DeviceMatch::Name(name) => device.name.to_lowercase().contains(name.as_str()),
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceMatch::Id { vendor, product } => write!(f, "{:04x}:{:04x}", vendor, product),
+            DeviceMatch::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A configured button and its action
+#[derive(Debug, Clone)]
+pub struct HidTrigger {
+    device: DeviceMatch,
+    button: u16,
+    action: HidAction,
+    grab: bool,
+}
+
+impl HidTrigger {
+    /// Parse a `[[hotkey.hid]]` entry
+    pub fn from_config(config: &HidTriggerConfig) -> crate::Result<Self> {
+        let button = parse_button(&config.button).ok_or_else(|| {
+            crate::Error::Config(format!("Unknown HID button '{}'", config.button))
+        })?;
+        Ok(Self {
+            device: DeviceMatch::parse(&config.device),
+            button,
+            action: HidAction::parse(&config.action)?,
+            grab: config.grab,
+        })
+    }
+}
+
+/// Button code from an evdev key name or a number
+fn parse_button(button: &str) -> Option<u16> {
+    if let Ok(code) = button.parse() {
+        return Some(code);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        button
+            .to_ascii_uppercase()
+            .parse::<evdev::Key>()
+            .ok()
+            .map(|key| key.code())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Readable name of a button code
+pub fn button_name(code: u16) -> String {
+    #[cfg(target_os = "linux")]
+    {
+        format!("{:?}", evdev::Key::new(code))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        code.to_string()
+    }
+}
+
+/// Input devices with keys or buttons
+#[cfg(target_os = "linux")]
+pub fn list_devices() -> crate::Result<Vec<HidDevice>> {
+    let mut devices: Vec<HidDevice> = evdev::enumerate()
+        .filter_map(|(path, device)| {
+            let buttons = device.supported_keys()?.iter().count();
+            let id = device.input_id();
+            Some(HidDevice {
+                path,
+                name: device.name().unwrap_or("Unknown device").to_string(),
+                vendor: id.vendor(),
+                product: id.product(),
+                buttons,
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(devices)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_devices() -> crate::Result<Vec<HidDevice>> {
+    Err(crate::Error::Platform(
+        "HID triggers are only supported on Linux".to_string(),
+    ))
+}
+
+/// Report button presses on `device` to `on_press` until `duration` passes
+#[cfg(target_os = "linux")]
+pub fn probe(
+    device: &HidDevice,
+    duration: std::time::Duration,
+    mut on_press: impl FnMut(u16),
+) -> crate::Result<()> {
+    let mut input = evdev::Device::open(&device.path).map_err(|e| {
+        crate::Error::Platform(format!("Cannot open {}: {}", device.path.display(), e))
+    })?;
+    set_nonblocking(&input)?;
+
+    let start = std::time::Instant::now();
+    while start.elapsed() < duration {
+        match input.fetch_events() {
+            Ok(events) => {
+                for event in events {
+                    if let evdev::InputEventKind::Key(key) = event.kind()
+                        && event.value() == 1
+                    {
+                        on_press(key.code());
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn probe(
+    _device: &HidDevice,
+    _duration: std::time::Duration,
+    _on_press: impl FnMut(u16),
+) -> crate::Result<()> {
+    Err(crate::Error::Platform(
+        "HID triggers are only supported on Linux".to_string(),
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn set_nonblocking(device: &evdev::Device) -> crate::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let fd = device.as_raw_fd();
+    // SAFETY: fcntl on a file descriptor owned by `device`
+    let ok = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        flags >= 0 && libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) >= 0
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().into())
+    }
+}
+
+/// Start a listener thread per matched device, sending trigger events to `tx`
+///
+/// Returns the number of devices being listened to. Configured devices that
+/// aren't connected are skipped with a warning.
+#[cfg(target_os = "linux")]
+pub fn spawn_listeners(
+    triggers: Vec<HidTrigger>,
+    tx: mpsc::UnboundedSender<HotkeyEvent>,
+) -> crate::Result<usize> {
+    use tracing::{info, warn};
+
+    let devices = list_devices()?;
+    let mut started = 0;
+
+    for device in &devices {
+        let device_triggers: Vec<HidTrigger> = triggers
+            .iter()
+            .filter(|trigger| trigger.device.matches(device))
+            .cloned()
+            .collect();
+        if device_triggers.is_empty() {
+            continue;
+        }
+
+        let mut input = match evdev::Device::open(&device.path) {
+            Ok(input) => input,
+            Err(e) => {
+                warn!(
+                    "Cannot open {} ({}): {}",
+                    device.name,
+                    device.path.display(),
+                    e
+                );
+                continue;
+            }
+        };
+        if device_triggers.iter().any(|trigger| trigger.grab)
+            && let Err(e) = input.grab()
+        {
+            warn!("Cannot grab {}: {}", device.name, e);
+        }
+
+        let tx = tx.clone();
+        let name = device.name.clone();
+        std::thread::spawn(move || {
+            loop {
+                let events = match input.fetch_events() {
+                    Ok(events) => events,
+                    Err(e) => {
+                        warn!("HID device {} stopped: {}", name, e);
+                        break;
+                    }
+                };
+                for event in events {
+                    let evdev::InputEventKind::Key(key) = event.kind() else {
+                        continue;
+                    };
+                    let triggered = device_triggers
+                        .iter()
+                        .filter(|trigger| trigger.button == key.code())
+                        .filter_map(|trigger| trigger.action.event(event.value()));
+                    for hotkey_event in triggered {
+                        if tx.send(hotkey_event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        info!(
+            "🦶 HID trigger listening on {} ({})",
+            device.name,
+            device.id()
+        );
+        started += 1;
+    }
+
+    for trigger in &triggers {
+        if !devices.iter().any(|device| trigger.device.matches(device)) {
+            warn!("HID trigger device '{}' not connected", trigger.device);
+        }
+    }
+
+    Ok(started)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_listeners(
+    _triggers: Vec<HidTrigger>,
+    _tx: mpsc::UnboundedSender<HotkeyEvent>,
+) -> crate::Result<usize> {
+    Err(crate::Error::Platform(
+        "HID triggers are only supported on Linux".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_matching() {
+        let pedal = HidDevice {
+            path: PathBuf::from("/dev/input/event7"),
+            name: "VEC USB Footpedal".to_string(),
+            vendor: 0x05f3,
+            product: 0x00ff,
+            buttons: 3,
+        };
+        assert_eq!(pedal.id(), "05f3:00ff");
+        assert!(DeviceMatch::parse("05f3:00ff").matches(&pedal));
+        assert!(DeviceMatch::parse("footpedal").matches(&pedal));
+        assert!(!DeviceMatch::parse("05f3:0001").matches(&pedal));
+
+        let trigger = HidTrigger::from_config(&HidTriggerConfig {
+            device: "footpedal".to_string(),
+            button: "257".to_string(),
+            action: "Dictate".to_string(),
+            grab: true,
+        })
+        .unwrap();
+        assert_eq!(trigger.button, 257);
+        assert_eq!(trigger.action.event(1), Some(HotkeyEvent::Pressed));
+        assert_eq!(trigger.action.event(2), None);
+        assert_eq!(HidAction::Undo.event(0), None);
+        assert!(HidAction::parse("paste").is_err());
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(parse_button("btn_0"), Some(0x100));
+    }
+}
@@ -1,10 +1,21 @@
 //! Global Hotkey Management
 //!
-//! System-wide hotkey registration and handling for push-to-talk.
+//! System-wide hotkey registration and handling for dictation, which the
+//! hotkey drives according to `hotkey.mode` (hold, toggle, or hands-free).
+//! `hotkey.backend` picks how keys are read: the native OS hotkey API, or
+//! evdev on Linux, which also works under Wayland. Further combos can be
+//! bound to other actions in the `[hotkeys]` table; combos bound twice are
+//! reported by [`find_conflicts`].
 
-use handy_keys::{Hotkey as HandyHotkey, HotkeyManager as HandyHotkeyManager, Key, Modifiers};
+pub mod evdev_listener;
+
+#[cfg(feature = "hotkeys")]
+use handy_keys::{
+    Hotkey as HandyHotkey, HotkeyId, HotkeyManager as HandyHotkeyManager, Key, Modifiers,
+};
 
 use tokio::sync::mpsc;
+#[cfg(feature = "hotkeys")]
 use tracing::{error, info, warn};
 
 /// Hotkey event
@@ -14,6 +25,254 @@ pub enum HotkeyEvent {
     Pressed,
     /// Hotkey released
     Released,
+    /// Undo hotkey pressed
+    Undo,
+    /// Clipboard ring picker hotkey pressed
+    Picker,
+    /// Pause / resume hotkey pressed
+    Pause,
+    /// A `[hotkeys]` binding pressed
+    ActionPressed(HotkeyAction),
+    /// A `[hotkeys]` binding released
+    ActionReleased(HotkeyAction),
+}
+
+impl HotkeyEvent {
+    /// Event for releasing the combo that sent this press, for bindings
+    /// that report releases
+    pub fn release(self) -> Option<HotkeyEvent> {
+        match self {
+            HotkeyEvent::Pressed => Some(HotkeyEvent::Released),
+            HotkeyEvent::ActionPressed(action) => Some(HotkeyEvent::ActionReleased(action)),
+            _ => None,
+        }
+    }
+}
+
+/// What a hotkey binding does (the keys of `[hotkeys]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HotkeyAction {
+    /// Dictate, like the trigger hotkey
+    Dictate,
+    /// Dictate, translating the speech to English
+    DictateTranslate,
+    /// Start or stop VAD-driven hands-free listening, whatever `hotkey.mode`
+    HandsFree,
+    /// Speak a voice command; only matching commands are applied and
+    /// nothing else is typed
+    Command,
+    /// Undo the last injection
+    Undo,
+    /// Open the clipboard ring picker
+    Picker,
+    /// Pause or resume the running session
+    Pause,
+}
+
+impl HotkeyAction {
+    /// Parse a `[hotkeys]` key ("dictate", "dictate-translate",
+    /// "hands-free", "command", "undo", "picker", "pause")
+    pub fn parse(action: &str) -> crate::Result<Self> {
+        match action.to_ascii_lowercase().replace('_', "-").as_str() {
+            "dictate" => Ok(HotkeyAction::Dictate),
+            "dictate-translate" | "translate" => Ok(HotkeyAction::DictateTranslate),
+            "hands-free" | "toggle-hands-free" => Ok(HotkeyAction::HandsFree),
+            "command" | "push-to-command" => Ok(HotkeyAction::Command),
+            "undo" => Ok(HotkeyAction::Undo),
+            "picker" => Ok(HotkeyAction::Picker),
+            "pause" | "pause-resume" => Ok(HotkeyAction::Pause),
+            other => Err(crate::Error::Config(format!(
+                "Unknown hotkey action '{}' (expected dictate, dictate-translate, hands-free, command, undo, picker, or pause)",
+                other
+            ))),
+        }
+    }
+
+    /// Event the binding sends when pressed
+    pub fn event(self) -> HotkeyEvent {
+        match self {
+            HotkeyAction::Dictate => HotkeyEvent::Pressed,
+            HotkeyAction::Undo => HotkeyEvent::Undo,
+            HotkeyAction::Picker => HotkeyEvent::Picker,
+            HotkeyAction::Pause => HotkeyEvent::Pause,
+            action => HotkeyEvent::ActionPressed(action),
+        }
+    }
+}
+
+impl std::fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyAction::Dictate => write!(f, "dictate"),
+            HotkeyAction::DictateTranslate => write!(f, "dictate-translate"),
+            HotkeyAction::HandsFree => write!(f, "hands-free"),
+            HotkeyAction::Command => write!(f, "command"),
+            HotkeyAction::Undo => write!(f, "undo"),
+            HotkeyAction::Picker => write!(f, "picker"),
+            HotkeyAction::Pause => write!(f, "pause"),
+        }
+    }
+}
+
+/// Combos claimed by more than one binding
+///
+/// `bindings` pairs a name for each binding (such as "hotkey.trigger" or
+/// "hotkeys.command") with its combo. Combos are compared with modifier
+/// aliases, key aliases, and case folded, so "Cmd+Shift+Return" and
+/// "shift+super+enter" clash. Returns one message per conflict.
+pub fn find_conflicts(bindings: &[(String, String)]) -> Vec<String> {
+    let mut seen: Vec<(String, &str, &str)> = Vec::new();
+    let mut conflicts = Vec::new();
+    for (name, combo) in bindings {
+        let normalized = normalize_combo(combo);
+        match seen.iter().find(|(other, _, _)| *other == normalized) {
+            Some((_, first, first_combo)) => conflicts.push(format!(
+                "{} ({}) uses the same keys as {} ({})",
+                name, combo, first, first_combo
+            )),
+            None => seen.push((normalized, name, combo)),
+        }
+    }
+    conflicts
+}
+
+/// Canonical form of a combo: modifiers in a fixed order, then the key
+fn normalize_combo(combo: &str) -> String {
+    let parts: Vec<String> = combo
+        .split('+')
+        .map(|part| part.trim().to_ascii_lowercase())
+        .collect();
+    let Some((key, modifiers)) = parts.split_last() else {
+        return String::new();
+    };
+    let mut modifiers: Vec<&str> = modifiers
+        .iter()
+        .map(|modifier| match modifier.as_str() {
+            "cmd" | "super" | "meta" | "win" => "super",
+            "alt" | "option" => "alt",
+            "ctrl" | "control" => "ctrl",
+            other => other,
+        })
+        .collect();
+    modifiers.sort_unstable();
+    modifiers.dedup();
+    let key = match key.as_str() {
+        "return" => "enter",
+        "esc" => "escape",
+        other => other,
+    };
+    modifiers.push(key);
+    modifiers.join("+")
+}
+
+/// How the dictation hotkey starts and stops dictation (`hotkey.mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DictationMode {
+    /// Dictate while the hotkey is held (push-to-talk)
+    #[default]
+    Hold,
+    /// First press starts dictation, the second press stops it
+    Toggle,
+    /// Always listening; the VAD picks out speech and the hotkey pauses and
+    /// resumes listening
+    HandsFree,
+}
+
+impl DictationMode {
+    /// Parse a `hotkey.mode` value ("hold", "toggle", "hands-free")
+    pub fn parse(mode: &str) -> crate::Result<Self> {
+        match mode.to_ascii_lowercase().replace('_', "-").as_str() {
+            "hold" | "push-to-talk" => Ok(DictationMode::Hold),
+            "toggle" => Ok(DictationMode::Toggle),
+            "hands-free" | "handsfree" => Ok(DictationMode::HandsFree),
+            other => Err(crate::Error::Config(format!(
+                "Unknown hotkey mode '{}' (expected hold, toggle, or hands-free)",
+                other
+            ))),
+        }
+    }
+
+    /// Whether dictation should run after `event`, given whether it is
+    /// running now (`None` leaves it as is)
+    pub fn next_state(&self, event: HotkeyEvent, dictating: bool) -> Option<bool> {
+        match (self, event) {
+            (
+                _,
+                HotkeyEvent::Undo
+                | HotkeyEvent::Picker
+                | HotkeyEvent::Pause
+                | HotkeyEvent::ActionPressed(_)
+                | HotkeyEvent::ActionReleased(_),
+            ) => None,
+            (DictationMode::Hold, HotkeyEvent::Pressed) => Some(true),
+            (DictationMode::Hold, HotkeyEvent::Released) => Some(false),
+            (_, HotkeyEvent::Pressed) => Some(!dictating),
+            (_, HotkeyEvent::Released) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DictationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictationMode::Hold => write!(f, "hold"),
+            DictationMode::Toggle => write!(f, "toggle"),
+            DictationMode::HandsFree => write!(f, "hands-free"),
+        }
+    }
+}
+
+/// How global hotkeys are read (`hotkey.backend`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HotkeyBackend {
+    /// Evdev in a Wayland session, the native API otherwise
+    #[default]
+    Auto,
+    /// The OS hotkey API (macOS, Windows, X11)
+    Native,
+    /// Keyboards read from `/dev/input` (Linux, any display server)
+    Evdev,
+}
+
+impl HotkeyBackend {
+    /// Parse a `hotkey.backend` value ("auto", "native", "evdev")
+    pub fn parse(backend: &str) -> crate::Result<Self> {
+        match backend.to_ascii_lowercase().as_str() {
+            "auto" => Ok(HotkeyBackend::Auto),
+            "native" => Ok(HotkeyBackend::Native),
+            "evdev" => Ok(HotkeyBackend::Evdev),
+            other => Err(crate::Error::Config(format!(
+                "Unknown hotkey backend '{}' (expected auto, native, or evdev)",
+                other
+            ))),
+        }
+    }
+
+    /// The backend to use, resolving `Auto` for the current session
+    pub fn resolve(self, wayland: bool) -> Self {
+        match self {
+            HotkeyBackend::Auto if cfg!(target_os = "linux") && wayland => HotkeyBackend::Evdev,
+            HotkeyBackend::Auto => HotkeyBackend::Native,
+            backend => backend,
+        }
+    }
+
+    /// Backend for `hotkey.backend` in this session (auto when unrecognized)
+    pub fn from_config(backend: &str) -> Self {
+        Self::parse(backend)
+            .unwrap_or_default()
+            .resolve(std::env::var("WAYLAND_DISPLAY").is_ok())
+    }
+}
+
+impl std::fmt::Display for HotkeyBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyBackend::Auto => write!(f, "auto"),
+            HotkeyBackend::Native => write!(f, "native"),
+            HotkeyBackend::Evdev => write!(f, "evdev"),
+        }
+    }
 }
 
 /// Hotkey configuration
@@ -69,6 +328,7 @@ impl HotkeyConfig {
     }
 
     /// Convert to handy-keys Hotkey
+    #[cfg(feature = "hotkeys")]
     fn to_hotkey(&self) -> crate::Result<HandyHotkey> {
         // Parse modifiers
         let mut mods = Modifiers::empty();
@@ -92,6 +352,7 @@ impl HotkeyConfig {
     }
 
     /// Parse key from string
+    #[cfg(feature = "hotkeys")]
     fn parse_key(&self, key_str: &str) -> crate::Result<Key> {
         let key = match key_str.to_lowercase().as_str() {
             "space" => Key::Space,
@@ -177,12 +438,17 @@ impl HotkeyConfig {
 }
 
 /// Global hotkey manager
+#[cfg(feature = "hotkeys")]
 pub struct HotkeyManager {
     manager: HandyHotkeyManager,
     event_tx: Option<mpsc::UnboundedSender<HotkeyEvent>>,
     listener_handle: Option<std::thread::JoinHandle<()>>,
+    /// Extra hotkeys (undo, picker, `[hotkeys]`) and the event each one
+    /// sends on press
+    actions: Vec<(HotkeyId, HotkeyEvent)>,
 }
 
+#[cfg(feature = "hotkeys")]
 impl HotkeyManager {
     /// Create a new hotkey manager
     pub fn new() -> crate::Result<Self> {
@@ -194,6 +460,7 @@ impl HotkeyManager {
             manager,
             event_tx: None,
             listener_handle: None,
+            actions: Vec::new(),
         })
     }
 
@@ -221,6 +488,27 @@ impl HotkeyManager {
         Ok(rx)
     }
 
+    /// Register an additional hotkey that sends `HotkeyEvent::Undo`
+    pub fn register_undo(&mut self, config: HotkeyConfig) -> crate::Result<()> {
+        self.register_action(config, HotkeyEvent::Undo)
+    }
+
+    /// Register an additional hotkey that sends `event` when pressed
+    pub fn register_action(
+        &mut self,
+        config: HotkeyConfig,
+        event: HotkeyEvent,
+    ) -> crate::Result<()> {
+        info!("Registering {:?} hotkey: {:?}", event, config);
+
+        let id = self.manager.register(config.to_hotkey()?).map_err(|e| {
+            crate::Error::Platform(format!("Failed to register {:?} hotkey: {}", event, e))
+        })?;
+        self.actions.push((id, event));
+
+        Ok(())
+    }
+
     /// Start listening for hotkey events
     ///
     /// Note: This consumes self because HotkeyManager needs to be moved into the listener thread
@@ -236,9 +524,24 @@ impl HotkeyManager {
                 // Use blocking recv to wait for events
                 match self.manager.recv() {
                     Ok(event) => {
-                        let hotkey_event = match event.state {
-                            handy_keys::HotkeyState::Pressed => HotkeyEvent::Pressed,
-                            handy_keys::HotkeyState::Released => HotkeyEvent::Released,
+                        let action = self
+                            .actions
+                            .iter()
+                            .find(|(id, _)| *id == event.id)
+                            .map(|(_, action)| *action);
+                        let hotkey_event = if let Some(action) = action {
+                            match event.state {
+                                handy_keys::HotkeyState::Pressed => action,
+                                handy_keys::HotkeyState::Released => match action.release() {
+                                    Some(release) => release,
+                                    None => continue,
+                                },
+                            }
+                        } else {
+                            match event.state {
+                                handy_keys::HotkeyState::Pressed => HotkeyEvent::Pressed,
+                                handy_keys::HotkeyState::Released => HotkeyEvent::Released,
+                            }
                         };
 
                         if tx.send(hotkey_event).is_err() {
@@ -267,18 +570,68 @@ impl HotkeyManager {
     }
 }
 
+#[cfg(feature = "hotkeys")]
 impl Default for HotkeyManager {
     fn default() -> Self {
         Self::new().unwrap()
     }
 }
 
+#[cfg(feature = "hotkeys")]
 impl Drop for HotkeyManager {
     fn drop(&mut self) {
         let _ = self.unregister();
     }
 }
 
+// Stub implementation when feature is disabled
+#[cfg(not(feature = "hotkeys"))]
+pub struct HotkeyManager;
+
+#[cfg(not(feature = "hotkeys"))]
+impl HotkeyManager {
+    pub fn new() -> crate::Result<Self> {
+        Err(crate::Error::Platform(
+            "hotkeys feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn register(
+        &mut self,
+        _config: HotkeyConfig,
+    ) -> crate::Result<mpsc::UnboundedReceiver<HotkeyEvent>> {
+        Err(crate::Error::Platform(
+            "hotkeys feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn register_undo(&mut self, _config: HotkeyConfig) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "hotkeys feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn register_action(
+        &mut self,
+        _config: HotkeyConfig,
+        _event: HotkeyEvent,
+    ) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "hotkeys feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn start_listener(self) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "hotkeys feature not enabled".to_string(),
+        ))
+    }
+
+    pub fn unregister(&mut self) -> crate::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +644,95 @@ mod tests {
     }
 
     #[test]
+    fn test_dictation_modes() {
+        let hold = DictationMode::parse("push-to-talk").unwrap();
+        assert_eq!(hold, DictationMode::Hold);
+        assert_eq!(hold.next_state(HotkeyEvent::Pressed, false), Some(true));
+        assert_eq!(hold.next_state(HotkeyEvent::Released, true), Some(false));
+
+        let toggle = DictationMode::parse("toggle").unwrap();
+        assert_eq!(toggle.next_state(HotkeyEvent::Pressed, false), Some(true));
+        assert_eq!(toggle.next_state(HotkeyEvent::Pressed, true), Some(false));
+        assert_eq!(toggle.next_state(HotkeyEvent::Released, true), None);
+
+        let hands_free = DictationMode::parse("Hands_Free").unwrap();
+        assert_eq!(hands_free, DictationMode::HandsFree);
+        assert_eq!(hands_free.next_state(HotkeyEvent::Undo, true), None);
+        assert_eq!(hands_free.next_state(HotkeyEvent::Picker, false), None);
+        assert_eq!(hands_free.next_state(HotkeyEvent::Pause, true), None);
+        assert!(DictationMode::parse("sometimes").is_err());
+    }
+
+    #[test]
+    fn test_hotkey_actions_and_conflicts() {
+        let translate = HotkeyAction::parse("dictate_translate").unwrap();
+        assert_eq!(
+            translate.event(),
+            HotkeyEvent::ActionPressed(HotkeyAction::DictateTranslate)
+        );
+        assert_eq!(
+            translate.event().release(),
+            Some(HotkeyEvent::ActionReleased(translate))
+        );
+        assert_eq!(
+            HotkeyAction::parse("dictate").unwrap().event(),
+            HotkeyEvent::Pressed
+        );
+        assert_eq!(HotkeyAction::parse("undo").unwrap().event().release(), None);
+        assert_eq!(
+            HotkeyAction::parse("pause_resume").unwrap().event(),
+            HotkeyEvent::Pause
+        );
+        assert!(HotkeyAction::parse("shout").is_err());
+        assert_eq!(
+            DictationMode::Hold.next_state(HotkeyEvent::ActionPressed(translate), false),
+            None
+        );
+
+        let bindings = |combos: &[(&str, &str)]| -> Vec<(String, String)> {
+            combos
+                .iter()
+                .map(|(name, combo)| (name.to_string(), combo.to_string()))
+                .collect()
+        };
+        assert!(
+            find_conflicts(&bindings(&[
+                ("hotkey.trigger", "Cmd+Shift+Space"),
+                ("hotkeys.command", "Cmd+Shift+C"),
+                ("hotkeys.hands-free", "Cmd+Alt+Space"),
+            ]))
+            .is_empty()
+        );
+        let conflicts = find_conflicts(&bindings(&[
+            ("hotkey.trigger", "Cmd+Shift+Return"),
+            ("hotkeys.command", "shift+super+enter"),
+            ("hotkeys.undo", "Ctrl+Z"),
+            ("hotkey.undo", "Control+z"),
+        ]));
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts[0].starts_with("hotkeys.command (shift+super+enter)"));
+        assert!(conflicts[1].ends_with("hotkeys.undo (Ctrl+Z)"));
+    }
+
+    #[test]
+    fn test_hotkey_backends() {
+        let auto = HotkeyBackend::parse("Auto").unwrap();
+        assert_eq!(auto.resolve(false), HotkeyBackend::Native);
+        #[cfg(target_os = "linux")]
+        assert_eq!(auto.resolve(true), HotkeyBackend::Evdev);
+        assert_eq!(
+            HotkeyBackend::parse("native").unwrap().resolve(true),
+            HotkeyBackend::Native
+        );
+        assert_eq!(
+            HotkeyBackend::parse("evdev").unwrap().resolve(false),
+            HotkeyBackend::Evdev
+        );
+        assert!(HotkeyBackend::parse("portal").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "hotkeys")]
     fn test_hotkey_config_to_hotkey() {
         let config = HotkeyConfig::default();
         let hotkey = config.to_hotkey();
@@ -0,0 +1,318 @@
+//! Evdev Hotkey Backend
+//!
+//! Wayland compositors don't let clients grab global shortcuts, so this
+//! backend reads keyboards straight from `/dev/input` instead. Every
+//! keyboard that has the trigger key gets a listener thread that follows
+//! the modifier state and reports the chord's press and release; keys are
+//! only observed, never grabbed, so they still reach the focused app.
+//!
+//! Linux only; reading `/dev/input` needs the user in the `input` group.
+
+use super::{HotkeyConfig, HotkeyEvent};
+use tokio::sync::mpsc;
+
+/// Modifier groups, as bits of a mask (left and right keys count alike)
+const CTRL: u8 = 1;
+const SHIFT: u8 = 1 << 1;
+const ALT: u8 = 1 << 2;
+const SUPER: u8 = 1 << 3;
+
+/// Modifier bit for a key code, if it is a modifier
+fn modifier_bit(code: u16) -> Option<u8> {
+    match code {
+        29 | 97 => Some(CTRL),    // KEY_LEFTCTRL, KEY_RIGHTCTRL
+        42 | 54 => Some(SHIFT),   // KEY_LEFTSHIFT, KEY_RIGHTSHIFT
+        56 | 100 => Some(ALT),    // KEY_LEFTALT, KEY_RIGHTALT
+        125 | 126 => Some(SUPER), // KEY_LEFTMETA, KEY_RIGHTMETA
+        _ => None,
+    }
+}
+
+/// A key combination as evdev codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    modifiers: u8,
+    key: u16,
+}
+
+impl Chord {
+    /// Chord for a hotkey such as "Super+Shift+Space"
+    pub fn from_config(config: &HotkeyConfig) -> crate::Result<Self> {
+        let mut modifiers = 0;
+        for modifier in &config.modifiers {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "cmd" | "super" | "meta" | "win" => SUPER,
+                "shift" => SHIFT,
+                "alt" | "option" => ALT,
+                "ctrl" | "control" => CTRL,
+                other => {
+                    return Err(crate::Error::Config(format!("Unknown modifier: {}", other)));
+                }
+            };
+        }
+        let key = key_code(&config.key)
+            .ok_or_else(|| crate::Error::Config(format!("Unknown key: {}", config.key)))?;
+        Ok(Self { modifiers, key })
+    }
+}
+
+/// Evdev code for a hotkey key name ("Space", "F13", "KEY_PAUSE", "57")
+fn key_code(name: &str) -> Option<u16> {
+    let name = name.to_ascii_uppercase();
+    let alias = match name.as_str() {
+        "RETURN" => "ENTER",
+        "ESCAPE" => "ESC",
+        // Matches the native backend, where Delete is the backspace key
+        "DELETE" => "BACKSPACE",
+        "FORWARDDELETE" => "DELETE",
+        other => other,
+    };
+    #[cfg(target_os = "linux")]
+    {
+        let name = if alias.starts_with("KEY_") || alias.starts_with("BTN_") {
+            alias.to_string()
+        } else {
+            format!("KEY_{}", alias)
+        };
+        if let Ok(key) = name.parse::<evdev::Key>() {
+            return Some(key.code());
+        }
+    }
+    alias.parse().ok()
+}
+
+/// Turns one keyboard's key events into hotkey events for the chords
+struct ChordTracker {
+    /// Held modifier keys, by code
+    held: Vec<u16>,
+    /// Each chord and whether it is currently pressed
+    chords: Vec<(Chord, HotkeyEvent, bool)>,
+}
+
+impl ChordTracker {
+    /// Track the dictation chord and the action chords (undo, picker,
+    /// `[hotkeys]`)
+    fn new(dictate: Chord, actions: &[(Chord, HotkeyEvent)]) -> Self {
+        let mut chords = vec![(dictate, HotkeyEvent::Pressed, false)];
+        chords.extend(actions.iter().map(|&(chord, event)| (chord, event, false)));
+        Self {
+            held: Vec::new(),
+            chords,
+        }
+    }
+
+    fn modifiers(&self) -> u8 {
+        self.held
+            .iter()
+            .filter_map(|&code| modifier_bit(code))
+            .fold(0, |mask, bit| mask | bit)
+    }
+
+    /// Feed a key event (value 1 = press, 0 = release, 2 = repeat)
+    ///
+    /// A chord fires when its key goes down with exactly its modifiers
+    /// held, and is released with its key, whatever the modifiers do in
+    /// between.
+    fn feed(&mut self, code: u16, value: i32) -> Option<HotkeyEvent> {
+        if modifier_bit(code).is_some() {
+            match value {
+                1 if !self.held.contains(&code) => self.held.push(code),
+                0 => self.held.retain(|&held| held != code),
+                _ => {}
+            }
+        }
+
+        let modifiers = self.modifiers();
+        for (chord, on_press, pressed) in &mut self.chords {
+            if chord.key != code {
+                continue;
+            }
+            match value {
+                1 if !*pressed && chord.modifiers == modifiers => {
+                    *pressed = true;
+                    return Some(*on_press);
+                }
+                0 if *pressed => {
+                    *pressed = false;
+                    return on_press.release();
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Start a listener thread per keyboard that has the trigger key
+///
+/// `actions` are extra hotkeys (undo, picker, `[hotkeys]`) with the event
+/// each sends on press. Returns the number of keyboards being listened to; fails when
+/// none can be opened.
+#[cfg(target_os = "linux")]
+pub fn spawn_listeners(
+    trigger: &HotkeyConfig,
+    actions: &[(HotkeyConfig, HotkeyEvent)],
+    tx: mpsc::UnboundedSender<HotkeyEvent>,
+) -> crate::Result<usize> {
+    use tracing::{debug, info, warn};
+
+    let dictate = Chord::from_config(trigger)?;
+    let actions = actions
+        .iter()
+        .map(|(config, event)| Ok((Chord::from_config(config)?, *event)))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let mut started = 0;
+    for (path, mut input) in evdev::enumerate() {
+        let has_trigger = input
+            .supported_keys()
+            .is_some_and(|keys| keys.contains(evdev::Key::new(dictate.key)));
+        if !has_trigger {
+            continue;
+        }
+        let name = input.name().unwrap_or("Unknown device").to_string();
+        debug!("Hotkey keyboard {} ({})", name, path.display());
+
+        let tx = tx.clone();
+        let mut tracker = ChordTracker::new(dictate, &actions);
+        std::thread::spawn(move || {
+            loop {
+                let events = match input.fetch_events() {
+                    Ok(events) => events,
+                    Err(e) => {
+                        warn!("Keyboard {} stopped: {}", name, e);
+                        break;
+                    }
+                };
+                for event in events {
+                    let evdev::InputEventKind::Key(key) = event.kind() else {
+                        continue;
+                    };
+                    if let Some(hotkey_event) = tracker.feed(key.code(), event.value())
+                        && tx.send(hotkey_event).is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+        started += 1;
+    }
+
+    if started == 0 {
+        // Devices that can't be opened are left out of the enumeration
+        let reason = if !input_readable() {
+            "cannot read /dev/input; add your user to the input group \
+             (sudo usermod -aG input $USER) and log in again"
+        } else {
+            "no keyboard has the trigger key"
+        };
+        return Err(crate::Error::Platform(format!(
+            "Evdev hotkeys unavailable: {}",
+            reason
+        )));
+    }
+    info!("⌨️  Evdev hotkey listening on {} keyboard(s)", started);
+    Ok(started)
+}
+
+/// Whether any `/dev/input/event*` node can be opened
+#[cfg(target_os = "linux")]
+pub(crate) fn input_readable() -> bool {
+    std::fs::read_dir("/dev/input")
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                entry.file_name().to_string_lossy().starts_with("event")
+                    && std::fs::File::open(entry.path()).is_ok()
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_listeners(
+    _trigger: &HotkeyConfig,
+    _actions: &[(HotkeyConfig, HotkeyEvent)],
+    _tx: mpsc::UnboundedSender<HotkeyEvent>,
+) -> crate::Result<usize> {
+    Err(crate::Error::Platform(
+        "The evdev hotkey backend is only supported on Linux".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::HotkeyAction;
+
+    #[test]
+    fn test_chord_tracking() {
+        const SPACE: u16 = 57;
+        const LEFTSHIFT: u16 = 42;
+        const LEFTMETA: u16 = 125;
+        const Z: u16 = 44;
+
+        let dictate = Chord {
+            modifiers: SUPER | SHIFT,
+            key: SPACE,
+        };
+        let undo = Chord {
+            modifiers: SUPER | SHIFT,
+            key: Z,
+        };
+        let command = Chord {
+            modifiers: SUPER,
+            key: Z,
+        };
+        let mut tracker = ChordTracker::new(
+            dictate,
+            &[
+                (undo, HotkeyEvent::Undo),
+                (command, HotkeyAction::Command.event()),
+            ],
+        );
+
+        // Space alone, or with the wrong modifiers, does nothing
+        assert_eq!(tracker.feed(SPACE, 1), None);
+        assert_eq!(tracker.feed(SPACE, 0), None);
+        assert_eq!(tracker.feed(LEFTMETA, 1), None);
+        assert_eq!(tracker.feed(SPACE, 1), None);
+        assert_eq!(tracker.feed(SPACE, 0), None);
+
+        // Held chord: repeats are ignored, releasing a modifier first
+        // doesn't end it
+        assert_eq!(tracker.feed(LEFTSHIFT, 1), None);
+        assert_eq!(tracker.feed(SPACE, 1), Some(HotkeyEvent::Pressed));
+        assert_eq!(tracker.feed(SPACE, 2), None);
+        assert_eq!(tracker.feed(LEFTSHIFT, 0), None);
+        assert_eq!(tracker.feed(SPACE, 0), Some(HotkeyEvent::Released));
+
+        // Undo only reports its press
+        assert_eq!(tracker.feed(LEFTSHIFT, 1), None);
+        assert_eq!(tracker.feed(Z, 1), Some(HotkeyEvent::Undo));
+        assert_eq!(tracker.feed(Z, 0), None);
+
+        // [hotkeys] bindings report both press and release
+        assert_eq!(tracker.feed(LEFTSHIFT, 0), None);
+        assert_eq!(
+            tracker.feed(Z, 1),
+            Some(HotkeyEvent::ActionPressed(HotkeyAction::Command))
+        );
+        assert_eq!(
+            tracker.feed(Z, 0),
+            Some(HotkeyEvent::ActionReleased(HotkeyAction::Command))
+        );
+
+        #[cfg(target_os = "linux")]
+        {
+            let chord =
+                Chord::from_config(&HotkeyConfig::from_string("Super+Shift+Space").unwrap())
+                    .unwrap();
+            assert_eq!(chord, dictate);
+            assert_eq!(key_code("F13"), Some(183));
+            assert_eq!(key_code("return"), Some(28));
+            assert_eq!(key_code("0"), Some(11));
+        }
+        assert!(Chord::from_config(&HotkeyConfig::from_string("Hyper+Space").unwrap()).is_err());
+    }
+}
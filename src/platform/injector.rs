@@ -1,8 +1,21 @@
 //! Text Injection
 //!
 //! Insert transcribed text into the active application.
+//!
+//! `injection.method` picks how: typing through the platform text API
+//! ("accessibility"), inserting into the focused element through the macOS
+//! Accessibility API ("ax"), keystrokes, pasting through the clipboard, only
+//! copying to the clipboard, or printing to stdout for scripts. Pasting puts the previous clipboard text back
+//! afterwards; clipboard contents other than text (images, files) are not
+//! restored.
 
-use enigo::{Enigo, Keyboard, Settings};
+#[cfg(feature = "injection")]
+use super::quirks::chunk_text;
+use super::quirks::{InjectionMethod, InjectionPlan, QuirksTable};
+use super::{ax, focus, unicode_input};
+#[cfg(feature = "injection")]
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use tracing::{debug, info, warn};
@@ -10,17 +23,48 @@ use tracing::{debug, info, warn};
 /// Text injector configuration
 #[derive(Debug, Clone)]
 pub struct InjectorConfig {
+    /// Method for apps without a quirk
+    pub method: InjectionMethod,
+    /// Wait after pasting before restoring the previous clipboard text
+    /// (`None` leaves the pasted text on the clipboard)
+    pub clipboard_restore_ms: Option<u64>,
     /// Delay between keypresses in milliseconds
     pub key_delay_ms: u64,
     /// Delay before typing starts in milliseconds
     pub initial_delay_ms: u64,
+    /// Per-app overrides checked before the built-in quirks
+    pub quirks: Vec<crate::config::InjectionQuirk>,
+}
+
+impl InjectorConfig {
+    /// Injector settings from the `[injection]` config section
+    pub fn from_config(config: &crate::config::InjectionConfig) -> Self {
+        let method = InjectionMethod::parse(&config.method).unwrap_or_else(|| {
+            warn!(
+                "Unknown injection method '{}', using accessibility",
+                config.method
+            );
+            InjectionMethod::Accessibility
+        });
+        Self {
+            method,
+            clipboard_restore_ms: (config.clipboard_restore_ms > 0)
+                .then_some(config.clipboard_restore_ms),
+            key_delay_ms: config.paste_delay_ms as u64,
+            initial_delay_ms: 50,
+            quirks: config.quirks.clone(),
+        }
+    }
 }
 
 impl Default for InjectorConfig {
     fn default() -> Self {
         Self {
+            method: InjectionMethod::default(),
+            clipboard_restore_ms: None,
             key_delay_ms: 10,
             initial_delay_ms: 50,
+            quirks: Vec::new(),
         }
     }
 }
@@ -29,26 +73,85 @@ impl Default for InjectorConfig {
 #[derive(Clone)]
 pub struct TextInjector {
     config: InjectorConfig,
+    quirks: Arc<QuirksTable>,
 }
 
 impl TextInjector {
     /// Create a new text injector
     pub fn new(config: InjectorConfig) -> Self {
-        Self { config }
+        let quirks = Arc::new(QuirksTable::new(&config.quirks));
+        Self { config, quirks }
+    }
+
+    /// Injection settings for an app, from the quirks table
+    pub fn plan_for(&self, app: Option<&str>) -> InjectionPlan {
+        self.quirks.plan(app, &self.config)
     }
 
     /// Type text into the active application
+    ///
+    /// The method, chunking, and delays come from the quirks table entry for
+    /// the focused app.
     pub fn inject(&self, text: &str) -> crate::Result<()> {
         if text.is_empty() {
             return Ok(());
         }
 
+        if self.config.method == InjectionMethod::Stdout {
+            use std::io::Write;
+            let mut stdout = std::io::stdout().lock();
+            writeln!(stdout, "{}", text)?;
+            stdout.flush()?;
+            return Ok(());
+        }
+
         info!("Injecting text: {} chars", text.len());
         debug!("Text: {}", text);
 
+        let plan = self.plan_for(focus::focused_app().as_deref());
+        if let Some(quirk) = &plan.quirk {
+            debug!("Injection quirk '{}': {:?}", quirk, plan);
+        }
+
         // Wait a bit to ensure the app is ready
-        if self.config.initial_delay_ms > 0 {
-            thread::sleep(Duration::from_millis(self.config.initial_delay_ms));
+        if plan.initial_delay_ms > 0 {
+            thread::sleep(Duration::from_millis(plan.initial_delay_ms));
+        }
+
+        match plan.method {
+            InjectionMethod::ClipboardOnly => {
+                copy_to_clipboard(text)?;
+                info!("Text copied to the clipboard");
+                return Ok(());
+            }
+            InjectionMethod::Paste => match self.inject_with_paste(text) {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!("Paste injection failed ({}), typing instead", e),
+            },
+            InjectionMethod::Ax => {
+                if ax::insert_text(text) {
+                    info!("Text inserted through the Accessibility API");
+                    return Ok(());
+                }
+                debug!("Focused element doesn't accept Accessibility text, typing instead");
+            }
+            InjectionMethod::Accessibility
+            | InjectionMethod::Keystrokes
+            | InjectionMethod::Stdout => {}
+        }
+
+        // Key presses can't carry CJK text or emoji past an input method
+        if unicode_input::needs_unicode(text) {
+            match unicode_input::type_text(text, plan.key_delay_ms) {
+                Ok(()) => {
+                    info!("Text injected as Unicode input");
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Unicode input failed ({}), pasting instead", e);
+                    return self.inject_with_paste(text);
+                }
+            }
         }
 
         // Try Wayland-specific tools first on Linux
@@ -56,12 +159,12 @@ impl TextInjector {
         {
             if std::env::var("WAYLAND_DISPLAY").is_ok() {
                 // Try wtype first (most reliable on Wayland)
-                if let Ok(result) = self.inject_with_wtype(text) {
+                if let Ok(result) = self.inject_with_wtype(text, &plan) {
                     return result;
                 }
 
                 // Fallback to ydotool
-                if let Ok(result) = self.inject_with_ydotool(text) {
+                if let Ok(result) = self.inject_with_ydotool(text, &plan) {
                     return result;
                 }
 
@@ -70,11 +173,209 @@ impl TextInjector {
         }
 
         // Fallback to enigo for X11 and other platforms
-        self.inject_with_enigo(text)
+        self.inject_with_enigo(text, &plan)
+    }
+
+    /// Paste text into the active application, whatever the injection method
+    ///
+    /// Restores the previous clipboard text like the paste method.
+    pub fn paste(&self, text: &str) -> crate::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.inject_with_paste(text)
+    }
+
+    /// Copy text to the clipboard and send the paste shortcut, then put
+    /// the previous clipboard text back
+    fn inject_with_paste(&self, text: &str) -> crate::Result<()> {
+        let previous = self
+            .config
+            .clipboard_restore_ms
+            .and_then(|_| read_clipboard());
+        copy_to_clipboard(text)?;
+        let pasted = self.send_paste();
+
+        if let (Some(previous), Some(delay_ms)) = (previous, self.config.clipboard_restore_ms) {
+            // The app reads the clipboard asynchronously after the shortcut
+            thread::sleep(Duration::from_millis(delay_ms));
+            match copy_to_clipboard(&previous) {
+                Ok(()) => debug!("Restored previous clipboard contents"),
+                Err(e) => warn!("Failed to restore clipboard: {}", e),
+            }
+        }
+        pasted
+    }
+
+    /// Send the paste shortcut once the clipboard holds the text
+    fn send_paste(&self) -> crate::Result<()> {
+        #[cfg(target_os = "linux")]
+        use std::process::{Command, Stdio};
+
+        // Give the clipboard owner a moment before the target app reads it
+        thread::sleep(Duration::from_millis(self.config.key_delay_ms.max(20)));
+
+        #[cfg(target_os = "linux")]
+        if std::env::var("WAYLAND_DISPLAY").is_ok() {
+            let sent = Command::new("wtype")
+                .args(["-M", "ctrl", "v", "-m", "ctrl"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .or_else(|_| {
+                    // 29 is KEY_LEFTCTRL, 47 is KEY_V
+                    Command::new("ydotool")
+                        .args(["key", "29:1", "47:1", "47:0", "29:0"])
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .status()
+                })
+                .is_ok_and(|status| status.success());
+            return if sent {
+                info!("Text injected successfully with paste");
+                Ok(())
+            } else {
+                Err(crate::Error::Platform(
+                    "Failed to send paste shortcut".to_string(),
+                ))
+            };
+        }
+
+        self.paste_with_enigo()
+    }
+
+    #[cfg(feature = "injection")]
+    fn paste_with_enigo(&self) -> crate::Result<()> {
+        #[cfg(target_os = "macos")]
+        let modifier = Key::Meta;
+        #[cfg(not(target_os = "macos"))]
+        let modifier = Key::Control;
+
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| {
+            crate::Error::Platform(format!("Failed to initialize text injector: {:?}", e))
+        })?;
+        let map_err = |e| crate::Error::Platform(format!("Failed to send paste shortcut: {:?}", e));
+        enigo.key(modifier, Direction::Press).map_err(map_err)?;
+        let clicked = enigo.key(Key::Unicode('v'), Direction::Click);
+        enigo.key(modifier, Direction::Release).map_err(map_err)?;
+        clicked.map_err(map_err)?;
+
+        info!("Text injected successfully with paste");
+        Ok(())
+    }
+
+    #[cfg(not(feature = "injection"))]
+    fn paste_with_enigo(&self) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "injection feature not enabled".to_string(),
+        ))
+    }
+
+    /// Press backspace `chars` times
+    ///
+    /// Used to undo injections, with one press per user-perceived
+    /// character of the injected text.
+    pub fn erase(&self, chars: usize) -> crate::Result<()> {
+        if chars == 0 {
+            return Ok(());
+        }
+
+        info!("Erasing {} chars", chars);
+
+        #[cfg(target_os = "linux")]
+        {
+            if std::env::var("WAYLAND_DISPLAY").is_ok() {
+                if let Ok(result) = self.erase_with_wtype(chars) {
+                    return result;
+                }
+                if let Ok(result) = self.erase_with_ydotool(chars) {
+                    return result;
+                }
+
+                warn!("Wayland detected but no injection tools found. Install wtype or ydotool.");
+            }
+        }
+
+        self.erase_with_enigo(chars)
     }
 
     #[cfg(target_os = "linux")]
-    fn inject_with_wtype(&self, text: &str) -> Result<crate::Result<()>, ()> {
+    fn erase_with_wtype(&self, chars: usize) -> Result<crate::Result<()>, ()> {
+        use std::process::{Command, Stdio};
+
+        let mut command = Command::new("wtype");
+        for _ in 0..chars {
+            command.args(["-k", "BackSpace"]);
+        }
+
+        let output = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|_| ())?;
+
+        if output.status.success() {
+            Ok(Ok(()))
+        } else {
+            warn!(
+                "wtype failed: {:?}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Err(())
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn erase_with_ydotool(&self, chars: usize) -> Result<crate::Result<()>, ()> {
+        use std::process::{Command, Stdio};
+
+        // 14 is KEY_BACKSPACE; each press is a down/up pair
+        let mut command = Command::new("ydotool");
+        command.arg("key");
+        for _ in 0..chars {
+            command.args(["14:1", "14:0"]);
+        }
+
+        let output = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|_| ())?;
+
+        if output.status.success() {
+            Ok(Ok(()))
+        } else {
+            warn!(
+                "ydotool failed: {:?}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Err(())
+        }
+    }
+
+    #[cfg(feature = "injection")]
+    fn erase_with_enigo(&self, chars: usize) -> crate::Result<()> {
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| {
+            crate::Error::Platform(format!("Failed to initialize text injector: {:?}", e))
+        })?;
+
+        for _ in 0..chars {
+            enigo
+                .key(Key::Backspace, Direction::Click)
+                .map_err(|e| crate::Error::Platform(format!("Failed to erase text: {:?}", e)))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "injection"))]
+    fn erase_with_enigo(&self, _chars: usize) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "injection feature not enabled".to_string(),
+        ))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn inject_with_wtype(&self, text: &str, plan: &InjectionPlan) -> Result<crate::Result<()>, ()> {
         use std::io::Write;
         use std::process::{Command, Stdio};
 
@@ -92,7 +393,11 @@ impl TextInjector {
 
         debug!("Using wtype for text injection");
 
-        let mut child = Command::new("wtype")
+        let mut command = Command::new("wtype");
+        if plan.method == InjectionMethod::Keystrokes && plan.key_delay_ms > 0 {
+            command.args(["-d", &plan.key_delay_ms.to_string()]);
+        }
+        let mut child = command
             .arg("-")
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
@@ -119,7 +424,11 @@ impl TextInjector {
     }
 
     #[cfg(target_os = "linux")]
-    fn inject_with_ydotool(&self, text: &str) -> Result<crate::Result<()>, ()> {
+    fn inject_with_ydotool(
+        &self,
+        text: &str,
+        plan: &InjectionPlan,
+    ) -> Result<crate::Result<()>, ()> {
         use std::process::{Command, Stdio};
 
         // Check if ydotool is available
@@ -136,8 +445,12 @@ impl TextInjector {
 
         debug!("Using ydotool for text injection");
 
-        let output = Command::new("ydotool")
-            .arg("type")
+        let mut command = Command::new("ydotool");
+        command.arg("type");
+        if plan.method == InjectionMethod::Keystrokes {
+            command.args(["--key-delay", &plan.key_delay_ms.to_string()]);
+        }
+        let output = command
             .arg(text)
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
@@ -156,17 +469,24 @@ impl TextInjector {
         }
     }
 
-    fn inject_with_enigo(&self, text: &str) -> crate::Result<()> {
+    #[cfg(feature = "injection")]
+    fn inject_with_enigo(&self, text: &str, plan: &InjectionPlan) -> crate::Result<()> {
         let settings = Settings::default();
         match Enigo::new(&settings) {
             Ok(mut enigo) => {
-                enigo.text(text).map_err(|e| {
-                    crate::Error::Platform(format!("Failed to inject text: {:?}", e))
-                })?;
+                let chunk_size = match plan.method {
+                    InjectionMethod::Keystrokes => plan.chunk_size,
+                    _ => 0,
+                };
+                for chunk in chunk_text(text, chunk_size) {
+                    enigo.text(chunk).map_err(|e| {
+                        crate::Error::Platform(format!("Failed to inject text: {:?}", e))
+                    })?;
 
-                // Small delay after typing
-                if self.config.key_delay_ms > 0 {
-                    thread::sleep(Duration::from_millis(self.config.key_delay_ms));
+                    // Small delay after typing
+                    if plan.key_delay_ms > 0 {
+                        thread::sleep(Duration::from_millis(plan.key_delay_ms));
+                    }
                 }
 
                 info!("Text injected successfully with enigo");
@@ -181,6 +501,13 @@ impl TextInjector {
             }
         }
     }
+
+    #[cfg(not(feature = "injection"))]
+    fn inject_with_enigo(&self, _text: &str, _plan: &InjectionPlan) -> crate::Result<()> {
+        Err(crate::Error::Platform(
+            "injection feature not enabled".to_string(),
+        ))
+    }
 }
 
 impl Default for TextInjector {
@@ -199,6 +526,80 @@ pub enum InjectionStrategy {
     Clipboard,
 }
 
+/// Current clipboard text, if there is any and a clipboard tool can read it
+pub fn read_clipboard() -> Option<String> {
+    use std::process::{Command, Stdio};
+
+    #[cfg(target_os = "macos")]
+    let paste: &[&[&str]] = &[&["pbpaste"]];
+    #[cfg(target_os = "linux")]
+    let paste: &[&[&str]] = if std::env::var("WAYLAND_DISPLAY").is_ok() {
+        &[&["wl-paste", "--no-newline", "--type", "text"]]
+    } else {
+        &[
+            &["xclip", "-selection", "clipboard", "-o"],
+            &["xsel", "--clipboard", "--output"],
+        ]
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let paste: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]];
+
+    paste.iter().find_map(|command| {
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let text = String::from_utf8(output.stdout).ok()?;
+        (output.status.success() && !text.is_empty()).then_some(text)
+    })
+}
+
+/// Replace the clipboard contents with `text`
+pub fn copy_to_clipboard(text: &str) -> crate::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[cfg(target_os = "macos")]
+    let copy: &[&[&str]] = &[&["pbcopy"]];
+    #[cfg(target_os = "linux")]
+    let copy: &[&[&str]] = if std::env::var("WAYLAND_DISPLAY").is_ok() {
+        &[&["wl-copy"]]
+    } else {
+        &[
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let copy: &[&[&str]] = &[&["clip"]];
+
+    let copied = copy.iter().any(|command| {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        child.wait().is_ok_and(|status| status.success()) && written
+    });
+    if copied {
+        Ok(())
+    } else {
+        Err(crate::Error::Platform(
+            "No clipboard tool available".to_string(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,226 @@
+//! Desktop Notifications
+//!
+//! Best-effort notifications for events the user should see even when the
+//! overlay is hidden (e.g. a transcript waiting for its app to regain focus).
+//! The [`Notifier`] also turns daemon events into notifications: errors,
+//! denied permissions, finished downloads, and optionally each transcript
+//! (`[notifications]`).
+//!
+//! - macOS: Notification Center (via AppleScript)
+//! - Linux: `notify-send` (libnotify)
+//! - Windows: a toast via PowerShell
+
+use crate::config::NotificationsConfig;
+use crate::ipc::protocol::Event;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Longest notification body, in characters
+const MAX_BODY_CHARS: usize = 200;
+
+const TRANSCRIPT_TITLE: &str = "Onevox transcript";
+
+/// Shows notifications for the daemon events `[notifications]` asks for
+#[derive(Debug)]
+pub struct Notifier {
+    config: NotificationsConfig,
+    /// When each kind of notification (by title) was last shown
+    last_shown: HashMap<&'static str, Instant>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            last_shown: HashMap::new(),
+        }
+    }
+
+    /// Title and body for an event the config surfaces
+    pub fn notification(&self, event: &Event) -> Option<(&'static str, String)> {
+        let config = &self.config;
+        let (title, body) = match event {
+            Event::TranscriptionFailed { message } if config.errors => {
+                ("Onevox transcription failed", message.clone())
+            }
+            Event::InjectionFailed { message } if config.errors => {
+                ("Onevox couldn't type the transcript", message.clone())
+            }
+            Event::Error { message } if config.errors => ("Onevox error", message.clone()),
+            Event::PermissionDenied { permission, hint } if config.permissions => (
+                "Onevox needs a permission",
+                format!("{} is denied: {}", permission, hint),
+            ),
+            Event::DownloadComplete { model } if config.downloads => {
+                ("Onevox model downloaded", format!("{} is ready", model))
+            }
+            Event::DownloadFailed { model, message } if config.downloads => {
+                ("Onevox download failed", format!("{}: {}", model, message))
+            }
+            Event::TranscriptionComplete { text, .. }
+                if config.transcriptions && !text.trim().is_empty() =>
+            {
+                (TRANSCRIPT_TITLE, text.trim().to_string())
+            }
+            _ => return None,
+        };
+        Some((title, truncate(body)))
+    }
+
+    /// Whether a notification titled `title` may show at `now`; repeats of
+    /// the same failure within `min_interval_secs` would only pile up
+    fn due(&mut self, title: &'static str, now: Instant) -> bool {
+        let interval = Duration::from_secs(self.config.min_interval_secs);
+        if title != TRANSCRIPT_TITLE
+            && self
+                .last_shown
+                .get(title)
+                .is_some_and(|last| now.duration_since(*last) < interval)
+        {
+            return false;
+        }
+        self.last_shown.insert(title, now);
+        true
+    }
+
+    /// Notify about `event`, if the config asks for it
+    pub fn handle(&mut self, event: &Event) {
+        let Some((title, body)) = self.notification(event) else {
+            return;
+        };
+        if self.due(title, Instant::now()) {
+            notify(title, &body);
+        } else {
+            debug!("Holding back repeated notification: {}", title);
+        }
+    }
+}
+
+fn truncate(body: String) -> String {
+    match body.char_indices().nth(MAX_BODY_CHARS) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body,
+    }
+}
+
+/// Show a desktop notification without blocking
+pub fn notify(title: &str, body: &str) {
+    debug!("Notification: {}: {}", title, body);
+    let mut command = match notification_command(title, body) {
+        Ok(command) => command,
+        Err(e) => {
+            warn!("Desktop notification failed: {}", e);
+            return;
+        }
+    };
+    command.stdout(Stdio::null()).stderr(Stdio::null());
+
+    // Wait on a thread so the child is reaped without blocking the caller
+    std::thread::spawn(move || match command.status() {
+        Ok(status) if !status.success() => {
+            debug!("{:?} exited with {}", command.get_program(), status);
+        }
+        Ok(_) => {}
+        Err(e) => warn!(
+            "Desktop notification failed: could not run {:?}: {}",
+            command.get_program(),
+            e
+        ),
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn notification_command(title: &str, body: &str) -> crate::Result<Command> {
+    use super::announcer::applescript_string;
+
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    Ok(command)
+}
+
+/// Needs libnotify
+#[cfg(target_os = "linux")]
+fn notification_command(title: &str, body: &str) -> crate::Result<Command> {
+    let mut command = Command::new("notify-send");
+    command
+        .args(["--app-name", "onevox", "--"])
+        .arg(title)
+        .arg(body);
+    Ok(command)
+}
+
+#[cfg(target_os = "windows")]
+fn notification_command(title: &str, body: &str) -> crate::Result<Command> {
+    let escape = |s: &str| s.replace('\'', "''");
+    // Toasts need a registered app id; PowerShell's own is always there
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $toast = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $toast.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($toast.CreateTextNode('{}')) > $null; \
+         $text.Item(1).AppendChild($toast.CreateTextNode('{}')) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe').Show([Windows.UI.Notifications.ToastNotification]::new($toast))",
+        escape(title),
+        escape(body)
+    );
+
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    Ok(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn notification_command(_title: &str, _body: &str) -> crate::Result<Command> {
+    Err(crate::Error::Platform(
+        "Desktop notifications are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifications_for_events() {
+        let mut notifier = Notifier::new(&NotificationsConfig::default());
+        let failed = Event::TranscriptionFailed {
+            message: "model crashed".to_string(),
+        };
+        assert_eq!(
+            notifier.notification(&failed),
+            Some(("Onevox transcription failed", "model crashed".to_string()))
+        );
+        let transcript = Event::TranscriptionComplete {
+            text: "x".repeat(300),
+            duration_ms: 100,
+            confidence: None,
+        };
+        assert_eq!(notifier.notification(&transcript), None);
+
+        // The same failure isn't repeated within the interval
+        let now = Instant::now();
+        assert!(notifier.due("Onevox error", now));
+        assert!(!notifier.due("Onevox error", now + Duration::from_secs(5)));
+        assert!(notifier.due("Onevox download failed", now));
+        assert!(notifier.due("Onevox error", now + Duration::from_secs(31)));
+
+        let mut notifier = Notifier::new(&NotificationsConfig {
+            errors: false,
+            transcriptions: true,
+            ..NotificationsConfig::default()
+        });
+        assert_eq!(notifier.notification(&failed), None);
+        let (title, body) = notifier.notification(&transcript).unwrap();
+        assert_eq!(body.chars().count(), MAX_BODY_CHARS + 1);
+        assert!(notifier.due(title, now));
+        assert!(notifier.due(title, now));
+    }
+}
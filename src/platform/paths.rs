@@ -2,10 +2,79 @@
 //!
 //! Uses the `directories` crate with `ProjectDirs` for professional-grade
 //! cross-platform path management that follows platform conventions.
+//!
+//! In portable mode (`--portable <dir>` or `ONEVOX_PORTABLE_DIR`) every
+//! path resolves under one directory instead, so an install can live on a
+//! USB stick and a full backup is a single copy:
+//!
+//! ```text
+//! <dir>/config/config.toml
+//! <dir>/data/      history, backups, recordings
+//! <dir>/cache/     models
+//! <dir>/logs/
+//! <dir>/run/       IPC socket and PID file (Unix)
+//! ```
 
 use crate::Result;
 use directories::ProjectDirs;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable that enables portable mode
+pub const PORTABLE_DIR_ENV: &str = "ONEVOX_PORTABLE_DIR";
+
+/// Portable root set from the command line
+static PORTABLE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Enable portable mode for this process
+///
+/// Creates the directory if needed. Must be called before any other path
+/// is resolved; child processes inherit the mode through
+/// `ONEVOX_PORTABLE_DIR` (see `portable_dir`).
+pub fn set_portable_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let dir = std::fs::canonicalize(dir)?;
+
+    match PORTABLE_DIR.get() {
+        Some(existing) if *existing != dir => Err(crate::Error::Config(format!(
+            "Portable directory already set to {}",
+            existing.display()
+        ))),
+        Some(_) => Ok(()),
+        None => {
+            let _ = PORTABLE_DIR.set(dir);
+            Ok(())
+        }
+    }
+}
+
+/// Root directory of portable mode, if enabled
+///
+/// Pass it to spawned onevox processes as `ONEVOX_PORTABLE_DIR`.
+pub fn portable_dir() -> Option<PathBuf> {
+    resolve_portable_dir(
+        PORTABLE_DIR.get().map(PathBuf::as_path),
+        std::env::var_os(PORTABLE_DIR_ENV),
+    )
+}
+
+/// The command-line directory wins over the environment; an empty
+/// variable counts as unset
+fn resolve_portable_dir(explicit: Option<&Path>, env: Option<OsString>) -> Option<PathBuf> {
+    explicit
+        .map(Path::to_path_buf)
+        .or_else(|| env.filter(|v| !v.is_empty()).map(PathBuf::from))
+}
+
+/// Create a directory (owner-only) if it does not exist yet
+fn ensure_dir(dir: PathBuf) -> Result<PathBuf> {
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+        set_dir_permissions(&dir)?;
+    }
+    Ok(dir)
+}
 
 /// Get ProjectDirs instance for onevox
 fn project_dirs() -> Result<ProjectDirs> {
@@ -19,7 +88,12 @@ fn project_dirs() -> Result<ProjectDirs> {
 /// - macOS: `~/Library/Caches/com.onevox.onevox`
 /// - Linux: `~/.cache/onevox`
 /// - Windows: `%LOCALAPPDATA%\onevox\onevox\cache`
+/// - Portable: `<dir>/cache`
 pub fn cache_dir() -> Result<PathBuf> {
+    if let Some(root) = portable_dir() {
+        return ensure_dir(root.join("cache"));
+    }
+
     let proj_dirs = project_dirs()?;
     let cache = proj_dirs.cache_dir().to_path_buf();
 
@@ -38,7 +112,12 @@ pub fn cache_dir() -> Result<PathBuf> {
 /// - macOS: `~/Library/Application Support/com.onevox.onevox`
 /// - Linux: `~/.config/onevox`
 /// - Windows: `%APPDATA%\onevox\onevox\config`
+/// - Portable: `<dir>/config`
 pub fn config_dir() -> Result<PathBuf> {
+    if let Some(root) = portable_dir() {
+        return ensure_dir(root.join("config"));
+    }
+
     let proj_dirs = project_dirs()?;
     let config = proj_dirs.config_dir().to_path_buf();
 
@@ -57,7 +136,12 @@ pub fn config_dir() -> Result<PathBuf> {
 /// - macOS: `~/Library/Application Support/com.onevox.onevox`
 /// - Linux: `~/.local/share/onevox`
 /// - Windows: `%APPDATA%\onevox\onevox\data`
+/// - Portable: `<dir>/data`
 pub fn data_dir() -> Result<PathBuf> {
+    if let Some(root) = portable_dir() {
+        return ensure_dir(root.join("data"));
+    }
+
     let proj_dirs = project_dirs()?;
     let data = proj_dirs.data_dir().to_path_buf();
 
@@ -85,8 +169,32 @@ pub fn models_dir() -> Result<PathBuf> {
 }
 
 /// Get the path for a specific model
+///
+/// Resolves the active version when the model uses the versioned layout
+/// (`<model_id>/versions/<version>` with a `current` pointer).
 pub fn model_path(model_id: &str) -> Result<PathBuf> {
-    Ok(models_dir()?.join(model_id))
+    Ok(active_model_dir(&models_dir()?.join(model_id)))
+}
+
+/// Resolve the active version directory inside a model directory
+///
+/// Checks the `current` symlink first, then the `CURRENT` pointer file
+/// (used where symlinks are unavailable), and finally falls back to the
+/// legacy flat layout where files live directly in the model directory.
+pub fn active_model_dir(model_root: &Path) -> PathBuf {
+    let current = model_root.join("current");
+    if current.is_dir() {
+        return current;
+    }
+
+    if let Ok(label) = std::fs::read_to_string(model_root.join("CURRENT")) {
+        let version_dir = model_root.join("versions").join(label.trim());
+        if version_dir.is_dir() {
+            return version_dir;
+        }
+    }
+
+    model_root.to_path_buf()
 }
 
 /// Get the history database path
@@ -94,6 +202,30 @@ pub fn history_db_path() -> Result<PathBuf> {
     Ok(data_dir()?.join("history.json"))
 }
 
+/// Get the history backups directory
+pub fn backups_dir() -> Result<PathBuf> {
+    let backups = data_dir()?.join("backups");
+
+    if !backups.exists() {
+        std::fs::create_dir_all(&backups)?;
+        set_dir_permissions(&backups)?;
+    }
+
+    Ok(backups)
+}
+
+/// Get the retained audio recordings directory
+pub fn recordings_dir() -> Result<PathBuf> {
+    let recordings = data_dir()?.join("recordings");
+
+    if !recordings.exists() {
+        std::fs::create_dir_all(&recordings)?;
+        set_dir_permissions(&recordings)?;
+    }
+
+    Ok(recordings)
+}
+
 /// Get the config file path
 pub fn config_file_path() -> Result<PathBuf> {
     Ok(config_dir()?.join("config.toml"))
@@ -105,9 +237,14 @@ pub fn config_file_path() -> Result<PathBuf> {
 /// - macOS: `/tmp/onevox`
 /// - Linux: `$XDG_RUNTIME_DIR/onevox` or `/tmp/onevox`
 /// - Windows: Fallback to cache directory
+/// - Portable: `<dir>/run` (Unix)
 pub fn runtime_dir() -> Result<PathBuf> {
     #[cfg(unix)]
     {
+        if let Some(root) = portable_dir() {
+            return ensure_dir(root.join("run"));
+        }
+
         // Use XDG_RUNTIME_DIR on Linux if available
         #[cfg(target_os = "linux")]
         if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
@@ -141,7 +278,12 @@ pub fn runtime_dir() -> Result<PathBuf> {
 /// - macOS: `~/Library/Logs/com.onevox.onevox`
 /// - Linux: `~/.local/share/onevox/logs`
 /// - Windows: `%APPDATA%\onevox\onevox\data\logs`
+/// - Portable: `<dir>/logs`
 pub fn log_dir() -> Result<PathBuf> {
+    if let Some(root) = portable_dir() {
+        return ensure_dir(root.join("logs"));
+    }
+
     #[cfg(target_os = "macos")]
     let log_dir = {
         // macOS has a dedicated Logs directory
@@ -170,9 +312,14 @@ pub fn log_dir() -> Result<PathBuf> {
 /// - macOS: `/tmp/onevox.sock`
 /// - Linux: `$XDG_RUNTIME_DIR/onevox.sock` or `/tmp/onevox.sock`
 /// - Windows: `\\.\pipe\onevox` (named pipe)
+/// - Portable: `<dir>/run/onevox.sock` (Unix; Windows keeps the named pipe)
 pub fn ipc_socket_path() -> Result<PathBuf> {
     #[cfg(unix)]
     {
+        if portable_dir().is_some() {
+            return Ok(runtime_dir()?.join("onevox.sock"));
+        }
+
         // Use XDG_RUNTIME_DIR on Linux if available (better for systemd integration)
         #[cfg(target_os = "linux")]
         if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
@@ -260,6 +407,37 @@ mod tests {
         println!("Model path: {}", path.display());
     }
 
+    #[test]
+    fn test_active_model_dir() {
+        let root = tempfile::tempdir().unwrap();
+
+        // Legacy flat layout resolves to the model directory itself
+        assert_eq!(active_model_dir(root.path()), root.path());
+
+        // Versioned layout resolves through the CURRENT pointer
+        std::fs::create_dir_all(root.path().join("versions").join("v2")).unwrap();
+        std::fs::write(root.path().join("CURRENT"), "v2\n").unwrap();
+        assert_eq!(
+            active_model_dir(root.path()),
+            root.path().join("versions").join("v2")
+        );
+    }
+
+    #[test]
+    fn test_resolve_portable_dir() {
+        let explicit = Path::new("/media/usb/onevox");
+        assert_eq!(
+            resolve_portable_dir(Some(explicit), Some("/elsewhere".into())),
+            Some(explicit.to_path_buf())
+        );
+        assert_eq!(
+            resolve_portable_dir(None, Some("/elsewhere".into())),
+            Some(PathBuf::from("/elsewhere"))
+        );
+        assert_eq!(resolve_portable_dir(None, Some(OsString::new())), None);
+        assert_eq!(resolve_portable_dir(None, None), None);
+    }
+
     #[test]
     fn test_log_dir() {
         let dir = log_dir().unwrap();
@@ -1,6 +1,12 @@
 //! Platform Permissions
 //!
 //! Check and request platform-specific permissions (macOS Accessibility, etc.).
+//!
+//! On macOS the checks ask the OS (TCC) for the running executable:
+//! Accessibility through `AXIsProcessTrustedWithOptions`, Input Monitoring
+//! through `IOHIDCheckAccess`, and the microphone through `AVCaptureDevice`.
+//! A terminal that launches onevox and the launchd agent are granted
+//! separately. `onevox doctor permissions` prints the report.
 
 use tracing::info;
 
@@ -17,6 +23,63 @@ pub enum Permission {
     ScreenRecording,
 }
 
+impl Permission {
+    /// Where to grant the permission on this platform
+    pub fn fix_hint(&self) -> String {
+        #[cfg(target_os = "macos")]
+        {
+            let pane = match self {
+                Permission::Accessibility => "Accessibility",
+                Permission::InputMonitoring => "Input Monitoring",
+                Permission::Microphone => "Microphone",
+                Permission::ScreenRecording => "Screen Recording",
+            };
+            let exe = std::env::current_exe()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|_| "onevox".to_string());
+            format!(
+                "System Settings → Privacy & Security → {}: enable {} (or the terminal running it), then restart onevox",
+                pane, exe
+            )
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            match self {
+                Permission::InputMonitoring => {
+                    "add your user to the input group (sudo usermod -aG input $USER) and log in again"
+                        .to_string()
+                }
+                Permission::Microphone => {
+                    "check that /dev/snd exists and your user is in the audio group".to_string()
+                }
+                _ => "no permission needed on Linux".to_string(),
+            }
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        {
+            match self {
+                Permission::Microphone => {
+                    "Settings → Privacy → Microphone: allow desktop apps".to_string()
+                }
+                _ => "no permission needed on this platform".to_string(),
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::Accessibility => write!(f, "Accessibility"),
+            Permission::InputMonitoring => write!(f, "Input Monitoring"),
+            Permission::Microphone => write!(f, "Microphone"),
+            Permission::ScreenRecording => write!(f, "Screen Recording"),
+        }
+    }
+}
+
 /// Permission status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PermissionStatus {
@@ -30,13 +93,119 @@ pub enum PermissionStatus {
     NotApplicable,
 }
 
+impl PermissionStatus {
+    /// Whether the feature needing it can't work
+    pub fn is_missing(&self) -> bool {
+        matches!(
+            self,
+            PermissionStatus::Denied | PermissionStatus::NotDetermined
+        )
+    }
+}
+
+impl std::fmt::Display for PermissionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PermissionStatus::Granted => write!(f, "granted"),
+            PermissionStatus::Denied => write!(f, "denied"),
+            PermissionStatus::NotDetermined => write!(f, "not asked yet"),
+            PermissionStatus::NotApplicable => write!(f, "not needed"),
+        }
+    }
+}
+
+/// TCC queries (macOS)
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PermissionStatus;
+    use core_foundation::base::TCFType;
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+    use core_foundation::string::{CFString, CFStringRef};
+    use objc::runtime::{Class, Object};
+    use objc::{msg_send, sel, sel_impl};
+
+    /// kIOHIDRequestTypeListenEvent
+    const LISTEN_EVENT: u32 = 1;
+    /// kIOHIDAccessTypeGranted / kIOHIDAccessTypeDenied
+    const ACCESS_GRANTED: u32 = 0;
+    const ACCESS_DENIED: u32 = 1;
+    /// AVAuthorizationStatus values
+    const AV_DENIED: isize = 2;
+    const AV_RESTRICTED: isize = 1;
+    const AV_AUTHORIZED: isize = 3;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        static kAXTrustedCheckOptionPrompt: CFStringRef;
+        fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> u8;
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOHIDCheckAccess(request: u32) -> u32;
+        fn IOHIDRequestAccess(request: u32) -> bool;
+    }
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    unsafe extern "C" {
+        static AVMediaTypeAudio: *mut Object;
+    }
+
+    /// Whether this process is trusted for Accessibility, showing the
+    /// system prompt when `prompt` is set and it isn't
+    pub fn accessibility_trusted(prompt: bool) -> bool {
+        // SAFETY: the key is a constant CFString owned by the framework;
+        // wrapping it under the get rule retains it for the dictionary
+        let key = unsafe { CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt) };
+        let options = CFDictionary::from_CFType_pairs(&[(
+            key.as_CFType(),
+            CFBoolean::from(prompt).as_CFType(),
+        )]);
+        // SAFETY: the dictionary outlives the call
+        unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) != 0 }
+    }
+
+    /// Input Monitoring (listening to keyboard events)
+    pub fn input_monitoring() -> PermissionStatus {
+        // SAFETY: takes a request type constant and only reads TCC state
+        match unsafe { IOHIDCheckAccess(LISTEN_EVENT) } {
+            ACCESS_GRANTED => PermissionStatus::Granted,
+            ACCESS_DENIED => PermissionStatus::Denied,
+            _ => PermissionStatus::NotDetermined,
+        }
+    }
+
+    /// Ask for Input Monitoring; macOS prompts only the first time
+    pub fn request_input_monitoring() -> bool {
+        // SAFETY: as above; may show a system dialog
+        unsafe { IOHIDRequestAccess(LISTEN_EVENT) }
+    }
+
+    /// Microphone authorization for audio capture
+    pub fn microphone() -> PermissionStatus {
+        let Some(class) = Class::get("AVCaptureDevice") else {
+            return PermissionStatus::NotDetermined;
+        };
+        // SAFETY: a class method taking the framework's media type constant
+        let status: isize =
+            unsafe { msg_send![class, authorizationStatusForMediaType: AVMediaTypeAudio] };
+        match status {
+            AV_AUTHORIZED => PermissionStatus::Granted,
+            AV_DENIED | AV_RESTRICTED => PermissionStatus::Denied,
+            _ => PermissionStatus::NotDetermined,
+        }
+    }
+}
+
 /// Check if accessibility permission is granted (macOS)
 #[cfg(target_os = "macos")]
 pub fn check_accessibility_permission() -> PermissionStatus {
-    // For now, assume granted since checking is complex
-    // In production, we'd use CGEventTap or AXIsProcessTrusted
-    info!("Skipping accessibility permission check (assumed granted)");
-    PermissionStatus::Granted
+    if macos::accessibility_trusted(false) {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Denied
+    }
 }
 
 /// Check if accessibility permission is granted (Linux)
@@ -87,8 +256,65 @@ pub fn check_microphone_permission() -> PermissionStatus {
 /// Check microphone permission (macOS)
 #[cfg(target_os = "macos")]
 pub fn check_microphone_permission() -> PermissionStatus {
-    // macOS will prompt automatically
-    PermissionStatus::Granted
+    // Not determined until the first capture, when macOS prompts
+    macos::microphone()
+}
+
+/// Check Input Monitoring permission, which global hotkeys need (macOS)
+#[cfg(target_os = "macos")]
+pub fn check_input_monitoring_permission() -> PermissionStatus {
+    macos::input_monitoring()
+}
+
+/// Check Input Monitoring permission (Linux)
+///
+/// Only the evdev hotkey backend, which `auto` picks under Wayland, needs
+/// it, as read access to `/dev/input`.
+#[cfg(target_os = "linux")]
+pub fn check_input_monitoring_permission() -> PermissionStatus {
+    if std::env::var("WAYLAND_DISPLAY").is_err() {
+        return PermissionStatus::NotApplicable;
+    }
+    if super::hotkey::evdev_listener::input_readable() {
+        PermissionStatus::Granted
+    } else {
+        PermissionStatus::Denied
+    }
+}
+
+/// Check Input Monitoring permission (Windows)
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn check_input_monitoring_permission() -> PermissionStatus {
+    PermissionStatus::NotApplicable
+}
+
+/// Show the system prompt for a permission where the OS has one, returning
+/// the status afterwards
+///
+/// macOS prompts for Accessibility and Input Monitoring; the microphone
+/// prompt appears on the first capture. Elsewhere this only checks.
+pub fn request_permission(permission: Permission) -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    match permission {
+        Permission::Accessibility => {
+            macos::accessibility_trusted(true);
+        }
+        Permission::InputMonitoring => {
+            macos::request_input_monitoring();
+        }
+        Permission::Microphone | Permission::ScreenRecording => {}
+    }
+    check_permission(permission)
+}
+
+/// Check one permission
+pub fn check_permission(permission: Permission) -> PermissionStatus {
+    match permission {
+        Permission::Accessibility => check_accessibility_permission(),
+        Permission::InputMonitoring => check_input_monitoring_permission(),
+        Permission::Microphone => check_microphone_permission(),
+        Permission::ScreenRecording => PermissionStatus::NotApplicable,
+    }
 }
 
 /// Prompt user to grant accessibility permission (macOS)
@@ -288,6 +514,10 @@ pub fn check_required_permissions() -> Vec<(Permission, PermissionStatus)> {
     let status = check_accessibility_permission();
     results.push((Permission::Accessibility, status));
 
+    // Check Input Monitoring permission (global hotkeys)
+    let input_status = check_input_monitoring_permission();
+    results.push((Permission::InputMonitoring, input_status));
+
     // Check microphone permission
     let mic_status = check_microphone_permission();
     results.push((Permission::Microphone, mic_status));
@@ -300,7 +530,7 @@ pub fn verify_permissions() -> crate::Result<()> {
     let permissions = check_required_permissions();
 
     for (perm, status) in permissions {
-        if status == PermissionStatus::Denied || status == PermissionStatus::NotDetermined {
+        if status.is_missing() {
             match perm {
                 Permission::Accessibility => {
                     prompt_accessibility_permission();
@@ -340,5 +570,14 @@ mod tests {
         let permissions = check_required_permissions();
         // Should return at least one permission on any platform
         assert!(!permissions.is_empty());
+        assert!(
+            permissions
+                .iter()
+                .any(|(permission, _)| *permission == Permission::InputMonitoring)
+        );
+        assert_eq!(Permission::InputMonitoring.to_string(), "Input Monitoring");
+        assert!(PermissionStatus::NotDetermined.is_missing());
+        assert!(!PermissionStatus::NotApplicable.is_missing());
+        assert!(!Permission::Microphone.fix_hint().is_empty());
     }
 }
@@ -0,0 +1,329 @@
+//! Injection Quirks
+//!
+//! Some apps drop or reorder synthetic keystrokes, others ignore them
+//! entirely. The quirks table maps app identifiers to the injection method,
+//! chunk size, and delays known to work there. Built-in entries cover common
+//! offenders; `[[injection.quirks]]` entries in the config are checked first
+//! and can override them.
+
+use super::injector::InjectorConfig;
+use crate::config::InjectionQuirk;
+use tracing::warn;
+
+/// How text is delivered to the focused app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InjectionMethod {
+    /// Insert the whole string at once through the platform text API
+    #[default]
+    Accessibility,
+    /// Set the text of the focused element through the macOS Accessibility
+    /// API, independent of the keyboard layout and input method; typed as
+    /// keystrokes where that isn't possible
+    Ax,
+    /// Copy to the clipboard and send the paste shortcut
+    Paste,
+    /// Type in chunks with a delay between them
+    Keystrokes,
+    /// Only copy to the clipboard; the user pastes
+    ClipboardOnly,
+    /// Print each transcript as a line on standard output, for piping
+    /// into scripts
+    Stdout,
+}
+
+impl InjectionMethod {
+    /// Parse a config value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "accessibility" => Some(InjectionMethod::Accessibility),
+            "ax" => Some(InjectionMethod::Ax),
+            "paste" | "clipboard" => Some(InjectionMethod::Paste),
+            "keystrokes" | "keystroke" | "type" => Some(InjectionMethod::Keystrokes),
+            "clipboard-only" | "clipboard_only" => Some(InjectionMethod::ClipboardOnly),
+            "stdout" => Some(InjectionMethod::Stdout),
+            _ => None,
+        }
+    }
+
+    /// Config name
+    pub fn name(&self) -> &'static str {
+        match self {
+            InjectionMethod::Accessibility => "accessibility",
+            InjectionMethod::Ax => "ax",
+            InjectionMethod::Paste => "paste",
+            InjectionMethod::Keystrokes => "keystrokes",
+            InjectionMethod::ClipboardOnly => "clipboard-only",
+            InjectionMethod::Stdout => "stdout",
+        }
+    }
+
+    /// Whether the text ends up typed into the app (and can be undone)
+    pub fn types_text(&self) -> bool {
+        !matches!(
+            self,
+            InjectionMethod::ClipboardOnly | InjectionMethod::Stdout
+        )
+    }
+}
+
+/// Injection settings resolved for one app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectionPlan {
+    /// Delivery method
+    pub method: InjectionMethod,
+    /// Characters per chunk when typing (0 = whole text)
+    pub chunk_size: usize,
+    /// Delay between chunks / keypresses in milliseconds
+    pub key_delay_ms: u64,
+    /// Delay before injection starts in milliseconds
+    pub initial_delay_ms: u64,
+    /// App pattern of the quirk that applied, if any
+    pub quirk: Option<String>,
+}
+
+/// Built-in quirk
+struct BuiltinQuirk {
+    app: &'static str,
+    method: InjectionMethod,
+    chunk_size: usize,
+    key_delay_ms: Option<u64>,
+}
+
+/// Known per-app quirks, matched as case-insensitive substrings
+const BUILTIN_QUIRKS: &[BuiltinQuirk] = &[
+    // Electron chat apps drop characters when typed into quickly
+    BuiltinQuirk {
+        app: "slack",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    BuiltinQuirk {
+        app: "discord",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    // Microsoft Teams by bundle ID (macOS), window class (Linux), and
+    // process name (Windows); a bare "teams" would also catch TeamSpeak
+    BuiltinQuirk {
+        app: "com.microsoft.teams",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    BuiltinQuirk {
+        app: "microsoft teams",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    BuiltinQuirk {
+        app: "teams-for-linux",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    BuiltinQuirk {
+        app: "ms-teams",
+        method: InjectionMethod::Paste,
+        chunk_size: 0,
+        key_delay_ms: None,
+    },
+    // Terminals handle paste specially (bracketed paste, confirmations)
+    BuiltinQuirk {
+        app: "iterm",
+        method: InjectionMethod::Keystrokes,
+        chunk_size: 1,
+        key_delay_ms: Some(2),
+    },
+    // Remote sessions only forward key events (text set through the
+    // accessibility API never reaches the host, and the clipboard may not
+    // be shared), so type slowly
+    BuiltinQuirk {
+        app: "citrix",
+        method: InjectionMethod::Keystrokes,
+        chunk_size: 1,
+        key_delay_ms: Some(10),
+    },
+    // Citrix Workspace's session window on Linux and Windows
+    BuiltinQuirk {
+        app: "wfica",
+        method: InjectionMethod::Keystrokes,
+        chunk_size: 1,
+        key_delay_ms: Some(10),
+    },
+];
+
+/// Per-app injection settings (user entries first, then built-ins)
+#[derive(Debug, Clone)]
+pub struct QuirksTable {
+    quirks: Vec<InjectionQuirk>,
+}
+
+impl QuirksTable {
+    /// Build the table from user quirks followed by the built-in ones
+    pub fn new(user: &[InjectionQuirk]) -> Self {
+        let mut quirks = user.to_vec();
+        quirks.extend(Self::builtin());
+        Self { quirks }
+    }
+
+    /// The built-in quirks
+    pub fn builtin() -> Vec<InjectionQuirk> {
+        BUILTIN_QUIRKS
+            .iter()
+            .map(|q| InjectionQuirk {
+                app: q.app.to_string(),
+                method: Some(q.method.name().to_string()),
+                chunk_size: Some(q.chunk_size),
+                key_delay_ms: q.key_delay_ms,
+                initial_delay_ms: None,
+            })
+            .collect()
+    }
+
+    /// All quirks in lookup order
+    pub fn quirks(&self) -> &[InjectionQuirk] {
+        &self.quirks
+    }
+
+    /// First quirk whose app pattern matches the focused app
+    pub fn lookup(&self, app: Option<&str>) -> Option<&InjectionQuirk> {
+        let app = app?.to_lowercase();
+        self.quirks
+            .iter()
+            .find(|q| !q.app.is_empty() && app.contains(&q.app.to_lowercase()))
+    }
+
+    /// Injection settings for the focused app
+    ///
+    /// Fields a quirk leaves unset fall back to `defaults`. Quirks don't
+    /// apply when transcripts go to stdout, since nothing is typed.
+    pub fn plan(&self, app: Option<&str>, defaults: &InjectorConfig) -> InjectionPlan {
+        let mut plan = InjectionPlan {
+            method: defaults.method,
+            chunk_size: 0,
+            key_delay_ms: defaults.key_delay_ms,
+            initial_delay_ms: defaults.initial_delay_ms,
+            quirk: None,
+        };
+
+        let Some(quirk) = self
+            .lookup(app)
+            .filter(|_| defaults.method != InjectionMethod::Stdout)
+        else {
+            return plan;
+        };
+
+        if let Some(method) = &quirk.method {
+            match InjectionMethod::parse(method) {
+                Some(method) => plan.method = method,
+                None => warn!(
+                    "Unknown injection method '{}' in quirk for {}",
+                    method, quirk.app
+                ),
+            }
+        }
+        plan.chunk_size = quirk.chunk_size.unwrap_or(plan.chunk_size);
+        plan.key_delay_ms = quirk.key_delay_ms.unwrap_or(plan.key_delay_ms);
+        plan.initial_delay_ms = quirk.initial_delay_ms.unwrap_or(plan.initial_delay_ms);
+        plan.quirk = Some(quirk.app.clone());
+        plan
+    }
+}
+
+impl Default for QuirksTable {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// Split `text` into chunks of `size` characters (0 = one chunk)
+pub fn chunk_text(text: &str, size: usize) -> Vec<&str> {
+    if size == 0 || text.is_empty() {
+        return vec![text];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for (count, (index, _)) in text.char_indices().enumerate() {
+        if count > 0 && count % size == 0 {
+            chunks.push(&text[start..index]);
+            start = index;
+        }
+    }
+    chunks.push(&text[start..]);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_quirks_override_builtin() {
+        let user = InjectionQuirk {
+            app: "Slack".into(),
+            method: Some("keystrokes".into()),
+            chunk_size: Some(4),
+            key_delay_ms: None,
+            initial_delay_ms: Some(200),
+        };
+        let table = QuirksTable::new(&[user]);
+        let defaults = InjectorConfig::default();
+
+        let slack = table.plan(Some("com.tinyspeck.slackmacgap"), &defaults);
+        assert_eq!(slack.method, InjectionMethod::Keystrokes);
+        assert_eq!(slack.chunk_size, 4);
+        assert_eq!(slack.key_delay_ms, defaults.key_delay_ms);
+        assert_eq!(slack.initial_delay_ms, 200);
+
+        let iterm = table.plan(Some("iTerm2"), &defaults);
+        assert_eq!(iterm.method, InjectionMethod::Keystrokes);
+        assert_eq!(iterm.key_delay_ms, 2);
+
+        let teams = table.plan(Some("com.microsoft.teams2"), &defaults);
+        assert_eq!(teams.method, InjectionMethod::Paste);
+        assert_eq!(
+            table
+                .plan(Some("com.teamspeak.TeamSpeak3"), &defaults)
+                .quirk,
+            None
+        );
+        let citrix = table.plan(Some("com.citrix.receiver.icaviewer.mac"), &defaults);
+        assert_eq!(citrix.method, InjectionMethod::Keystrokes);
+        assert_eq!(citrix.chunk_size, 1);
+
+        let other = table.plan(Some("TextEdit"), &defaults);
+        assert_eq!(other.method, InjectionMethod::Accessibility);
+        assert_eq!(other.quirk, None);
+        assert_eq!(table.plan(None, &defaults).quirk, None);
+
+        let clipboard = InjectorConfig {
+            method: InjectionMethod::parse("clipboard-only").unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            table.plan(Some("TextEdit"), &clipboard).method,
+            InjectionMethod::ClipboardOnly
+        );
+        let stdout = InjectorConfig {
+            method: InjectionMethod::Stdout,
+            ..Default::default()
+        };
+        let plan = table.plan(Some("iTerm2"), &stdout);
+        assert_eq!(plan.method, InjectionMethod::Stdout);
+        assert!(!plan.method.types_text());
+        assert_eq!(
+            InjectionMethod::parse("keystroke"),
+            Some(InjectionMethod::Keystrokes)
+        );
+        let ax = InjectionMethod::parse("AX").unwrap();
+        assert_eq!(ax.name(), "ax");
+        assert!(ax.types_text());
+
+        assert_eq!(chunk_text("héllo", 2), vec!["hé", "ll", "o"]);
+        assert_eq!(chunk_text("hello", 0), vec!["hello"]);
+    }
+}
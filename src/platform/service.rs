@@ -0,0 +1,392 @@
+//! Login Service
+//!
+//! `onevox service install` registers the daemon with the platform's
+//! service manager so it starts at login, running the current binary as
+//! `onevox daemon --foreground`:
+//!
+//! - macOS: a launchd agent, `~/Library/LaunchAgents/com.onevox.daemon.plist`,
+//!   kept alive by launchd; stray output goes to `onevox-stderr.log` in the
+//!   log directory
+//! - Linux: a systemd user unit, `~/.config/systemd/user/onevox.service`,
+//!   restarted on failure and logging to the journal
+//! - Windows: a scheduled task run at logon. A Windows service would run in
+//!   session 0, away from the user's desktop, microphone, and hotkeys. The
+//!   task runs `onevox daemon`, which detaches, so no console stays open.
+//!
+//! In portable mode the registration passes `--portable <dir>`.
+
+use std::path::Path;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::{debug, info};
+
+/// launchd job label (macOS)
+pub const LAUNCHD_LABEL: &str = "com.onevox.daemon";
+
+/// systemd user unit name (Linux)
+pub const SYSTEMD_UNIT: &str = "onevox.service";
+
+/// Scheduled task name (Windows)
+pub const WINDOWS_TASK: &str = "Onevox";
+
+/// Whether the daemon is registered to start at login
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceStatus {
+    /// Service manager: launchd, systemd, or Task Scheduler
+    pub manager: &'static str,
+    /// The plist, unit file, or task name
+    pub location: String,
+    pub installed: bool,
+}
+
+/// Arguments the service starts onevox with
+pub fn daemon_args(portable: Option<&Path>, foreground: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(dir) = portable {
+        args.push("--portable".to_string());
+        args.push(dir.display().to_string());
+    }
+    args.push("daemon".to_string());
+    if foreground {
+        args.push("--foreground".to_string());
+    }
+    args
+}
+
+/// launchd agent plist for `binary`; `output_file` takes what the daemon
+/// prints outside its log
+pub fn launchd_plist(binary: &Path, output_file: &Path, portable: Option<&Path>) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let arguments: String = std::iter::once(binary.display().to_string())
+        .chain(daemon_args(portable, true))
+        .map(|arg| format!("    <string>{}</string>\n", escape(&arg)))
+        .collect();
+    let log = escape(&output_file.display().to_string());
+    let file_only = crate::logging::FILE_ONLY_ENV;
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN"
+ "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{LAUNCHD_LABEL}</string>
+  <key>ProgramArguments</key>
+  <array>
+{arguments}  </array>
+  <key>EnvironmentVariables</key>
+  <dict>
+    <key>PATH</key>
+    <string>/usr/local/bin:/opt/homebrew/bin:/usr/bin:/bin:/usr/sbin:/sbin</string>
+    <key>{file_only}</key>
+    <string>1</string>
+  </dict>
+  <key>RunAtLoad</key>
+  <true/>
+  <key>KeepAlive</key>
+  <true/>
+  <key>StandardOutPath</key>
+  <string>{log}</string>
+  <key>StandardErrorPath</key>
+  <string>{log}</string>
+  <key>ProcessType</key>
+  <string>Interactive</string>
+  <key>LimitLoadToSessionType</key>
+  <array>
+    <string>Aqua</string>
+  </array>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// systemd user unit for `binary`
+pub fn systemd_unit(binary: &Path, portable: Option<&Path>) -> String {
+    // Quote every argument; % starts a systemd specifier
+    let exec_start = std::iter::once(binary.display().to_string())
+        .chain(daemon_args(portable, true))
+        .map(|arg| format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('%', "%%");
+    format!(
+        "[Unit]
+Description=Onevox Speech-to-Text Daemon
+Documentation=https://github.com/kssgarcia/onevox
+After=graphical-session.target
+
+[Service]
+Type=simple
+ExecStart={exec_start}
+Restart=on-failure
+RestartSec=3
+StandardOutput=journal
+StandardError=journal
+
+[Install]
+WantedBy=default.target
+"
+    )
+}
+
+/// Command line the Windows logon task runs
+pub fn windows_task_command(binary: &Path, portable: Option<&Path>) -> String {
+    std::iter::once(binary.display().to_string())
+        .chain(daemon_args(portable, false))
+        .map(|arg| {
+            if arg.contains(' ') {
+                format!("\"{}\"", arg)
+            } else {
+                arg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run a service manager command, returning its output
+fn run(program: &str, args: &[&str]) -> crate::Result<String> {
+    debug!("Running {} {}", program, args.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| crate::Error::Platform(format!("Failed to run {}: {}", program, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(crate::Error::Platform(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Home directory
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn base_dirs() -> crate::Result<directories::BaseDirs> {
+    directories::BaseDirs::new()
+        .ok_or_else(|| crate::Error::Config("Cannot determine base directories".into()))
+}
+
+#[cfg(target_os = "macos")]
+fn plist_path() -> crate::Result<PathBuf> {
+    Ok(base_dirs()?
+        .home_dir()
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{}.plist", LAUNCHD_LABEL)))
+}
+
+#[cfg(target_os = "linux")]
+fn unit_path() -> crate::Result<PathBuf> {
+    Ok(base_dirs()?
+        .config_dir()
+        .join("systemd")
+        .join("user")
+        .join(SYSTEMD_UNIT))
+}
+
+/// launchd domain of the logged-in user
+#[cfg(target_os = "macos")]
+fn gui_domain() -> String {
+    // SAFETY: getuid has no preconditions
+    format!("gui/{}", unsafe { libc::getuid() })
+}
+
+/// Register the daemon to start at login, and start it now
+pub fn install(binary: &Path) -> crate::Result<ServiceStatus> {
+    let portable = crate::platform::paths::portable_dir();
+
+    #[cfg(target_os = "macos")]
+    {
+        let path = plist_path()?;
+        let output_file = crate::daemon::detach::output_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(
+            &path,
+            launchd_plist(binary, &output_file, portable.as_deref()),
+        )?;
+        info!("Wrote {}", path.display());
+
+        let domain = gui_domain();
+        let plist = path.to_string_lossy();
+        // Replace an agent loaded from an older plist
+        let _ = run("launchctl", &["bootout", &domain, &plist]);
+        run("launchctl", &["bootstrap", &domain, &plist])?;
+        let target = format!("{}/{}", domain, LAUNCHD_LABEL);
+        run("launchctl", &["enable", &target])?;
+        run("launchctl", &["kickstart", "-k", &target])?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let path = unit_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, systemd_unit(binary, portable.as_deref()))?;
+        info!("Wrote {}", path.display());
+
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", SYSTEMD_UNIT])?;
+        run("systemctl", &["--user", "restart", SYSTEMD_UNIT])?;
+    }
+
+    #[cfg(windows)]
+    {
+        let command = windows_task_command(binary, portable.as_deref());
+        run(
+            "schtasks",
+            &[
+                "/Create",
+                "/F",
+                "/TN",
+                WINDOWS_TASK,
+                "/SC",
+                "ONLOGON",
+                "/RL",
+                "LIMITED",
+                "/TR",
+                &command,
+            ],
+        )
+        .map_err(|e| {
+            crate::Error::Platform(format!(
+                "{} (creating a logon task may need an elevated prompt)",
+                e
+            ))
+        })?;
+        run("schtasks", &["/Run", "/TN", WINDOWS_TASK])?;
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    {
+        let _ = (binary, portable);
+        return Err(crate::Error::Platform(
+            "No supported service manager on this platform".to_string(),
+        ));
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux", windows))]
+    status()
+}
+
+/// Stop the service and remove its registration
+///
+/// Returns whether a registration was found.
+pub fn uninstall() -> crate::Result<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        let path = plist_path()?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        let _ = run(
+            "launchctl",
+            &["bootout", &gui_domain(), &path.to_string_lossy()],
+        );
+        std::fs::remove_file(&path)?;
+        info!("Removed {}", path.display());
+        Ok(true)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let path = unit_path()?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        let _ = run("systemctl", &["--user", "disable", "--now", SYSTEMD_UNIT]);
+        std::fs::remove_file(&path)?;
+        run("systemctl", &["--user", "daemon-reload"])?;
+        info!("Removed {}", path.display());
+        Ok(true)
+    }
+
+    #[cfg(windows)]
+    {
+        if !status()?.installed {
+            return Ok(false);
+        }
+        run("schtasks", &["/Delete", "/F", "/TN", WINDOWS_TASK])?;
+        info!("Removed scheduled task {}", WINDOWS_TASK);
+        Ok(true)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    Ok(false)
+}
+
+/// Whether the daemon is registered to start at login
+pub fn status() -> crate::Result<ServiceStatus> {
+    #[cfg(target_os = "macos")]
+    {
+        let path = plist_path()?;
+        Ok(ServiceStatus {
+            manager: "launchd",
+            installed: path.exists(),
+            location: path.display().to_string(),
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let path = unit_path()?;
+        let enabled = run("systemctl", &["--user", "is-enabled", SYSTEMD_UNIT])
+            .is_ok_and(|output| output.trim() == "enabled");
+        Ok(ServiceStatus {
+            manager: "systemd",
+            installed: path.exists() && enabled,
+            location: path.display().to_string(),
+        })
+    }
+
+    #[cfg(windows)]
+    {
+        Ok(ServiceStatus {
+            manager: "Task Scheduler",
+            installed: run("schtasks", &["/Query", "/TN", WINDOWS_TASK]).is_ok(),
+            location: WINDOWS_TASK.to_string(),
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    Err(crate::Error::Platform(
+        "No supported service manager on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_definitions() {
+        let binary = Path::new("/Users/me/My Apps/onevox");
+        let portable = Path::new("/Volumes/USB/onevox & co");
+
+        let plist = launchd_plist(binary, Path::new("/tmp/onevox.log"), Some(portable));
+        assert!(plist.contains("<string>/Users/me/My Apps/onevox</string>"));
+        assert!(plist.contains("<string>/Volumes/USB/onevox &amp; co</string>"));
+        assert!(plist.contains("<string>--foreground</string>"));
+        assert!(plist.contains(&format!("<string>{}</string>", LAUNCHD_LABEL)));
+
+        let unit = systemd_unit(Path::new("/home/me/50%/onevox"), None);
+        assert!(unit.contains("ExecStart=\"/home/me/50%%/onevox\" \"daemon\" \"--foreground\"\n"));
+
+        assert_eq!(
+            windows_task_command(Path::new(r"C:\Program Files\Onevox\onevox.exe"), None),
+            r#""C:\Program Files\Onevox\onevox.exe" daemon"#
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Injection Undo
+//!
+//! Remembers the last N injections so misfires can be rolled back with
+//! `onevox undo [--count N]`, the undo hotkey, or "scratch that". Each entry
+//! records the text typed, the backspaces needed to erase it, and which app
+//! had focus; entries are dropped as soon as text is injected into another
+//! app. An undo is refused while another app has focus, since backspacing
+//! there would delete unrelated text, but the entries are kept so it can be
+//! retried from the right app (e.g. `sleep 2 && onevox undo` from a
+//! terminal).
+//!
+//! Where the focused element exposes its text through the accessibility
+//! API, the injected text is selected and deleted in one step instead, and
+//! only if it is still right before the cursor.
+
+use super::injector::TextInjector;
+use super::{ax, focus};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+use tracing::{debug, info};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// One injection that can be undone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoEntry {
+    /// Text typed
+    pub text: String,
+    /// Characters typed
+    pub chars: usize,
+    /// Backspaces that erase the text: one per user-perceived character,
+    /// so an emoji sequence or a letter with an accent takes one
+    pub keystrokes: usize,
+    /// App focused when the text was injected
+    pub app: Option<String>,
+    /// When the text was injected
+    pub injected_at: SystemTime,
+}
+
+/// Result of an undo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoOutcome {
+    /// Injections rolled back
+    pub injections: usize,
+    /// Characters erased
+    pub chars: usize,
+}
+
+/// Bounded stack of recent injections (newest last)
+#[derive(Debug)]
+pub struct UndoStack {
+    entries: VecDeque<UndoEntry>,
+    depth: usize,
+}
+
+impl UndoStack {
+    /// Create a stack remembering up to `depth` injections
+    pub fn new(depth: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(depth),
+            depth,
+        }
+    }
+
+    /// Record an injection
+    ///
+    /// Entries from a different app are invalidated first.
+    pub fn push(&mut self, text: &str, app: Option<String>) {
+        if self.depth == 0 || text.is_empty() {
+            return;
+        }
+
+        if self.entries.back().is_some_and(|last| last.app != app) {
+            debug!("Focus changed to {:?}, clearing undo stack", app);
+            self.entries.clear();
+        }
+
+        if self.entries.len() == self.depth {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(UndoEntry {
+            text: text.to_string(),
+            chars: text.chars().count(),
+            keystrokes: text.graphemes(true).count(),
+            app,
+            injected_at: SystemTime::now(),
+        });
+    }
+
+    /// Number of injections that can be undone
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there is nothing to undo
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Characters covered by the newest `count` injections
+    pub fn cumulative_chars(&self, count: usize) -> usize {
+        self.entries.iter().rev().take(count).map(|e| e.chars).sum()
+    }
+
+    /// Remove the newest `count` injections and return them, newest first
+    ///
+    /// Fails, keeping every entry, when `app` is not the app the text was
+    /// injected into.
+    pub fn pop(&mut self, count: usize, app: Option<&str>) -> crate::Result<Vec<UndoEntry>> {
+        let Some(last) = self.entries.back() else {
+            return Err(crate::Error::Platform("Nothing to undo".to_string()));
+        };
+
+        if last.app.is_some() && app.is_some() && last.app.as_deref() != app {
+            return Err(crate::Error::Platform(format!(
+                "{} has focus, but the text was typed into {}; focus it and try again",
+                app.unwrap_or_default(),
+                last.app.as_deref().unwrap_or_default()
+            )));
+        }
+
+        let count = count.min(self.entries.len());
+        Ok((0..count).filter_map(|_| self.entries.pop_back()).collect())
+    }
+
+    /// Forget all injections
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+/// Undo the newest `count` injections in the focused app
+///
+/// Blocks while querying focus and sending backspaces; call from a blocking
+/// context.
+pub fn undo_injections(
+    stack: &Mutex<UndoStack>,
+    injector: &TextInjector,
+    count: usize,
+) -> crate::Result<UndoOutcome> {
+    let app = focus::focused_app();
+    let entries = stack.lock().pop(count.max(1), app.as_deref())?;
+    let chars = entries.iter().map(|e| e.chars).sum();
+
+    // Entries come newest first; the text reads oldest first
+    let text: String = entries.iter().rev().map(|e| e.text.as_str()).collect();
+    if ax::delete_before_cursor(&text) {
+        info!(
+            "↩️  Undid {} injections ({} chars) through accessibility",
+            entries.len(),
+            chars
+        );
+        return Ok(UndoOutcome {
+            injections: entries.len(),
+            chars,
+        });
+    }
+
+    let keystrokes = entries.iter().map(|e| e.keystrokes).sum();
+    if let Err(e) = injector.erase(keystrokes) {
+        // Put the entries back so a retry can still undo them
+        let mut stack = stack.lock();
+        for entry in entries.into_iter().rev() {
+            stack.entries.push_back(entry);
+        }
+        return Err(e);
+    }
+
+    info!("↩️  Undid {} injections ({} chars)", entries.len(), chars);
+    Ok(UndoOutcome {
+        injections: entries.len(),
+        chars,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_stack_depth_and_focus_invalidation() {
+        let mut stack = UndoStack::new(2);
+        stack.push("one ", Some("Editor".into()));
+        stack.push("two ", Some("Editor".into()));
+        stack.push("three", Some("Editor".into()));
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.cumulative_chars(2), 9);
+
+        let entries = stack.pop(5, Some("Editor")).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].chars, 5);
+        assert_eq!(entries[0].text, "three");
+        assert!(stack.is_empty());
+
+        // One backspace per perceived character
+        stack.push("café 👍🏽\r\n", Some("Editor".into()));
+        let entry = stack.pop(1, Some("Editor")).unwrap().remove(0);
+        assert_eq!((entry.chars, entry.keystrokes), (9, 7));
+
+        // Injecting into another app drops older entries
+        stack.push("hola", Some("Editor".into()));
+        stack.push("mundo", Some("Terminal".into()));
+        assert_eq!(stack.len(), 1);
+
+        // Undo from a different app (the terminal running `onevox undo`)
+        // deletes nothing and keeps the entry for a retry
+        assert!(stack.pop(1, Some("Browser")).is_err());
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack.pop(1, Some("Terminal")).unwrap()[0].text, "mundo");
+        assert!(stack.pop(1, None).is_err());
+    }
+}
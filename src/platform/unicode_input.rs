@@ -0,0 +1,195 @@
+//! Unicode Text Input
+//!
+//! CJK text and emoji can't be typed as key presses: no key produces them,
+//! and an active input method (IME) rewrites the keys it sees. Text with
+//! such characters is sent as Unicode strings instead, in chunks split
+//! between user-perceived characters:
+//!
+//! - macOS: keyboard events carrying the string
+//!   (`CGEventKeyboardSetUnicodeString`)
+//! - Windows: `SendInput` with `KEYEVENTF_UNICODE`
+//! - Linux: `xdotool type` on X11, `wtype` on Wayland
+//!
+//! Where none of these works the injector pastes the text through the
+//! clipboard (`wl-copy`, `xclip`) instead.
+
+use std::thread;
+use std::time::Duration;
+use tracing::debug;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// UTF-16 units per chunk (macOS keyboard events carry at most 20)
+#[cfg(target_os = "macos")]
+const CHUNK_UTF16: usize = 20;
+#[cfg(not(target_os = "macos"))]
+const CHUNK_UTF16: usize = 200;
+
+/// Whether `text` has characters beyond the Latin scripts, which need
+/// Unicode input rather than key presses
+pub fn needs_unicode(text: &str) -> bool {
+    text.chars().any(|c| c >= '\u{0300}')
+}
+
+/// Split `text` into chunks of at most `max_utf16` UTF-16 units without
+/// breaking a user-perceived character (one longer than that is a chunk
+/// of its own)
+pub fn chunks(text: &str, max_utf16: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let (mut start, mut units) = (0, 0);
+    for (index, grapheme) in text.grapheme_indices(true) {
+        let len = grapheme.encode_utf16().count();
+        if units > 0 && units + len > max_utf16 {
+            chunks.push(&text[start..index]);
+            (start, units) = (index, 0);
+        }
+        units += len;
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
+/// Send `text` to the focused app as Unicode input, waiting `delay_ms`
+/// between chunks
+pub fn type_text(text: &str, delay_ms: u64) -> crate::Result<()> {
+    let chunks = chunks(text, CHUNK_UTF16);
+    debug!(
+        "Sending {} chars as {} Unicode chunks",
+        text.len(),
+        chunks.len()
+    );
+    for (i, chunk) in chunks.iter().enumerate() {
+        if i > 0 && delay_ms > 0 {
+            thread::sleep(Duration::from_millis(delay_ms));
+        }
+        send(chunk)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn send(chunk: &str) -> crate::Result<()> {
+    use core_graphics::event::{CGEvent, CGEventTapLocation};
+    use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+    let failed = || crate::Error::Platform("Failed to create a keyboard event".to_string());
+    let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState).map_err(|_| failed())?;
+    for keydown in [true, false] {
+        // The key code is ignored once the event carries a string
+        let event =
+            CGEvent::new_keyboard_event(source.clone(), 0, keydown).map_err(|_| failed())?;
+        event.set_string(chunk);
+        event.post(CGEventTapLocation::HID);
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn send(chunk: &str) -> crate::Result<()> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        INPUT, INPUT_0, INPUT_KEYBOARD, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP,
+        KEYEVENTF_UNICODE, SendInput, VIRTUAL_KEY,
+    };
+
+    let input = |unit: u16, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(0),
+                wScan: unit,
+                dwFlags: KEYEVENTF_UNICODE | flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    let inputs: Vec<INPUT> = chunk
+        .encode_utf16()
+        .flat_map(|unit| {
+            [
+                input(unit, KEYBD_EVENT_FLAGS(0)),
+                input(unit, KEYEVENTF_KEYUP),
+            ]
+        })
+        .collect();
+    // SAFETY: the inputs are fully initialised keyboard inputs, passed with
+    // their size as the API requires
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize == inputs.len() {
+        Ok(())
+    } else {
+        Err(crate::Error::Platform(format!(
+            "SendInput delivered {} of {} key events",
+            sent,
+            inputs.len()
+        )))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send(chunk: &str) -> crate::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let wayland = std::env::var("WAYLAND_DISPLAY").is_ok();
+    let mut command = if wayland {
+        let mut command = Command::new("wtype");
+        command.arg("-");
+        command
+    } else {
+        let mut command = Command::new("xdotool");
+        command.args(["type", "--clearmodifiers", "--delay", "0", "--", chunk]);
+        command
+    };
+    let program = if wayland { "wtype" } else { "xdotool" };
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| crate::Error::Platform(format!("{} unavailable: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take()
+        && wayland
+    {
+        stdin.write_all(chunk.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(crate::Error::Platform(format!(
+            "{} failed ({})",
+            program, status
+        )))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn send(_chunk: &str) -> crate::Result<()> {
+    Err(crate::Error::Platform(
+        "Unicode input is not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_text_chunks() {
+        assert!(!needs_unicode("Café, naïve résumé"));
+        assert!(needs_unicode("明日は晴れ"));
+        assert!(needs_unicode("done 👍"));
+
+        // Each of these takes two UTF-16 units and one of them a skin tone
+        let text = "👍🏽ok🎉🎉";
+        assert_eq!(chunks(text, 4), vec!["👍🏽", "ok🎉", "🎉"]);
+        assert_eq!(
+            chunks("日本語のテキスト", 3),
+            vec!["日本語", "のテキ", "スト"]
+        );
+        assert_eq!(chunks(text, 100), vec![text]);
+        assert!(chunks("", 20).is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! URL Scheme Events
+//!
+//! On macOS, opening an `onevox://` URL launches the app bundle's executable
+//! without arguments and delivers the URL as a "get URL" Apple Event. This
+//! module receives those events so the binary can forward them to the
+//! daemon (see `crate::automation`). Other platforms pass the URL on the
+//! command line (`onevox open-url <url>`) instead.
+
+use std::time::Duration;
+
+/// Whether the process was started by LaunchServices from the app bundle
+/// (rather than from a terminal)
+pub fn launched_from_bundle() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        use std::io::IsTerminal;
+
+        let in_bundle = std::env::current_exe()
+            .map(|exe| exe.to_string_lossy().contains(".app/Contents/MacOS/"))
+            .unwrap_or(false);
+        // Older macOS versions append a -psn_ process serial number
+        let no_args = std::env::args().skip(1).all(|arg| arg.starts_with("-psn_"));
+        in_bundle && no_args && !std::io::stdin().is_terminal()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    false
+}
+
+/// Collect URLs opened with this process, waiting up to `timeout` for the
+/// first one and a short grace period for any that follow
+///
+/// Must be called from the main thread.
+#[cfg(target_os = "macos")]
+pub fn receive_urls(timeout: Duration) -> Vec<String> {
+    use objc::declare::ClassDecl;
+    use objc::runtime::{BOOL, Class, Object, Sel, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::{CStr, CString};
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    #[link(name = "AppKit", kind = "framework")]
+    unsafe extern "C" {}
+
+    /// Four-character codes: kInternetEventClass / kAEGetURL and keyDirectObject
+    const GET_URL: u32 = u32::from_be_bytes(*b"GURL");
+    const DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+    /// NSApplicationActivationPolicyProhibited: no Dock icon or menu bar
+    const ACTIVATION_PROHIBITED: i64 = 2;
+    /// Idle time after the last URL before returning
+    const GRACE: Duration = Duration::from_millis(300);
+
+    static URLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    extern "C" fn handle_get_url(
+        _this: &Object,
+        _cmd: Sel,
+        event: *mut Object,
+        _reply: *mut Object,
+    ) {
+        unsafe {
+            let descriptor: *mut Object =
+                msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+            if descriptor.is_null() {
+                return;
+            }
+            let string: *mut Object = msg_send![descriptor, stringValue];
+            if string.is_null() {
+                return;
+            }
+            let utf8: *const std::os::raw::c_char = msg_send![string, UTF8String];
+            if !utf8.is_null() {
+                let url = CStr::from_ptr(utf8).to_string_lossy().into_owned();
+                URLS.lock().unwrap_or_else(|e| e.into_inner()).push(url);
+            }
+        }
+    }
+
+    unsafe {
+        let class = match Class::get("OnevoxURLHandler") {
+            Some(class) => class,
+            None => {
+                let Some(mut decl) = ClassDecl::new("OnevoxURLHandler", class!(NSObject)) else {
+                    return Vec::new();
+                };
+                decl.add_method(
+                    sel!(handleGetURLEvent:withReplyEvent:),
+                    handle_get_url as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+                );
+                decl.register()
+            }
+        };
+        let handler: *mut Object = msg_send![class, new];
+
+        let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let _: BOOL = msg_send![app, setActivationPolicy: ACTIVATION_PROHIBITED];
+
+        let manager: *mut Object = msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+        let _: () = msg_send![manager,
+            setEventHandler: handler
+            andSelector: sel!(handleGetURLEvent:withReplyEvent:)
+            forEventClass: GET_URL
+            andEventID: GET_URL];
+
+        let _: () = msg_send![app, finishLaunching];
+
+        let mode_name = CString::new("kCFRunLoopDefaultMode").unwrap_or_default();
+        let mode: *mut Object =
+            msg_send![class!(NSString), stringWithUTF8String: mode_name.as_ptr()];
+        let deadline = Instant::now() + timeout;
+        let mut last_count = 0;
+        let mut last_change = Instant::now();
+
+        loop {
+            let until: *mut Object =
+                msg_send![class!(NSDate), dateWithTimeIntervalSinceNow: 0.05f64];
+            let event: *mut Object = msg_send![app,
+                nextEventMatchingMask: u64::MAX
+                untilDate: until
+                inMode: mode
+                dequeue: YES];
+            if !event.is_null() {
+                let _: () = msg_send![app, sendEvent: event];
+            }
+
+            let count = URLS.lock().unwrap_or_else(|e| e.into_inner()).len();
+            if count != last_count {
+                last_count = count;
+                last_change = Instant::now();
+            }
+            if (count > 0 && last_change.elapsed() >= GRACE) || Instant::now() >= deadline {
+                break;
+            }
+        }
+    }
+
+    std::mem::take(&mut *URLS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn receive_urls(_timeout: Duration) -> Vec<String> {
+    Vec::new()
+}
@@ -0,0 +1,98 @@
+//! Transcript Post-Processing
+//!
+//! Text stages applied to each transcript before it is recorded in history
+//! and injected, configured under `[post_processing]` (or `[postprocess]`).
+//! Transcripts in a language listed under `[post_processing.languages]`
+//! use that language's settings instead. Near-misses of custom vocabulary
+//! terms are corrected before the stages run.
+
+pub mod commands;
+pub mod itn;
+pub mod language;
+pub mod stages;
+
+pub use commands::{CommandAction, Interpreted, VoiceCommands};
+pub use itn::{DateOrder, Locale};
+pub use stages::Stage;
+
+use crate::config::PostProcessingConfig;
+use crate::models::Transcription;
+use crate::vocabulary::Vocabulary;
+use std::collections::HashMap;
+
+/// Post-processing stages for transcripts
+#[derive(Debug, Clone, Default)]
+pub struct PostProcessor {
+    /// Locale for numbers, amounts, and dates
+    locale: Locale,
+    /// Stages, in the order they run
+    stages: Vec<Stage>,
+    /// Processors for transcripts in specific languages
+    languages: HashMap<String, PostProcessor>,
+    /// Custom vocabulary whose near-misses are replaced
+    vocabulary: Vocabulary,
+}
+
+impl PostProcessor {
+    /// Build from `[post_processing]`
+    pub fn from_config(config: &PostProcessingConfig) -> Self {
+        Self {
+            locale: Locale::from_config(&config.locale),
+            stages: Stage::from_config(config),
+            languages: config
+                .languages
+                .iter()
+                .map(|(code, rules)| {
+                    (
+                        language::primary(code),
+                        Self::from_config(&rules.apply_to(config)),
+                    )
+                })
+                .collect(),
+            vocabulary: Vocabulary::default(),
+        }
+    }
+
+    /// Correct near-misses of custom vocabulary terms, in every language
+    pub fn with_vocabulary(mut self, vocabulary: Vocabulary) -> Self {
+        for processor in self.languages.values_mut() {
+            processor.vocabulary = vocabulary.clone();
+        }
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    /// Locale numbers and dates are written in
+    pub fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// Process a piece of text
+    pub fn process_text(&self, text: &str) -> String {
+        self.stages
+            .iter()
+            .fold(self.vocabulary.correct(text), |text, stage| {
+                stage.apply(&text)
+            })
+    }
+
+    /// Process a transcript and its sentence segments in place
+    ///
+    /// A transcript without a language from the model gets one detected
+    /// from its text, which also picks the language's settings.
+    pub fn process(&self, transcription: &mut Transcription) {
+        if transcription.language.is_none() {
+            transcription.language = language::detect(&transcription.text).map(str::to_string);
+        }
+        let processor = transcription
+            .language
+            .as_deref()
+            .and_then(|code| self.languages.get(&language::primary(code)))
+            .unwrap_or(self);
+
+        transcription.text = processor.process_text(&transcription.text);
+        for segment in &mut transcription.segments {
+            segment.text = processor.process_text(&segment.text);
+        }
+    }
+}
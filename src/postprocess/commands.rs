@@ -0,0 +1,293 @@
+//! Voice Commands
+//!
+//! Spoken editing commands are turned into edits right before a transcript
+//! is injected: "new line" types Enter, "period" and "comma" attach
+//! punctuation to the previous word, and "scratch that" removes the last
+//! injected utterance (or whatever was said before it in the same one).
+//! Phrases are matched as whole words, ignoring case and the punctuation
+//! the model puts around them. `[commands]` adds phrases or overrides the
+//! built-in ones; history keeps the words as spoken.
+
+use crate::config::CommandsConfig;
+use tracing::warn;
+
+/// Built-in phrases and what they do
+const BUILTIN: &[(&str, &str)] = &[
+    ("new line", "{enter}"),
+    ("new paragraph", "{enter}{enter}"),
+    ("period", "."),
+    ("full stop", "."),
+    ("comma", ","),
+    ("question mark", "?"),
+    ("exclamation mark", "!"),
+    ("exclamation point", "!"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+    ("scratch that", "{undo}"),
+    ("delete that", "{undo}"),
+];
+
+/// Punctuation a command replaces at the end of the previous word
+const TRAILING_PUNCTUATION: &[char] = &[',', '.', ';', ':', '!', '?'];
+
+/// What a voice command does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandAction {
+    /// Type text (punctuation, newlines, or words)
+    Insert(String),
+    /// Remove the last injected utterance
+    ScratchThat,
+}
+
+impl CommandAction {
+    /// Action for a `[commands]` value (`None` for "", which disables)
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.is_empty() {
+            return None;
+        }
+        if value.eq_ignore_ascii_case("{undo}") {
+            return Some(CommandAction::ScratchThat);
+        }
+        let text = value
+            .replace("{enter}", "\n")
+            .replace("{ENTER}", "\n")
+            .replace("{tab}", "\t")
+            .replace("{TAB}", "\t");
+        if text.contains(['{', '}']) {
+            warn!(
+                "Voice command text '{}' has an unknown {{action}} (expected {{enter}}, {{tab}}, or {{undo}})",
+                value
+            );
+        }
+        Some(CommandAction::Insert(text))
+    }
+}
+
+/// Result of interpreting a transcript
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interpreted {
+    /// Text to inject
+    pub text: String,
+    /// Earlier utterances to remove before injecting `text`
+    pub scratch: usize,
+}
+
+/// Voice-command interpreter for `[commands]`
+#[derive(Debug, Clone, Default)]
+pub struct VoiceCommands {
+    /// Phrase words and actions, longest phrase first
+    phrases: Vec<(Vec<String>, CommandAction)>,
+    /// Capitalize the word after a sentence-ending command
+    capitalize: bool,
+}
+
+impl VoiceCommands {
+    /// Build from `[commands]`; `capitalize` follows
+    /// `post_processing.auto_capitalize`
+    pub fn from_config(config: &CommandsConfig, capitalize: bool) -> Self {
+        if !config.enabled {
+            return Self::default();
+        }
+
+        let mut phrases: Vec<(Vec<String>, CommandAction)> = Vec::new();
+        let builtin = BUILTIN.iter().map(|&(p, v)| (p.to_string(), v.to_string()));
+        let custom = config.phrases.iter().map(|(p, v)| (p.clone(), v.clone()));
+        for (phrase, value) in builtin.chain(custom) {
+            let words: Vec<String> = phrase.split_whitespace().map(bare_word).collect();
+            if words.iter().all(String::is_empty) {
+                continue;
+            }
+            // Configured phrases replace built-in ones
+            phrases.retain(|(existing, _)| *existing != words);
+            if let Some(action) = CommandAction::parse(&value) {
+                phrases.push((words, action));
+            }
+        }
+        phrases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+        Self {
+            phrases,
+            capitalize,
+        }
+    }
+
+    /// Whether any phrase is active
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    /// Apply the commands in `text`
+    pub fn interpret(&self, text: &str) -> Interpreted {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let bare: Vec<String> = words.iter().map(|word| bare_word(word)).collect();
+        let mut result = Interpreted::default();
+        let mut capitalize_next = false;
+
+        let mut i = 0;
+        while i < words.len() {
+            let command = self.phrases.iter().find(|(phrase, _)| {
+                bare.get(i..i + phrase.len())
+                    .is_some_and(|window| window == phrase.as_slice())
+            });
+
+            let Some((phrase, action)) = command else {
+                let out = &mut result.text;
+                if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+                if capitalize_next {
+                    let mut chars = words[i].chars();
+                    if let Some(first) = chars.next() {
+                        out.extend(first.to_uppercase());
+                        out.push_str(chars.as_str());
+                    }
+                } else {
+                    out.push_str(words[i]);
+                }
+                capitalize_next = false;
+                i += 1;
+                continue;
+            };
+            i += phrase.len();
+
+            match action {
+                CommandAction::ScratchThat if result.text.is_empty() => result.scratch += 1,
+                CommandAction::ScratchThat => result.text.clear(),
+                CommandAction::Insert(insert) => {
+                    insert_text(&mut result.text, insert);
+                    capitalize_next = self.capitalize
+                        && insert
+                            .trim_end_matches([' ', '\t'])
+                            .ends_with(['.', '!', '?', '\n']);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Apply `text` as commands only (the `command` hotkey)
+    ///
+    /// `None` unless every word belongs to a phrase, so speech that isn't a
+    /// command is never typed.
+    pub fn interpret_command(&self, text: &str) -> Option<Interpreted> {
+        let bare: Vec<String> = text
+            .split_whitespace()
+            .map(bare_word)
+            .filter(|word| !word.is_empty())
+            .collect();
+        if bare.is_empty() {
+            return None;
+        }
+        let mut i = 0;
+        while i < bare.len() {
+            let (phrase, _) = self.phrases.iter().find(|(phrase, _)| {
+                bare.get(i..i + phrase.len())
+                    .is_some_and(|window| window == phrase.as_slice())
+            })?;
+            i += phrase.len();
+        }
+        Some(self.interpret(&bare.join(" ")))
+    }
+}
+
+/// Lowercased word without surrounding punctuation
+fn bare_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Append a command's text, attaching punctuation and line breaks to the
+/// previous word
+fn insert_text(out: &mut String, insert: &str) {
+    match insert.chars().next() {
+        Some(c) if c.is_whitespace() => {
+            out.truncate(out.trim_end_matches(' ').len());
+        }
+        Some(_) if insert.chars().all(|c| TRAILING_PUNCTUATION.contains(&c)) => {
+            out.truncate(out.trim_end_matches(' ').len());
+            out.truncate(out.trim_end_matches(TRAILING_PUNCTUATION).len());
+        }
+        _ => {
+            if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+                out.push(' ');
+            }
+        }
+    }
+    out.push_str(insert);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_commands() {
+        let mut config = CommandsConfig::default();
+        let commands = VoiceCommands::from_config(&config, true);
+
+        let interpreted = commands.interpret("Hello world, period. How are you question mark");
+        assert_eq!(interpreted.text, "Hello world. How are you?");
+        assert_eq!(interpreted.scratch, 0);
+
+        assert_eq!(
+            commands
+                .interpret("dear team comma new line the build is green period thanks")
+                .text,
+            "dear team,\nThe build is green. Thanks"
+        );
+
+        // On its own it removes the previous utterance, mid-utterance it
+        // drops what came before it
+        assert_eq!(
+            commands.interpret("Scratch that."),
+            Interpreted {
+                text: String::new(),
+                scratch: 1,
+            }
+        );
+        assert_eq!(
+            commands
+                .interpret("send it friday scratch that send it monday")
+                .text,
+            "send it monday"
+        );
+
+        // Commands only: everything spoken must be a command
+        assert_eq!(
+            commands.interpret_command("New line. New line."),
+            Some(Interpreted {
+                text: "\n\n".to_string(),
+                scratch: 0,
+            })
+        );
+        assert_eq!(
+            commands
+                .interpret_command("scratch that")
+                .map(|i| i.scratch),
+            Some(1)
+        );
+        assert_eq!(commands.interpret_command("new line please"), None);
+        assert_eq!(commands.interpret_command("..."), None);
+
+        config
+            .phrases
+            .insert("smiley face".to_string(), ":)".to_string());
+        config.phrases.insert("Period".to_string(), String::new());
+        config
+            .phrases
+            .insert("next field".to_string(), "{tab}".to_string());
+        let commands = VoiceCommands::from_config(&config, false);
+        assert_eq!(
+            commands
+                .interpret("the period ended smiley face next field done")
+                .text,
+            "the period ended :)\tdone"
+        );
+
+        config.enabled = false;
+        let disabled = VoiceCommands::from_config(&config, true);
+        assert!(disabled.is_empty());
+        assert_eq!(disabled.interpret("new line").text, "new line");
+    }
+}
@@ -0,0 +1,298 @@
+//! Locale-Aware Inverse Text Normalization
+//!
+//! Speech models write numbers, amounts, and dates the US way
+//! (`$1,234.56`, `03/14/2025`). This stage rewrites them for the user's
+//! locale (`1.234,56 $`, `14.03.2025` in German), taken from
+//! `post_processing.locale` or the OS locale. Other text is untouched.
+
+use std::fmt;
+
+/// Order of day, month, and year in a numeric date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// 03/14/2025
+    MonthDayYear,
+    /// 14/03/2025
+    DayMonthYear,
+    /// 2025/03/14
+    YearMonthDay,
+}
+
+/// Number and date conventions of a locale
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// Locale tag, e.g. `de-DE`
+    pub tag: String,
+    /// Decimal separator
+    pub decimal: char,
+    /// Thousands separator
+    pub group: char,
+    /// Date field order
+    pub date_order: DateOrder,
+    /// Date field separator
+    pub date_separator: char,
+    /// Currency symbol written after the amount (`12,50 €`)
+    pub currency_after: bool,
+}
+
+impl Locale {
+    /// Conventions for a BCP 47 or POSIX locale (`de-DE`, `fr_FR.UTF-8`);
+    /// unknown languages use US English conventions
+    pub fn from_tag(tag: &str) -> Self {
+        let base = tag.split(['.', '@']).next().unwrap_or_default();
+        let mut parts = base.split(['-', '_']);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        use DateOrder::*;
+        let (decimal, group, date_order, date_separator, currency_after) =
+            match (language.as_str(), region.as_str()) {
+                ("en", "" | "US" | "PH" | "CA") => ('.', ',', MonthDayYear, '/', false),
+                ("en", _) => ('.', ',', DayMonthYear, '/', false),
+                ("de", "CH") => ('.', '\'', DayMonthYear, '.', true),
+                ("de" | "da" | "tr" | "ro" | "hr" | "sl" | "sr", _) => {
+                    (',', '.', DayMonthYear, '.', true)
+                }
+                ("nl", _) => (',', '.', DayMonthYear, '-', false),
+                ("es" | "it" | "pt" | "el" | "id", _) => (',', '.', DayMonthYear, '/', true),
+                ("fr", _) => (',', '\u{a0}', DayMonthYear, '/', true),
+                ("ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "no" | "bg", _) => {
+                    (',', '\u{a0}', DayMonthYear, '.', true)
+                }
+                ("sv", _) => (',', '\u{a0}', YearMonthDay, '-', true),
+                ("hu", _) => (',', '\u{a0}', YearMonthDay, '.', true),
+                ("ja" | "zh" | "ko", _) => ('.', ',', YearMonthDay, '/', false),
+                _ => ('.', ',', MonthDayYear, '/', false),
+            };
+
+        Self {
+            tag: if region.is_empty() {
+                language
+            } else {
+                format!("{}-{}", language, region)
+            },
+            decimal,
+            group,
+            date_order,
+            date_separator,
+            currency_after,
+        }
+    }
+
+    /// The OS locale (`LC_ALL` / `LC_NUMERIC` / `LANG`, or the macOS
+    /// region setting); US English when unknown
+    pub fn system() -> Self {
+        let from_env = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX");
+
+        #[cfg(target_os = "macos")]
+        let from_env = from_env.or_else(|| {
+            crate::platform::focus::command_output("defaults", &["read", "-g", "AppleLocale"])
+        });
+
+        Self::from_tag(from_env.as_deref().unwrap_or("en-US"))
+    }
+
+    /// Resolve `post_processing.locale` ("auto" = OS locale)
+    pub fn from_config(locale: &str) -> Self {
+        if locale.trim().is_empty() || locale.eq_ignore_ascii_case("auto") {
+            Self::system()
+        } else {
+            Self::from_tag(locale)
+        }
+    }
+
+    /// Whether the locale writes numbers and dates as the models do
+    fn is_model_format(&self) -> bool {
+        self.decimal == '.'
+            && self.group == ','
+            && self.date_order == DateOrder::MonthDayYear
+            && self.date_separator == '/'
+            && !self.currency_after
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::from_tag("en-US")
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag)
+    }
+}
+
+/// Currency symbols recognized before an amount
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹', '₩'];
+
+/// Rewrite numbers, currency amounts, and dates in `text` for `locale`
+pub fn apply(text: &str, locale: &Locale) -> String {
+    if locale.is_model_format() {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let starts_word = i == 0 || !chars[i - 1].is_alphanumeric();
+        let currency = CURRENCY_SYMBOLS.contains(&chars[i])
+            && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+
+        if starts_word && (currency || chars[i].is_ascii_digit()) {
+            let start = if currency { i + 1 } else { i };
+            let mut end = start;
+            while end < chars.len() && (chars[end].is_ascii_digit() || ",./".contains(chars[end])) {
+                end += 1;
+            }
+            // Sentence punctuation after the token is not part of it
+            while end > start && !chars[end - 1].is_ascii_digit() {
+                end -= 1;
+            }
+            let glued = chars.get(end).is_some_and(|c| c.is_alphanumeric());
+
+            let token: String = chars[start..end].iter().collect();
+            match (currency, glued) {
+                (_, true) => {
+                    out.extend(&chars[i..end]);
+                }
+                (true, false) => {
+                    let amount = format_number(&token, locale).unwrap_or(token);
+                    if locale.currency_after {
+                        out.push_str(&amount);
+                        out.push('\u{a0}');
+                        out.push(chars[i]);
+                    } else {
+                        out.push(chars[i]);
+                        out.push_str(&amount);
+                    }
+                }
+                (false, false) => {
+                    let formatted = format_date(&token, locale)
+                        .or_else(|| format_number(&token, locale))
+                        .unwrap_or(token);
+                    out.push_str(&formatted);
+                }
+            }
+            i = end;
+            continue;
+        }
+
+        out.push(chars[i]);
+        i += 1;
+    }
+
+    out
+}
+
+/// Reformat a US-style number (`1,234.56`); `None` when the token isn't one
+fn format_number(token: &str, locale: &Locale) -> Option<String> {
+    if token.contains('/') {
+        return None;
+    }
+    let (integer, fraction) = match token.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (token, None),
+    };
+    if fraction.is_some_and(|f| f.is_empty() || !f.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+
+    let groups: Vec<&str> = integer.split(',').collect();
+    let grouped = groups.len() > 1;
+    let valid_groups = groups[0].len() <= 3
+        && !groups[0].is_empty()
+        && groups[1..].iter().all(|group| group.len() == 3);
+    if grouped && !valid_groups {
+        return None;
+    }
+
+    let mut out = if grouped {
+        groups.join(&locale.group.to_string())
+    } else {
+        integer.to_string()
+    };
+    if let Some(fraction) = fraction {
+        out.push(locale.decimal);
+        out.push_str(fraction);
+    }
+    Some(out)
+}
+
+/// Reformat a numeric date; `None` when the token isn't one
+///
+/// Dates are read as month/day/year unless the first field can only be a
+/// day (`14/03/2025`).
+fn format_date(token: &str, locale: &Locale) -> Option<String> {
+    let fields: Vec<&str> = token.split('/').collect();
+    let [first, second, year] = fields.as_slice() else {
+        return None;
+    };
+    let number = |field: &str| -> Option<u32> {
+        (matches!(field.len(), 1 | 2) && field.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| field.parse().ok())
+            .flatten()
+    };
+    let (a, b) = (number(first)?, number(second)?);
+    if !matches!(year.len(), 2 | 4) || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (month, day) = if a > 12 {
+        (*second, *first)
+    } else {
+        (*first, *second)
+    };
+    if a.max(b) > 31 || a.min(b) == 0 || (a > 12 && b > 12) {
+        return None;
+    }
+
+    let fields = match locale.date_order {
+        DateOrder::MonthDayYear => [month, day, *year],
+        DateOrder::DayMonthYear => [day, month, *year],
+        DateOrder::YearMonthDay => [*year, month, day],
+    };
+    Some(fields.join(&locale.date_separator.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_formatting() {
+        let de = Locale::from_tag("de_DE.UTF-8");
+        assert_eq!(de.tag, "de-DE");
+        assert_eq!(
+            apply(
+                "It costs $1,234.56 on 03/14/2025, about 2.5 times 1,2,3.",
+                &de
+            ),
+            "It costs 1.234,56\u{a0}$ on 14.03.2025, about 2,5 times 1,2,3."
+        );
+        assert_eq!(apply("Due 14/03/25", &de), "Due 14.03.25");
+
+        let us = Locale::from_tag("en-US");
+        let text = "Pay $1,234.56 by 03/14/2025.";
+        assert_eq!(apply(text, &us), text);
+
+        let gb = Locale::from_tag("en-GB");
+        assert_eq!(
+            apply("Pay £10.50 by 3/4/2025", &gb),
+            "Pay £10.50 by 4/3/2025"
+        );
+
+        let fr = Locale::from_tag("fr");
+        assert_eq!(
+            apply("12,000 km and v1.2b", &fr),
+            "12\u{a0}000 km and v1.2b"
+        );
+
+        let ja = Locale::from_tag("ja-JP");
+        assert_eq!(apply("03/14/2025", &ja), "2025/03/14");
+    }
+}
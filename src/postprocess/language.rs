@@ -0,0 +1,154 @@
+//! Transcript Language Detection
+//!
+//! Backends that don't report the spoken language (ONNX CTC models, or
+//! whisper.cpp with a forced language) get one guessed from the transcript
+//! so history and per-language post-processing still know it. Non-Latin
+//! scripts identify their language directly; Latin-script text scores a
+//! point per distinct common function word of each language, and only a
+//! clear winner is reported, so short or mixed utterances stay unlabelled.
+
+/// Most frequent function words of the Latin-script languages detected
+const FUNCTION_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "to", "of", "in", "that", "it", "you", "i", "this", "for",
+            "with", "was", "what", "have", "be", "not", "on", "we", "my", "should", "will",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "de", "que", "y", "en", "un", "una", "es", "por", "para",
+            "con", "no", "lo", "se", "del", "al", "pero", "como", "más", "mi", "yo", "está",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "de", "des", "et", "est", "un", "une", "que", "qui", "dans", "pour",
+            "pas", "je", "vous", "nous", "ce", "sur", "avec", "du", "il", "elle",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "es", "ein", "eine", "zu",
+            "mit", "den", "dem", "auf", "für", "wir", "auch", "von",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "de", "que", "e", "um", "uma", "não", "para", "com", "é", "do", "da",
+            "em", "se", "eu", "você", "mas", "por",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "gli", "di", "che", "e", "un", "una", "non", "per", "con", "è", "sono",
+            "del", "della", "in", "io", "ma", "anche",
+        ],
+    ),
+];
+
+/// Language (ISO 639-1) of `text`, if it can be told with confidence
+pub fn detect(text: &str) -> Option<&'static str> {
+    if let Some(language) = detect_script(text) {
+        return Some(language);
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scores: Vec<(&str, usize)> = FUNCTION_WORDS
+        .iter()
+        .map(|(language, common)| {
+            let mut hits: Vec<&str> = words
+                .iter()
+                .map(String::as_str)
+                .filter(|word| common.contains(word))
+                .collect();
+            hits.sort_unstable();
+            hits.dedup();
+            (*language, hits.len())
+        })
+        .collect();
+    scores.sort_by_key(|&(_, hits)| std::cmp::Reverse(hits));
+
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= 2 && best > second => Some(language),
+        _ => None,
+    }
+}
+
+/// Language of text written mostly in a script used by one language
+fn detect_script(text: &str) -> Option<&'static str> {
+    let mut counts = [0usize; 8];
+    let mut letters = 0;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let slot = match c as u32 {
+            0x3040..=0x30FF => 0,                   // Hiragana, Katakana
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => 1, // Hangul
+            0x4E00..=0x9FFF => 2,                   // CJK ideographs
+            0x0400..=0x04FF => 3,                   // Cyrillic
+            0x0600..=0x06FF => 4,                   // Arabic
+            0x0370..=0x03FF => 5,                   // Greek
+            0x0590..=0x05FF => 6,                   // Hebrew
+            0x0900..=0x097F => 7,                   // Devanagari
+            _ => continue,
+        };
+        counts[slot] += 1;
+    }
+    if letters == 0 {
+        return None;
+    }
+
+    // Kana marks Japanese even when most characters are kanji
+    if counts[0] > 0 && (counts[0] + counts[2]) * 2 > letters {
+        return Some("ja");
+    }
+    const LANGUAGES: [&str; 8] = ["ja", "ko", "zh", "ru", "ar", "el", "he", "hi"];
+    counts
+        .iter()
+        .zip(LANGUAGES)
+        .find(|(count, _)| **count * 2 > letters)
+        .map(|(_, language)| language)
+}
+
+/// Primary subtag of a language code, lowercased ("es-MX" → "es")
+pub fn primary(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or(language)
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("I think we should ship it today"), Some("en"));
+        assert_eq!(
+            detect("Creo que deberíamos enviarlo hoy por la tarde"),
+            Some("es")
+        );
+        assert_eq!(detect("Wir treffen uns morgen mit dem Team"), Some("de"));
+        assert_eq!(detect("Привет, как дела?"), Some("ru"));
+        assert_eq!(detect("今日は良い天気ですね"), Some("ja"));
+        // Too little to go on
+        assert_eq!(detect("okay"), None);
+        assert_eq!(detect(""), None);
+
+        assert_eq!(primary("es-MX"), "es");
+        assert_eq!(primary("EN"), "en");
+    }
+}
@@ -0,0 +1,257 @@
+//! Post-Processing Stages
+//!
+//! The text processors a transcript passes through, in the order listed in
+//! `post_processing.stages` (or derived from the on/off flags when that is
+//! empty): locale-aware numbers, filler-word removal, profanity masking,
+//! sentence punctuation, and capitalization.
+
+use super::itn::{self, Locale};
+use crate::config::PostProcessingConfig;
+use tracing::warn;
+
+/// Fillers removed when no `filler_words` are configured
+pub const DEFAULT_FILLER_WORDS: &[&str] = &["um", "umm", "uh", "uhm", "er", "erm", "ah", "hmm"];
+
+/// Words masked by the profanity stage, besides `profanity_words`
+const PROFANITY: &[&str] = &[
+    "fuck", "shit", "bitch", "cunt", "asshole", "bastard", "dick", "piss", "crap", "damn",
+];
+
+/// Endings that still count as the same word when masking ("fucking")
+const PROFANITY_SUFFIXES: &[&str] = &["", "s", "ed", "er", "ers", "ing", "y", "ty"];
+
+/// Characters that end a sentence
+const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+/// One post-processing step
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    /// Numbers, amounts, and dates written for the locale
+    Numbers(Locale),
+    /// Drop filler words ("um", "uh")
+    Fillers(Vec<String>),
+    /// Replace all but the first letter of profanity with `*`
+    Profanity(Vec<String>),
+    /// End the text with sentence punctuation and tidy spaces before it
+    Punctuation,
+    /// Capitalize the first letter of each sentence
+    Capitalize,
+}
+
+impl Stage {
+    /// Stage for a `post_processing.stages` entry
+    pub fn parse(name: &str, config: &PostProcessingConfig) -> Option<Self> {
+        let stage = match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "numbers" | "itn" => Stage::Numbers(Locale::from_config(&config.locale)),
+            "fillers" | "filler-words" | "remove-filler-words" => {
+                Stage::Fillers(config.filler_words.clone())
+            }
+            "profanity" | "mask-profanity" => Stage::Profanity(
+                PROFANITY
+                    .iter()
+                    .map(|word| word.to_string())
+                    .chain(config.profanity_words.iter().map(|w| w.to_lowercase()))
+                    .collect(),
+            ),
+            "punctuation" | "auto-punctuation" => Stage::Punctuation,
+            "capitalize" | "capitalization" | "auto-capitalize" => Stage::Capitalize,
+            _ => return None,
+        };
+        Some(stage)
+    }
+
+    /// Stages for `[post_processing]`, in order
+    pub fn from_config(config: &PostProcessingConfig) -> Vec<Self> {
+        let names: Vec<&str> = if config.stages.is_empty() {
+            [
+                ("numbers", true),
+                ("fillers", config.remove_filler_words),
+                ("profanity", config.mask_profanity),
+                ("punctuation", config.auto_punctuation),
+                ("capitalize", config.auto_capitalize),
+            ]
+            .into_iter()
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect()
+        } else {
+            config.stages.iter().map(String::as_str).collect()
+        };
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let stage = Self::parse(name, config);
+                if stage.is_none() {
+                    warn!(
+                        "Ignoring unknown post-processing stage '{}' (expected numbers, fillers, profanity, punctuation, or capitalize)",
+                        name
+                    );
+                }
+                stage
+            })
+            .collect()
+    }
+
+    /// Apply the stage to a piece of text
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Stage::Numbers(locale) => itn::apply(text, locale),
+            Stage::Fillers(fillers) => remove_fillers(text, fillers),
+            Stage::Profanity(words) => mask_profanity(text, words),
+            Stage::Punctuation => punctuate(text),
+            Stage::Capitalize => capitalize(text),
+        }
+    }
+}
+
+/// A word without surrounding punctuation, lowercased
+fn bare_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn remove_fillers(text: &str, fillers: &[String]) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        if !fillers
+            .iter()
+            .any(|filler| filler.eq_ignore_ascii_case(&bare_word(word)))
+        {
+            kept.push(word.to_string());
+            continue;
+        }
+        // "…said um." keeps the sentence end on the previous word
+        if let Some(end) = word.chars().last().filter(|c| SENTENCE_END.contains(c))
+            && let Some(previous) = kept.last_mut()
+        {
+            let previous_trimmed = previous.trim_end_matches([',', ';', ':']).len();
+            previous.truncate(previous_trimmed);
+            if !previous.ends_with(SENTENCE_END) {
+                previous.push(end);
+            }
+        }
+    }
+    kept.join(" ")
+}
+
+fn mask_profanity(text: &str, words: &[String]) -> String {
+    let is_profane = |bare: &str| {
+        words.iter().any(|word| {
+            bare.strip_prefix(word.as_str())
+                .is_some_and(|rest| PROFANITY_SUFFIXES.contains(&rest))
+        })
+    };
+
+    text.split(' ')
+        .map(|token| {
+            if !is_profane(&bare_word(token)) {
+                return token.to_string();
+            }
+            let mut seen_letter = false;
+            token
+                .chars()
+                .map(|c| {
+                    if !c.is_alphanumeric() {
+                        c
+                    } else if seen_letter {
+                        '*'
+                    } else {
+                        seen_letter = true;
+                        c
+                    }
+                })
+                .collect()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn punctuate(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 1);
+    for c in text.trim().chars() {
+        // "hello , world" → "hello, world"
+        if matches!(c, ',' | '.' | '!' | '?' | ';' | ':') && out.ends_with(' ') {
+            out.pop();
+        }
+        out.push(c);
+    }
+    if out.chars().last().is_some_and(char::is_alphanumeric) {
+        out.push('.');
+    }
+    out
+}
+
+fn capitalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    for c in text.chars() {
+        if sentence_start && c.is_alphabetic() {
+            out.extend(c.to_uppercase());
+            sentence_start = false;
+            continue;
+        }
+        if SENTENCE_END.contains(&c) {
+            sentence_start = true;
+        } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '(' | '¿' | '¡') {
+            sentence_start = false;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(stages: &[Stage], text: &str) -> String {
+        stages
+            .iter()
+            .fold(text.to_string(), |text, stage| stage.apply(&text))
+    }
+
+    #[test]
+    fn test_stages_in_order() {
+        let mut config = PostProcessingConfig {
+            remove_filler_words: true,
+            mask_profanity: true,
+            locale: "en-US".to_string(),
+            ..Default::default()
+        };
+        let stages = Stage::from_config(&config);
+        assert_eq!(stages.len(), 5);
+        assert_eq!(
+            run(
+                &stages,
+                "um so this is shitty , uh. then we ship it um. is it done"
+            ),
+            "So this is s*****. Then we ship it. Is it done."
+        );
+
+        // Explicit order; capitalizing before the fillers go leaves "um"'s
+        // sentence lowercase
+        config.stages = vec![
+            "capitalize".to_string(),
+            "fillers".to_string(),
+            "magic".to_string(),
+        ];
+        let stages = Stage::from_config(&config);
+        assert_eq!(
+            stages,
+            vec![
+                Stage::Capitalize,
+                Stage::Fillers(config.filler_words.clone())
+            ]
+        );
+        assert_eq!(run(&stages, "um, hello there"), "hello there");
+
+        assert_eq!(
+            capitalize("¿qué tal? bien. \"yes\""),
+            "¿Qué tal? Bien. \"Yes\""
+        );
+        assert_eq!(
+            mask_profanity("Dickens wrote it", &["dick".to_string()]),
+            "Dickens wrote it"
+        );
+    }
+}
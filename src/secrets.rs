@@ -0,0 +1,250 @@
+//! Configuration Secrets
+//!
+//! Keeps sensitive config values (API keys, sync credentials) in the OS
+//! keychain instead of plaintext TOML. Config values of the form
+//! `keyring:<name>` are looked up at the point of use, so the config file
+//! only ever contains the reference. Manage entries with
+//! `onevox secret set/get/remove`.
+//!
+//! - macOS: login keychain (via `security`, with secrets passed on stdin)
+//! - Linux: Secret Service, e.g. GNOME Keyring or KWallet (via `secret-tool`)
+//! - Windows: not supported (no Credential Manager backend); `keyring:`
+//!   references fail to resolve there
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Prefix marking a config value as a keychain reference
+pub const KEYRING_PREFIX: &str = "keyring:";
+
+/// Keychain service the secrets are stored under
+const SERVICE: &str = "onevox";
+
+/// Name referenced by a `keyring:<name>` value, if it is one
+pub fn reference_name(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(KEYRING_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Resolve a config value, replacing a `keyring:<name>` reference with the
+/// stored secret (other values are returned unchanged)
+pub fn resolve(value: &str) -> crate::Result<String> {
+    let Some(name) = reference_name(value) else {
+        return Ok(value.to_string());
+    };
+    get(name)?.ok_or_else(|| {
+        crate::Error::Config(format!(
+            "Secret '{}' not found in the keychain. Run: onevox secret set {}",
+            name, name
+        ))
+    })
+}
+
+/// Store a secret, replacing any existing value
+pub fn set(name: &str, value: &str) -> crate::Result<()> {
+    validate_name(name)?;
+    set_platform(name, value)
+}
+
+/// Look up a secret (`None` when it does not exist)
+pub fn get(name: &str) -> crate::Result<Option<String>> {
+    validate_name(name)?;
+    get_platform(name)
+}
+
+/// Delete a secret (`false` when it did not exist)
+pub fn remove(name: &str) -> crate::Result<bool> {
+    validate_name(name)?;
+    remove_platform(name)
+}
+
+fn validate_name(name: &str) -> crate::Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(crate::Error::Config(format!(
+            "Invalid secret name '{}' (use letters, digits, '-', '_' or '.')",
+            name
+        )))
+    }
+}
+
+fn run_tool(program: &str, args: &[&str], stdin: Option<&str>) -> crate::Result<(bool, String)> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| crate::Error::Platform(format!("Failed to run {}: {}", program, e)))?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    Ok((output.status.success(), stdout))
+}
+
+/// Quote an argument for a `security -i` command line
+#[cfg(any(target_os = "macos", test))]
+fn quote_interactive(arg: &str) -> crate::Result<String> {
+    if arg.contains(['\n', '\r', '\0']) {
+        return Err(crate::Error::Config(
+            "Secrets can't contain line breaks".to_string(),
+        ));
+    }
+    Ok(format!(
+        "\"{}\"",
+        arg.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn set_platform(name: &str, value: &str) -> crate::Result<()> {
+    // The command goes in on stdin (interactive mode) so the secret never
+    // shows up in the process list; -U updates an existing item
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quote_interactive(SERVICE)?,
+        quote_interactive(name)?,
+        quote_interactive(value)?
+    );
+    let (ok, _) = run_tool("security", &["-i"], Some(&command))?;
+    // Interactive mode can exit cleanly after a failed command, so check
+    // that the item is there
+    if ok && get_platform(name)?.as_deref() == Some(value) {
+        Ok(())
+    } else {
+        Err(crate::Error::Platform(format!(
+            "Failed to store secret '{}' in the keychain",
+            name
+        )))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn get_platform(name: &str) -> crate::Result<Option<String>> {
+    let (ok, stdout) = run_tool(
+        "security",
+        &["find-generic-password", "-s", SERVICE, "-a", name, "-w"],
+        None,
+    )?;
+    Ok(ok.then_some(stdout))
+}
+
+#[cfg(target_os = "macos")]
+fn remove_platform(name: &str) -> crate::Result<bool> {
+    let (ok, _) = run_tool(
+        "security",
+        &["delete-generic-password", "-s", SERVICE, "-a", name],
+        None,
+    )?;
+    Ok(ok)
+}
+
+#[cfg(target_os = "linux")]
+fn set_platform(name: &str, value: &str) -> crate::Result<()> {
+    // secret-tool reads the value from stdin, keeping it out of argv
+    let label = format!("onevox: {}", name);
+    let (ok, _) = run_tool(
+        "secret-tool",
+        &[
+            "store", "--label", &label, "service", SERVICE, "account", name,
+        ],
+        Some(value),
+    )
+    .map_err(|e| crate::Error::Platform(format!("{} (install libsecret-tools / libsecret)", e)))?;
+    if ok {
+        Ok(())
+    } else {
+        Err(crate::Error::Platform(format!(
+            "Failed to store secret '{}' (is a Secret Service keyring running?)",
+            name
+        )))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_platform(name: &str) -> crate::Result<Option<String>> {
+    let (ok, stdout) = run_tool(
+        "secret-tool",
+        &["lookup", "service", SERVICE, "account", name],
+        None,
+    )?;
+    Ok((ok && !stdout.is_empty()).then_some(stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn remove_platform(name: &str) -> crate::Result<bool> {
+    let existed = get_platform(name)?.is_some();
+    if existed {
+        run_tool(
+            "secret-tool",
+            &["clear", "service", SERVICE, "account", name],
+            None,
+        )?;
+    }
+    Ok(existed)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn unsupported() -> crate::Error {
+    crate::Error::Platform(
+        "Keychain secrets are only supported on macOS and Linux (Windows Credential Manager is not)"
+            .to_string(),
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn set_platform(_name: &str, _value: &str) -> crate::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn get_platform(_name: &str) -> crate::Result<Option<String>> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn remove_platform(_name: &str) -> crate::Result<bool> {
+    Err(unsupported())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references() {
+        assert_eq!(reference_name("keyring:remote-asr"), Some("remote-asr"));
+        assert_eq!(reference_name("keyring:"), None);
+        assert_eq!(reference_name("plain-value"), None);
+
+        // Plain values pass through without touching the keychain
+        assert_eq!(resolve("--threads").unwrap(), "--threads");
+
+        assert!(validate_name("sync.token_2").is_ok());
+        assert!(validate_name("has space").is_err());
+        assert!(get("").is_err());
+
+        assert_eq!(
+            quote_interactive(r#"sk "a\b" c"#).unwrap(),
+            r#""sk \"a\\b\" c""#
+        );
+        assert!(quote_interactive("two\nlines").is_err());
+    }
+}
@@ -0,0 +1,397 @@
+//! Storage Management
+//!
+//! Reports disk usage per category (`onevox storage status`), enforces the
+//! caps from `[storage]` by deleting least-recently-used files, and checks
+//! free space before large model downloads.
+
+use crate::config::StorageConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+const MB: u64 = 1024 * 1024;
+
+/// Kind of data onevox keeps on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageCategory {
+    /// Downloaded models (all versions)
+    Models,
+    /// Transcription history database
+    History,
+    /// History backup archives
+    Backups,
+    /// Retained audio recordings
+    Audio,
+    /// Log files
+    Logs,
+}
+
+impl StorageCategory {
+    /// All categories in display order
+    pub const ALL: [StorageCategory; 5] = [
+        StorageCategory::Models,
+        StorageCategory::History,
+        StorageCategory::Backups,
+        StorageCategory::Audio,
+        StorageCategory::Logs,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageCategory::Models => "models",
+            StorageCategory::History => "history",
+            StorageCategory::Backups => "backups",
+            StorageCategory::Audio => "audio",
+            StorageCategory::Logs => "logs",
+        }
+    }
+
+    /// Location of the category on disk
+    pub fn path(&self) -> crate::Result<PathBuf> {
+        use crate::platform::paths;
+        match self {
+            StorageCategory::Models => paths::models_dir(),
+            StorageCategory::History => paths::history_db_path(),
+            StorageCategory::Backups => paths::backups_dir(),
+            StorageCategory::Audio => paths::recordings_dir(),
+            StorageCategory::Logs => paths::log_dir(),
+        }
+    }
+}
+
+/// Disk usage of one category
+#[derive(Debug, Clone)]
+pub struct CategoryUsage {
+    /// Category
+    pub category: StorageCategory,
+    /// File or directory holding the category
+    pub path: PathBuf,
+    /// Total size in bytes
+    pub size_bytes: u64,
+    /// Number of files
+    pub files: usize,
+    /// Configured cap in bytes (`None` = unlimited)
+    pub cap_bytes: Option<u64>,
+}
+
+impl CategoryUsage {
+    /// Whether the category exceeds its cap
+    pub fn over_cap(&self) -> bool {
+        self.cap_bytes.is_some_and(|cap| self.size_bytes > cap)
+    }
+}
+
+/// Disk usage across all categories
+#[derive(Debug, Clone)]
+pub struct StorageReport {
+    /// Per-category usage
+    pub categories: Vec<CategoryUsage>,
+    /// Free space on the volume holding the data directory
+    pub free_bytes: Option<u64>,
+}
+
+impl StorageReport {
+    /// Total bytes used by onevox
+    pub fn total_bytes(&self) -> u64 {
+        self.categories.iter().map(|c| c.size_bytes).sum()
+    }
+}
+
+/// Result of cleaning up one category
+#[derive(Debug, Clone, Default)]
+pub struct CleanupResult {
+    /// Files or model versions removed
+    pub removed: usize,
+    /// Bytes freed
+    pub freed_bytes: u64,
+}
+
+/// Outcome of a free-space check before a download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceCheck {
+    /// Enough room left afterwards
+    Ok,
+    /// The download fits but leaves less than the warning threshold
+    Low { free_bytes: u64 },
+    /// The download does not fit
+    Insufficient { free_bytes: u64 },
+    /// Free space could not be determined
+    Unknown,
+}
+
+/// Reports and limits disk usage
+pub struct StorageManager {
+    config: StorageConfig,
+}
+
+impl StorageManager {
+    /// Create a storage manager from config
+    pub fn new(config: StorageConfig) -> Self {
+        Self { config }
+    }
+
+    /// Configured cap for a category in bytes (`None` = unlimited)
+    pub fn cap(&self, category: StorageCategory) -> Option<u64> {
+        let mb = match category {
+            StorageCategory::Models => self.config.max_models_mb,
+            StorageCategory::Audio => self.config.max_audio_mb,
+            StorageCategory::Logs => self.config.max_logs_mb,
+            StorageCategory::History | StorageCategory::Backups => 0,
+        };
+        (mb > 0).then_some(mb * MB)
+    }
+
+    /// Measure disk usage of every category
+    pub fn status(&self) -> crate::Result<StorageReport> {
+        let mut categories = Vec::new();
+        for category in StorageCategory::ALL {
+            let path = category.path()?;
+            let files = collect_files(&path);
+            categories.push(CategoryUsage {
+                category,
+                size_bytes: files.iter().map(|f| f.size).sum(),
+                files: files.len(),
+                cap_bytes: self.cap(category),
+                path,
+            });
+        }
+
+        Ok(StorageReport {
+            categories,
+            free_bytes: free_space(&crate::platform::paths::data_dir()?),
+        })
+    }
+
+    /// Enforce all configured caps
+    pub fn enforce_caps(&self) -> crate::Result<Vec<(StorageCategory, CleanupResult)>> {
+        let mut results = Vec::new();
+        for category in [
+            StorageCategory::Audio,
+            StorageCategory::Logs,
+            StorageCategory::Models,
+        ] {
+            let Some(cap) = self.cap(category) else {
+                continue;
+            };
+
+            let path = category.path()?;
+            let result = match category {
+                StorageCategory::Models => cleanup_model_versions(&path, cap)?,
+                _ => cleanup_lru(&path, cap)?,
+            };
+            if result.removed > 0 {
+                info!(
+                    "🧹 Storage cap for {}: removed {} items ({:.1} MB)",
+                    category.name(),
+                    result.removed,
+                    result.freed_bytes as f64 / MB as f64
+                );
+            }
+            results.push((category, result));
+        }
+        Ok(results)
+    }
+
+    /// Check whether `needed_bytes` fit on the volume holding `path`
+    pub fn check_space(&self, path: &Path, needed_bytes: u64) -> SpaceCheck {
+        let Some(free_bytes) = free_space(path) else {
+            return SpaceCheck::Unknown;
+        };
+
+        if free_bytes < needed_bytes {
+            SpaceCheck::Insufficient { free_bytes }
+        } else if free_bytes - needed_bytes < self.config.low_space_warning_mb * MB {
+            SpaceCheck::Low { free_bytes }
+        } else {
+            SpaceCheck::Ok
+        }
+    }
+}
+
+/// A file with its size and last use time
+struct TrackedFile {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// All files below `path` (or `path` itself if it is a file)
+fn collect_files(path: &Path) -> Vec<TrackedFile> {
+    let mut files = Vec::new();
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return files;
+    };
+
+    if metadata.is_file() {
+        // Access times are unreliable on noatime mounts, so take the later
+        // of access and modification time
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let accessed = metadata.accessed().unwrap_or(modified);
+        files.push(TrackedFile {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            last_used: accessed.max(modified),
+        });
+    } else if metadata.is_dir()
+        && let Ok(entries) = fs::read_dir(path)
+    {
+        for entry in entries.flatten() {
+            files.extend(collect_files(&entry.path()));
+        }
+    }
+
+    files
+}
+
+/// Delete least-recently-used files until `dir` is within `cap` bytes
+pub fn cleanup_lru(dir: &Path, cap: u64) -> crate::Result<CleanupResult> {
+    let mut files = collect_files(dir);
+    let mut total: u64 = files.iter().map(|f| f.size).sum();
+    let mut result = CleanupResult::default();
+
+    files.sort_by_key(|f| f.last_used);
+    for file in files {
+        if total <= cap {
+            break;
+        }
+        debug!("Removing {:?} to stay under storage cap", file.path);
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                total -= file.size;
+                result.removed += 1;
+                result.freed_bytes += file.size;
+            }
+            Err(e) => warn!("Failed to remove {:?}: {}", file.path, e),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Delete least-recently-used inactive model versions until the models
+/// directory is within `cap` bytes
+///
+/// The active version of every model is never removed.
+fn cleanup_model_versions(models_dir: &Path, cap: u64) -> crate::Result<CleanupResult> {
+    let mut total: u64 = collect_files(models_dir).iter().map(|f| f.size).sum();
+    let mut result = CleanupResult::default();
+    if total <= cap {
+        return Ok(result);
+    }
+
+    let mut inactive = Vec::new();
+    for model in fs::read_dir(models_dir)?.flatten() {
+        let root = model.path();
+        let active = crate::platform::paths::active_model_dir(&root);
+        let Ok(versions) = fs::read_dir(root.join("versions")) else {
+            continue;
+        };
+        for version in versions.flatten() {
+            let path = version.path();
+            if path == active || fs::canonicalize(&path).ok() == fs::canonicalize(&active).ok() {
+                continue;
+            }
+            let files = collect_files(&path);
+            let size: u64 = files.iter().map(|f| f.size).sum();
+            let last_used = files
+                .iter()
+                .map(|f| f.last_used)
+                .max()
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            inactive.push((last_used, size, path));
+        }
+    }
+
+    inactive.sort_by_key(|(last_used, _, _)| *last_used);
+    for (_, size, path) in inactive {
+        if total <= cap {
+            break;
+        }
+        info!("Removing inactive model version {:?}", path);
+        match fs::remove_dir_all(&path) {
+            Ok(()) => {
+                total -= size;
+                result.removed += 1;
+                result.freed_bytes += size;
+            }
+            Err(e) => warn!("Failed to remove {:?}: {}", path, e),
+        }
+    }
+
+    if total > cap {
+        warn!(
+            "Models use {:.1} MB, above the {:.1} MB cap, but only active versions remain",
+            total as f64 / MB as f64,
+            cap as f64 / MB as f64
+        );
+    }
+
+    Ok(result)
+}
+
+/// Free space on the volume holding `path`
+pub fn free_space(path: &Path) -> Option<u64> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+
+    // The disk with the longest mount point containing the path
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::FileTimes;
+    use std::time::Duration;
+
+    fn write_file(path: &Path, size: usize, age_secs: u64) {
+        fs::write(path, vec![0u8; size]).unwrap();
+        let time = SystemTime::now() - Duration::from_secs(age_secs);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(FileTimes::new().set_accessed(time).set_modified(time))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_lru_removes_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        write_file(&dir.path().join("old.wav"), 100, 300);
+        write_file(&dir.path().join("mid.wav"), 100, 200);
+        write_file(&dir.path().join("new.wav"), 100, 100);
+
+        let result = cleanup_lru(dir.path(), 150).unwrap();
+
+        assert_eq!(result.removed, 2);
+        assert_eq!(result.freed_bytes, 200);
+        assert!(dir.path().join("new.wav").exists());
+        assert!(!dir.path().join("old.wav").exists());
+    }
+
+    #[test]
+    fn test_cleanup_model_versions_keeps_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let versions = dir.path().join("ggml-base").join("versions");
+        for (label, age) in [("v1", 300), ("v2", 200), ("v3", 100)] {
+            fs::create_dir_all(versions.join(label)).unwrap();
+            write_file(&versions.join(label).join("model.bin"), 100, age);
+        }
+        fs::write(dir.path().join("ggml-base").join("CURRENT"), "v1").unwrap();
+
+        let result = cleanup_model_versions(dir.path(), 150).unwrap();
+
+        assert_eq!(result.removed, 2);
+        assert!(versions.join("v1").exists());
+        assert!(!versions.join("v2").exists());
+    }
+}
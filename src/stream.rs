@@ -0,0 +1,418 @@
+//! Streaming Transcription API
+//!
+//! For applications embedding onevox as a library: `transcribe_stream` runs
+//! the capture → VAD → model pipeline on an audio source and yields its
+//! progress as a `futures::Stream` of [`TranscriptEvent`]s (speech start and
+//! end, interim partials, final transcripts).
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use onevox::stream::{AudioSource, TranscriptEvent, transcribe_stream};
+//!
+//! # async fn run() -> onevox::Result<()> {
+//! let config = onevox::Config::load_default()?;
+//! let mut events = Box::pin(transcribe_stream(&config, AudioSource::microphone(&config))?);
+//! while let Some(event) = events.next().await {
+//!     if let TranscriptEvent::Final { text, .. } = event {
+//!         println!("{}", text);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The pipeline publishes to its own [`EventBus`], the same bus type the
+//! daemon uses, and the stream is a subscription to it. [`transcript_events`]
+//! turns any bus (e.g. an embedded daemon's) into the same stream.
+
+use crate::audio::{AudioChunk, AudioEngine, CaptureConfig, Denoiser};
+use crate::config::Config;
+use crate::daemon::events::EventBus;
+use crate::ipc::Event;
+use crate::models::{ModelRuntime, backend};
+use crate::vad::{SpeechSegment, VadProcessor};
+use futures::Stream;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+/// Events buffered for a slow stream consumer before it starts lagging
+const STREAM_CAPACITY: usize = 1024;
+
+/// Audio between interim transcripts of a segment in progress
+const PARTIAL_INTERVAL_MS: u64 = 1000;
+
+/// Chunks buffered between the microphone thread and the pipeline
+const CAPTURE_QUEUE: usize = 64;
+
+/// Audio to transcribe
+#[derive(Debug, Clone)]
+pub enum AudioSource {
+    /// Capture from a microphone until the stream is dropped
+    Microphone(CaptureConfig),
+    /// Read an audio file (WAV, MP3, FLAC, OGG; resampled to the model's rate)
+    File(PathBuf),
+    /// Mono samples already in memory
+    Samples { samples: Vec<f32>, sample_rate: u32 },
+}
+
+impl AudioSource {
+    /// Capture from the microphone (or system audio) configured in `[audio]`
+    pub fn microphone(config: &Config) -> Self {
+        AudioSource::Microphone(CaptureConfig {
+            source: config.audio.capture_source(),
+            device_name: config.audio.device.clone(),
+            sample_rate: config.audio.sample_rate,
+            chunk_duration_ms: config.audio.chunk_duration_ms,
+            agc: config.audio.agc.enabled.then(|| config.audio.agc.clone()),
+            ..CaptureConfig::default()
+        })
+    }
+}
+
+/// Progress of a transcription stream
+///
+/// Serializes as JSON tagged by `type`, e.g. `{"type":"final","text":…}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEvent {
+    /// VAD detected the start of speech
+    SpeechStarted,
+    /// A speech segment ended and is being transcribed
+    SpeechEnded { chunks: usize },
+    /// Interim transcript of the segment in progress
+    Partial { text: String },
+    /// Final transcript of a segment
+    Final { text: String, processing_ms: u64 },
+    /// A segment could not be transcribed
+    Error { message: String },
+}
+
+impl TranscriptEvent {
+    /// Map a daemon event to a transcript event (`None` for unrelated events)
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::SpeechStarted => Some(TranscriptEvent::SpeechStarted),
+            Event::SpeechDetected { chunks } => {
+                Some(TranscriptEvent::SpeechEnded { chunks: *chunks })
+            }
+            Event::PartialTranscription { text } => {
+                Some(TranscriptEvent::Partial { text: text.clone() })
+            }
+            Event::TranscriptionComplete {
+                text, duration_ms, ..
+            } => Some(TranscriptEvent::Final {
+                text: text.clone(),
+                processing_ms: *duration_ms,
+            }),
+            Event::TranscriptionFailed { message } => Some(TranscriptEvent::Error {
+                message: message.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Stream the transcript events published on `bus` from now on
+///
+/// Ends when every publisher of the bus has been dropped.
+pub fn transcript_events(bus: &EventBus) -> impl Stream<Item = TranscriptEvent> + Send + use<> {
+    futures::stream::unfold(bus.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if let Some(event) = TranscriptEvent::from_event(&event) {
+                        return Some((event, rx));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Transcript stream lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Transcribe `source` with the model configured in `[model]`
+///
+/// Loads the model, then runs the pipeline in the background. File and
+/// in-memory sources end the stream once all audio is transcribed; the
+/// microphone records until the stream is dropped. Must be called from
+/// within a Tokio runtime.
+pub fn transcribe_stream(
+    config: &Config,
+    source: AudioSource,
+) -> crate::Result<impl Stream<Item = TranscriptEvent> + Send + use<>> {
+    let mut model = backend::create_runtime(&config.model)?;
+    model.load(backend::runtime_config(&config.model))?;
+    model.set_vocabulary(&crate::vocabulary::Vocabulary::from_config(
+        &config.vocabulary,
+    ));
+    transcribe_stream_with_model(model, config, source)
+}
+
+/// Transcribe `source` with an already loaded model
+pub fn transcribe_stream_with_model(
+    model: Box<dyn ModelRuntime>,
+    config: &Config,
+    source: AudioSource,
+) -> crate::Result<impl Stream<Item = TranscriptEvent> + Send + use<>> {
+    let chunks = match source {
+        AudioSource::Microphone(capture) => capture_microphone(capture)?,
+        AudioSource::File(path) => {
+            let (samples, report) = crate::audio::convert::load_audio(&path, model.sample_rate())?;
+            debug!("Streaming {} ({})", path.display(), report);
+            buffered_chunks(samples, model.sample_rate(), config.audio.chunk_duration_ms)
+        }
+        AudioSource::Samples {
+            samples,
+            sample_rate,
+        } => buffered_chunks(samples, sample_rate, config.audio.chunk_duration_ms),
+    };
+
+    let vad_config = config.vad.enabled.then(|| config.vad.clone());
+    let denoiser = config
+        .audio
+        .denoise
+        .then(|| Denoiser::new(config.audio.sample_rate));
+
+    let bus = EventBus::new(STREAM_CAPACITY);
+    let stream = transcript_events(&bus);
+    tokio::spawn(async move {
+        // The Silero detector may need to download its model first
+        let vad = match vad_config {
+            Some(vad_config) => Some(VadProcessor::new(
+                vad_config.to_processor_config(),
+                crate::vad::create_detector(&vad_config).await,
+            )),
+            None => None,
+        };
+        run_pipeline(Arc::new(Mutex::new(model)), vad, denoiser, chunks, bus).await;
+    });
+    Ok(stream)
+}
+
+/// Split in-memory audio into capture-sized chunks
+fn buffered_chunks(
+    samples: Vec<f32>,
+    sample_rate: u32,
+    chunk_duration_ms: u32,
+) -> mpsc::Receiver<AudioChunk> {
+    let chunk_len = (sample_rate as usize * chunk_duration_ms as usize / 1000).max(1);
+    let chunks: Vec<AudioChunk> = samples
+        .chunks(chunk_len)
+        .map(|chunk| AudioChunk::new(chunk.to_vec(), sample_rate))
+        .collect();
+
+    let (tx, rx) = mpsc::channel(chunks.len().max(1));
+    for chunk in chunks {
+        let _ = tx.try_send(chunk);
+    }
+    rx
+}
+
+/// Record on a dedicated thread (the audio engine is not `Send`) until the
+/// pipeline stops reading
+fn capture_microphone(capture: CaptureConfig) -> crate::Result<mpsc::Receiver<AudioChunk>> {
+    let (tx, rx) = mpsc::channel(CAPTURE_QUEUE);
+    let (started_tx, started_rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let mut engine = AudioEngine::new();
+        let mut captured = match engine.start_capture(capture) {
+            Ok(captured) => {
+                let _ = started_tx.send(Ok(()));
+                captured
+            }
+            Err(e) => {
+                let _ = started_tx.send(Err(e));
+                return;
+            }
+        };
+        while let Some(chunk) = captured.blocking_recv() {
+            if tx.blocking_send(chunk).is_err() {
+                break;
+            }
+        }
+        if let Err(e) = engine.stop_capture() {
+            warn!("Failed to stop capture: {}", e);
+        }
+    });
+
+    started_rx
+        .recv()
+        .map_err(|_| crate::Error::Audio("Capture thread exited".to_string()))??;
+    Ok(rx)
+}
+
+/// Feed audio through VAD and the model, publishing progress to `bus`
+///
+/// Without VAD the whole input is one segment.
+async fn run_pipeline(
+    model: Arc<Mutex<Box<dyn ModelRuntime>>>,
+    mut vad: Option<VadProcessor>,
+    mut denoiser: Option<Denoiser>,
+    mut chunks: mpsc::Receiver<AudioChunk>,
+    bus: EventBus,
+) {
+    let mut collected: Vec<AudioChunk> = Vec::new();
+    let mut last_partial_len = 0;
+
+    while let Some(mut chunk) = chunks.recv().await {
+        // Nobody is listening any more (stream dropped)
+        if bus.subscriber_count() == 0 {
+            debug!("Transcript stream dropped, stopping pipeline");
+            return;
+        }
+        if let Some(denoiser) = denoiser.as_mut() {
+            denoiser.process_chunk(&mut chunk);
+        }
+        let sample_rate = chunk.sample_rate;
+
+        let Some(vad) = vad.as_mut() else {
+            if collected.is_empty() {
+                bus.publish(Event::SpeechStarted);
+            }
+            collected.push(chunk);
+            let pending: Vec<f32> = collected
+                .iter()
+                .flat_map(|chunk| chunk.samples.iter().copied())
+                .collect();
+            publish_partial(&model, &bus, pending, sample_rate, &mut last_partial_len).await;
+            continue;
+        };
+
+        let was_in_speech = vad.is_in_speech();
+        let result = vad.process(chunk);
+        if !was_in_speech && vad.is_in_speech() {
+            bus.publish(Event::SpeechStarted);
+        }
+
+        match result {
+            Ok(Some(segment)) => {
+                last_partial_len = 0;
+                transcribe_segment(&model, &bus, segment).await;
+            }
+            Ok(None) if vad.is_in_speech() => {
+                let pending = vad.pending_samples();
+                publish_partial(&model, &bus, pending, sample_rate, &mut last_partial_len).await;
+            }
+            Ok(None) => {}
+            Err(e) => warn!("VAD processing failed: {}", e),
+        }
+    }
+
+    // End of input: close the segment in progress
+    let last = match vad.as_mut() {
+        Some(vad) => vad.flush(),
+        None => (!collected.is_empty()).then(|| SpeechSegment::new(collected)),
+    };
+    if let Some(segment) = last {
+        transcribe_segment(&model, &bus, segment).await;
+    }
+}
+
+/// Transcribe a finished segment
+async fn transcribe_segment(
+    model: &Arc<Mutex<Box<dyn ModelRuntime>>>,
+    bus: &EventBus,
+    mut segment: SpeechSegment,
+) {
+    bus.publish(Event::SpeechDetected {
+        chunks: segment.len(),
+    });
+    let model = Arc::clone(model);
+    let result = tokio::task::spawn_blocking(move || {
+        let mut guard = model
+            .lock()
+            .map_err(|_| "Model mutex poisoned".to_string())?;
+        guard
+            .transcribe_segment(&mut segment)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("Transcription task failed: {}", e)));
+
+    bus.publish(match result {
+        Ok(transcript) => Event::TranscriptionComplete {
+            text: transcript.text,
+            duration_ms: transcript.processing_time_ms,
+            confidence: transcript.confidence,
+        },
+        Err(message) => Event::TranscriptionFailed { message },
+    });
+}
+
+/// Transcribe the segment in progress once enough new audio has arrived
+async fn publish_partial(
+    model: &Arc<Mutex<Box<dyn ModelRuntime>>>,
+    bus: &EventBus,
+    pending: Vec<f32>,
+    sample_rate: u32,
+    last_len: &mut usize,
+) {
+    let interval = (sample_rate as u64 * PARTIAL_INTERVAL_MS / 1000) as usize;
+    if pending.len() < *last_len + interval {
+        return;
+    }
+    *last_len = pending.len();
+
+    let model = Arc::clone(model);
+    let result = tokio::task::spawn_blocking(move || {
+        model
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.transcribe(&pending, sample_rate).ok())
+    })
+    .await;
+    if let Ok(Some(transcript)) = result
+        && !transcript.text.trim().is_empty()
+    {
+        bus.publish(Event::PartialTranscription {
+            text: transcript.text,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::mock::MockModel;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_stream_yields_partials_and_final() {
+        let mut config = Config::default();
+        config.vad.enabled = false;
+        let samples = vec![0.1; 16000 * 2];
+        let mut model = MockModel::new();
+        model.load(backend::runtime_config(&config.model)).unwrap();
+
+        let events: Vec<TranscriptEvent> = transcribe_stream_with_model(
+            Box::new(model),
+            &config,
+            AudioSource::Samples {
+                samples,
+                sample_rate: 16000,
+            },
+        )
+        .unwrap()
+        .collect()
+        .await;
+
+        assert_eq!(events.first(), Some(&TranscriptEvent::SpeechStarted));
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, TranscriptEvent::Partial { .. }))
+        );
+        assert!(matches!(
+            events.iter().rev().nth(1),
+            Some(TranscriptEvent::SpeechEnded { chunks: 10 })
+        ));
+        assert!(matches!(events.last(), Some(TranscriptEvent::Final { .. })));
+    }
+}
@@ -0,0 +1,370 @@
+//! Local Telemetry
+//!
+//! Opt-in usage analytics that never leave the machine. Daily aggregates of
+//! sessions, transcriptions, latency, and failures are kept in
+//! `telemetry.json` in the data directory; no transcribed text is stored.
+//! `onevox dashboard` reads the store and shows weekly trends so users can
+//! tell whether a config or model change helped.
+
+use crate::ipc::protocol::Event;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Upper bounds (ms) of the latency histogram buckets; the last bucket is
+/// open-ended
+pub const LATENCY_BUCKETS_MS: [u64; 6] = [100, 250, 500, 1000, 2000, 5000];
+
+/// Aggregated metrics for one day (or a merged range of days)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyStats {
+    /// Dictation sessions started
+    pub sessions: u64,
+    /// Successful transcriptions
+    pub transcriptions: u64,
+    /// Words transcribed
+    pub words: u64,
+    /// Transcriptions that failed
+    pub transcription_failures: u64,
+    /// Injections that failed
+    pub injection_failures: u64,
+    /// Other daemon errors
+    pub errors: u64,
+    /// Sum of transcription latencies
+    pub latency_total_ms: u64,
+    /// Slowest transcription
+    pub latency_max_ms: u64,
+    /// Latency histogram (see `LATENCY_BUCKETS_MS`)
+    pub latency_buckets: Vec<u64>,
+    /// Transcriptions per model
+    pub models: BTreeMap<String, u64>,
+}
+
+impl DailyStats {
+    /// Mean transcription latency
+    pub fn avg_latency_ms(&self) -> Option<u64> {
+        (self.transcriptions > 0).then(|| self.latency_total_ms / self.transcriptions)
+    }
+
+    /// Approximate median latency (upper bound of the median bucket)
+    pub fn median_latency_ms(&self) -> Option<u64> {
+        let total: u64 = self.latency_buckets.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut seen = 0;
+        for (i, count) in self.latency_buckets.iter().enumerate() {
+            seen += count;
+            if seen * 2 >= total {
+                return Some(
+                    LATENCY_BUCKETS_MS
+                        .get(i)
+                        .copied()
+                        .unwrap_or(self.latency_max_ms),
+                );
+            }
+        }
+        None
+    }
+
+    /// Share of attempts (transcriptions + failures) that failed
+    pub fn error_rate(&self) -> f64 {
+        let failures = self.transcription_failures + self.injection_failures;
+        let attempts = self.transcriptions + self.transcription_failures;
+        if attempts == 0 {
+            0.0
+        } else {
+            failures as f64 / attempts as f64
+        }
+    }
+
+    /// Add another day's stats to this one
+    pub fn merge(&mut self, other: &DailyStats) {
+        self.sessions += other.sessions;
+        self.transcriptions += other.transcriptions;
+        self.words += other.words;
+        self.transcription_failures += other.transcription_failures;
+        self.injection_failures += other.injection_failures;
+        self.errors += other.errors;
+        self.latency_total_ms += other.latency_total_ms;
+        self.latency_max_ms = self.latency_max_ms.max(other.latency_max_ms);
+        if self.latency_buckets.len() < other.latency_buckets.len() {
+            self.latency_buckets.resize(other.latency_buckets.len(), 0);
+        }
+        for (total, count) in self.latency_buckets.iter_mut().zip(&other.latency_buckets) {
+            *total += count;
+        }
+        for (model, count) in &other.models {
+            *self.models.entry(model.clone()).or_insert(0) += count;
+        }
+    }
+
+    fn record_latency(&mut self, latency_ms: u64) {
+        self.latency_total_ms += latency_ms;
+        self.latency_max_ms = self.latency_max_ms.max(latency_ms);
+
+        self.latency_buckets.resize(LATENCY_BUCKETS_MS.len() + 1, 0);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| latency_ms <= bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.latency_buckets[bucket] += 1;
+    }
+}
+
+/// Stats for one week (Monday to Sunday)
+#[derive(Debug, Clone)]
+pub struct WeekSummary {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    /// Merged stats of the week's days
+    pub stats: DailyStats,
+}
+
+/// On-disk telemetry store
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TelemetryStore {
+    /// Daily aggregates keyed by date (YYYY-MM-DD)
+    #[serde(default)]
+    pub days: BTreeMap<String, DailyStats>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    current_model: Option<String>,
+}
+
+impl TelemetryStore {
+    /// Default store location
+    pub fn default_path() -> crate::Result<PathBuf> {
+        Ok(crate::platform::paths::data_dir()?.join("telemetry.json"))
+    }
+
+    /// Load the store (an empty store if the file does not exist yet)
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let mut store = if path.exists() {
+            let json = std::fs::read_to_string(path)?;
+            serde_json::from_str(&json).map_err(|e| {
+                crate::Error::Other(format!("Failed to parse telemetry store: {}", e))
+            })?
+        } else {
+            Self::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    /// Write the store to disk
+    pub fn save(&self) -> crate::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::Error::Other(format!("Failed to serialize telemetry: {}", e)))?;
+
+        // Write then rename so a crash never leaves a truncated store
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Fold an event into today's stats
+    ///
+    /// Returns whether the stats changed (and the store should be saved).
+    pub fn record(&mut self, event: &Event) -> bool {
+        self.record_on(Local::now().date_naive(), event)
+    }
+
+    fn record_on(&mut self, date: NaiveDate, event: &Event) -> bool {
+        if let Event::ModelLoaded { name } = event {
+            self.current_model = Some(name.clone());
+            return false;
+        }
+
+        let tracked = matches!(
+            event,
+            Event::DictationStarted
+                | Event::TranscriptionComplete { .. }
+                | Event::TranscriptionFailed { .. }
+                | Event::InjectionFailed { .. }
+                | Event::Error { .. }
+        );
+        if !tracked {
+            return false;
+        }
+
+        let day = self.days.entry(date_key(date)).or_default();
+        match event {
+            Event::DictationStarted => day.sessions += 1,
+            Event::TranscriptionComplete {
+                text, duration_ms, ..
+            } => {
+                day.transcriptions += 1;
+                day.words += text.split_whitespace().count() as u64;
+                day.record_latency(*duration_ms);
+                let model = self.current_model.as_deref().unwrap_or("unknown");
+                *day.models.entry(model.to_string()).or_insert(0) += 1;
+            }
+            Event::TranscriptionFailed { .. } => day.transcription_failures += 1,
+            Event::InjectionFailed { .. } => day.injection_failures += 1,
+            Event::Error { .. } => day.errors += 1,
+            _ => {}
+        }
+        true
+    }
+
+    /// Drop days older than `retention_days`
+    pub fn prune(&mut self, retention_days: u32) {
+        let cutoff = date_key(Local::now().date_naive() - Duration::days(retention_days as i64));
+        self.days.retain(|date, _| *date >= cutoff);
+    }
+
+    /// Per-week summaries for the last `weeks` weeks, oldest first
+    ///
+    /// Weeks without any activity are included with empty stats so trends
+    /// line up with the calendar.
+    pub fn weekly(&self, weeks: usize) -> Vec<WeekSummary> {
+        self.weekly_until(Local::now().date_naive(), weeks)
+    }
+
+    fn weekly_until(&self, today: NaiveDate, weeks: usize) -> Vec<WeekSummary> {
+        let this_week = week_start(today);
+        let mut summaries: Vec<WeekSummary> = (0..weeks)
+            .rev()
+            .map(|i| WeekSummary {
+                week_start: this_week - Duration::weeks(i as i64),
+                stats: DailyStats::default(),
+            })
+            .collect();
+
+        for (date, stats) in &self.days {
+            let Ok(date) = NaiveDate::parse_from_str(date, DATE_FORMAT) else {
+                continue;
+            };
+            let start = week_start(date);
+            if let Some(summary) = summaries.iter_mut().find(|s| s.week_start == start) {
+                summary.stats.merge(stats);
+            }
+        }
+        summaries
+    }
+
+    /// Stats merged across all stored days
+    pub fn totals(&self) -> DailyStats {
+        let mut totals = DailyStats::default();
+        for stats in self.days.values() {
+            totals.merge(stats);
+        }
+        totals
+    }
+}
+
+/// Key format of `TelemetryStore::days`
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn date_key(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Monday of the week containing `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Record daemon events into the telemetry store
+///
+/// Used by the event bus consumer; failures are logged and never
+/// interrupt dictation.
+pub struct TelemetryRecorder {
+    store: TelemetryStore,
+}
+
+impl TelemetryRecorder {
+    /// Open the store at the default location and prune old days
+    pub fn open(retention_days: u32) -> crate::Result<Self> {
+        let mut store = TelemetryStore::load(&TelemetryStore::default_path()?)?;
+        store.prune(retention_days);
+        Ok(Self { store })
+    }
+
+    /// Record an event, saving the store when it changed
+    pub fn handle(&mut self, event: &Event) {
+        if self.store.record(event) {
+            if let Err(e) = self.store.save() {
+                warn!("Failed to save telemetry: {}", e);
+            } else {
+                debug!("Telemetry updated ({})", event.name());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_weekly_trends() {
+        let mut store = TelemetryStore::default();
+        // Wednesday and Friday of the same week, then the following Monday
+        let wed = NaiveDate::from_ymd_opt(2026, 10, 7).unwrap();
+        let fri = NaiveDate::from_ymd_opt(2026, 10, 9).unwrap();
+        let mon = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+
+        store.record_on(
+            wed,
+            &Event::ModelLoaded {
+                name: "ggml-base.en".into(),
+            },
+        );
+        store.record_on(wed, &Event::DictationStarted);
+        store.record_on(
+            wed,
+            &Event::TranscriptionComplete {
+                text: "hello there world".into(),
+                duration_ms: 300,
+                confidence: None,
+            },
+        );
+        store.record_on(
+            fri,
+            &Event::TranscriptionComplete {
+                text: "again".into(),
+                duration_ms: 900,
+                confidence: None,
+            },
+        );
+        store.record_on(
+            fri,
+            &Event::TranscriptionFailed {
+                message: "boom".into(),
+            },
+        );
+        store.record_on(
+            mon,
+            &Event::TranscriptionComplete {
+                text: "next week".into(),
+                duration_ms: 120,
+                confidence: None,
+            },
+        );
+
+        let weeks = store.weekly_until(mon, 3);
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[2].week_start, mon);
+        assert_eq!(weeks[0].stats, DailyStats::default());
+
+        let previous = &weeks[1].stats;
+        assert_eq!(previous.sessions, 1);
+        assert_eq!(previous.transcriptions, 2);
+        assert_eq!(previous.words, 4);
+        assert_eq!(previous.avg_latency_ms(), Some(600));
+        assert_eq!(previous.median_latency_ms(), Some(500));
+        assert_eq!(previous.models["ggml-base.en"], 2);
+        assert!((previous.error_rate() - 1.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(weeks[2].stats.transcriptions, 1);
+        assert_eq!(store.totals().transcriptions, 3);
+    }
+}
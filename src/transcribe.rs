@@ -0,0 +1,247 @@
+//! File Transcription
+//!
+//! Backs `onevox transcribe <file>`: runs the configured model over audio
+//! decoded from a file, optionally split at pauses by the VAD so long
+//! recordings come back as timed pieces, and renders the transcript as
+//! plain text, SRT or WebVTT subtitles, or JSON.
+
+use crate::audio::AudioChunk;
+use crate::models::{ModelRuntime, TranscriptSegment, Transcription};
+use crate::vad::{SpeechSegment, VadProcessor};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Transcript output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl OutputFormat {
+    /// Parse a format name ("text", "srt", "vtt", "json")
+    pub fn parse(format: &str) -> crate::Result<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "srt" => Ok(OutputFormat::Srt),
+            "vtt" | "webvtt" => Ok(OutputFormat::Vtt),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(crate::Error::Config(format!(
+                "Unknown output format '{}' (expected text, srt, vtt, or json)",
+                other
+            ))),
+        }
+    }
+
+    /// Format implied by an output file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::parse(path.extension()?.to_str()?).ok()
+    }
+
+    /// File extension for transcripts in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Srt => "srt",
+            OutputFormat::Vtt => "vtt",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+/// Transcribe mono `samples` at the model's sample rate
+///
+/// With `vad`, the audio is fed through it in `chunk_duration_ms` chunks
+/// and each speech segment is transcribed on its own; otherwise the whole
+/// file goes to the model at once. Segment offsets are relative to the
+/// start of the file.
+pub fn transcribe_samples(
+    model: &mut dyn ModelRuntime,
+    samples: &[f32],
+    vad: Option<&mut VadProcessor>,
+    chunk_duration_ms: u32,
+) -> crate::Result<Transcription> {
+    let sample_rate = model.sample_rate();
+    let pieces = match vad {
+        Some(vad) => speech_pieces(vad, samples, sample_rate, chunk_duration_ms)?,
+        None => vec![(0, samples.to_vec())],
+    };
+
+    let mut result = Transcription::new(String::new());
+    let mut texts = Vec::new();
+    for (offset_ms, piece) in pieces {
+        let transcription = model.transcribe(&piece, sample_rate)?;
+        result.processing_time_ms += transcription.processing_time_ms;
+        result.language = result.language.or(transcription.language);
+
+        let text = transcription.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        texts.push(text.to_string());
+        result.words.extend(
+            transcription
+                .words
+                .iter()
+                .map(|word| word.shifted(offset_ms)),
+        );
+        if transcription.segments.is_empty() {
+            result.segments.push(TranscriptSegment {
+                text: text.to_string(),
+                start_ms: offset_ms,
+                end_ms: offset_ms + piece.len() as u64 * 1000 / sample_rate.max(1) as u64,
+            });
+        } else {
+            result.segments.extend(
+                transcription
+                    .segments
+                    .iter()
+                    .map(|segment| segment.shifted(offset_ms)),
+            );
+        }
+    }
+    result.text = texts.join(" ");
+    Ok(result)
+}
+
+/// Split audio into VAD speech segments with their offsets in milliseconds
+fn speech_pieces(
+    vad: &mut VadProcessor,
+    samples: &[f32],
+    sample_rate: u32,
+    chunk_duration_ms: u32,
+) -> crate::Result<Vec<(u64, Vec<f32>)>> {
+    // Chunks are stamped with their file position so segment start times
+    // can be turned back into offsets
+    let origin = Instant::now();
+    let chunk_len = (sample_rate as usize * chunk_duration_ms as usize / 1000).max(1);
+    let mut pieces = Vec::new();
+    let mut take = |segment: Option<SpeechSegment>| {
+        if let Some(mut segment) = segment {
+            let offset = segment.start_time.duration_since(origin).as_millis() as u64;
+            pieces.push((offset, segment.get_samples().to_vec()));
+        }
+    };
+
+    for (i, chunk) in samples.chunks(chunk_len).enumerate() {
+        let mut chunk = AudioChunk::new(chunk.to_vec(), sample_rate);
+        chunk.timestamp = origin
+            + Duration::from_millis((i * chunk_len) as u64 * 1000 / sample_rate.max(1) as u64);
+        take(vad.process(chunk)?);
+    }
+    take(vad.flush());
+    Ok(pieces)
+}
+
+/// Render a transcript of `duration_ms` of audio
+pub fn render(transcription: &Transcription, format: OutputFormat, duration_ms: u64) -> String {
+    let segments = &transcription.segments;
+    match format {
+        OutputFormat::Text => format!("{}\n", transcription.text),
+        OutputFormat::Srt => segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                format!(
+                    "{}\n{} --> {}\n{}\n\n",
+                    i + 1,
+                    timestamp(segment.start_ms, ','),
+                    timestamp(segment.end_ms, ','),
+                    segment.text
+                )
+            })
+            .collect(),
+        OutputFormat::Vtt => {
+            let cues: String = segments
+                .iter()
+                .map(|segment| {
+                    format!(
+                        "{} --> {}\n{}\n\n",
+                        timestamp(segment.start_ms, '.'),
+                        timestamp(segment.end_ms, '.'),
+                        segment.text
+                    )
+                })
+                .collect();
+            format!("WEBVTT\n\n{}", cues)
+        }
+        OutputFormat::Json => {
+            let segments: Vec<serde_json::Value> = segments
+                .iter()
+                .map(|segment| {
+                    serde_json::json!({
+                        "start_ms": segment.start_ms,
+                        "end_ms": segment.end_ms,
+                        "text": segment.text,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "text": transcription.text,
+                "language": transcription.language,
+                "duration_ms": duration_ms,
+                "processing_ms": transcription.processing_time_ms,
+                "segments": segments,
+            });
+            format!(
+                "{}\n",
+                serde_json::to_string_pretty(&json).unwrap_or_default()
+            )
+        }
+    }
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT)
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_formats() {
+        let mut transcription = Transcription::new("Hello there. How are you?".to_string());
+        transcription.segments = vec![
+            TranscriptSegment {
+                text: "Hello there.".to_string(),
+                start_ms: 500,
+                end_ms: 1750,
+            },
+            TranscriptSegment {
+                text: "How are you?".to_string(),
+                start_ms: 3_723_004,
+                end_ms: 3_725_000,
+            },
+        ];
+
+        assert_eq!(
+            render(&transcription, OutputFormat::Srt, 3_725_000),
+            "1\n00:00:00,500 --> 00:00:01,750\nHello there.\n\n\
+             2\n01:02:03,004 --> 01:02:05,000\nHow are you?\n\n"
+        );
+        assert!(
+            render(&transcription, OutputFormat::Vtt, 3_725_000)
+                .starts_with("WEBVTT\n\n00:00:00.500 --> 00:00:01.750\nHello there.\n")
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&transcription, OutputFormat::Json, 3_725_000)).unwrap();
+        assert_eq!(json["segments"][1]["start_ms"], 3_723_004);
+
+        assert_eq!(
+            OutputFormat::from_path(Path::new("demo.SRT")),
+            Some(OutputFormat::Srt)
+        );
+        assert!(OutputFormat::parse("docx").is_err());
+    }
+}
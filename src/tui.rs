@@ -1,6 +1,13 @@
 //! Terminal User Interface
 //!
-//! Launches the OpenTUI-based TypeScript TUI for interactive configuration and monitoring.
+//! Launches the OpenTUI-based TypeScript TUI for interactive configuration and monitoring,
+//! and hosts the built-in live monitor (`onevox monitor`) and telemetry dashboard
+//! (`onevox dashboard`), with the `tui` feature.
+
+#[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "tui")]
+pub mod monitor;
 
 use crate::Result;
 use std::path::{Path, PathBuf};
@@ -35,7 +42,11 @@ pub fn launch() -> Result<()> {
     // Launch the TUI
     eprintln!("🖥️  Launching ONEVOX TUI...\n");
 
-    let status = Command::new("bun")
+    let mut command = Command::new("bun");
+    if let Some(dir) = crate::platform::paths::portable_dir() {
+        command.env(crate::platform::paths::PORTABLE_DIR_ENV, dir);
+    }
+    let status = command
         .arg("run")
         .arg("src/index.ts")
         .current_dir(&tui_dir)
@@ -55,6 +66,30 @@ pub fn launch() -> Result<()> {
     Ok(())
 }
 
+/// Run the live daemon monitor in this terminal
+#[cfg(feature = "tui")]
+pub async fn run_monitor() -> Result<()> {
+    monitor::run().await
+}
+
+#[cfg(not(feature = "tui"))]
+pub async fn run_monitor() -> Result<()> {
+    Err(crate::Error::Other(
+        "The live monitor needs a build with the tui feature (cargo build --features tui)"
+            .to_string(),
+    ))
+}
+
+/// Show the telemetry dashboard in this terminal
+#[cfg(feature = "tui")]
+pub fn run_dashboard(
+    weeks: Vec<crate::telemetry::WeekSummary>,
+    totals: crate::telemetry::DailyStats,
+    recording: bool,
+) -> Result<()> {
+    dashboard::run(weeks, totals, recording)
+}
+
 /// Find the TUI directory by walking up from the binary location
 fn find_tui_directory() -> Result<PathBuf> {
     // Get the actual binary path (resolve symlinks)
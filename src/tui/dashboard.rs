@@ -0,0 +1,282 @@
+//! Telemetry Dashboard
+//!
+//! `onevox dashboard` in a terminal: a table of weekly sessions, words,
+//! latency, and error rate from the local telemetry store, bar charts of
+//! the same trends, and the latency histogram and model usage of the
+//! selected week. The store is read once; nothing talks to the daemon.
+
+use crate::telemetry::{DailyStats, LATENCY_BUCKETS_MS, WeekSummary};
+use crossterm::event::{self as term, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{BarChart, Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Dashboard state
+struct Dashboard {
+    weeks: Vec<WeekSummary>,
+    totals: DailyStats,
+    /// Whether telemetry is still being recorded
+    recording: bool,
+    table: TableState,
+}
+
+impl Dashboard {
+    fn new(weeks: Vec<WeekSummary>, totals: DailyStats, recording: bool) -> Self {
+        // Start on the latest week
+        let selected = weeks.len().checked_sub(1);
+        Self {
+            weeks,
+            totals,
+            recording,
+            table: TableState::default().with_selected(selected),
+        }
+    }
+
+    /// Handle a key press, returning false to quit
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.table.select_next(),
+            KeyCode::Home | KeyCode::Char('g') => self.table.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.table.select_last(),
+            _ => {}
+        }
+        true
+    }
+
+    fn selected(&self) -> Option<&WeekSummary> {
+        self.table
+            .selected()
+            .and_then(|i| self.weeks.get(i.min(self.weeks.len().saturating_sub(1))))
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [header, table, trends, week, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(self.weeks.len() as u16 + 3),
+            Constraint::Min(8),
+            Constraint::Length(9),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let mut summary = format!(
+            "{} sessions  │  {} transcripts  │  {} words",
+            self.totals.sessions, self.totals.transcriptions, self.totals.words
+        );
+        if let Some(avg) = self.totals.avg_latency_ms() {
+            summary.push_str(&format!("  │  avg latency {} ms", avg));
+        }
+        let header_line = if self.recording {
+            Line::raw(summary)
+        } else {
+            Line::from(vec![
+                summary.into(),
+                "  │  telemetry disabled".fg(Color::Yellow),
+            ])
+        };
+        frame.render_widget(
+            Paragraph::new(header_line).block(Block::bordered().title(" Onevox dashboard ")),
+            header,
+        );
+
+        let rows = self.weeks.iter().map(|week| {
+            let stats = &week.stats;
+            Row::new(vec![
+                week.week_start.format("%Y-%m-%d").to_string(),
+                stats.sessions.to_string(),
+                stats.transcriptions.to_string(),
+                stats.words.to_string(),
+                format_ms(stats.avg_latency_ms()),
+                stats
+                    .median_latency_ms()
+                    .map(|ms| format!("≤{} ms", ms))
+                    .unwrap_or_else(|| "-".to_string()),
+                format!("{:.1}%", stats.error_rate() * 100.0),
+            ])
+        });
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(11),
+            Constraint::Length(7),
+            Constraint::Length(11),
+            Constraint::Length(9),
+            Constraint::Length(6),
+        ];
+        frame.render_stateful_widget(
+            Table::new(rows, widths)
+                .header(
+                    Row::new([
+                        "Week of",
+                        "Sessions",
+                        "Transcripts",
+                        "Words",
+                        "Avg latency",
+                        "Median",
+                        "Errors",
+                    ])
+                    .bold(),
+                )
+                .block(Block::bordered().title(" Weeks "))
+                .row_highlight_style(Style::default().reversed()),
+            table,
+            &mut self.table,
+        );
+
+        // Trends, oldest to newest
+        let labels: Vec<String> = self
+            .weeks
+            .iter()
+            .map(|w| w.week_start.format("%m-%d").to_string())
+            .collect();
+        let series = |value: &dyn Fn(&DailyStats) -> u64| -> Vec<(&str, u64)> {
+            labels
+                .iter()
+                .zip(&self.weeks)
+                .map(|(label, week)| (label.as_str(), value(&week.stats)))
+                .collect()
+        };
+        let latency = series(&|s| s.avg_latency_ms().unwrap_or(0));
+        let words = series(&|s| s.words);
+        // Tenths of a percent, so small rates still show a bar
+        let errors = series(&|s| (s.error_rate() * 1000.0).round() as u64);
+
+        let [latency_area, words_area, errors_area] =
+            Layout::horizontal([Constraint::Ratio(1, 3); 3]).areas(trends);
+        frame.render_widget(
+            trend(" Avg latency (ms) ", &latency, Color::Cyan),
+            latency_area,
+        );
+        frame.render_widget(trend(" Words ", &words, Color::Green), words_area);
+        frame.render_widget(trend(" Error rate (‰) ", &errors, Color::Red), errors_area);
+
+        if let Some(selected) = self.selected() {
+            let title = format!(
+                " Week of {} latency (ms) ",
+                selected.week_start.format("%Y-%m-%d")
+            );
+            let [histogram, models] =
+                Layout::horizontal([Constraint::Min(30), Constraint::Length(32)]).areas(week);
+            let buckets = bucket_labels();
+            let counts: Vec<(&str, u64)> = buckets
+                .iter()
+                .enumerate()
+                .map(|(i, label)| {
+                    let count = selected.stats.latency_buckets.get(i).copied();
+                    (label.as_str(), count.unwrap_or(0))
+                })
+                .collect();
+            frame.render_widget(trend(&title, &counts, Color::Magenta), histogram);
+
+            // Most used first
+            let mut models_used: Vec<(&String, &u64)> = selected.stats.models.iter().collect();
+            models_used.sort_by(|a, b| b.1.cmp(a.1));
+            let mut lines: Vec<Line> = models_used
+                .into_iter()
+                .map(|(model, count)| Line::raw(format!("{} ({})", model, count)))
+                .collect();
+            if lines.is_empty() {
+                lines.push(Line::styled(
+                    "No transcriptions",
+                    Style::default().dark_gray(),
+                ));
+            }
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::bordered().title(" Models ")),
+                models,
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(Line::styled(
+                "↑/↓ select week · q quit",
+                Style::default().fg(Color::DarkGray),
+            )),
+            footer,
+        );
+    }
+}
+
+/// Bar chart of one value per label
+fn trend<'a>(title: &str, data: &'a [(&'a str, u64)], color: Color) -> BarChart<'a> {
+    BarChart::default()
+        .block(Block::bordered().title(title.to_string()))
+        .data(data)
+        .bar_width(5)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(color))
+        .value_style(Style::default().fg(Color::Black).bg(color))
+}
+
+/// Labels of the latency histogram buckets
+fn bucket_labels() -> Vec<String> {
+    LATENCY_BUCKETS_MS
+        .iter()
+        .map(|ms| format!("≤{}", ms))
+        .chain(std::iter::once(format!(
+            ">{}",
+            LATENCY_BUCKETS_MS[LATENCY_BUCKETS_MS.len() - 1]
+        )))
+        .collect()
+}
+
+fn format_ms(ms: Option<u64>) -> String {
+    ms.map(|ms| format!("{} ms", ms))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Show the dashboard until the user quits
+pub fn run(weeks: Vec<WeekSummary>, totals: DailyStats, recording: bool) -> crate::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, Dashboard::new(weeks, totals, recording));
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut dashboard: Dashboard) -> crate::Result<()> {
+    loop {
+        terminal.draw(|frame| dashboard.render(frame))?;
+        if let term::Event::Key(key) = term::read()?
+            && key.kind == KeyEventKind::Press
+            && !dashboard.handle_key(key.code)
+        {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_dashboard_selects_weeks() {
+        let weeks: Vec<WeekSummary> = (0..3)
+            .map(|i| WeekSummary {
+                week_start: NaiveDate::from_ymd_opt(2026, 1, 5 + 7 * i).unwrap(),
+                stats: DailyStats {
+                    words: 10 * i as u64,
+                    ..DailyStats::default()
+                },
+            })
+            .collect();
+        let mut dashboard = Dashboard::new(weeks, DailyStats::default(), true);
+        assert_eq!(dashboard.selected().unwrap().stats.words, 20);
+
+        assert!(dashboard.handle_key(KeyCode::Up));
+        assert_eq!(dashboard.selected().unwrap().stats.words, 10);
+        assert!(dashboard.handle_key(KeyCode::Char('g')));
+        assert_eq!(dashboard.selected().unwrap().stats.words, 0);
+        assert!(!dashboard.handle_key(KeyCode::Char('q')));
+
+        let labels = bucket_labels();
+        assert_eq!(labels.len(), LATENCY_BUCKETS_MS.len() + 1);
+        assert_eq!(labels.last().unwrap(), ">5000");
+        assert_eq!(format_ms(None), "-");
+    }
+}
@@ -0,0 +1,590 @@
+//! Live Daemon Monitor
+//!
+//! `onevox monitor` draws a dashboard of the running daemon in the
+//! terminal: its state and model, the input level with a rolling
+//! waveform, whether the VAD hears speech, and a feed of transcripts and
+//! errors. Levels and transcripts arrive as daemon events; status is
+//! polled once a second. Keys start and stop dictation and switch models.
+
+use crate::ipc::IpcClient;
+use crate::ipc::protocol::{DaemonStatus, Event};
+use crossterm::event::{self as term, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Gauge, List, ListItem, ListState, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Feed lines kept for scrollback
+const FEED_LEN: usize = 200;
+
+/// Level samples kept for the waveform
+const LEVEL_HISTORY: usize = 300;
+
+/// Level shown as an empty meter, in dBFS
+const FLOOR_DB: f32 = -60.0;
+
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait before subscribing again after the daemon went away
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(2);
+
+/// What the monitor reacts to
+enum Input {
+    Key(KeyCode),
+    Daemon(Event),
+    Redraw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeedKind {
+    Transcript,
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+struct FeedLine {
+    time: String,
+    kind: FeedKind,
+    text: String,
+}
+
+/// Downloaded models to switch to
+struct ModelPicker {
+    models: Vec<String>,
+    state: ListState,
+}
+
+/// Dashboard state, updated from daemon events, status polls, and keys
+#[derive(Default)]
+pub struct Monitor {
+    status: Option<DaemonStatus>,
+    connection_error: Option<String>,
+    /// Meter positions (0.0 - 1.0) of the latest chunk
+    level: f32,
+    peak: f32,
+    speech: Option<bool>,
+    /// AGC gain of the latest chunk, in dB
+    gain_db: Option<f32>,
+    levels: VecDeque<u64>,
+    partial: Option<String>,
+    feed: VecDeque<FeedLine>,
+    picker: Option<ModelPicker>,
+    notice: Option<String>,
+}
+
+impl Monitor {
+    /// Update from a daemon event
+    pub fn apply(&mut self, event: &Event) {
+        match event {
+            Event::AudioLevel {
+                rms,
+                peak,
+                speech,
+                gain_db,
+            } => {
+                self.level = meter(*rms);
+                self.peak = meter(*peak);
+                self.speech = *speech;
+                self.gain_db = *gain_db;
+                self.levels.push_back((self.peak * 100.0) as u64);
+                if self.levels.len() > LEVEL_HISTORY {
+                    self.levels.pop_front();
+                }
+            }
+            Event::SpeechStarted => self.speech = Some(true),
+            Event::PartialTranscription { text } => self.partial = Some(text.clone()),
+            Event::TranscriptionComplete {
+                text,
+                duration_ms,
+                confidence,
+            } => {
+                self.partial = None;
+                let detail = match confidence {
+                    Some(confidence) => format!("{}ms, {:.0}%", duration_ms, confidence * 100.0),
+                    None => format!("{}ms", duration_ms),
+                };
+                self.push(FeedKind::Transcript, format!("{} ({})", text, detail));
+            }
+            Event::TranscriptionFailed { message }
+            | Event::InjectionFailed { message }
+            | Event::Error { message } => {
+                self.partial = None;
+                self.push(FeedKind::Error, message.clone());
+            }
+            Event::DictationStarted | Event::DictationStopped => {
+                let dictating = matches!(event, Event::DictationStarted);
+                if let Some(status) = &mut self.status {
+                    status.is_dictating = dictating;
+                }
+                if !dictating {
+                    self.level = 0.0;
+                    self.peak = 0.0;
+                    self.speech = None;
+                    self.gain_db = None;
+                }
+                self.push(
+                    FeedKind::Info,
+                    if dictating {
+                        "Dictation started"
+                    } else {
+                        "Dictation stopped"
+                    }
+                    .to_string(),
+                );
+            }
+            Event::DictationPaused => {
+                self.level = 0.0;
+                self.peak = 0.0;
+                self.speech = None;
+                self.push(FeedKind::Info, "Dictation paused".to_string());
+            }
+            Event::DictationResumed => {
+                self.push(FeedKind::Info, "Dictation resumed".to_string());
+            }
+            Event::ModelLoaded { name } => {
+                if let Some(status) = &mut self.status {
+                    status.model_loaded = true;
+                    status.model_name = Some(name.clone());
+                }
+                self.push(FeedKind::Info, format!("Model loaded: {}", name));
+            }
+            Event::ModelLoading { .. }
+            | Event::ModelUnloaded
+            | Event::DownloadComplete { .. }
+            | Event::InjectionDeferred { .. }
+            | Event::ShuttingDown => self.push(FeedKind::Info, event.to_string()),
+            Event::DownloadFailed { .. } => self.push(FeedKind::Error, event.to_string()),
+            Event::AudioDeviceChanged { from, to } => {
+                let change = crate::audio::DeviceChange {
+                    from: from.clone(),
+                    to: to.clone(),
+                };
+                let kind = if to.is_some() {
+                    FeedKind::Info
+                } else {
+                    FeedKind::Error
+                };
+                self.push(kind, change.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    /// Update from a status poll
+    pub fn set_status(&mut self, status: Result<DaemonStatus, String>) {
+        match status {
+            Ok(status) => {
+                self.status = Some(status);
+                self.connection_error = None;
+            }
+            Err(e) => {
+                self.status = None;
+                self.connection_error = Some(e);
+            }
+        }
+    }
+
+    fn push(&mut self, kind: FeedKind, text: String) {
+        self.feed.push_back(FeedLine {
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+            kind,
+            text,
+        });
+        if self.feed.len() > FEED_LEN {
+            self.feed.pop_front();
+        }
+    }
+
+    /// Handle a key press, returning false to quit
+    async fn handle_key(&mut self, key: KeyCode, client: &mut IpcClient) -> bool {
+        if let Some(picker) = &mut self.picker {
+            match key {
+                KeyCode::Up | KeyCode::Char('k') => picker.state.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => picker.state.select_next(),
+                KeyCode::Enter => {
+                    let selected = picker
+                        .state
+                        .selected()
+                        .and_then(|index| picker.models.get(index))
+                        .cloned();
+                    self.picker = None;
+                    if let Some(model) = selected {
+                        self.notice = Some(match client.load_model(&model).await {
+                            Ok(message) => message,
+                            Err(e) => format!("Failed to switch model: {}", e),
+                        });
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('q') => self.picker = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') | KeyCode::Char('d') => {
+                let dictating = self.status.as_ref().is_some_and(|s| s.is_dictating);
+                let result = if dictating {
+                    client.stop_dictation().await
+                } else {
+                    client.start_dictation().await
+                };
+                self.notice = result.err().map(|e| format!("Failed: {}", e));
+            }
+            KeyCode::Char('m') => match client.list_models().await {
+                Ok(models) if models.is_empty() => {
+                    self.notice = Some("No models downloaded".to_string());
+                }
+                Ok(models) => {
+                    let current = self.status.as_ref().and_then(|s| s.model_name.as_deref());
+                    let selected = models
+                        .iter()
+                        .position(|m| Some(m.as_str()) == current)
+                        .unwrap_or(0);
+                    self.picker = Some(ModelPicker {
+                        models,
+                        state: ListState::default().with_selected(Some(selected)),
+                    });
+                    self.notice = None;
+                }
+                Err(e) => self.notice = Some(format!("Failed to list models: {}", e)),
+            },
+            KeyCode::Char('c') => {
+                self.feed.clear();
+                self.notice = None;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [header, meters, waveform, feed, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(self.header(), header);
+
+        let [level, vad] =
+            Layout::horizontal([Constraint::Min(20), Constraint::Length(16)]).areas(meters);
+        let db = if self.level > 0.0 {
+            format!("{:.0} dBFS", FLOOR_DB * (1.0 - self.level))
+        } else {
+            "-".to_string()
+        };
+        let color = if self.peak >= meter(0.98) {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        let title = match self.gain_db {
+            Some(gain_db) => format!(" Input level (AGC {:+.0} dB) ", gain_db),
+            None => " Input level ".to_string(),
+        };
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(title))
+                .gauge_style(Style::default().fg(color))
+                .ratio(self.level.clamp(0.0, 1.0) as f64)
+                .label(db),
+            level,
+        );
+        let (vad_text, vad_style) = match self.speech {
+            Some(true) => ("Speech", Style::default().fg(Color::Green).bold()),
+            Some(false) => ("Silence", Style::default().fg(Color::DarkGray)),
+            None if self.dictating() => ("VAD off", Style::default().fg(Color::DarkGray)),
+            None => ("-", Style::default().fg(Color::DarkGray)),
+        };
+        frame.render_widget(
+            Paragraph::new(Span::styled(vad_text, vad_style))
+                .centered()
+                .block(Block::bordered().title(" VAD ")),
+            vad,
+        );
+
+        let width = waveform.width.saturating_sub(2) as usize;
+        let recent = self
+            .levels
+            .iter()
+            .skip(self.levels.len().saturating_sub(width));
+        frame.render_widget(
+            Sparkline::default()
+                .block(Block::bordered().title(" Waveform "))
+                .data(recent)
+                .max(100)
+                .style(Style::default().fg(Color::Cyan)),
+            waveform,
+        );
+
+        let rows = feed.height.saturating_sub(2) as usize;
+        let mut lines: Vec<ListItem> = self
+            .feed
+            .iter()
+            .map(|line| {
+                let style = match line.kind {
+                    FeedKind::Transcript => Style::default(),
+                    FeedKind::Info => Style::default().fg(Color::DarkGray),
+                    FeedKind::Error => Style::default().fg(Color::Red),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", line.time),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(line.text.clone(), style),
+                ]))
+            })
+            .collect();
+        if let Some(partial) = &self.partial {
+            lines.push(ListItem::new(Span::styled(
+                format!("         {}…", partial),
+                Style::default().add_modifier(Modifier::ITALIC),
+            )));
+        }
+        let skip = lines.len().saturating_sub(rows);
+        frame.render_widget(
+            List::new(lines.into_iter().skip(skip)).block(Block::bordered().title(" Transcripts ")),
+            feed,
+        );
+
+        let keys = "space start/stop · m switch model · c clear · q quit";
+        let footer_line = match &self.notice {
+            Some(notice) => Line::from(vec![
+                Span::styled(notice.clone(), Style::default().fg(Color::Yellow)),
+                Span::raw("  "),
+                Span::styled(keys, Style::default().fg(Color::DarkGray)),
+            ]),
+            None => Line::styled(keys, Style::default().fg(Color::DarkGray)),
+        };
+        frame.render_widget(Paragraph::new(footer_line), footer);
+
+        if let Some(picker) = &mut self.picker {
+            let height = (picker.models.len() as u16 + 2).min(frame.area().height);
+            let area = centered(frame.area(), 44, height);
+            let items: Vec<ListItem> = picker
+                .models
+                .iter()
+                .map(|m| ListItem::new(m.as_str()))
+                .collect();
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(
+                List::new(items)
+                    .block(Block::bordered().title(" Switch model (Enter) "))
+                    .highlight_style(Style::default().reversed())
+                    .highlight_symbol("> "),
+                area,
+                &mut picker.state,
+            );
+        }
+    }
+
+    fn dictating(&self) -> bool {
+        self.status.as_ref().is_some_and(|s| s.is_dictating)
+    }
+
+    fn header(&self) -> Paragraph<'_> {
+        let line = match (&self.status, &self.connection_error) {
+            (Some(status), _) => {
+                let (dot, activity) = if status.is_dictating {
+                    (
+                        Span::styled("● ", Style::default().fg(Color::Red)),
+                        "Dictating",
+                    )
+                } else {
+                    (Span::raw("○ "), "Not dictating")
+                };
+                Line::from(vec![
+                    dot,
+                    Span::raw(activity).bold(),
+                    Span::raw(format!(
+                        "  │  {}  │  Model: {}  │  Up {}  │  CPU {:.0}%  │  {} MB  │  v{}",
+                        status.state,
+                        status.model_name.as_deref().unwrap_or("none"),
+                        format_uptime(status.uptime_secs),
+                        status.cpu_usage_percent,
+                        status.memory_usage_bytes / 1024 / 1024,
+                        status.version
+                    )),
+                ])
+            }
+            (None, Some(e)) => Line::styled(
+                format!("Daemon not reachable: {}", e),
+                Style::default().fg(Color::Red),
+            ),
+            (None, None) => Line::raw("Connecting to daemon…"),
+        };
+        Paragraph::new(line).block(Block::bordered().title(" Onevox "))
+    }
+}
+
+/// Meter position (0.0 - 1.0) of an amplitude, on a dB scale down to
+/// `FLOOR_DB`
+fn meter(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - 20.0 * amplitude.log10() / FLOOR_DB).clamp(0.0, 1.0)
+}
+
+fn format_uptime(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+/// Run the monitor until the user quits
+pub async fn run() -> crate::Result<()> {
+    // One attempt with short timeouts so a stopped daemon doesn't stall
+    // the screen
+    let mut client = IpcClient::default()
+        .with_client_name("monitor")
+        .with_timeouts(Duration::from_millis(500), Duration::from_secs(5))
+        .with_max_attempts(1);
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let events = tokio::spawn(forward_events(tx.clone()));
+    std::thread::spawn(move || read_keys(tx));
+
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, &mut client, &mut rx).await;
+    ratatui::restore();
+    events.abort();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    client: &mut IpcClient,
+    rx: &mut mpsc::UnboundedReceiver<Input>,
+) -> crate::Result<()> {
+    let mut monitor = Monitor::default();
+    let mut status_tick = tokio::time::interval(STATUS_INTERVAL);
+    loop {
+        terminal.draw(|frame| monitor.render(frame))?;
+        tokio::select! {
+            _ = status_tick.tick() => {
+                monitor.set_status(client.get_status().await.map_err(|e| e.to_string()));
+            }
+            input = rx.recv() => match input {
+                Some(Input::Key(key)) => {
+                    if !monitor.handle_key(key, client).await {
+                        return Ok(());
+                    }
+                }
+                Some(Input::Daemon(event)) => monitor.apply(&event),
+                Some(Input::Redraw) => {}
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+/// Forward daemon events, subscribing again whenever the daemon restarts
+async fn forward_events(tx: mpsc::UnboundedSender<Input>) {
+    // Audio levels are only sent when asked for by name
+    let names: Vec<String> = Event::NAMES.iter().map(|name| name.to_string()).collect();
+    while !tx.is_closed() {
+        let mut client = IpcClient::default()
+            .with_client_name("monitor")
+            .with_max_attempts(1);
+        if let Ok(mut subscription) = client.subscribe(names.clone()).await {
+            while let Ok(Some(event)) = subscription.next().await {
+                if tx.send(Input::Daemon(event)).is_err() {
+                    return;
+                }
+            }
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+/// Forward key presses from the terminal until the monitor exits
+fn read_keys(tx: mpsc::UnboundedSender<Input>) {
+    while !tx.is_closed() {
+        match term::poll(Duration::from_millis(200)) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(_) => return,
+        }
+        let input = match term::read() {
+            Ok(term::Event::Key(key)) if key.kind == KeyEventKind::Press => Input::Key(key.code),
+            Ok(_) => Input::Redraw,
+            Err(_) => return,
+        };
+        if tx.send(input).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_applies_events() {
+        assert_eq!(meter(0.0), 0.0);
+        assert_eq!(meter(1.0), 1.0);
+        assert!((meter(0.1) - 2.0 / 3.0).abs() < 1e-4);
+
+        let mut monitor = Monitor::default();
+        monitor.apply(&Event::AudioLevel {
+            rms: 0.1,
+            peak: 1.0,
+            speech: Some(true),
+            gain_db: Some(6.0),
+        });
+        assert_eq!(monitor.gain_db, Some(6.0));
+        assert_eq!(monitor.speech, Some(true));
+        assert_eq!(monitor.levels.back(), Some(&100));
+
+        monitor.apply(&Event::PartialTranscription {
+            text: "hello".to_string(),
+        });
+        assert_eq!(monitor.partial.as_deref(), Some("hello"));
+        monitor.apply(&Event::TranscriptionComplete {
+            text: "Hello world.".to_string(),
+            duration_ms: 120,
+            confidence: Some(0.87),
+        });
+        assert_eq!(monitor.partial, None);
+        assert_eq!(
+            monitor.feed.back().unwrap().text,
+            "Hello world. (120ms, 87%)"
+        );
+        assert_eq!(monitor.feed.back().unwrap().kind, FeedKind::Transcript);
+
+        monitor.apply(&Event::DictationStopped);
+        assert_eq!(monitor.speech, None);
+        assert_eq!(monitor.level, 0.0);
+        assert_eq!(monitor.feed.len(), 2);
+        monitor.apply(&Event::DictationPaused);
+        assert_eq!(monitor.feed.back().unwrap().text, "Dictation paused");
+
+        assert_eq!(format_uptime(3725), "1h 02m");
+        assert_eq!(format_uptime(65), "1m 05s");
+    }
+}
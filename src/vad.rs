@@ -2,11 +2,43 @@
 //!
 //! Intelligent silence filtering and speech detection.
 
+pub mod calibrate;
 pub mod detector;
 pub mod energy;
+pub mod flatness;
 pub mod processor;
+pub mod silero;
 
 // Re-export commonly used types
 pub use detector::{VadDecision, VadDetector};
 pub use energy::{EnergyVad, EnergyVadConfig};
 pub use processor::{SpeechSegment, VadProcessor, VadProcessorConfig};
+pub use silero::SileroVad;
+
+use crate::config::VadConfig;
+use tracing::warn;
+
+/// Create the detector selected by `vad.detector`
+///
+/// The Silero model is downloaded on first use. If it can't be loaded, the
+/// energy detector is used instead so dictation keeps working.
+pub async fn create_detector(config: &VadConfig) -> Box<dyn VadDetector> {
+    match config.detector.as_str() {
+        "silero" => {
+            let loaded = match silero::ensure_model().await {
+                Ok(path) => SileroVad::load(&path, config.silero_threshold),
+                Err(e) => Err(crate::Error::Vad(format!(
+                    "Failed to download Silero model: {:#}",
+                    e
+                ))),
+            };
+            match loaded {
+                Ok(vad) => return Box::new(vad),
+                Err(e) => warn!("{}; falling back to energy VAD", e),
+            }
+        }
+        "energy" => {}
+        other => warn!("Unknown VAD detector '{}'; using energy VAD", other),
+    }
+    Box::new(EnergyVad::new(config.to_energy_vad_config()))
+}
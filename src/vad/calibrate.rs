@@ -0,0 +1,123 @@
+//! VAD Calibration
+//!
+//! `onevox calibrate` records a few seconds of room noise and a few seconds
+//! of speech and derives the energy detector's settings from them. Both
+//! recordings are cut into chunks of the capture size and measured as the
+//! detector would: the noise floor is the 90th percentile chunk level of
+//! the quiet recording (a louder moment or two doesn't matter), the speech
+//! level the median of the speech chunks above it (pauses between words are
+//! left out), and the threshold their geometric mean, which sits at the
+//! same distance in dB from both.
+
+use super::energy::EnergyVad;
+
+/// The threshold stays at least this many times the noise floor
+const MIN_FLOOR_RATIO: f32 = 2.0;
+
+/// Speech closer than this to the noise floor (dB) is hard to separate
+pub const MIN_MARGIN_DB: f32 = 6.0;
+
+/// Settings derived from the two recordings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// Background energy (RMS)
+    pub noise_floor: f32,
+    /// Typical speech energy (RMS)
+    pub speech_level: f32,
+    /// Energy separating the two (RMS)
+    pub threshold: f32,
+}
+
+impl Calibration {
+    /// Measure `silence` and `speech`, both at `sample_rate`, in chunks of
+    /// `chunk_ms`
+    pub fn measure(silence: &[f32], speech: &[f32], sample_rate: u32, chunk_ms: u32) -> Self {
+        let chunk_len = (sample_rate as usize * chunk_ms as usize / 1000).max(1);
+        let levels = |samples: &[f32]| -> Vec<f32> {
+            let mut levels: Vec<f32> = samples
+                .chunks(chunk_len)
+                .map(EnergyVad::calculate_rms_energy)
+                .collect();
+            levels.sort_by(f32::total_cmp);
+            levels
+        };
+
+        let noise = levels(silence);
+        let noise_floor = percentile(&noise, 0.9);
+        let voiced: Vec<f32> = levels(speech)
+            .into_iter()
+            .filter(|&level| level > noise_floor * MIN_FLOOR_RATIO)
+            .collect();
+        let speech_level = percentile(&voiced, 0.5);
+        let threshold = (noise_floor * speech_level)
+            .sqrt()
+            .max(noise_floor * MIN_FLOOR_RATIO);
+
+        Self {
+            noise_floor,
+            speech_level,
+            threshold,
+        }
+    }
+
+    /// Distance between speech and the noise floor in dB
+    pub fn margin_db(&self) -> f32 {
+        20.0 * (self.speech_level.max(1e-6) / self.noise_floor.max(1e-6)).log10()
+    }
+
+    /// Whether speech stood out from the noise enough to use the result
+    pub fn is_usable(&self) -> bool {
+        self.speech_level > 0.0 && self.margin_db() >= MIN_MARGIN_DB
+    }
+
+    /// Value for `vad.threshold`: an offset above the background when
+    /// `adaptive`, an absolute level otherwise
+    pub fn vad_threshold(&self, adaptive: bool) -> f32 {
+        if adaptive {
+            self.threshold - self.noise_floor
+        } else {
+            self.threshold
+        }
+    }
+}
+
+/// Value at fraction `p` of sorted `values` (0.0 when empty)
+fn percentile(values: &[f32], p: f32) -> f32 {
+    values
+        .get((values.len().saturating_sub(1) as f32 * p) as usize)
+        .copied()
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_from_recordings() {
+        let tone = |amplitude: f32, len: usize| -> Vec<f32> {
+            (0..len)
+                .map(|i| amplitude * (i as f32 * 0.3).sin())
+                .collect()
+        };
+        let silence = tone(0.005, 16000);
+        // Speech with a pause in the middle
+        let mut speech = tone(0.2, 8000);
+        speech.extend(tone(0.005, 4000));
+        speech.extend(tone(0.2, 8000));
+
+        let calibration = Calibration::measure(&silence, &speech, 16000, 100);
+        assert!((calibration.noise_floor - 0.0035).abs() < 0.0005);
+        assert!((calibration.speech_level - 0.141).abs() < 0.005);
+        assert!(calibration.threshold > calibration.noise_floor * 2.0);
+        assert!(calibration.threshold < calibration.speech_level);
+        assert!(calibration.is_usable());
+        assert_eq!(
+            calibration.vad_threshold(true),
+            calibration.threshold - calibration.noise_floor
+        );
+
+        let noisy = Calibration::measure(&silence, &silence, 16000, 100);
+        assert!(!noisy.is_usable());
+    }
+}
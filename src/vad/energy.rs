@@ -1,6 +1,13 @@
 //! Energy-based Voice Activity Detection
 //!
 //! Simple and fast VAD using RMS energy detection with adaptive threshold.
+//!
+//! The adaptive threshold sits `threshold` above the background energy. By
+//! default the background is the median of the last few chunks; with
+//! `track_noise_floor` it is a running estimate updated only outside
+//! speech, rising slowly and falling quickly, so a long session follows a
+//! fan spinning up without speech raising the floor. `noise_floor` (set by
+//! `onevox calibrate`) seeds the estimate.
 
 use super::detector::{VadDecision, VadDetector};
 use crate::audio::buffer::{AudioChunk, Sample};
@@ -19,8 +26,18 @@ pub struct EnergyVadConfig {
     pub adaptive: bool,
     /// Window size for adaptive threshold (in chunks)
     pub adaptive_window_size: usize,
+    /// Calibrated background energy to start from (0.0 = unknown)
+    pub noise_floor: f32,
+    /// Track the background continuously instead of using the median window
+    pub track_noise_floor: bool,
 }
 
+/// Time for the tracked noise floor to follow rising background noise (ms)
+const FLOOR_RISE_MS: f32 = 10_000.0;
+
+/// Time for the tracked noise floor to follow falling background noise (ms)
+const FLOOR_FALL_MS: f32 = 500.0;
+
 impl Default for EnergyVadConfig {
     fn default() -> Self {
         Self {
@@ -29,6 +46,8 @@ impl Default for EnergyVadConfig {
             min_silence_chunks: 3,
             adaptive: true,
             adaptive_window_size: 30,
+            noise_floor: 0.0,
+            track_noise_floor: false,
         }
     }
 }
@@ -46,18 +65,24 @@ pub struct EnergyVad {
 impl EnergyVad {
     /// Create a new energy-based VAD
     pub fn new(config: EnergyVadConfig) -> Self {
+        let background_energy = config.noise_floor;
         Self {
             config,
             speech_count: 0,
             silence_count: 0,
             current_state: VadDecision::Silence,
             energy_history: VecDeque::with_capacity(30),
-            background_energy: 0.0,
+            background_energy,
         }
     }
 
+    /// Current background energy estimate
+    pub fn noise_floor(&self) -> f32 {
+        self.background_energy
+    }
+
     /// Calculate RMS energy of audio samples
-    fn calculate_rms_energy(samples: &[Sample]) -> f32 {
+    pub(crate) fn calculate_rms_energy(samples: &[Sample]) -> f32 {
         if samples.is_empty() {
             return 0.0;
         }
@@ -67,10 +92,14 @@ impl EnergyVad {
     }
 
     /// Update background energy estimate
-    fn update_background_energy(&mut self, energy: f32) {
+    fn update_background_energy(&mut self, energy: f32, duration_ms: f32) {
         if !self.config.adaptive {
             return;
         }
+        if self.config.track_noise_floor {
+            self.track_background_energy(energy, duration_ms);
+            return;
+        }
 
         // Add to history
         self.energy_history.push_back(energy);
@@ -86,6 +115,25 @@ impl EnergyVad {
         }
     }
 
+    /// Move the running background estimate toward a non-speech chunk
+    fn track_background_energy(&mut self, energy: f32, duration_ms: f32) {
+        if self.background_energy <= 0.0 {
+            self.background_energy = energy;
+            return;
+        }
+        // Speech (and the chunks keeping it open) says nothing about the noise
+        if self.current_state == VadDecision::Speech || energy > self.get_threshold() {
+            return;
+        }
+        let time_constant = if energy > self.background_energy {
+            FLOOR_RISE_MS
+        } else {
+            FLOOR_FALL_MS
+        };
+        let alpha = (duration_ms / time_constant).min(1.0);
+        self.background_energy += (energy - self.background_energy) * alpha;
+    }
+
     /// Get effective threshold
     fn get_threshold(&self) -> f32 {
         if self.config.adaptive {
@@ -104,7 +152,7 @@ impl VadDetector for EnergyVad {
         let energy = Self::calculate_rms_energy(&chunk.samples);
 
         // Update background energy estimate
-        self.update_background_energy(energy);
+        self.update_background_energy(energy, chunk.duration_ms() as f32);
 
         // Get current threshold
         let threshold = self.get_threshold();
@@ -164,7 +212,7 @@ impl VadDetector for EnergyVad {
         self.silence_count = 0;
         self.current_state = VadDecision::Silence;
         self.energy_history.clear();
-        self.background_energy = 0.0;
+        self.background_energy = self.config.noise_floor;
     }
 }
 
@@ -188,4 +236,31 @@ mod tests {
         let decision = vad.detect(&chunk).unwrap();
         assert_eq!(decision, VadDecision::Silence);
     }
+
+    #[test]
+    fn test_noise_floor_tracking() {
+        let mut vad = EnergyVad::new(EnergyVadConfig {
+            noise_floor: 0.01,
+            track_noise_floor: true,
+            ..EnergyVadConfig::default()
+        });
+        let chunk = |level: f32| AudioChunk::new(vec![level; 1600], 16000);
+
+        // A fan spinning up raises the floor over seconds, not at once
+        vad.detect(&chunk(0.02)).unwrap();
+        assert!(vad.noise_floor() < 0.0102);
+        for _ in 0..300 {
+            vad.detect(&chunk(0.02)).unwrap();
+        }
+        assert!(vad.noise_floor() > 0.019);
+
+        // Speech leaves it alone
+        for _ in 0..20 {
+            vad.detect(&chunk(0.3)).unwrap();
+        }
+        assert!(vad.noise_floor() < 0.021);
+
+        vad.reset();
+        assert_eq!(vad.noise_floor(), 0.01);
+    }
 }
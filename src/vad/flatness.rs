@@ -0,0 +1,88 @@
+//! Spectral Flatness
+//!
+//! Ratio of the geometric to the arithmetic mean of the power spectrum.
+//! Voiced speech concentrates energy in harmonics and scores low; broadband
+//! bursts (door slams, coughs, keyboard clatter) score close to 1.
+
+use rustfft::FftPlanner;
+use rustfft::num_complex::Complex;
+
+/// Samples per analysis frame (32ms at 16kHz)
+const FRAME_SIZE: usize = 512;
+
+/// Frames quieter than this (sum of squares) are skipped
+const MIN_FRAME_ENERGY: f32 = 1e-6;
+
+/// Keeps the logarithm finite for empty bins
+const POWER_FLOOR: f32 = 1e-12;
+
+/// Energy-weighted spectral flatness of `samples` (0.0 = tonal, 1.0 = noise)
+///
+/// Loud frames dominate the result, so silence around a burst does not
+/// dilute it. Returns 0.0 when there is no audible frame.
+pub fn spectral_flatness(samples: &[f32]) -> f32 {
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(FRAME_SIZE);
+    let window: Vec<f32> = (0..FRAME_SIZE)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME_SIZE as f32).cos())
+        .collect();
+    let mut buffer = vec![Complex::new(0.0f32, 0.0); FRAME_SIZE];
+
+    let mut weighted = 0.0f32;
+    let mut total_energy = 0.0f32;
+
+    for frame in samples.chunks_exact(FRAME_SIZE) {
+        let energy: f32 = frame.iter().map(|s| s * s).sum();
+        if energy < MIN_FRAME_ENERGY {
+            continue;
+        }
+
+        for ((bin, &sample), &w) in buffer.iter_mut().zip(frame).zip(&window) {
+            *bin = Complex::new(sample * w, 0.0);
+        }
+        fft.process(&mut buffer);
+
+        // Skip DC; keep the positive frequencies
+        let power = buffer[1..=FRAME_SIZE / 2]
+            .iter()
+            .map(|c| c.norm_sqr() + POWER_FLOOR);
+        let bins = (FRAME_SIZE / 2) as f32;
+        let (log_sum, sum) = power.fold((0.0f32, 0.0f32), |(log_sum, sum), p| {
+            (log_sum + p.ln(), sum + p)
+        });
+        let flatness = (log_sum / bins).exp() / (sum / bins);
+
+        weighted += flatness * energy;
+        total_energy += energy;
+    }
+
+    if total_energy > 0.0 {
+        weighted / total_energy
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_is_flatter_than_noise() {
+        let tone: Vec<f32> = (0..16000)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16000.0).sin())
+            .collect();
+
+        // Deterministic white noise
+        let mut state = 0x1234_5678u32;
+        let noise: Vec<f32> = (0..16000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+            })
+            .collect();
+
+        assert!(spectral_flatness(&tone) < 0.1);
+        assert!(spectral_flatness(&noise) > 0.4);
+        assert_eq!(spectral_flatness(&[0.0; 4096]), 0.0);
+    }
+}
@@ -1,12 +1,34 @@
 //! VAD Processor
 //!
 //! Streaming VAD with pre-roll and post-roll buffering.
+//!
+//! Post-roll can adapt to the speaker: the typical pause between words and
+//! the energy decay of the last words decide how long to wait after the
+//! detector reports silence before closing the segment.
+//!
+//! Closed segments with too little speech, or with a noise-like spectrum
+//! (door slams, coughs), are dropped before they reach the model.
+//!
+//! Separately from the post-roll, which ends a segment, the processor
+//! tracks silence since the last speech so a whole session can end after
+//! a longer quiet spell (`auto_stop_silence_ms`).
 
 use super::detector::{VadDecision, VadDetector};
+use super::energy::EnergyVad;
+use super::flatness::spectral_flatness;
 use crate::audio::buffer::AudioChunk;
 use std::collections::VecDeque;
 use tracing::{debug, info};
 
+/// Number of trailing speech chunks used to estimate the energy decay slope
+const DECAY_WINDOW: usize = 4;
+
+/// Smoothing factor for the running average of pause lengths
+const PAUSE_SMOOTHING: f32 = 0.3;
+
+/// Chunks quieter than this fraction of the mean speech energy count as a pause
+const PAUSE_ENERGY_RATIO: f32 = 0.25;
+
 /// VAD processor configuration
 #[derive(Debug, Clone)]
 pub struct VadProcessorConfig {
@@ -15,7 +37,22 @@ pub struct VadProcessorConfig {
     pub pre_roll_ms: u32,
     /// Post-roll buffer duration in milliseconds
     /// This is how much audio after speech ends to include
+    /// (the starting point when adaptive post-roll is enabled)
     pub post_roll_ms: u32,
+    /// Adapt post-roll to speech cadence and trailing energy decay
+    pub adaptive_post_roll: bool,
+    /// Lower bound for adaptive post-roll in milliseconds
+    pub min_post_roll_ms: u32,
+    /// Upper bound for adaptive post-roll in milliseconds
+    pub max_post_roll_ms: u32,
+    /// Segments with less detected speech than this are dropped (0 =
+    /// disabled)
+    pub min_speech_ms: u32,
+    /// Segments whose spectral flatness exceeds this are dropped as noise
+    /// bursts (1.0 = disabled)
+    pub max_spectral_flatness: f32,
+    /// Silence after which the session should end (0 = never)
+    pub auto_stop_silence_ms: u32,
 }
 
 impl Default for VadProcessorConfig {
@@ -23,6 +60,14 @@ impl Default for VadProcessorConfig {
         Self {
             pre_roll_ms: 300,
             post_roll_ms: 500,
+            adaptive_post_roll: true,
+            min_post_roll_ms: 200,
+            max_post_roll_ms: 1000,
+            // Noise-burst rejection is opt-in: short answers like "yes"
+            // and whispered speech would otherwise be dropped
+            min_speech_ms: 0,
+            max_spectral_flatness: 1.0,
+            auto_stop_silence_ms: 0,
         }
     }
 }
@@ -97,6 +142,81 @@ enum ProcessorState {
     Idle,
     /// Currently processing speech
     InSpeech,
+    /// Speech ended, collecting trailing audio before closing the segment
+    PostRoll { remaining_ms: u64 },
+}
+
+/// Tracks speech cadence and energy for adaptive post-roll
+#[derive(Debug, Default)]
+struct CadenceTracker {
+    /// Sum of speech chunk energies in the current segment
+    speech_energy_sum: f32,
+    /// Number of speech chunks in the current segment
+    speech_chunks: usize,
+    /// Energies of the most recent speech chunks
+    recent_speech_energy: VecDeque<f32>,
+    /// Length of the current run of quiet chunks in milliseconds
+    current_pause_ms: u64,
+    /// Running average of pauses between words (kept across segments)
+    avg_pause_ms: Option<f32>,
+}
+
+impl CadenceTracker {
+    /// Start tracking a new speech segment
+    fn start_segment(&mut self) {
+        self.speech_energy_sum = 0.0;
+        self.speech_chunks = 0;
+        self.recent_speech_energy.clear();
+        self.current_pause_ms = 0;
+    }
+
+    /// Observe a chunk that belongs to the current segment
+    fn observe(&mut self, energy: f32, duration_ms: u64) {
+        let mean = self.mean_speech_energy();
+        if mean > 0.0 && energy < mean * PAUSE_ENERGY_RATIO {
+            self.current_pause_ms += duration_ms;
+            return;
+        }
+
+        // Speech resumed after a pause between words
+        if self.current_pause_ms > 0 {
+            let pause = self.current_pause_ms as f32;
+            self.avg_pause_ms = Some(match self.avg_pause_ms {
+                Some(avg) => avg + PAUSE_SMOOTHING * (pause - avg),
+                None => pause,
+            });
+            self.current_pause_ms = 0;
+        }
+
+        self.speech_energy_sum += energy;
+        self.speech_chunks += 1;
+        self.recent_speech_energy.push_back(energy);
+        while self.recent_speech_energy.len() > DECAY_WINDOW {
+            self.recent_speech_energy.pop_front();
+        }
+    }
+
+    fn mean_speech_energy(&self) -> f32 {
+        if self.speech_chunks == 0 {
+            0.0
+        } else {
+            self.speech_energy_sum / self.speech_chunks as f32
+        }
+    }
+
+    /// Energy slope over the last speech chunks, relative to the mean speech
+    /// energy (negative when the last words fade out)
+    fn decay_slope(&self) -> f32 {
+        let mean = self.mean_speech_energy();
+        let n = self.recent_speech_energy.len();
+        if n < 2 || mean <= 0.0 {
+            return 0.0;
+        }
+
+        let first = self.recent_speech_energy[0];
+        let last = self.recent_speech_energy[n - 1];
+        (last - first) / (n - 1) as f32 / mean
+    }
 }
 
 /// VAD processor for streaming audio
@@ -107,6 +227,11 @@ pub struct VadProcessor {
     pre_roll_buffer: VecDeque<AudioChunk>,
     speech_buffer: Vec<AudioChunk>,
     max_pre_roll_chunks: usize,
+    cadence: CadenceTracker,
+    /// Audio the detector classified as speech in the current segment
+    voiced_ms: u64,
+    /// Audio since the detector last heard speech
+    silent_ms: u64,
 }
 
 impl VadProcessor {
@@ -119,6 +244,9 @@ impl VadProcessor {
             pre_roll_buffer: VecDeque::new(),
             speech_buffer: Vec::new(),
             max_pre_roll_chunks: 10, // Will be updated based on chunk duration
+            cadence: CadenceTracker::default(),
+            voiced_ms: 0,
+            silent_ms: 0,
         }
     }
 
@@ -140,17 +268,25 @@ impl VadProcessor {
 
         // Run VAD detection on this chunk
         let decision = self.detector.detect(&chunk)?;
+        if decision == VadDecision::Speech {
+            self.silent_ms = 0;
+        } else {
+            self.silent_ms += chunk.duration_ms();
+        }
 
         match self.state {
             ProcessorState::Idle => {
                 if decision == VadDecision::Speech {
                     info!("Speech started");
                     self.state = ProcessorState::InSpeech;
+                    self.cadence.start_segment();
+                    self.voiced_ms = chunk.duration_ms();
 
                     // Move pre-roll buffer to speech buffer
                     self.speech_buffer.extend(self.pre_roll_buffer.drain(..));
 
                     // Add current chunk
+                    self.observe(&chunk);
                     self.speech_buffer.push(chunk);
                 } else {
                     // Add to pre-roll buffer
@@ -166,7 +302,12 @@ impl VadProcessor {
             }
 
             ProcessorState::InSpeech => {
+                if decision == VadDecision::Speech {
+                    self.voiced_ms += chunk.duration_ms();
+                }
+
                 // Add chunk to speech buffer
+                self.observe(&chunk);
                 self.speech_buffer.push(chunk);
 
                 // Check if speech ended
@@ -176,39 +317,176 @@ impl VadProcessor {
                         self.speech_buffer.len()
                     );
 
-                    // Create speech segment
-                    let segment = SpeechSegment::new(std::mem::take(&mut self.speech_buffer));
+                    // Trailing silence already buffered counts towards the post-roll
+                    let post_roll_ms = self.post_roll_ms();
+                    let remaining_ms = post_roll_ms.saturating_sub(self.cadence.current_pause_ms);
+                    debug!(
+                        "Post-roll {}ms ({}ms still to collect)",
+                        post_roll_ms, remaining_ms
+                    );
 
-                    // Reset state
-                    self.state = ProcessorState::Idle;
-                    self.pre_roll_buffer.clear();
+                    if remaining_ms == 0 {
+                        Ok(self.finish_segment())
+                    } else {
+                        self.state = ProcessorState::PostRoll { remaining_ms };
+                        Ok(None)
+                    }
+                } else {
+                    Ok(None)
+                }
+            }
 
-                    info!(
-                        "Speech segment complete: {} chunks, {}ms duration",
-                        segment.len(),
-                        segment.duration_ms
-                    );
+            ProcessorState::PostRoll { remaining_ms } => {
+                let duration_ms = chunk.duration_ms();
+                self.observe(&chunk);
+                self.speech_buffer.push(chunk);
 
-                    Ok(Some(segment))
+                if decision == VadDecision::Speech {
+                    debug!("Speech resumed during post-roll");
+                    self.voiced_ms += duration_ms;
+                    self.state = ProcessorState::InSpeech;
+                    return Ok(None);
+                }
+
+                let remaining_ms = remaining_ms.saturating_sub(duration_ms);
+                if remaining_ms == 0 {
+                    Ok(self.finish_segment())
                 } else {
+                    self.state = ProcessorState::PostRoll { remaining_ms };
                     Ok(None)
                 }
             }
         }
     }
 
+    /// Track cadence and energy for a chunk in the current segment
+    fn observe(&mut self, chunk: &AudioChunk) {
+        let energy = EnergyVad::calculate_rms_energy(&chunk.samples);
+        self.cadence.observe(energy, chunk.duration_ms());
+    }
+
+    /// Post-roll to apply for the segment that just ended
+    fn post_roll_ms(&self) -> u64 {
+        if !self.config.adaptive_post_roll {
+            return self.config.post_roll_ms as u64;
+        }
+
+        // Wait somewhat longer than this speaker's typical pause between words
+        let base = self
+            .cadence
+            .avg_pause_ms
+            .map(|pause| pause * 1.5)
+            .unwrap_or(self.config.post_roll_ms as f32);
+
+        // Fading energy suggests a trailing word may follow; an abrupt stop
+        // means the speaker finished cleanly and we can close sooner
+        let slope = self.cadence.decay_slope();
+        let factor = if slope < -0.15 {
+            1.25
+        } else if slope > -0.05 {
+            0.75
+        } else {
+            1.0
+        };
+
+        let min = self
+            .config
+            .min_post_roll_ms
+            .min(self.config.max_post_roll_ms) as f32;
+        let max = self.config.max_post_roll_ms as f32;
+        (base * factor).clamp(min, max) as u64
+    }
+
+    /// Close the current segment and return to idle
+    ///
+    /// Returns `None` when the segment is rejected as a non-speech burst.
+    fn finish_segment(&mut self) -> Option<SpeechSegment> {
+        let mut segment = SpeechSegment::new(std::mem::take(&mut self.speech_buffer));
+
+        // Reset state
+        self.state = ProcessorState::Idle;
+        self.pre_roll_buffer.clear();
+
+        if self.voiced_ms < self.config.min_speech_ms as u64 {
+            info!(
+                "Dropping segment: {}ms of speech is below the {}ms minimum",
+                self.voiced_ms, self.config.min_speech_ms
+            );
+            return None;
+        }
+
+        if self.config.max_spectral_flatness < 1.0 {
+            let flatness = spectral_flatness(segment.get_samples());
+            if flatness > self.config.max_spectral_flatness {
+                info!(
+                    "Dropping segment: spectral flatness {:.2} above {:.2} (noise burst)",
+                    flatness, self.config.max_spectral_flatness
+                );
+                return None;
+            }
+            debug!("Segment spectral flatness {:.2}", flatness);
+        }
+
+        info!(
+            "Speech segment complete: {} chunks, {}ms duration",
+            segment.len(),
+            segment.duration_ms
+        );
+
+        Some(segment)
+    }
+
+    /// Close the segment in progress at the end of the input
+    ///
+    /// Returns `None` when no speech is in progress or the segment is
+    /// rejected as a non-speech burst.
+    pub fn flush(&mut self) -> Option<SpeechSegment> {
+        if self.state == ProcessorState::Idle {
+            return None;
+        }
+        self.finish_segment()
+    }
+
+    /// Samples of the segment in progress (empty when idle), for interim
+    /// transcripts
+    pub fn pending_samples(&self) -> Vec<f32> {
+        self.speech_buffer
+            .iter()
+            .flat_map(|chunk| chunk.samples.iter().copied())
+            .collect()
+    }
+
+    /// Capture time of the oldest buffered chunk; no segment still to come
+    /// can start before it (pre-roll included)
+    pub fn earliest_pending(&self) -> Option<std::time::Instant> {
+        self.speech_buffer
+            .first()
+            .or(self.pre_roll_buffer.front())
+            .map(|chunk| chunk.timestamp)
+    }
+
+    /// Whether the silence since the last speech (or since the start)
+    /// reached `auto_stop_silence_ms`, with no segment left in progress
+    pub fn auto_stop_due(&self) -> bool {
+        self.config.auto_stop_silence_ms > 0
+            && self.state == ProcessorState::Idle
+            && self.silent_ms >= self.config.auto_stop_silence_ms as u64
+    }
+
     /// Reset processor state
     pub fn reset(&mut self) {
         self.state = ProcessorState::Idle;
+        self.silent_ms = 0;
         self.pre_roll_buffer.clear();
         self.speech_buffer.clear();
+        self.cadence = CadenceTracker::default();
         self.detector.reset();
         info!("VAD processor reset");
     }
 
     /// Get current processor state
     pub fn is_in_speech(&self) -> bool {
-        self.state == ProcessorState::InSpeech
+        self.state != ProcessorState::Idle
     }
 
     /// Get detector name
@@ -259,7 +537,7 @@ mod tests {
             min_speech_chunks: 2,
             min_silence_chunks: 3,
             adaptive: false, // Disable adaptive for test
-            adaptive_window_size: 30,
+            ..EnergyVadConfig::default()
         };
         let detector = Box::new(EnergyVad::new(vad_config));
         let config = VadProcessorConfig::default();
@@ -301,4 +579,195 @@ mod tests {
             "Speech segment should have been detected after silence"
         );
     }
+
+    fn non_adaptive_vad() -> Box<EnergyVad> {
+        Box::new(EnergyVad::new(EnergyVadConfig {
+            threshold: 0.02,
+            min_speech_chunks: 2,
+            min_silence_chunks: 3,
+            adaptive: false,
+            ..EnergyVadConfig::default()
+        }))
+    }
+
+    /// Feed speech then silence, returning (silent chunks fed, segment)
+    fn run_until_segment(processor: &mut VadProcessor) -> (usize, Option<SpeechSegment>) {
+        for _ in 0..5 {
+            processor.process(create_speech_chunk(100, 16000)).unwrap();
+        }
+        for i in 1..=20 {
+            if let Some(segment) = processor.process(create_silent_chunk(100, 16000)).unwrap() {
+                return (i, Some(segment));
+            }
+        }
+        (20, None)
+    }
+
+    #[test]
+    fn test_fixed_post_roll_collects_trailing_audio() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 800,
+            adaptive_post_roll: false,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        // 3 chunks of hysteresis silence count towards the 800ms post-roll
+        let (silent_chunks, segment) = run_until_segment(&mut processor);
+        assert_eq!(silent_chunks, 8);
+        assert_eq!(segment.unwrap().len(), 13);
+        assert!(!processor.is_in_speech());
+    }
+
+    #[test]
+    fn test_adaptive_post_roll_is_bounded() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 2000,
+            adaptive_post_roll: true,
+            min_post_roll_ms: 200,
+            max_post_roll_ms: 400,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        let (silent_chunks, segment) = run_until_segment(&mut processor);
+        assert!(segment.is_some());
+        assert!(
+            silent_chunks <= 4,
+            "post-roll exceeded max: {}",
+            silent_chunks
+        );
+    }
+
+    #[test]
+    fn test_speech_resumes_during_post_roll() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 1000,
+            adaptive_post_roll: false,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        for _ in 0..5 {
+            processor.process(create_speech_chunk(100, 16000)).unwrap();
+        }
+        for _ in 0..4 {
+            assert!(
+                processor
+                    .process(create_silent_chunk(100, 16000))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        // Speaker continues within the post-roll window: same segment
+        for _ in 0..3 {
+            assert!(
+                processor
+                    .process(create_speech_chunk(100, 16000))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert!(processor.is_in_speech());
+    }
+
+    #[test]
+    fn test_short_and_noisy_segments_are_dropped() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 300,
+            adaptive_post_roll: false,
+            min_speech_ms: 400,
+            max_spectral_flatness: 0.45,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        // A 200ms burst never reaches the model
+        for _ in 0..2 {
+            processor.process(create_speech_chunk(100, 16000)).unwrap();
+        }
+        for _ in 0..10 {
+            assert!(
+                processor
+                    .process(create_silent_chunk(100, 16000))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert!(!processor.is_in_speech());
+
+        // Broadband noise is rejected by spectral flatness
+        let mut state = 0x1234_5678u32;
+        let noise: Vec<f32> = (0..8000)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+            })
+            .collect();
+        for samples in noise.chunks(1600) {
+            processor
+                .process(AudioChunk::new(samples.to_vec(), 16000))
+                .unwrap();
+        }
+        for _ in 0..10 {
+            assert!(
+                processor
+                    .process(create_silent_chunk(100, 16000))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        // Real speech-length tonal segments still pass
+        let (_, segment) = run_until_segment(&mut processor);
+        assert!(segment.is_some());
+    }
+
+    #[test]
+    fn test_short_utterance_is_kept_by_default() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 300,
+            adaptive_post_roll: false,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        // A 200ms "yes"
+        for _ in 0..2 {
+            processor.process(create_speech_chunk(100, 16000)).unwrap();
+        }
+        let segment =
+            (0..10).find_map(|_| processor.process(create_silent_chunk(100, 16000)).unwrap());
+        assert!(segment.is_some());
+    }
+
+    #[test]
+    fn test_auto_stop_after_silence() {
+        let config = VadProcessorConfig {
+            post_roll_ms: 300,
+            adaptive_post_roll: false,
+            auto_stop_silence_ms: 1500,
+            ..Default::default()
+        };
+        let mut processor = VadProcessor::new(config, non_adaptive_vad());
+
+        // The silence the segment ended on counts towards it
+        let (mut silent_chunks, segment) = run_until_segment(&mut processor);
+        assert!(segment.is_some());
+        assert!(!processor.auto_stop_due());
+        while !processor.auto_stop_due() {
+            processor.process(create_silent_chunk(100, 16000)).unwrap();
+            silent_chunks += 1;
+            assert!(silent_chunks < 30);
+        }
+        // 1.5s, plus the chunks the detector still called speech
+        assert!((15..=18).contains(&silent_chunks));
+
+        // Speech starts the count over
+        for _ in 0..2 {
+            processor.process(create_speech_chunk(100, 16000)).unwrap();
+        }
+        assert!(!processor.auto_stop_due());
+    }
 }
@@ -0,0 +1,308 @@
+//! Custom Vocabulary
+//!
+//! Product names and jargon from `[vocabulary]`, plus `vocabulary.txt` in
+//! the config directory (one term per line, optionally `term = boost`),
+//! are pushed onto the transcript in three places: whisper models get them
+//! in the initial prompt, the ONNX CTC beam search adds each term's boost
+//! to hypotheses that spell it (hotword biasing), and post-processing
+//! replaces near-misses left in the text ("post gres sql" → "PostgreSQL").
+
+use crate::config::{VocabularyConfig, VocabularyTerm};
+use tracing::{debug, warn};
+
+/// File in the config directory with extra terms
+pub const VOCABULARY_FILE: &str = "vocabulary.txt";
+
+/// Most terms listed in a whisper prompt (the prompt is capped at about
+/// 224 tokens; the highest boosts go first)
+const PROMPT_TERMS: usize = 40;
+
+/// Shortest term (in letters) replaced by fuzzy matching; shorter ones
+/// match ordinary words too easily
+const MIN_FUZZY_LETTERS: usize = 4;
+
+/// Extra words a transcript may split a term into ("post gres sql")
+const EXTRA_WINDOW_WORDS: usize = 2;
+
+/// A term with its boost
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    /// Spelling to produce
+    pub text: String,
+    /// Decoder bias weight (1.0 = default)
+    pub boost: f32,
+    /// Lowercased words, for matching decoder output
+    words: Vec<String>,
+    /// Lowercased letters and digits, for fuzzy matching
+    letters: String,
+}
+
+impl Term {
+    pub fn new(text: &str, boost: f32) -> Self {
+        let text = text.trim().to_string();
+        Self {
+            words: text.split_whitespace().map(bare_word).collect(),
+            letters: letters(&text),
+            text,
+            boost,
+        }
+    }
+}
+
+/// The user's vocabulary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vocabulary {
+    terms: Vec<Term>,
+    /// Similarity a phrase needs to be replaced (`None` = no replacement)
+    fuzzy_threshold: Option<f32>,
+}
+
+impl Vocabulary {
+    /// Build from `[vocabulary]` and the config directory's vocabulary.txt
+    pub fn from_config(config: &VocabularyConfig) -> Self {
+        let mut vocabulary = Self::from_terms(config, &[]);
+        let file = crate::platform::paths::config_dir().map(|dir| dir.join(VOCABULARY_FILE));
+        if let Ok(path) = file
+            && path.exists()
+        {
+            match std::fs::read_to_string(&path) {
+                Ok(text) => vocabulary.terms.extend(parse_file(&text)),
+                Err(e) => warn!("Failed to read {}: {}", path.display(), e),
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        vocabulary
+            .terms
+            .retain(|term| seen.insert(term.text.clone()));
+        debug!("Custom vocabulary: {} terms", vocabulary.terms.len());
+        vocabulary
+    }
+
+    /// Build from `[vocabulary]` plus `extra` terms, without reading files
+    pub fn from_terms(config: &VocabularyConfig, extra: &[Term]) -> Self {
+        let terms = config
+            .terms
+            .iter()
+            .map(|term| match term {
+                VocabularyTerm::Plain(text) => Term::new(text, 1.0),
+                VocabularyTerm::Boosted { term, boost } => Term::new(term, *boost),
+            })
+            .chain(extra.iter().cloned())
+            .filter(|term| !term.text.is_empty() && term.boost > 0.0)
+            .collect();
+        Self {
+            terms,
+            fuzzy_threshold: config.fuzzy_replace.then_some(config.fuzzy_threshold),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// Initial prompt for whisper models: the terms, highest boost first
+    pub fn prompt(&self) -> Option<String> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        let mut terms: Vec<&Term> = self.terms.iter().collect();
+        terms.sort_by(|a, b| b.boost.total_cmp(&a.boost));
+        let list: Vec<&str> = terms
+            .iter()
+            .take(PROMPT_TERMS)
+            .map(|term| term.text.as_str())
+            .collect();
+        Some(format!("Glossary: {}.", list.join(", ")))
+    }
+
+    /// Decoder bonus for a hypothesis: each completed word of a term adds
+    /// the term's boost, and a `partial` word that starts a term word adds
+    /// the share of it spelled so far
+    pub fn bonus(&self, words: &[&str], partial: Option<&str>) -> f32 {
+        if self.terms.is_empty() {
+            return 0.0;
+        }
+        let partial = partial.map(bare_word).filter(|partial| partial.len() >= 2);
+        let mut bonus = 0.0;
+        for term in &self.terms {
+            for word in &term.words {
+                bonus += term.boost
+                    * words
+                        .iter()
+                        .filter(|candidate| bare_word(candidate) == *word)
+                        .count() as f32;
+                if let Some(partial) = &partial
+                    && word.starts_with(partial.as_str())
+                {
+                    bonus += term.boost * partial.len() as f32 / word.len() as f32;
+                }
+            }
+        }
+        bonus
+    }
+
+    /// Replace phrases that nearly spell a term with the term
+    pub fn correct(&self, text: &str) -> String {
+        let Some(threshold) = self.fuzzy_threshold else {
+            return text.to_string();
+        };
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut out: Vec<String> = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            match self.best_match(&words[i..], threshold) {
+                Some((term, count)) => {
+                    let first = words[i];
+                    let last = words[i + count - 1];
+                    let lead: String = first.chars().take_while(|c| !c.is_alphanumeric()).collect();
+                    let trail: String = last
+                        .chars()
+                        .rev()
+                        .take_while(|c| !c.is_alphanumeric())
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .rev()
+                        .collect();
+                    out.push(format!("{}{}{}", lead, term.text, trail));
+                    i += count;
+                }
+                None => {
+                    out.push(words[i].to_string());
+                    i += 1;
+                }
+            }
+        }
+        out.join(" ")
+    }
+
+    /// The term closest to a phrase at the start of `words`, with the
+    /// number of words it covers
+    fn best_match(&self, words: &[&str], threshold: f32) -> Option<(&Term, usize)> {
+        let mut best: Option<(&Term, usize, f32)> = None;
+        for term in &self.terms {
+            if term.letters.chars().count() < MIN_FUZZY_LETTERS {
+                continue;
+            }
+            let longest = (term.words.len() + EXTRA_WINDOW_WORDS).min(words.len());
+            for count in 1..=longest {
+                let phrase = &words[..count];
+                let spelled: Vec<&str> = phrase
+                    .iter()
+                    .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+                    .collect();
+                if spelled.join(" ") == term.text {
+                    // Already right; nothing to replace
+                    continue;
+                }
+                let score = similarity(&letters(&spelled.join("")), &term.letters);
+                if score >= threshold && best.is_none_or(|(_, _, best)| score > best) {
+                    best = Some((term, count, score));
+                }
+            }
+        }
+        best.map(|(term, count, _)| (term, count))
+    }
+}
+
+/// Terms from vocabulary.txt: one per line, optionally `term = boost`;
+/// `#` starts a comment
+fn parse_file(text: &str) -> Vec<Term> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.rsplit_once('=') {
+            Some((term, boost)) if boost.trim().parse::<f32>().is_ok() => {
+                Term::new(term, boost.trim().parse().unwrap_or(1.0))
+            }
+            _ => Term::new(line, 1.0),
+        })
+        .collect()
+}
+
+/// A word without surrounding punctuation, lowercased
+fn bare_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Letters and digits only, lowercased
+fn letters(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 1 − edit distance / longer length
+fn similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vocabulary_bias_and_correction() {
+        let config = VocabularyConfig {
+            terms: vec![
+                VocabularyTerm::Plain("Onevox".to_string()),
+                VocabularyTerm::Boosted {
+                    term: "PostgreSQL".to_string(),
+                    boost: 2.0,
+                },
+                VocabularyTerm::Plain("Go".to_string()),
+            ],
+            ..VocabularyConfig::default()
+        };
+        let extra = parse_file("# team names\nKubernetes = 1.5\nVisual Studio Code\n");
+        let vocabulary = Vocabulary::from_terms(&config, &extra);
+        assert_eq!(vocabulary.terms().len(), 5);
+        assert_eq!(vocabulary.terms()[3].boost, 1.5);
+
+        assert_eq!(
+            vocabulary.prompt().unwrap(),
+            "Glossary: PostgreSQL, Kubernetes, Onevox, Go, Visual Studio Code."
+        );
+
+        // Whole words score their boost, a started word its share
+        assert_eq!(vocabulary.bonus(&["use", "postgresql"], None), 2.0);
+        assert_eq!(vocabulary.bonus(&["use"], Some("postg")), 1.0);
+        assert_eq!(vocabulary.bonus(&["hello"], None), 0.0);
+
+        assert_eq!(
+            vocabulary.correct("I set up post gres sql and one vox, then kubernetis."),
+            "I set up PostgreSQL and Onevox, then Kubernetes."
+        );
+        // Short terms and correct spellings are left alone
+        assert_eq!(vocabulary.correct("go to Onevox"), "go to Onevox");
+
+        let off = Vocabulary::from_terms(
+            &VocabularyConfig {
+                fuzzy_replace: false,
+                ..config
+            },
+            &[],
+        );
+        assert_eq!(off.correct("one vox"), "one vox");
+    }
+}
@@ -0,0 +1,59 @@
+#!/usr/bin/env bash
+#
+# Synthesize 16kHz mono WAV audio for every reference transcript in
+# fixtures/ that does not have audio yet. The references are original
+# sentences or public-domain text, and the audio is generated locally, so
+# the fixtures carry no licensing restrictions.
+#
+# Uses `say` on macOS, otherwise `espeak-ng` (or `espeak`) plus `ffmpeg`
+# or `sox` to convert to 16kHz.
+
+set -euo pipefail
+
+FIXTURES_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)/fixtures"
+
+synthesize() {
+  local text="$1" out="$2"
+
+  if command -v say >/dev/null 2>&1; then
+    say --file-format=WAVE --data-format=LEI16@16000 -o "$out" "$text"
+    return
+  fi
+
+  local tts=""
+  for candidate in espeak-ng espeak; do
+    if command -v "$candidate" >/dev/null 2>&1; then
+      tts="$candidate"
+      break
+    fi
+  done
+  if [[ -z "$tts" ]]; then
+    echo "❌ No TTS engine found (install espeak-ng)" >&2
+    exit 1
+  fi
+
+  local raw
+  raw="$(mktemp).wav"
+  "$tts" -w "$raw" "$text"
+  if command -v ffmpeg >/dev/null 2>&1; then
+    ffmpeg -loglevel error -y -i "$raw" -ar 16000 -ac 1 -sample_fmt s16 "$out"
+  elif command -v sox >/dev/null 2>&1; then
+    sox "$raw" -r 16000 -c 1 -b 16 "$out"
+  else
+    echo "❌ ffmpeg or sox is required to resample to 16kHz" >&2
+    rm -f "$raw"
+    exit 1
+  fi
+  rm -f "$raw"
+}
+
+for reference in "$FIXTURES_DIR"/*.txt; do
+  audio="${reference%.txt}.wav"
+  if [[ -f "$audio" ]]; then
+    continue
+  fi
+  echo "🔊 $(basename "$audio")"
+  synthesize "$(cat "$reference")" "$audio"
+done
+
+echo "✅ Fixtures ready in $FIXTURES_DIR"
@@ -0,0 +1,78 @@
+//! Accuracy regression suite
+//!
+//! Transcribes the fixtures in `tests/quality/fixtures` and fails when the
+//! corpus word error rate exceeds the threshold.
+//!
+//! Run: `cargo test --features quality-tests --test quality`
+//!
+//! - `tests/quality/generate_fixtures.sh` creates the audio
+//! - `ONEVOX_EVAL_MODEL` selects the model (default `ggml-base.en`)
+//! - `ONEVOX_EVAL_MAX_WER` sets the threshold (default 0.15)
+//!
+//! The suite only builds with the `quality-tests` feature, so a reference
+//! without audio or a missing model fails the test rather than skipping it.
+
+use onevox::config::Config;
+use onevox::eval;
+use std::path::Path;
+
+const DEFAULT_MODEL: &str = "ggml-base.en";
+const DEFAULT_MAX_WER: f64 = 0.15;
+
+#[test]
+fn test_fixture_word_error_rate() {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/quality/fixtures");
+    let missing: Vec<String> = std::fs::read_dir(&fixtures_dir)
+        .expect("Failed to read fixtures")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter(|path| !path.with_extension("wav").exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "No audio for {:?}; run tests/quality/generate_fixtures.sh",
+        missing
+    );
+    let fixtures = eval::load_fixtures(&fixtures_dir).expect("Failed to read fixtures");
+    assert!(!fixtures.is_empty(), "No fixtures in {:?}", fixtures_dir);
+
+    let mut model_config = Config::default().model;
+    model_config.model_path =
+        std::env::var("ONEVOX_EVAL_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+    let max_wer = std::env::var("ONEVOX_EVAL_MAX_WER")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_WER);
+
+    let mut model = onevox::models::backend::load_runtime(&model_config).unwrap_or_else(|e| {
+        panic!(
+            "Model {} unavailable ({}); run: cargo run --release -- models download {}",
+            model_config.model_path, e, model_config.model_path
+        )
+    });
+
+    let report = eval::evaluate(model.as_mut(), &fixtures).expect("Evaluation failed");
+    for result in &report.results {
+        println!(
+            "{:<16} WER {:>5.1}%  {:?}",
+            result.name,
+            result.counts.wer() * 100.0,
+            result.hypothesis
+        );
+    }
+    println!(
+        "Corpus WER {:.1}%, CER {:.1}%",
+        report.wer() * 100.0,
+        report.cer() * 100.0
+    );
+
+    assert!(
+        report.wer() <= max_wer,
+        "WER {:.3} exceeds {:.3} for {}",
+        report.wer(),
+        max_wer,
+        report.model
+    );
+}
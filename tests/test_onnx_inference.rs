@@ -20,6 +20,9 @@ fn test_onnx_transcription_silence() {
         use_gpu: false,
         n_threads: 4,
         beam_size: 1,
+        language: None,
+        translate: false,
+        prompt: None,
     };
 
     // Try to load model - skip test if model not downloaded
@@ -84,6 +87,9 @@ fn test_onnx_transcription_synthetic_audio() {
         use_gpu: false,
         n_threads: 4,
         beam_size: 1,
+        language: None,
+        translate: false,
+        prompt: None,
     };
 
     // Try to load model - skip test if model not downloaded
@@ -3,9 +3,9 @@
  *
  * ┌─────────────────────────────────────────────┐
  * │  █▀█ █▄ █ █▀▀ █ █ █▀█ ▀▄▀   (ASCIIFont)   │
- * │  History    Config                (tabs)     │
+ * │  History    Config    Models      (tabs)     │
  * │ ┌─────────────────────────────────────────┐  │
- * │ │  Content area (History or Config panel) │  │
+ * │ │  Content area (History/Config/Models)   │  │
  * │ └─────────────────────────────────────────┘  │
  * │  ↑/↓ j/k Move | dd/x Delete | D Clear All    │
  * └─────────────────────────────────────────────┘
@@ -27,13 +27,16 @@ import { saveConfig } from "./data/config.js"
 import { getTheme, type Theme, type ThemeName } from "./theme.js"
 import { createHistoryPanel, type HistoryPanelInstance } from "./panels/history.js"
 import { createConfigPanel, type ConfigPanelInstance } from "./panels/config.js"
+import { createModelsPanel, type ModelsPanelInstance } from "./panels/models.js"
 import { createHelpOverlay, type HelpOverlayInstance } from "./panels/help.js"
 
+const TAB_COUNT = 3
+
 export interface AppState {
   config: VoxConfig
   history: HistoryEntry[]
   configDirty: boolean
-  activeTab: number // 0 = History, 1 = Config
+  activeTab: number // 0 = History, 1 = Config, 2 = Models
   theme: Theme
 }
 
@@ -62,6 +65,7 @@ export function createApp(
   let helpOverlay: HelpOverlayInstance | null = null
   let historyPanel: HistoryPanelInstance | null = null
   let configPanel: ConfigPanelInstance | null = null
+  let modelsPanel: ModelsPanelInstance | null = null
 
   // ── Root container ────────────────────────────────────────────────────
   const root = new BoxRenderable(renderer, {
@@ -117,6 +121,7 @@ export function createApp(
     options: [
       { name: "History", description: "Transcription history" },
       { name: "Config", description: "Settings and configuration" },
+      { name: "Models", description: "Download and switch models" },
     ],
     tabWidth: 20,
     backgroundColor: RGBA.fromHex(state.theme.colors.bg),
@@ -203,9 +208,12 @@ export function createApp(
       if (state.activeTab === 0) {
         // History tab hints
         statusLeft.content = "↑/↓ j/k Move  c Copy  e Export  dd/x Delete  D Clear All  Esc Tabs"
-      } else {
+      } else if (state.activeTab === 1) {
         // Config tab hints
         statusLeft.content = "↑/↓ j/k Move  ←/→ h/l Change  Space Toggle  Ctrl+S Save  Esc Tabs"
+      } else {
+        // Models tab hints
+        statusLeft.content = "↑/↓ j/k Move  Enter Download/Use  p Pause/Resume  x Cancel  r Refresh  Esc Tabs"
       }
     }
     statusRight.content = `● ${state.theme.name === "dark" ? "Dark" : "Light"} Mode`
@@ -276,6 +284,38 @@ export function createApp(
     contentArea.add(configPanel.root)
   }
 
+  function showModels() {
+    clearContent()
+    modelsPanel = createModelsPanel(renderer, state, {
+      onStatusMessage: (msg) => {
+        statusCenter.fg = RGBA.fromHex(state.theme.colors.textPrimary)
+        statusCenter.content = msg
+      },
+      onEscape: () => {
+        // Return focus to tabs
+        setFocusMode("tabs")
+        tabs.focus()
+      },
+    })
+    contentArea.add(modelsPanel.root)
+  }
+
+  function showTab(index: number) {
+    if (index === 0) showHistory()
+    else if (index === 1) showConfig()
+    else showModels()
+  }
+
+  function focusActivePanel() {
+    if (state.activeTab === 0) {
+      if (historyPanel) historyPanel.focusFirst?.()
+    } else if (state.activeTab === 1) {
+      if (configPanel) configPanel.focusFirst()
+    } else {
+      if (modelsPanel) modelsPanel.focusFirst()
+    }
+  }
+
   function clearContent() {
     if (historyPanel) {
       try { contentArea.remove("history-panel") } catch {}
@@ -286,6 +326,11 @@ export function createApp(
       try { contentArea.remove("config-panel") } catch {}
       configPanel = null
     }
+    if (modelsPanel) {
+      modelsPanel.destroy()
+      try { contentArea.remove("models-panel") } catch {}
+      modelsPanel = null
+    }
   }
 
   // ── Tab switching ─────────────────────────────────────────────────────
@@ -293,13 +338,8 @@ export function createApp(
   tabs.on(TabSelectRenderableEvents.SELECTION_CHANGED, (index: number) => {
     state.activeTab = index
     setFocusMode("content")
-    if (index === 0) {
-      showHistory()
-      if (historyPanel) historyPanel.focusFirst?.()
-    } else {
-      showConfig()
-      if (configPanel) configPanel.focusFirst()
-    }
+    showTab(index)
+    focusActivePanel()
     // Refresh hints to show tab-specific shortcuts
     refreshStatusHints()
   })
@@ -307,6 +347,9 @@ export function createApp(
   // Show initial tab
   showHistory()
 
+  // Stop following daemon events on exit (downloads keep running)
+  renderer.on("destroy", () => modelsPanel?.destroy())
+
   // ── Theme toggle ──────────────────────────────────────────────────────
   
   function toggleTheme() {
@@ -336,8 +379,7 @@ export function createApp(
     refreshStatusHints()
     
     // Rebuild current panel with new theme
-    if (state.activeTab === 0) showHistory()
-    else showConfig()
+    showTab(state.activeTab)
     
     // Show feedback
     statusCenter.content = `✓ Switched to ${state.theme.name} mode`
@@ -396,7 +438,8 @@ export function createApp(
     // Left/Right or h/l: Navigate between tabs (only when tabs are focused)
     if (focusMode === "tabs") {
       if (key.name === "left" || key.name === "right" || key.name === "h" || key.name === "l") {
-        const next = (state.activeTab + 1) % 2
+        const step = key.name === "left" || key.name === "h" ? TAB_COUNT - 1 : 1
+        const next = (state.activeTab + step) % TAB_COUNT
         tabs.setSelectedIndex(next)
         state.activeTab = next
         return
@@ -405,11 +448,7 @@ export function createApp(
       // Enter or Down or j: Enter content area
       if (key.name === "return" || key.name === "down" || key.name === "j") {
         setFocusMode("content")
-        if (state.activeTab === 0) {
-          if (historyPanel) historyPanel.focusFirst?.()
-        } else {
-          if (configPanel) configPanel.focusFirst()
-        }
+        focusActivePanel()
         return
       }
     }
@@ -418,10 +457,12 @@ export function createApp(
     if (key.name === "escape" && focusMode === "content") {
       if (state.activeTab === 0) {
         if (historyPanel) historyPanel.blurAll?.()
-      } else {
+      } else if (state.activeTab === 1) {
         if (configPanel && configPanel.hasFocus()) {
           configPanel.blurAll()
         }
+      } else {
+        if (modelsPanel) modelsPanel.blurAll()
       }
       setFocusMode("tabs")
       tabs.focus()
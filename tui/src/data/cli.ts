@@ -299,6 +299,26 @@ export function getModelRegistry(): ModelInfo[] {
       description: "NVIDIA Parakeet - Fast multilingual ONNX model (ARM64 macOS, Linux, Windows)",
       downloaded: false,
     },
+    {
+      id: "parakeet-tdt-0.6b-v2",
+      name: "Parakeet TDT 0.6B v2 (ONNX)",
+      size: "~661 MB",
+      sizeBytes: 661_000_000,
+      speedFactor: 50,
+      memoryMb: 500,
+      description: "NVIDIA Parakeet transducer - More accurate English ONNX model with punctuation",
+      downloaded: false,
+    },
+    {
+      id: "parakeet-tdt-0.6b-v3",
+      name: "Parakeet TDT 0.6B v3 (ONNX)",
+      size: "~670 MB",
+      sizeBytes: 670_000_000,
+      speedFactor: 50,
+      memoryMb: 500,
+      description: "NVIDIA Parakeet transducer - 25 European languages with punctuation",
+      downloaded: false,
+    },
   ]
 }
 
@@ -355,6 +375,95 @@ export async function removeModel(modelId: string): Promise<string> {
   return run(["models", "remove", modelId])
 }
 
+// ── Background downloads (run by the daemon) ─────────────────────────────
+
+export type DownloadEvent =
+  | { kind: "progress"; model: string; file: string; downloaded: number; total: number }
+  | { kind: "complete"; model: string }
+  | { kind: "stopped"; model: string; cancelled: boolean }
+  | { kind: "failed"; model: string; message: string }
+
+/** Start a resumable download in the daemon; `activate` switches to it when done */
+export async function startDownload(modelId: string, activate: boolean): Promise<string> {
+  const args = ["models", "download", modelId, "--background"]
+  if (activate) args.push("--activate")
+  return run(args)
+}
+
+/** Pause a daemon download, keeping partial files for resuming */
+export async function pauseDownload(modelId: string): Promise<string> {
+  return run(["models", "pause", modelId])
+}
+
+/** Cancel a daemon download and remove its partial files */
+export async function cancelDownload(modelId: string): Promise<string> {
+  return run(["models", "cancel", modelId])
+}
+
+/** Make a downloaded model the active one (applied live by the daemon) */
+export async function useModel(modelId: string): Promise<string> {
+  return run(["config", "set", "model.model_path", modelId])
+}
+
+/** Parse a line of `onevox events` output into a download event */
+export function parseDownloadEvent(line: string): DownloadEvent | null {
+  // Lines look like "12:03:44 DownloadProgress ggml-base.en/ggml-base.en.bin: 1048576/147951465 bytes"
+  const body = line.replace(/^\d{2}:\d{2}:\d{2}\s+/, "")
+  let m = body.match(/^DownloadProgress ([^/\s]+)\/(.+): (\d+)\/(\d+) bytes$/)
+  if (m) {
+    return { kind: "progress", model: m[1], file: m[2], downloaded: Number(m[3]), total: Number(m[4]) }
+  }
+  m = body.match(/^DownloadComplete (\S+)$/)
+  if (m) return { kind: "complete", model: m[1] }
+  m = body.match(/^DownloadStopped (\S+) \((paused|cancelled)\)$/)
+  if (m) return { kind: "stopped", model: m[1], cancelled: m[2] === "cancelled" }
+  m = body.match(/^DownloadFailed (\S+): (.*)$/)
+  if (m) return { kind: "failed", model: m[1], message: m[2] }
+  return null
+}
+
+/**
+ * Follow download events from the daemon. Returns a function that stops
+ * listening. `onClosed` fires if the event stream ends (e.g. daemon stopped).
+ */
+export function watchDownloads(
+  onEvent: (event: DownloadEvent) => void,
+  onClosed?: () => void,
+): () => void {
+  const bin = onevoxBin()
+  if (!bin) {
+    onClosed?.()
+    return () => {}
+  }
+  const proc = Bun.spawn(
+    [bin, "events", "DownloadProgress", "DownloadComplete", "DownloadStopped", "DownloadFailed"],
+    { stdout: "pipe", stderr: "pipe" },
+  )
+  let stopped = false
+
+  ;(async () => {
+    const decoder = new TextDecoder()
+    let pending = ""
+    for await (const chunk of proc.stdout as any) {
+      pending += decoder.decode(chunk, { stream: true })
+      const lines = pending.split("\n")
+      pending = lines.pop() ?? ""
+      for (const line of lines) {
+        const event = parseDownloadEvent(line.trim())
+        if (event) onEvent(event)
+      }
+    }
+    if (!stopped) onClosed?.()
+  })().catch(() => {
+    if (!stopped) onClosed?.()
+  })
+
+  return () => {
+    stopped = true
+    proc.kill()
+  }
+}
+
 // ── Daemon status ────────────────────────────────────────────────────────
 
 export async function getDaemonStatus(): Promise<DaemonStatus | null> {
@@ -5,6 +5,7 @@
  *   Windows: %APPDATA%\onevox\onevox\config\config.toml
  *   macOS:   ~/Library/Application Support/com.onevox.onevox/config.toml
  *   Linux:   ~/.config/onevox/config.toml
+ *   Portable: $ONEVOX_PORTABLE_DIR/config/config.toml
  */
 
 import { existsSync, mkdirSync, readFileSync, writeFileSync } from "node:fs"
@@ -98,6 +99,7 @@ export interface DaemonConfig {
 export interface HotkeyConfig {
   trigger: string
   mode: string
+  backend: string
 }
 
 export interface AudioConfig {
@@ -127,6 +129,7 @@ export interface PostProcessingConfig {
   auto_punctuation: boolean
   auto_capitalize: boolean
   remove_filler_words: boolean
+  mask_profanity: boolean
 }
 
 export interface InjectionConfig {
@@ -157,7 +160,8 @@ export const DEFAULT_CONFIG: VoxConfig = {
   daemon: { auto_start: true, log_level: "info" },
   hotkey: {
     trigger: process.platform === "darwin" ? "Cmd+Shift+0" : "Ctrl+Shift+Space",
-    mode: "push-to-talk",
+    mode: "hold",
+    backend: "auto",
   },
   audio: { device: "default", sample_rate: 16000, chunk_duration_ms: 200 },
   vad: {
@@ -179,6 +183,7 @@ export const DEFAULT_CONFIG: VoxConfig = {
     auto_punctuation: true,
     auto_capitalize: true,
     remove_filler_words: false,
+    mask_profanity: false,
   },
   injection: { method: "accessibility", paste_delay_ms: 50, focus_settle_ms: 80 },
   ui: { recording_overlay: true, theme: "dark" },
@@ -189,6 +194,7 @@ export const DEFAULT_CONFIG: VoxConfig = {
 export function configDir(): string {
   if (process.env.ONEVOX_CONFIG_DIR) return process.env.ONEVOX_CONFIG_DIR
   if (process.env.VOX_CONFIG_DIR) return process.env.VOX_CONFIG_DIR
+  if (process.env.ONEVOX_PORTABLE_DIR) return join(process.env.ONEVOX_PORTABLE_DIR, "config")
   if (process.platform === "win32") {
     return join(process.env.APPDATA || join(homedir(), "AppData", "Roaming"), "onevox", "onevox", "config")
   }
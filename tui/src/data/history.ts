@@ -5,6 +5,7 @@
  *   Windows: %APPDATA%\onevox\onevox\data\history.json
  *   macOS:   ~/Library/Application Support/com.onevox.onevox/history.json
  *   Linux:   ~/.local/share/onevox/history.json
+ *   Portable: $ONEVOX_PORTABLE_DIR/data/history.json
  */
 
 import { existsSync, mkdirSync, readFileSync, writeFileSync } from "node:fs"
@@ -20,6 +21,7 @@ export interface HistoryEntry {
   model: string
   duration_ms: number
   confidence: number | null
+  duplicate?: boolean // Repeat of the previous segment, not injected
 }
 
 // ── Path resolution ──────────────────────────────────────────────────────
@@ -27,6 +29,7 @@ export interface HistoryEntry {
 export function dataDir(): string {
   if (process.env.ONEVOX_DATA_DIR) return process.env.ONEVOX_DATA_DIR
   if (process.env.VOX_DATA_DIR) return process.env.VOX_DATA_DIR
+  if (process.env.ONEVOX_PORTABLE_DIR) return join(process.env.ONEVOX_PORTABLE_DIR, "data")
   if (process.platform === "win32") {
     return join(process.env.APPDATA || join(homedir(), "AppData", "Roaming"), "onevox", "onevox", "data")
   }
@@ -276,24 +276,45 @@ export function createConfigPanel(
     onChange: (combo) => { config.hotkey.trigger = combo; markDirty() },
   })
 
-  const modeIdx = config.hotkey.mode === "toggle" ? 1 : 0
+  const modes = ["hold", "toggle", "hands-free"]
+  const modeIdx = Math.max(0, modes.indexOf(config.hotkey.mode))
   const modeField = createSelectField(renderer, {
     id: "mode-select",
     label: "Hotkey Mode:",
     options: [
-      { name: "push-to-talk", description: "Hold key to dictate" },
+      { name: "hold", description: "Hold key to dictate (push-to-talk)" },
       { name: "toggle", description: "Press to start/stop" },
+      { name: "hands-free", description: "Always listening; key pauses/resumes" },
     ],
     selectedIndex: modeIdx,
     theme,
     onChange: (index) => {
-      config.hotkey.mode = index === 0 ? "push-to-talk" : "toggle"
+      config.hotkey.mode = modes[index]
+      markDirty()
+    },
+  })
+
+  const backends = ["auto", "native", "evdev"]
+  const backendIdx = Math.max(0, backends.indexOf(config.hotkey.backend))
+  const backendField = createSelectField(renderer, {
+    id: "backend-select",
+    label: "Hotkey Backend:",
+    options: [
+      { name: "auto", description: "evdev under Wayland, native otherwise" },
+      { name: "native", description: "OS hotkey API (macOS, Windows, X11)" },
+      { name: "evdev", description: "Read /dev/input (Linux, needs input group)" },
+    ],
+    selectedIndex: backendIdx,
+    theme,
+    onChange: (index) => {
+      config.hotkey.backend = backends[index]
       markDirty()
     },
   })
 
   hotkeyContent.add(triggerCapture.root)
   hotkeyContent.add(modeField.root)
+  hotkeyContent.add(backendField.root)
 
   // ── 4. Device Selection ────────────────────────────────────────
   const deviceContent = createSection("sec-device", "Device Selection")
@@ -504,9 +525,18 @@ export function createConfigPanel(
     onChange: (v) => { config.post_processing.remove_filler_words = v; markDirty() },
   })
 
+  const ppProfanity = createToggle(renderer, {
+    id: "pp-profanity",
+    label: "Mask profanity",
+    value: config.post_processing.mask_profanity,
+    theme,
+    onChange: (v) => { config.post_processing.mask_profanity = v; markDirty() },
+  })
+
   ppContent.add(ppPunctuation.root)
   ppContent.add(ppCapitalize.root)
   ppContent.add(ppFiller.root)
+  ppContent.add(ppProfanity.root)
 
   // ── 8. Injection ───────────────────────────────────────────────
   const injContent = createSection("sec-injection", "Text Injection")
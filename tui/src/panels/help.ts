@@ -47,7 +47,7 @@ export function createHelpOverlay(
     {
       title: "Navigation",
       keys: [
-        ["← →  or  h l", "Switch between History / Config tabs"],
+        ["← →  or  h l", "Switch between History / Config / Models tabs"],
         ["↓  or  j", "Enter content area / Move down"],
         ["↑  or  k", "Move up"],
         ["Enter", "Select / Activate item"],
@@ -83,6 +83,15 @@ export function createHelpOverlay(
         ["Enter", "Activate dropdown/select"],
       ],
     },
+    {
+      title: "Models Tab",
+      keys: [
+        ["Enter  or  d", "Download selected model (or switch to it)"],
+        ["p", "Pause / resume download"],
+        ["x", "Cancel download, removing partial files"],
+        ["r", "Refresh downloaded models"],
+      ],
+    },
     {
       title: "Popups & Dialogs",
       keys: [
@@ -177,7 +177,7 @@ export function createHistoryPanel(
 
     for (let i = 0; i < entries.length; i++) {
       const entry = entries[i]
-      const subtitle = `${entry.model} • ${formatTimestamp(entry.timestamp)} • ${formatDuration(entry.duration_ms)}${entry.confidence != null ? ` • ${(entry.confidence * 100).toFixed(0)}%` : ""}`
+      const subtitle = `${entry.model} • ${formatTimestamp(entry.timestamp)} • ${formatDuration(entry.duration_ms)}${entry.confidence != null ? ` • ${(entry.confidence * 100).toFixed(0)}%` : ""}${entry.duplicate ? " • 🔁 repeat, not injected" : ""}`
 
       const card = createCard(renderer, {
         id: `hist-${entry.id}`,
@@ -337,7 +337,7 @@ export function createHistoryPanel(
 
     const metaText = new TextRenderable(renderer, {
       id: "expand-meta",
-      content: `Model: ${entry.model}  │  ${formatTimestamp(entry.timestamp)}  │  Duration: ${formatDuration(entry.duration_ms)}`,
+      content: `Model: ${entry.model}  │  ${formatTimestamp(entry.timestamp)}  │  Duration: ${formatDuration(entry.duration_ms)}${entry.confidence != null ? `  │  Confidence: ${(entry.confidence * 100).toFixed(0)}%` : ""}`,
       fg: theme.colors.textSecondary,
     })
 
@@ -0,0 +1,409 @@
+/**
+ * Models Panel — registry models with download progress, driven by daemon events.
+ *
+ * Features:
+ *   - One card per registry model: size, speed, downloaded/active markers
+ *   - Downloads run in the daemon, so they continue when the TUI closes
+ *   - Live progress bars from `onevox events` (DownloadProgress/Complete/...)
+ *   - Pause keeps partial files; downloading again resumes where it stopped
+ *   - Keyboard nav: Up/Down/j/k, Enter=download or use, p=pause/resume, x=cancel
+ */
+
+import {
+  BoxRenderable,
+  TextRenderable,
+  ScrollBoxRenderable,
+  type CliRenderer,
+  TextAttributes,
+} from "@opentui/core"
+
+import type { AppState } from "../app.js"
+import {
+  listModelsWithStatus,
+  startDownload,
+  pauseDownload,
+  cancelDownload,
+  useModel,
+  watchDownloads,
+  type DownloadEvent,
+  type ModelInfo,
+} from "../data/cli.js"
+import { createCard, type CardInstance } from "../components/card.js"
+
+export interface ModelsPanelCallbacks {
+  onStatusMessage: (msg: string) => void
+  onEscape?: () => void
+}
+
+export interface ModelsPanelInstance {
+  root: BoxRenderable
+  refresh: () => void
+  focusFirst: () => void
+  blurAll: () => void
+  destroy: () => void
+}
+
+interface DownloadState {
+  status: "downloading" | "paused" | "failed"
+  file: string
+  downloaded: number
+  total: number
+  message: string
+}
+
+// Kept across panel rebuilds (tab switches, theme changes) since downloads
+// keep running in the daemon
+const downloads = new Map<string, DownloadState>()
+
+const BAR_WIDTH = 24
+
+function progressBar(downloaded: number, total: number): string {
+  const fraction = total > 0 ? Math.min(downloaded / total, 1) : 0
+  const filled = Math.round(fraction * BAR_WIDTH)
+  return `${"█".repeat(filled)}${"░".repeat(BAR_WIDTH - filled)} ${Math.floor(fraction * 100)}%`
+}
+
+function mb(bytes: number): string {
+  return (bytes / 1024 / 1024).toFixed(1)
+}
+
+function isActiveModel(state: AppState, model: ModelInfo): boolean {
+  const current = state.config.model.model_path
+  return current === model.id || current === `${model.id}.bin`
+}
+
+export function createModelsPanel(
+  renderer: CliRenderer,
+  state: AppState,
+  callbacks: ModelsPanelCallbacks,
+): ModelsPanelInstance {
+  let selectedIndex = 0
+  let cards: CardInstance[] = []
+  let models: ModelInfo[] = []
+  let hasFocus = false
+
+  const theme = state.theme
+
+  // ── Root ─────────────────────────────────────────────────────────────
+  const root = new BoxRenderable(renderer, {
+    id: "models-panel",
+    width: "100%" as any,
+    height: "100%" as any,
+    flexDirection: "column",
+  })
+
+  // ── Top bar ──────────────────────────────────────────────────────────
+  const topBar = new BoxRenderable(renderer, {
+    id: "models-topbar",
+    width: "100%" as any,
+    height: 1,
+    flexDirection: "row",
+    justifyContent: "space-between",
+    marginBottom: 2,
+    paddingBottom: 1,
+  })
+
+  const titleText = new TextRenderable(renderer, {
+    id: "models-title",
+    content: "Models",
+    fg: theme.colors.textPrimary,
+    attributes: TextAttributes.BOLD,
+  })
+
+  const countText = new TextRenderable(renderer, {
+    id: "models-count",
+    content: "Loading…",
+    fg: theme.colors.textSecondary,
+  })
+
+  topBar.add(titleText)
+  topBar.add(countText)
+
+  let scrollBox = new ScrollBoxRenderable(renderer, {
+    id: "models-scroll",
+    width: "100%" as any,
+    height: "100%" as any,
+    viewportCulling: true,
+  })
+
+  root.add(topBar)
+  root.add(scrollBox)
+
+  // ── Cards ────────────────────────────────────────────────────────────
+
+  function subtitleFor(model: ModelInfo): string {
+    const download = downloads.get(model.id)
+    if (download?.status === "downloading") {
+      const size = download.total > 0 ? `${mb(download.downloaded)}/${mb(download.total)} MB` : "starting…"
+      return `${progressBar(download.downloaded, download.total)}  •  ${size}  •  ${download.file}`
+    }
+    if (download?.status === "paused") {
+      return `⏸ Paused  ${progressBar(download.downloaded, download.total)}  •  p resume  •  x cancel`
+    }
+    if (download?.status === "failed") {
+      return `✗ ${download.message}`
+    }
+    const markers = isActiveModel(state, model) ? "  •  ● Active" : model.downloaded ? "  •  ✓ Downloaded" : ""
+    return `${model.size}  •  ${model.speedFactor}x speed  •  ${model.memoryMb}MB RAM${markers}`
+  }
+
+  function buildCards() {
+    cards = []
+    try {
+      root.remove("models-scroll")
+    } catch {}
+    scrollBox = new ScrollBoxRenderable(renderer, {
+      id: "models-scroll",
+      width: "100%" as any,
+      height: "100%" as any,
+      viewportCulling: true,
+    })
+    root.add(scrollBox)
+
+    const downloaded = models.filter((m) => m.downloaded).length
+    countText.content = `${downloaded}/${models.length} downloaded`
+
+    for (let i = 0; i < models.length; i++) {
+      const model = models[i]
+      const card = createCard(renderer, {
+        id: `model-${model.id}`,
+        text: model.name,
+        subtitle: subtitleFor(model),
+        selected: i === selectedIndex && hasFocus,
+        theme,
+        actions: [],
+      })
+      card.root.onMouseDown = () => {
+        hasFocus = true
+        selectedIndex = i
+        updateSelection()
+      }
+      cards.push(card)
+      scrollBox.add(card.root)
+    }
+  }
+
+  function updateCard(modelId: string) {
+    const index = models.findIndex((m) => m.id === modelId)
+    if (index >= 0 && cards[index]) cards[index].setSubtitle(subtitleFor(models[index]))
+  }
+
+  function updateSelection() {
+    for (let i = 0; i < cards.length; i++) {
+      cards[i].setSelected(i === selectedIndex && hasFocus)
+    }
+    const cardHeight = 4
+    const selectedTop = selectedIndex * cardHeight
+    const viewportHeight = (scrollBox as any).viewportHeight || 20
+    if (selectedTop < scrollBox.scrollTop) {
+      scrollBox.scrollTop = selectedTop
+    } else if (selectedTop + cardHeight > scrollBox.scrollTop + viewportHeight) {
+      scrollBox.scrollTop = Math.max(0, selectedTop + cardHeight - viewportHeight)
+    }
+  }
+
+  async function refresh() {
+    models = await listModelsWithStatus()
+    buildCards()
+    if (hasFocus) updateSelection()
+  }
+
+  function flash(msg: string, ms = 3000) {
+    callbacks.onStatusMessage(msg)
+    setTimeout(() => callbacks.onStatusMessage(""), ms)
+  }
+
+  // ── Daemon events ────────────────────────────────────────────────────
+
+  function onDownloadEvent(event: DownloadEvent) {
+    const model = models.find((m) => m.id === event.model)
+    switch (event.kind) {
+      case "progress":
+        downloads.set(event.model, {
+          status: "downloading",
+          file: event.file,
+          downloaded: event.downloaded,
+          total: event.total,
+          message: "",
+        })
+        break
+      case "complete":
+        downloads.delete(event.model)
+        if (model) model.downloaded = true
+        // The daemon switched to it (downloads start with --activate)
+        state.config.model.model_path = event.model
+        for (const m of models) updateCard(m.id)
+        countText.content = `${models.filter((m) => m.downloaded).length}/${models.length} downloaded`
+        flash(`✅ ${model?.name ?? event.model} downloaded and active`)
+        return
+      case "stopped": {
+        const previous = downloads.get(event.model)
+        if (event.cancelled) {
+          downloads.delete(event.model)
+          flash(`✗ Download of ${event.model} cancelled`)
+        } else {
+          downloads.set(event.model, {
+            status: "paused",
+            file: previous?.file ?? "",
+            downloaded: previous?.downloaded ?? 0,
+            total: previous?.total ?? 0,
+            message: "",
+          })
+          flash(`⏸ Download of ${event.model} paused`)
+        }
+        break
+      }
+      case "failed":
+        downloads.set(event.model, {
+          status: "failed",
+          file: "",
+          downloaded: 0,
+          total: 0,
+          message: event.message,
+        })
+        flash(`❌ Download of ${event.model} failed`)
+        break
+    }
+    updateCard(event.model)
+  }
+
+  const stopWatching = watchDownloads(onDownloadEvent, () => {
+    callbacks.onStatusMessage("⚠ Not receiving daemon events — is the daemon running?")
+  })
+
+  // ── Actions ──────────────────────────────────────────────────────────
+
+  async function downloadOrUse(model: ModelInfo) {
+    const download = downloads.get(model.id)
+    if (download?.status === "downloading") {
+      flash(`${model.name} is already downloading`)
+      return
+    }
+    if (model.downloaded && !download) {
+      try {
+        await useModel(model.id)
+        state.config.model.model_path = model.id
+        for (const m of models) updateCard(m.id)
+        flash(`● Switched to ${model.name}`)
+      } catch (err) {
+        flash(`❌ ${err instanceof Error ? err.message : err}`)
+      }
+      return
+    }
+    try {
+      await startDownload(model.id, true)
+      downloads.set(model.id, {
+        status: "downloading",
+        file: download?.file ?? "",
+        downloaded: download?.downloaded ?? 0,
+        total: download?.total ?? 0,
+        message: "",
+      })
+      updateCard(model.id)
+      flash(`📥 Downloading ${model.name}…`)
+    } catch (err) {
+      flash(`❌ ${err instanceof Error ? err.message : err}`)
+    }
+  }
+
+  async function togglePause(model: ModelInfo) {
+    const download = downloads.get(model.id)
+    if (download?.status === "paused") {
+      await downloadOrUse(model)
+      return
+    }
+    if (download?.status !== "downloading") return
+    try {
+      await pauseDownload(model.id)
+    } catch (err) {
+      flash(`❌ ${err instanceof Error ? err.message : err}`)
+    }
+  }
+
+  async function cancel(model: ModelInfo) {
+    const download = downloads.get(model.id)
+    if (!download) return
+    try {
+      if (download.status === "downloading") {
+        await cancelDownload(model.id)
+      } else {
+        // Nothing running in the daemon; forget the paused/failed state and
+        // let the next download start over
+        downloads.delete(model.id)
+        updateCard(model.id)
+      }
+    } catch (err) {
+      flash(`❌ ${err instanceof Error ? err.message : err}`)
+    }
+  }
+
+  // ── Keyboard handling ────────────────────────────────────────────────
+
+  const keyHandler = (key: any) => {
+    // Only respond when the models tab is active and we have focus
+    if (state.activeTab !== 2 || !hasFocus || models.length === 0) return
+    const model = models[selectedIndex]
+
+    if (key.name === "down" || key.name === "j") {
+      selectedIndex = Math.min(selectedIndex + 1, models.length - 1)
+      updateSelection()
+      return
+    }
+    if (key.name === "up" || key.name === "k") {
+      selectedIndex = Math.max(selectedIndex - 1, 0)
+      updateSelection()
+      return
+    }
+    if (key.name === "escape") {
+      hasFocus = false
+      updateSelection()
+      callbacks.onEscape?.()
+      return
+    }
+    if (key.name === "return" || (key.name === "d" && !key.ctrl)) {
+      if (model) downloadOrUse(model)
+      return
+    }
+    if (key.name === "p" && !key.ctrl) {
+      if (model) togglePause(model)
+      return
+    }
+    if (key.name === "x" && !key.ctrl) {
+      if (model) cancel(model)
+      return
+    }
+    if (key.name === "r" && !key.ctrl) {
+      refresh()
+      return
+    }
+  }
+  renderer.keyInput.on("keypress", keyHandler)
+
+  // ── Focus management ─────────────────────────────────────────────────
+
+  function focusFirst() {
+    hasFocus = true
+    updateSelection()
+  }
+
+  function blurAll() {
+    hasFocus = false
+    updateSelection()
+  }
+
+  function destroy() {
+    stopWatching()
+    renderer.keyInput.off("keypress", keyHandler)
+  }
+
+  // ── Initial render ───────────────────────────────────────────────────
+  refresh()
+
+  return {
+    root,
+    refresh,
+    focusFirst,
+    blurAll,
+    destroy,
+  }
+}